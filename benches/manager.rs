@@ -0,0 +1,91 @@
+//! Benchmarks of the proxy manager's pool operations
+//!
+//! Run with `cargo bench --bench manager`. Pools hold up to 100k proxies,
+//! the size at which indexing and interning changes start to matter.
+
+mod support;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use gooty_proxy::definitions::enums::DedupPolicy;
+use gooty_proxy::orchestration::manager::{ProxyCriteria, ProxyManager};
+use std::hint::black_box;
+use support::proxies;
+
+/// Number of proxies in a large pool
+const LARGE_POOL: u32 = 100_000;
+
+/// Number of proxies in a pool scanned on every lease
+const LEASE_POOL: u32 = 10_000;
+
+/// Build a manager holding a pool of proxies
+fn manager_with(count: u32) -> ProxyManager {
+    let mut manager = ProxyManager::new().unwrap();
+    manager.add_proxies(proxies(count, 1)).unwrap();
+    manager
+}
+
+fn bench_add_proxies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_proxies");
+    group.sample_size(10);
+    group.bench_function(BenchmarkId::from_parameter(LARGE_POOL), |b| {
+        b.iter_batched(
+            || (ProxyManager::new().unwrap(), proxies(LARGE_POOL, 1)),
+            |(mut manager, proxies)| black_box(manager.add_proxies(proxies).unwrap()),
+            BatchSize::LargeInput,
+        );
+    });
+    group.finish();
+}
+
+fn bench_dedup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dedup");
+    group.sample_size(10);
+    for policy in [DedupPolicy::IpPort, DedupPolicy::Ip, DedupPolicy::Subnet] {
+        // Four ports per address, so coarser policies drop most of the input
+        group.bench_function(BenchmarkId::from_parameter(format!("{policy:?}")), |b| {
+            b.iter_batched(
+                || {
+                    let mut manager = ProxyManager::new().unwrap();
+                    manager.set_dedup_policy(policy);
+                    (manager, proxies(LARGE_POOL, 4))
+                },
+                |(mut manager, proxies)| black_box(manager.add_proxies(proxies).unwrap()),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_get_proxy_stats(c: &mut Criterion) {
+    let manager = manager_with(LARGE_POOL);
+    let mut group = c.benchmark_group("get_proxy_stats");
+    group.sample_size(20);
+    group.bench_function(BenchmarkId::from_parameter(LARGE_POOL), |b| {
+        b.iter(|| black_box(manager.get_proxy_stats()));
+    });
+    group.finish();
+}
+
+fn bench_acquire_proxy(c: &mut Criterion) {
+    let mut manager = manager_with(LEASE_POOL);
+    let criteria = ProxyCriteria::new();
+    let mut group = c.benchmark_group("acquire_proxy");
+    group.bench_function(BenchmarkId::from_parameter(LEASE_POOL), |b| {
+        b.iter(|| {
+            let proxy = manager.acquire_proxy(&criteria).unwrap();
+            manager.release_proxy(&proxy.to_connection_string());
+            black_box(proxy)
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_add_proxies,
+    bench_dedup,
+    bench_get_proxy_stats,
+    bench_acquire_proxy
+);
+criterion_main!(benches);
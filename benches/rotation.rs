@@ -0,0 +1,78 @@
+//! Benchmarks of proxy selection in a rotation
+//!
+//! Run with `cargo bench --bench rotation`. Every selection hands out a
+//! `ProxyHandle`, so the timings cover the selection alone rather than
+//! copies of the selected proxies.
+
+mod support;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use gooty_proxy::definitions::proxy::Proxy;
+use gooty_proxy::orchestration::listener::UpstreamPool;
+use gooty_proxy::orchestration::rotation::WeightedSelector;
+use rand::Rng;
+use std::hint::black_box;
+use support::proxies;
+
+/// Pool sizes to select from
+const POOL_SIZES: [u32; 3] = [100, 10_000, 100_000];
+
+fn bench_weighted_selector(c: &mut Criterion) {
+    let mut group = c.benchmark_group("weighted_selector");
+    for size in POOL_SIZES {
+        let mut selector = WeightedSelector::from_proxies(&proxies(size, 1));
+        let mut rng = rand::rng();
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| black_box(selector.select(&mut rng).map(str::len)));
+        });
+    }
+    group.finish();
+}
+
+fn bench_weighted_selector_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("weighted_selector_churn");
+    for size in POOL_SIZES {
+        let pool = proxies(size, 1);
+        let ids: Vec<String> = pool.iter().map(Proxy::to_connection_string).collect();
+        let mut selector = WeightedSelector::from_proxies(&pool);
+        let mut rng = rand::rng();
+        let mut next = 0;
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            // Rescore one proxy before every selection, as after each use
+            b.iter(|| {
+                next = (next + 1) % ids.len();
+                selector.set_weight(&ids[next], rng.random_range(0.1..1.0));
+                black_box(selector.select(&mut rng).map(str::len))
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_upstream_pool(c: &mut Criterion) {
+    let mut group = c.benchmark_group("upstream_pool");
+    for size in POOL_SIZES {
+        let mut pool = UpstreamPool::from_proxies(proxies(size, 1));
+        group.bench_with_input(BenchmarkId::new("next_proxy", size), &size, |b, _| {
+            b.iter(|| black_box(pool.next_proxy()));
+        });
+
+        let mut pool = UpstreamPool::from_proxies(proxies(size, 1)).with_subnet_diversity(8);
+        group.bench_with_input(
+            BenchmarkId::new("next_proxy_diverse", size),
+            &size,
+            |b, _| {
+                b.iter(|| black_box(pool.next_proxy()));
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_weighted_selector,
+    bench_weighted_selector_churn,
+    bench_upstream_pool
+);
+criterion_main!(benches);
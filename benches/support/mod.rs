@@ -0,0 +1,32 @@
+//! Proxy pools shared by the benchmarks
+
+use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+use gooty_proxy::definitions::proxy::Proxy;
+use gooty_proxy::definitions::types::Port;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// Build distinct proxies spread over /24 subnets, with varied scores and ASNs
+///
+/// Every `ports_per_address` consecutive proxies share an address on
+/// different ports, so pools can be made to dedup under coarser policies.
+pub fn proxies(count: u32, ports_per_address: u16) -> Vec<Proxy> {
+    let ports_per_address = ports_per_address.max(1);
+    (0..count)
+        .map(|i| {
+            let address = i / u32::from(ports_per_address);
+            let port = 8080 + u16::try_from(i % u32::from(ports_per_address)).unwrap();
+            let [_, b, c, d] = address.to_be_bytes();
+            let mut proxy = Proxy::new(
+                ProxyType::Http,
+                IpAddr::V4(Ipv4Addr::new(10, b, c, d)),
+                Port::new(port).unwrap(),
+                AnonymityLevel::Elite,
+            );
+            proxy.asn = Some(format!("AS{}", 64_500 + i % 64));
+            for check in 0..i % 5 {
+                proxy.record_check(u128::from(100 + check * 50));
+            }
+            proxy
+        })
+        .collect()
+}
@@ -0,0 +1,40 @@
+//! Benchmarks of the overhead of the threading primitives
+//!
+//! Run with `cargo bench --bench threading`. Jobs finish immediately, so
+//! the timings are the cost of scheduling and collecting them.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use futures::FutureExt;
+use gooty_proxy::orchestration::threading::run_concurrent_batch;
+use std::future::Future;
+use std::hint::black_box;
+use std::pin::Pin;
+
+/// Number of items in a batch
+const BATCH_SIZE: u32 = 1_000;
+
+/// Concurrency limits to run batches at
+const CONCURRENCY: [usize; 3] = [1, 16, 256];
+
+/// A job doing no work of its own
+fn noop(item: u32) -> Pin<Box<dyn Future<Output = (u32, bool)> + Send>> {
+    async move { (item, true) }.boxed()
+}
+
+fn bench_run_concurrent_batch(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("run_concurrent_batch");
+    group.throughput(Throughput::Elements(u64::from(BATCH_SIZE)));
+    for concurrency in CONCURRENCY {
+        group.bench_function(BenchmarkId::from_parameter(concurrency), |b| {
+            b.to_async(&runtime).iter(|| async move {
+                let items: Vec<u32> = (0..BATCH_SIZE).collect();
+                black_box(run_concurrent_batch(items, concurrency, &noop).await)
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_run_concurrent_batch);
+criterion_main!(benches);
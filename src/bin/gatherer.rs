@@ -7,8 +7,20 @@
 //!
 //! The CLI supports the following commands:
 //! - `Config`: Manage configuration files (create or validate)
-//! - `Proxy`: Test and manage individual proxies
+//! - `Proxy`: Test and manage individual proxies, or import lists of them
 //! - `Source`: Scrape proxies from websites and manage sources
+//! - `Report`: Summarise stored proxies and source health
+//! - `Export`: Write stored proxies as plain lists, JSON, CSV or Parquet for other tools
+//! - `Recheck`: Re-judge stored proxies, resuming an interrupted run
+//! - `Serve`: Relay SOCKS5 clients through the stored working proxies
+//! - `Daemon`: Run the configured maintenance schedules until interrupted
+//! - `ServeJudge`: Serve a judge echoing request headers, for validating without public judges
+//! - `Coordinate`: Hand the stale stored proxies out to workers on other hosts to judge
+//! - `Work`: Judge proxies handed out by a coordinator
+//! - `ValidateData`: Check the stored proxies, sources and configuration, optionally repairing them
+//! - `Sources`: Share curated sets of sources as bundles, and install those of others
+//! - `Pool`: Combine proxy pools gathered on different machines, or render them for other tools
+//! - `Debug`: Inspect what the last run saw, such as raw judge and ipinfo.io responses
 //!
 //! ## Examples
 //!
@@ -17,23 +29,57 @@
 //! gatherer proxy --judge "http://127.0.0.1:8080"
 //! ```
 
+#[cfg(feature = "mimalloc")]
+use mimalloc::MiMalloc;
+
 use clap::{CommandFactory, Parser, Subcommand};
 use gooty_proxy::{
     defaults,
     definitions::{
-        enums::{AnonymityLevel, JudgementMode, LogLevel, ProxyType},
+        enums::{
+            ConflictPolicy, DedupPolicy, ExportColumn, ExportFormat, JudgementMode, LogLevel,
+            MergePolicy, ProxySortKey, RotationStrategy, SyncPolicy,
+        },
+        errors::FilestoreError,
         proxy::Proxy,
         source::Source,
     },
+    inspection::{
+        churn::SurvivalStats,
+        judgement::{
+            AnonymityRule, Judge, JudgementRetryPolicy, LocalJudge, classify_anonymity,
+            parse_judge_headers,
+        },
+    },
     io::{
-        filesystem::{AppConfig, Filestore, FilestoreConfig},
+        bundle::{self, SourceBundle, VerifyingKey},
+        export,
+        filesystem::{self, AppConfig, Filestore, FilestoreConfig, ProxyJournal},
         http::Requestor,
+        wayback::Wayback,
+    },
+    orchestration::{
+        coordinator::{Coordinator, CoordinatorServer, WorkerClient},
+        events::{EventBus, EventServer},
+        listener::{ClientStatsMap, PortWatcher, SocksListener, UpstreamPool},
+        manager::{ChurnStats, ProxyManager},
+        scheduler::Scheduler,
     },
-    orchestration::manager::ProxyManager,
     utils,
 };
 use indicatif::{ProgressBar, ProgressStyle};
-use std::{net::IpAddr, str::FromStr};
+use rand::Rng;
+use std::{
+    collections::{BTreeMap, HashSet},
+    net::IpAddr,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static GLOBAL: MiMalloc = MiMalloc;
 
 #[derive(Parser)]
 #[command(
@@ -51,6 +97,27 @@ struct Cli {
     /// Log level for the application (default: Info)
     #[arg(long, global = true, value_enum, default_value_t = LogLevel::Info)]
     log_level: LogLevel,
+
+    /// Directory where proxies, sources, and configuration are stored
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        env = "GOOTY_DATA_DIR",
+        help = "Directory containing data files (default: the platform data directory)"
+    )]
+    data_dir: Option<String>,
+
+    /// Number of raw judge and lookup responses to keep for `debug last-responses`
+    #[arg(
+        long,
+        global = true,
+        value_name = "COUNT",
+        env = "GOOTY_CAPTURE_RESPONSES",
+        default_value_t = 0,
+        help = "Keep the last COUNT raw judge and ipinfo.io responses for debugging (0 disables)"
+    )]
+    capture_responses: usize,
 }
 
 #[derive(Subcommand)]
@@ -77,7 +144,7 @@ enum Commands {
     },
     /// Test and manage proxies
     Proxy {
-        /// Test a proxy's capabilities (format: protocol://ip:port)
+        /// Test a proxy's capabilities (format: protocol://[user:pass@]ip:port)
         #[arg(
             long,
             value_name = "URL",
@@ -91,6 +158,51 @@ enum Commands {
             help = "Test the proxy without saving it to the persistent proxy list"
         )]
         dry: bool,
+
+        /// Also check that the proxy leaves TLS SNI and Host intact
+        #[arg(
+            long,
+            help = "Check that the proxy passes TLS SNI and the Host header through untouched"
+        )]
+        verify_sni: bool,
+
+        /// Import proxies from a file into the persistent proxy list
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with = "judge",
+            help = "Import proxies from an ip:port list, a scheme://ip:port list, JSON or CSV (- for stdin)"
+        )]
+        import: Option<String>,
+
+        /// Format of the imported file
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            requires = "import",
+            help = "Format of the imported file: list, urls, json or csv (default: detected)"
+        )]
+        format: Option<ExportFormat>,
+
+        /// Testing mode for the imported proxies
+        #[arg(
+            long,
+            value_name = "MODE",
+            requires = "import",
+            help = "Testing mode for the imported proxies: none (0), quick (1) or full (2)",
+            default_value_t = JudgementMode::None
+        )]
+        check: JudgementMode,
+        /// Address to stream progress events on
+        #[arg(
+            long,
+            value_name = "ADDRESS",
+            num_args = 0..=1,
+            default_missing_value = defaults::events::DEFAULT_ADDRESS,
+            help = "Stream progress as Server-Sent Events from http://ADDRESS/events \
+                    (ADDRESS defaults to 127.0.0.1:8090)"
+        )]
+        events: Option<String>,
     },
     /// Manage proxy sources and scrape proxies
     Source {
@@ -106,7 +218,7 @@ enum Commands {
         #[arg(
             long,
             value_name = "PATH",
-            help = "Directory containing configuration files (default: 'data')"
+            help = "Directory containing configuration files (default: --data-dir)"
         )]
         config: Option<String>,
 
@@ -126,6 +238,22 @@ enum Commands {
         )]
         pattern: Option<String>,
 
+        /// Template variables substituted into the URL
+        #[arg(
+            long = "var",
+            value_name = "NAME=VALUES",
+            help = "Template variable for {NAME} placeholders in the URL, with comma-separated values (repeatable)"
+        )]
+        vars: Vec<String>,
+
+        /// Further headers sent when fetching the source
+        #[arg(
+            long = "header",
+            value_name = "NAME: VALUE",
+            help = "Header sent when fetching the source, such as \"Referer: https://example.com/\" (repeatable)"
+        )]
+        headers: Vec<String>,
+
         /// Proxy testing and information gathering mode
         #[arg(
             long,
@@ -135,6 +263,23 @@ enum Commands {
         )]
         judge: JudgementMode,
 
+        /// Also harvest archived snapshots of the source
+        #[arg(
+            long,
+            help = "Also scrape the source's most recent snapshots kept by the Wayback Machine"
+        )]
+        wayback: bool,
+
+        /// Most snapshots read with --wayback
+        #[arg(
+            long,
+            value_name = "COUNT",
+            requires = "wayback",
+            help = "Number of the most recent snapshots to scrape with --wayback",
+            default_value_t = defaults::wayback::MAX_SNAPSHOTS
+        )]
+        snapshots: usize,
+
         /// Don't save to sources list
         #[arg(
             long,
@@ -142,365 +287,2637 @@ enum Commands {
         )]
         dry: bool,
     },
-}
-
-/// Prints detailed information about a proxy to the console.
-///
-/// # Arguments
-/// * `proxy` - The proxy object containing information to display
-fn print_proxy_details(proxy: &Proxy) {
-    println!("Proxy Type: {}", proxy.proxy_type);
-    println!("Anonymity Level: {}", proxy.anonymity);
-    if let Some(latency) = proxy.latency_ms {
-        println!("Latency: {latency}ms");
-    }
-    if let Some(country) = &proxy.country {
-        println!("Country: {country}");
-    }
-    if let Some(org) = &proxy.organization {
-        println!("Organization: {org}");
-    }
-    if let Some(asn) = &proxy.asn {
-        println!("ASN: {asn}");
-    }
-    if let Some(hostname) = &proxy.hostname {
-        println!("Hostname: {hostname}");
-    }
-}
+    /// Summarise stored proxies and sources
+    Report {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
+    },
+    /// Export stored proxies for analytics and downstream tools
+    Export {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
 
-/// Handles the Config command, creating or validating configuration files.
-///
-/// # Arguments
-/// * `create` - Optional path where to create default configuration
-/// * `validate` - Optional path to validate configuration
-///
-/// # Returns
-/// * `()` - The function exits the program with appropriate status code
-fn handle_config_command(create: Option<String>, validate: Option<String>) {
-    if let Some(path) = create {
-        // Create default configuration
-        let config = FilestoreConfig {
-            data_dir: path.clone(),
-            ..Default::default()
-        };
-        let filestore = match Filestore::with_config(config) {
-            Ok(fs) => fs,
-            Err(e) => {
-                eprintln!("Failed to create filestore: {e}");
-                std::process::exit(1);
-            }
-        };
+        /// Format of the exported file
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            help = "Export format: list (ip:port lines), urls (scheme://ip:port lines), json, csv, \
+                    or parquet (parquet needs the `arrow` feature)",
+            default_value = "csv"
+        )]
+        format: ExportFormat,
 
-        // Create default configuration files
-        let default_config = AppConfig::default();
-        if let Err(e) = filestore.save_config(&default_config, "config") {
-            eprintln!("Failed to save configuration: {e}");
-            std::process::exit(1);
-        }
+        /// Columns of CSV and JSON exports
+        #[arg(
+            long,
+            value_name = "COLUMNS",
+            value_delimiter = ',',
+            help = "Comma-separated columns of CSV and JSON exports, such as ip,port,latency,country,anonymity \
+                    (default: all)"
+        )]
+        columns: Vec<ExportColumn>,
 
-        println!("Created default configuration in {path}");
-        std::process::exit(0);
-    }
+        /// File to write
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "File to write (default: proxies.<format> in the current directory)"
+        )]
+        output: Option<String>,
 
-    if let Some(path) = validate {
-        // Validate existing configuration
-        let config = FilestoreConfig {
-            data_dir: path.clone(),
-            create_defaults_if_missing: false,
-            ..Default::default()
-        };
-        let filestore = match Filestore::with_config(config) {
-            Ok(fs) => fs,
-            Err(e) => {
-                eprintln!("Failed to access filestore: {e}");
-                std::process::exit(1);
-            }
-        };
+        /// Order of the exported proxies
+        #[arg(
+            long,
+            value_name = "KEY",
+            help = "Sort by latency, success-rate, last-checked, anonymity, or country \
+                    (default: overall quality)"
+        )]
+        sort: Option<ProxySortKey>,
+    },
+    /// Re-judge stored proxies whose checks have gone stale
+    Recheck {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
 
-        // Try to load and validate configuration
-        match filestore.load_config("config") {
-            Ok(_) => {
-                println!("Configuration in {path} is valid");
-                std::process::exit(0);
-            }
-            Err(e) => {
-                eprintln!("Configuration validation failed: {e}");
-                std::process::exit(1);
-            }
-        }
-    }
-}
+        /// How long a check result stays fresh
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Re-judge proxies not checked within this many seconds",
+            default_value_t = defaults::persistence::MAX_PROXY_AGE_SECS
+        )]
+        max_age: u64,
 
-/// Initializes a proxy manager with judge and optionally sleuth.
-///
-/// # Arguments
-/// * `with_sleuth` - Whether to initialize the sleuth component
-///
-/// # Returns
-/// * `Result<ProxyManager, Box<dyn std::error::Error>>` - The initialized manager or an error
-fn init_proxy_manager(with_sleuth: bool) -> Result<ProxyManager, Box<dyn std::error::Error>> {
-    let mut manager = ProxyManager::new()?;
+        /// How long a judged anonymity level is trusted
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Re-judge anonymity only if judged more than this many seconds ago, \
+                    checking liveness otherwise (0 re-judges every time)",
+            default_value_t = defaults::liveness::ANONYMITY_MAX_AGE_SECS
+        )]
+        anonymity_max_age: u64,
 
-    // Initialize judge
-    manager.init_judge()?;
+        /// Number of proxies judged at once
+        #[arg(
+            long,
+            value_name = "COUNT",
+            help = "Maximum number of proxies judged concurrently",
+            default_value_t = defaults::DEFAULT_PARALLEL_VALIDATIONS
+        )]
+        concurrency: usize,
 
-    // Initialize sleuth if needed
-    if with_sleuth {
-        manager.init_sleuth()?;
-    }
+        /// Address to stream progress events on
+        #[arg(
+            long,
+            value_name = "ADDRESS",
+            num_args = 0..=1,
+            default_missing_value = defaults::events::DEFAULT_ADDRESS,
+            help = "Stream progress as Server-Sent Events from http://ADDRESS/events \
+                    (ADDRESS defaults to 127.0.0.1:8090)"
+        )]
+        events: Option<String>,
+    },
+    /// Serve the stored working proxies as a local rotating proxy
+    Serve {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
 
-    Ok(manager)
-}
+        /// Address of the SOCKS5 listener
+        #[arg(
+            long,
+            value_name = "ADDRESS",
+            help = "Address to accept SOCKS5 clients on",
+            default_value = defaults::listener::DEFAULT_SOCKS_ADDRESS
+        )]
+        socks: String,
 
-/// Handles the Proxy command, testing individual proxies.
-///
-/// # Arguments
-/// * `judge` - Optional proxy URL to test
-/// * `dry` - Whether to avoid saving results
-///
-/// # Returns
-/// * `()` - The function exits the program with appropriate status code
-async fn handle_proxy_command(judge: Option<String>, dry: bool) {
-    if let Some(proxy_url) = judge {
-        // Initialize proxy manager and required components
-        let mut manager = match init_proxy_manager(true) {
-            Ok(m) => m,
-            Err(e) => {
-                eprintln!("Failed to initialize proxy manager: {e}");
-                std::process::exit(1);
-            }
-        };
+        /// Number of upstream proxies tried per client connection
+        #[arg(
+            long,
+            value_name = "COUNT",
+            help = "Try up to COUNT proxies before refusing a client connection",
+            default_value_t = defaults::listener::MAX_UPSTREAM_ATTEMPTS
+        )]
+        attempts: usize,
 
-        // Parse proxy URL
-        let proxy = match parse_proxy_url(&proxy_url) {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("Invalid proxy URL: {e}");
-                std::process::exit(1);
-            }
-        };
+        /// Number of latest connections whose subnets and ASNs are avoided
+        #[arg(
+            long,
+            value_name = "COUNT",
+            help = "Keep each connection off the /24 subnets and ASNs of the last COUNT connections",
+            default_value_t = 0
+        )]
+        diversity_window: usize,
 
-        println!("Testing proxy: {proxy_url}");
+        /// Number of the highest scoring proxies whose ports are watched
+        #[arg(
+            long,
+            value_name = "COUNT",
+            help = "Probe the ports of the top COUNT proxies every few seconds, taking dead ones out of rotation (0 disables)",
+            default_value_t = defaults::listener::WATCH_TOP_PROXIES
+        )]
+        watch_top: usize,
+        /// Address to stream progress events on
+        #[arg(
+            long,
+            value_name = "ADDRESS",
+            num_args = 0..=1,
+            default_missing_value = defaults::events::DEFAULT_ADDRESS,
+            help = "Stream the probes of watched ports as Server-Sent Events from http://ADDRESS/events \
+                    (ADDRESS defaults to 127.0.0.1:8090)"
+        )]
+        events: Option<String>,
+    },
+    /// Run the configured maintenance schedules until interrupted
+    Daemon {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
 
-        // Add proxy to manager
-        if let Err(e) = manager.add_proxy(proxy) {
-            eprintln!("Failed to add proxy: {e}");
-            std::process::exit(1);
-        }
+        /// Address to stream progress events on
+        #[arg(
+            long,
+            value_name = "ADDRESS",
+            num_args = 0..=1,
+            default_missing_value = defaults::events::DEFAULT_ADDRESS,
+            help = "Stream progress as Server-Sent Events from http://ADDRESS/events \
+                    (ADDRESS defaults to 127.0.0.1:8090)"
+        )]
+        events: Option<String>,
+    },
+    /// Serve a judge echoing request headers, for validating without public judges
+    ServeJudge {
+        /// Address of the judge
+        #[arg(
+            long,
+            value_name = "ADDRESS",
+            help = "Address to accept judge requests on, reachable by the proxies judged",
+            default_value = defaults::local_judge::DEFAULT_ADDRESS
+        )]
+        listen: String,
+    },
+    /// Hand the stale stored proxies out to workers to judge
+    Coordinate {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
 
-        // Get proxy ID for management
-        let proxy_id = proxy_url.clone();
+        /// Address of the coordinator's API
+        #[arg(
+            long,
+            value_name = "ADDRESS",
+            help = "Address to accept workers on",
+            default_value = defaults::coordinator::DEFAULT_ADDRESS
+        )]
+        listen: String,
 
-        // Check proxy connectivity and anonymity
-        if let Err(e) = manager.check_proxy(&proxy_id).await {
-            eprintln!("Proxy test failed: {e}");
-            std::process::exit(1);
-        }
+        /// How long a check result stays fresh
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Hand out proxies not checked within this many seconds",
+            default_value_t = defaults::persistence::MAX_PROXY_AGE_SECS
+        )]
+        max_age: u64,
 
-        // Enrich with IP metadata
-        if let Err(e) = manager.enrich_proxy(&proxy_id).await {
-            eprintln!("Failed to enrich proxy data: {e}");
+        /// Time a worker has to report a claimed batch
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            help = "Hand a claimed batch to another worker if not reported within this many seconds",
+            default_value_t = defaults::coordinator::LEASE_TIMEOUT_SECS
+        )]
+        lease_timeout: u64,
+
+        /// Token shared with the workers
+        #[arg(
+            long,
+            value_name = "TOKEN",
+            help = "Token workers must send (default: a random one, printed on start)",
+            env = defaults::coordinator::TOKEN_ENV,
+            hide_env_values = true
+        )]
+        token: Option<String>,
+    },
+    /// Judge proxies handed out by a coordinator
+    Work {
+        /// Address of the coordinator
+        #[arg(
+            long,
+            value_name = "URL",
+            help = "Address or URL of the coordinator's API"
+        )]
+        coordinator: String,
+
+        /// Name of the worker
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Name to report results under (default: worker-<process id>)"
+        )]
+        name: Option<String>,
+
+        /// Vantage point judged latencies are recorded under
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Record judged latencies under NAME, such as this host's region (default: the worker name)"
+        )]
+        vantage: Option<String>,
+
+        /// Number of proxies claimed at once
+        #[arg(
+            long,
+            value_name = "COUNT",
+            help = "Claim COUNT proxies at a time",
+            default_value_t = defaults::coordinator::BATCH_SIZE
+        )]
+        batch: usize,
+
+        /// Number of proxies judged at once
+        #[arg(
+            long,
+            value_name = "COUNT",
+            help = "Maximum number of proxies judged concurrently",
+            default_value_t = defaults::DEFAULT_PARALLEL_VALIDATIONS
+        )]
+        concurrency: usize,
+
+        /// Token shared with the coordinator
+        #[arg(
+            long,
+            value_name = "TOKEN",
+            help = "Token the coordinator printed on start",
+            env = defaults::coordinator::TOKEN_ENV,
+            hide_env_values = true
+        )]
+        token: String,
+    },
+    /// Check the stored data files for invalid entries
+    ValidateData {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
+
+        /// Whether to repair the files
+        #[arg(
+            long,
+            help = "Rewrite the proxies and sources files without their invalid entries"
+        )]
+        fix: bool,
+    },
+    /// Share and install curated sets of sources
+    Sources {
+        /// Sources command to execute
+        #[command(subcommand)]
+        command: SourcesCommands,
+    },
+    /// Manage stored proxy pools
+    Pool {
+        /// Pool command to execute
+        #[command(subcommand)]
+        command: PoolCommands,
+    },
+    /// Inspect what earlier runs saw
+    Debug {
+        /// Debugging command to execute
+        #[command(subcommand)]
+        command: DebugCommands,
+    },
+}
+
+#[derive(Subcommand)]
+enum PoolCommands {
+    /// Merge two stored pools, such as those of gatherers in different regions
+    Merge {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
+
+        /// Name of the first pool
+        #[arg(
+            value_name = "FIRST",
+            help = "Name of the first pool's file in the data directory, without extension"
+        )]
+        first: String,
+
+        /// Name of the second pool
+        #[arg(
+            value_name = "SECOND",
+            help = "Name of the second pool's file in the data directory, without extension"
+        )]
+        second: String,
+
+        /// Name of the merged pool
+        #[arg(
+            long,
+            value_name = "NAME",
+            help = "Name of the file to write the merged pool to, without extension; an existing file is replaced"
+        )]
+        output: String,
+
+        /// How proxies found in both pools are combined
+        #[arg(
+            long,
+            value_name = "POLICY",
+            help = "Combine shared proxies: reconcile, prefer-first or prefer-second",
+            default_value = "reconcile"
+        )]
+        policy: MergePolicy,
+    },
+    /// Render every stored proxy through a template, such as a command line
+    /// or a config snippet for another tool
+    Export {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
+
+        /// Template rendered once per proxy
+        #[arg(
+            long,
+            value_name = "TEMPLATE",
+            help = "Line written per proxy, with {scheme}, {host}, {port}, {user}, {pass} \
+                    and {country} replaced, e.g. \"curl -x {scheme}://{host}:{port}\""
+        )]
+        template: String,
+
+        /// File to write
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "File to write the rendered lines to (default: standard output)"
+        )]
+        output: Option<String>,
+
+        /// Order of the rendered proxies
+        #[arg(
+            long,
+            value_name = "KEY",
+            help = "Sort by latency, success-rate, last-checked, anonymity, or country \
+                    (default: overall quality)"
+        )]
+        sort: Option<ProxySortKey>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SourcesCommands {
+    /// Install the sources of a bundle shared by someone else
+    Install {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
+
+        /// Bundle to install
+        #[arg(
+            value_name = "FILE",
+            help = "Bundle file to install, read as JSON if it ends in .json and TOML otherwise"
+        )]
+        file: String,
+
+        /// What to do with sources that are already installed
+        #[arg(
+            long,
+            value_name = "POLICY",
+            help = "Handle sources already installed: keep, replace or abort",
+            default_value = "keep"
+        )]
+        on_conflict: ConflictPolicy,
+
+        /// Keys of the bundle authors trusted
+        #[arg(
+            long = "trusted-key",
+            value_name = "KEY",
+            help = "Base64 public key of a trusted author, in addition to trusted_bundle_keys of the configuration (repeatable)"
+        )]
+        trusted_keys: Vec<String>,
+
+        /// Whether unsigned bundles are refused
+        #[arg(
+            long,
+            help = "Refuse bundles that aren't signed, even when no author is trusted"
+        )]
+        require_signature: bool,
+
+        /// Whether to only show what would be installed
+        #[arg(long, help = "Show what would be installed without saving")]
+        dry: bool,
+    },
+    /// Write the installed sources to a bundle to share
+    Export {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
+
+        /// File to write the bundle to
+        #[arg(
+            value_name = "FILE",
+            help = "File to write the bundle to, as JSON if it ends in .json and TOML otherwise"
+        )]
+        output: String,
+
+        /// Name of the bundle
+        #[arg(long, value_name = "NAME", help = "Name of the bundle")]
+        name: String,
+
+        /// Who curated the sources
+        #[arg(long, value_name = "NAME", help = "Author of the bundle")]
+        author: Option<String>,
+
+        /// What the sources have in common
+        #[arg(
+            long,
+            value_name = "TEXT",
+            help = "Description of the bundle's sources"
+        )]
+        description: Option<String>,
+
+        /// Cron expression suggested for scraping the sources
+        #[arg(
+            long,
+            value_name = "CRON",
+            help = "Cron expression suggested for scraping the sources"
+        )]
+        schedule: Option<String>,
+
+        /// Key to sign the bundle with
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Sign the bundle with the key in PATH, as written by `sources keygen`"
+        )]
+        signing_key: Option<String>,
+    },
+    /// Generate a key to sign bundles with
+    Keygen {
+        /// File to write the signing key to
+        #[arg(
+            value_name = "FILE",
+            help = "File to write the signing key to; an existing file is kept"
+        )]
+        output: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DebugCommands {
+    /// Show the raw responses captured by the last run
+    LastResponses {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
+
+        /// Number of responses to show
+        #[arg(
+            long,
+            value_name = "COUNT",
+            help = "Show only the COUNT most recent responses"
+        )]
+        limit: Option<usize>,
+    },
+    /// Classify a saved judge response with the configured anonymity rules
+    Classify {
+        /// Path to configuration folder
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Directory containing configuration files (default: --data-dir)"
+        )]
+        config: Option<String>,
+
+        /// Public address of the machine that ran the check
+        #[arg(
+            long,
+            value_name = "IP",
+            help = "Public address of the machine the proxy was judged from"
+        )]
+        real_ip: IpAddr,
+
+        /// File holding the judge response
+        #[arg(
+            value_name = "FILE",
+            help = "File holding the judge response body (default: standard input)"
+        )]
+        file: Option<String>,
+    },
+}
+
+/// Prints detailed information about a proxy to the console.
+///
+/// # Arguments
+/// * `proxy` - The proxy object containing information to display
+fn print_proxy_details(proxy: &Proxy) {
+    println!("Proxy Type: {}", proxy.proxy_type);
+    if proxy.anonymity_disputed {
+        println!("Anonymity Level: {} (judges disagreed)", proxy.anonymity);
+    } else {
+        println!("Anonymity Level: {}", proxy.anonymity);
+    }
+    println!("Lifecycle: {}", proxy.effective_lifecycle());
+    if let Some(passthrough) = proxy.sni_passthrough {
+        let verdict = if passthrough { "intact" } else { "mangled" };
+        println!("SNI and Host: {verdict}");
+    }
+    if let Some(tls) = proxy.tls_capable {
+        let verdict = if tls {
+            "works"
+        } else {
+            "broken or intercepted"
+        };
+        println!("TLS: {verdict}");
+    }
+    if proxy.probe_count > 0 {
+        println!(
+            "Probes: {} total, {} failed",
+            proxy.probe_count, proxy.probe_failure_count
+        );
+    }
+    if let Some(latency) = proxy.latency_ms {
+        println!("Latency: {latency}");
+    }
+    if let Some(exit) = proxy.exit_ip.filter(|_| proxy.has_distinct_exit()) {
+        let rotating = if proxy.is_rotating { " (rotating)" } else { "" };
+        println!("Exit IP: {exit}{rotating}");
+    }
+    if let Some(country) = &proxy.country {
+        println!("Country: {country}");
+    }
+    if proxy.exit_countries().len() > 1 {
+        let countries: Vec<_> = proxy.exit_countries().into_iter().collect();
+        println!("Exit Countries: {}", countries.join(", "));
+    }
+    if let Some(org) = &proxy.organization {
+        println!("Organization: {org}");
+    }
+    if let Some(asn) = &proxy.asn {
+        println!("ASN: {asn}");
+    }
+    if let Some(hostname) = &proxy.hostname {
+        println!("Hostname: {hostname}");
+    }
+    if let Some(registry) = proxy.registry() {
+        println!("Registry: {registry}");
+    }
+    if let Some(cloud) = proxy.cloud_provider() {
+        println!("Cloud Provider: {cloud}");
+    }
+    if let Some(abuse) = proxy.abuse_email() {
+        println!("Abuse Contact: {abuse}");
+    }
+    let flags: Vec<&str> = [
+        (proxy.is_hosting(), "hosting"),
+        (proxy.is_mobile(), "mobile"),
+        (proxy.is_anycast(), "anycast"),
+    ]
+    .into_iter()
+    .filter_map(|(flag, name)| (flag == Some(true)).then_some(name))
+    .collect();
+    if !flags.is_empty() {
+        println!("Network: {}", flags.join(", "));
+    }
+}
+
+/// Handles the Config command, creating or validating configuration files.
+///
+/// # Arguments
+/// * `create` - Optional path where to create default configuration
+/// * `validate` - Optional path to validate configuration
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+fn handle_config_command(create: Option<String>, validate: Option<String>) {
+    if let Some(path) = create {
+        // Create default configuration
+        let config = FilestoreConfig {
+            data_dir: path.clone(),
+            ..Default::default()
+        };
+        let filestore = match Filestore::with_config(config) {
+            Ok(fs) => fs,
+            Err(e) => {
+                eprintln!("Failed to create filestore: {e}");
+                std::process::exit(1);
+            }
+        };
+
+        // Create default configuration files
+        let default_config = AppConfig::default();
+        if let Err(e) = filestore.save_config(&default_config, defaults::persistence::CONFIG_FILE) {
+            eprintln!("Failed to save configuration: {e}");
+            std::process::exit(1);
+        }
+
+        println!("Created default configuration in {path}");
+        std::process::exit(0);
+    }
+
+    if let Some(path) = validate {
+        // Validate existing configuration
+        let config = FilestoreConfig {
+            data_dir: path.clone(),
+            create_defaults_if_missing: false,
+            ..Default::default()
+        };
+        let filestore = match Filestore::with_config(config) {
+            Ok(fs) => fs,
+            Err(e) => {
+                eprintln!("Failed to access filestore: {e}");
+                std::process::exit(1);
+            }
+        };
+
+        // Try to load and validate configuration
+        match filestore.load_config(defaults::persistence::CONFIG_FILE) {
+            Ok(_) => {
+                println!("Configuration in {path} is valid");
+                std::process::exit(0);
+            }
+            Err(e) => {
+                eprintln!("Configuration validation failed: {e}");
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Initializes a proxy manager with judge and, in full mode, sleuth.
+///
+/// # Arguments
+/// * `mode` - Judgement mode deciding the judge's retry policy and whether to
+///   initialize the sleuth component
+/// * `capture` - Number of raw responses to capture, zero to capture none
+///
+/// # Returns
+/// * `Result<ProxyManager, Box<dyn std::error::Error>>` - The initialized manager or an error
+fn init_proxy_manager(
+    mode: JudgementMode,
+    capture: usize,
+) -> Result<ProxyManager, Box<dyn std::error::Error>> {
+    let mut manager = ProxyManager::new()?;
+    manager.response_capture().set_capacity(capture);
+
+    // Initialize judge
+    manager.init_judge_with_retry_policy(JudgementRetryPolicy::for_mode(mode))?;
+
+    // Initialize sleuth if needed
+    if mode == JudgementMode::Full {
+        manager.init_sleuth()?;
+    }
+
+    Ok(manager)
+}
+
+/// Initializes the proxy manager a worker judges with.
+///
+/// # Arguments
+/// * `vantage` - Vantage point the judged latencies are recorded under
+///
+/// # Returns
+/// * `Result<ProxyManager, Box<dyn std::error::Error>>` - The manager or an error
+fn init_worker_manager(vantage: &str) -> Result<ProxyManager, Box<dyn std::error::Error>> {
+    let judge = Judge::new()?
+        .with_retry_policy(JudgementRetryPolicy::for_mode(JudgementMode::Quick))
+        .with_vantage(vantage);
+    Ok(ProxyManager::builder().judge(judge).build()?)
+}
+
+/// Streams the events published to a new bus as Server-Sent Events.
+///
+/// # Arguments
+/// * `addr` - Address to serve the events on
+///
+/// # Returns
+/// * `EventBus` - The bus whose events are streamed
+async fn serve_events(addr: &str) -> EventBus {
+    let bus = EventBus::default();
+    let server = match EventServer::bind(addr, bus.clone()).await {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Failed to stream events on {addr}: {e}");
+            std::process::exit(1);
+        }
+    };
+    let local = server
+        .local_addr()
+        .map_or_else(|_| addr.to_string(), |local| local.to_string());
+    println!("Streaming events from http://{local}/events");
+
+    tokio::spawn(async move {
+        if let Err(e) = server.run().await {
+            eprintln!("Event stream stopped: {e}");
+        }
+    });
+    bus
+}
+
+/// Saves the responses captured by a manager for `debug last-responses`.
+///
+/// Nothing is saved when capturing is disabled, so the responses of an
+/// earlier run are kept.
+///
+/// # Arguments
+/// * `manager` - The manager whose capture to save
+/// * `data_dir` - Directory containing data files
+fn save_captured_responses(manager: &ProxyManager, data_dir: &Path) {
+    let capture = manager.response_capture();
+    if !capture.is_enabled() {
+        return;
+    }
+
+    if let Some(filestore) = get_filestore(data_dir) {
+        if let Err(e) = filestore.save_captured_responses(
+            &capture.recent(),
+            defaults::persistence::CAPTURED_RESPONSES_FILE,
+        ) {
+            eprintln!("Failed to save captured responses: {e}");
+        }
+    }
+}
+
+/// Handles the Proxy command, testing individual proxies.
+///
+/// # Arguments
+/// * `judge` - Optional proxy URL to test
+/// * `dry` - Whether to avoid saving results
+/// * `verify_sni` - Whether to also check that the proxy leaves TLS SNI and Host intact
+/// * `data_dir` - Data directory to save to (default: the platform data directory)
+/// * `capture` - Number of raw responses to capture, zero to capture none
+/// * `events` - Address to stream the check's events on, if any
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+async fn handle_proxy_command(
+    judge: Option<String>,
+    dry: bool,
+    verify_sni: bool,
+    data_dir: Option<String>,
+    capture: usize,
+    events: Option<&str>,
+) {
+    if let Some(proxy_url) = judge {
+        let data_dir = filesystem::resolve_data_dir(data_dir.as_deref());
+
+        // Initialize proxy manager and required components
+        let mut manager = match init_proxy_manager(JudgementMode::Full, capture) {
+            Ok(m) => m,
+            Err(e) => {
+                eprintln!("Failed to initialize proxy manager: {e}");
+                std::process::exit(1);
+            }
+        };
+        manager.set_dry_run(dry);
+        if let Some(addr) = events {
+            manager.set_event_bus(serve_events(addr).await);
+        }
+
+        // Parse proxy URL
+        let proxy = match proxy_url.parse::<Proxy>() {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("Invalid proxy URL: {e}");
+                std::process::exit(1);
+            }
+        };
+
+        println!("Testing proxy: {proxy}");
+
+        // Get proxy ID for management
+        let proxy_id = proxy.to_connection_string();
+
+        // Add proxy to manager
+        if let Err(e) = manager.add_proxy(proxy) {
+            eprintln!("Failed to add proxy: {e}");
+            std::process::exit(1);
+        }
+
+        // Check proxy connectivity and anonymity, then enrich with IP metadata
+        let outcome = match manager.check_proxy(&proxy_id).await {
+            Ok(()) => manager
+                .enrich_proxy(&proxy_id)
+                .await
+                .map_err(|e| format!("Failed to enrich proxy data: {e}")),
+            Err(e) => Err(format!("Proxy test failed: {e}")),
+        };
+
+        // An inconclusive SNI check doesn't fail the test, the proxy just stays unverified
+        if verify_sni && outcome.is_ok() {
+            if let Err(e) = manager.verify_sni(&proxy_id).await {
+                eprintln!("SNI check inconclusive: {e}");
+            }
+        }
+
+        // Keep the raw responses even when the test failed, that is when they help most
+        save_captured_responses(&manager, &data_dir);
+        if let Err(message) = outcome {
+            eprintln!("{message}");
+            std::process::exit(1);
+        }
+
+        // Get the tested proxy, which a dry run holds back from the pool
+        let tested = if dry {
+            manager.dry_run_changes().proxy(&proxy_id)
+        } else {
+            manager.get_proxy(&proxy_id)
+        };
+        #[allow(clippy::cast_precision_loss)]
+        if let Some(proxy) = tested {
+            // Print detailed results
+            println!("\nProxy Test Results:");
+            println!("------------------");
+            print_proxy_details(proxy);
+
+            println!("\nTest Statistics:");
+            println!(
+                "Success Rate: {:.2}%",
+                (proxy.check_success_rate() as f64 / 100.0)
+            );
+            println!(
+                "Checks: {} total, {} failed",
+                proxy.check_count, proxy.check_failure_count
+            );
+
+            // Save to proxy list if test was successful; a dry run writes nothing
+            if !dry && proxy.effective_lifecycle().is_usable() {
+                if let Some(filestore) = get_filestore(&data_dir) {
+                    match manager.sync_with_filestore(&filestore, SyncPolicy::Merge) {
+                        Ok(_) => println!("\nProxy saved to list successfully"),
+                        Err(e) => eprintln!("Failed to save proxy: {e}"),
+                    }
+                }
+            }
+        }
+
+        std::process::exit(0);
+    }
+}
+
+/// Handles the proxy command's `--import` option.
+///
+/// Reads the proxies in the file, skipping entries that can't be read, and
+/// adds those new to the persistent proxy list. With a testing mode, the new
+/// proxies are then judged and their results saved.
+///
+/// # Arguments
+/// * `file` - File to import, or `-` for standard input
+/// * `format` - Format of the file, or `None` to detect it
+/// * `check` - Testing mode for the imported proxies
+/// * `dry` - If true, report what would be imported without saving
+/// * `data_dir` - Directory where proxies are stored (default: the platform data directory)
+/// * `capture` - Number of raw responses to capture, zero to capture none
+/// * `events` - Address to stream the checks' events on, if any
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+async fn handle_proxy_import_command(
+    file: &str,
+    format: Option<ExportFormat>,
+    check: JudgementMode,
+    dry: bool,
+    data_dir: Option<String>,
+    capture: usize,
+    events: Option<&str>,
+) {
+    let data_dir = filesystem::resolve_data_dir(data_dir.as_deref());
+    let Some(filestore) = get_filestore(&data_dir) else {
+        std::process::exit(1);
+    };
+
+    // A dry run compares the file with the stored list without touching it
+    let path = Path::new(file);
+    let imported = if dry {
+        filestore.preview_import(defaults::persistence::PROXIES_FILE, path, format)
+    } else {
+        filestore.import_proxies(defaults::persistence::PROXIES_FILE, path, format)
+    };
+    let summary = match imported {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("Failed to import proxies: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    for issue in &summary.skipped {
+        eprintln!("Skipped {issue}");
+    }
+    println!(
+        "{} {} new proxies ({} read, {} already known, {} skipped)",
+        if dry { "Would import" } else { "Imported" },
+        summary.added.len(),
+        summary.read,
+        summary.duplicates,
+        summary.skipped.len()
+    );
+
+    if check == JudgementMode::None || summary.added.is_empty() {
+        std::process::exit(0);
+    }
+
+    let events = match events {
+        Some(addr) => Some(serve_events(addr).await),
+        None => None,
+    };
+    let tested =
+        match test_and_enrich_proxies(summary.added, check, capture, &data_dir, events).await {
+            Ok(tested) => tested,
+            Err(e) => {
+                eprintln!("Failed to test imported proxies: {e}");
+                std::process::exit(1);
+            }
+        };
+    let usable = tested
+        .iter()
+        .filter(|proxy| proxy.effective_lifecycle().is_usable())
+        .count();
+    println!("\n{usable} of {} imported proxies work", tested.len());
+
+    if !dry {
+        let saved = ProxyManager::new()
+            .map_err(|e| e.to_string())
+            .and_then(|mut manager| {
+                manager.add_proxies(tested).map_err(|e| e.to_string())?;
+                manager
+                    .sync_with_filestore(&filestore, SyncPolicy::Merge)
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = saved {
+            eprintln!("Failed to save test results: {e}");
+            std::process::exit(1);
+        }
+    }
+
+    std::process::exit(0);
+}
+
+/// Sets up a filestore with the given configuration path.
+///
+/// # Arguments
+/// * `config_path` - Path to the configuration directory
+///
+/// # Returns
+/// * `Result<Filestore, Box<dyn std::error::Error>>` - The initialized filestore or an error
+fn setup_filestore(config_path: &Path) -> Result<Filestore, Box<dyn std::error::Error>> {
+    Ok(Filestore::with_config(FilestoreConfig {
+        data_dir: config_path.to_string_lossy().to_string(),
+        ..Default::default()
+    })?)
+}
+
+/// Tests and enriches proxies based on the specified judgement mode.
+///
+/// # Arguments
+/// * `proxies` - List of proxies to test
+/// * `mode` - Judgement mode determining the level of testing and enrichment
+/// * `capture` - Number of raw responses to capture, zero to capture none
+/// * `data_dir` - Directory to save captured responses to
+/// * `events` - Bus to publish the checks and lookups to, if any
+///
+/// # Returns
+/// * `Result<Vec<Proxy>, Box<dyn std::error::Error>>` - The tested proxies or an error
+async fn test_and_enrich_proxies(
+    mut proxies: Vec<Proxy>,
+    mode: JudgementMode,
+    capture: usize,
+    data_dir: &Path,
+    events: Option<EventBus>,
+) -> Result<Vec<Proxy>, Box<dyn std::error::Error>> {
+    if mode == JudgementMode::None {
+        return Ok(proxies);
+    }
+
+    // Initialize manager
+    let mut manager = init_proxy_manager(mode, capture)?;
+    if let Some(events) = events {
+        manager.set_event_bus(events);
+    }
+    let outcome = judge_proxies(&mut manager, &mut proxies, mode).await;
+    save_captured_responses(&manager, data_dir);
+    outcome?;
+
+    Ok(proxies)
+}
+
+/// Tests proxies and, in full mode, gathers their IP metadata.
+///
+/// # Arguments
+/// * `manager` - Manager with the judge and sleuth to use
+/// * `proxies` - List of proxies to test
+/// * `mode` - Judgement mode determining the level of testing and enrichment
+///
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - Success or an error
+async fn judge_proxies(
+    manager: &mut ProxyManager,
+    proxies: &mut [Proxy],
+    mode: JudgementMode,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Test proxies (basic connectivity)
+    println!("Testing proxies...");
+    let pb = ProgressBar::new(proxies.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
+            .expect("Failed to create progress bar style")
+            .progress_chars("##-"),
+    );
+
+    // Check all proxies with progress
+    manager.check_all_proxies(proxies, 10).await?;
+    pb.finish_with_message("Proxy testing complete");
+
+    // Gather additional information in full mode
+    if mode == JudgementMode::Full {
+        println!("\nGathering detailed proxy information...");
+        let pb = ProgressBar::new(proxies.len() as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
+                .expect("Failed to create progress bar style")
+                .progress_chars("##-"),
+        );
+
+        manager.enrich_all_proxies(proxies, 10).await?;
+        pb.finish_with_message("Detail gathering complete");
+    }
+
+    Ok(())
+}
+
+/// Handles saving proxies and sources to the filestore.
+///
+/// # Arguments
+/// * `proxies` - List of proxies to save
+/// * `source` - The source to save
+/// * `filestore` - Filestore for persistence
+/// * `dry` - Whether to avoid saving
+/// * `raw_response` - Optional raw response data to save
+/// * `mode` - Judgement mode used
+/// * `scrape_url` - URL that was scraped
+///
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - Success or an error
+fn save_results(
+    proxies: &[Proxy],
+    source: &Source,
+    filestore: &Filestore,
+    dry: bool,
+    raw_response: Option<String>,
+    mode: JudgementMode,
+    scrape_url: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if dry {
+        return Ok(());
+    }
+
+    // Merge the results into the stored pools
+    let mut manager = ProxyManager::new()?;
+    manager.add_proxies(proxies.to_vec())?;
+    manager.add_source(source.clone())?;
+
+    // Save raw response data if in full mode
+    if let (JudgementMode::Full, Some(raw_response)) = (mode, raw_response) {
+        let timestamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+        let sanitized_url = utils::sanitize_url_for_filename(scrape_url);
+        let dump_filename = format!("{timestamp}-{sanitized_url}.txt");
+
+        if let Err(e) = std::fs::write(dump_filename.clone(), raw_response) {
+            eprintln!("Failed to save raw response: {e}");
+        } else {
+            println!("Raw response saved to {dump_filename}");
+        }
+    }
+
+    manager.sync_with_filestore(filestore, SyncPolicy::Merge)?;
+    println!("Source saved successfully");
+
+    Ok(())
+}
+
+/// Applies the template variables and headers given on the command line to a source.
+///
+/// # Arguments
+/// * `source` - The source to customize
+/// * `vars` - Template variables in `NAME=VALUES` form, values separated by commas
+/// * `headers` - Further headers in `NAME: VALUE` form, sent in the order given
+///
+/// # Returns
+/// * `Result<(), String>` - Success, or a message naming the malformed option
+fn customize_source(
+    source: &mut Source,
+    vars: Vec<String>,
+    headers: Vec<String>,
+) -> Result<(), String> {
+    for var in vars {
+        let Some((name, values)) = var.split_once('=') else {
+            return Err(format!(
+                "Invalid template variable '{var}', expected NAME=VALUES"
+            ));
+        };
+        source.add_template_variable(
+            name.to_string(),
+            values.split(',').map(str::to_string).collect(),
+        );
+    }
+    for header in headers {
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(format!("Invalid header '{header}', expected NAME: VALUE"));
+        };
+        source.add_header(name.trim().to_string(), value.trim().to_string());
+    }
+    Ok(())
+}
+
+/// Scrapes and processes proxies from a source URL.
+///
+/// This function handles the entire proxy scraping workflow:
+/// 1. Loads configuration and initializes components
+/// 2. Scrapes proxies from the specified URL
+/// 3. Tests and enriches the proxies based on the judgement mode
+/// 4. Saves the results to the filestore
+///
+/// # Arguments
+///
+/// * `scrape` - URL to scrape for proxies
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `useragent` - Custom User-Agent string to use for requests
+/// * `pattern` - Custom regex pattern for finding proxies
+/// * `vars` - Template variables in `NAME=VALUES` form, values separated by commas
+/// * `headers` - Further headers in `NAME: VALUE` form, sent in the order given
+/// * `judge` - Judgement mode determining test intensity:
+///   - None (0): No testing, just scrape
+///   - Quick (1): Basic connectivity testing
+///   - Full (2): Comprehensive testing with metadata collection
+/// * `dry` - If true, don't save results to persistent storage
+/// * `capture` - Number of raw responses to capture, zero to capture none
+///
+/// # Returns
+///
+/// * `()` - The function exits the process with an appropriate status code
+#[allow(clippy::too_many_arguments)]
+async fn handle_source_command(
+    scrape: String,
+    config: Option<String>,
+    useragent: Option<String>,
+    pattern: Option<String>,
+    vars: Vec<String>,
+    headers: Vec<String>,
+    judge: JudgementMode,
+    wayback: Option<usize>,
+    dry: bool,
+    capture: usize,
+) {
+    // Load configuration
+    let config_path = filesystem::resolve_data_dir(config.as_deref());
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // Initialize source with provided options
+    let mut source = match Source::new(
+        scrape.clone(),
+        useragent.unwrap_or_else(|| utils::get_random_user_agent().to_string()),
+        pattern.unwrap_or_else(|| defaults::regex_patterns::IP_PORT.to_string()),
+    ) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to create source: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // Apply template variables and headers
+    if let Err(message) = customize_source(&mut source, vars, headers) {
+        eprintln!("{message}");
+        std::process::exit(1);
+    }
+    if let Err(e) = source.validate() {
+        eprintln!("Invalid source: {e}");
+        std::process::exit(1);
+    }
+
+    // Create requestor for fetching
+    let requestor = match Requestor::new() {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Failed to create requestor: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // Fetch proxies from the source
+    println!("Scraping proxies from {scrape}");
+    let (proxies, raw_response) = match source.fetch_proxies_with_response(&requestor).await {
+        Ok((proxies, response)) => {
+            source.record_success(proxies.len());
+            (proxies, response)
+        }
+        Err(e) => {
+            eprintln!("Failed to fetch proxies: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("Found {} proxies", proxies.len());
+
+    // Add proxies from archived snapshots if requested
+    let proxies = match wayback {
+        Some(max_snapshots) => {
+            add_archived_proxies(proxies, &source, &requestor, &filestore, max_snapshots).await
+        }
+        None => proxies,
+    };
+
+    // Test and enrich proxies if requested
+    let proxies = match test_and_enrich_proxies(proxies, judge, capture, &config_path, None).await {
+        Ok(proxies) => {
+            if judge != JudgementMode::None {
+                // Count working proxies
+                let working = proxies
+                    .iter()
+                    .filter(|p| p.effective_lifecycle().is_usable())
+                    .count();
+                println!("\nWorking proxies: {}/{}", working, proxies.len());
+            }
+            proxies
+        }
+        Err(e) => {
+            eprintln!("Failed during proxy testing: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    // Save results
+    let raw_response_to_save = if judge == JudgementMode::Full {
+        Some(raw_response)
+    } else {
+        None
+    };
+
+    if let Err(e) = save_results(
+        &proxies,
+        &source,
+        &filestore,
+        dry,
+        raw_response_to_save,
+        judge,
+        &scrape,
+    ) {
+        eprintln!("Failed to save results: {e}");
+        std::process::exit(1);
+    }
+
+    std::process::exit(0);
+}
+
+/// Adds the proxies found in archived snapshots of a source.
+///
+/// The archive is queried under the configured identity.
+///
+/// # Arguments
+/// * `proxies` - Proxies already found on the live source
+/// * `source` - The source whose snapshots to scrape
+/// * `requestor` - The HTTP client to use for making requests
+/// * `filestore` - Filestore holding the configuration
+/// * `max_snapshots` - Number of the most recent snapshots to scrape
+///
+/// # Returns
+/// * `Vec<Proxy>` - The proxies with the ones only found in snapshots added;
+///   the program exits if the snapshots can't be scraped
+async fn add_archived_proxies(
+    mut proxies: Vec<Proxy>,
+    source: &Source,
+    requestor: &Requestor,
+    filestore: &Filestore,
+    max_snapshots: usize,
+) -> Vec<Proxy> {
+    let app_config = match filestore.load_config(defaults::persistence::CONFIG_FILE) {
+        Ok(app_config) => app_config,
+        Err(e) => {
+            eprintln!("Failed to load configuration: {e}");
+            std::process::exit(1);
+        }
+    };
+    let harvester = Wayback::from_config(&app_config).with_max_snapshots(max_snapshots);
+
+    println!(
+        "Scraping up to {max_snapshots} archived snapshots of {}",
+        source.url
+    );
+    let archived = match harvester.harvest(requestor, source).await {
+        Ok(archived) => archived,
+        Err(e) => {
+            eprintln!("Failed to harvest snapshots: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut seen: HashSet<_> = proxies.iter().map(|p| (p.address, p.port)).collect();
+    let found = proxies.len();
+    proxies.extend(
+        archived
+            .into_iter()
+            .filter(|p| seen.insert((p.address, p.port))),
+    );
+    println!("Found {} more proxies in snapshots", proxies.len() - found);
+    proxies
+}
+
+/// Prints how many proxies are known to run on hosting, mobile or anycast
+/// networks, and how many fall under each regional registry.
+///
+/// # Arguments
+///
+/// * `manager` - The manager holding the proxies
+fn print_network_breakdown(manager: &ProxyManager) {
+    let proxies = manager.get_all_proxies();
+    let count =
+        |f: fn(&Proxy) -> Option<bool>| proxies.iter().filter(|p| f(p) == Some(true)).count();
+    println!(
+        "Hosting: {}, mobile: {}, anycast: {}",
+        count(Proxy::is_hosting),
+        count(Proxy::is_mobile),
+        count(Proxy::is_anycast)
+    );
+    print_counts(
+        "Registries",
+        proxies
+            .iter()
+            .filter_map(|p| p.registry().map(|r| r.to_string())),
+    );
+    print_counts(
+        "Cloud providers",
+        proxies
+            .iter()
+            .filter_map(|p| p.cloud_provider().map(|c| c.to_string())),
+    );
+}
+
+/// Prints how often each value occurs, unless there are none.
+///
+/// # Arguments
+///
+/// * `label` - Label of the printed line
+/// * `values` - The values to count
+fn print_counts(label: &str, values: impl Iterator<Item = String>) {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for value in values {
+        *counts.entry(value).or_insert(0) += 1;
+    }
+    if !counts.is_empty() {
+        let counts: Vec<String> = counts
+            .iter()
+            .map(|(value, count)| format!("{value}: {count}"))
+            .collect();
+        println!("{label}: {}", counts.join(", "));
+    }
+}
+
+/// Prints how long proxies keep working, overall, per source and per country.
+///
+/// # Arguments
+///
+/// * `churn` - Survival statistics of the stored proxies
+fn print_churn(churn: &ChurnStats) {
+    if churn.overall.alive + churn.overall.dead == 0 {
+        return;
+    }
+
+    println!("\nChurn");
+    println!("-----");
+    println!("Overall: {}", describe_survival(&churn.overall));
+    for (label, groups) in [("source", &churn.by_source), ("country", &churn.by_country)] {
+        let mut groups: Vec<_> = groups
+            .iter()
+            .filter(|(_, stats)| stats.alive + stats.dead > 0)
+            .collect();
+        if groups.is_empty() {
+            continue;
+        }
+        groups.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.alive + stats.dead));
+        println!("By {label}:");
+        for (group, stats) in groups {
+            println!("  {group}: {}", describe_survival(stats));
+        }
+    }
+}
+
+/// Describes survival statistics on a single line.
+///
+/// # Arguments
+///
+/// * `stats` - The survival statistics to describe
+///
+/// # Returns
+///
+/// The counts, median lifetime and share of survivors at each horizon
+fn describe_survival(stats: &SurvivalStats) -> String {
+    let age = |age: chrono::Duration| match age.num_minutes() {
+        minutes @ ..60 => format!("{minutes}m"),
+        minutes @ ..1440 => format!("{}h", minutes / 60),
+        minutes => format!("{}d", minutes / 1440),
+    };
+    let median = stats
+        .median_lifetime
+        .map_or_else(|| "not reached".to_string(), age);
+    let survival: Vec<String> = stats
+        .survival
+        .iter()
+        .map(|(horizon, share)| format!("{} {:.0}%", age(*horizon), share * 100.0))
+        .collect();
+    format!(
+        "{} alive, {} dead, {} never worked, median life {median}, surviving {}",
+        stats.alive,
+        stats.dead,
+        stats.never_alive,
+        survival.join(", ")
+    )
+}
+
+/// Handles the Export command, writing stored proxy metadata to a file.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `format` - Format of the exported file
+/// * `columns` - Columns of CSV and JSON exports (default: all)
+/// * `output` - File to write (default: `proxies.<extension>` in the current directory)
+/// * `sort` - Key to order the proxies by before the overall quality order
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+fn handle_export_command(
+    config: Option<&str>,
+    format: ExportFormat,
+    columns: &[ExportColumn],
+    output: Option<String>,
+    sort: Option<ProxySortKey>,
+) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut manager = match ProxyManager::new() {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Failed to initialize proxy manager: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = manager.import_from_filestore(&filestore, SyncPolicy::PreferDisk) {
+        eprintln!("Failed to load stored data: {e}");
+        std::process::exit(1);
+    }
+
+    // Ties under the chosen key fall back to the overall quality order
+    let keys: Vec<ProxySortKey> = sort.into_iter().chain(ProxySortKey::QUALITY).collect();
+    let output = output.unwrap_or_else(|| format!("proxies.{}", format.extension()));
+    match export::export_proxies_with_columns(
+        Path::new(&output),
+        format,
+        columns,
+        manager.get_sorted_proxies(&keys),
+    ) {
+        Ok(count) => {
+            println!("Exported {count} proxies to {output} as {format}");
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Failed to export proxies: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Handles the Report command, printing statistics about stored proxies and sources.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+fn handle_report_command(config: Option<&str>) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut manager = match ProxyManager::new() {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Failed to initialize proxy manager: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = manager.import_from_filestore(&filestore, SyncPolicy::PreferDisk) {
+        eprintln!("Failed to load stored data: {e}");
+        std::process::exit(1);
+    }
+
+    let proxy_stats = manager.get_proxy_stats();
+    println!("Proxies");
+    println!("-------");
+    println!(
+        "Total: {}, working: {}",
+        proxy_stats.total, proxy_stats.working
+    );
+    println!(
+        "Distinct working exits: {} by address, {} by subnet",
+        manager.count_working_exits(DedupPolicy::Ip),
+        manager.count_working_exits(DedupPolicy::Subnet)
+    );
+    let mut lifecycles: Vec<_> = proxy_stats.by_lifecycle.iter().collect();
+    lifecycles.sort();
+    for (lifecycle, count) in lifecycles {
+        println!("  {lifecycle}: {count}");
+    }
+    if let Some(latency) = proxy_stats.avg_latency {
+        println!("Average latency: {latency}");
+    }
+
+    print_network_breakdown(&manager);
+
+    let source_stats = manager.get_source_stats();
+    println!("\nSources");
+    println!("-------");
+    println!(
+        "Total: {}, active: {}, proxies found: {}",
+        source_stats.total, source_stats.active, source_stats.total_proxies_found
+    );
+
+    let mut failing: Vec<_> = source_stats.failures_by_source.iter().collect();
+    failing.sort_by_key(|(_, f)| std::cmp::Reverse(f.failure_count));
+    for (url, failures) in failing {
+        println!("\n{url}: {} failures", failures.failure_count);
+        for (reason, count) in &failures.reasons {
+            println!("  {count:>5} x {reason}");
+        }
+        for (code, count) in &failures.status_codes {
+            println!("  {count:>5} x HTTP {code}");
+        }
+        for failure in &failures.recent {
+            let code = failure
+                .status_code
+                .map(|c| format!(" (HTTP {c})"))
+                .unwrap_or_default();
+            println!(
+                "  {} {}{code}",
+                failure.at.format("%Y-%m-%d %H:%M:%S"),
+                failure.reason
+            );
+        }
+    }
+
+    print_churn(&manager.get_churn_stats());
+
+    match filestore.load_provider_usage(defaults::persistence::PROVIDER_USAGE_FILE) {
+        Ok(usage) if !usage.is_empty() => {
+            println!("\nLookup providers");
+            println!("----------------");
+            let now = chrono::Utc::now();
+            for mut provider in usage {
+                provider.roll_over(now);
+                let quota = |q: Option<u64>| q.map(|q| format!("/{q}")).unwrap_or_default();
+                println!(
+                    "{}: {}{} this hour, {}{} today, {} total",
+                    provider.provider,
+                    provider.hour_count,
+                    quota(provider.quota.hourly),
+                    provider.day_count,
+                    quota(provider.quota.daily),
+                    provider.total
+                );
+            }
+        }
+        Ok(_) | Err(FilestoreError::FileNotFound(_)) => {}
+        Err(e) => eprintln!("Failed to load provider usage: {e}"),
+    }
+
+    match filestore.load_client_stats(defaults::persistence::CLIENT_STATS_FILE) {
+        Ok(clients) if !clients.is_empty() => print_client_stats(&clients),
+        Ok(_) => {}
+        Err(e) => eprintln!("Failed to load listener clients: {e}"),
+    }
+
+    std::process::exit(0);
+}
+
+/// Prints what each client of the listener used it for.
+///
+/// # Arguments
+/// * `clients` - Statistics of the clients, by source address
+fn print_client_stats(clients: &ClientStatsMap) {
+    println!("\nListener clients");
+    println!("----------------");
+    for (client, stats) in clients {
+        println!(
+            "{client}: {} connections, {}% failed, {} bytes sent, {} bytes received",
+            stats.connections,
+            stats.failure_rate(),
+            stats.bytes_sent,
+            stats.bytes_received
+        );
+        let mut upstreams: Vec<_> = stats.upstreams.iter().collect();
+        upstreams.sort_by_key(|(_, count)| std::cmp::Reverse(**count));
+        for (upstream, count) in upstreams {
+            println!("  {count:>5} x {upstream}");
+        }
+    }
+}
+
+/// Handles the `ValidateData` command, reporting invalid entries of the stored files.
+///
+/// Exits with a non-zero status if problems were found and left in place.
+///
+/// # Arguments
+/// * `config` - Directory containing the data files
+/// * `fix` - Whether to rewrite the files without their problems
+fn handle_validate_data_command(config: Option<&str>, fix: bool) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let report = match filestore.validate_data(fix) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Failed to validate stored data: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    for issue in &report.issues {
+        println!("{issue}");
+    }
+    for file in &report.repaired {
+        println!("Repaired {}", file.display());
+    }
+    println!(
+        "Checked {} files, found {} problems",
+        report.checked.len(),
+        report.issues.len()
+    );
+
+    let unrepaired = report
+        .issues
+        .iter()
+        .any(|issue| !report.repaired.contains(&issue.file));
+    if unrepaired {
+        if !fix {
+            println!("Run again with --fix to repair the proxies and sources files");
+        }
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
+
+/// Handles the Recheck command, re-judging stale proxies in the stored pool.
+///
+/// Progress is checkpointed to the data directory, so an interrupted run
+/// resumes where it left off when the command is run again.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `max_age` - How long a check result stays fresh, in seconds
+/// * `anonymity_max_age` - How long a judged anonymity level is trusted, in
+///   seconds, zero to re-judge it every time
+/// * `concurrency` - Maximum number of proxies judged concurrently
+/// * `capture` - Number of raw responses to capture, zero to capture none
+/// * `events` - Address to stream the rechecks' events on, if any
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+async fn handle_recheck_command(
+    config: Option<&str>,
+    max_age: u64,
+    anonymity_max_age: u64,
+    concurrency: usize,
+    capture: usize,
+    events: Option<&str>,
+) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut manager = match init_proxy_manager(JudgementMode::Quick, capture) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Failed to initialize proxy manager: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = manager.import_from_filestore(&filestore, SyncPolicy::PreferDisk) {
+        eprintln!("Failed to load stored proxies: {e}");
+        std::process::exit(1);
+    }
+    if anonymity_max_age > 0 {
+        manager.set_anonymity_max_age(Some(Duration::from_secs(anonymity_max_age)));
+    }
+    if let Some(addr) = events {
+        manager.set_event_bus(serve_events(addr).await);
+    }
+
+    let max_age = chrono::Duration::seconds(i64::try_from(max_age).unwrap_or(i64::MAX));
+    let outcome = manager
+        .recheck_stale_proxies_checkpointed(max_age, concurrency, &filestore)
+        .await;
+    save_captured_responses(&manager, &config_path);
+    let rechecked = match outcome {
+        Ok(rechecked) => rechecked,
+        Err(e) => {
+            eprintln!("Recheck interrupted, progress is checkpointed: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let retired = manager
+        .retire_quarantined_proxies(Duration::from_secs(defaults::rotation::RETIRE_AFTER_SECS));
+
+    if let Err(e) = manager.sync_with_filestore(&filestore, SyncPolicy::Merge) {
+        eprintln!("Failed to save rechecked proxies: {e}");
+        std::process::exit(1);
+    }
+
+    let stats = manager.get_proxy_stats();
+    println!(
+        "Rechecked {rechecked} proxies, {} of {} working, {retired} retired",
+        stats.working, stats.total
+    );
+    std::process::exit(0);
+}
+
+/// Handles the Serve command, relaying SOCKS5 clients through the stored
+/// working proxies until the listener fails or the process is interrupted.
+///
+/// The rotation picks up the cursor, cooldowns, and bans it saved when it
+/// last stopped, and saves them again at a fixed interval and on the way out.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `socks` - Address to accept SOCKS5 clients on
+/// * `attempts` - Number of proxies tried per client connection
+/// * `diversity_window` - Number of latest connections whose subnets and ASNs
+///   are avoided, none at zero
+/// * `watch_top` - Number of the highest scoring proxies whose ports are
+///   watched, none at zero
+/// * `events` - Address to stream the probes of watched ports on, if any
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+async fn handle_serve_command(
+    config: Option<&str>,
+    socks: &str,
+    attempts: usize,
+    diversity_window: usize,
+    watch_top: usize,
+    events: Option<&str>,
+) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut manager = match ProxyManager::new() {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Failed to initialize proxy manager: {e}");
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = manager.import_from_filestore(&filestore, SyncPolicy::PreferDisk) {
+        eprintln!("Failed to load stored proxies: {e}");
+        std::process::exit(1);
+    }
+
+    let mut pool = manager
+        .rotation_pool(RotationStrategy::Weighted)
+        .with_subnet_diversity(diversity_window);
+    if pool.is_empty() {
+        eprintln!("No working proxies stored, run recheck or source --judge first");
+        std::process::exit(1);
+    }
+    match filestore.load_rotation_state(defaults::persistence::ROTATION_STATE_FILE) {
+        Ok(Some(state)) => pool = pool.with_state(state),
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load rotation state, starting afresh: {e}"),
+    }
+
+    let clients = match filestore.load_client_stats(defaults::persistence::CLIENT_STATS_FILE) {
+        Ok(clients) => clients,
+        Err(e) => {
+            eprintln!("Failed to load listener clients: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let listener = match SocksListener::bind(socks, pool).await {
+        Ok(listener) => listener
+            .with_max_attempts(attempts)
+            .with_client_stats(clients),
+        Err(e) => {
+            eprintln!("Failed to listen on {socks}: {e}");
+            std::process::exit(1);
+        }
+    };
+    let count = listener.pool().lock().map_or(0, |pool| pool.len());
+    println!("Relaying SOCKS5 clients on {socks} through {count} proxies");
+
+    let clients = listener.clients();
+    let pool = listener.pool();
+    if watch_top > 0 {
+        let mut watcher = PortWatcher::new(Arc::clone(&pool)).with_top(watch_top);
+        if let Some(addr) = events {
+            watcher = watcher.with_events(serve_events(addr).await);
+        }
+        tokio::spawn(watcher.run());
+    } else if events.is_some() {
+        eprintln!("No ports are watched, so no events will be streamed");
+    }
+    tokio::select! {
+        result = listener.run() => {
+            save_listener_state(&clients, &pool, &filestore);
+            if let Err(e) = result {
+                eprintln!("Listener stopped: {e}");
+            }
+            std::process::exit(1);
+        }
+        _ = tokio::signal::ctrl_c() => {
+            save_listener_state(&clients, &pool, &filestore);
+            println!("Listener stopped");
+        }
+        () = save_listener_state_periodically(&clients, &pool, &filestore) => {}
+    }
+}
+
+/// Handles the Daemon command, running the schedules of the configuration
+/// against the stored proxies until the process is interrupted.
+///
+/// Each round of due jobs is journaled as it runs and merged into the stored
+/// pools once it finishes, so other commands see its results.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `capture` - Number of raw responses to capture, zero to capture none
+/// * `events` - Address to stream the jobs' events on, if any
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+async fn handle_daemon_command(config: Option<&str>, capture: usize, events: Option<&str>) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+    let app_config = match filestore.load_config(defaults::persistence::CONFIG_FILE) {
+        Ok(app_config) => app_config,
+        Err(e) => {
+            eprintln!("Failed to load configuration: {e}");
             std::process::exit(1);
         }
+    };
 
-        #[allow(clippy::cast_precision_loss)]
-        // Get the tested proxy
-        if let Some(proxy) = manager.get_proxy(&proxy_id) {
-            // Print detailed results
-            println!("\nProxy Test Results:");
-            println!("------------------");
-            print_proxy_details(proxy);
+    let mut scheduler = match Scheduler::from_config(&app_config) {
+        Ok(scheduler) => scheduler.with_checkpoints(filestore.clone()),
+        Err(e) => {
+            eprintln!("Invalid schedule in configuration: {e}");
+            std::process::exit(1);
+        }
+    };
+    let upcoming = scheduler.upcoming();
+    if upcoming.is_empty() {
+        eprintln!("No schedules enabled in the configuration");
+        std::process::exit(1);
+    }
 
-            println!("\nTest Statistics:");
-            println!(
-                "Success Rate: {:.2}%",
-                (proxy.check_success_rate() as f64 / 100.0)
-            );
-            println!(
-                "Checks: {} total, {} failed",
-                proxy.check_count, proxy.check_failure_count
-            );
+    // Cloud range refreshes need the sleuth
+    let mut manager = match init_proxy_manager(JudgementMode::Full, capture) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Failed to initialize proxy manager: {e}");
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = manager.import_from_filestore(&filestore, SyncPolicy::PreferDisk) {
+        eprintln!("Failed to load stored proxies: {e}");
+        std::process::exit(1);
+    }
+    if let Some(addr) = events {
+        manager.set_event_bus(serve_events(addr).await);
+    }
 
-            // Save to proxy list if test was successful and not in dry run mode
-            if !dry && proxy.check_success_rate() > 0 {
-                if let Some(filestore) = get_filestore("data") {
-                    match filestore.load_proxies("proxies") {
-                        Ok(mut proxies) => {
-                            proxies.push(proxy.clone());
-                            if let Err(e) = filestore.save_proxies(&proxies, "proxies") {
-                                eprintln!("Failed to save proxy: {e}");
-                            } else {
-                                println!("\nProxy saved to list successfully");
-                            }
-                        }
-                        Err(e) => eprintln!("Failed to load proxy list: {e}"),
-                    }
-                }
+    for (job, at) in upcoming {
+        println!("{job} next runs at {}", at.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
+
+    loop {
+        let wait = scheduler
+            .time_until_next(chrono::Utc::now())
+            .unwrap_or(Duration::MAX);
+        tokio::select! {
+            () = tokio::time::sleep(wait) => {}
+            _ = tokio::signal::ctrl_c() => {
+                println!("Daemon stopped");
+                std::process::exit(0);
             }
         }
 
-        std::process::exit(0);
+        let results = scheduler.run_due(&mut manager).await;
+        if results.is_empty() {
+            continue;
+        }
+        for (job, result) in &results {
+            match result {
+                Ok(summary) => println!("{job}: {summary}"),
+                Err(e) => eprintln!("{job} failed: {e}"),
+            }
+        }
+        save_captured_responses(&manager, &config_path);
+        if let Err(e) = manager.sync_with_filestore(&filestore, SyncPolicy::Merge) {
+            eprintln!("Failed to save proxies: {e}");
+        }
     }
 }
 
-/// Sets up a filestore with the given configuration path.
+/// Saves the client statistics and rotation state of a running listener at
+/// a fixed interval, so `report` can show them and a restart resumes them.
 ///
 /// # Arguments
-/// * `config_path` - Path to the configuration directory
+/// * `clients` - Statistics of the listener's clients
+/// * `pool` - The pool the listener rotates through
+/// * `filestore` - Filestore to save to
+async fn save_listener_state_periodically(
+    clients: &Arc<Mutex<ClientStatsMap>>,
+    pool: &Arc<Mutex<UpstreamPool>>,
+    filestore: &Filestore,
+) {
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        defaults::listener::STATS_SAVE_INTERVAL_SECS,
+    ));
+    loop {
+        interval.tick().await;
+        save_listener_state(clients, pool, filestore);
+    }
+}
+
+/// Saves the client statistics and rotation state of a listener.
+///
+/// # Arguments
+/// * `clients` - Statistics of the listener's clients
+/// * `pool` - The pool the listener rotates through
+/// * `filestore` - Filestore to save to
+fn save_listener_state(
+    clients: &Arc<Mutex<ClientStatsMap>>,
+    pool: &Arc<Mutex<UpstreamPool>>,
+    filestore: &Filestore,
+) {
+    save_client_stats(clients, filestore);
+
+    let mut state = pool
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .state()
+        .clone();
+    state.prune(chrono::Utc::now());
+    if let Err(e) =
+        filestore.save_rotation_state(&state, defaults::persistence::ROTATION_STATE_FILE)
+    {
+        eprintln!("Failed to save rotation state: {e}");
+    }
+}
+
+/// Saves the client statistics of a listener.
+///
+/// # Arguments
+/// * `clients` - Statistics of the listener's clients
+/// * `filestore` - Filestore to save to
+fn save_client_stats(clients: &Arc<Mutex<ClientStatsMap>>, filestore: &Filestore) {
+    let snapshot = clients
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    if let Err(e) = filestore.save_client_stats(&snapshot, defaults::persistence::CLIENT_STATS_FILE)
+    {
+        eprintln!("Failed to save listener clients: {e}");
+    }
+}
+
+/// Handles the `ServeJudge` command, answering judge requests until the
+/// judge fails or the process is interrupted.
+///
+/// # Arguments
+/// * `listen` - Address to accept judge requests on
 ///
 /// # Returns
-/// * `Result<Filestore, Box<dyn std::error::Error>>` - The initialized filestore or an error
-fn setup_filestore(config_path: &str) -> Result<Filestore, Box<dyn std::error::Error>> {
-    Ok(Filestore::with_config(FilestoreConfig {
-        data_dir: config_path.to_string(),
-        ..Default::default()
-    })?)
+/// * `()` - The function exits the program with appropriate status code
+async fn handle_serve_judge_command(listen: &str) {
+    let judge = match LocalJudge::bind(listen).await {
+        Ok(judge) => judge,
+        Err(e) => {
+            eprintln!("Failed to listen on {listen}: {e}");
+            std::process::exit(1);
+        }
+    };
+    match judge.url() {
+        Ok(url) => println!("Serving a judge at {url}"),
+        Err(e) => println!("Serving a judge on {listen} ({e})"),
+    }
+
+    tokio::select! {
+        result = judge.run() => {
+            if let Err(e) = result {
+                eprintln!("Judge stopped: {e}");
+            }
+            std::process::exit(1);
+        }
+        _ = tokio::signal::ctrl_c() => {
+            println!("Judge stopped");
+        }
+    }
 }
 
-/// Tests and enriches proxies based on the specified judgement mode.
+/// Handles the Coordinate command, handing the stale stored proxies out to
+/// workers until every one of them has been judged.
+///
+/// The pool is saved at a fixed interval while workers judge it, and once
+/// they are done.
 ///
 /// # Arguments
-/// * `proxies` - List of proxies to test
-/// * `mode` - Judgement mode determining the level of testing and enrichment
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `listen` - Address to accept workers on
+/// * `max_age` - How long a check result stays fresh, in seconds
+/// * `lease_timeout` - Time a worker has to report a claimed batch, in seconds
+/// * `token` - Token shared with the workers, generated if not given
 ///
 /// # Returns
-/// * `Result<Vec<Proxy>, Box<dyn std::error::Error>>` - The tested proxies or an error
-async fn test_and_enrich_proxies(
-    mut proxies: Vec<Proxy>,
-    mode: JudgementMode,
-) -> Result<Vec<Proxy>, Box<dyn std::error::Error>> {
-    if mode == JudgementMode::None {
-        return Ok(proxies);
+/// * `()` - The function exits the program with appropriate status code
+async fn handle_coordinate_command(
+    config: Option<&str>,
+    listen: &str,
+    max_age: u64,
+    lease_timeout: u64,
+    token: Option<String>,
+) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let proxies = match filestore.load_proxies_with_journal(defaults::persistence::PROXIES_FILE) {
+        Ok(proxies) => proxies,
+        Err(e) => {
+            eprintln!("Failed to load stored proxies: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let max_age = chrono::Duration::seconds(i64::try_from(max_age).unwrap_or(i64::MAX));
+    let coordinator =
+        Coordinator::new(proxies, max_age).with_lease_timeout(Duration::from_secs(lease_timeout));
+    let total = coordinator.progress().total;
+    if total == 0 {
+        println!("No stale proxies to judge");
+        std::process::exit(0);
     }
 
-    // Initialize manager
-    let mut manager = init_proxy_manager(mode == JudgementMode::Full)?;
+    let token = token.unwrap_or_else(|| {
+        rand::rng()
+            .sample_iter(rand::distr::Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect()
+    });
+    let server = match CoordinatorServer::bind(listen, coordinator, &token).await {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Failed to listen on {listen}: {e}");
+            std::process::exit(1);
+        }
+    };
+    println!("Handing {total} stale proxies out to workers on {listen}");
+    println!(
+        "Workers need the token in {}: {token}",
+        defaults::coordinator::TOKEN_ENV
+    );
 
-    // Test proxies (basic connectivity)
-    println!("Testing proxies...");
-    let pb = ProgressBar::new(proxies.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
-            .expect("Failed to create progress bar style")
-            .progress_chars("##-"),
+    let mut journal = match filestore.open_journal(defaults::persistence::PROXIES_FILE) {
+        Ok(journal) => journal.with_flush_interval(Duration::from_secs(
+            defaults::coordinator::SAVE_INTERVAL_SECS,
+        )),
+        Err(e) => {
+            eprintln!("Failed to open the proxy journal: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let coordinator = server.coordinator();
+    let mut server = tokio::spawn(server.run());
+    tokio::select! {
+        result = &mut server => {
+            save_coordinated_proxies(&coordinator, &mut journal, &filestore);
+            if let Ok(Err(e)) = result {
+                eprintln!("Coordinator stopped: {e}");
+            }
+            std::process::exit(1);
+        }
+        () = wait_for_workers(&coordinator, &mut journal) => {}
+    }
+
+    // Idle workers learn the run is over on their next claim
+    tokio::time::sleep(Duration::from_secs(
+        defaults::coordinator::IDLE_POLL_SECS * 2,
+    ))
+    .await;
+    server.abort();
+
+    save_coordinated_proxies(&coordinator, &mut journal, &filestore);
+    let progress = coordinator
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .progress();
+    println!(
+        "Judged {} proxies with {} workers",
+        progress.completed,
+        progress.workers.len()
     );
+    for (worker, count) in &progress.workers {
+        println!("  {count:>7} by {worker}");
+    }
+    if progress.rejected > 0 {
+        println!("Refused {} implausible reports", progress.rejected);
+    }
+    std::process::exit(0);
+}
 
-    // Check all proxies with progress
-    manager.check_all_proxies(&mut proxies, 10).await?;
-    pb.finish_with_message("Proxy testing complete");
+/// Waits until every job of a coordinator has been reported, journaling
+/// the judged proxies as they come in.
+///
+/// The journal only appends what changed, at most once per its flush
+/// interval, so a large pool isn't rewritten while workers judge it.
+///
+/// # Arguments
+/// * `coordinator` - The running coordinator
+/// * `journal` - Journal of the proxies file
+async fn wait_for_workers(coordinator: &Arc<Mutex<Coordinator>>, journal: &mut ProxyJournal) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(defaults::coordinator::IDLE_POLL_SECS));
+    loop {
+        interval.tick().await;
+        let (reported, finished) = {
+            let mut coordinator = coordinator.lock().unwrap_or_else(PoisonError::into_inner);
+            (coordinator.take_reported(), coordinator.is_finished())
+        };
+        for proxy in &reported {
+            if let Err(e) = journal.record(proxy) {
+                eprintln!("Failed to journal judged proxies: {e}");
+            }
+        }
+        if finished {
+            return;
+        }
+    }
+}
 
-    // Gather additional information in full mode
-    if mode == JudgementMode::Full {
-        println!("\nGathering detailed proxy information...");
-        let pb = ProgressBar::new(proxies.len() as u64);
-        pb.set_style(
-            ProgressStyle::default_bar()
-                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
-                .expect("Failed to create progress bar style")
-                .progress_chars("##-"),
-        );
+/// Saves the canonical pool of a coordinator, with the results reported so far.
+///
+/// Results not journaled yet are, and the journal is folded into the
+/// proxies file.
+///
+/// # Arguments
+/// * `coordinator` - The coordinator
+/// * `journal` - Journal of the proxies file
+/// * `filestore` - Filestore to save to
+fn save_coordinated_proxies(
+    coordinator: &Arc<Mutex<Coordinator>>,
+    journal: &mut ProxyJournal,
+    filestore: &Filestore,
+) {
+    let reported = coordinator
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take_reported();
+    let saved = reported
+        .iter()
+        .try_for_each(|proxy| journal.record(proxy).map(drop))
+        .and_then(|()| journal.compact(filestore));
+    if let Err(e) = saved {
+        eprintln!("Failed to save judged proxies: {e}");
+    }
+}
 
-        manager.enrich_all_proxies(&mut proxies, 10).await?;
-        pb.finish_with_message("Detail gathering complete");
+/// Handles the Work command, judging proxies claimed from a coordinator
+/// until it has handed out every job.
+///
+/// A coordinator that can't be reached for a moment is asked again; batches
+/// whose report is lost are handed to another worker once their lease runs
+/// out.
+///
+/// # Arguments
+/// * `coordinator` - Address or URL of the coordinator
+/// * `name` - Name to report results under
+/// * `vantage` - Vantage point judged latencies are recorded under, the name if `None`
+/// * `batch` - Number of proxies claimed at once
+/// * `concurrency` - Maximum number of proxies judged concurrently
+/// * `token` - Token shared with the coordinator
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+async fn handle_work_command(
+    coordinator: &str,
+    name: Option<String>,
+    vantage: Option<String>,
+    batch: usize,
+    concurrency: usize,
+    token: &str,
+) {
+    let name = name.unwrap_or_else(|| format!("worker-{}", std::process::id()));
+    let worker = match WorkerClient::new(coordinator, &name, token) {
+        Ok(worker) => worker,
+        Err(e) => {
+            eprintln!("Failed to create coordinator client: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let vantage = vantage.unwrap_or_else(|| name.clone());
+    let mut manager = match init_worker_manager(&vantage) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Failed to initialize proxy manager: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let idle = Duration::from_secs(defaults::coordinator::IDLE_POLL_SECS);
+    let (mut judged, mut accepted) = (0, 0);
+    loop {
+        let claim = match worker.claim(batch).await {
+            Ok(claim) => claim,
+            Err(e) if e.is_retryable() => {
+                eprintln!("Failed to claim proxies, retrying: {e}");
+                tokio::time::sleep(idle).await;
+                continue;
+            }
+            Err(e) => {
+                eprintln!("Coordinator refused the claim: {e}");
+                std::process::exit(1);
+            }
+        };
+        if claim.finished {
+            break;
+        }
+        if claim.proxies.is_empty() {
+            tokio::time::sleep(idle).await;
+            continue;
+        }
+
+        let mut proxies = claim.proxies;
+        if let Err(e) = manager.check_all_proxies(&mut proxies, concurrency).await {
+            eprintln!("Failed to judge proxies: {e}");
+            std::process::exit(1);
+        }
+        judged += proxies.len();
+
+        match worker.report(proxies).await {
+            Ok(response) => {
+                accepted += response.accepted;
+                if response.rejected > 0 {
+                    eprintln!("Coordinator refused {} judged proxies", response.rejected);
+                }
+            }
+            Err(e) => eprintln!("Failed to report judged proxies: {e}"),
+        }
     }
 
-    Ok(proxies)
+    println!("Judged {judged} proxies as {name}, {accepted} kept by the coordinator");
+    std::process::exit(0);
 }
 
-/// Handles saving proxies and sources to the filestore.
+/// Dispatches the Pool command to the handler of its subcommand.
 ///
 /// # Arguments
-/// * `proxies` - List of proxies to save
-/// * `source` - The source to save
-/// * `filestore` - Filestore for persistence
-/// * `dry` - Whether to avoid saving
-/// * `raw_response` - Optional raw response data to save
-/// * `mode` - Judgement mode used
-/// * `scrape_url` - URL that was scraped
+/// * `command` - The pool command to execute
+/// * `data_dir` - Data directory used when the subcommand names no configuration folder
+fn handle_pool_command(command: PoolCommands, data_dir: Option<String>) {
+    match command {
+        PoolCommands::Merge {
+            config,
+            first,
+            second,
+            output,
+            policy,
+        } => {
+            handle_pool_merge_command(
+                config.or(data_dir).as_deref(),
+                &first,
+                &second,
+                &output,
+                policy,
+            );
+        }
+        PoolCommands::Export {
+            config,
+            template,
+            output,
+            sort,
+        } => {
+            handle_pool_export_command(
+                config.or(data_dir).as_deref(),
+                &template,
+                output.as_deref(),
+                sort,
+            );
+        }
+    }
+}
+
+/// Handles the Pool command's `merge`, combining two stored pools into one.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `first` - Name of the first pool's file
+/// * `second` - Name of the second pool's file
+/// * `output` - Name of the file to write the merged pool to
+/// * `policy` - How proxies found in both pools are combined
 ///
 /// # Returns
-/// * `Result<(), Box<dyn std::error::Error>>` - Success or an error
-fn save_results(
-    proxies: &[Proxy],
-    source: &Source,
-    filestore: &Filestore,
-    dry: bool,
-    raw_response: Option<String>,
-    mode: JudgementMode,
-    scrape_url: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    if dry {
-        return Ok(());
+/// * `()` - The function exits the program with appropriate status code
+fn handle_pool_merge_command(
+    config: Option<&str>,
+    first: &str,
+    second: &str,
+    output: &str,
+    policy: MergePolicy,
+) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    match filestore.merge_proxy_files(first, second, output, policy) {
+        Ok(summary) => {
+            println!(
+                "Merged {} and {} proxies into {}, {} of them in both pools",
+                summary.first, summary.second, summary.merged, summary.reconciled
+            );
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Failed to merge pools: {e}");
+            std::process::exit(1);
+        }
     }
+}
+
+/// Handles the Pool command's `export`, rendering every stored proxy through a template.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `template` - Template rendered once per proxy, see `Proxy::render`
+/// * `output` - File to write the rendered lines to (default: standard output)
+/// * `sort` - Key to order the proxies by before the overall quality order
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+fn handle_pool_export_command(
+    config: Option<&str>,
+    template: &str,
+    output: Option<&str>,
+    sort: Option<ProxySortKey>,
+) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut manager = match ProxyManager::new() {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Failed to initialize proxy manager: {e}");
+            std::process::exit(1);
+        }
+    };
 
-    // Save proxies if they exist
-    if !proxies.is_empty() {
-        filestore.save_proxies(proxies, "proxies")?;
+    if let Err(e) = manager.import_from_filestore(&filestore, SyncPolicy::PreferDisk) {
+        eprintln!("Failed to load stored data: {e}");
+        std::process::exit(1);
     }
 
-    // Save raw response data if in full mode
-    if mode == JudgementMode::Full && raw_response.is_some() {
-        let timestamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
-        let sanitized_url = utils::sanitize_url_for_filename(scrape_url);
-        let dump_filename = format!("{timestamp}-{sanitized_url}.txt");
+    // Ties under the chosen key fall back to the overall quality order
+    let keys: Vec<ProxySortKey> = sort.into_iter().chain(ProxySortKey::QUALITY).collect();
+    let proxies = manager.get_sorted_proxies(&keys);
+    let rendered: String = proxies
+        .iter()
+        .map(|proxy| proxy.render(template) + "\n")
+        .collect();
 
-        if let Err(e) = std::fs::write(dump_filename.clone(), raw_response.unwrap()) {
-            eprintln!("Failed to save raw response: {e}");
-        } else {
-            println!("Raw response saved to {dump_filename}");
+    let Some(output) = output else {
+        print!("{rendered}");
+        std::process::exit(0);
+    };
+
+    match std::fs::write(output, rendered) {
+        Ok(()) => {
+            println!("Rendered {} proxies to {output}", proxies.len());
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Failed to write {output}: {e}");
+            std::process::exit(1);
         }
     }
+}
 
-    // Load existing sources
-    let mut sources = filestore.load_sources("sources").unwrap_or_default();
-
-    // Update or add new source
-    if let Some(pos) = sources.iter().position(|s| s.url == source.url) {
-        sources[pos] = source.clone();
-    } else {
-        sources.push(source.clone());
+/// Dispatches the Sources command to the handler of its subcommand.
+///
+/// # Arguments
+/// * `command` - The sources command to execute
+/// * `data_dir` - Data directory used when the subcommand names no configuration folder
+fn handle_sources_command(command: SourcesCommands, data_dir: Option<String>) {
+    match command {
+        SourcesCommands::Install {
+            config,
+            file,
+            on_conflict,
+            trusted_keys,
+            require_signature,
+            dry,
+        } => {
+            handle_sources_install_command(
+                config.or(data_dir).as_deref(),
+                Path::new(&file),
+                on_conflict,
+                &trusted_keys,
+                require_signature,
+                dry,
+            );
+        }
+        SourcesCommands::Export {
+            config,
+            output,
+            name,
+            author,
+            description,
+            schedule,
+            signing_key,
+        } => {
+            let mut shared = SourceBundle::new(name);
+            shared.author = author;
+            shared.description = description;
+            shared.schedule = schedule;
+            handle_sources_export_command(
+                config.or(data_dir).as_deref(),
+                Path::new(&output),
+                shared,
+                signing_key.as_deref().map(Path::new),
+            );
+        }
+        SourcesCommands::Keygen { output } => {
+            handle_sources_keygen_command(Path::new(&output));
+        }
     }
+}
 
-    filestore.save_sources(&sources, "sources")?;
-    println!("Source saved successfully");
-
-    Ok(())
+/// Loads the installed sources, none if no sources file exists yet.
+///
+/// # Arguments
+/// * `filestore` - Filestore holding the sources
+///
+/// # Returns
+/// * `Vec<Source>` - The installed sources; the program exits if they can't be loaded
+fn load_installed_sources(filestore: &Filestore) -> Vec<Source> {
+    match filestore.load_sources(defaults::persistence::SOURCES_FILE) {
+        Ok(sources) => sources,
+        Err(FilestoreError::FileNotFound(_)) => Vec::new(),
+        Err(e) => {
+            eprintln!("Failed to load sources: {e}");
+            std::process::exit(1);
+        }
+    }
 }
 
-/// Scrapes and processes proxies from a source URL.
+/// Handles the Sources command's `install`, installing the sources of a
+/// bundle after checking its signature.
 ///
-/// This function handles the entire proxy scraping workflow:
-/// 1. Loads configuration and initializes components
-/// 2. Scrapes proxies from the specified URL
-/// 3. Tests and enriches the proxies based on the judgement mode
-/// 4. Saves the results to the filestore
+/// Signed bundles must match their signature. When authors are trusted,
+/// through `--trusted-key` or the configuration, only bundles signed by one
+/// of them are installed.
 ///
 /// # Arguments
-///
-/// * `scrape` - URL to scrape for proxies
-/// * `config` - Path to configuration folder (default: 'data')
-/// * `useragent` - Custom User-Agent string to use for requests
-/// * `pattern` - Custom regex pattern for finding proxies
-/// * `judge` - Judgement mode determining test intensity:
-///   - None (0): No testing, just scrape
-///   - Quick (1): Basic connectivity testing
-///   - Full (2): Comprehensive testing with metadata collection
-/// * `dry` - If true, don't save results to persistent storage
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `file` - The bundle file
+/// * `on_conflict` - What to do with sources that are already installed
+/// * `trusted_keys` - Base64 public keys of trusted authors
+/// * `require_signature` - Whether unsigned bundles are refused
+/// * `dry` - If true, only show what would be installed
 ///
 /// # Returns
-///
-/// * `()` - The function exits the process with an appropriate status code
-async fn handle_source_command(
-    scrape: String,
-    config: Option<String>,
-    useragent: Option<String>,
-    pattern: Option<String>,
-    judge: JudgementMode,
+/// * `()` - The function exits the program with appropriate status code
+fn handle_sources_install_command(
+    config: Option<&str>,
+    file: &Path,
+    on_conflict: ConflictPolicy,
+    trusted_keys: &[String],
+    require_signature: bool,
     dry: bool,
 ) {
-    // Load configuration
-    let config_path = config.unwrap_or_else(|| "data".to_string());
+    let config_path = filesystem::resolve_data_dir(config);
     let filestore = match setup_filestore(&config_path) {
         Ok(fs) => fs,
         Err(e) => {
@@ -508,125 +2925,322 @@ async fn handle_source_command(
             std::process::exit(1);
         }
     };
-
-    // Initialize source with provided options
-    let source = match Source::new(
-        scrape.clone(),
-        useragent.unwrap_or_else(|| utils::get_random_user_agent().to_string()),
-        pattern.unwrap_or_else(|| defaults::regex_patterns::IP_PORT.to_string()),
-    ) {
-        Ok(s) => s,
+    let app_config = match filestore.load_config(defaults::persistence::CONFIG_FILE) {
+        Ok(app_config) => app_config,
         Err(e) => {
-            eprintln!("Failed to create source: {e}");
+            eprintln!("Failed to load configuration: {e}");
             std::process::exit(1);
         }
     };
 
-    // Create requestor for fetching
-    let requestor = match Requestor::new() {
-        Ok(r) => r,
+    let trusted: Vec<VerifyingKey> = match trusted_keys
+        .iter()
+        .chain(&app_config.trusted_bundle_keys)
+        .map(|key| bundle::parse_verifying_key(key))
+        .collect()
+    {
+        Ok(trusted) => trusted,
         Err(e) => {
-            eprintln!("Failed to create requestor: {e}");
+            eprintln!("Failed to read trusted keys: {e}");
             std::process::exit(1);
         }
     };
 
-    // Fetch proxies from the source
-    println!("Scraping proxies from {scrape}");
-    let (proxies, raw_response) = match source.fetch_proxies_with_response(&requestor).await {
-        Ok((proxies, response)) => (proxies, response),
+    let shared = match SourceBundle::load(file) {
+        Ok(shared) => shared,
         Err(e) => {
-            eprintln!("Failed to fetch proxies: {e}");
+            eprintln!("Failed to read bundle: {e}");
             std::process::exit(1);
         }
     };
 
-    println!("Found {} proxies", proxies.len());
-
-    // Test and enrich proxies if requested
-    let proxies = match test_and_enrich_proxies(proxies, judge).await {
-        Ok(proxies) => {
-            if judge != JudgementMode::None {
-                // Count working proxies
-                let working = proxies
-                    .iter()
-                    .filter(|p| p.check_success_rate() > 0)
-                    .count();
-                println!("\nWorking proxies: {}/{}", working, proxies.len());
+    if shared.signature.is_some() || require_signature || !trusted.is_empty() {
+        let verified = if trusted.is_empty() {
+            shared.verify()
+        } else {
+            shared.verify_trusted(&trusted)
+        };
+        match verified {
+            Ok(key) => println!("Signed by {}", bundle::encode_key(&key)),
+            Err(e) => {
+                eprintln!("Refusing bundle: {e}");
+                std::process::exit(1);
             }
-            proxies
         }
+    } else {
+        println!("Bundle isn't signed");
+    }
+
+    println!("Bundle: {} ({} sources)", shared.name, shared.sources.len());
+    if let Some(author) = &shared.author {
+        println!("Author: {author}");
+    }
+    if let Some(description) = &shared.description {
+        println!("Description: {description}");
+    }
+
+    let mut manager = match ProxyManager::new() {
+        Ok(manager) => manager,
         Err(e) => {
-            eprintln!("Failed during proxy testing: {e}");
+            eprintln!("Failed to initialize manager: {e}");
             std::process::exit(1);
         }
     };
+    if let Err(e) = manager.add_sources(load_installed_sources(&filestore)) {
+        eprintln!("Failed to load sources: {e}");
+        std::process::exit(1);
+    }
 
-    // Save results
-    let raw_response_to_save = if judge == JudgementMode::Full {
-        Some(raw_response)
-    } else {
-        None
+    let summary = match manager.install_bundle(&shared, on_conflict) {
+        Ok(summary) => summary,
+        Err(e) => {
+            eprintln!("Failed to install bundle: {e}");
+            std::process::exit(1);
+        }
     };
+    println!(
+        "{} sources added, {} replaced, {} already installed kept",
+        summary.added, summary.replaced, summary.kept
+    );
 
-    if let Err(e) = save_results(
-        &proxies,
-        &source,
-        &filestore,
-        dry,
-        raw_response_to_save,
-        judge,
-        &scrape,
+    if let Some(schedule) = &shared.schedule {
+        println!("Suggested schedule: {schedule}");
+    }
+
+    if dry {
+        println!("Dry run, nothing saved");
+        std::process::exit(0);
+    }
+
+    if let Err(e) = filestore.save_sources(
+        &manager.get_all_sources_owned(),
+        defaults::persistence::SOURCES_FILE,
     ) {
-        eprintln!("Failed to save results: {e}");
+        eprintln!("Failed to save sources: {e}");
         std::process::exit(1);
     }
+    std::process::exit(0);
+}
+
+/// Handles the Sources command's `export`, writing the installed sources to
+/// a bundle.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `output` - File to write the bundle to
+/// * `shared` - The bundle's metadata, without sources
+/// * `signing_key` - File holding the key to sign the bundle with, if any
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+fn handle_sources_export_command(
+    config: Option<&str>,
+    output: &Path,
+    shared: SourceBundle,
+    signing_key: Option<&Path>,
+) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut shared = shared.with_sources(&load_installed_sources(&filestore));
+
+    if let Some(path) = signing_key {
+        let key = match std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|encoded| bundle::parse_signing_key(&encoded).map_err(|e| e.to_string()))
+        {
+            Ok(key) => key,
+            Err(e) => {
+                eprintln!("Failed to read signing key: {e}");
+                std::process::exit(1);
+            }
+        };
+        if let Err(e) = shared.sign(&key) {
+            eprintln!("Failed to sign bundle: {e}");
+            std::process::exit(1);
+        }
+    }
 
+    if let Err(e) = shared.save(output) {
+        eprintln!("Failed to write bundle: {e}");
+        std::process::exit(1);
+    }
+    println!(
+        "Wrote {} sources to {}",
+        shared.sources.len(),
+        output.display()
+    );
     std::process::exit(0);
 }
 
-/// Parses a proxy URL string into a Proxy object.
+/// Handles the Sources command's `keygen`, writing a new signing key and
+/// printing the public key others trust it by.
 ///
 /// # Arguments
-/// * `url` - The proxy URL in format protocol://ip:port
+/// * `output` - File to write the signing key to
 ///
 /// # Returns
-/// * `Result<Proxy, String>` - The parsed Proxy object or an error message
-fn parse_proxy_url(url: &str) -> Result<Proxy, String> {
-    // Basic URL parsing - protocol://ip:port
-    let parts: Vec<&str> = url.split("://").collect();
-    if parts.len() != 2 {
-        return Err("Invalid proxy URL format. Expected: protocol://ip:port".to_string());
+/// * `()` - The function exits the program with appropriate status code
+fn handle_sources_keygen_command(output: &Path) {
+    if output.exists() {
+        eprintln!("{} already exists, not replacing it", output.display());
+        std::process::exit(1);
     }
 
-    let lower = if parts.is_empty() {
-        return Err("No protocol specified in proxy URL".to_string());
-    } else {
-        parts[0].to_lowercase()
+    let key = match bundle::generate_signing_key() {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("Failed to generate signing key: {e}");
+            std::process::exit(1);
+        }
     };
+    if let Err(e) = std::fs::write(output, bundle::encode_signing_key(&key)) {
+        eprintln!("Failed to write signing key: {e}");
+        std::process::exit(1);
+    }
+    println!("Wrote signing key to {}", output.display());
+    println!("Public key: {}", bundle::encode_key(&key.verifying_key()));
+    std::process::exit(0);
+}
+
+/// Dispatches the Debug command to the handler of its subcommand.
+///
+/// # Arguments
+/// * `command` - The debugging command to execute
+/// * `data_dir` - Data directory used when the subcommand names no configuration folder
+fn handle_debug_command(command: DebugCommands, data_dir: Option<String>) {
+    match command {
+        DebugCommands::LastResponses { config, limit } => {
+            handle_last_responses_command(config.or(data_dir).as_deref(), limit);
+        }
+        DebugCommands::Classify {
+            config,
+            real_ip,
+            file,
+        } => {
+            handle_classify_command(config.or(data_dir).as_deref(), real_ip, file.as_deref());
+        }
+    }
+}
 
-    let protocol = match lower.as_str() {
-        "http" => ProxyType::Http,
-        "https" => ProxyType::Https,
-        "socks4" => ProxyType::Socks4,
-        "socks5" => ProxyType::Socks5,
-        _ => return Err("Invalid protocol. Use: http, https, socks4, or socks5".to_string()),
+/// Handles the Debug command's `last-responses`, printing the raw responses
+/// captured by the last run that had `--capture-responses` set.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `limit` - Show only this many of the most recent responses
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+fn handle_last_responses_command(config: Option<&str>, limit: Option<usize>) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let filestore = match setup_filestore(&config_path) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
     };
 
-    let addr_parts: Vec<&str> = parts[1].split(':').collect();
-    if addr_parts.len() != 2 {
-        return Err("Invalid address format. Expected: ip:port".to_string());
+    let responses =
+        match filestore.load_captured_responses(defaults::persistence::CAPTURED_RESPONSES_FILE) {
+            Ok(responses) => responses,
+            Err(e) => {
+                eprintln!("Failed to load captured responses: {e}");
+                std::process::exit(1);
+            }
+        };
+
+    if responses.is_empty() {
+        println!("No captured responses, run a command with --capture-responses first");
+        std::process::exit(0);
+    }
+
+    let skip = limit.map_or(0, |limit| responses.len().saturating_sub(limit));
+    for response in &responses[skip..] {
+        let status = response
+            .status
+            .map_or_else(|| "no status".to_string(), |status| status.to_string());
+        println!(
+            "[{}] {} {} {} ({})",
+            response.captured_at.format("%Y-%m-%d %H:%M:%S"),
+            response.provider,
+            status,
+            response.url,
+            response.subject
+        );
+        println!("{}\n", response.body.trim_end());
     }
+    std::process::exit(0);
+}
 
-    let Ok(ip) = IpAddr::from_str(addr_parts[0]) else {
-        return Err("Invalid IP address".to_string());
+/// Handles the Debug command's `classify`, showing how a judge response is
+/// parsed and classified, and which configured anonymity rules apply.
+///
+/// # Arguments
+/// * `config` - Path to configuration folder (default: the platform data directory)
+/// * `real_ip` - Public address of the machine the proxy was judged from
+/// * `file` - File holding the response body, standard input if `None`
+///
+/// # Returns
+/// * `()` - The function exits the program with appropriate status code
+fn handle_classify_command(config: Option<&str>, real_ip: IpAddr, file: Option<&str>) {
+    let config_path = filesystem::resolve_data_dir(config);
+    let rules = match setup_filestore(&config_path)
+        .map(|fs| fs.load_config(defaults::persistence::CONFIG_FILE))
+    {
+        Ok(Ok(config)) => config.anonymity_rules,
+        Ok(Err(e)) => {
+            eprintln!("Failed to load configuration: {e}");
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to initialize filestore: {e}");
+            std::process::exit(1);
+        }
     };
 
-    let Ok(port) = addr_parts[1].parse::<u16>() else {
-        return Err("Invalid port number".to_string());
+    let body = match file {
+        Some(path) => std::fs::read_to_string(path),
+        None => std::io::read_to_string(std::io::stdin()),
+    };
+    let body = match body {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("Failed to read judge response: {e}");
+            std::process::exit(1);
+        }
     };
 
-    Ok(Proxy::new(protocol, ip, port, AnonymityLevel::Anonymous))
+    let headers = parse_judge_headers(&body);
+    println!("Parsed headers");
+    println!("--------------");
+    for (name, value) in &headers {
+        println!("{name} = {value}");
+    }
+
+    let level = classify_anonymity(&headers, real_ip);
+    println!("\nBuilt-in classification: {level}");
+    for rule in &rules {
+        if let Some(header) = rule.matching_header(&headers) {
+            println!(
+                "Rule {} matches {header}, demoting to {}",
+                rule.header, rule.demote_to
+            );
+        }
+    }
+    println!(
+        "Final classification: {} ({} rules configured)",
+        AnonymityRule::apply_all(&rules, level, &headers),
+        rules.len()
+    );
+    std::process::exit(0);
 }
 
 /// Helper function to get filestore.
@@ -636,9 +3250,9 @@ fn parse_proxy_url(url: &str) -> Result<Proxy, String> {
 ///
 /// # Returns
 /// * `Option<Filestore>` - The filestore if successfully initialized, None otherwise
-fn get_filestore(data_dir: &str) -> Option<Filestore> {
+fn get_filestore(data_dir: &Path) -> Option<Filestore> {
     match Filestore::with_config(FilestoreConfig {
-        data_dir: data_dir.to_string(),
+        data_dir: data_dir.to_string_lossy().to_string(),
         ..Default::default()
     }) {
         Ok(fs) => Some(fs),
@@ -649,21 +3263,22 @@ fn get_filestore(data_dir: &str) -> Option<Filestore> {
     }
 }
 
+/// Converts a `LogLevel` to the matching `log::LevelFilter`.
+fn log_level_to_filter(log_level: LogLevel) -> log::LevelFilter {
+    match log_level {
+        LogLevel::Error => log::LevelFilter::Error,
+        LogLevel::Warn => log::LevelFilter::Warn,
+        LogLevel::Info => log::LevelFilter::Info,
+        LogLevel::Debug => log::LevelFilter::Debug,
+        LogLevel::Trace => log::LevelFilter::Trace,
+    }
+}
+
 /// Main function that handles CLI argument parsing and command dispatching.
 /// Uses the clap crate for command-line argument parsing.
 #[tokio::main]
+#[allow(clippy::too_many_lines)]
 async fn main() {
-    // Helper function to convert our LogLevel enum to log::LevelFilter
-    fn log_level_to_filter(log_level: LogLevel) -> log::LevelFilter {
-        match log_level {
-            LogLevel::Error => log::LevelFilter::Error,
-            LogLevel::Warn => log::LevelFilter::Warn,
-            LogLevel::Info => log::LevelFilter::Info,
-            LogLevel::Debug => log::LevelFilter::Debug,
-            LogLevel::Trace => log::LevelFilter::Trace,
-        }
-    }
-
     let cli = Cli::parse();
 
     // Set up logging based on log level
@@ -682,18 +3297,171 @@ async fn main() {
         Some(Commands::Config { create, validate }) => {
             handle_config_command(create, validate);
         }
-        Some(Commands::Proxy { judge, dry }) => {
-            handle_proxy_command(judge, dry).await;
+        Some(Commands::Proxy {
+            dry,
+            import: Some(file),
+            format,
+            check,
+            events,
+            ..
+        }) => {
+            handle_proxy_import_command(
+                &file,
+                format,
+                check,
+                dry,
+                cli.data_dir,
+                cli.capture_responses,
+                events.as_deref(),
+            )
+            .await;
+        }
+        Some(Commands::Proxy {
+            judge,
+            dry,
+            verify_sni,
+            import: None,
+            events,
+            ..
+        }) => {
+            handle_proxy_command(
+                judge,
+                dry,
+                verify_sni,
+                cli.data_dir,
+                cli.capture_responses,
+                events.as_deref(),
+            )
+            .await;
         }
         Some(Commands::Source {
             scrape,
             config,
             useragent,
             pattern,
+            vars,
+            headers,
             judge,
+            wayback,
+            snapshots,
             dry,
         }) => {
-            handle_source_command(scrape, config, useragent, pattern, judge, dry).await;
+            let config = config.or(cli.data_dir);
+            handle_source_command(
+                scrape,
+                config,
+                useragent,
+                pattern,
+                vars,
+                headers,
+                judge,
+                wayback.then_some(snapshots),
+                dry,
+                cli.capture_responses,
+            )
+            .await;
+        }
+        Some(Commands::Report { config }) => {
+            handle_report_command(config.or(cli.data_dir).as_deref());
+        }
+        Some(Commands::Export {
+            config,
+            format,
+            columns,
+            output,
+            sort,
+        }) => {
+            handle_export_command(
+                config.or(cli.data_dir).as_deref(),
+                format,
+                &columns,
+                output,
+                sort,
+            );
+        }
+        Some(Commands::Recheck {
+            config,
+            max_age,
+            anonymity_max_age,
+            concurrency,
+            events,
+        }) => {
+            handle_recheck_command(
+                config.or(cli.data_dir).as_deref(),
+                max_age,
+                anonymity_max_age,
+                concurrency,
+                cli.capture_responses,
+                events.as_deref(),
+            )
+            .await;
+        }
+        Some(Commands::Serve {
+            config,
+            socks,
+            attempts,
+            diversity_window,
+            watch_top,
+            events,
+        }) => {
+            handle_serve_command(
+                config.or(cli.data_dir).as_deref(),
+                &socks,
+                attempts,
+                diversity_window,
+                watch_top,
+                events.as_deref(),
+            )
+            .await;
+        }
+        Some(Commands::Daemon { config, events }) => {
+            handle_daemon_command(
+                config.or(cli.data_dir).as_deref(),
+                cli.capture_responses,
+                events.as_deref(),
+            )
+            .await;
+        }
+        Some(Commands::ServeJudge { listen }) => {
+            handle_serve_judge_command(&listen).await;
+        }
+        Some(Commands::Coordinate {
+            config,
+            listen,
+            max_age,
+            lease_timeout,
+            token,
+        }) => {
+            handle_coordinate_command(
+                config.or(cli.data_dir).as_deref(),
+                &listen,
+                max_age,
+                lease_timeout,
+                token,
+            )
+            .await;
+        }
+        Some(Commands::Work {
+            coordinator,
+            name,
+            vantage,
+            batch,
+            concurrency,
+            token,
+        }) => {
+            handle_work_command(&coordinator, name, vantage, batch, concurrency, &token).await;
+        }
+        Some(Commands::ValidateData { config, fix }) => {
+            handle_validate_data_command(config.or(cli.data_dir).as_deref(), fix);
+        }
+        Some(Commands::Sources { command }) => {
+            handle_sources_command(command, cli.data_dir);
+        }
+        Some(Commands::Pool { command }) => {
+            handle_pool_command(command, cli.data_dir);
+        }
+        Some(Commands::Debug { command }) => {
+            handle_debug_command(command, cli.data_dir);
         }
     }
 }
@@ -19,6 +19,7 @@
 //! println!("Default log level: {}", config.application.log_level);
 //! ```
 
+use crate::io::filesystem;
 use serde::{Deserialize, Serialize};
 
 /// Main application configuration
@@ -136,7 +137,7 @@ pub struct StorageConfig {
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
-            data_dir: "data".to_string(),
+            data_dir: filesystem::default_data_dir().to_string_lossy().to_string(),
             create_defaults_if_missing: true,
             auto_save_interval_secs: 300,
             pretty_print: true,
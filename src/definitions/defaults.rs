@@ -41,6 +41,21 @@
 /// ```
 pub const PROXY_JUDGE_URLS: &[&str] = &["http://proxyjudge.us/azenv.php", "http://azenv.net"];
 
+/// Proxy judge URLs served over HTTPS
+///
+/// Judging against these shows whether a proxy tunnels TLS, and a proxy
+/// intercepting TLS fails them on the certificate.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::defaults;
+///
+/// assert!(defaults::PROXY_JUDGE_HTTPS_URLS.iter().all(|url| url.starts_with("https://")));
+/// ```
+pub const PROXY_JUDGE_HTTPS_URLS: &[&str] =
+    &["https://proxyjudge.us/azenv.php", "https://azenv.net"];
+
 /// Default User-Agent strings that can be rotated when making requests
 ///
 /// These User-Agent strings are organized by browser type and platform.
@@ -198,6 +213,35 @@ pub mod rotation {
     ///
     /// Determines how long to wait before attempting to use a failed proxy again.
     pub const FAILURE_COOLDOWN_SECS: u64 = 300; // 5 minutes
+
+    /// Time a proxy stays quarantined before it is retired (in seconds)
+    ///
+    /// Quarantined proxies that pass no check within this time are retired
+    /// and no longer checked.
+    pub const RETIRE_AFTER_SECS: u64 = 604_800; // 7 days
+
+    /// Time a proxy that refused a target stays banned from it (in seconds)
+    ///
+    /// The proxy is still selected for other targets meanwhile.
+    pub const TARGET_BAN_SECS: u64 = 3600; // 1 hour
+
+    /// Weighted draws tried before searching for a proxy on another network
+    ///
+    /// With subnet diversity, a rotation draws up to this many proxies in
+    /// proportion to their scores before scanning the whole pool for one
+    /// outside the recent subnets and ASNs.
+    pub const DIVERSITY_MAX_DRAWS: usize = 8;
+
+    /// Share of a weighted selector's proxies that may change before its
+    /// alias table is rebuilt, as a divisor of the number of proxies
+    ///
+    /// Until then, selections draw from the previous table and reject draws
+    /// of proxies whose score dropped, so a rebuild costs constant time per
+    /// change on average.
+    pub const SELECTOR_REBUILD_DIVISOR: usize = 16;
+
+    /// Rejected draws a weighted selector tries before rebuilding its table
+    pub const SELECTOR_MAX_REJECTIONS: usize = 32;
 }
 
 /// Regex patterns for extracting proxies from text sources
@@ -236,6 +280,80 @@ pub mod persistence {
     ///
     /// Proxies older than this value will need to be retested before use.
     pub const MAX_PROXY_AGE_SECS: u64 = 86400; // 24 hours
+
+    /// Environment variable overriding the default data directory
+    pub const DATA_DIR_ENV: &str = "GOOTY_DATA_DIR";
+
+    /// Data directory used when no platform data directory can be determined
+    ///
+    /// Relative to the current working directory.
+    pub const FALLBACK_DATA_DIR: &str = "data";
+
+    /// Qualifier used to derive the platform data directory
+    pub const APP_QUALIFIER: &str = "com";
+
+    /// Organization used to derive the platform data directory
+    pub const APP_ORGANIZATION: &str = "Azzybana";
+
+    /// Application name used to derive the platform data directory
+    pub const APP_NAME: &str = "gooty-proxy";
+
+    /// Base name of the file the proxy pool is stored in
+    pub const PROXIES_FILE: &str = "proxies";
+
+    /// Base name of the file the source list is stored in
+    pub const SOURCES_FILE: &str = "sources";
+
+    /// Base name of the application configuration file
+    pub const CONFIG_FILE: &str = "config";
+
+    /// Minimum time between journal flushes (in milliseconds)
+    ///
+    /// Proxies recorded more often than this are buffered and written together.
+    pub const JOURNAL_FLUSH_INTERVAL_MS: u64 = 1000;
+
+    /// Number of proxies serialized at a time when a pool is saved
+    ///
+    /// Bounds the memory used to save large pools, which are written a
+    /// chunk at a time rather than serialized whole.
+    pub const SAVE_CHUNK_SIZE: usize = 1000;
+
+    /// Number of journal entries after which the journal should be compacted
+    ///
+    /// Compaction folds the journal into the TOML snapshot and truncates it.
+    pub const JOURNAL_COMPACT_THRESHOLD: usize = 10_000;
+
+    /// Base name of the file lookup provider usage is stored in
+    pub const PROVIDER_USAGE_FILE: &str = "provider_usage";
+
+    /// Base name of the file downloaded cloud ranges are cached in
+    pub const CLOUD_RANGES_FILE: &str = "cloud_ranges";
+
+    /// Base name of the checkpoint left behind by an unfinished recheck
+    pub const RECHECK_CHECKPOINT: &str = "recheck";
+
+    /// Base name of the file captured responses are saved in
+    pub const CAPTURED_RESPONSES_FILE: &str = "last_responses";
+
+    /// Base name of the file the rotation state is saved in
+    pub const ROTATION_STATE_FILE: &str = "rotation";
+
+    /// Base name of the file cached lookups are saved in
+    pub const LOOKUP_CACHE_FILE: &str = "lookup_cache";
+
+    /// Base name of the file the listener's client statistics are saved in
+    pub const CLIENT_STATS_FILE: &str = "listener";
+
+    /// Base name of a public suffix list replacing the compiled-in copy
+    pub const PUBLIC_SUFFIX_FILE: &str = "public_suffix_list";
+
+    /// Number of proxies judged between two checkpoints of a long-running job
+    pub const CHECKPOINT_BATCH_SIZE: usize = 100;
+
+    /// Format version of exported manager sessions
+    ///
+    /// Sessions of a newer version are refused on import.
+    pub const SESSION_VERSION: u32 = 1;
 }
 
 /// Default ports for different proxy types
@@ -254,3 +372,508 @@ pub mod default_ports {
     /// Default port for SOCKS5 proxies
     pub const SOCKS5: u16 = 1080;
 }
+
+/// Default experiment settings
+///
+/// Contains constants related to A/B testing between proxy cohorts.
+pub mod experiment {
+    /// Length of each time window outcomes are bucketed into (in seconds)
+    ///
+    /// Comparing per-window success rates shows how cohorts trend over time.
+    pub const WINDOW_SECS: i64 = 3600; // 1 hour
+
+    /// Maximum number of windows kept per cohort
+    ///
+    /// Older windows are dropped once this many have been recorded.
+    pub const MAX_WINDOWS: usize = 48;
+}
+
+/// Default source settings
+///
+/// Contains constants related to tracking the health of proxy sources.
+pub mod sources {
+    /// Number of recent failures remembered per source
+    ///
+    /// Older failures still count towards the aggregated totals.
+    pub const MAX_RECENT_FAILURES: usize = 10;
+
+    /// Number of consecutive failed fetches before a source is blacklisted
+    ///
+    /// A single successful fetch resets the streak.
+    pub const BLACKLIST_THRESHOLD: usize = 5;
+
+    /// Number of sources of the same registered domain fetched at once
+    pub const PER_DOMAIN_CONCURRENCY: usize = 2;
+
+    /// Time between the starts of two fetches from the same registered domain (in milliseconds)
+    pub const PER_DOMAIN_INTERVAL_MS: u64 = 1000;
+
+    /// Largest response downloaded from a source (in bytes)
+    ///
+    /// Compressed responses and archives count at their downloaded size.
+    pub const MAX_DOWNLOAD_BYTES: u64 = 32 * 1024 * 1024; // 32 MiB
+
+    /// Largest text extracted from a compressed response or archive (in bytes)
+    ///
+    /// Guards against archives that expand far beyond their download size.
+    pub const MAX_EXTRACTED_BYTES: u64 = 64 * 1024 * 1024; // 64 MiB
+
+    /// Number of list files followed from the index pages of a source
+    ///
+    /// Links beyond the limit are ignored, in the order the index lists them.
+    pub const MAX_INDEX_LINKS: usize = 50;
+}
+
+/// Default Wayback Machine settings
+///
+/// Contains constants related to harvesting proxies from archived snapshots
+/// of sources (`wayback` feature).
+pub mod wayback {
+    /// URL of the Internet Archive's CDX API, which lists the snapshots of a URL
+    pub const CDX_URL: &str = "https://web.archive.org/cdx/search/cdx";
+
+    /// URL under which the Internet Archive serves snapshots
+    pub const ARCHIVE_URL: &str = "https://web.archive.org/web";
+
+    /// Number of the most recent snapshots read per source URL
+    pub const MAX_SNAPSHOTS: usize = 20;
+
+    /// Time between two requests to the archive (in milliseconds)
+    ///
+    /// The archive throttles clients that query it faster.
+    pub const REQUEST_INTERVAL_MS: u64 = 1500;
+}
+
+/// Default concurrency isolation settings
+///
+/// Contains constants related to isolating bulk judgement from the host runtime.
+pub mod isolation {
+    /// Number of worker threads of the dedicated judgement runtime
+    ///
+    /// Only used when bulk judgement runs on its own runtime.
+    pub const WORKER_THREADS: usize = 2;
+
+    /// Name given to the worker threads of the dedicated judgement runtime
+    pub const THREAD_NAME: &str = "gooty-judgement";
+}
+
+/// Default judgement retry settings
+///
+/// Contains constants related to retrying judgement of a proxy, independent of
+/// the requestor's own retries.
+pub mod judgement_retry {
+    /// Judgement attempts per proxy in quick mode
+    pub const QUICK_ATTEMPTS: u32 = 2;
+
+    /// Judgement attempts per proxy in full mode
+    pub const FULL_ATTEMPTS: u32 = 3;
+
+    /// Factor applied to the timeout after each failed attempt
+    ///
+    /// Slow proxies get more time on later attempts instead of failing on a
+    /// single transient timeout.
+    pub const TIMEOUT_MULTIPLIER: f64 = 1.5;
+
+    /// Judges each proxy is checked against in quick mode
+    pub const QUICK_CONSENSUS_JUDGES: usize = 1;
+
+    /// Judges each proxy is checked against in full mode
+    ///
+    /// Judges may strip headers such as `X-Forwarded-For`, so a single judge
+    /// can overrate a proxy's anonymity.
+    pub const FULL_CONSENSUS_JUDGES: usize = 3;
+
+    /// Judges whose round trip through a proxy is measured to find the
+    /// nearest one
+    pub const NEAREST_JUDGE_CANDIDATES: usize = 3;
+}
+
+/// Default SNI passthrough check settings
+///
+/// Contains constants related to verifying that proxies leave the TLS SNI
+/// and Host header of a connection intact.
+pub mod sni_check {
+    /// Target only answering connections with the right SNI
+    ///
+    /// Cloudflare refuses TLS handshakes without a matching SNI, and its trace
+    /// endpoint reports the host and SNI state it received.
+    pub const TARGET_URL: &str = "https://www.cloudflare.com/cdn-cgi/trace";
+}
+
+/// Default target access check settings
+///
+/// Contains constants related to checking whether proxies can reach a
+/// particular target.
+pub mod target_access {
+    /// How long the outcome of a check is reused, in seconds
+    pub const CACHE_TTL_SECS: u64 = 900; // 15 minutes
+}
+
+/// Default pool warm-up settings
+///
+/// Contains constants related to making sure a pool is ready before a job
+/// starts.
+pub mod warm_up {
+    /// How long ago a proxy may have passed its last check to count as ready,
+    /// in seconds
+    pub const MAX_AGE_SECS: u64 = 600; // 10 minutes
+}
+
+/// Default local judge settings
+///
+/// Contains constants related to the judge gooty serves itself.
+pub mod local_judge {
+    /// Default address the local judge listens on
+    pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8092";
+
+    /// Time a client has to send its request (in seconds)
+    pub const REQUEST_TIMEOUT_SECS: u64 = 10;
+}
+
+/// Default judge baseline settings
+///
+/// Contains constants related to measuring the direct latency to each judge,
+/// which is subtracted from proxy latencies.
+pub mod judge_baseline {
+    /// Age after which a judge's baseline is measured again, in seconds
+    pub const MAX_AGE_SECS: i64 = 900; // 15 minutes
+
+    /// Direct requests per measurement; the fastest one is the baseline
+    pub const SAMPLES: usize = 3;
+}
+
+/// Default request profile settings
+///
+/// Contains constants related to the headers gooty sends on its own behalf,
+/// for judgement requests unless a request profile is configured, and for
+/// lookups under the stealth identity.
+pub mod request_profile {
+    /// Name of the profile judgement requests use by default
+    pub const JUDGE_NAME: &str = "gooty";
+
+    /// User-Agent of the default judgement profile, kept fixed for consistent results
+    pub const JUDGE_USER_AGENT: &str = "Mozilla/5.0 (compatible; Gooty-Proxy/0.1)";
+
+    /// Name of the profile that blends with browser traffic
+    pub const STEALTH_NAME: &str = "stealth";
+
+    /// User-Agent sent under the stealth identity
+    pub const STEALTH_USER_AGENT: &str =
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:124.0) Gecko/20100101 Firefox/124.0";
+
+    /// Accept header of page requests under the stealth identity
+    pub const STEALTH_ACCEPT: &str =
+        "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8";
+
+    /// Accept-Language header sent under the stealth identity
+    pub const STEALTH_ACCEPT_LANGUAGE: &str = "en-US,en;q=0.5";
+}
+
+/// Default lookup provider settings
+///
+/// Contains constants related to the IP lookup providers used by the sleuth.
+pub mod providers {
+    /// Name under which ipinfo.io lookups are accounted
+    pub const IPINFO: &str = "ipinfo";
+
+    /// Number of ipinfo.io lookups allowed per day
+    ///
+    /// Matches the lookup allowance of the free ipinfo.io plan.
+    pub const IPINFO_DAILY_QUOTA: u64 = 50_000;
+
+    /// Share of a quota after which warnings are emitted
+    pub const QUOTA_WARNING_RATIO: f64 = 0.8;
+
+    /// Prefix of the environment variables holding provider API tokens
+    pub const TOKEN_ENV_PREFIX: &str = "GOOTY_";
+
+    /// Suffix of the environment variables holding provider API tokens
+    ///
+    /// The ipinfo.io token is read from `GOOTY_IPINFO_TOKEN`.
+    pub const TOKEN_ENV_SUFFIX: &str = "_TOKEN";
+
+    /// Name under which RDAP lookups are accounted
+    pub const RDAP: &str = "rdap";
+}
+
+/// Default response capture settings
+///
+/// Contains constants related to keeping raw responses of judges and lookup
+/// providers for debugging.
+pub mod capture {
+    /// Number of responses kept when capturing is enabled without a number
+    pub const DEFAULT_CAPACITY: usize = 50;
+
+    /// Longest response body kept, in bytes; longer bodies are cut off
+    pub const MAX_BODY_BYTES: usize = 16 * 1024;
+}
+
+/// Default listener settings
+///
+/// Contains constants related to the local endpoint that relays client
+/// connections through the pool.
+pub mod listener {
+    /// Address the SOCKS5 listener binds to
+    pub const DEFAULT_SOCKS_ADDRESS: &str = "127.0.0.1:1080";
+
+    /// Time a client has to finish the SOCKS5 handshake (in seconds)
+    pub const HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+
+    /// Time an upstream proxy has to open the connection to the target (in seconds)
+    pub const UPSTREAM_CONNECT_TIMEOUT_SECS: u64 = 10;
+
+    /// Number of upstream proxies tried for a client before it is refused
+    pub const MAX_UPSTREAM_ATTEMPTS: usize = 3;
+
+    /// Time between two saves of the client statistics of a running listener (in seconds)
+    pub const STATS_SAVE_INTERVAL_SECS: u64 = 60;
+
+    /// Number of the highest scoring proxies whose ports are watched
+    pub const WATCH_TOP_PROXIES: usize = 20;
+
+    /// Time between probes of the watched ports (in seconds)
+    pub const WATCH_INTERVAL_SECS: u64 = 5;
+
+    /// Time a watched port has to accept a connection (in seconds)
+    pub const WATCH_CONNECT_TIMEOUT_SECS: u64 = 3;
+}
+
+/// Default event stream settings
+///
+/// Contains constants related to streaming the events of gather runs.
+pub mod events {
+    /// Address the event stream server binds to
+    pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8090";
+
+    /// Number of events a subscriber may fall behind before missing the oldest
+    pub const CHANNEL_CAPACITY: usize = 1024;
+
+    /// Time between comment lines on an idle event stream (in seconds)
+    ///
+    /// Keeps proxies and load balancers from closing the connection.
+    pub const KEEP_ALIVE_SECS: u64 = 15;
+
+    /// Time a client has to send its request (in seconds)
+    pub const REQUEST_TIMEOUT_SECS: u64 = 10;
+}
+
+/// Default distributed judging settings
+///
+/// Contains constants related to coordinators handing out judging jobs and
+/// the workers judging them.
+pub mod coordinator {
+    /// Address the coordinator's API binds to
+    pub const DEFAULT_ADDRESS: &str = "127.0.0.1:8091";
+
+    /// Time a worker has to report a claimed job before it is handed out again (in seconds)
+    pub const LEASE_TIMEOUT_SECS: u64 = 600;
+
+    /// Number of proxies a worker claims at once
+    pub const BATCH_SIZE: usize = 200;
+
+    /// Time a worker waits before claiming again when every job is leased (in seconds)
+    pub const IDLE_POLL_SECS: u64 = 5;
+
+    /// Time a client has to send its request (in seconds)
+    pub const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+    /// Largest request body the coordinator reads, in bytes
+    pub const MAX_REQUEST_BODY: usize = 64 * 1024 * 1024;
+
+    /// Time between two saves of the canonical pool of a running coordinator (in seconds)
+    pub const SAVE_INTERVAL_SECS: u64 = 60;
+
+    /// How far ahead of the coordinator's clock a worker's check may be dated (in seconds)
+    pub const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+    /// Environment variable holding the token shared by a coordinator and its workers
+    pub const TOKEN_ENV: &str = "GOOTY_COORDINATOR_TOKEN";
+}
+
+/// Default lookup cache settings
+///
+/// Contains constants related to caching the documents lookup providers
+/// return about addresses.
+pub mod lookup_cache {
+    /// How long a looked up document is reused, in seconds
+    pub const TTL_SECS: u64 = 86400; // 24 hours
+}
+
+/// Default cloud range settings
+///
+/// Contains constants related to the published address ranges of cloud
+/// providers.
+pub mod cloud_ranges {
+    /// Feed of the Amazon Web Services address ranges
+    pub const AWS_URL: &str = "https://ip-ranges.amazonaws.com/ip-ranges.json";
+
+    /// Feed of the Google Cloud address ranges
+    pub const GCP_URL: &str = "https://www.gstatic.com/ipranges/cloud.json";
+
+    /// Download page of the Azure service tags
+    ///
+    /// Microsoft publishes the service tags under a new file name every
+    /// week, so the current file is looked up on this page.
+    pub const AZURE_URL: &str = "https://www.microsoft.com/en-us/download/details.aspx?id=56519";
+
+    /// Feed of the Cloudflare address ranges
+    pub const CLOUDFLARE_URL: &str = "https://api.cloudflare.com/client/v4/ips";
+
+    /// Age after which downloaded ranges are refreshed, in seconds
+    pub const MAX_AGE_SECS: u64 = 86400; // 24 hours
+
+    /// Timeout for downloading a feed, in seconds
+    pub const DOWNLOAD_TIMEOUT_SECS: u64 = 60;
+}
+
+/// Default RDAP settings
+///
+/// Contains constants related to resolving ownership chains through RDAP.
+pub mod rdap {
+    /// Bootstrap service redirecting queries to the responsible registry
+    pub const BOOTSTRAP_URL: &str = "https://rdap.org";
+
+    /// Maximum number of organizations in an ownership chain
+    pub const MAX_CHAIN_DEPTH: usize = 8;
+}
+
+/// Default streaming settings
+///
+/// Contains constants related to streaming proxies from sources to validators.
+pub mod streaming {
+    /// Number of scraped proxies buffered between fetchers and validators
+    ///
+    /// Fetchers wait once the buffer is full, so memory use stays bounded when
+    /// validation lags behind scraping.
+    pub const CHANNEL_CAPACITY: usize = 256;
+
+    /// Maximum number of concurrent source fetches
+    pub const FETCH_CONCURRENCY: usize = 4;
+}
+
+/// Default scheduler settings
+///
+/// Contains the default cron schedules of the recurring maintenance jobs.
+/// Expressions use the six-field form with a leading seconds field.
+pub mod scheduler {
+    /// When to fetch new proxies from all active sources
+    pub const FETCH_SOURCES_CRON: &str = "0 0 * * * *"; // hourly
+
+    /// When to re-judge proxies that have not been checked recently
+    pub const RECHECK_STALE_CRON: &str = "0 */15 * * * *"; // every 15 minutes
+
+    /// When to remove proxies that keep failing their checks
+    pub const PRUNE_CRON: &str = "0 30 3 * * *"; // daily at 03:30
+
+    /// When to record a snapshot of proxy and source statistics
+    pub const SNAPSHOT_STATS_CRON: &str = "0 */5 * * * *"; // every 5 minutes
+
+    /// Default schedule of the `RefreshCloudRanges` job
+    pub const REFRESH_CLOUD_RANGES_CRON: &str = "0 15 4 * * *"; // daily at 04:15
+
+    /// Minimum number of checks before a proxy can be pruned
+    ///
+    /// Keeps freshly added proxies from being pruned after a single failure.
+    pub const PRUNE_MIN_CHECKS: usize = 3;
+
+    /// Number of statistics snapshots kept by the scheduler
+    pub const MAX_SNAPSHOTS: usize = 288; // one day at five minute intervals
+
+    /// Number of recent snapshots a new snapshot is compared against
+    pub const ANOMALY_WINDOW: usize = 12; // one hour at five minute intervals
+
+    /// Minimum number of earlier snapshots before anomalies are detected
+    pub const ANOMALY_MIN_SAMPLES: usize = 6;
+
+    /// Z-score beyond which a health metric is considered abnormal
+    pub const ANOMALY_Z_THRESHOLD: f64 = 3.0;
+
+    /// Smallest standard deviation assumed, as a share of the mean
+    ///
+    /// Keeps a pool whose figures barely moved from flagging tiny changes.
+    pub const ANOMALY_MIN_RELATIVE_SPREAD: f64 = 0.05;
+}
+
+/// Default liveness check settings
+///
+/// Contains constants related to the cheap probes run between full
+/// judgements. A probe only confirms the proxy accepts connections, while a
+/// full judgement also re-establishes its anonymity level.
+pub mod liveness {
+    /// Timeout of a liveness probe, connecting and handshake together (in milliseconds)
+    pub const PROBE_TIMEOUT_MS: u64 = 1500;
+
+    /// How long a judged anonymity level is trusted (in seconds)
+    ///
+    /// Proxies judged longer ago are fully re-judged on their next recheck,
+    /// others are only probed.
+    pub const ANONYMITY_MAX_AGE_SECS: u64 = 259_200; // 3 days
+}
+
+/// Default churn statistics settings
+///
+/// Contains constants related to how long proxies stay alive in the pool.
+pub mod churn {
+    /// Ages at which the share of surviving proxies is reported (in seconds)
+    pub const SURVIVAL_HORIZONS_SECS: [i64; 4] = [3_600, 21_600, 86_400, 604_800]; // 1h, 6h, 1d, 7d
+}
+
+/// Default per-domain usage settings
+///
+/// Contains constants related to tracking how well proxies work for each
+/// target domain.
+pub mod domain_stats {
+    /// Time after which recorded outcomes count half as much (in seconds)
+    pub const HALF_LIFE_SECS: i64 = 86_400; // 1 day
+
+    /// Number of target domains tracked per proxy
+    ///
+    /// The least recently used domain is dropped when a proxy is used for a
+    /// new one beyond this limit.
+    pub const MAX_DOMAINS: usize = 32;
+}
+
+/// Default exit address settings
+///
+/// Contains constants related to the exit addresses judges see proxies'
+/// requests come from.
+pub mod exits {
+    /// Number of exit addresses kept in a proxy's exit history
+    ///
+    /// The least recently seen address is dropped when a new one is seen
+    /// beyond this limit.
+    pub const MAX_HISTORY: usize = 16;
+}
+
+/// Default source bundle settings
+///
+/// Contains constants related to sharing sets of sources as bundles.
+pub mod bundle {
+    /// Version of the bundle format written by this version
+    ///
+    /// Bundles of a newer format are refused rather than installed partly.
+    pub const FORMAT_VERSION: u32 = 1;
+}
+
+/// Default rate limiting settings
+///
+/// Contains constants related to pacing the requests gooty makes on its own
+/// behalf, such as lookups and source fetches, per host.
+pub mod rate_limit {
+    /// Requests sent to a host per second once its burst is spent
+    pub const REQUESTS_PER_SEC: f64 = 5.0;
+
+    /// Requests sent to a host at once before the rate applies
+    pub const BURST: u32 = 10;
+
+    /// Times a request answered with 429 Too Many Requests is sent again
+    pub const MAX_RETRIES: u32 = 3;
+
+    /// Wait before the first retry when the host doesn't say (in milliseconds)
+    ///
+    /// Doubled on every further retry.
+    pub const BACKOFF_MS: u64 = 1000;
+
+    /// Longest wait before a retry, even if the host asks for longer (in seconds)
+    pub const MAX_BACKOFF_SECS: u64 = 60;
+}
@@ -240,7 +240,7 @@ impl fmt::Display for ValidationState {
 }
 
 /// Represents the different rotation strategies for proxy selection
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RotationStrategy {
     /// Round-robin selection without considering performance
     Sequential,
@@ -251,6 +251,7 @@ pub enum RotationStrategy {
     /// Select based on successful requests history
     Reliability,
     /// Weighted random selection based on performance metrics
+    #[default]
     Weighted,
 }
 
@@ -312,7 +313,8 @@ impl fmt::Display for SourceStatus {
 ///     println!("Debug information: {}", "details");
 /// }
 /// ```
-#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
 pub enum LogLevel {
     /// Critical errors that may cause application failure
     Error,
@@ -349,6 +351,8 @@ pub enum VerificationMethod {
     AnonymityCheck,
     /// Extended verification with multiple judges and targets
     Comprehensive,
+    /// Check that the proxy passes TLS SNI and the Host header through untouched
+    SniPassthrough,
 }
 
 impl fmt::Display for VerificationMethod {
@@ -358,6 +362,7 @@ impl fmt::Display for VerificationMethod {
             VerificationMethod::TargetAccess => write!(f, "Target Access"),
             VerificationMethod::AnonymityCheck => write!(f, "Anonymity Check"),
             VerificationMethod::Comprehensive => write!(f, "Comprehensive"),
+            VerificationMethod::SniPassthrough => write!(f, "SNI Passthrough"),
         }
     }
 }
@@ -421,3 +426,1517 @@ impl std::str::FromStr for JudgementMode {
         }
     }
 }
+
+/// # TLS Profile
+///
+/// Represents a client TLS configuration used when talking to targets.
+///
+/// Anti-bot systems fingerprint the TLS `ClientHello` (JA3). Each profile offers a
+/// distinct combination of protocol versions, cipher suite order, key exchange
+/// groups, and ALPN values, so requests made with the same profile always present
+/// the same fingerprint.
+///
+/// * `Native` - The TLS backend compiled into reqwest, left untouched
+/// * `Modern` - TLS 1.3 only, AES-GCM preferred, advertises HTTP/2
+/// * `Mobile` - TLS 1.2 and 1.3, `ChaCha20` preferred, advertises HTTP/2
+/// * `Compatible` - TLS 1.2 and 1.3, AES-GCM preferred, HTTP/1.1 only
+///
+/// Profiles other than `Native` require the `tls-profiles` feature.
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::TlsProfile;
+/// use std::str::FromStr;
+///
+/// let profile = TlsProfile::from_str("mobile").unwrap();
+/// assert_eq!(profile, TlsProfile::Mobile);
+/// assert_eq!(profile.to_string(), "Mobile");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TlsProfile {
+    /// The TLS backend compiled into reqwest, left untouched
+    #[default]
+    Native,
+    /// TLS 1.3 only, AES-GCM preferred, advertises HTTP/2
+    Modern,
+    /// TLS 1.2 and 1.3, `ChaCha20` preferred, advertises HTTP/2
+    Mobile,
+    /// TLS 1.2 and 1.3, AES-GCM preferred, HTTP/1.1 only
+    Compatible,
+}
+
+impl TlsProfile {
+    /// Profiles that produce a customised `ClientHello`.
+    pub const CUSTOM: [TlsProfile; 3] = [
+        TlsProfile::Modern,
+        TlsProfile::Mobile,
+        TlsProfile::Compatible,
+    ];
+}
+
+impl fmt::Display for TlsProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsProfile::Native => write!(f, "Native"),
+            TlsProfile::Modern => write!(f, "Modern"),
+            TlsProfile::Mobile => write!(f, "Mobile"),
+            TlsProfile::Compatible => write!(f, "Compatible"),
+        }
+    }
+}
+
+impl std::str::FromStr for TlsProfile {
+    type Err = String;
+
+    /// Converts a string to a `TlsProfile`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(TlsProfile)` - If the string matches a known profile
+    /// * `Err(String)` - If the string doesn't match any known profile
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "native" => Ok(TlsProfile::Native),
+            "modern" => Ok(TlsProfile::Modern),
+            "mobile" => Ok(TlsProfile::Mobile),
+            "compatible" => Ok(TlsProfile::Compatible),
+            _ => Err(format!("Unknown TLS profile: {s}")),
+        }
+    }
+}
+
+/// # Identity Profile
+///
+/// Decides how gooty identifies itself in requests it makes on its own
+/// behalf, as opposed to requests sent with the user's source settings.
+///
+/// * `Declared` - Requests say they come from gooty
+/// * `Stealth` - Requests blend in with browser traffic
+///
+/// With `Declared`, judgement requests carry the
+/// `Mozilla/5.0 (compatible; Gooty-Proxy/0.1)` User-Agent, and lookups at
+/// ipinfo.io, RDAP, and the cloud range feeds carry no User-Agent at all,
+/// only the `Accept` header their API needs. With `Stealth`, all of them carry
+/// a current browser User-Agent and `Accept-Language`, and judgement requests
+/// also send a browser `Accept` header.
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::IdentityProfile;
+/// use std::str::FromStr;
+///
+/// assert_eq!(IdentityProfile::default(), IdentityProfile::Declared);
+/// assert_eq!(IdentityProfile::from_str("browser").unwrap(), IdentityProfile::Stealth);
+/// assert_eq!(IdentityProfile::Stealth.to_string(), "Stealth");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IdentityProfile {
+    /// Requests say they come from gooty
+    #[default]
+    Declared,
+    /// Requests blend in with browser traffic
+    Stealth,
+}
+
+impl fmt::Display for IdentityProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdentityProfile::Declared => write!(f, "Declared"),
+            IdentityProfile::Stealth => write!(f, "Stealth"),
+        }
+    }
+}
+
+impl std::str::FromStr for IdentityProfile {
+    type Err = String;
+
+    /// Converts a string to an `IdentityProfile`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(IdentityProfile)` - If the string matches a known profile
+    /// * `Err(String)` - If the string doesn't match any known profile
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "declared" | "gooty" => Ok(IdentityProfile::Declared),
+            "stealth" | "browser" => Ok(IdentityProfile::Stealth),
+            _ => Err(format!("Unknown identity profile: {s}")),
+        }
+    }
+}
+
+/// # Error Class
+///
+/// How an error should be handled by code that decides whether to try again.
+///
+/// * `Transient` - The failure is likely temporary, trying again may succeed
+/// * `RateLimited` - The other side asked to slow down, try again after a while
+/// * `Permanent` - Trying again will fail the same way
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ErrorClass;
+///
+/// assert_eq!(ErrorClass::from_status(503), ErrorClass::Transient);
+/// assert_eq!(ErrorClass::from_status(429), ErrorClass::RateLimited);
+/// assert_eq!(ErrorClass::from_status(404), ErrorClass::Permanent);
+/// assert!(ErrorClass::RateLimited.is_retryable());
+/// assert!(ErrorClass::Permanent.is_permanent());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorClass {
+    /// The failure is likely temporary, trying again may succeed
+    Transient,
+    /// The other side asked to slow down, try again after a while
+    RateLimited,
+    /// Trying again will fail the same way
+    Permanent,
+}
+
+impl ErrorClass {
+    /// Classifies an HTTP status code returned with a failed request
+    ///
+    /// Server errors and request timeouts are transient, 429 asks to slow
+    /// down, and every other status is permanent.
+    ///
+    /// # Arguments
+    ///
+    /// * `status` - The HTTP status code
+    #[must_use]
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            429 => ErrorClass::RateLimited,
+            408 | 500..=599 => ErrorClass::Transient,
+            _ => ErrorClass::Permanent,
+        }
+    }
+
+    /// Checks whether trying again may succeed
+    #[must_use]
+    pub fn is_retryable(self) -> bool {
+        self != ErrorClass::Permanent
+    }
+
+    /// Checks whether trying again will fail the same way
+    #[must_use]
+    pub fn is_permanent(self) -> bool {
+        self == ErrorClass::Permanent
+    }
+}
+
+impl fmt::Display for ErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorClass::Transient => write!(f, "Transient"),
+            ErrorClass::RateLimited => write!(f, "Rate Limited"),
+            ErrorClass::Permanent => write!(f, "Permanent"),
+        }
+    }
+}
+
+/// # Sync Policy
+///
+/// Decides which copy wins when the in-memory pool and the on-disk pool both
+/// contain the same proxy or source.
+///
+/// * `Merge` - The copy with the most recent activity wins
+/// * `PreferMemory` - The in-memory copy always wins
+/// * `PreferDisk` - The on-disk copy always wins
+///
+/// Entries present on only one side are always kept.
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::SyncPolicy;
+///
+/// assert_eq!(SyncPolicy::default(), SyncPolicy::Merge);
+/// assert_eq!(SyncPolicy::PreferDisk.to_string(), "Prefer Disk");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SyncPolicy {
+    /// The copy with the most recent activity wins
+    #[default]
+    Merge,
+    /// The in-memory copy always wins
+    PreferMemory,
+    /// The on-disk copy always wins
+    PreferDisk,
+}
+
+impl fmt::Display for SyncPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncPolicy::Merge => write!(f, "Merge"),
+            SyncPolicy::PreferMemory => write!(f, "Prefer Memory"),
+            SyncPolicy::PreferDisk => write!(f, "Prefer Disk"),
+        }
+    }
+}
+
+/// # Merge Policy
+///
+/// Decides how two copies of the same proxy are combined when merging pools
+/// gathered on different machines.
+///
+/// * `Reconcile` - The copy with the most recent activity provides the
+///   metadata, and the counts of both copies are summed
+/// * `PreferFirst` - The copy of the first pool wins
+/// * `PreferSecond` - The copy of the second pool wins
+///
+/// Proxies present in only one pool are always kept.
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::MergePolicy;
+///
+/// assert_eq!(MergePolicy::default(), MergePolicy::Reconcile);
+/// assert_eq!("prefer-second".parse(), Ok(MergePolicy::PreferSecond));
+/// assert_eq!(MergePolicy::PreferFirst.to_string(), "Prefer First");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MergePolicy {
+    /// The copy with the most recent activity provides the metadata, and the
+    /// counts of both copies are summed
+    #[default]
+    Reconcile,
+    /// The copy of the first pool wins
+    PreferFirst,
+    /// The copy of the second pool wins
+    PreferSecond,
+}
+
+impl fmt::Display for MergePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergePolicy::Reconcile => write!(f, "Reconcile"),
+            MergePolicy::PreferFirst => write!(f, "Prefer First"),
+            MergePolicy::PreferSecond => write!(f, "Prefer Second"),
+        }
+    }
+}
+
+impl std::str::FromStr for MergePolicy {
+    type Err = String;
+
+    /// Converts a string to a `MergePolicy`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(MergePolicy)` - If the string matches a known policy
+    /// * `Err(String)` - If the string doesn't match any known policy
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "reconcile" => Ok(MergePolicy::Reconcile),
+            "preferfirst" | "first" => Ok(MergePolicy::PreferFirst),
+            "prefersecond" | "second" => Ok(MergePolicy::PreferSecond),
+            _ => Err(format!("Unknown merge policy: {s}")),
+        }
+    }
+}
+
+/// # Conflict Policy
+///
+/// Decides what installing a source bundle does with sources that are
+/// already installed, matched by URL.
+///
+/// * `Keep` - The installed source stays as it is
+/// * `Replace` - The bundle's definition replaces the installed one, which
+///   keeps its history
+/// * `Abort` - Nothing is installed if any source is already installed
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ConflictPolicy;
+///
+/// assert_eq!(ConflictPolicy::default(), ConflictPolicy::Keep);
+/// assert_eq!("replace".parse(), Ok(ConflictPolicy::Replace));
+/// assert_eq!(ConflictPolicy::Abort.to_string(), "Abort");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConflictPolicy {
+    /// The installed source stays as it is
+    #[default]
+    Keep,
+    /// The bundle's definition replaces the installed one, which keeps its
+    /// history
+    Replace,
+    /// Nothing is installed if any source is already installed
+    Abort,
+}
+
+impl fmt::Display for ConflictPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConflictPolicy::Keep => write!(f, "Keep"),
+            ConflictPolicy::Replace => write!(f, "Replace"),
+            ConflictPolicy::Abort => write!(f, "Abort"),
+        }
+    }
+}
+
+impl std::str::FromStr for ConflictPolicy {
+    type Err = String;
+
+    /// Converts a string to a `ConflictPolicy`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ConflictPolicy)` - If the string matches a known policy
+    /// * `Err(String)` - If the string doesn't match any known policy
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "keep" | "skip" => Ok(ConflictPolicy::Keep),
+            "replace" | "overwrite" => Ok(ConflictPolicy::Replace),
+            "abort" => Ok(ConflictPolicy::Abort),
+            _ => Err(format!("Unknown conflict policy: {s}")),
+        }
+    }
+}
+
+/// # Concurrency Isolation
+///
+/// Decides how bulk judgement and enrichment share resources with the host
+/// application's async runtime.
+///
+/// * `Shared` - Jobs run on the caller's runtime without extra limits
+/// * `Budget` - Jobs across all bulk operations share a bounded number of permits
+/// * `Dedicated` - Jobs run on a separate multi-thread runtime owned by the manager
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ConcurrencyIsolation;
+///
+/// assert_eq!(ConcurrencyIsolation::default(), ConcurrencyIsolation::Shared);
+/// assert_eq!("dedicated".parse(), Ok(ConcurrencyIsolation::Dedicated));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConcurrencyIsolation {
+    /// Jobs run on the caller's runtime without extra limits
+    #[default]
+    Shared,
+    /// Jobs across all bulk operations share a bounded number of permits
+    Budget,
+    /// Jobs run on a separate multi-thread runtime owned by the manager
+    Dedicated,
+}
+
+impl fmt::Display for ConcurrencyIsolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcurrencyIsolation::Shared => write!(f, "Shared"),
+            ConcurrencyIsolation::Budget => write!(f, "Budget"),
+            ConcurrencyIsolation::Dedicated => write!(f, "Dedicated"),
+        }
+    }
+}
+
+impl std::str::FromStr for ConcurrencyIsolation {
+    type Err = String;
+
+    /// Converts a string to a `ConcurrencyIsolation`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ConcurrencyIsolation)` - If the string matches a known isolation mode
+    /// * `Err(String)` - If the string doesn't match any known isolation mode
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "shared" => Ok(ConcurrencyIsolation::Shared),
+            "budget" => Ok(ConcurrencyIsolation::Budget),
+            "dedicated" => Ok(ConcurrencyIsolation::Dedicated),
+            _ => Err(format!("Unknown concurrency isolation: {s}")),
+        }
+    }
+}
+
+/// # Scheduled Job
+///
+/// Represents a recurring maintenance job run by the scheduler.
+///
+/// * `FetchSources` - Fetch new proxies from all active sources, judging them as they arrive
+/// * `RecheckStale` - Re-judge proxies that have not been checked recently
+/// * `Prune` - Remove proxies that keep failing their checks
+/// * `SnapshotStats` - Record a snapshot of proxy and source statistics
+/// * `RefreshCloudRanges` - Download the published address ranges of cloud providers
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ScheduledJob;
+///
+/// assert_eq!("recheck-stale".parse(), Ok(ScheduledJob::RecheckStale));
+/// assert_eq!(ScheduledJob::SnapshotStats.to_string(), "Snapshot Stats");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ScheduledJob {
+    /// Fetch new proxies from all active sources, judging them as they arrive
+    FetchSources,
+    /// Re-judge proxies that have not been checked recently
+    RecheckStale,
+    /// Remove proxies that keep failing their checks
+    Prune,
+    /// Record a snapshot of proxy and source statistics
+    SnapshotStats,
+    /// Download the published address ranges of cloud providers
+    RefreshCloudRanges,
+}
+
+impl fmt::Display for ScheduledJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduledJob::FetchSources => write!(f, "Fetch Sources"),
+            ScheduledJob::RecheckStale => write!(f, "Recheck Stale"),
+            ScheduledJob::Prune => write!(f, "Prune"),
+            ScheduledJob::SnapshotStats => write!(f, "Snapshot Stats"),
+            ScheduledJob::RefreshCloudRanges => write!(f, "Refresh Cloud Ranges"),
+        }
+    }
+}
+
+impl std::str::FromStr for ScheduledJob {
+    type Err = String;
+
+    /// Converts a string to a `ScheduledJob`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ScheduledJob)` - If the string matches a known job
+    /// * `Err(String)` - If the string doesn't match any known job
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "fetchsources" => Ok(ScheduledJob::FetchSources),
+            "recheckstale" => Ok(ScheduledJob::RecheckStale),
+            "prune" => Ok(ScheduledJob::Prune),
+            "snapshotstats" => Ok(ScheduledJob::SnapshotStats),
+            "refreshcloudranges" => Ok(ScheduledJob::RefreshCloudRanges),
+            _ => Err(format!("Unknown scheduled job: {s}")),
+        }
+    }
+}
+
+/// # Quota Window
+///
+/// Represents the period a lookup provider quota applies to.
+///
+/// * `Hour` - The current clock hour, in UTC
+/// * `Day` - The current calendar day, in UTC
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::QuotaWindow;
+///
+/// assert_eq!("daily".parse(), Ok(QuotaWindow::Day));
+/// assert_eq!(QuotaWindow::Hour.to_string(), "Hour");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuotaWindow {
+    /// The current clock hour, in UTC
+    Hour,
+    /// The current calendar day, in UTC
+    Day,
+}
+
+impl fmt::Display for QuotaWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaWindow::Hour => write!(f, "Hour"),
+            QuotaWindow::Day => write!(f, "Day"),
+        }
+    }
+}
+
+impl std::str::FromStr for QuotaWindow {
+    type Err = String;
+
+    /// Converts a string to a `QuotaWindow`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(QuotaWindow)` - If the string matches a known window
+    /// * `Err(String)` - If the string doesn't match any known window
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "hour" | "hourly" => Ok(QuotaWindow::Hour),
+            "day" | "daily" => Ok(QuotaWindow::Day),
+            _ => Err(format!("Unknown quota window: {s}")),
+        }
+    }
+}
+
+/// # Registry
+///
+/// Represents the regional internet registry an address block is allocated by.
+///
+/// * `Arin` - North America
+/// * `Ripe` - Europe, the Middle East, and Central Asia
+/// * `Apnic` - Asia-Pacific
+/// * `Lacnic` - Latin America and the Caribbean
+/// * `Afrinic` - Africa
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::Registry;
+///
+/// assert_eq!("ripe ncc".parse(), Ok(Registry::Ripe));
+/// assert_eq!(Registry::Arin.to_string(), "ARIN");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Registry {
+    /// North America
+    Arin,
+    /// Europe, the Middle East, and Central Asia
+    Ripe,
+    /// Asia-Pacific
+    Apnic,
+    /// Latin America and the Caribbean
+    Lacnic,
+    /// Africa
+    Afrinic,
+}
+
+impl fmt::Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Registry::Arin => write!(f, "ARIN"),
+            Registry::Ripe => write!(f, "RIPE"),
+            Registry::Apnic => write!(f, "APNIC"),
+            Registry::Lacnic => write!(f, "LACNIC"),
+            Registry::Afrinic => write!(f, "AFRINIC"),
+        }
+    }
+}
+
+impl std::str::FromStr for Registry {
+    type Err = String;
+
+    /// Converts a string to a `Registry`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Registry)` - If the string matches a known registry
+    /// * `Err(String)` - If the string doesn't match any known registry
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace([' ', '-', '_'], "").as_str() {
+            "arin" => Ok(Registry::Arin),
+            "ripe" | "ripencc" => Ok(Registry::Ripe),
+            "apnic" => Ok(Registry::Apnic),
+            "lacnic" => Ok(Registry::Lacnic),
+            "afrinic" => Ok(Registry::Afrinic),
+            _ => Err(format!("Unknown registry: {s}")),
+        }
+    }
+}
+
+/// # Cloud Provider
+///
+/// Represents a cloud platform whose published address ranges are tracked.
+///
+/// * `Aws` - Amazon Web Services
+/// * `Gcp` - Google Cloud Platform
+/// * `Azure` - Microsoft Azure
+/// * `Cloudflare` - Cloudflare
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::CloudProvider;
+///
+/// assert_eq!("google cloud".parse(), Ok(CloudProvider::Gcp));
+/// assert_eq!(CloudProvider::Aws.to_string(), "AWS");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CloudProvider {
+    /// Amazon Web Services
+    Aws,
+    /// Google Cloud Platform
+    Gcp,
+    /// Microsoft Azure
+    Azure,
+    /// Cloudflare
+    Cloudflare,
+}
+
+impl CloudProvider {
+    /// Returns every tracked cloud provider
+    #[must_use]
+    pub fn all() -> [CloudProvider; 4] {
+        [
+            CloudProvider::Aws,
+            CloudProvider::Gcp,
+            CloudProvider::Azure,
+            CloudProvider::Cloudflare,
+        ]
+    }
+}
+
+impl fmt::Display for CloudProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloudProvider::Aws => write!(f, "AWS"),
+            CloudProvider::Gcp => write!(f, "GCP"),
+            CloudProvider::Azure => write!(f, "Azure"),
+            CloudProvider::Cloudflare => write!(f, "Cloudflare"),
+        }
+    }
+}
+
+impl std::str::FromStr for CloudProvider {
+    type Err = String;
+
+    /// Converts a string to a `CloudProvider`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CloudProvider)` - If the string matches a known provider
+    /// * `Err(String)` - If the string doesn't match any known provider
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace([' ', '-', '_'], "").as_str() {
+            "aws" | "amazon" | "amazonwebservices" => Ok(CloudProvider::Aws),
+            "gcp" | "google" | "googlecloud" => Ok(CloudProvider::Gcp),
+            "azure" | "microsoftazure" => Ok(CloudProvider::Azure),
+            "cloudflare" => Ok(CloudProvider::Cloudflare),
+            _ => Err(format!("Unknown cloud provider: {s}")),
+        }
+    }
+}
+
+/// # Consensus Rule
+///
+/// Represents how the verdicts of several judges are combined when they
+/// disagree on a proxy's anonymity level.
+///
+/// * `Pessimistic` - The least anonymous verdict wins
+/// * `Majority` - The most common verdict wins, ties going to the less anonymous
+/// * `Optimistic` - The most anonymous verdict wins
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ConsensusRule;
+///
+/// assert_eq!(ConsensusRule::default(), ConsensusRule::Pessimistic);
+/// assert_eq!("majority".parse(), Ok(ConsensusRule::Majority));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConsensusRule {
+    /// The least anonymous verdict wins
+    #[default]
+    Pessimistic,
+    /// The most common verdict wins, ties going to the less anonymous
+    Majority,
+    /// The most anonymous verdict wins
+    Optimistic,
+}
+
+impl fmt::Display for ConsensusRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsensusRule::Pessimistic => write!(f, "Pessimistic"),
+            ConsensusRule::Majority => write!(f, "Majority"),
+            ConsensusRule::Optimistic => write!(f, "Optimistic"),
+        }
+    }
+}
+
+impl std::str::FromStr for ConsensusRule {
+    type Err = String;
+
+    /// Converts a string to a `ConsensusRule`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ConsensusRule)` - If the string matches a known rule
+    /// * `Err(String)` - If the string doesn't match any known rule
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pessimistic" => Ok(ConsensusRule::Pessimistic),
+            "majority" => Ok(ConsensusRule::Majority),
+            "optimistic" => Ok(ConsensusRule::Optimistic),
+            _ => Err(format!("Unknown consensus rule: {s}")),
+        }
+    }
+}
+
+/// # Judge Scheme
+///
+/// Represents which judges a proxy is judged against, by URL scheme.
+///
+/// * `Http` - Plain HTTP judges only
+/// * `Https` - HTTPS judges only, so the proxy must carry TLS intact
+/// * `Any` - Judges of either scheme
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::JudgeScheme;
+///
+/// assert_eq!(JudgeScheme::default(), JudgeScheme::Http);
+/// assert!(JudgeScheme::Https.matches("https://judge.example/azenv.php"));
+/// assert!(!JudgeScheme::Https.matches("http://judge.example/azenv.php"));
+/// assert!(JudgeScheme::Any.matches("http://judge.example/azenv.php"));
+/// assert_eq!("https".parse(), Ok(JudgeScheme::Https));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum JudgeScheme {
+    /// Plain HTTP judges only
+    #[default]
+    Http,
+    /// HTTPS judges only, so the proxy must carry TLS intact
+    Https,
+    /// Judges of either scheme
+    Any,
+}
+
+impl JudgeScheme {
+    /// Checks whether a judge URL has a scheme this selects
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The judge URL
+    ///
+    /// # Returns
+    ///
+    /// `true` if judges at the URL may be used
+    #[must_use]
+    pub fn matches(self, url: &str) -> bool {
+        let https = url
+            .get(..8)
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https://"));
+        match self {
+            JudgeScheme::Http => !https,
+            JudgeScheme::Https => https,
+            JudgeScheme::Any => true,
+        }
+    }
+}
+
+impl fmt::Display for JudgeScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JudgeScheme::Http => write!(f, "HTTP"),
+            JudgeScheme::Https => write!(f, "HTTPS"),
+            JudgeScheme::Any => write!(f, "Any"),
+        }
+    }
+}
+
+impl std::str::FromStr for JudgeScheme {
+    type Err = String;
+
+    /// Converts a string to a `JudgeScheme`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JudgeScheme)` - If the string matches a known scheme
+    /// * `Err(String)` - If the string doesn't match any known scheme
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "http" => Ok(JudgeScheme::Http),
+            "https" | "tls" => Ok(JudgeScheme::Https),
+            "any" | "both" => Ok(JudgeScheme::Any),
+            _ => Err(format!("Unknown judge scheme: {s}")),
+        }
+    }
+}
+
+/// # Proxy Lifecycle
+///
+/// Where a proxy stands in the pool, moved along by checks, usage reports,
+/// and retirement policies.
+///
+/// * `New` - Not checked yet
+/// * `Validating` - Being checked for the first time, or again after quarantine
+/// * `Active` - Passed its last check and is handed out
+/// * `Degraded` - Failed recently but not often enough to be pulled, still handed out
+/// * `Quarantined` - Failed too often in a row, not handed out until a check passes
+/// * `Retired` - Given up on, never handed out or checked again
+///
+/// A passed check makes any proxy but a retired one active. Failures move an
+/// active proxy to degraded, and a degraded proxy to quarantine once
+/// `rotation::MAX_CONSECUTIVE_FAILURES` failures follow each other. Proxies
+/// that fail their first check go to quarantine at once.
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ProxyLifecycle;
+///
+/// assert_eq!(ProxyLifecycle::default(), ProxyLifecycle::New);
+/// assert!(ProxyLifecycle::Degraded.is_usable());
+/// assert!(!ProxyLifecycle::Quarantined.is_usable());
+/// assert_eq!("retired".parse(), Ok(ProxyLifecycle::Retired));
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub enum ProxyLifecycle {
+    /// Not checked yet
+    #[default]
+    New,
+    /// Being checked for the first time, or again after quarantine
+    Validating,
+    /// Passed its last check and is handed out
+    Active,
+    /// Failed recently but not often enough to be pulled, still handed out
+    Degraded,
+    /// Failed too often in a row, not handed out until a check passes
+    Quarantined,
+    /// Given up on, never handed out or checked again
+    Retired,
+}
+
+impl ProxyLifecycle {
+    /// Checks whether proxies in this state are handed out
+    #[must_use]
+    pub fn is_usable(self) -> bool {
+        matches!(self, ProxyLifecycle::Active | ProxyLifecycle::Degraded)
+    }
+}
+
+impl fmt::Display for ProxyLifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyLifecycle::New => write!(f, "New"),
+            ProxyLifecycle::Validating => write!(f, "Validating"),
+            ProxyLifecycle::Active => write!(f, "Active"),
+            ProxyLifecycle::Degraded => write!(f, "Degraded"),
+            ProxyLifecycle::Quarantined => write!(f, "Quarantined"),
+            ProxyLifecycle::Retired => write!(f, "Retired"),
+        }
+    }
+}
+
+impl std::str::FromStr for ProxyLifecycle {
+    type Err = String;
+
+    /// Converts a string to a `ProxyLifecycle`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProxyLifecycle)` - If the string matches a known state
+    /// * `Err(String)` - If the string doesn't match any known state
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "new" => Ok(ProxyLifecycle::New),
+            "validating" => Ok(ProxyLifecycle::Validating),
+            "active" => Ok(ProxyLifecycle::Active),
+            "degraded" => Ok(ProxyLifecycle::Degraded),
+            "quarantined" => Ok(ProxyLifecycle::Quarantined),
+            "retired" => Ok(ProxyLifecycle::Retired),
+            _ => Err(format!("Unknown proxy lifecycle state: {s}")),
+        }
+    }
+}
+
+/// # Export Format
+///
+/// File format proxies are exported in, for analytics or downstream tools.
+///
+/// * `Csv` - Comma-separated values with a header row
+/// * `Parquet` - Apache Parquet, available with the `arrow` feature
+/// * `Json` - A JSON array with an object per proxy
+/// * `List` - One `ip:port` per line
+/// * `Urls` - One `scheme://ip:port` per line
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ExportFormat;
+///
+/// assert_eq!("parquet".parse(), Ok(ExportFormat::Parquet));
+/// assert_eq!("ip:port".parse(), Ok(ExportFormat::List));
+/// assert_eq!(ExportFormat::Csv.extension(), "csv");
+/// assert_eq!(ExportFormat::Urls.extension(), "txt");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    #[default]
+    Csv,
+    /// Apache Parquet, available with the `arrow` feature
+    Parquet,
+    /// A JSON array with an object per proxy
+    Json,
+    /// One `ip:port` per line
+    List,
+    /// One `scheme://ip:port` per line
+    Urls,
+}
+
+impl ExportFormat {
+    /// Returns the file extension of the format
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Json => "json",
+            ExportFormat::List | ExportFormat::Urls => "txt",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Csv => write!(f, "CSV"),
+            ExportFormat::Parquet => write!(f, "Parquet"),
+            ExportFormat::Json => write!(f, "JSON"),
+            ExportFormat::List => write!(f, "List"),
+            ExportFormat::Urls => write!(f, "URLs"),
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    /// Converts a string to an `ExportFormat`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ExportFormat)` - If the string matches a known format
+    /// * `Err(String)` - If the string doesn't match any known format
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' ', ':'], "").as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "parquet" => Ok(ExportFormat::Parquet),
+            "json" => Ok(ExportFormat::Json),
+            "list" | "ipport" | "txt" => Ok(ExportFormat::List),
+            "urls" | "url" => Ok(ExportFormat::Urls),
+            _ => Err(format!("Unknown export format: {s}")),
+        }
+    }
+}
+
+/// # Export Column
+///
+/// A column of the CSV and JSON exports, named after its header.
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ExportColumn;
+///
+/// assert_eq!("latency".parse(), Ok(ExportColumn::Latency));
+/// assert_eq!(ExportColumn::Latency.to_string(), "latency_ms");
+/// assert_eq!(ExportColumn::ALL[0], ExportColumn::Ip);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExportColumn {
+    /// Address of the proxy
+    Ip,
+    /// Port of the proxy
+    Port,
+    /// Protocol of the proxy
+    ProxyType,
+    /// Anonymity level found by the last judgement
+    Anonymity,
+    /// Country code of the proxy's address
+    Country,
+    /// Autonomous system number of the proxy's address
+    Asn,
+    /// Organization owning the proxy's address
+    Organization,
+    /// Latency of the last passed check, in milliseconds
+    Latency,
+    /// Fraction of checks passed, between 0 and 1
+    SuccessRate,
+    /// When the proxy was first added
+    FirstSeen,
+    /// When the proxy was last checked or probed
+    LastSeen,
+}
+
+impl ExportColumn {
+    /// Every column, in the order of a full export
+    pub const ALL: [ExportColumn; 11] = [
+        ExportColumn::Ip,
+        ExportColumn::Port,
+        ExportColumn::ProxyType,
+        ExportColumn::Anonymity,
+        ExportColumn::Country,
+        ExportColumn::Asn,
+        ExportColumn::Organization,
+        ExportColumn::Latency,
+        ExportColumn::SuccessRate,
+        ExportColumn::FirstSeen,
+        ExportColumn::LastSeen,
+    ];
+
+    /// Returns the header of the column
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportColumn::Ip => "ip",
+            ExportColumn::Port => "port",
+            ExportColumn::ProxyType => "proxy_type",
+            ExportColumn::Anonymity => "anonymity",
+            ExportColumn::Country => "country",
+            ExportColumn::Asn => "asn",
+            ExportColumn::Organization => "organization",
+            ExportColumn::Latency => "latency_ms",
+            ExportColumn::SuccessRate => "success_rate",
+            ExportColumn::FirstSeen => "first_seen",
+            ExportColumn::LastSeen => "last_seen",
+        }
+    }
+}
+
+impl fmt::Display for ExportColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for ExportColumn {
+    type Err = String;
+
+    /// Converts a string to an `ExportColumn`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ExportColumn)` - If the string names a known column
+    /// * `Err(String)` - If the string doesn't name any known column
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "ip" | "address" => Ok(ExportColumn::Ip),
+            "port" => Ok(ExportColumn::Port),
+            "proxytype" | "type" | "scheme" => Ok(ExportColumn::ProxyType),
+            "anonymity" => Ok(ExportColumn::Anonymity),
+            "country" => Ok(ExportColumn::Country),
+            "asn" => Ok(ExportColumn::Asn),
+            "organization" | "org" => Ok(ExportColumn::Organization),
+            "latency" | "latencyms" => Ok(ExportColumn::Latency),
+            "successrate" => Ok(ExportColumn::SuccessRate),
+            "firstseen" => Ok(ExportColumn::FirstSeen),
+            "lastseen" => Ok(ExportColumn::LastSeen),
+            _ => Err(format!("Unknown export column: {s}")),
+        }
+    }
+}
+
+/// # Health Metric
+///
+/// A pool health figure watched for abnormal changes between statistics
+/// snapshots.
+///
+/// * `WorkingProxies` - Number of working proxies, abnormal when it drops
+/// * `AverageLatency` - Average latency of working proxies, abnormal when it rises
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::HealthMetric;
+///
+/// assert_eq!("working-proxies".parse(), Ok(HealthMetric::WorkingProxies));
+/// assert_eq!(HealthMetric::AverageLatency.to_string(), "Average Latency");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HealthMetric {
+    /// Number of working proxies, abnormal when it drops
+    WorkingProxies,
+    /// Average latency of working proxies, abnormal when it rises
+    AverageLatency,
+}
+
+impl fmt::Display for HealthMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HealthMetric::WorkingProxies => write!(f, "Working Proxies"),
+            HealthMetric::AverageLatency => write!(f, "Average Latency"),
+        }
+    }
+}
+
+impl std::str::FromStr for HealthMetric {
+    type Err = String;
+
+    /// Converts a string to a `HealthMetric`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HealthMetric)` - If the string matches a known metric
+    /// * `Err(String)` - If the string doesn't match any known metric
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "workingproxies" => Ok(HealthMetric::WorkingProxies),
+            "averagelatency" => Ok(HealthMetric::AverageLatency),
+            _ => Err(format!("Unknown health metric: {s}")),
+        }
+    }
+}
+
+/// # Dedup Policy
+///
+/// Decides which proxies count as the same exit, for deduplication,
+/// statistics, and keeping leased proxies apart.
+///
+/// * `IpPort` - Every address and port is its own exit
+/// * `Ip` - All ports of an address are one exit
+/// * `Subnet` - All addresses of an IPv4 /24 or IPv6 /48 are one exit
+///
+/// Some providers expose many ports on one exit address, or hand out
+/// neighbouring addresses of one network; grouping these keeps the pool from
+/// counting, or a session from using, the same exit twice.
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::DedupPolicy;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let address = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+/// assert_eq!(DedupPolicy::IpPort.exit_key(address, 8080), "203.0.113.7:8080");
+/// assert_eq!(DedupPolicy::Ip.exit_key(address, 8080), "203.0.113.7");
+/// assert_eq!(DedupPolicy::Subnet.exit_key(address, 8080), "203.0.113.0/24");
+/// assert_eq!("subnet".parse(), Ok(DedupPolicy::Subnet));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DedupPolicy {
+    /// Every address and port is its own exit
+    #[default]
+    IpPort,
+    /// All ports of an address are one exit
+    Ip,
+    /// All addresses of an IPv4 /24 or IPv6 /48 are one exit
+    Subnet,
+}
+
+impl DedupPolicy {
+    /// Returns the key identifying the exit of an address and port
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - Address of the proxy
+    /// * `port` - Port of the proxy
+    ///
+    /// # Returns
+    ///
+    /// A key equal for all proxies the policy counts as the same exit
+    #[must_use]
+    pub fn exit_key(self, address: std::net::IpAddr, port: u16) -> String {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+        match (self, address) {
+            (DedupPolicy::IpPort, IpAddr::V4(_)) => format!("{address}:{port}"),
+            (DedupPolicy::IpPort, IpAddr::V6(_)) => format!("[{address}]:{port}"),
+            (DedupPolicy::Ip, _) => address.to_string(),
+            (DedupPolicy::Subnet, IpAddr::V4(v4)) => {
+                let network = Ipv4Addr::from(v4.to_bits() & !0xFF);
+                format!("{network}/24")
+            }
+            (DedupPolicy::Subnet, IpAddr::V6(v6)) => {
+                let network = Ipv6Addr::from(v6.to_bits() & !((1_u128 << 80) - 1));
+                format!("{network}/48")
+            }
+        }
+    }
+}
+
+impl fmt::Display for DedupPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DedupPolicy::IpPort => write!(f, "IP and Port"),
+            DedupPolicy::Ip => write!(f, "IP"),
+            DedupPolicy::Subnet => write!(f, "Subnet"),
+        }
+    }
+}
+
+impl std::str::FromStr for DedupPolicy {
+    type Err = String;
+
+    /// Converts a string to a `DedupPolicy`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DedupPolicy)` - If the string matches a known policy
+    /// * `Err(String)` - If the string doesn't match any known policy
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' ', ':'], "").as_str() {
+            "ipport" => Ok(DedupPolicy::IpPort),
+            "ip" => Ok(DedupPolicy::Ip),
+            "subnet" | "24" | "/24" => Ok(DedupPolicy::Subnet),
+            _ => Err(format!("Unknown dedup policy: {s}")),
+        }
+    }
+}
+
+/// # Duplicate Policy
+///
+/// Decides what happens when a proxy already in the pool arrives again, such
+/// as the same address, port and type found on another source with
+/// credentials or a country attached.
+///
+/// * `Keep` - The pooled copy stays as it is
+/// * `Enrich` - Metadata the pooled copy lacks is taken from the arrival
+/// * `Overwrite` - Metadata the arrival carries replaces the pooled copy's
+///
+/// The pooled copy's check, use and probe history is kept either way.
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::DuplicatePolicy;
+///
+/// assert_eq!(DuplicatePolicy::default(), DuplicatePolicy::Enrich);
+/// assert_eq!("overwrite".parse(), Ok(DuplicatePolicy::Overwrite));
+/// assert_eq!(DuplicatePolicy::Keep.to_string(), "Keep");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DuplicatePolicy {
+    /// The pooled copy stays as it is
+    Keep,
+    /// Metadata the pooled copy lacks is taken from the arrival
+    #[default]
+    Enrich,
+    /// Metadata the arrival carries replaces the pooled copy's
+    Overwrite,
+}
+
+impl fmt::Display for DuplicatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicatePolicy::Keep => write!(f, "Keep"),
+            DuplicatePolicy::Enrich => write!(f, "Enrich"),
+            DuplicatePolicy::Overwrite => write!(f, "Overwrite"),
+        }
+    }
+}
+
+impl std::str::FromStr for DuplicatePolicy {
+    type Err = String;
+
+    /// Converts a string to a `DuplicatePolicy`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DuplicatePolicy)` - If the string matches a known policy
+    /// * `Err(String)` - If the string doesn't match any known policy
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "keep" => Ok(DuplicatePolicy::Keep),
+            "enrich" => Ok(DuplicatePolicy::Enrich),
+            "overwrite" | "replace" => Ok(DuplicatePolicy::Overwrite),
+            _ => Err(format!("Unknown duplicate policy: {s}")),
+        }
+    }
+}
+
+/// # Proxy Sort Key
+///
+/// A property proxies are ordered by, best first.
+///
+/// * `Latency` - Lower latency first, unmeasured latency last
+/// * `SuccessRate` - Higher check success rate first
+/// * `LastChecked` - More recently checked first, unchecked proxies last
+/// * `Anonymity` - More anonymous first
+/// * `Country` - By country code, proxies without a country last
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ProxySortKey;
+///
+/// assert_eq!("success-rate".parse(), Ok(ProxySortKey::SuccessRate));
+/// assert_eq!(ProxySortKey::LastChecked.to_string(), "Last Checked");
+/// assert_eq!(ProxySortKey::QUALITY[0], ProxySortKey::SuccessRate);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProxySortKey {
+    /// Lower latency first, unmeasured latency last
+    Latency,
+    /// Higher check success rate first
+    SuccessRate,
+    /// More recently checked first, unchecked proxies last
+    LastChecked,
+    /// More anonymous first
+    Anonymity,
+    /// By country code, proxies without a country last
+    Country,
+}
+
+impl ProxySortKey {
+    /// Keys ordering proxies by overall quality, most significant first
+    pub const QUALITY: [ProxySortKey; 4] = [
+        ProxySortKey::SuccessRate,
+        ProxySortKey::Latency,
+        ProxySortKey::Anonymity,
+        ProxySortKey::LastChecked,
+    ];
+}
+
+impl fmt::Display for ProxySortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxySortKey::Latency => write!(f, "Latency"),
+            ProxySortKey::SuccessRate => write!(f, "Success Rate"),
+            ProxySortKey::LastChecked => write!(f, "Last Checked"),
+            ProxySortKey::Anonymity => write!(f, "Anonymity"),
+            ProxySortKey::Country => write!(f, "Country"),
+        }
+    }
+}
+
+impl std::str::FromStr for ProxySortKey {
+    type Err = String;
+
+    /// Converts a string to a `ProxySortKey`
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to convert
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProxySortKey)` - If the string matches a known sort key
+    /// * `Err(String)` - If the string doesn't match any known sort key
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "latency" => Ok(ProxySortKey::Latency),
+            "successrate" | "success" => Ok(ProxySortKey::SuccessRate),
+            "lastchecked" | "checked" => Ok(ProxySortKey::LastChecked),
+            "anonymity" => Ok(ProxySortKey::Anonymity),
+            "country" => Ok(ProxySortKey::Country),
+            _ => Err(format!("Unknown sort key: {s}")),
+        }
+    }
+}
+
+/// # Data Issue Kind
+///
+/// What is wrong with an entry of a persisted file.
+///
+/// * `Malformed` - The entry can't be read at all
+/// * `InvalidPort` - A proxy whose port is zero or out of range
+/// * `DuplicateKey` - An entry stored again under a key already used
+/// * `InvalidRegex` - A source whose pattern doesn't compile
+/// * `InvalidSource` - A source whose URL or URL template is invalid
+/// * `StaleSchema` - Written by an older version, with fields no longer used
+///
+/// ## Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::DataIssueKind;
+///
+/// assert!(DataIssueKind::DuplicateKey.drops_entry());
+/// assert!(!DataIssueKind::StaleSchema.drops_entry());
+/// assert_eq!(DataIssueKind::InvalidRegex.to_string(), "Invalid Regex");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DataIssueKind {
+    /// The entry can't be read at all
+    Malformed,
+    /// A proxy whose port is zero or out of range
+    InvalidPort,
+    /// An entry stored again under a key already used
+    DuplicateKey,
+    /// A source whose pattern doesn't compile
+    InvalidRegex,
+    /// A source whose URL or URL template is invalid
+    InvalidSource,
+    /// Written by an older version, with fields no longer used
+    StaleSchema,
+}
+
+impl DataIssueKind {
+    /// Checks whether repairing the issue removes the entry
+    ///
+    /// Stale entries are rewritten in the current format, every other
+    /// entry at fault is dropped.
+    #[must_use]
+    pub fn drops_entry(self) -> bool {
+        !matches!(self, DataIssueKind::StaleSchema)
+    }
+}
+
+impl fmt::Display for DataIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataIssueKind::Malformed => write!(f, "Malformed"),
+            DataIssueKind::InvalidPort => write!(f, "Invalid Port"),
+            DataIssueKind::DuplicateKey => write!(f, "Duplicate Key"),
+            DataIssueKind::InvalidRegex => write!(f, "Invalid Regex"),
+            DataIssueKind::InvalidSource => write!(f, "Invalid Source"),
+            DataIssueKind::StaleSchema => write!(f, "Stale Schema"),
+        }
+    }
+}
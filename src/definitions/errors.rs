@@ -18,8 +18,11 @@
 //! - `OwnershipError`: For ASN and organization lookup failures
 //! - `SleuthError`: For IP investigation failures
 //! - `ManagerError`: For high-level proxy management errors
+//! - `ListenerError`: For failures of the local listener relaying through the pool
 //!
 //! Each error type has a corresponding `Result` type alias for more convenient function signatures.
+//! Errors of network operations also report an `ErrorClass`, so callers can
+//! decide whether to try again without matching on error messages.
 //!
 //! ## Examples
 //!
@@ -35,7 +38,9 @@
 //! }
 //! ```
 
-use reqwest::StatusCode;
+use crate::definitions::enums::ErrorClass;
+use http::StatusCode;
+use std::fmt;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -78,6 +83,12 @@ pub enum CidrError {
     /// This typically occurs when trying to use an IPv4 address in an IPv6 context or vice versa.
     #[error("IP version mismatch")]
     IpVersionMismatch,
+
+    /// Indicates that a `CidrSet` has no room for another block.
+    ///
+    /// A set holds up to about four billion trie nodes per address family.
+    #[error("CIDR set is full")]
+    SetFull,
 }
 
 /// Result type for CIDR operations
@@ -90,6 +101,7 @@ pub enum RequestorError {
     ///
     /// This typically occurs for network-level issues such as DNS failures,
     /// connection problems, or TLS errors.
+    #[cfg(feature = "network")]
     #[error("HTTP request error: {0}")]
     RequestError(#[from] reqwest::Error),
 
@@ -107,10 +119,86 @@ pub enum RequestorError {
 
     /// Represents errors specific to proxy connection failures.
     ///
-    /// This could include authentication failures, connection refused errors,
+    /// This could include connection refused errors, handshake failures,
     /// or other proxy-specific connectivity issues.
     #[error("Proxy connection error: {0}")]
     ProxyError(String),
+
+    /// Indicates that the proxy rejected the supplied credentials.
+    ///
+    /// Raised for HTTP proxies answering `407 Proxy Authentication Required`
+    /// and for SOCKS5 proxies refusing the username/password negotiation.
+    #[error("Proxy authentication failed: {0}")]
+    ProxyAuthenticationFailed(String),
+
+    /// Indicates that a client TLS configuration could not be built.
+    ///
+    /// Raised when a TLS profile combines protocol versions and cipher suites
+    /// that the TLS backend refuses.
+    #[error("TLS configuration error: {0}")]
+    TlsConfiguration(String),
+
+    /// Indicates that the TLS handshake with the target failed.
+    ///
+    /// Raised when the certificate presented does not match the requested
+    /// name, or the handshake is cut off, as happens when a proxy drops or
+    /// rewrites the SNI of the connection.
+    #[error("TLS handshake failed: {0}")]
+    TlsHandshake(String),
+
+    /// Indicates that a response body is larger than allowed.
+    ///
+    /// Raised while downloading, so oversized responses are cut off rather
+    /// than held in memory.
+    #[error("Response exceeds the limit of {0} bytes")]
+    ResponseTooLarge(u64),
+}
+
+impl RequestorError {
+    /// Classifies the error for retry decisions.
+    ///
+    /// Timeouts and connection failures are transient, HTTP statuses are
+    /// classified by code, and rejected credentials, broken TLS
+    /// configurations, and malformed requests are permanent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::ErrorClass;
+    /// use gooty_proxy::definitions::errors::RequestorError;
+    ///
+    /// assert_eq!(RequestorError::Timeout(10).class(), ErrorClass::Transient);
+    /// assert!(RequestorError::ProxyAuthenticationFailed("407".into()).is_permanent());
+    /// ```
+    #[must_use]
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            #[cfg(feature = "network")]
+            RequestorError::RequestError(e) => match e.status() {
+                Some(status) => ErrorClass::from_status(status.as_u16()),
+                None if e.is_builder() => ErrorClass::Permanent,
+                None => ErrorClass::Transient,
+            },
+            RequestorError::Timeout(_) | RequestorError::ProxyError(_) => ErrorClass::Transient,
+            RequestorError::StatusError(status, _) => ErrorClass::from_status(status.as_u16()),
+            RequestorError::ProxyAuthenticationFailed(_)
+            | RequestorError::TlsConfiguration(_)
+            | RequestorError::TlsHandshake(_)
+            | RequestorError::ResponseTooLarge(_) => ErrorClass::Permanent,
+        }
+    }
+
+    /// Checks whether trying again may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// Checks whether trying again will fail the same way.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        self.class().is_permanent()
+    }
 }
 
 /// Result type for HTTP requests
@@ -299,11 +387,141 @@ pub enum SourceError {
     /// This typically occurs when a source returns data in an unexpected format.
     #[error("Failed to parse source response: {0}")]
     ParseError(String),
+
+    /// Indicates that the source answered with a non-success HTTP status.
+    ///
+    /// Includes the status code and its description, so failures can be
+    /// aggregated by code.
+    #[error("Source returned HTTP status {0}: {1}")]
+    HttpStatus(u16, String),
+
+    /// Indicates that a source URL template can't be expanded.
+    ///
+    /// This occurs when a template variable is configured without any values.
+    #[error("Invalid source template: {0}")]
+    InvalidTemplate(String),
+
+    /// Indicates that a compressed response or archive couldn't be extracted.
+    ///
+    /// This occurs when a gzip stream or zip archive is corrupt or uses an
+    /// unsupported compression method.
+    #[error("Failed to extract source archive: {0}")]
+    ArchiveError(String),
+
+    /// Indicates that a response, or the lists extracted from it, is larger
+    /// than allowed.
+    #[error("Source response exceeds the limit of {0} bytes")]
+    ResponseTooLarge(u64),
+}
+
+impl SourceError {
+    /// Returns the HTTP status code associated with the error, if any.
+    #[must_use]
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            SourceError::HttpStatus(code, _) => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Classifies the error for retry decisions.
+    ///
+    /// Fetch failures are transient and HTTP statuses are classified by code.
+    /// Invalid URLs, patterns, and templates, and responses that can't be
+    /// parsed, fail the same way every time.
+    #[must_use]
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            SourceError::FetchFailure(_) => ErrorClass::Transient,
+            SourceError::HttpStatus(code, _) => ErrorClass::from_status(*code),
+            SourceError::InvalidUrl(_)
+            | SourceError::InvalidRegexPattern(_)
+            | SourceError::ParseError(_)
+            | SourceError::InvalidTemplate(_)
+            | SourceError::ArchiveError(_)
+            | SourceError::ResponseTooLarge(_) => ErrorClass::Permanent,
+        }
+    }
+
+    /// Checks whether trying again may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// Checks whether trying again will fail the same way.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        self.class().is_permanent()
+    }
+}
+
+impl From<RequestorError> for SourceError {
+    fn from(error: RequestorError) -> Self {
+        match error {
+            RequestorError::StatusError(status, message) => {
+                SourceError::HttpStatus(status.as_u16(), message)
+            }
+            RequestorError::ResponseTooLarge(limit) => SourceError::ResponseTooLarge(limit),
+            other => SourceError::FetchFailure(other.to_string()),
+        }
+    }
 }
 
 /// Result type for source operations
 pub type SourceResult<T> = Result<T, SourceError>;
 
+/// Errors that can occur while reading, signing or installing source bundles
+#[cfg(feature = "bundle")]
+#[derive(Debug, Error)]
+pub enum BundleError {
+    /// Represents I/O errors reading or writing a bundle or key file.
+    #[error("I/O error: {0}")]
+    IoError(String),
+
+    /// Indicates that a bundle or key can't be parsed.
+    #[error("Parse error: {0}")]
+    ParseError(String),
+
+    /// Indicates that a bundle can't be serialized.
+    #[error("Serialization error: {0}")]
+    SerializationError(String),
+
+    /// Indicates that a bundle was written in a newer format.
+    #[error("Unsupported bundle format version {0}")]
+    UnsupportedVersion(u32),
+
+    /// Indicates that a bundle has no signature where one is required.
+    #[error("Bundle is not signed")]
+    Unsigned,
+
+    /// Indicates that a bundle's signature doesn't match its content.
+    ///
+    /// The bundle was changed after it was signed, or the signature is
+    /// corrupt.
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+
+    /// Indicates that a bundle was signed by a key that isn't trusted.
+    #[error("Bundle signed by untrusted key {0}")]
+    UntrustedSigner(String),
+
+    /// Indicates that a bundle carries sources that are already installed.
+    ///
+    /// Returned when installing with `ConflictPolicy::Abort`; lists the
+    /// URLs of the conflicting sources.
+    #[error("Sources already installed: {0}")]
+    Conflict(String),
+
+    /// Encapsulates an invalid source carried by a bundle.
+    #[error("Source error: {0}")]
+    SourceError(#[from] SourceError),
+}
+
+/// Result type for source bundle operations
+#[cfg(feature = "bundle")]
+pub type BundleResult<T> = Result<T, BundleError>;
+
 /// Error types that can occur during proxy judgement
 #[derive(Debug, Error)]
 pub enum JudgementError {
@@ -345,6 +563,36 @@ pub enum JudgementError {
     Other(String),
 }
 
+impl JudgementError {
+    /// Classifies the error for retry decisions.
+    ///
+    /// Request errors are classified like the underlying `RequestorError`.
+    /// Timeouts, failed checks, and unreadable judge responses are transient,
+    /// while a missing judge URL and other errors are permanent.
+    #[must_use]
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            JudgementError::RequestError(e) => e.class(),
+            JudgementError::ParseError(_)
+            | JudgementError::Timeout
+            | JudgementError::ProxyFailure(_) => ErrorClass::Transient,
+            JudgementError::NoJudgeUrl | JudgementError::Other(_) => ErrorClass::Permanent,
+        }
+    }
+
+    /// Checks whether trying again may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// Checks whether trying again will fail the same way.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        self.class().is_permanent()
+    }
+}
+
 /// Result type for judgement operations
 pub type JudgementResult<T> = Result<T, JudgementError>;
 
@@ -374,6 +622,12 @@ pub enum UtilError {
     /// This can occur when constructing regex patterns for various parsing operations.
     #[error("Invalid regex pattern: {0}")]
     InvalidRegex(String),
+
+    /// Indicates that a public suffix list couldn't be parsed.
+    ///
+    /// The list grouping hosts so far stays in use.
+    #[error("Invalid public suffix list: {0}")]
+    InvalidSuffixList(String),
 }
 
 /// Result type for utility functions
@@ -414,6 +668,33 @@ pub enum OwnershipError {
     RateLimited,
 }
 
+impl OwnershipError {
+    /// Classifies the error for retry decisions.
+    ///
+    /// Network and API errors are transient, while unknown resources and
+    /// responses that can't be parsed are permanent.
+    #[must_use]
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            OwnershipError::NetworkError(_) | OwnershipError::ApiError(_) => ErrorClass::Transient,
+            OwnershipError::RateLimited => ErrorClass::RateLimited,
+            OwnershipError::ParseError(_) | OwnershipError::NotFound(_) => ErrorClass::Permanent,
+        }
+    }
+
+    /// Checks whether trying again may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// Checks whether trying again will fail the same way.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        self.class().is_permanent()
+    }
+}
+
 /// Result type for ownership operations
 pub type OwnershipResult<T> = Result<T, OwnershipError>;
 
@@ -451,6 +732,13 @@ pub enum SleuthError {
     #[error("Rate limited")]
     RateLimited,
 
+    /// Indicates that a local `GeoIP` database couldn't be used.
+    ///
+    /// This occurs when a database file is missing, corrupt, or of a type
+    /// that isn't supported.
+    #[error("GeoIP database error: {0}")]
+    DatabaseError(String),
+
     /// Encapsulates an underlying ownership lookup error.
     ///
     /// This occurs when ownership lookup operations fail during IP investigation.
@@ -458,9 +746,227 @@ pub enum SleuthError {
     OwnershipError(#[from] OwnershipError),
 }
 
+impl SleuthError {
+    /// Classifies the error for retry decisions.
+    ///
+    /// Network and API errors are transient, while unknown resources,
+    /// responses that can't be parsed, and unusable databases are permanent.
+    /// Ownership lookup errors are classified like the underlying
+    /// `OwnershipError`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::ErrorClass;
+    /// use gooty_proxy::definitions::errors::SleuthError;
+    ///
+    /// assert_eq!(SleuthError::RateLimited.class(), ErrorClass::RateLimited);
+    /// assert!(SleuthError::RateLimited.is_retryable());
+    /// assert!(SleuthError::NotFound("10.0.0.1".into()).is_permanent());
+    /// ```
+    #[must_use]
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            SleuthError::NetworkError(_) | SleuthError::ApiError(_) => ErrorClass::Transient,
+            SleuthError::RateLimited => ErrorClass::RateLimited,
+            SleuthError::ParseError(_)
+            | SleuthError::NotFound(_)
+            | SleuthError::DatabaseError(_) => ErrorClass::Permanent,
+            SleuthError::OwnershipError(e) => e.class(),
+        }
+    }
+
+    /// Checks whether trying again may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// Checks whether trying again will fail the same way.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        self.class().is_permanent()
+    }
+}
+
 /// Result type for Sleuth operations
 pub type SleuthResult<T> = Result<T, SleuthError>;
 
+/// Errors that can occur while relaying client connections through the pool
+#[derive(Debug, Error)]
+pub enum ListenerError {
+    /// Represents I/O errors on the listening socket or a relayed connection.
+    #[error("I/O error: {0}")]
+    IoError(String),
+
+    /// Indicates that a client broke the SOCKS protocol.
+    #[error("Handshake error: {0}")]
+    HandshakeError(String),
+
+    /// Indicates that a client asked for something the listener doesn't do.
+    ///
+    /// This includes authentication methods other than none, and commands
+    /// other than CONNECT.
+    #[error("Unsupported request: {0}")]
+    Unsupported(String),
+
+    /// Indicates that no proxy in the pool can take the connection.
+    #[error("No proxy available")]
+    NoProxyAvailable,
+
+    /// Represents an upstream proxy failing to reach the target.
+    #[error("Upstream error: {0}")]
+    UpstreamError(String),
+
+    /// Indicates that an upstream proxy answered but refused the target.
+    ///
+    /// The proxy works, so it is banned from the target rather than cooled
+    /// down for every target.
+    #[error("Target refused: {0}")]
+    TargetRefused(String),
+
+    /// Indicates that a handshake or upstream connection took too long.
+    #[error("Timed out: {0}")]
+    Timeout(String),
+}
+
+impl ListenerError {
+    /// Classifies the error for retry decisions.
+    ///
+    /// Failures of sockets and upstream proxies are transient, and so is an
+    /// exhausted pool, as cooldowns run out. Clients breaking the protocol or
+    /// asking for unsupported features fail the same way every time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::errors::ListenerError;
+    ///
+    /// assert!(ListenerError::NoProxyAvailable.is_retryable());
+    /// assert!(ListenerError::Unsupported("BIND".into()).is_permanent());
+    /// ```
+    #[must_use]
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            ListenerError::IoError(_)
+            | ListenerError::NoProxyAvailable
+            | ListenerError::UpstreamError(_)
+            | ListenerError::TargetRefused(_)
+            | ListenerError::Timeout(_) => ErrorClass::Transient,
+            ListenerError::HandshakeError(_) | ListenerError::Unsupported(_) => {
+                ErrorClass::Permanent
+            }
+        }
+    }
+
+    /// Checks whether trying again may succeed.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.class().is_retryable()
+    }
+
+    /// Checks whether trying again will fail the same way.
+    #[must_use]
+    pub fn is_permanent(&self) -> bool {
+        self.class().is_permanent()
+    }
+}
+
+impl From<std::io::Error> for ListenerError {
+    fn from(error: std::io::Error) -> Self {
+        ListenerError::IoError(error.to_string())
+    }
+}
+
+/// Result type for listener operations
+pub type ListenerResult<T> = Result<T, ListenerError>;
+
+/// Identifies what the manager was working on when an error occurred.
+///
+/// Attached to a `ManagerError` with [`ManagerError::with_context`], so errors
+/// from bulk runs still name the proxy or source that failed.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::errors::{ErrorContext, ManagerError};
+///
+/// let context = ErrorContext::new("fetch")
+///     .with_id("https://example.com/proxies")
+///     .with_attempt(3);
+/// assert_eq!(context.to_string(), "fetch https://example.com/proxies (attempt 3)");
+///
+/// let error = ManagerError::InvalidSourceId("missing".into()).with_context(context.clone());
+/// assert_eq!(error.context(), Some(&context));
+/// assert!(matches!(error.inner(), ManagerError::InvalidSourceId(_)));
+/// assert_eq!(
+///     error.to_string(),
+///     "Invalid source ID: missing (during fetch https://example.com/proxies (attempt 3))"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The operation that failed, such as `fetch` or `judge`
+    pub operation: String,
+
+    /// Identifier of the proxy or source the operation worked on
+    pub id: Option<String>,
+
+    /// Number of the attempt that failed, counting from one
+    pub attempt: Option<usize>,
+}
+
+impl ErrorContext {
+    /// Creates a context for an operation.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The operation that failed
+    #[must_use]
+    pub fn new(operation: &str) -> Self {
+        ErrorContext {
+            operation: operation.to_string(),
+            id: None,
+            attempt: None,
+        }
+    }
+
+    /// Names the proxy or source the operation worked on.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Connection string of the proxy or URL of the source
+    #[must_use]
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Records which attempt failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - Number of the attempt, counting from one
+    #[must_use]
+    pub fn with_attempt(mut self, attempt: usize) -> Self {
+        self.attempt = Some(attempt);
+        self
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.operation)?;
+        if let Some(id) = &self.id {
+            write!(f, " {id}")?;
+        }
+        if let Some(attempt) = self.attempt {
+            write!(f, " (attempt {attempt})")?;
+        }
+        Ok(())
+    }
+}
+
 /// Errors that can occur in the proxy manager
 #[derive(Debug, Error)]
 pub enum ManagerError {
@@ -505,6 +1011,117 @@ pub enum ManagerError {
     /// This typically occurs when operations reference sources that don't exist.
     #[error("Invalid source ID: {0}")]
     InvalidSourceId(String),
+
+    /// Indicates that an experiment name is not registered with the manager.
+    ///
+    /// This typically occurs when reporting on or drawing from an experiment
+    /// that was never started or has already been stopped.
+    #[error("Invalid experiment ID: {0}")]
+    InvalidExperimentId(String),
+
+    /// Encapsulates an underlying filestore error.
+    ///
+    /// This occurs when the manager's pools cannot be loaded from or written to disk.
+    #[error("Filestore error: {0}")]
+    FilestoreError(#[from] FilestoreError),
+
+    /// Encapsulates an underlying source bundle error.
+    ///
+    /// This occurs when a bundle of sources cannot be installed.
+    #[cfg(feature = "bundle")]
+    #[error("Bundle error: {0}")]
+    BundleError(#[from] BundleError),
+
+    /// Indicates that the executor for bulk operations could not be started,
+    /// or shut down before a job finished.
+    ///
+    /// This typically occurs when the dedicated judgement runtime cannot spawn
+    /// its worker threads.
+    #[error("Executor error: {0}")]
+    ExecutorError(String),
+
+    /// Indicates that a service an operation needs, such as the judge, was
+    /// not set up.
+    #[error("Not initialized: {0}")]
+    NotInitialized(String),
+
+    /// Indicates that a task name is already taken by a running task.
+    ///
+    /// Task names identify tasks for aborting and reporting, so they must be
+    /// unique among running tasks.
+    #[error("Task already running: {0}")]
+    TaskAlreadyRunning(String),
+
+    /// Indicates that a schedule expression cannot be parsed.
+    ///
+    /// Schedules use cron syntax with five or six fields.
+    #[error("Invalid schedule: {0}")]
+    InvalidSchedule(String),
+
+    /// Indicates that an exported session cannot be imported.
+    ///
+    /// This occurs when the session was exported by a newer version with a
+    /// format this version doesn't know.
+    #[error("Unsupported session: {0}")]
+    UnsupportedSession(String),
+
+    /// Indicates a write to the filestore by a read-only replica.
+    ///
+    /// Replicas only read the state another manager saves; see
+    /// `ProxyManager::open_replica`.
+    #[error("Read-only replica: {0}")]
+    ReadOnly(String),
+
+    /// Wraps an error with the proxy or source it occurred on.
+    ///
+    /// Created with [`ManagerError::with_context`].
+    #[error("{source} (during {context})")]
+    Context {
+        /// What the manager was working on
+        context: ErrorContext,
+
+        /// The error that occurred
+        source: Box<ManagerError>,
+    },
+}
+
+impl ManagerError {
+    /// Attaches context to the error.
+    ///
+    /// Errors that already carry context keep it, since the innermost
+    /// context names the most specific proxy or source.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - What the manager was working on
+    #[must_use]
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            ManagerError::Context { .. } => self,
+            error => ManagerError::Context {
+                context,
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// Returns the context attached to the error, if any.
+    #[must_use]
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            ManagerError::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without its context.
+    #[must_use]
+    pub fn inner(&self) -> &ManagerError {
+        match self {
+            ManagerError::Context { source, .. } => source.inner(),
+            error => error,
+        }
+    }
 }
 
 /// Result type for proxy manager operations
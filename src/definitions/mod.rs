@@ -11,6 +11,7 @@
 //! * **Errors** - Custom error types for the system
 //! * **Defaults** - Default values for configuration and runtime behavior
 //! * **Enums** - Enumerations used across the system
+//! * **Types** - Validated value types, such as ports and latencies
 
 //! # Core Definitions
 //!
@@ -34,7 +35,7 @@
 //!
 //! ```
 //! use gooty_proxy::definitions::{
-//!     Proxy,
+//!     Port, Proxy,
 //!     enums::{ProxyType, AnonymityLevel},
 //! };
 //! use std::net::{IpAddr, Ipv4Addr};
@@ -43,7 +44,7 @@
 //! let proxy = Proxy::new(
 //!     ProxyType::Http,
 //!     IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
-//!     8080,
+//!     Port::new(8080).unwrap(),
 //!     AnonymityLevel::Elite
 //! );
 //!
@@ -56,6 +57,7 @@ pub mod enums;
 pub mod errors;
 pub mod proxy;
 pub mod source;
+pub mod types;
 
 // Re-exports for backward compatibility
 pub use defaults::{
@@ -65,15 +67,19 @@ pub use defaults::{
 };
 
 pub use enums::{
-    AnonymityLevel, LogLevel, ProxyType, RotationStrategy, SourceStatus, ValidationState,
-    VerificationMethod,
+    AnonymityLevel, CloudProvider, ConcurrencyIsolation, ConsensusRule, DataIssueKind, DedupPolicy,
+    ErrorClass, ExportFormat, HealthMetric, IdentityProfile, LogLevel, MergePolicy, ProxyLifecycle,
+    ProxyType, QuotaWindow, Registry, RotationStrategy, ScheduledJob, SourceStatus, SyncPolicy,
+    TlsProfile, ValidationState, VerificationMethod,
 };
 
 pub use errors::{
     CidrError, CidrResult, FilestoreError, FilestoreResult, JudgementError, JudgementResult,
-    ManagerError, ManagerResult, OwnershipError, OwnershipResult, ProxyError, RequestResult,
-    RequestorError, SleuthError, SleuthResult, SourceError, SourceResult, UtilError, UtilResult,
+    ListenerError, ListenerResult, ManagerError, ManagerResult, OwnershipError, OwnershipResult,
+    ProxyError, RequestResult, RequestorError, SleuthError, SleuthResult, SourceError,
+    SourceResult, UtilError, UtilResult,
 };
 
 pub use proxy::Proxy;
-pub use source::Source;
+pub use source::{Source, SourceFailure};
+pub use types::{LatencyMs, Port};
@@ -10,6 +10,8 @@
 //!
 //! - Defining sources with URLs and regex patterns for proxy extraction
 //! - Fetching and parsing proxy lists from various sources
+//! - Unpacking lists published as gzip files or zip archives
+//! - Following index pages that link to many list files
 //! - Tracking source reliability and performance metrics
 //! - Managing source parameters for customized requests
 //!
@@ -41,17 +43,114 @@
 //! ```
 
 use crate::definitions::{
-    enums::{AnonymityLevel, ProxyType},
+    defaults,
+    enums::SourceStatus,
     errors::{SourceError, SourceResult},
+};
+#[cfg(feature = "network")]
+use crate::definitions::{
+    enums::{AnonymityLevel, ProxyType},
     proxy::Proxy,
+    types::Port,
+};
+#[cfg(feature = "network")]
+use crate::io::{
+    archive,
+    http::{RequestProfile, Requestor},
 };
-use crate::io::http::Requestor;
 use crate::utils::{self, SerializableRegex};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::net::IpAddr;
-use std::str::FromStr;
+#[cfg(feature = "network")]
+use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+#[cfg(feature = "network")]
+use std::{net::IpAddr, str::FromStr};
+#[cfg(feature = "network")]
+use url::Url;
+
+/// A single recorded failure of a proxy source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceFailure {
+    /// When the failure happened
+    pub at: DateTime<Utc>,
+
+    /// Description of why the source failed
+    pub reason: String,
+
+    /// HTTP status code if the failure was related to an HTTP response
+    pub status_code: Option<u16>,
+}
+
+/// What a server said about a list file, to tell whether it changed
+///
+/// Taken from the answer to a HEAD request. A list counts as unchanged if
+/// its entity tag or modification time is the same as before, and so is its
+/// size where both answers give one.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::source::ListValidators;
+///
+/// let before = ListValidators {
+///     etag: Some("\"v1\"".to_string()),
+///     last_modified: None,
+///     content_length: Some(1256),
+/// };
+/// assert!(before.unchanged(&before.clone()));
+///
+/// let grown = ListValidators { content_length: Some(2048), ..before.clone() };
+/// assert!(!before.unchanged(&grown));
+///
+/// // Without an entity tag or modification time, nothing can be told
+/// let bare = ListValidators { etag: None, last_modified: None, content_length: Some(1256) };
+/// assert!(!bare.is_usable());
+/// assert!(!bare.unchanged(&bare.clone()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListValidators {
+    /// Entity tag of the list, quotes included
+    #[serde(default)]
+    pub etag: Option<String>,
+
+    /// When the list was last modified
+    #[serde(default)]
+    pub last_modified: Option<DateTime<Utc>>,
+
+    /// Size of the list in bytes
+    #[serde(default)]
+    pub content_length: Option<u64>,
+}
+
+impl ListValidators {
+    /// Checks whether the validators can tell a changed list apart.
+    #[must_use]
+    pub fn is_usable(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Checks whether a list is unchanged since these validators were taken.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The validators the server gives now
+    #[must_use]
+    pub fn unchanged(&self, current: &ListValidators) -> bool {
+        let same = |a: Option<&str>, b: Option<&str>| a.zip(b).map(|(a, b)| a == b);
+        let etag = same(self.etag.as_deref(), current.etag.as_deref());
+        let modified = self
+            .last_modified
+            .zip(current.last_modified)
+            .map(|(a, b)| a == b);
+        let length = self
+            .content_length
+            .zip(current.content_length)
+            .is_none_or(|(a, b)| a == b);
+
+        length && etag.or(modified).unwrap_or(false) && modified.unwrap_or(true)
+    }
+}
 
 /// Represents a source of proxy servers.
 ///
@@ -100,6 +199,10 @@ pub struct Source {
     /// Number of times the source has failed
     pub failure_count: usize,
 
+    /// Number of failures since the last successful fetch
+    #[serde(default)]
+    pub consecutive_failures: usize,
+
     /// Last failure reason
     pub last_failure_reason: Option<String>,
 
@@ -111,6 +214,35 @@ pub struct Source {
 
     /// Number of proxies found from this source
     pub proxies_found: usize,
+
+    /// Number of failures recorded per failure reason
+    #[serde(default)]
+    pub failure_reasons: BTreeMap<String, usize>,
+
+    /// Number of failures recorded per HTTP status code
+    #[serde(default, with = "status_code_map")]
+    pub failure_codes: BTreeMap<u16, usize>,
+
+    /// Most recent failures, oldest first
+    #[serde(default)]
+    pub recent_failures: VecDeque<SourceFailure>,
+
+    /// Values substituted for `{name}` placeholders in the URL
+    #[serde(default)]
+    pub template_variables: BTreeMap<String, Vec<String>>,
+
+    /// Regex selecting the links to list files when the URL is an index page
+    #[serde(default)]
+    pub link_pattern: Option<String>,
+
+    /// What the server said about the list when it was last fetched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validators: Option<ListValidators>,
+
+    /// Further headers sent when fetching the source, such as a `Referer`,
+    /// as name and value pairs in sending order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<(String, String)>,
 }
 
 impl Source {
@@ -158,10 +290,18 @@ impl Source {
             last_used_at: None,
             use_count: 0,
             failure_count: 0,
+            consecutive_failures: 0,
             last_failure_reason: None,
             last_failure_code: None,
             parameters: HashMap::new(),
             proxies_found: 0,
+            failure_reasons: BTreeMap::new(),
+            failure_codes: BTreeMap::new(),
+            recent_failures: VecDeque::new(),
+            template_variables: BTreeMap::new(),
+            link_pattern: None,
+            validators: None,
+            headers: Vec::new(),
         })
     }
 
@@ -195,6 +335,35 @@ impl Source {
         self.parameters.insert(key, value);
     }
 
+    /// Adds a header sent when fetching the source.
+    ///
+    /// Some sites only hand out their lists to requests that seem to come
+    /// from their own pages. Headers are sent in the order they are added,
+    /// after the User-Agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The header name
+    /// * `value` - The header value
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use gooty_proxy::definitions::source::Source;
+    /// # let mut source = Source::new(
+    /// #    "https://example.com/proxies".to_string(),
+    /// #    "Mozilla/5.0".to_string(),
+    /// #    r"(\d+\.\d+\.\d+\.\d+:\d+)".to_string()
+    /// # ).unwrap();
+    /// source.add_header("Referer".to_string(), "https://example.com/".to_string());
+    /// source.add_header("Origin".to_string(), "https://example.com".to_string());
+    ///
+    /// assert_eq!(source.headers[0].0, "Referer");
+    /// ```
+    pub fn add_header(&mut self, name: String, value: String) {
+        self.headers.push((name, value));
+    }
+
     /// Removes a parameter from the source configuration.
     ///
     /// # Arguments
@@ -223,6 +392,98 @@ impl Source {
         self.parameters.remove(key)
     }
 
+    /// Adds a template variable to the source configuration.
+    ///
+    /// Every `{name}` placeholder in the source URL is replaced by each of the
+    /// values in turn, so a single source expands into one fetch per combination
+    /// of variable values. Values are inserted verbatim.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The placeholder name, without braces
+    /// * `values` - The values to substitute for the placeholder
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use gooty_proxy::definitions::source::Source;
+    /// # let mut source = Source::new(
+    /// #    "https://api.example.com/proxies?country={country}&page={page}".to_string(),
+    /// #    "Mozilla/5.0".to_string(),
+    /// #    r"(\d+\.\d+\.\d+\.\d+:\d+)".to_string()
+    /// # ).unwrap();
+    /// source.add_template_variable("country".to_string(), vec!["US".to_string(), "DE".to_string()]);
+    /// source.add_template_variable("page".to_string(), vec!["1".to_string(), "2".to_string()]);
+    ///
+    /// let urls = source.expand_urls().unwrap();
+    /// assert_eq!(urls.len(), 4);
+    /// assert!(urls.contains(&"https://api.example.com/proxies?country=DE&page=2".to_string()));
+    /// ```
+    pub fn add_template_variable(&mut self, name: String, values: Vec<String>) {
+        self.template_variables.insert(name, values);
+    }
+
+    /// Removes a template variable from the source configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The placeholder name, without braces
+    ///
+    /// # Returns
+    ///
+    /// The values of the variable if it was set, or `None` if it wasn't present
+    pub fn remove_template_variable(&mut self, name: &str) -> Option<Vec<String>> {
+        self.template_variables.remove(name)
+    }
+
+    /// Checks whether the source URL is a template.
+    ///
+    /// # Returns
+    ///
+    /// `true` if any template variable is configured.
+    #[must_use]
+    pub fn is_template(&self) -> bool {
+        !self.template_variables.is_empty()
+    }
+
+    /// Expands the source URL template into the URLs to fetch.
+    ///
+    /// Parameters are appended before substitution, so placeholders may also
+    /// appear in parameter values. Variables whose placeholder doesn't occur in
+    /// the URL are ignored.
+    ///
+    /// # Returns
+    ///
+    /// One URL per combination of variable values, or just the full URL if the
+    /// source isn't a template
+    ///
+    /// # Errors
+    ///
+    /// Returns `SourceError::InvalidTemplate` if a variable used in the URL has
+    /// no values.
+    pub fn expand_urls(&self) -> SourceResult<Vec<String>> {
+        let mut urls = vec![self.get_full_url()];
+
+        for (name, values) in &self.template_variables {
+            let placeholder = format!("{{{name}}}");
+            if !urls.first().is_some_and(|url| url.contains(&placeholder)) {
+                continue;
+            }
+            if values.is_empty() {
+                return Err(SourceError::InvalidTemplate(format!(
+                    "variable '{name}' has no values"
+                )));
+            }
+
+            urls = urls
+                .iter()
+                .flat_map(|url| values.iter().map(|value| url.replace(&placeholder, value)))
+                .collect();
+        }
+
+        Ok(urls)
+    }
+
     /// Records a successful use of the source.
     ///
     /// This method updates usage statistics by incrementing the use count
@@ -235,18 +496,142 @@ impl Source {
     /// Records a failure when using the source.
     ///
     /// This method updates failure statistics and records the reason
-    /// and optional status code for the failure.
+    /// and optional status code for the failure. Reasons and status codes are
+    /// aggregated over the lifetime of the source, and the most recent failures
+    /// are kept with their timestamps.
     ///
     /// # Arguments
     ///
     /// * `reason` - A description of why the source failed
     /// * `status_code` - Optional HTTP status code if the failure was related to an HTTP response
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use gooty_proxy::definitions::source::Source;
+    /// # let mut source = Source::new(
+    /// #    "https://example.com/proxies".to_string(),
+    /// #    "Mozilla/5.0".to_string(),
+    /// #    r"(\d+\.\d+\.\d+\.\d+:\d+)".to_string()
+    /// # ).unwrap();
+    /// source.record_failure("Rate limited".to_string(), Some(429));
+    /// source.record_failure("Rate limited".to_string(), Some(429));
+    ///
+    /// assert_eq!(source.failure_reasons["Rate limited"], 2);
+    /// assert_eq!(source.failure_codes[&429], 2);
+    /// assert_eq!(source.recent_failures.len(), 2);
+    /// ```
     pub fn record_failure(&mut self, reason: String, status_code: Option<u16>) {
         self.failure_count += 1;
+        self.consecutive_failures += 1;
+
+        *self.failure_reasons.entry(reason.clone()).or_insert(0) += 1;
+        if let Some(code) = status_code {
+            *self.failure_codes.entry(code).or_insert(0) += 1;
+        }
+
+        self.recent_failures.push_back(SourceFailure {
+            at: Utc::now(),
+            reason: reason.clone(),
+            status_code,
+        });
+        while self.recent_failures.len() > defaults::sources::MAX_RECENT_FAILURES {
+            self.recent_failures.pop_front();
+        }
+
         self.last_failure_reason = Some(reason);
         self.last_failure_code = status_code;
     }
 
+    /// Records a successful fetch from the source.
+    ///
+    /// Counts the use, adds the proxies found, and resets the failure streak.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxies_found` - Number of proxies extracted by the fetch
+    pub fn record_success(&mut self, proxies_found: usize) {
+        self.record_use();
+        self.proxies_found += proxies_found;
+        self.consecutive_failures = 0;
+    }
+
+    /// Records a failed fetch from the source.
+    ///
+    /// Counts the use and records the error's description and HTTP status code.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error the fetch failed with
+    pub fn record_fetch_failure(&mut self, error: &SourceError) {
+        self.record_use();
+        self.record_failure(error.to_string(), error.status_code());
+    }
+
+    /// Checks whether the source has failed too many times in a row to be used.
+    ///
+    /// # Returns
+    ///
+    /// `true` once the failure streak reaches `defaults::sources::BLACKLIST_THRESHOLD`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use gooty_proxy::definitions::{defaults, source::Source};
+    /// # let mut source = Source::new(
+    /// #    "https://example.com/proxies".to_string(),
+    /// #    "Mozilla/5.0".to_string(),
+    /// #    r"(\d+\.\d+\.\d+\.\d+:\d+)".to_string()
+    /// # ).unwrap();
+    /// for _ in 1..defaults::sources::BLACKLIST_THRESHOLD {
+    ///     source.record_failure("Connection refused".to_string(), None);
+    /// }
+    /// assert!(!source.is_blacklisted());
+    ///
+    /// source.record_failure("Connection refused".to_string(), None);
+    /// assert!(source.is_blacklisted());
+    ///
+    /// // A successful fetch ends the streak
+    /// source.record_success(10);
+    /// assert!(!source.is_blacklisted());
+    /// ```
+    #[must_use]
+    pub fn is_blacklisted(&self) -> bool {
+        self.consecutive_failures >= defaults::sources::BLACKLIST_THRESHOLD
+    }
+
+    /// Returns the current status of the source.
+    ///
+    /// # Returns
+    ///
+    /// `Blacklisted` once the failure streak reaches the threshold, `New` if the
+    /// source has never been used, and `Active` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use gooty_proxy::definitions::{enums::SourceStatus, source::Source};
+    /// # let mut source = Source::new(
+    /// #    "https://example.com/proxies".to_string(),
+    /// #    "Mozilla/5.0".to_string(),
+    /// #    r"(\d+\.\d+\.\d+\.\d+:\d+)".to_string()
+    /// # ).unwrap();
+    /// assert_eq!(source.status(), SourceStatus::New);
+    ///
+    /// source.record_success(3);
+    /// assert_eq!(source.status(), SourceStatus::Active);
+    /// ```
+    #[must_use]
+    pub fn status(&self) -> SourceStatus {
+        if self.is_blacklisted() {
+            SourceStatus::Blacklisted
+        } else if self.use_count == 0 {
+            SourceStatus::New
+        } else {
+            SourceStatus::Active
+        }
+    }
+
     /// Returns the success rate of using this source.
     ///
     /// The success rate is calculated as the ratio of successful uses
@@ -293,10 +678,71 @@ impl Source {
         }
     }
 
+    /// Makes the source URL an index page linking to the list files.
+    ///
+    /// Fetches then read the index, select the links matched by the pattern,
+    /// and fetch every linked file instead. A link is the pattern's first
+    /// capture group if it has one and the whole match otherwise, and relative
+    /// links are resolved against the index URL. At most
+    /// `defaults::sources::MAX_INDEX_LINKS` files are followed per fetch.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The regex pattern selecting links from the index page
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the pattern was valid and set successfully
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use gooty_proxy::definitions::source::Source;
+    /// # let mut source = Source::new(
+    /// #    "https://example.com/daily/".to_string(),
+    /// #    "Mozilla/5.0".to_string(),
+    /// #    r"(\d+\.\d+\.\d+\.\d+:\d+)".to_string()
+    /// # ).unwrap();
+    /// source.set_link_pattern(r#"href="([^"]+\.txt)""#.to_string()).unwrap();
+    /// assert!(source.is_index());
+    ///
+    /// assert!(source.set_link_pattern("(".to_string()).is_err());
+    /// ```
+    pub fn set_link_pattern(&mut self, pattern: String) -> Result<(), SourceError> {
+        if let Err(err) = utils::SerializableRegex::new(&pattern) {
+            return Err(SourceError::InvalidRegexPattern(err.to_string()));
+        }
+        self.link_pattern = Some(pattern);
+        Ok(())
+    }
+
+    /// Makes the source URL a list again rather than an index page.
+    ///
+    /// # Returns
+    ///
+    /// The link pattern if one was set, or `None` otherwise
+    pub fn remove_link_pattern(&mut self) -> Option<String> {
+        self.link_pattern.take()
+    }
+
+    /// Checks whether the source URL is an index page of list files.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a link pattern is configured.
+    #[must_use]
+    pub fn is_index(&self) -> bool {
+        self.link_pattern.is_some()
+    }
+
     /// Validates the source configuration.
     ///
     /// This method checks that the URL is well-formed and the
-    /// regex pattern is valid.
+    /// regex patterns are valid.
     ///
     /// # Returns
     ///
@@ -306,13 +752,29 @@ impl Source {
     ///
     /// Returns an error if:
     /// * The URL is invalid
-    /// * The regex pattern is invalid
+    /// * The URL template can't be expanded into valid URLs
+    /// * The regex pattern or link pattern is invalid
     pub fn validate(&self) -> Result<(), SourceError> {
         // Validate URL
         if !utils::is_valid_url(&self.url) {
             return Err(SourceError::InvalidUrl(self.url.clone()));
         }
 
+        // Validate every URL the template expands into
+        if let Some(url) = self
+            .expand_urls()?
+            .into_iter()
+            .find(|url| !utils::is_valid_url(url))
+        {
+            return Err(SourceError::InvalidUrl(url));
+        }
+
+        // Validate the link pattern of index sources
+        if let Some(pattern) = &self.link_pattern {
+            utils::SerializableRegex::new(pattern)
+                .map_err(|err| SourceError::InvalidRegexPattern(err.to_string()))?;
+        }
+
         // Validate regex by compiling it
         match utils::SerializableRegex::new(&self.regex_pattern) {
             Ok(_) => Ok(()),
@@ -369,7 +831,11 @@ impl Source {
     /// Fetches proxies from this source.
     ///
     /// Makes an HTTP request to the source URL and extracts proxies from
-    /// the response using the defined regex pattern.
+    /// the response using the defined regex pattern. Gzip and zip responses
+    /// are unpacked first, up to `defaults::sources::MAX_EXTRACTED_BYTES`. Template sources fetch
+    /// every expanded URL and combine the proxies found, dropping duplicates.
+    /// Index sources fetch every list file their index pages link to, and
+    /// attribute each proxy to the file it was found in.
     ///
     /// # Arguments
     ///
@@ -382,55 +848,239 @@ impl Source {
     /// # Errors
     ///
     /// This function will return an error if:
-    /// * The HTTP request fails
+    /// * The URL template can't be expanded
+    /// * The HTTP request fails for every expanded URL
+    /// * The source answers with a non-success HTTP status
+    /// * The response is a corrupt archive, or too large to download or extract
     /// * The regex pattern isn't compiled properly
     /// * The response can't be parsed
+    /// * The index pages of an index source link to no list files
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use flate2::{Compression, write::GzEncoder};
+    /// use gooty_proxy::definitions::source::Source;
+    /// use gooty_proxy::io::http::{Egress, Requestor};
+    /// use std::io::Write;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    ///     encoder.write_all(b"203.0.113.7:8080\n198.51.100.1:3128\n").unwrap();
+    ///     let list = encoder.finish().unwrap();
+    ///
+    ///     // A server publishing the list as a .txt.gz file
+    ///     let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let url = format!("http://{}/proxies.txt.gz", server.local_addr().unwrap());
+    ///     tokio::spawn(async move {
+    ///         let (mut stream, _) = server.accept().await.unwrap();
+    ///         let mut request = Vec::new();
+    ///         while !request.ends_with(b"\r\n\r\n") {
+    ///             request.push(stream.read_u8().await.unwrap());
+    ///         }
+    ///         let header = format!(
+    ///             "HTTP/1.1 200 OK\r\nContent-Type: application/gzip\r\nContent-Length: {}\r\n\r\n",
+    ///             list.len()
+    ///         );
+    ///         stream.write_all(header.as_bytes()).await.unwrap();
+    ///         stream.write_all(&list).await.unwrap();
+    ///     });
+    ///
+    ///     let source = Source::new(
+    ///         url,
+    ///         "Mozilla/5.0".to_string(),
+    ///         r"\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}:\d{2,5}".to_string(),
+    ///     )
+    ///     .unwrap();
+    ///     let requestor = Requestor::new().unwrap().with_egress(Egress::Direct).unwrap();
+    ///     let proxies = source.fetch_proxies(&requestor).await.unwrap();
+    ///     assert_eq!(proxies.len(), 2);
+    /// }
+    /// ```
+    ///
+    /// Sources are untrusted, and malformed responses fail with an error or
+    /// yield no proxies rather than panic:
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::errors::SourceError;
+    /// use gooty_proxy::definitions::source::Source;
+    /// use gooty_proxy::io::http::{Egress, Requestor};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let addr = server.local_addr().unwrap();
+    ///     tokio::spawn(async move {
+    ///         loop {
+    ///             let (mut stream, _) = server.accept().await.unwrap();
+    ///             tokio::spawn(async move {
+    ///                 let mut request = Vec::new();
+    ///                 while !request.ends_with(b"\r\n\r\n") {
+    ///                     request.push(stream.read_u8().await.unwrap());
+    ///                 }
+    ///                 let request = String::from_utf8_lossy(&request);
+    ///                 let (length, body): (usize, &[u8]) = if request.contains("/junk") {
+    ///                     let body = b"\xff\xfe999.1.1.1:80 1.2.3.4:99999 1.2.3.4:0 \xc3(\x80:80";
+    ///                     (body.len(), body)
+    ///                 } else if request.contains("/truncated.gz") {
+    ///                     (6, b"\x1f\x8b\x08\x00\x00\x00")
+    ///                 } else if request.contains("/corrupt.zip") {
+    ///                     (12, b"PK\x03\x04\xff\xff\xff\xff\xff\xff\xff\xff")
+    ///                 } else {
+    ///                     // Promises more than it sends
+    ///                     (4096, b"203.0.113.7:8080")
+    ///                 };
+    ///                 let header = format!("HTTP/1.1 200 OK\r\nContent-Length: {length}\r\n\r\n");
+    ///                 let _ = stream.write_all(header.as_bytes()).await;
+    ///                 let _ = stream.write_all(body).await;
+    ///             });
+    ///         }
+    ///     });
+    ///
+    ///     let requestor = Requestor::new().unwrap().with_egress(Egress::Direct).unwrap();
+    ///     let fetch = |path: &str| {
+    ///         let source = Source::new(
+    ///             format!("http://{addr}/{path}"),
+    ///             "Mozilla/5.0".to_string(),
+    ///             r"\S+:\d+".to_string(),
+    ///         )
+    ///         .unwrap();
+    ///         let requestor = requestor.clone();
+    ///         async move { source.fetch_proxies(&requestor).await }
+    ///     };
+    ///
+    ///     assert!(fetch("junk").await.unwrap().is_empty());
+    ///     assert!(matches!(fetch("truncated.gz").await, Err(SourceError::ArchiveError(_))));
+    ///     assert!(matches!(fetch("corrupt.zip").await, Err(SourceError::ArchiveError(_))));
+    ///     assert!(fetch("short").await.is_err());
+    /// }
+    /// ```
+    #[cfg(feature = "network")]
     pub async fn fetch_proxies(&self, requestor: &Requestor) -> SourceResult<Vec<Proxy>> {
-        let url = self.get_full_url();
-
-        // Make the HTTP request
-        let response = requestor
-            .get(&url, &self.user_agent)
+        self.fetch_proxies_with_response(requestor)
             .await
-            .map_err(|e| SourceError::FetchFailure(e.to_string()))?;
-
-        // Extract proxies using regex
-        let Some(regex) = &self.compiled_regex else {
-            return Err(SourceError::InvalidRegexPattern(
-                "Regex not compiled".to_string(),
-            ));
-        };
-
-        // Parse proxies from the response
-        let mut proxies = Vec::new();
-
-        // Use the SerializableRegex's find_iter method
-        let matches_iterator = regex.find_iter(&response);
+            .map(|(proxies, _)| proxies)
+    }
 
-        for match_result in matches_iterator {
-            // Each match is a Result that needs to be handled
-            match match_result {
-                Ok(m) => {
-                    let proxy_str = m.as_str();
+    /// Fetches proxies unless the list is unchanged since the last fetch.
+    ///
+    /// Before downloading the list, a HEAD request asks for its entity tag,
+    /// modification time, and size. If they match those of the last fetch,
+    /// the download is skipped, since the proxies listed are already known.
+    /// Servers that give neither an entity tag nor a modification time are
+    /// asked once and then always downloaded from. Template and index
+    /// sources, which span several files, are always downloaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `requestor` - The HTTP client to use for making requests
+    ///
+    /// # Returns
+    ///
+    /// The proxies found, or None if the list is unchanged
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the list is downloaded and `fetch_proxies` fails.
+    /// A failed HEAD request only means the list is downloaded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::source::Source;
+    /// use gooty_proxy::io::http::{Egress, Requestor};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // A server publishing a list that never changes
+    ///     let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let url = format!("http://{}/http.txt", server.local_addr().unwrap());
+    ///     let (gets, mut counted) = tokio::sync::mpsc::unbounded_channel();
+    ///     tokio::spawn(async move {
+    ///         loop {
+    ///             let (mut stream, _) = server.accept().await.unwrap();
+    ///             let mut request = Vec::new();
+    ///             while !request.ends_with(b"\r\n\r\n") {
+    ///                 request.push(stream.read_u8().await.unwrap());
+    ///             }
+    ///             let body = "203.0.113.7:8080";
+    ///             let header = format!(
+    ///                 "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+    ///                 body.len()
+    ///             );
+    ///             stream.write_all(header.as_bytes()).await.unwrap();
+    ///             if request.starts_with(b"GET") {
+    ///                 gets.send(()).unwrap();
+    ///                 stream.write_all(body.as_bytes()).await.unwrap();
+    ///             }
+    ///         }
+    ///     });
+    ///
+    ///     let mut source = Source::new(
+    ///         url,
+    ///         "Mozilla/5.0".to_string(),
+    ///         r"\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}:\d{2,5}".to_string(),
+    ///     )
+    ///     .unwrap();
+    ///     let requestor = Requestor::new().unwrap().with_egress(Egress::Direct).unwrap();
+    ///     let first = source.fetch_changed_proxies(&requestor).await.unwrap();
+    ///     assert_eq!(first.map(|proxies| proxies.len()), Some(1));
+    ///
+    ///     // The second fetch stops at the HEAD request
+    ///     assert!(source.fetch_changed_proxies(&requestor).await.unwrap().is_none());
+    ///     counted.recv().await.unwrap();
+    ///     assert!(counted.try_recv().is_err());
+    /// }
+    /// ```
+    #[cfg(feature = "network")]
+    pub async fn fetch_changed_proxies(
+        &mut self,
+        requestor: &Requestor,
+    ) -> SourceResult<Option<Vec<Proxy>>> {
+        let single_file = self.link_pattern.is_none() && !self.is_template();
+        let asked_before = self.validators.as_ref();
+        if !single_file || asked_before.is_some_and(|stored| !stored.is_usable()) {
+            return self.fetch_proxies(requestor).await.map(Some);
+        }
 
-                    // Try to parse the proxy string
-                    if let Some(proxy) = Self::parse_proxy(proxy_str) {
-                        proxies.push(proxy);
-                    }
-                }
-                Err(e) => {
-                    return Err(SourceError::ParseError(e.to_string()));
-                }
+        let current = match requestor.head(&self.url, &self.user_agent).await {
+            Ok(head) if head.status.is_success() => ListValidators {
+                etag: head.etag().map(str::to_string),
+                last_modified: head.last_modified(),
+                content_length: head.content_length(),
+            },
+            Ok(head) => {
+                log::debug!("HEAD {} answered {}, downloading", self.url, head.status);
+                ListValidators::default()
+            }
+            Err(e) => {
+                log::debug!("HEAD {} failed, downloading: {e}", self.url);
+                self.validators = None;
+                return self.fetch_proxies(requestor).await.map(Some);
             }
+        };
+        if asked_before.is_some_and(|stored| stored.unchanged(&current)) {
+            return Ok(None);
         }
 
-        Ok(proxies)
+        let proxies = self.fetch_proxies(requestor).await?;
+        self.validators = Some(current);
+        Ok(Some(proxies))
     }
 
     /// Fetches proxies and returns both the proxies and raw response.
     ///
     /// Similar to `fetch_proxies` but also returns the raw response text,
-    /// which can be useful for debugging or further processing.
+    /// which can be useful for debugging or further processing. For template
+    /// sources the responses of all expanded URLs are joined by newlines, and
+    /// for index sources the responses of all linked files.
     ///
     /// # Arguments
     ///
@@ -445,20 +1095,224 @@ impl Source {
     /// # Errors
     ///
     /// This function will return an error if:
-    /// * The HTTP request fails
+    /// * The URL template can't be expanded
+    /// * The HTTP request fails for every expanded URL
+    /// * The source answers with a non-success HTTP status
+    /// * The response is a corrupt archive, or too large to download or extract
     /// * The regex pattern isn't compiled properly
     /// * The response can't be parsed
+    /// * The index pages of an index source link to no list files
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::source::Source;
+    /// use gooty_proxy::io::http::{Egress, Requestor};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // A server publishing one list file per day and an index linking to them
+    ///     let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let url = format!("http://{}/daily/", server.local_addr().unwrap());
+    ///     tokio::spawn(async move {
+    ///         loop {
+    ///             let (mut stream, _) = server.accept().await.unwrap();
+    ///             let mut request = Vec::new();
+    ///             while !request.ends_with(b"\r\n\r\n") {
+    ///                 request.push(stream.read_u8().await.unwrap());
+    ///             }
+    ///             let request = String::from_utf8(request).unwrap();
+    ///             let body = match request.split(' ').nth(1).unwrap() {
+    ///                 "/daily/" => {
+    ///                     r#"<a href="2026-10-15.txt">15</a> <a href="/daily/2026-10-16.txt">16</a>"#
+    ///                 }
+    ///                 "/daily/2026-10-15.txt" => "203.0.113.7:8080\n198.51.100.1:3128",
+    ///                 _ => "198.51.100.1:3128\n192.0.2.44:80",
+    ///             };
+    ///             let response = format!(
+    ///                 "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+    ///                 body.len()
+    ///             );
+    ///             stream.write_all(response.as_bytes()).await.unwrap();
+    ///         }
+    ///     });
+    ///
+    ///     let mut source = Source::new(
+    ///         url.clone(),
+    ///         "Mozilla/5.0".to_string(),
+    ///         r"\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}:\d{2,5}".to_string(),
+    ///     )
+    ///     .unwrap();
+    ///     source.set_link_pattern(r#"href="([^"]+\.txt)""#.to_string()).unwrap();
+    ///
+    ///     let requestor = Requestor::new().unwrap().with_egress(Egress::Direct).unwrap();
+    ///     let (proxies, _) = source.fetch_proxies_with_response(&requestor).await.unwrap();
+    ///
+    ///     // Proxies listed on both days are kept once, attributed to the first file
+    ///     assert_eq!(proxies.len(), 3);
+    ///     assert_eq!(proxies[1].source, Some(format!("{url}2026-10-15.txt")));
+    ///     assert_eq!(proxies[2].source, Some(format!("{url}2026-10-16.txt")));
+    /// }
+    /// ```
+    #[cfg(feature = "network")]
     pub async fn fetch_proxies_with_response(
         &self,
         requestor: &Requestor,
     ) -> SourceResult<(Vec<Proxy>, String)> {
-        let url = self.get_full_url();
+        let urls = self.expand_urls()?;
+        if let Some(pattern) = &self.link_pattern {
+            let files = self.fetch_index_links(requestor, &urls, pattern).await?;
+            return self.fetch_urls(requestor, &files).await;
+        }
 
-        // Make the HTTP request
-        let response = requestor
-            .get(&url, &self.user_agent)
-            .await
-            .map_err(|e| SourceError::FetchFailure(e.to_string()))?;
+        self.fetch_urls(requestor, &urls).await
+    }
+
+    /// Fetches the index pages of the source and selects the list files they link to.
+    ///
+    /// Links are resolved against their index URL and deduplicated, and only
+    /// the first `defaults::sources::MAX_INDEX_LINKS` are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `requestor` - The HTTP client to use for making requests
+    /// * `urls` - The URLs of the index pages
+    /// * `pattern` - The regex pattern selecting links from the index pages
+    ///
+    /// # Returns
+    ///
+    /// The URLs of the linked list files
+    #[cfg(feature = "network")]
+    async fn fetch_index_links(
+        &self,
+        requestor: &Requestor,
+        urls: &[String],
+        pattern: &str,
+    ) -> SourceResult<Vec<String>> {
+        let regex = utils::SerializableRegex::new(pattern)
+            .map_err(|err| SourceError::InvalidRegexPattern(err.to_string()))?;
+
+        let mut files = Vec::new();
+        let mut seen = HashSet::new();
+        let mut first_error = None;
+
+        for url in urls {
+            let index = match self.fetch_text(requestor, url).await {
+                Ok(index) => index,
+                Err(e) => {
+                    log::warn!("Failed to fetch index page {url}: {e}");
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+            let Ok(base) = Url::parse(url) else {
+                first_error.get_or_insert(SourceError::InvalidUrl(url.clone()));
+                continue;
+            };
+
+            for captures in regex.regex().captures_iter(&index) {
+                let captures = captures.map_err(|e| SourceError::ParseError(e.to_string()))?;
+                let Some(link) = captures.get(1).or_else(|| captures.get(0)) else {
+                    continue;
+                };
+                match base.join(link.as_str()) {
+                    Ok(file) if seen.insert(file.to_string()) => files.push(file.into()),
+                    Ok(_) => {}
+                    Err(e) => log::debug!("Skipping link {} on {url}: {e}", link.as_str()),
+                }
+            }
+        }
+
+        if files.len() > defaults::sources::MAX_INDEX_LINKS {
+            log::warn!(
+                "Index of {} links to {} list files, following the first {}",
+                self.url,
+                files.len(),
+                defaults::sources::MAX_INDEX_LINKS
+            );
+            files.truncate(defaults::sources::MAX_INDEX_LINKS);
+        }
+
+        match first_error {
+            Some(e) if files.is_empty() => Err(e),
+            _ if files.is_empty() => Err(SourceError::ParseError(
+                "index page links to no list files".to_string(),
+            )),
+            _ => Ok(files),
+        }
+    }
+
+    /// Fetches a set of URLs and combines the proxies found, dropping duplicates.
+    ///
+    /// # Arguments
+    ///
+    /// * `requestor` - The HTTP client to use for making requests
+    /// * `urls` - The URLs to fetch
+    ///
+    /// # Returns
+    ///
+    /// The proxies found and the raw responses joined by newlines, or the
+    /// first error if every URL failed
+    #[cfg(feature = "network")]
+    async fn fetch_urls(
+        &self,
+        requestor: &Requestor,
+        urls: &[String],
+    ) -> SourceResult<(Vec<Proxy>, String)> {
+        if let [url] = urls {
+            return self.fetch_url(requestor, url).await;
+        }
+
+        let mut proxies = Vec::new();
+        let mut responses = Vec::new();
+        let mut seen = HashSet::new();
+        let mut first_error = None;
+
+        for url in urls {
+            match self.fetch_url(requestor, url).await {
+                Ok((found, response)) => {
+                    proxies.extend(
+                        found
+                            .into_iter()
+                            .filter(|p| seen.insert((p.address, p.port))),
+                    );
+                    responses.push(response);
+                }
+                Err(e) => {
+                    log::warn!("Failed to fetch source URL {url}: {e}");
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        match first_error {
+            Some(e) if responses.is_empty() => Err(e),
+            _ => Ok((proxies, responses.join("\n"))),
+        }
+    }
+
+    /// Fetches a single URL and extracts proxies from the response.
+    ///
+    /// # Arguments
+    ///
+    /// * `requestor` - The HTTP client to use for making requests
+    /// * `url` - The URL to fetch
+    ///
+    /// # Returns
+    ///
+    /// The proxies found and the raw response text
+    #[cfg(feature = "network")]
+    async fn fetch_url(
+        &self,
+        requestor: &Requestor,
+        url: &str,
+    ) -> SourceResult<(Vec<Proxy>, String)> {
+        let response = self.fetch_text(requestor, url).await?;
+
+        // Proxies of index sources are attributed to the list file they're in
+        let attribution = if self.is_index() { url } else { &self.url };
 
         // Extract proxies using regex
         let Some(regex) = &self.compiled_regex else {
@@ -477,7 +1331,7 @@ impl Source {
                 Ok(m) => {
                     let proxy_str = m.as_str();
                     if let Some(proxy) = Self::parse_proxy(proxy_str) {
-                        proxies.push(proxy);
+                        proxies.push(proxy.with_source(attribution));
                     }
                 }
                 Err(e) => {
@@ -489,6 +1343,35 @@ impl Source {
         Ok((proxies, response))
     }
 
+    /// Fetches a single URL and returns the text of its response.
+    ///
+    /// # Arguments
+    ///
+    /// * `requestor` - The HTTP client to use for making requests
+    /// * `url` - The URL to fetch
+    ///
+    /// # Returns
+    ///
+    /// The response text, unpacked if it was compressed or archived
+    #[cfg(feature = "network")]
+    async fn fetch_text(&self, requestor: &Requestor, url: &str) -> SourceResult<String> {
+        // Make the HTTP request, unpacking compressed lists and archives
+        let profile = RequestProfile {
+            name: "source".to_string(),
+            user_agent: self.user_agent.clone(),
+            headers: self.headers.clone(),
+        };
+        let (head, body) = requestor
+            .get_bytes_with_profile(url, &profile, defaults::sources::MAX_DOWNLOAD_BYTES)
+            .await
+            .map_err(SourceError::from)?;
+        archive::extract_text(
+            &body,
+            head.header("content-encoding"),
+            defaults::sources::MAX_EXTRACTED_BYTES,
+        )
+    }
+
     /// Parse a proxy from a string match.
     ///
     /// Attempts to parse a string like "127.0.0.1:8080" into a Proxy object.
@@ -501,10 +1384,11 @@ impl Source {
     /// # Returns
     ///
     /// Some(Proxy) if parsing succeeds, None otherwise
+    #[cfg(feature = "network")]
     fn parse_proxy(proxy_str: &str) -> Option<Proxy> {
         // Simple IP:PORT parsing
         if let Some((ip_str, port_str)) = proxy_str.split_once(':') {
-            if let (Ok(ip), Ok(port)) = (IpAddr::from_str(ip_str), port_str.parse::<u16>()) {
+            if let (Ok(ip), Ok(port)) = (IpAddr::from_str(ip_str), port_str.parse::<Port>()) {
                 // Default to HTTP proxy type if not specified
                 return Some(Proxy::new(
                     ProxyType::Http,
@@ -560,3 +1444,29 @@ impl Source {
         Ok(source)
     }
 }
+
+/// Serializes maps keyed by HTTP status code with string keys, as TOML requires.
+mod status_code_map {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(
+        map: &BTreeMap<u16, usize>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(map.iter().map(|(code, count)| (code.to_string(), count)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<u16, usize>, D::Error> {
+        BTreeMap::<String, usize>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(code, count)| {
+                code.parse()
+                    .map(|code| (code, count))
+                    .map_err(D::Error::custom)
+            })
+            .collect()
+    }
+}
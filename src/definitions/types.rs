@@ -0,0 +1,184 @@
+//! # Types Module
+//!
+//! This module provides validated value types for fields that plain integers
+//! would let take invalid values.
+//!
+//! ## Components
+//!
+//! * **`Port`** - A port number, never zero
+//! * **`LatencyMs`** - A measured latency in whole milliseconds, never zero
+//!
+//! Both serialize as plain integers, so stored proxies keep their format.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::types::{LatencyMs, Port};
+//! use std::time::Duration;
+//!
+//! let port = Port::new(8080).unwrap();
+//! assert_eq!(port, 8080);
+//! assert!(Port::new(0).is_none());
+//!
+//! // Sub-millisecond latencies round up to a millisecond
+//! assert_eq!(LatencyMs::from(Duration::from_micros(300)).get(), 1);
+//! assert_eq!(LatencyMs::from(250).to_string(), "250ms");
+//! ```
+
+use crate::definitions::errors::ProxyError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::num::{NonZeroU16, NonZeroU128};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A port number, never zero
+///
+/// Parses from and serializes to a plain `u16`.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::types::Port;
+///
+/// let port: Port = "1080".parse().unwrap();
+/// assert_eq!(port.get(), 1080);
+/// assert!("0".parse::<Port>().is_err());
+/// assert!(Port::try_from(0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u16", into = "u16")]
+pub struct Port(NonZeroU16);
+
+impl Port {
+    /// Creates a port, or None for port zero
+    #[must_use]
+    pub const fn new(port: u16) -> Option<Self> {
+        match NonZeroU16::new(port) {
+            Some(port) => Some(Port(port)),
+            None => None,
+        }
+    }
+
+    /// Returns the port number
+    #[must_use]
+    pub const fn get(self) -> u16 {
+        self.0.get()
+    }
+}
+
+impl TryFrom<u16> for Port {
+    type Error = ProxyError;
+
+    fn try_from(port: u16) -> Result<Self, Self::Error> {
+        Port::new(port).ok_or(ProxyError::InvalidPort(port))
+    }
+}
+
+impl From<Port> for u16 {
+    fn from(port: Port) -> Self {
+        port.get()
+    }
+}
+
+impl PartialEq<u16> for Port {
+    fn eq(&self, other: &u16) -> bool {
+        self.get() == *other
+    }
+}
+
+impl fmt::Display for Port {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Port {
+    type Err = String;
+
+    /// Parses a port number
+    ///
+    /// # Errors
+    ///
+    /// Returns an error string if the text is not a number between 1 and 65535
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .parse::<u16>()
+            .ok()
+            .and_then(Port::new)
+            .ok_or_else(|| format!("Invalid port number: {s}"))
+    }
+}
+
+/// A measured latency in whole milliseconds, never zero
+///
+/// Latencies below a millisecond count as one millisecond, so a measured
+/// latency is always told apart from a missing one. Serializes to a plain
+/// `u128`, and stored latencies of zero load as one millisecond.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::types::LatencyMs;
+/// use std::time::Duration;
+///
+/// assert_eq!(LatencyMs::from(0).get(), 1);
+/// assert_eq!(LatencyMs::from(Duration::from_millis(1500)).get(), 1500);
+/// assert!(LatencyMs::from(100) < LatencyMs::from(200));
+/// assert_eq!(LatencyMs::average([LatencyMs::from(100), LatencyMs::from(200)]), Some(LatencyMs::from(150)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "u128", into = "u128")]
+pub struct LatencyMs(NonZeroU128);
+
+impl LatencyMs {
+    /// Returns the latency in milliseconds
+    #[must_use]
+    pub const fn get(self) -> u128 {
+        self.0.get()
+    }
+
+    /// Returns the latency as a duration
+    #[must_use]
+    pub fn as_duration(self) -> Duration {
+        Duration::from_millis(u64::try_from(self.get()).unwrap_or(u64::MAX))
+    }
+
+    /// Averages latencies
+    ///
+    /// # Returns
+    ///
+    /// The mean latency, or None if there are no latencies
+    pub fn average(latencies: impl IntoIterator<Item = LatencyMs>) -> Option<LatencyMs> {
+        let (sum, count) = latencies
+            .into_iter()
+            .fold((0_u128, 0_u128), |(sum, count), latency| {
+                (sum.saturating_add(latency.get()), count + 1)
+            });
+        sum.checked_div(count).map(LatencyMs::from)
+    }
+}
+
+impl From<u128> for LatencyMs {
+    fn from(millis: u128) -> Self {
+        LatencyMs(NonZeroU128::new(millis).unwrap_or(NonZeroU128::MIN))
+    }
+}
+
+impl From<Duration> for LatencyMs {
+    fn from(duration: Duration) -> Self {
+        LatencyMs::from(duration.as_micros().div_ceil(1000))
+    }
+}
+
+impl From<LatencyMs> for u128 {
+    fn from(latency: LatencyMs) -> Self {
+        latency.get()
+    }
+}
+
+impl fmt::Display for LatencyMs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}ms", self.0)
+    }
+}
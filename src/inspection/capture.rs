@@ -0,0 +1,186 @@
+//! # Response Capture Module
+//!
+//! This module keeps the raw responses of external services for debugging.
+//!
+//! ## Components
+//!
+//! * **`ResponseCapture`** - A ring buffer of the most recent responses
+//! * **`CapturedResponse`** - A single response with where it came from
+//!
+//! When a proxy is judged with the wrong anonymity level or an address is
+//! attributed to the wrong network, the response that led to it is usually
+//! the quickest way to the cause. A capture shared by the judge and the
+//! sleuth keeps the last judge bodies and ipinfo.io JSON documents in memory,
+//! so they can be inspected without a packet capture. Capturing is disabled
+//! until the capture is given a capacity.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::inspection::capture::ResponseCapture;
+//!
+//! let capture = ResponseCapture::new(2);
+//! capture.record("judge", "http://judge.example/", "http://10.0.0.1:8080", Some(200), "REMOTE_ADDR = 10.0.0.1");
+//! capture.record("ipinfo", "https://ipinfo.io/10.0.0.1/json", "10.0.0.1", Some(200), "{}");
+//! capture.record("ipinfo", "https://ipinfo.io/10.0.0.2/json", "10.0.0.2", Some(429), "");
+//!
+//! // Only the two most recent responses are kept, oldest first
+//! let responses = capture.recent();
+//! assert_eq!(responses.len(), 2);
+//! assert_eq!(responses[0].subject, "10.0.0.1");
+//! assert_eq!(responses[1].status, Some(429));
+//! ```
+
+use crate::definitions::defaults;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A raw response of an external service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapturedResponse {
+    /// The service that responded, such as `judge` or `ipinfo`
+    pub provider: String,
+
+    /// The URL that was requested
+    pub url: String,
+
+    /// The proxy or address the request was about
+    pub subject: String,
+
+    /// HTTP status code of the response, if one was received
+    pub status: Option<u16>,
+
+    /// The response body, cut off at `capture::MAX_BODY_BYTES`
+    pub body: String,
+
+    /// When the response was received
+    pub captured_at: DateTime<Utc>,
+}
+
+/// Keeps the most recent raw responses of external services
+///
+/// The capture is shared through an `Arc` by everything that records into it,
+/// and its capacity can be changed while it is shared. A capacity of zero
+/// disables capturing.
+#[derive(Debug, Default)]
+pub struct ResponseCapture {
+    /// Number of responses kept
+    capacity: AtomicUsize,
+
+    /// The kept responses, oldest first
+    responses: Mutex<VecDeque<CapturedResponse>>,
+}
+
+impl ResponseCapture {
+    /// Create a capture keeping the given number of responses
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Number of responses kept, zero to disable capturing
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: AtomicUsize::new(capacity),
+            responses: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Get the number of responses kept
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::Relaxed)
+    }
+
+    /// Change the number of responses kept, dropping the oldest ones over it
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Number of responses kept, zero to disable capturing
+    pub fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+        let mut responses = self.lock();
+        while responses.len() > capacity {
+            responses.pop_front();
+        }
+    }
+
+    /// Check whether responses are being captured
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.capacity() > 0
+    }
+
+    /// Record a response, dropping the oldest one if the capture is full
+    ///
+    /// Does nothing while capturing is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The service that responded
+    /// * `url` - The URL that was requested
+    /// * `subject` - The proxy or address the request was about
+    /// * `status` - HTTP status code of the response, if one was received
+    /// * `body` - The response body
+    pub fn record(
+        &self,
+        provider: &str,
+        url: &str,
+        subject: &str,
+        status: Option<u16>,
+        body: &str,
+    ) {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return;
+        }
+
+        let response = CapturedResponse {
+            provider: provider.to_string(),
+            url: url.to_string(),
+            subject: subject.to_string(),
+            status,
+            body: truncate(body, defaults::capture::MAX_BODY_BYTES).to_string(),
+            captured_at: Utc::now(),
+        };
+
+        let mut responses = self.lock();
+        while responses.len() >= capacity {
+            responses.pop_front();
+        }
+        responses.push_back(response);
+    }
+
+    /// Get the kept responses, oldest first
+    #[must_use]
+    pub fn recent(&self) -> Vec<CapturedResponse> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Drop all kept responses
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Locks the responses, recovering them if a holder panicked
+    fn lock(&self) -> MutexGuard<'_, VecDeque<CapturedResponse>> {
+        self.responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Cut a string off at a byte length, on a character boundary
+fn truncate(text: &str, max_bytes: usize) -> &str {
+    if text.len() <= max_bytes {
+        return text;
+    }
+
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
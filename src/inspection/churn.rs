@@ -0,0 +1,204 @@
+//! # Churn Module
+//!
+//! This module measures how long proxies keep working after they are found.
+//!
+//! ## Components
+//!
+//! * **`SurvivalStats`** - Lifetimes of a group of proxies and the share surviving to given ages
+//! * **`survival`** - Computes survival statistics of a group of proxies
+//! * **`survival_by`** - Computes survival statistics per source, country or other key
+//!
+//! A proxy is born when it is first found and dies when it is quarantined or
+//! retired, at the time it last passed a check. Proxies that still work have
+//! not died yet, so their lifetimes so far only put a lower bound on how long
+//! they live. The survival curve is therefore a Kaplan-Meier estimate, which
+//! counts such proxies as at risk up to their current age instead of treating
+//! them as dead. Proxies that never passed a check have no lifetime and are
+//! counted separately.
+//!
+//! ## Examples
+//!
+//! ```
+//! use chrono::Duration;
+//! use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+//! use gooty_proxy::definitions::proxy::Proxy;
+//! use gooty_proxy::definitions::types::Port;
+//! use gooty_proxy::inspection::churn;
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! let mut proxies: Vec<Proxy> = (8080..8084)
+//!     .map(|port| Proxy::new(ProxyType::Http, IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), Port::new(port).unwrap(), AnonymityLevel::Elite))
+//!     .collect();
+//!
+//! // Two proxies worked for two hours before dying, one still works, and
+//! // one never worked at all
+//! for proxy in &mut proxies[..3] {
+//!     proxy.record_check(100);
+//!     proxy.added_at = proxy.added_at.map(|at| at - Duration::hours(2));
+//! }
+//! for proxy in &mut proxies[..2] {
+//!     proxy.retire();
+//! }
+//! proxies[3].record_check_failure();
+//!
+//! let stats = churn::survival(&proxies, &[Duration::hours(1), Duration::hours(3)]);
+//! assert_eq!((stats.alive, stats.dead, stats.never_alive), (1, 2, 1));
+//! assert_eq!(stats.survival[0], (Duration::hours(1), 1.0));
+//! assert!(stats.survival[1].1 < 0.5);
+//! assert!(stats.median_lifetime.is_some());
+//! ```
+
+use crate::definitions::{enums::ProxyLifecycle, proxy::Proxy};
+use chrono::Duration;
+use std::collections::BTreeMap;
+
+/// Lifetimes of a group of proxies
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SurvivalStats {
+    /// Number of proxies in the group
+    pub total: usize,
+
+    /// Number of proxies that passed a check and still work
+    pub alive: usize,
+
+    /// Number of proxies that passed a check and have since died
+    pub dead: usize,
+
+    /// Number of proxies that never passed a check
+    pub never_alive: usize,
+
+    /// Age by which half the proxies have died, if half of them have
+    pub median_lifetime: Option<Duration>,
+
+    /// Estimated share of proxies still working at each age, between 0 and 1
+    pub survival: Vec<(Duration, f64)>,
+}
+
+impl SurvivalStats {
+    /// Returns the share of proxies that died, among those that ever worked
+    #[must_use]
+    pub fn churn_rate(&self) -> Option<f64> {
+        let observed = self.alive + self.dead;
+        #[allow(clippy::cast_precision_loss)]
+        (observed > 0).then(|| self.dead as f64 / observed as f64)
+    }
+}
+
+/// Computes survival statistics of a group of proxies
+///
+/// Proxies stored without the time they were added count as alive or dead,
+/// but their unknown lifetimes are left out of the survival curve.
+///
+/// # Arguments
+///
+/// * `proxies` - The proxies of the group
+/// * `horizons` - Ages at which the share of surviving proxies is estimated
+///
+/// # Returns
+///
+/// The survival statistics of the group, with one survival entry per horizon
+#[must_use]
+pub fn survival<'a>(
+    proxies: impl IntoIterator<Item = &'a Proxy>,
+    horizons: &[Duration],
+) -> SurvivalStats {
+    let mut stats = SurvivalStats::default();
+    let mut lifetimes = Vec::new();
+
+    for proxy in proxies {
+        stats.total += 1;
+        let Some(last_alive) = proxy.last_alive_at() else {
+            stats.never_alive += 1;
+            continue;
+        };
+
+        let died = matches!(
+            proxy.effective_lifecycle(),
+            ProxyLifecycle::Quarantined | ProxyLifecycle::Retired
+        );
+        if died {
+            stats.dead += 1;
+        } else {
+            stats.alive += 1;
+        }
+        // Without the time it was found, the lifetime of a proxy is unknown
+        if let Some(added_at) = proxy.added_at {
+            lifetimes.push(((last_alive - added_at).max(Duration::zero()), died));
+        }
+    }
+
+    let curve = kaplan_meier(lifetimes);
+    stats.median_lifetime = curve
+        .iter()
+        .find(|(_, surviving)| *surviving <= 0.5)
+        .map(|(age, _)| *age);
+    stats.survival = horizons
+        .iter()
+        .map(|horizon| {
+            let surviving = curve
+                .iter()
+                .take_while(|(age, _)| age <= horizon)
+                .last()
+                .map_or(1.0, |(_, surviving)| *surviving);
+            (*horizon, surviving)
+        })
+        .collect();
+    stats
+}
+
+/// Computes survival statistics per group of proxies
+///
+/// # Arguments
+///
+/// * `proxies` - The proxies to group
+/// * `key` - Returns the group of a proxy, or `None` to leave it out
+/// * `horizons` - Ages at which the share of surviving proxies is estimated
+///
+/// # Returns
+///
+/// The survival statistics of each group, by key
+pub fn survival_by<'a, K: Ord>(
+    proxies: impl IntoIterator<Item = &'a Proxy>,
+    key: impl Fn(&Proxy) -> Option<K>,
+    horizons: &[Duration],
+) -> BTreeMap<K, SurvivalStats> {
+    let mut groups: BTreeMap<K, Vec<&Proxy>> = BTreeMap::new();
+    for proxy in proxies {
+        if let Some(group) = key(proxy) {
+            groups.entry(group).or_default().push(proxy);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(group, proxies)| (group, survival(proxies, horizons)))
+        .collect()
+}
+
+/// Estimates the survival curve of observed lifetimes
+///
+/// Each lifetime is paired with whether the proxy died at its end, or was
+/// still alive. The curve holds the estimated share of survivors right after
+/// each age at which a proxy died, youngest first.
+fn kaplan_meier(mut lifetimes: Vec<(Duration, bool)>) -> Vec<(Duration, f64)> {
+    lifetimes.sort_by_key(|(age, _)| *age);
+
+    let mut curve = Vec::new();
+    let mut surviving = 1.0;
+    let mut at_risk = lifetimes.len();
+    for same_age in lifetimes.chunk_by(|(a, _), (b, _)| a == b) {
+        let Some(&(age, _)) = same_age.first() else {
+            continue;
+        };
+        let deaths = same_age.iter().filter(|(_, died)| *died).count();
+
+        if deaths > 0 {
+            #[allow(clippy::cast_precision_loss)]
+            let share_dying = deaths as f64 / at_risk as f64;
+            surviving *= 1.0 - share_dying;
+            curve.push((age, surviving));
+        }
+        at_risk -= same_age.len();
+    }
+    curve
+}
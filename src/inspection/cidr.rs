@@ -13,6 +13,12 @@
 //! - Calculate the number of addresses in a CIDR block
 //! - Determine network address, broadcast address, and valid IP ranges
 //! - Convert between different representations of network blocks
+//! - Test addresses against large sets of blocks with `CidrSet`
+//! - Decide which addresses are worth looking up at metadata providers
+//!
+//! IPv4 and IPv6 are handled alike. An IPv4-mapped IPv6 address such as
+//! `::ffff:192.0.2.1` is treated as the IPv4 address it carries, so it
+//! matches IPv4 blocks and is looked up as IPv4.
 //!
 //! ## Examples
 //!
@@ -30,6 +36,29 @@
 //! // Get the network address and prefix length
 //! println!("Network: {}, Prefix: {}", cidr.network(), cidr.prefix_len());
 //! ```
+//!
+//! IPv6 proxies go through the same checks:
+//!
+//! ```
+//! use gooty_proxy::definitions::proxy::Proxy;
+//! use gooty_proxy::inspection::cidr::{CidrSet, helpers};
+//!
+//! let proxy: Proxy = "socks5://[2606:4700:10::6816:1]:1080".parse().unwrap();
+//! let ranges = CidrSet::from_strs(["2606:4700::/32", "104.16.0.0/13"]).unwrap();
+//! assert_eq!(ranges.longest_match(&proxy.address), Some(32));
+//! assert_eq!(helpers::lookup_address(&proxy.address), Some(proxy.address));
+//!
+//! // A proxy on an IPv4-mapped address is matched and looked up as IPv4
+//! let proxy: Proxy = "http://[::ffff:104.16.1.1]:8080".parse().unwrap();
+//! assert!(ranges.contains(&proxy.address));
+//! assert_eq!(
+//!     helpers::lookup_address(&proxy.address),
+//!     Some("104.16.1.1".parse().unwrap())
+//! );
+//! ```
+
+// The trie only follows node indices it handed out itself
+#![allow(clippy::indexing_slicing)]
 
 use crate::definitions::errors::{CidrError, CidrResult};
 use std::net::IpAddr;
@@ -152,56 +181,52 @@ impl Cidr {
     ///
     /// assert!(cidr.contains(&ip_in));
     /// assert!(!cidr.contains(&ip_out));
+    ///
+    /// // IPv6 blocks, IPv4-mapped addresses, and zero-length prefixes
+    /// let cidr = Cidr::to_cidr("2001:db8:abcd::/48").unwrap();
+    /// assert!(cidr.contains(&"2001:db8:abcd:12::1".parse().unwrap()));
+    /// assert!(!cidr.contains(&"2001:db8:abce::1".parse().unwrap()));
+    ///
+    /// let cidr = Cidr::to_cidr("192.0.2.0/24").unwrap();
+    /// assert!(cidr.contains(&"::ffff:192.0.2.7".parse().unwrap()));
+    ///
+    /// let any = Cidr::to_cidr("0.0.0.0/0").unwrap();
+    /// assert!(any.contains(&ip_out));
+    /// assert!(!any.contains(&"2001:db8::1".parse().unwrap()));
     /// ```
     #[must_use]
     pub fn contains(&self, ip: &IpAddr) -> bool {
-        // Ensure IP versions match
-        match (ip, &self.network_address) {
-            (IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_)) => {}
-            _ => return false, // Different IP versions can't be in the same network
-        }
-
-        // For a proper implementation, we need to compare the network bits
-        // This requires converting IPs to their binary representation
-        match (ip, &self.network_address) {
-            (IpAddr::V4(check_ip), IpAddr::V4(network)) => {
-                let mask = !0u32 << (32 - self.prefix_length);
-                let network_bits = u32::from(*network) & mask;
-                let check_bits = u32::from(*check_ip) & mask;
-                network_bits == check_bits
-            }
+        // Compare the network bits of both addresses as integers, reading an
+        // IPv4-mapped address as the family of the block it's checked against
+        let (check_bits, network_bits, width) = match (ip, &self.network_address) {
+            (IpAddr::V4(check_ip), IpAddr::V4(network)) => (
+                u128::from(u32::from(*check_ip)),
+                u128::from(u32::from(*network)),
+                32,
+            ),
+            (IpAddr::V6(check_ip), IpAddr::V4(network)) => match check_ip.to_ipv4_mapped() {
+                Some(check_ip) => (
+                    u128::from(u32::from(check_ip)),
+                    u128::from(u32::from(*network)),
+                    32,
+                ),
+                None => return false,
+            },
+            (IpAddr::V4(check_ip), IpAddr::V6(network)) => (
+                u128::from(check_ip.to_ipv6_mapped()),
+                u128::from(*network),
+                128,
+            ),
             (IpAddr::V6(check_ip), IpAddr::V6(network)) => {
-                // For IPv6, we need to work with the segments
-                let segments_network = network.segments();
-                let segments_check = check_ip.segments();
-
-                // Calculate how many full 16-bit segments are covered by the prefix
-                let full_segments = self.prefix_length / 16;
-                let remainder_bits = self.prefix_length % 16;
-
-                // Check full segments first
-                for i in 0..full_segments as usize {
-                    if segments_network[i] != segments_check[i] {
-                        return false;
-                    }
-                }
-
-                // Check remaining bits in the partial segment, if any
-                if remainder_bits > 0 {
-                    let segment_idx = full_segments as usize;
-                    let mask = !0u16 << (16 - remainder_bits);
-                    let network_bits = segments_network[segment_idx] & mask;
-                    let check_bits = segments_check[segment_idx] & mask;
-                    if network_bits != check_bits {
-                        return false;
-                    }
-                }
-
-                true
+                (u128::from(*check_ip), u128::from(*network), 128)
             }
-            // This should never happen due to the earlier check
-            _ => false,
-        }
+        };
+
+        // A zero-length prefix shifts every bit out and matches everything
+        let mask = u128::MAX
+            .checked_shl(u32::from(width - self.prefix_length.min(width)))
+            .unwrap_or(0);
+        (check_bits ^ network_bits) & mask == 0
     }
 
     /// Returns the network address of the CIDR block.
@@ -235,12 +260,296 @@ impl Cidr {
     }
 }
 
+/// A node of a `CidrSet` trie
+#[derive(Debug, Clone, Copy, Default)]
+struct TrieNode {
+    /// Arena indices of the children for a zero and a one bit, zero if absent
+    children: [u32; 2],
+
+    /// Whether a CIDR block ends at this node
+    terminal: bool,
+}
+
+/// A binary trie over the bits of network addresses
+///
+/// Nodes live in an arena, so thousands of blocks don't mean thousands of
+/// allocations. The root is always node zero, which is why zero doubles as
+/// the absent child.
+#[derive(Debug, Clone)]
+struct CidrTrie {
+    /// Nodes of the trie, the root first
+    nodes: Vec<TrieNode>,
+
+    /// Number of address bits, 32 for IPv4 and 128 for IPv6
+    width: u8,
+}
+
+impl CidrTrie {
+    /// Creates an empty trie over addresses of `width` bits
+    fn new(width: u8) -> Self {
+        CidrTrie {
+            nodes: vec![TrieNode::default()],
+            width,
+        }
+    }
+
+    /// Returns bit `index` of `key`, counting from the most significant
+    fn bit(&self, key: u128, index: u8) -> usize {
+        usize::from((key >> (self.width - 1 - index)) & 1 == 1)
+    }
+
+    /// Inserts the block of the first `prefix` bits of `key`
+    ///
+    /// Returns `true` if the block was not in the trie yet, or an error if
+    /// the trie has no room for its nodes.
+    fn insert(&mut self, key: u128, prefix: u8) -> CidrResult<bool> {
+        let mut node = 0;
+        for index in 0..prefix {
+            let bit = self.bit(key, index);
+            let child = self.nodes[node].children[bit];
+            node = if child == 0 {
+                let next = u32::try_from(self.nodes.len()).map_err(|_| CidrError::SetFull)?;
+                self.nodes[node].children[bit] = next;
+                self.nodes.push(TrieNode::default());
+                self.nodes.len() - 1
+            } else {
+                child as usize
+            };
+        }
+        Ok(!std::mem::replace(&mut self.nodes[node].terminal, true))
+    }
+
+    /// Returns the prefix length of the most specific block containing `key`
+    fn longest_match(&self, key: u128) -> Option<u8> {
+        let mut node = 0;
+        let mut found = self.nodes[0].terminal.then_some(0);
+        for index in 0..self.width {
+            let child = self.nodes[node].children[self.bit(key, index)];
+            if child == 0 {
+                break;
+            }
+            node = child as usize;
+            if self.nodes[node].terminal {
+                found = Some(index + 1);
+            }
+        }
+        found
+    }
+}
+
+/// A set of CIDR blocks with fast membership testing
+///
+/// Blocks are stored in a binary trie per address family, so checking an
+/// address takes at most one step per prefix bit, however many blocks the
+/// set holds. Use it instead of scanning a `Vec<Cidr>` when checking
+/// addresses against large lists, such as cloud provider ranges.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::inspection::cidr::CidrSet;
+/// use std::net::IpAddr;
+///
+/// let set = CidrSet::from_strs(["10.0.0.0/8", "192.168.1.0/24", "2001:db8::/32"]).unwrap();
+/// assert_eq!(set.len(), 3);
+///
+/// let ip: IpAddr = "10.20.30.40".parse().unwrap();
+/// assert!(set.contains(&ip));
+/// assert_eq!(set.longest_match(&ip), Some(8));
+///
+/// let ip: IpAddr = "2001:db8::1".parse().unwrap();
+/// assert!(set.contains(&ip));
+///
+/// let ip: IpAddr = "192.168.2.1".parse().unwrap();
+/// assert!(!set.contains(&ip));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CidrSet {
+    /// Trie of the IPv4 blocks
+    v4: CidrTrie,
+
+    /// Trie of the IPv6 blocks
+    v6: CidrTrie,
+
+    /// Number of distinct blocks in the set
+    len: usize,
+}
+
+impl Default for CidrSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CidrSet {
+    /// Creates an empty set.
+    #[must_use]
+    pub fn new() -> Self {
+        CidrSet {
+            v4: CidrTrie::new(32),
+            v6: CidrTrie::new(128),
+            len: 0,
+        }
+    }
+
+    /// Creates a set from blocks in CIDR notation.
+    ///
+    /// # Arguments
+    ///
+    /// * `cidrs` - The blocks, such as "10.0.0.0/8"
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first block that cannot be parsed, or
+    /// `CidrError::SetFull` if the set has no room for the blocks.
+    pub fn from_strs<I, S>(cidrs: I) -> CidrResult<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut set = CidrSet::new();
+        for cidr in cidrs {
+            set.insert(&Cidr::to_cidr(cidr.as_ref())?)?;
+        }
+        Ok(set)
+    }
+
+    /// Adds a block to the set.
+    ///
+    /// Host bits beyond the prefix length are ignored.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the block was not in the set yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CidrError::SetFull` if the set has no room for the block.
+    pub fn insert(&mut self, cidr: &Cidr) -> CidrResult<bool> {
+        let (trie, key) = self.trie_mut(&cidr.network_address);
+        let inserted = trie.insert(key, cidr.prefix_length)?;
+        if inserted {
+            self.len += 1;
+        }
+        Ok(inserted)
+    }
+
+    /// Checks whether any block in the set contains an address.
+    #[must_use]
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.longest_match(ip).is_some()
+    }
+
+    /// Returns the prefix length of the most specific block containing an address.
+    ///
+    /// An IPv4-mapped IPv6 address not covered by an IPv6 block is checked
+    /// against the IPv4 blocks, and the prefix length of the IPv4 block is
+    /// returned.
+    ///
+    /// # Returns
+    ///
+    /// The prefix length, or `None` if no block contains the address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::inspection::cidr::CidrSet;
+    ///
+    /// let set = CidrSet::from_strs(["203.0.113.0/24", "2001:db8::/32"]).unwrap();
+    /// assert_eq!(set.longest_match(&"::ffff:203.0.113.9".parse().unwrap()), Some(24));
+    /// assert_eq!(set.longest_match(&"2001:db8:1::1".parse().unwrap()), Some(32));
+    /// assert_eq!(set.longest_match(&"2001:db9::1".parse().unwrap()), None);
+    /// ```
+    #[must_use]
+    pub fn longest_match(&self, ip: &IpAddr) -> Option<u8> {
+        match ip {
+            IpAddr::V4(v4) => self.v4.longest_match(u128::from(u32::from(*v4))),
+            IpAddr::V6(v6) => self.v6.longest_match(u128::from(*v6)).or_else(|| {
+                v6.to_ipv4_mapped()
+                    .and_then(|v4| self.v4.longest_match(u128::from(u32::from(v4))))
+            }),
+        }
+    }
+
+    /// Returns the number of distinct blocks in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks whether the set holds no blocks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the trie and key for an address.
+    fn trie_mut(&mut self, ip: &IpAddr) -> (&mut CidrTrie, u128) {
+        match ip {
+            IpAddr::V4(v4) => (&mut self.v4, u128::from(u32::from(*v4))),
+            IpAddr::V6(v6) => (&mut self.v6, u128::from(*v6)),
+        }
+    }
+}
+
+/// Blocks that don't fit once the set is full are dropped.
+impl Extend<Cidr> for CidrSet {
+    fn extend<T: IntoIterator<Item = Cidr>>(&mut self, iter: T) {
+        for cidr in iter {
+            if self.insert(&cidr).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl FromIterator<Cidr> for CidrSet {
+    fn from_iter<T: IntoIterator<Item = Cidr>>(iter: T) -> Self {
+        let mut set = CidrSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
 /// Helper functions for working with CIDR notations.
 ///
 /// This module provides utility functions for parsing and working with CIDR
 /// notation strings without needing to create full CIDR objects.
 pub mod helpers {
-    use super::{Cidr, IpAddr};
+    use super::{Cidr, CidrSet, IpAddr};
+    use std::sync::LazyLock;
+
+    /// Blocks of the IANA special-purpose address registries, whose
+    /// addresses aren't publicly routable
+    const SPECIAL_PURPOSE_BLOCKS: [&str; 19] = [
+        "0.0.0.0/8",
+        "10.0.0.0/8",
+        "100.64.0.0/10",
+        "127.0.0.0/8",
+        "169.254.0.0/16",
+        "172.16.0.0/12",
+        "192.0.2.0/24",
+        "192.168.0.0/16",
+        "198.51.100.0/24",
+        "203.0.113.0/24",
+        "224.0.0.0/4",
+        "240.0.0.0/4",
+        "::/128",
+        "::1/128",
+        "100::/64",
+        "2001:db8::/32",
+        "fc00::/7",
+        "fe80::/10",
+        "ff00::/8",
+    ];
+
+    /// The special-purpose blocks, built on first use
+    static SPECIAL_PURPOSE: LazyLock<CidrSet> = LazyLock::new(|| {
+        SPECIAL_PURPOSE_BLOCKS
+            .iter()
+            .filter_map(|block| Cidr::to_cidr(block).ok())
+            .collect()
+    });
 
     /// Extracts the network part of a CIDR notation.
     ///
@@ -325,4 +634,74 @@ pub mod helpers {
             Err(_) => false,
         }
     }
+
+    /// Checks if an IP address is publicly routable.
+    ///
+    /// Private, loopback, link-local, shared, documentation, multicast,
+    /// reserved, and unspecified addresses of either family are not public,
+    /// and metadata providers have nothing to report about them. An
+    /// IPv4-mapped IPv6 address is judged by the IPv4 address it carries.
+    /// The special-purpose blocks are held in a `CidrSet`, so the check
+    /// costs one trie walk.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The IP address to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the address is publicly routable, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::inspection::cidr::helpers;
+    ///
+    /// assert!(helpers::is_public(&"8.8.8.8".parse().unwrap()));
+    /// assert!(helpers::is_public(&"2606:4700:4700::1111".parse().unwrap()));
+    /// assert!(!helpers::is_public(&"192.168.1.10".parse().unwrap()));
+    /// assert!(!helpers::is_public(&"100.64.0.1".parse().unwrap()));
+    /// assert!(!helpers::is_public(&"fd00::1".parse().unwrap()));
+    /// assert!(!helpers::is_public(&"fe80::1".parse().unwrap()));
+    /// assert!(!helpers::is_public(&"2001:db8::1".parse().unwrap()));
+    /// assert!(!helpers::is_public(&"::ffff:10.0.0.1".parse().unwrap()));
+    /// ```
+    #[must_use]
+    pub fn is_public(ip: &IpAddr) -> bool {
+        !SPECIAL_PURPOSE.contains(ip)
+    }
+
+    /// Returns the address to look an IP address up by at metadata providers.
+    ///
+    /// IPv4-mapped IPv6 addresses become the IPv4 address they carry, since
+    /// providers only know the network under its IPv4 address.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The IP address to look up
+    ///
+    /// # Returns
+    ///
+    /// `Some(IpAddr)` with the address to look up, or `None` if the address
+    /// isn't publicly routable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::inspection::cidr::helpers;
+    /// use std::net::IpAddr;
+    ///
+    /// let mapped: IpAddr = "::ffff:203.0.113.9".parse().unwrap();
+    /// let ip: IpAddr = "2606:4700::6810:85e5".parse().unwrap();
+    /// assert_eq!(helpers::lookup_address(&ip), Some(ip));
+    /// assert_eq!(helpers::lookup_address(&mapped), None);
+    ///
+    /// let mapped: IpAddr = "::ffff:1.1.1.1".parse().unwrap();
+    /// assert_eq!(helpers::lookup_address(&mapped), Some("1.1.1.1".parse().unwrap()));
+    /// ```
+    #[must_use]
+    pub fn lookup_address(ip: &IpAddr) -> Option<IpAddr> {
+        let ip = ip.to_canonical();
+        is_public(&ip).then_some(ip)
+    }
 }
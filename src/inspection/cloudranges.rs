@@ -0,0 +1,453 @@
+//! # Cloud Ranges Module
+//!
+//! This module keeps the address ranges published by large cloud providers,
+//! so addresses hosted on them can be recognised without an external lookup.
+//!
+//! ## Components
+//!
+//! * **`CloudRanges`** - Downloads, caches, and searches the published ranges
+//! * **`CloudRangeSnapshot`** - The ranges of one provider, as last downloaded
+//!
+//! ## Overview
+//!
+//! The official feeds of Amazon Web Services, Google Cloud, Microsoft Azure,
+//! and Cloudflare are downloaded on demand, usually by the
+//! `RefreshCloudRanges` scheduled job. Snapshots are persisted by the
+//! filestore, so ranges survive restarts and only need refreshing once they
+//! grow stale. The sleuth consults the ranges when classifying addresses as
+//! hosting networks.
+//!
+//! The ranges of each provider are held in a `CidrSet`, so checking an
+//! address doesn't scan every range.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::enums::CloudProvider;
+//! use gooty_proxy::inspection::cloudranges::CloudRanges;
+//! use std::net::IpAddr;
+//!
+//! let ranges = CloudRanges::new();
+//! ranges.set_ranges(
+//!     CloudProvider::Cloudflare,
+//!     vec!["104.16.0.0/13".to_string(), "2606:4700::/32".to_string()],
+//! );
+//!
+//! let ip: IpAddr = "104.16.132.229".parse().unwrap();
+//! assert_eq!(ranges.is_cloud_ip(&ip), Some(CloudProvider::Cloudflare));
+//!
+//! let ip: IpAddr = "192.0.2.1".parse().unwrap();
+//! assert_eq!(ranges.is_cloud_ip(&ip), None);
+//! ```
+
+use crate::definitions::enums::CloudProvider;
+#[cfg(feature = "sleuth")]
+use crate::definitions::{
+    defaults,
+    errors::{SleuthError, SleuthResult},
+};
+#[cfg(feature = "sleuth")]
+use crate::inspection::cidr::{Cidr, CidrSet};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "sleuth")]
+use log::{info, warn};
+#[cfg(feature = "sleuth")]
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "sleuth")]
+use std::collections::BTreeMap;
+#[cfg(feature = "sleuth")]
+use std::net::IpAddr;
+#[cfg(feature = "sleuth")]
+use std::path::Path;
+#[cfg(feature = "sleuth")]
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(feature = "sleuth")]
+use std::time::Duration;
+
+/// The ranges of one cloud provider, as last downloaded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudRangeSnapshot {
+    /// The provider publishing the ranges
+    pub provider: CloudProvider,
+
+    /// When the ranges were downloaded
+    pub updated_at: DateTime<Utc>,
+
+    /// The ranges in CIDR notation
+    pub ranges: Vec<String>,
+}
+
+/// Searchable index over the snapshots of every provider
+#[cfg(feature = "sleuth")]
+#[derive(Debug, Default)]
+struct RangeIndex {
+    /// Snapshots by provider
+    snapshots: BTreeMap<CloudProvider, CloudRangeSnapshot>,
+
+    /// Ranges of every provider
+    sets: BTreeMap<CloudProvider, CidrSet>,
+}
+
+#[cfg(feature = "sleuth")]
+impl RangeIndex {
+    /// Stores a snapshot and indexes its ranges
+    ///
+    /// Ranges that aren't valid CIDR notation are skipped.
+    fn insert(&mut self, snapshot: CloudRangeSnapshot) {
+        let set = snapshot
+            .ranges
+            .iter()
+            .filter_map(|range| Cidr::to_cidr(range).ok())
+            .collect();
+        self.sets.insert(snapshot.provider, set);
+        self.snapshots.insert(snapshot.provider, snapshot);
+    }
+}
+
+/// Downloads, caches, and searches the address ranges of cloud providers
+///
+/// The ranges are held behind a lock, so a shared instance can be refreshed
+/// while lookups continue against the previous ranges.
+#[cfg(feature = "sleuth")]
+#[derive(Debug)]
+pub struct CloudRanges {
+    /// HTTP client for downloading the feeds
+    client: Client,
+
+    /// Feed URL of every provider
+    feeds: BTreeMap<CloudProvider, String>,
+
+    /// The downloaded ranges and their index
+    index: RwLock<RangeIndex>,
+}
+
+#[cfg(feature = "sleuth")]
+impl Default for CloudRanges {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "sleuth")]
+impl CloudRanges {
+    /// Creates an empty range database using the official feeds
+    #[must_use]
+    pub fn new() -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(
+                defaults::cloud_ranges::DOWNLOAD_TIMEOUT_SECS,
+            ))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Self::with_client(client)
+    }
+
+    /// Creates an empty range database using a custom HTTP client
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - A pre-configured HTTP client
+    #[must_use]
+    pub fn with_client(client: Client) -> Self {
+        let feeds = BTreeMap::from([
+            (
+                CloudProvider::Aws,
+                defaults::cloud_ranges::AWS_URL.to_string(),
+            ),
+            (
+                CloudProvider::Gcp,
+                defaults::cloud_ranges::GCP_URL.to_string(),
+            ),
+            (
+                CloudProvider::Azure,
+                defaults::cloud_ranges::AZURE_URL.to_string(),
+            ),
+            (
+                CloudProvider::Cloudflare,
+                defaults::cloud_ranges::CLOUDFLARE_URL.to_string(),
+            ),
+        ]);
+
+        CloudRanges {
+            client,
+            feeds,
+            index: RwLock::new(RangeIndex::default()),
+        }
+    }
+
+    /// Sets the feed a provider's ranges are downloaded from
+    ///
+    /// The feed must have the format of the provider's official feed. For
+    /// Azure, either the service tags file or the page linking it is accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The provider publishing the feed
+    /// * `url` - URL of the feed
+    ///
+    /// # Returns
+    ///
+    /// Self with the feed set
+    #[must_use]
+    pub fn with_feed(mut self, provider: CloudProvider, url: &str) -> Self {
+        self.feeds.insert(provider, url.to_string());
+        self
+    }
+
+    /// Returns the cloud provider an address belongs to
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The address to look up
+    ///
+    /// # Returns
+    ///
+    /// The provider with the most specific range containing the address, or
+    /// None if no known range contains it
+    #[must_use]
+    pub fn is_cloud_ip(&self, ip: &IpAddr) -> Option<CloudProvider> {
+        self.read()
+            .sets
+            .iter()
+            .filter_map(|(&provider, set)| set.longest_match(ip).map(|prefix| (prefix, provider)))
+            .max_by_key(|&(prefix, _)| prefix)
+            .map(|(_, provider)| provider)
+    }
+
+    /// Returns the number of distinct ranges known, summed over providers
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.read().sets.values().map(CidrSet::len).sum()
+    }
+
+    /// Checks whether no ranges are known
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.read().sets.values().all(CidrSet::is_empty)
+    }
+
+    /// Returns when a provider's ranges were downloaded
+    #[must_use]
+    pub fn updated_at(&self, provider: CloudProvider) -> Option<DateTime<Utc>> {
+        self.read().snapshots.get(&provider).map(|s| s.updated_at)
+    }
+
+    /// Checks whether any provider's ranges are missing or older than `max_age`
+    #[must_use]
+    pub fn needs_refresh(&self, max_age: chrono::Duration) -> bool {
+        let cutoff = Utc::now() - max_age;
+        let index = self.read();
+        self.feeds.keys().any(|provider| {
+            index
+                .snapshots
+                .get(provider)
+                .is_none_or(|s| s.updated_at < cutoff)
+        })
+    }
+
+    /// Replaces a provider's ranges
+    ///
+    /// Ranges that aren't valid CIDR notation are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - The provider publishing the ranges
+    /// * `ranges` - The ranges in CIDR notation
+    pub fn set_ranges(&self, provider: CloudProvider, ranges: Vec<String>) {
+        self.write().insert(CloudRangeSnapshot {
+            provider,
+            updated_at: Utc::now(),
+            ranges,
+        });
+    }
+
+    /// Returns the ranges of every provider, for persisting
+    #[must_use]
+    pub fn snapshots(&self) -> Vec<CloudRangeSnapshot> {
+        self.read().snapshots.values().cloned().collect()
+    }
+
+    /// Restores ranges downloaded earlier, such as by a previous run
+    ///
+    /// A stored snapshot only replaces the ranges of its provider if it is
+    /// newer than the ones already known.
+    ///
+    /// # Arguments
+    ///
+    /// * `stored` - Snapshots previously returned by `snapshots`
+    pub fn restore(&self, stored: Vec<CloudRangeSnapshot>) {
+        let mut index = self.write();
+        for snapshot in stored {
+            let newer = index
+                .snapshots
+                .get(&snapshot.provider)
+                .is_none_or(|known| known.updated_at < snapshot.updated_at);
+            if newer {
+                index.insert(snapshot);
+            }
+        }
+    }
+
+    /// Downloads the ranges of every provider
+    ///
+    /// A provider whose feed fails keeps its previous ranges, and the failure
+    /// is logged.
+    ///
+    /// # Returns
+    ///
+    /// The number of distinct ranges known afterwards
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error if every feed failed
+    pub async fn refresh(&self) -> SleuthResult<usize> {
+        let mut last_error = None;
+        let mut refreshed = 0;
+        for (&provider, url) in &self.feeds {
+            match self.download(provider, url).await {
+                Ok(ranges) => {
+                    info!("Downloaded {} {provider} ranges", ranges.len());
+                    self.set_ranges(provider, ranges);
+                    refreshed += 1;
+                }
+                Err(e) => {
+                    warn!("Failed to download {provider} ranges: {e}");
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if refreshed == 0 => Err(e),
+            _ => Ok(self.len()),
+        }
+    }
+
+    /// Downloads and parses the feed of one provider
+    async fn download(&self, provider: CloudProvider, url: &str) -> SleuthResult<Vec<String>> {
+        let url = if provider == CloudProvider::Azure && !is_json_link(url) {
+            let page = self.fetch(url).await?.text().await.map_err(|e| {
+                SleuthError::ParseError(format!("Failed to read Azure download page: {e}"))
+            })?;
+            service_tags_link(&page).ok_or_else(|| {
+                SleuthError::NotFound("Azure service tags link on download page".to_string())
+            })?
+        } else {
+            url.to_string()
+        };
+
+        let data: serde_json::Value = self
+            .fetch(&url)
+            .await?
+            .json()
+            .await
+            .map_err(|e| SleuthError::ParseError(e.to_string()))?;
+
+        let mut ranges = match provider {
+            CloudProvider::Aws => collect_strings(
+                &data,
+                &["prefixes", "ipv6_prefixes"],
+                &["ip_prefix", "ipv6_prefix"],
+            ),
+            CloudProvider::Gcp => {
+                collect_strings(&data, &["prefixes"], &["ipv4Prefix", "ipv6Prefix"])
+            }
+            CloudProvider::Azure => data
+                .get("values")
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|tag| tag.pointer("/properties/addressPrefixes")?.as_array())
+                .flatten()
+                .filter_map(|prefix| prefix.as_str().map(String::from))
+                .collect(),
+            CloudProvider::Cloudflare => ["/result/ipv4_cidrs", "/result/ipv6_cidrs"]
+                .into_iter()
+                .filter_map(|pointer| data.pointer(pointer)?.as_array())
+                .flatten()
+                .filter_map(|prefix| prefix.as_str().map(String::from))
+                .collect(),
+        };
+
+        ranges.sort();
+        ranges.dedup();
+        if ranges.is_empty() {
+            return Err(SleuthError::ParseError(format!(
+                "No ranges in {provider} feed"
+            )));
+        }
+        Ok(ranges)
+    }
+
+    /// Requests a URL, failing on error statuses
+    async fn fetch(&self, url: &str) -> SleuthResult<reqwest::Response> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| SleuthError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return match response.status().as_u16() {
+                404 => Err(SleuthError::NotFound(url.to_string())),
+                429 => Err(SleuthError::RateLimited),
+                _ => Err(SleuthError::ApiError(format!(
+                    "Status {}",
+                    response.status()
+                ))),
+            };
+        }
+        Ok(response)
+    }
+
+    /// Locks the index for reading, recovering it if a writer panicked
+    fn read(&self) -> RwLockReadGuard<'_, RangeIndex> {
+        self.index.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the index for writing, recovering it if a writer panicked
+    fn write(&self) -> RwLockWriteGuard<'_, RangeIndex> {
+        self.index.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Collects string fields of the objects in the given arrays of a feed
+#[cfg(feature = "sleuth")]
+fn collect_strings(data: &serde_json::Value, arrays: &[&str], fields: &[&str]) -> Vec<String> {
+    arrays
+        .iter()
+        .filter_map(|array| data.get(array)?.as_array())
+        .flatten()
+        .flat_map(|entry| {
+            fields
+                .iter()
+                .filter_map(|field| entry.get(field)?.as_str().map(String::from))
+        })
+        .collect()
+}
+
+/// Checks whether a link points to a JSON file
+#[cfg(feature = "sleuth")]
+fn is_json_link(link: &str) -> bool {
+    Path::new(link)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Finds the link to the current service tags file on the Azure download page
+#[cfg(feature = "sleuth")]
+fn service_tags_link(page: &str) -> Option<String> {
+    page.match_indices("https://download.microsoft.com/download/")
+        .map(|(start, _)| {
+            let rest = &page[start..];
+            let end = rest
+                .find(|c: char| c == '"' || c == '\'' || c == '<' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            &rest[..end]
+        })
+        .find(|link| link.contains("ServiceTags_Public") && is_json_link(link))
+        .map(String::from)
+}
@@ -0,0 +1,273 @@
+//! # `GeoIP` Module
+//!
+//! This module looks addresses up in local `MaxMind` databases, so proxies can
+//! be enriched with their country, city, and network owner without a single
+//! request.
+//!
+//! ## Components
+//!
+//! * **`GeoIpDatabase`** - The `GeoLite2` or `GeoIP2` databases an address is
+//!   looked up in
+//!
+//! ## Overview
+//!
+//! `MaxMind` ships locations and owners in separate files: a City (or
+//! Country) database, and an ASN database. Either or both can be opened, and
+//! each address is looked up in all of them. A `Sleuth` given the databases
+//! with `Sleuth::with_geoip` answers from them first, and only asks ipinfo.io
+//! for addresses they don't know.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use gooty_proxy::inspection::geoip::GeoIpDatabase;
+//! use std::net::IpAddr;
+//!
+//! let geoip = GeoIpDatabase::new()
+//!     .with_database("GeoLite2-City.mmdb")
+//!     .unwrap()
+//!     .with_database("GeoLite2-ASN.mmdb")
+//!     .unwrap();
+//!
+//! let ip: IpAddr = "8.8.8.8".parse().unwrap();
+//! if let Some(metadata) = geoip.lookup(&ip) {
+//!     println!("ASN: {:?}", metadata.asn);
+//! }
+//! ```
+
+use crate::definitions::errors::{SleuthError, SleuthResult};
+use crate::inspection::{
+    cidr,
+    ipinfo::IpMetadata,
+    location::Location,
+    ownership::{NetworkInfo, Organization},
+};
+use maxminddb::{MaxMindDBError, Reader, geoip2};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+/// Language whose names are taken from the databases
+const NAME_LANGUAGE: &str = "en";
+
+/// Local `MaxMind` databases addresses are looked up in
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::errors::SleuthError;
+/// use gooty_proxy::inspection::geoip::GeoIpDatabase;
+/// use std::net::IpAddr;
+///
+/// // Without databases, no address is known
+/// let geoip = GeoIpDatabase::new();
+/// assert!(geoip.is_empty());
+/// let ip: IpAddr = "8.8.8.8".parse().unwrap();
+/// assert!(geoip.lookup(&ip).is_none());
+///
+/// let missing = GeoIpDatabase::new().with_database("missing.mmdb");
+/// assert!(matches!(missing, Err(SleuthError::DatabaseError(_))));
+/// ```
+#[derive(Default)]
+pub struct GeoIpDatabase {
+    /// City or Country databases, for locations
+    locations: Vec<Reader<Vec<u8>>>,
+
+    /// ASN databases, for network owners
+    networks: Vec<Reader<Vec<u8>>>,
+}
+
+impl fmt::Debug for GeoIpDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let types = |readers: &[Reader<Vec<u8>>]| {
+            readers
+                .iter()
+                .map(|reader| reader.metadata.database_type.clone())
+                .collect::<Vec<_>>()
+        };
+        f.debug_struct("GeoIpDatabase")
+            .field("locations", &types(&self.locations))
+            .field("networks", &types(&self.networks))
+            .finish()
+    }
+}
+
+impl GeoIpDatabase {
+    /// Create a lookup without any database
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open a database file and add it to the lookup
+    ///
+    /// Whether the file holds locations or network owners is read from its
+    /// metadata, so City, Country, and ASN databases can be added alike.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the `.mmdb` file
+    ///
+    /// # Returns
+    ///
+    /// Self with the database added
+    ///
+    /// # Errors
+    ///
+    /// Returns `SleuthError::DatabaseError` if the file cannot be read or
+    /// isn't a `MaxMind` database of a supported type
+    pub fn with_database(mut self, path: impl AsRef<Path>) -> SleuthResult<Self> {
+        let path = path.as_ref();
+        let reader = Reader::open_readfile(path).map_err(|e| {
+            SleuthError::DatabaseError(format!("Failed to open {}: {e}", path.display()))
+        })?;
+
+        let database_type = reader.metadata.database_type.clone();
+        if database_type.contains("ASN") {
+            self.networks.push(reader);
+        } else if database_type.contains("City") || database_type.contains("Country") {
+            self.locations.push(reader);
+        } else {
+            return Err(SleuthError::DatabaseError(format!(
+                "Unsupported database type {database_type} in {}",
+                path.display()
+            )));
+        }
+        Ok(self)
+    }
+
+    /// Check whether no database was added
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty() && self.networks.is_empty()
+    }
+
+    /// Look an address up in the databases
+    ///
+    /// Only the location and the network owner are filled in; the databases
+    /// know neither hostnames nor privacy flags. Addresses that aren't
+    /// publicly routable aren't looked up, and IPv4-mapped addresses are
+    /// looked up as IPv4.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The address to look up
+    ///
+    /// # Returns
+    ///
+    /// The metadata the databases hold, or None if none of them knows the
+    /// address
+    #[must_use]
+    pub fn lookup(&self, ip: &IpAddr) -> Option<IpMetadata> {
+        let target = cidr::helpers::lookup_address(ip)?;
+        let location = self
+            .locations
+            .iter()
+            .find_map(|reader| lookup_location(reader, target));
+        let (organization, cidr) = self
+            .networks
+            .iter()
+            .find_map(|reader| lookup_network(reader, target))
+            .unzip();
+
+        if location.is_none() && organization.is_none() {
+            return None;
+        }
+
+        let asn = organization.as_ref().and_then(|org| org.asn.clone());
+        Some(IpMetadata {
+            ip: *ip,
+            hostname: None,
+            network: Some(NetworkInfo {
+                cidr,
+                organization,
+                location,
+                ..Default::default()
+            }),
+            asn,
+            privacy: None,
+            company: None,
+            is_anycast: None,
+            is_hosting: None,
+            is_mobile: None,
+            cloud_provider: None,
+        })
+    }
+}
+
+/// Looks the location of an address up in a City or Country database
+fn lookup_location(reader: &Reader<Vec<u8>>, ip: IpAddr) -> Option<Location> {
+    let record: geoip2::City = found(reader.lookup(ip))?;
+    let location = Location {
+        city: record.city.and_then(|city| english_name(city.names)),
+        state: record
+            .subdivisions
+            .and_then(|subdivisions| subdivisions.into_iter().next())
+            .and_then(|subdivision| english_name(subdivision.names)),
+        postal_code: record
+            .postal
+            .and_then(|postal| postal.code)
+            .map(String::from),
+        country: record
+            .country
+            .and_then(|country| country.iso_code)
+            .map(String::from),
+        facility_name: None,
+    };
+
+    let known = location.city.is_some()
+        || location.state.is_some()
+        || location.postal_code.is_some()
+        || location.country.is_some();
+    known.then_some(location)
+}
+
+/// Looks the owner and network of an address up in an ASN database
+fn lookup_network(reader: &Reader<Vec<u8>>, ip: IpAddr) -> Option<(Organization, String)> {
+    let (record, prefix): (geoip2::Asn, usize) = found(reader.lookup_prefix(ip))?;
+    if record.autonomous_system_number.is_none() && record.autonomous_system_organization.is_none()
+    {
+        return None;
+    }
+
+    let organization = Organization::new(
+        record.autonomous_system_organization.map(String::from),
+        record.autonomous_system_number.map(|asn| asn.to_string()),
+    );
+    Some((organization, network_cidr(ip, prefix)))
+}
+
+/// Turns a lookup into its record, logging failures other than unknown addresses
+fn found<T>(result: Result<T, MaxMindDBError>) -> Option<T> {
+    match result {
+        Ok(record) => Some(record),
+        Err(MaxMindDBError::AddressNotFoundError(_)) => None,
+        Err(e) => {
+            log::debug!("GeoIP lookup failed: {e}");
+            None
+        }
+    }
+}
+
+/// Picks the English name out of a database's names
+fn english_name(names: Option<BTreeMap<&str, &str>>) -> Option<String> {
+    names?.get(NAME_LANGUAGE).map(|name| (*name).to_string())
+}
+
+/// Formats the network of an address with the prefix length the database
+/// stores it under
+fn network_cidr(ip: IpAddr, prefix: usize) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let prefix = u32::try_from(prefix.min(32)).unwrap_or(32);
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            format!("{}/{prefix}", Ipv4Addr::from(u32::from(v4) & mask))
+        }
+        IpAddr::V6(v6) => {
+            let prefix = u32::try_from(prefix.min(128)).unwrap_or(128);
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            format!("{}/{prefix}", Ipv6Addr::from(u128::from(v6) & mask))
+        }
+    }
+}
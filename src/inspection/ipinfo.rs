@@ -5,9 +5,18 @@
 //!
 //! ## Components
 //!
-//! * **Sleuth** - A struct for performing IP lookups
+//! * **Sleuth** - A struct for performing IP lookups (`sleuth` feature)
 //! * **`IpMetadata`** - A struct for storing comprehensive IP metadata
 //!
+//! Every lookup is accounted in a shared `UsageTracker`, which warns as the
+//! provider's quota runs low. With an API token, read from the configuration
+//! or the `GOOTY_IPINFO_TOKEN` environment variable, requests are authenticated
+//! and paid-plan fields such as privacy detection are filled in.
+//!
+//! IPv4 and IPv6 addresses are looked up alike. IPv4-mapped IPv6 addresses
+//! are looked up by the IPv4 address they carry, and addresses that aren't
+//! publicly routable fail with `SleuthError::NotFound` without a request.
+//!
 //! ## Examples
 //!
 //! ```
@@ -24,15 +33,38 @@
 //! }
 //! ```
 
-use crate::definitions::errors::{SleuthError, SleuthResult};
+use crate::definitions::{defaults, enums::CloudProvider};
+#[cfg(feature = "sleuth")]
+use crate::definitions::{
+    enums::{IdentityProfile, Registry},
+    errors::{SleuthError, SleuthResult},
+};
+#[cfg(feature = "geoip")]
+use crate::inspection::geoip::GeoIpDatabase;
+use crate::inspection::ownership::NetworkInfo;
+#[cfg(feature = "sleuth")]
 use crate::inspection::{
+    capture::ResponseCapture,
     cidr,
+    cloudranges::CloudRanges,
     location::Location,
-    ownership::{NetworkInfo, Organization, OwnershipLookup},
+    lookup_cache::LookupCache,
+    ownership::{Organization, OwnershipLookup},
+    usage::UsageTracker,
 };
-use reqwest::Client;
+#[cfg(feature = "sleuth")]
+use crate::io::{
+    filesystem::AppConfig,
+    http::{self, Egress, RateLimiter},
+};
+#[cfg(feature = "sleuth")]
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::net::IpAddr;
+#[cfg(feature = "sleuth")]
+use std::sync::Arc;
+#[cfg(feature = "sleuth")]
 use std::time::Duration;
 
 /// Full IP address metadata gathered by Sleuth
@@ -73,6 +105,231 @@ pub struct IpMetadata {
 
     /// ASN (Autonomous System Number) specifically for the IP
     pub asn: Option<String>,
+
+    /// Privacy detection flags, only reported with an API token
+    #[serde(default)]
+    pub privacy: Option<PrivacyInfo>,
+
+    /// Company using the IP address, only reported with an API token
+    #[serde(default)]
+    pub company: Option<CompanyInfo>,
+
+    /// Whether the address is announced from several locations, if known
+    #[serde(default)]
+    pub is_anycast: Option<bool>,
+
+    /// Whether the address belongs to a hosting provider, if known
+    #[serde(default)]
+    pub is_hosting: Option<bool>,
+
+    /// Whether the address belongs to a mobile carrier, if known
+    #[serde(default)]
+    pub is_mobile: Option<bool>,
+
+    /// Cloud provider whose published ranges contain the address, if any
+    #[serde(default)]
+    pub cloud_provider: Option<CloudProvider>,
+}
+
+/// Privacy detection flags reported by paid ipinfo.io plans
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::inspection::ipinfo::PrivacyInfo;
+///
+/// let privacy: PrivacyInfo = serde_json::from_str(
+///     r#"{"vpn": true, "proxy": false, "tor": false, "relay": false, "hosting": true, "service": ""}"#,
+/// )
+/// .unwrap();
+/// assert!(privacy.is_anonymizing());
+/// assert!(privacy.hosting);
+/// assert_eq!(privacy.service, None);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct PrivacyInfo {
+    /// The address belongs to a VPN
+    pub vpn: bool,
+
+    /// The address is an open or commercial proxy
+    pub proxy: bool,
+
+    /// The address is a Tor exit node
+    pub tor: bool,
+
+    /// The address belongs to a relay service such as iCloud Private Relay
+    pub relay: bool,
+
+    /// The address belongs to a hosting provider or data center
+    pub hosting: bool,
+
+    /// Name of the privacy service, if known
+    #[serde(deserialize_with = "empty_as_none")]
+    pub service: Option<String>,
+}
+
+impl PrivacyInfo {
+    /// Checks whether any anonymizing service was detected
+    #[must_use]
+    pub fn is_anonymizing(&self) -> bool {
+        self.vpn || self.proxy || self.tor || self.relay
+    }
+}
+
+/// Company details reported by paid ipinfo.io plans
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct CompanyInfo {
+    /// Name of the company
+    pub name: Option<String>,
+
+    /// Domain of the company
+    pub domain: Option<String>,
+
+    /// Kind of company: isp, business, education, government, or hosting
+    #[serde(rename = "type")]
+    pub company_type: Option<String>,
+}
+
+/// Treats an empty string as a missing value
+fn empty_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    Ok(value.filter(|v| !v.is_empty()))
+}
+
+/// Extracts the abuse contact and registry of the network, if reported
+#[cfg(feature = "sleuth")]
+fn network_contacts(data: &serde_json::Value) -> (Option<String>, Option<Registry>) {
+    let abuse_email = data
+        .pointer("/abuse/email")
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())
+        .map(String::from);
+    let registry = data
+        .pointer("/asn/registry")
+        .or_else(|| data.get("registry"))
+        .and_then(|v| v.as_str())
+        .and_then(|v| v.parse().ok());
+    (abuse_email, registry)
+}
+
+/// Parses the location fields of an ipinfo.io response
+#[cfg(feature = "sleuth")]
+fn location_from(data: &serde_json::Value) -> Option<Location> {
+    let city = data.get("city").and_then(|v| v.as_str()).map(String::from);
+    let region = data
+        .get("region")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let postal = data
+        .get("postal")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+    let country = data
+        .get("country")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    if city.is_some() || region.is_some() || postal.is_some() || country.is_some() {
+        Some(Location {
+            city,
+            state: region,
+            postal_code: postal,
+            country,
+            facility_name: None,
+        })
+    } else {
+        None
+    }
+}
+
+/// Returns the address to look `ip` up by
+///
+/// Providers report nothing useful for private or reserved addresses, so
+/// those are refused before any request is made.
+#[cfg(feature = "sleuth")]
+fn lookup_target(ip: &IpAddr) -> SleuthResult<IpAddr> {
+    cidr::helpers::lookup_address(ip)
+        .ok_or_else(|| SleuthError::NotFound(format!("{ip} is not a publicly routable address")))
+}
+
+/// Works out whether an address belongs to a hosting provider
+///
+/// Prefers the privacy flag, then the type of the ASN or company.
+#[cfg(feature = "sleuth")]
+fn hosting_flag(
+    data: &serde_json::Value,
+    privacy: Option<&PrivacyInfo>,
+    company: Option<&CompanyInfo>,
+) -> Option<bool> {
+    if let Some(privacy) = privacy {
+        return Some(privacy.hosting);
+    }
+    data.pointer("/asn/type")
+        .and_then(|v| v.as_str())
+        .or_else(|| company.and_then(|c| c.company_type.as_deref()))
+        .map(|kind| kind.eq_ignore_ascii_case("hosting"))
+}
+
+/// Returns the environment variable holding a provider's API token
+///
+/// # Arguments
+///
+/// * `provider` - Name of the provider, such as `ipinfo`
+///
+/// # Returns
+///
+/// The variable name, such as `GOOTY_IPINFO_TOKEN`
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::inspection::ipinfo::api_token_env_var;
+///
+/// assert_eq!(api_token_env_var("ipinfo"), "GOOTY_IPINFO_TOKEN");
+/// ```
+#[must_use]
+pub fn api_token_env_var(provider: &str) -> String {
+    format!(
+        "{}{}{}",
+        defaults::providers::TOKEN_ENV_PREFIX,
+        provider.to_uppercase().replace('-', "_"),
+        defaults::providers::TOKEN_ENV_SUFFIX
+    )
+}
+
+/// Resolves the API token of every provider from configuration and environment
+///
+/// A token set in the provider's environment variable takes precedence over
+/// the configured one. Empty tokens are ignored.
+///
+/// # Arguments
+///
+/// * `configured` - Tokens from the configuration, by provider name
+///
+/// # Returns
+///
+/// The tokens to use, by provider name
+#[must_use]
+pub fn resolve_api_tokens(configured: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+    let mut providers: Vec<&str> = configured.keys().map(String::as_str).collect();
+    providers.push(defaults::providers::IPINFO);
+
+    providers
+        .into_iter()
+        .filter_map(|provider| {
+            std::env::var(api_token_env_var(provider))
+                .ok()
+                .or_else(|| configured.get(provider).cloned())
+                .filter(|token| !token.trim().is_empty())
+                .map(|token| (provider.to_string(), token.trim().to_string()))
+        })
+        .collect()
 }
 
 impl Default for IpMetadata {
@@ -81,10 +338,16 @@ impl Default for IpMetadata {
     /// The default instance uses 0.0.0.0 as the IP address with all other fields set to None.
     fn default() -> Self {
         IpMetadata {
-            ip: IpAddr::V4(std::net::Ipv4Addr::new(0, 0, 0, 0)),
+            ip: IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED),
             hostname: None,
             network: None,
             asn: None,
+            privacy: None,
+            company: None,
+            is_anycast: None,
+            is_hosting: None,
+            is_mobile: None,
+            cloud_provider: None,
         }
     }
 }
@@ -115,14 +378,38 @@ impl Default for IpMetadata {
 ///     Ok(())
 /// }
 /// ```
+#[cfg(feature = "sleuth")]
 pub struct Sleuth {
     /// HTTP client for making API requests
     client: Client,
 
     /// Ownership lookup service for retrieving ASN and organization information
     ownership_lookup: OwnershipLookup,
+
+    /// Accounts lookups against provider quotas
+    usage: Arc<UsageTracker>,
+
+    /// API tokens by provider name
+    api_tokens: BTreeMap<String, String>,
+
+    /// Published address ranges of cloud providers
+    cloud_ranges: Arc<CloudRanges>,
+
+    /// Keeps the raw ipinfo.io responses for debugging
+    capture: Arc<ResponseCapture>,
+
+    /// Documents looked up before, shared with the ownership lookup
+    lookup_cache: Arc<LookupCache>,
+
+    /// Paces lookups, shared with the ownership lookup
+    rate_limiter: Arc<RateLimiter>,
+
+    /// Local databases answering lookups before ipinfo.io
+    #[cfg(feature = "geoip")]
+    geoip: Option<Arc<GeoIpDatabase>>,
 }
 
+#[cfg(feature = "sleuth")]
 impl Default for Sleuth {
     /// Creates a default Sleuth instance with standard configuration
     fn default() -> Self {
@@ -130,11 +417,12 @@ impl Default for Sleuth {
     }
 }
 
+#[cfg(feature = "sleuth")]
 impl Sleuth {
     /// Create a new Sleuth instance with default configuration
     ///
     /// Initializes a Sleuth instance with a default HTTP client that has
-    /// a 10-second timeout.
+    /// a 10-second timeout. API tokens are read from the environment.
     ///
     /// # Returns
     ///
@@ -146,10 +434,54 @@ impl Sleuth {
             .build()
             .unwrap_or_else(|_| Client::new());
 
-        Sleuth {
-            client: client.clone(),
-            ownership_lookup: OwnershipLookup::with_client(client),
-        }
+        Self::with_client(client)
+    }
+
+    /// Create a new Sleuth instance whose requests carry an identity
+    ///
+    /// Lookups at ipinfo.io and RDAP and cloud range downloads are all
+    /// made with the headers of the identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - How lookup requests identify themselves
+    ///
+    /// # Returns
+    ///
+    /// A new Sleuth instance with default settings otherwise
+    #[must_use]
+    pub fn with_identity(identity: IdentityProfile) -> Self {
+        Self::with_outbound(identity, &Egress::System)
+    }
+
+    /// Create a new Sleuth instance whose requests carry an identity and take
+    /// an egress route
+    ///
+    /// Like [`Sleuth::with_identity`], but lookups and cloud range downloads
+    /// connect the way the egress says.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - How lookup requests identify themselves
+    /// * `egress` - Route lookup requests take to the network
+    ///
+    /// # Returns
+    ///
+    /// A new Sleuth instance with default settings otherwise
+    #[must_use]
+    pub fn with_outbound(identity: IdentityProfile, egress: &Egress) -> Self {
+        let download_client = http::lookup_client(
+            identity,
+            Duration::from_secs(defaults::cloud_ranges::DOWNLOAD_TIMEOUT_SECS),
+            egress,
+        );
+
+        Self::with_client(http::lookup_client(
+            identity,
+            Duration::from_secs(10),
+            egress,
+        ))
+        .with_cloud_ranges(Arc::new(CloudRanges::with_client(download_client)))
     }
 
     /// Create a new Sleuth instance with a custom HTTP client
@@ -167,9 +499,335 @@ impl Sleuth {
     #[must_use]
     pub fn with_client(client: Client) -> Self {
         Sleuth {
-            client: client.clone(),
-            ownership_lookup: OwnershipLookup::with_client(client),
+            ownership_lookup: OwnershipLookup::with_client(client.clone()),
+            client,
+            usage: Arc::new(UsageTracker::default()),
+            api_tokens: resolve_api_tokens(&BTreeMap::new()),
+            cloud_ranges: Arc::new(CloudRanges::new()),
+            capture: Arc::new(ResponseCapture::default()),
+            lookup_cache: Arc::new(LookupCache::default()),
+            rate_limiter: Arc::new(RateLimiter::default()),
+            #[cfg(feature = "geoip")]
+            geoip: None,
         }
+        .rebuild_ownership_lookup()
+    }
+
+    /// Create a new Sleuth instance from the application configuration
+    ///
+    /// Uses the configured provider quotas, identity, egress, and lookup
+    /// cache lifetime, and the configured API tokens unless the environment
+    /// overrides them.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The application configuration
+    ///
+    /// # Returns
+    ///
+    /// A new Sleuth instance configured from `config`
+    #[must_use]
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::with_outbound(config.outbound_identity, &config.egress())
+            .with_usage_tracker(Arc::new(UsageTracker::new(
+                config.provider_quotas.clone(),
+                defaults::providers::QUOTA_WARNING_RATIO,
+            )))
+            .with_api_tokens(resolve_api_tokens(&config.provider_tokens))
+            .with_lookup_cache(Arc::new(LookupCache::new(Duration::from_secs(
+                config.lookup_cache_ttl_secs,
+            ))))
+            .with_rate_limiter(Arc::new(RateLimiter::new(config.rate_limit.clone())))
+    }
+
+    /// Set the API token of a provider
+    ///
+    /// With an ipinfo.io token, requests are authenticated and the richer
+    /// fields of paid plans, such as privacy detection, are filled in.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Name of the provider, such as `ipinfo`
+    /// * `token` - The API token
+    ///
+    /// # Returns
+    ///
+    /// Self with the token set
+    #[must_use]
+    pub fn with_api_token(mut self, provider: &str, token: String) -> Self {
+        self.api_tokens.insert(provider.to_string(), token);
+        self.rebuild_ownership_lookup()
+    }
+
+    /// Replace the API tokens of all providers
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - API tokens by provider name
+    ///
+    /// # Returns
+    ///
+    /// Self with the tokens set
+    #[must_use]
+    pub fn with_api_tokens(mut self, tokens: BTreeMap<String, String>) -> Self {
+        self.api_tokens = tokens;
+        self.rebuild_ownership_lookup()
+    }
+
+    /// Check whether an API token is set for a provider
+    #[must_use]
+    pub fn has_api_token(&self, provider: &str) -> bool {
+        self.api_tokens.contains_key(provider)
+    }
+
+    /// Account lookups in the given usage tracker
+    ///
+    /// Every request made by the sleuth and its ownership lookups is counted
+    /// against the tracker's provider quotas.
+    ///
+    /// # Arguments
+    ///
+    /// * `usage` - The tracker to account lookups in
+    ///
+    /// # Returns
+    ///
+    /// Self with the tracker set
+    #[must_use]
+    pub fn with_usage_tracker(mut self, usage: Arc<UsageTracker>) -> Self {
+        self.usage = usage;
+        self.rebuild_ownership_lookup()
+    }
+
+    /// Share the client, tracker, and ipinfo.io token with the ownership lookup
+    fn rebuild_ownership_lookup(mut self) -> Self {
+        self.ownership_lookup = OwnershipLookup::with_client(self.client.clone())
+            .with_usage_tracker(Arc::clone(&self.usage))
+            .with_api_token(self.api_tokens.get(defaults::providers::IPINFO).cloned())
+            .with_lookup_cache(Arc::clone(&self.lookup_cache))
+            .with_rate_limiter(Arc::clone(&self.rate_limiter));
+        self
+    }
+
+    /// Build an ipinfo.io request, accounting it and attaching the API token
+    fn ipinfo_request(&self, url: &str) -> RequestBuilder {
+        self.usage.record(defaults::providers::IPINFO);
+        let request = self.client.get(url).header("Accept", "application/json");
+        match self.api_tokens.get(defaults::providers::IPINFO) {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Request an ipinfo.io document about an address
+    ///
+    /// Documents still in the lookup cache are reused without a request.
+    /// The raw response is kept in the response capture, whether it
+    /// succeeded or not.
+    async fn ipinfo_json(&self, url: &str, ip: &IpAddr) -> SleuthResult<serde_json::Value> {
+        if let Some(data) = self.lookup_cache.get(ip) {
+            return Ok(data);
+        }
+
+        let response = self
+            .rate_limiter
+            .send(self.ipinfo_request(url))
+            .await
+            .map_err(|e| SleuthError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| SleuthError::NetworkError(e.to_string()))?;
+        self.capture.record(
+            defaults::providers::IPINFO,
+            url,
+            &ip.to_string(),
+            Some(status.as_u16()),
+            &body,
+        );
+
+        if !status.is_success() {
+            return match status.as_u16() {
+                404 => Err(SleuthError::NotFound(ip.to_string())),
+                429 => Err(SleuthError::RateLimited),
+                _ => Err(SleuthError::ApiError(format!("Status {status}"))),
+            };
+        }
+
+        let data: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| SleuthError::ParseError(e.to_string()))?;
+
+        // Reserved addresses come back as a bare `"bogon": true` document
+        if data.get("bogon").and_then(serde_json::Value::as_bool) == Some(true) {
+            return Err(SleuthError::NotFound(ip.to_string()));
+        }
+
+        self.lookup_cache.insert(ip, &body);
+        Ok(data)
+    }
+
+    /// Keep the raw ipinfo.io responses in the given capture
+    ///
+    /// # Arguments
+    ///
+    /// * `capture` - The capture, possibly shared with a judge
+    ///
+    /// # Returns
+    ///
+    /// Self with the capture set
+    #[must_use]
+    pub fn with_response_capture(mut self, capture: Arc<ResponseCapture>) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Get the capture ipinfo.io responses are kept in
+    #[must_use]
+    pub fn response_capture(&self) -> &Arc<ResponseCapture> {
+        &self.capture
+    }
+
+    /// Share a cache of looked up documents
+    ///
+    /// The cache is shared with the sleuth's ownership lookup, so an
+    /// address looked up by either isn't requested again by the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - The cache, possibly shared with other sleuths
+    ///
+    /// # Returns
+    ///
+    /// Self with the cache set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::inspection::Sleuth;
+    /// use gooty_proxy::inspection::lookup_cache::LookupCache;
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// let cache = Arc::new(LookupCache::new(Duration::from_secs(600)));
+    /// let sleuth = Sleuth::new().with_lookup_cache(Arc::clone(&cache));
+    /// assert!(Arc::ptr_eq(sleuth.lookup_cache(), &cache));
+    /// ```
+    #[must_use]
+    pub fn with_lookup_cache(mut self, cache: Arc<LookupCache>) -> Self {
+        self.lookup_cache = cache;
+        self.rebuild_ownership_lookup()
+    }
+
+    /// Get the cache looked up documents are kept in
+    #[must_use]
+    pub fn lookup_cache(&self) -> &Arc<LookupCache> {
+        &self.lookup_cache
+    }
+
+    /// Pace lookups with a shared rate limiter
+    ///
+    /// The limiter is shared with the sleuth's ownership lookup. Lookups
+    /// answered with 429 Too Many Requests are retried after a backoff, so
+    /// bursts of enrichment slow down instead of failing with
+    /// `SleuthError::RateLimited`.
+    ///
+    /// # Arguments
+    ///
+    /// * `limiter` - The limiter, possibly shared with other sleuths
+    ///
+    /// # Returns
+    ///
+    /// Self with the limiter set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::inspection::Sleuth;
+    /// use gooty_proxy::io::http::{RateLimitConfig, RateLimiter};
+    /// use std::sync::Arc;
+    ///
+    /// let limiter = Arc::new(RateLimiter::new(RateLimitConfig {
+    ///     requests_per_sec: 1.0,
+    ///     ..RateLimitConfig::default()
+    /// }));
+    /// let sleuth = Sleuth::new().with_rate_limiter(Arc::clone(&limiter));
+    /// assert!(Arc::ptr_eq(sleuth.rate_limiter(), &limiter));
+    /// ```
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self.rebuild_ownership_lookup()
+    }
+
+    /// Get the limiter lookups are paced by
+    #[must_use]
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
+    }
+
+    /// Get the tracker lookups are accounted in
+    #[must_use]
+    pub fn usage_tracker(&self) -> &Arc<UsageTracker> {
+        &self.usage
+    }
+
+    /// Use the given cloud range database to classify addresses
+    ///
+    /// # Arguments
+    ///
+    /// * `cloud_ranges` - The database, possibly shared with other sleuths
+    ///
+    /// # Returns
+    ///
+    /// Self with the database set
+    #[must_use]
+    pub fn with_cloud_ranges(mut self, cloud_ranges: Arc<CloudRanges>) -> Self {
+        self.cloud_ranges = cloud_ranges;
+        self
+    }
+
+    /// Answer lookups from local `MaxMind` databases
+    ///
+    /// Addresses the databases know are enriched without any request, which
+    /// keeps bulk enrichment clear of ipinfo.io's rate limits. Addresses
+    /// they don't know are still looked up at ipinfo.io.
+    ///
+    /// # Arguments
+    ///
+    /// * `geoip` - The databases, possibly shared with other sleuths
+    ///
+    /// # Returns
+    ///
+    /// Self with the databases set
+    #[cfg(feature = "geoip")]
+    #[must_use]
+    pub fn with_geoip(mut self, geoip: Arc<GeoIpDatabase>) -> Self {
+        self.geoip = Some(geoip);
+        self
+    }
+
+    /// Get the cloud range database addresses are classified with
+    #[must_use]
+    pub fn cloud_ranges(&self) -> &Arc<CloudRanges> {
+        &self.cloud_ranges
+    }
+
+    /// Check whether an address lies in a cloud provider's published ranges
+    ///
+    /// Doesn't make any requests; the ranges must have been downloaded with
+    /// `CloudRanges::refresh` or restored from a cache beforehand.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The IP address to check
+    ///
+    /// # Returns
+    ///
+    /// The cloud provider, or None if the address isn't in any known range
+    #[must_use]
+    pub fn is_cloud_ip(&self, ip: &IpAddr) -> Option<CloudProvider> {
+        self.cloud_ranges.is_cloud_ip(ip)
     }
 
     /// Lookup hostname for an IP address using DNS reverse lookup
@@ -192,32 +850,12 @@ impl Sleuth {
     /// * The API returns an error response
     /// * The response cannot be parsed
     pub async fn lookup_hostname(&self, ip: &IpAddr) -> SleuthResult<Option<String>> {
+        let ip = &lookup_target(ip)?;
+
         // Use ipinfo.io's free API to get hostname information
         let url = format!("https://ipinfo.io/{ip}/json");
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| SleuthError::NetworkError(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return match response.status().as_u16() {
-                404 => Err(SleuthError::NotFound(ip.to_string())),
-                429 => Err(SleuthError::RateLimited),
-                _ => Err(SleuthError::ApiError(format!(
-                    "Status {}",
-                    response.status()
-                ))),
-            };
-        }
-
-        let data: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| SleuthError::ParseError(e.to_string()))?;
+        let data = self.ipinfo_json(&url, ip).await?;
 
         let hostname = data
             .get("hostname")
@@ -247,32 +885,12 @@ impl Sleuth {
     /// * The API returns an error response
     /// * The response cannot be parsed
     pub async fn lookup_cidr(&self, ip: &IpAddr) -> SleuthResult<Option<String>> {
+        let ip = &lookup_target(ip)?;
+
         // Use ipinfo.io's free API to get network information
         let url = format!("https://ipinfo.io/{ip}/json");
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| SleuthError::NetworkError(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return match response.status().as_u16() {
-                404 => Err(SleuthError::NotFound(ip.to_string())),
-                429 => Err(SleuthError::RateLimited),
-                _ => Err(SleuthError::ApiError(format!(
-                    "Status {}",
-                    response.status()
-                ))),
-            };
-        }
-
-        let data: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| SleuthError::ParseError(e.to_string()))?;
+        let data = self.ipinfo_json(&url, ip).await?;
 
         let cidr = data.get("cidr").and_then(|v| v.as_str()).map(String::from);
 
@@ -346,32 +964,12 @@ impl Sleuth {
     /// * The API returns an error response
     /// * The response cannot be parsed
     pub async fn lookup_location(&self, ip: &IpAddr) -> SleuthResult<Option<Location>> {
+        let ip = &lookup_target(ip)?;
+
         // Use ipinfo.io's free API to get location information
         let url = format!("https://ipinfo.io/{ip}/json");
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| SleuthError::NetworkError(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return match response.status().as_u16() {
-                404 => Err(SleuthError::NotFound(ip.to_string())),
-                429 => Err(SleuthError::RateLimited),
-                _ => Err(SleuthError::ApiError(format!(
-                    "Status {}",
-                    response.status()
-                ))),
-            };
-        }
-
-        let data: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| SleuthError::ParseError(e.to_string()))?;
+        let data = self.ipinfo_json(&url, ip).await?;
 
         let city = data.get("city").and_then(|v| v.as_str()).map(String::from);
         let region = data
@@ -407,6 +1005,8 @@ impl Sleuth {
     ///
     /// Performs a single API call to retrieve all available information about an IP address
     /// and combines it with data from other lookups to create a comprehensive profile.
+    /// With local databases set by `with_geoip`, addresses they know are answered
+    /// from them without any request.
     ///
     /// # Arguments
     ///
@@ -419,36 +1019,46 @@ impl Sleuth {
     /// # Errors
     ///
     /// Returns an error if:
+    /// * The address is not publicly routable
     /// * The network request fails
     /// * The API returns an error response
     /// * The response cannot be parsed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::errors::SleuthError;
+    /// use gooty_proxy::inspection::Sleuth;
+    /// use std::net::IpAddr;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let sleuth = Sleuth::new();
+    ///
+    ///     // Unique local and documentation addresses are refused up front
+    ///     for ip in ["fd12:3456::1", "2001:db8::8", "::ffff:192.168.0.1"] {
+    ///         let ip: IpAddr = ip.parse().unwrap();
+    ///         let result = sleuth.lookup_ip_metadata(&ip).await;
+    ///         assert!(matches!(result, Err(SleuthError::NotFound(_))));
+    ///     }
+    /// }
+    /// ```
     pub async fn lookup_ip_metadata(&self, ip: &IpAddr) -> SleuthResult<IpMetadata> {
-        // Use ipinfo.io's free API to get all information in one request
-        let url = format!("https://ipinfo.io/{ip}/json");
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| SleuthError::NetworkError(e.to_string()))?;
+        let original = *ip;
+        let ip = &lookup_target(ip)?;
 
-        if !response.status().is_success() {
-            return match response.status().as_u16() {
-                404 => Err(SleuthError::NotFound(ip.to_string())),
-                429 => Err(SleuthError::RateLimited),
-                _ => Err(SleuthError::ApiError(format!(
-                    "Status {}",
-                    response.status()
-                ))),
-            };
+        #[cfg(feature = "geoip")]
+        if let Some(mut metadata) = self.geoip.as_ref().and_then(|geoip| geoip.lookup(ip)) {
+            metadata.ip = original;
+            metadata.cloud_provider = self.cloud_ranges.is_cloud_ip(ip);
+            metadata.is_hosting = metadata.cloud_provider.map(|_| true);
+            return Ok(metadata);
         }
 
-        let data: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| SleuthError::ParseError(e.to_string()))?;
+        // Use ipinfo.io's free API to get all information in one request
+        let url = format!("https://ipinfo.io/{ip}/json");
+
+        let data = self.ipinfo_json(&url, ip).await?;
 
         let hostname = data
             .get("hostname")
@@ -460,53 +1070,65 @@ impl Sleuth {
         // Use the ownership lookup for organization information
         let organization = (self.lookup_organization(ip).await).unwrap_or_default();
 
-        // Parse location information
-        let city = data.get("city").and_then(|v| v.as_str()).map(String::from);
-        let region = data
-            .get("region")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-        let postal = data
-            .get("postal")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-        let country = data
-            .get("country")
-            .and_then(|v| v.as_str())
-            .map(String::from);
-
-        let location =
-            if city.is_some() || region.is_some() || postal.is_some() || country.is_some() {
-                Some(Location {
-                    city,
-                    state: region,
-                    postal_code: postal,
-                    country,
-                    facility_name: None,
-                })
-            } else {
-                None
-            };
+        let location = location_from(&data);
 
         // Extract ASN from org field
         let asn = (self.lookup_asn(ip).await).unwrap_or_default();
 
+        // Fields only reported by paid plans
+        let privacy = data
+            .get("privacy")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+        let company: Option<CompanyInfo> = data
+            .get("company")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+        let is_anycast = data.get("anycast").and_then(serde_json::Value::as_bool);
+        let cloud_provider = self.cloud_ranges.is_cloud_ip(ip);
+        let is_hosting = if cloud_provider.is_some() {
+            Some(true)
+        } else {
+            hosting_flag(&data, privacy.as_ref(), company.as_ref())
+        };
+        // Paid plans report a carrier for mobile addresses only
+        let is_mobile = if data.get("carrier").is_some() {
+            Some(true)
+        } else if self.has_api_token(defaults::providers::IPINFO) && privacy.is_some() {
+            Some(false)
+        } else {
+            None
+        };
+        let (abuse_email, registry) = network_contacts(&data);
+
         // Create network info if we have any relevant data
-        let network = if cidr.is_some() || organization.is_some() || location.is_some() {
+        let network = if cidr.is_some()
+            || organization.is_some()
+            || location.is_some()
+            || abuse_email.is_some()
+            || registry.is_some()
+        {
             Some(NetworkInfo {
                 cidr,
                 organization,
                 location,
+                abuse_email,
+                registry,
             })
         } else {
             None
         };
 
         Ok(IpMetadata {
-            ip: *ip,
+            ip: original,
             hostname,
             network,
             asn,
+            privacy,
+            company,
+            is_anycast,
+            is_hosting,
+            is_mobile,
+            cloud_provider,
         })
     }
 
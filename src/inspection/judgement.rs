@@ -5,13 +5,29 @@
 //!
 //! ## Components
 //!
-//! * **Judge** - A struct for determining the anonymity level of proxies
+//! * **Judge** - A struct for determining the anonymity level of proxies (`judge` feature)
+//! * **`JudgementRetryPolicy`** - How often and how patiently a proxy is judged
+//! * **`JudgementReport`** - The outcome of a judgement with its attempt history
+//!   and the verdict of every judge consulted
+//! * **`JudgeBatchSummary`** - Aggregate statistics of judging a list of proxies
+//! * **`JudgeBaseline`** - The direct latency to a judge, measured without a proxy
+//! * **`parse_judge_headers`** and **`classify_anonymity`** - Turn a judge
+//!   response into the headers it echoes, and those into an anonymity level
+//! * **`AnonymityRule`** - A configured rule demoting proxies that send a header
+//! * **`LocalJudge`** - A judge served by gooty itself, for validating
+//!   without the public judges (`judge` feature)
+//!
+//! Proxy latencies include the distance to the judge itself. The judge
+//! therefore measures a direct baseline to every judge URL, refreshed once it
+//! grows stale, and records proxy latency as the overhead above it. This keeps
+//! latencies comparable across judges and runs.
 //!
 //! ## Examples
 //!
 //! ```
 //! use gooty_proxy::inspection::Judge;
 //! use gooty_proxy::definitions::proxy::Proxy;
+//! use gooty_proxy::definitions::types::Port;
 //! use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
 //! use std::net::{IpAddr, Ipv4Addr};
 //!
@@ -21,7 +37,7 @@
 //!     let mut proxy = Proxy::new(
 //!         ProxyType::Http,
 //!         IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)),
-//!         8080,
+//!         Port::new(8080).unwrap(),
 //!         AnonymityLevel::Anonymous,
 //!     );
 //!     let anonymity = judge.judge_proxy(&mut proxy).await?;
@@ -31,218 +47,2314 @@
 //! ```
 
 use crate::definitions::{
-    self,
-    enums::AnonymityLevel,
-    errors::{JudgementError, JudgementResult},
+    defaults,
+    enums::{AnonymityLevel, ConsensusRule, JudgeScheme, JudgementMode, ProxyType},
+};
+#[cfg(feature = "judge")]
+use crate::definitions::{
+    enums::IdentityProfile,
+    errors::{JudgementError, JudgementResult, ListenerError, ListenerResult, RequestorError},
     proxy::Proxy,
 };
-use crate::io::http::Requestor;
+#[cfg(feature = "judge")]
+use crate::inspection::capture::ResponseCapture;
+#[cfg(feature = "judge")]
+use crate::io::http::{RequestProfile, Requestor};
+#[cfg(feature = "judge")]
+use crate::io::serving::{RequestHead, read_request_head};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "judge")]
+use futures::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+#[cfg(feature = "judge")]
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::net::{IpAddr, SocketAddr};
+#[cfg(feature = "judge")]
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+#[cfg(feature = "judge")]
+use std::time::Instant;
+#[cfg(feature = "judge")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "judge")]
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(feature = "judge")]
+use tokio::sync::OnceCell;
 
-/// Service for judging proxies to determine their anonymity level
+/// Retry policy for judging a single proxy
 ///
-/// This service provides functionality to test proxies against judge services
-/// and analyze their responses to determine the level of anonymity they provide.
-/// The anonymity levels are categorized as Transparent, Anonymous, or Elite,
-/// based on how much information about the client the proxy reveals.
+/// Separate from the requestor's retries, the policy decides how many times a
+/// proxy is judged before it counts as failed, whether later attempts move on
+/// to another judge URL, and how the timeout grows between attempts.
 ///
-/// # Examples
+/// Judges can disagree, for example when one of them strips forwarding
+/// headers. The policy therefore also sets how many judges each proxy is
+/// checked against, and how their verdicts are combined.
 ///
-/// ```no_run
-/// use gooty_proxy::inspection::Judge;
-/// use gooty_proxy::definitions::proxy::Proxy;
-/// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
-/// use std::net::{IpAddr, Ipv4Addr};
+/// Judges far from a proxy make slow proxies time out. With `nearest_judge`
+/// set, the round trip to a few judges is measured the first time a proxy is
+/// judged, and its judgements start with the nearest one from then on.
 ///
-/// #[tokio::main]
-/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
-///     // Create a judge service
-///     let judge = Judge::new().await?;
+/// Plain HTTP judges can't tell whether a proxy tunnels TLS. The policy's
+/// `judge_scheme` picks judges by scheme; full mode includes HTTPS judges, and
+/// the outcome over TLS is recorded in the proxy's `tls_capable`.
 ///
-///     // Create a proxy to test
-///     let mut proxy = Proxy::new(
-///         ProxyType::Http,
-///         IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)),
-///         8080,
-///         AnonymityLevel::Anonymous, // Initial assumption
-///     );
+/// SOCKS handshakes take longer than HTTP ones, so a timeout tuned for HTTP
+/// proxies marks working SOCKS proxies dead. `type_timeout_secs` gives each
+/// proxy type its own first timeout.
 ///
-///     // Test the proxy and determine its actual anonymity level
-///     let anonymity = judge.judge_proxy(&mut proxy).await?;
-///     println!("Proxy anonymity level: {}", anonymity);
+/// # Examples
 ///
-///     Ok(())
-/// }
 /// ```
-pub struct Judge {
-    /// URLs of proxy judge services
-    judge_urls: Vec<String>,
+/// use gooty_proxy::definitions::enums::{JudgementMode, ProxyType};
+/// use gooty_proxy::inspection::judgement::JudgementRetryPolicy;
+/// use std::time::Duration;
+///
+/// let policy = JudgementRetryPolicy::for_mode(JudgementMode::Full);
+/// assert_eq!(policy.attempts, 3);
+///
+/// // Timeouts escalate on every retry
+/// assert!(policy.timeout_for_attempt(1) > policy.timeout_for_attempt(0));
+/// assert_eq!(
+///     policy.timeout_for_attempt(0),
+///     Duration::from_secs(policy.timeout_secs)
+/// );
+///
+/// // SOCKS5 proxies get longer to answer
+/// let policy = policy.with_type_timeout(ProxyType::Socks5, 20);
+/// assert_eq!(policy.timeout_for(ProxyType::Socks5, 0), Duration::from_secs(20));
+/// assert_eq!(policy.timeout_for(ProxyType::Http, 0), policy.timeout_for_attempt(0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JudgementRetryPolicy {
+    /// Total number of attempts, including the first one
+    pub attempts: u32,
 
-    /// Requestor for making HTTP requests
-    requestor: Requestor,
+    /// Whether each retry uses the next judge URL
+    pub rotate_judges: bool,
+
+    /// Timeout of the first attempt in seconds
+    pub timeout_secs: u64,
+
+    /// Timeout of the first attempt in seconds by proxy type, overriding
+    /// `timeout_secs`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub type_timeout_secs: HashMap<ProxyType, u64>,
+
+    /// Factor applied to the timeout after each failed attempt
+    pub timeout_multiplier: f64,
+
+    /// Number of distinct judges each proxy is checked against
+    #[serde(default = "default_consensus_judges")]
+    pub consensus_judges: usize,
+
+    /// How the verdicts of several judges are combined
+    #[serde(default)]
+    pub consensus_rule: ConsensusRule,
+
+    /// Whether judgements start with the judge nearest to the proxy
+    #[serde(default)]
+    pub nearest_judge: bool,
+
+    /// Which judges are used, by URL scheme
+    #[serde(default)]
+    pub judge_scheme: JudgeScheme,
 }
 
-impl Judge {
-    /// Create a new judge with default configuration
-    ///
-    /// Initializes a Judge service with the default set of proxy judge URLs
-    /// and a requestor configured with the default validation timeout.
+fn default_consensus_judges() -> usize {
+    defaults::judgement_retry::QUICK_CONSENSUS_JUDGES
+}
+
+impl Default for JudgementRetryPolicy {
+    fn default() -> Self {
+        Self::for_mode(JudgementMode::Quick)
+    }
+}
+
+impl JudgementRetryPolicy {
+    /// Returns the default policy of a judgement mode
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// A new Judge instance with default configuration
+    /// * `mode` - The judgement mode
     ///
-    /// # Errors
+    /// # Returns
     ///
-    /// Returns an error if the Requestor cannot be created
-    pub fn new() -> JudgementResult<Self> {
-        let judge_urls = crate::defaults::PROXY_JUDGE_URLS
-            .iter()
-            .map(|url| (*url).to_string())
-            .collect();
-
-        let requestor = Requestor::with_timeout(crate::defaults::DEFAULT_VALIDATION_TIMEOUT_SECS)?;
+    /// A single attempt for `None`, and escalating retries for `Quick` and
+    /// `Full`. Only `Full` also judges over HTTPS.
+    #[must_use]
+    pub fn for_mode(mode: JudgementMode) -> Self {
+        let (attempts, consensus_judges, judge_scheme) = match mode {
+            JudgementMode::None => (1, 1, JudgeScheme::Http),
+            JudgementMode::Quick => (
+                defaults::judgement_retry::QUICK_ATTEMPTS,
+                defaults::judgement_retry::QUICK_CONSENSUS_JUDGES,
+                JudgeScheme::Http,
+            ),
+            JudgementMode::Full => (
+                defaults::judgement_retry::FULL_ATTEMPTS,
+                defaults::judgement_retry::FULL_CONSENSUS_JUDGES,
+                JudgeScheme::Any,
+            ),
+        };
 
-        Ok(Judge {
-            judge_urls,
-            requestor,
-        })
+        JudgementRetryPolicy {
+            attempts,
+            rotate_judges: true,
+            timeout_secs: defaults::DEFAULT_VALIDATION_TIMEOUT_SECS,
+            type_timeout_secs: HashMap::new(),
+            timeout_multiplier: defaults::judgement_retry::TIMEOUT_MULTIPLIER,
+            consensus_judges,
+            consensus_rule: ConsensusRule::default(),
+            nearest_judge: false,
+            judge_scheme,
+        }
     }
 
-    /// Judge a proxy to determine its anonymity level
-    ///
-    /// Makes a request through the provided proxy to a judge service and
-    /// analyzes the response to determine the proxy's anonymity level.
-    /// The proxy is also updated with latency information.
+    /// Returns the timeout of an attempt
     ///
     /// # Arguments
     ///
-    /// * `proxy` - The proxy to judge, which will be modified to record check statistics
+    /// * `attempt` - Zero-based index of the attempt
     ///
     /// # Returns
     ///
-    /// The determined anonymity level of the proxy
+    /// The first attempt's timeout, multiplied once for every earlier attempt
+    #[must_use]
+    pub fn timeout_for_attempt(&self, attempt: u32) -> Duration {
+        self.escalated_timeout(self.timeout_secs, attempt)
+    }
+
+    /// Returns the timeout of an attempt at judging a proxy of a type
     ///
-    /// # Errors
+    /// # Arguments
     ///
-    /// Returns an error if:
-    /// * No judge URL is available
-    /// * The request through the proxy fails
-    /// * The response analysis fails
-    pub async fn judge_proxy(&self, proxy: &mut Proxy) -> JudgementResult<AnonymityLevel> {
-        // Get a judge URL to use
-        let judge_url = self
-            .judge_urls
-            .first()
-            .ok_or(JudgementError::NoJudgeUrl)?
-            .to_string();
+    /// * `proxy_type` - Protocol of the proxy judged
+    /// * `attempt` - Zero-based index of the attempt
+    ///
+    /// # Returns
+    ///
+    /// Like `timeout_for_attempt`, starting from the type's own timeout
+    /// where one is set
+    #[must_use]
+    pub fn timeout_for(&self, proxy_type: ProxyType, attempt: u32) -> Duration {
+        let first = self
+            .type_timeout_secs
+            .get(&proxy_type)
+            .copied()
+            .unwrap_or(self.timeout_secs);
+        self.escalated_timeout(first, attempt)
+    }
 
-        // Use a standard user agent for consistency
-        let user_agent = "Mozilla/5.0 (compatible; Gooty-Proxy/0.1)";
+    /// Sets the timeout of the first attempt for a proxy type
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy_type` - Protocol the timeout applies to
+    /// * `timeout_secs` - Timeout of the first attempt in seconds
+    ///
+    /// # Returns
+    ///
+    /// Self with the timeout set
+    #[must_use]
+    pub fn with_type_timeout(mut self, proxy_type: ProxyType, timeout_secs: u64) -> Self {
+        self.type_timeout_secs.insert(proxy_type, timeout_secs);
+        self
+    }
 
-        // Attempt to make a request through the proxy
-        let start = std::time::Instant::now();
-        let response = self
-            .requestor
-            .get_with_proxy(&judge_url, user_agent, proxy)
-            .await?;
+    /// Multiplies a first timeout once for every earlier attempt
+    fn escalated_timeout(&self, first_secs: u64, attempt: u32) -> Duration {
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_wrap)]
+        let secs = first_secs as f64 * self.timeout_multiplier.max(1.0).powi(attempt as i32);
+        Duration::try_from_secs_f64(secs).unwrap_or(Duration::MAX)
+    }
+}
 
-        // Record the latency
-        let latency = start.elapsed().as_millis();
-        proxy.record_check(latency);
+/// Judgement retry policies of each judgement mode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JudgementRetryConfig {
+    /// Policy used in quick mode
+    pub quick: JudgementRetryPolicy,
 
-        // Analyze the response to determine anonymity level
-        let anonymity = Self::determine_anonymity_level(&response, proxy);
+    /// Policy used in full mode
+    pub full: JudgementRetryPolicy,
+}
 
-        Ok(anonymity)
+impl Default for JudgementRetryConfig {
+    fn default() -> Self {
+        JudgementRetryConfig {
+            quick: JudgementRetryPolicy::for_mode(JudgementMode::Quick),
+            full: JudgementRetryPolicy::for_mode(JudgementMode::Full),
+        }
     }
+}
 
-    /// Determine the anonymity level from a judge response
-    ///
-    /// Analyzes the response from a proxy judge service to determine
-    /// the anonymity level of the proxy.
-    ///
-    /// # Anonymity Levels
-    ///
-    /// * `Transparent` - The proxy reveals the client's IP address in headers
-    /// * `Anonymous` - The proxy reveals it is a proxy but hides the client's IP
-    /// * `Elite` - The proxy does not reveal any proxy information or client IP
+impl JudgementRetryConfig {
+    /// Returns the policy of a judgement mode
     ///
     /// # Arguments
     ///
-    /// * `response` - The response from the proxy judge service
-    /// * `proxy` - The proxy that was used for the request
+    /// * `mode` - The judgement mode
     ///
     /// # Returns
     ///
-    /// The determined anonymity level
-    fn determine_anonymity_level(
-        response: &str,
-        proxy: &Proxy,
-    ) -> definitions::enums::AnonymityLevel {
-        // Check if our proxy IP appears in the response
-        let proxy_ip = proxy.address.to_string();
-
-        // Headers to check for proxy information
-        let headers_to_check = [
-            "HTTP_VIA",
-            "HTTP_X_FORWARDED_FOR",
-            "HTTP_FORWARDED",
-            "HTTP_X_REAL_IP",
-            "VIA",
-            "X_FORWARDED_FOR",
-            "FORWARDED",
-        ];
-
-        // Check if any headers reveal proxy information
-        let mut found_proxy_headers = false;
-        let mut found_ip_in_headers = false;
-
-        // Simple parsing - in a real implementation we'd use a proper parser
-        for header in &headers_to_check {
-            if response.contains(header) {
-                found_proxy_headers = true;
-
-                // Check if our IP is exposed in this header
-                if response.contains(&proxy_ip) {
-                    found_ip_in_headers = true;
-                    break;
-                }
+    /// The configured policy, or a single attempt for `None`
+    #[must_use]
+    pub fn for_mode(&self, mode: JudgementMode) -> JudgementRetryPolicy {
+        match mode {
+            JudgementMode::None => JudgementRetryPolicy::for_mode(JudgementMode::None),
+            JudgementMode::Quick => self.quick.clone(),
+            JudgementMode::Full => self.full.clone(),
+        }
+    }
+}
+
+/// A single attempt at judging a proxy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JudgementAttempt {
+    /// Judge URL the attempt was made against
+    pub judge_url: String,
+
+    /// Timeout of the attempt
+    pub timeout: Duration,
+
+    /// Latency of a successful attempt in milliseconds, above the judge's
+    /// baseline if one is known
+    pub latency_ms: Option<u128>,
+
+    /// Direct latency to the judge subtracted from `latency_ms`
+    #[serde(default)]
+    pub baseline_ms: Option<u128>,
+
+    /// Error of a failed attempt
+    pub error: Option<String>,
+}
+
+/// Direct latency to a judge, measured without a proxy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JudgeBaseline {
+    /// Fastest direct latency in milliseconds, or `None` if the judge could
+    /// not be reached directly
+    pub latency_ms: Option<u128>,
+
+    /// When the baseline was measured
+    pub measured_at: DateTime<Utc>,
+}
+
+impl JudgeBaseline {
+    /// Checks whether the baseline is older than `defaults::judge_baseline::MAX_AGE_SECS`
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        Utc::now() - self.measured_at
+            > chrono::Duration::seconds(defaults::judge_baseline::MAX_AGE_SECS)
+    }
+}
+
+/// Anonymity level a single judge found
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JudgeVerdict {
+    /// Judge URL that gave the verdict
+    pub judge_url: String,
+
+    /// Anonymity level the judge found
+    pub anonymity: AnonymityLevel,
+}
+
+/// Outcome of judging a proxy, with every attempt made
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JudgementReport {
+    /// Anonymity level agreed on, or `None` if every attempt failed
+    pub anonymity: Option<AnonymityLevel>,
+
+    /// The attempts made, in order
+    pub attempts: Vec<JudgementAttempt>,
+
+    /// The verdict of every judge that answered
+    #[serde(default)]
+    pub verdicts: Vec<JudgeVerdict>,
+
+    /// Whether the judges found different anonymity levels
+    #[serde(default)]
+    pub disagreement: bool,
+
+    /// Name of the request profile the judgement requests were sent with
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+impl JudgementReport {
+    /// Checks whether the proxy was judged successfully
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.anonymity.is_some()
+    }
+}
+
+/// Combines the verdicts of several judges into one anonymity level
+///
+/// # Arguments
+///
+/// * `levels` - The level found by each judge
+/// * `rule` - How disagreeing levels are resolved
+///
+/// # Returns
+///
+/// The agreed level, or `None` if there are no verdicts
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, ConsensusRule};
+/// use gooty_proxy::inspection::judgement::resolve_consensus;
+///
+/// let levels = [
+///     AnonymityLevel::Elite,
+///     AnonymityLevel::Elite,
+///     AnonymityLevel::Transparent,
+/// ];
+/// assert_eq!(
+///     resolve_consensus(&levels, ConsensusRule::Pessimistic),
+///     Some(AnonymityLevel::Transparent)
+/// );
+/// assert_eq!(
+///     resolve_consensus(&levels, ConsensusRule::Majority),
+///     Some(AnonymityLevel::Elite)
+/// );
+/// ```
+#[must_use]
+pub fn resolve_consensus(levels: &[AnonymityLevel], rule: ConsensusRule) -> Option<AnonymityLevel> {
+    match rule {
+        ConsensusRule::Pessimistic => levels.iter().copied().min(),
+        ConsensusRule::Optimistic => levels.iter().copied().max(),
+        ConsensusRule::Majority => {
+            let mut counts: HashMap<AnonymityLevel, usize> = HashMap::new();
+            for level in levels {
+                *counts.entry(*level).or_insert(0) += 1;
             }
+            // Ties go to the less anonymous level
+            counts
+                .into_iter()
+                .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+                .map(|(level, _)| level)
         }
+    }
+}
 
-        // Determine anonymity level
-        if found_ip_in_headers {
-            // IP is visible in headers - transparent proxy
-            AnonymityLevel::Transparent
-        } else if found_proxy_headers {
-            // Proxy headers exist but don't reveal our IP - anonymous proxy
-            AnonymityLevel::Anonymous
-        } else {
-            // No proxy information revealed - elite proxy
-            AnonymityLevel::Elite
+/// Parses a judge response into the request headers it echoes
+///
+/// Judges such as azenv list the request environment one variable per line,
+/// as `KEY = value`, while others echo headers as `Key: value`. Both forms
+/// are accepted, also when wrapped in HTML. Keys are normalized to the CGI
+/// form without the `HTTP_` prefix, so `X-Forwarded-For: 1.2.3.4` and
+/// `HTTP_X_FORWARDED_FOR = 1.2.3.4` both yield `X_FORWARDED_FOR`. Lines that
+/// are not a variable, such as page text, are skipped.
+///
+/// # Arguments
+///
+/// * `body` - The body of the judge response
+///
+/// # Returns
+///
+/// The parsed headers, keyed by normalized name
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::inspection::judgement::parse_judge_headers;
+///
+/// // As returned by an azenv.php judge
+/// let azenv = "<pre>\nREMOTE_ADDR = 203.0.113.7\nREMOTE_PORT = 51234\n\
+///              HTTP_HOST = judge.example\nHTTP_ACCEPT = text/html;q=0.9\n\
+///              HTTP_VIA = 1.1 squid\nREQUEST_METHOD = GET\n</pre>";
+/// let headers = parse_judge_headers(azenv);
+/// assert_eq!(headers["REMOTE_ADDR"], "203.0.113.7");
+/// assert_eq!(headers["ACCEPT"], "text/html;q=0.9");
+/// assert_eq!(headers["VIA"], "1.1 squid");
+///
+/// // A judge echoing raw headers
+/// let echo = "Host: judge.example\r\nX-Forwarded-For: 198.51.100.4\r\n";
+/// assert_eq!(parse_judge_headers(echo)["X_FORWARDED_FOR"], "198.51.100.4");
+///
+/// // Text mentioning a header name is not a header
+/// let page = "<p>This judge shows your X_FORWARDED_FOR header.</p>";
+/// assert!(parse_judge_headers(page).is_empty());
+/// ```
+#[must_use]
+pub fn parse_judge_headers(body: &str) -> BTreeMap<String, String> {
+    let mut headers = BTreeMap::new();
+    for line in strip_tags(body).lines() {
+        let Some(split) = line.find(['=', ':']) else {
+            continue;
+        };
+        let key = line[..split].trim();
+        let value = line[split + 1..].trim();
+        let is_name = !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !is_name {
+            continue;
         }
+
+        headers.insert(normalize_header_name(key), value.to_string());
     }
+    headers
+}
 
-    /// Add a judge URL
-    ///
-    /// Adds a new URL to the list of judge services, if it's not already present.
+/// Classifies a proxy's anonymity from the headers a judge received
+///
+/// * `Transparent` - A forwarding header carries the checker's own public
+///   address
+/// * `Anonymous` - Forwarding headers reveal a proxy was used, but not the
+///   checker's address; chained hops and spoofed or private addresses only
+///   give the proxy away
+/// * `Elite` - No forwarding headers were received
+///
+/// # Arguments
+///
+/// * `headers` - Headers parsed with [`parse_judge_headers`]
+/// * `real_ip` - The public address of the machine that ran the check
+///
+/// # Returns
+///
+/// The anonymity level the headers show
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::AnonymityLevel;
+/// use gooty_proxy::inspection::judgement::{classify_anonymity, parse_judge_headers};
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let real_ip = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 4));
+/// let classify = |body: &str| classify_anonymity(&parse_judge_headers(body), real_ip);
+///
+/// assert_eq!(classify("REMOTE_ADDR = 203.0.113.7\nHTTP_HOST = judge.example"), AnonymityLevel::Elite);
+/// assert_eq!(
+///     classify("REMOTE_ADDR = 203.0.113.7\nHTTP_VIA = 1.1 proxy (squid/4.10)"),
+///     AnonymityLevel::Anonymous
+/// );
+/// assert_eq!(
+///     classify("REMOTE_ADDR = 203.0.113.7\nHTTP_X_FORWARDED_FOR = 198.51.100.4, 203.0.113.7"),
+///     AnonymityLevel::Transparent
+/// );
+///
+/// // An upstream hop or a private address doesn't reveal the checker
+/// assert_eq!(
+///     classify("REMOTE_ADDR = 203.0.113.7\nHTTP_X_FORWARDED_FOR = 192.0.2.50"),
+///     AnonymityLevel::Anonymous
+/// );
+/// assert_eq!(
+///     classify("REMOTE_ADDR = 203.0.113.7\nHTTP_FORWARDED = for=\"[fd00::1]:4711\";proto=http"),
+///     AnonymityLevel::Anonymous
+/// );
+/// ```
+#[must_use]
+pub fn classify_anonymity(headers: &BTreeMap<String, String>, real_ip: IpAddr) -> AnonymityLevel {
+    classify_for(headers, Some(real_ip))
+}
+
+/// Finds the exit address a judge saw the request come from
+///
+/// That's the address in `REMOTE_ADDR`, which for backconnect proxies
+/// differs from the address the proxy was reached on.
+///
+/// # Arguments
+///
+/// * `headers` - Headers parsed with [`parse_judge_headers`]
+///
+/// # Returns
+///
+/// The address the judge saw, or `None` if the judge didn't report one
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::inspection::judgement::{exit_address, parse_judge_headers};
+///
+/// let headers = parse_judge_headers("REMOTE_ADDR = 203.0.113.7\nREMOTE_PORT = 51234");
+/// assert_eq!(exit_address(&headers), Some("203.0.113.7".parse().unwrap()));
+/// assert_eq!(exit_address(&parse_judge_headers("HTTP_HOST = judge.example")), None);
+/// ```
+#[must_use]
+pub fn exit_address(headers: &BTreeMap<String, String>) -> Option<IpAddr> {
+    headers
+        .get("REMOTE_ADDR")
+        .and_then(|value| addresses_in(value).into_iter().next())
+}
+
+/// Classifies anonymity like [`classify_anonymity`], with the checker's
+/// address possibly unknown
+///
+/// Without the checker's address, no forwarded address can be told to be
+/// it, so forwarding headers only make a proxy `Anonymous`.
+fn classify_for(headers: &BTreeMap<String, String>, real_ip: Option<IpAddr>) -> AnonymityLevel {
+    let forwarding: Vec<&str> = PROXY_HEADERS
+        .iter()
+        .filter_map(|name| headers.get(*name))
+        .map(String::as_str)
+        .collect();
+
+    if forwarding.is_empty() {
+        return AnonymityLevel::Elite;
+    }
+
+    let leaks_client = real_ip.is_some_and(|real_ip| {
+        forwarding
+            .iter()
+            .flat_map(|value| addresses_in(value))
+            .any(|ip| ip.to_canonical() == real_ip.to_canonical())
+    });
+    if leaks_client {
+        AnonymityLevel::Transparent
+    } else {
+        AnonymityLevel::Anonymous
+    }
+}
+
+/// A configured rule demoting proxies that send a matching header
+///
+/// Judges differ in the headers they echo, and some proxies identify
+/// themselves with headers the built-in classification does not know. Rules
+/// are applied after [`classify_anonymity`] and can only lower its level.
+///
+/// Header patterns are matched against parsed header names, so `X-Proxy-*`
+/// and `HTTP_X_PROXY_*` are the same pattern. A `*` matches any run of
+/// characters, and matching ignores case.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::AnonymityLevel;
+/// use gooty_proxy::inspection::judgement::{AnonymityRule, parse_judge_headers};
+///
+/// let rules = [
+///     AnonymityRule::new("X-BlueCoat-*", AnonymityLevel::Anonymous),
+///     AnonymityRule::new("X_Sucuri_ClientIP", AnonymityLevel::Transparent),
+///     AnonymityRule::new("User-Agent", AnonymityLevel::Anonymous).with_value("*proxy*"),
+/// ];
+///
+/// let headers = parse_judge_headers("HTTP_X_BLUECOAT_VIA = 4a2f\nHTTP_USER_AGENT = curl/8.5");
+/// assert_eq!(rules[0].matching_header(&headers), Some("X_BLUECOAT_VIA"));
+/// assert_eq!(rules[2].matching_header(&headers), None);
+/// assert_eq!(
+///     AnonymityRule::apply_all(&rules, AnonymityLevel::Elite, &headers),
+///     AnonymityLevel::Anonymous
+/// );
+///
+/// // Rules never raise a level
+/// assert_eq!(
+///     AnonymityRule::apply_all(&rules, AnonymityLevel::Transparent, &headers),
+///     AnonymityLevel::Transparent
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnonymityRule {
+    /// Pattern of the header names the rule applies to
+    pub header: String,
+
+    /// Pattern the header value must match, any value if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Highest anonymity level of a proxy sending a matching header
+    pub demote_to: AnonymityLevel,
+}
+
+impl AnonymityRule {
+    /// Creates a rule applying to any value of the matching headers
     ///
     /// # Arguments
     ///
-    /// * `url` - The URL of the judge service to add
-    pub fn add_judge_url(&mut self, url: String) {
-        if !self.judge_urls.contains(&url) {
-            self.judge_urls.push(url);
+    /// * `header` - Pattern of the header names the rule applies to
+    /// * `demote_to` - Highest anonymity level of a proxy sending a matching header
+    #[must_use]
+    pub fn new(header: &str, demote_to: AnonymityLevel) -> Self {
+        AnonymityRule {
+            header: header.to_string(),
+            value: None,
+            demote_to,
         }
     }
 
-    /// Get the current judge URLs
+    /// Restricts the rule to header values matching a pattern
+    #[must_use]
+    pub fn with_value(mut self, pattern: &str) -> Self {
+        self.value = Some(pattern.to_string());
+        self
+    }
+
+    /// Finds the first header the rule applies to
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - Headers parsed with [`parse_judge_headers`]
     ///
     /// # Returns
     ///
-    /// A slice containing all the judge URLs currently configured
+    /// The name of the matching header, or `None` if the rule does not apply
     #[must_use]
-    pub fn get_judge_urls(&self) -> &[String] {
-        &self.judge_urls
+    pub fn matching_header<'h>(&self, headers: &'h BTreeMap<String, String>) -> Option<&'h str> {
+        let pattern = normalize_header_name(&self.header);
+        headers
+            .iter()
+            .find(|(name, value)| {
+                wildcard_match(&pattern, name)
+                    && self
+                        .value
+                        .as_ref()
+                        .is_none_or(|pattern| wildcard_match(pattern, value))
+            })
+            .map(|(name, _)| name.as_str())
     }
+
+    /// Applies rules to a level found by the built-in classification
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - The configured rules
+    /// * `level` - The level found by [`classify_anonymity`]
+    /// * `headers` - Headers parsed with [`parse_judge_headers`]
+    ///
+    /// # Returns
+    ///
+    /// The lowest of `level` and the levels of the rules that apply
+    #[must_use]
+    pub fn apply_all(
+        rules: &[AnonymityRule],
+        level: AnonymityLevel,
+        headers: &BTreeMap<String, String>,
+    ) -> AnonymityLevel {
+        rules
+            .iter()
+            .filter(|rule| rule.matching_header(headers).is_some())
+            .map(|rule| rule.demote_to)
+            .fold(level, AnonymityLevel::min)
+    }
+}
+
+/// Normalizes a header name to its parsed form, like [`parse_judge_headers`]
+fn normalize_header_name(name: &str) -> String {
+    let name = name.trim().to_ascii_uppercase().replace('-', "_");
+    name.strip_prefix("HTTP_").unwrap_or(&name).to_string()
+}
+
+/// Checks whether text matches a pattern where `*` matches any run of
+/// characters, ignoring case
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    let text = text.to_ascii_lowercase();
+    let mut parts = pattern.split('*');
+
+    // Without a wildcard the whole text must match
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// Headers, in parsed form, that proxies add to forwarded requests
+const PROXY_HEADERS: [&str; 10] = [
+    "VIA",
+    "X_FORWARDED_FOR",
+    "FORWARDED",
+    "FORWARDED_FOR",
+    "X_FORWARDED",
+    "X_REAL_IP",
+    "CLIENT_IP",
+    "X_CLIENT_IP",
+    "X_CLUSTER_CLIENT_IP",
+    "X_PROXY_ID",
+];
+
+/// Finds the IP addresses in a header value
+///
+/// Handles lists such as `X-Forwarded-For` and the `for=` parameters of
+/// `Forwarded`, including quoted and bracketed addresses with ports.
+fn addresses_in(value: &str) -> Vec<IpAddr> {
+    value
+        .split([',', ';', '=', ' '])
+        .map(|token| token.trim_matches('"'))
+        .filter_map(|token| {
+            token
+                .parse::<IpAddr>()
+                .or_else(|_| token.parse::<SocketAddr>().map(|addr| addr.ip()))
+                .or_else(|_| token.trim_start_matches('[').trim_end_matches(']').parse())
+                .ok()
+        })
+        .collect()
+}
+
+/// Removes HTML tags from a judge response, keeping line breaks
+fn strip_tags(body: &str) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut tag: Option<String> = None;
+    for c in body.chars() {
+        match (&mut tag, c) {
+            (None, '<') => tag = Some(String::new()),
+            (None, c) => text.push(c),
+            (Some(name), '>') => {
+                let name = name.trim_start_matches('/').to_ascii_lowercase();
+                if ["br", "p", "tr", "div", "li"]
+                    .iter()
+                    .any(|block| name.split([' ', '/']).next() == Some(*block))
+                {
+                    text.push('\n');
+                }
+                tag = None;
+            }
+            (Some(name), c) => name.push(c),
+        }
+    }
+    text
+}
+
+/// Options of a batch judgement
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JudgeBatchOptions {
+    /// Proxies answering slower than this many milliseconds count as failed
+    pub max_latency_ms: Option<u128>,
+
+    /// Whether the summary keeps the report of every proxy
+    pub keep_reports: bool,
+}
+
+/// Aggregate outcome of judging a list of proxies
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JudgeBatchSummary {
+    /// Number of proxies judged
+    pub total: usize,
+
+    /// Number of proxies that passed
+    pub passed: usize,
+
+    /// Number of proxies that failed, including slow ones
+    pub failed: usize,
+
+    /// Number of working proxies failed for exceeding the latency limit
+    pub too_slow: usize,
+
+    /// Lowest latency of the passed proxies in milliseconds
+    pub min_latency_ms: Option<u128>,
+
+    /// Average latency of the passed proxies in milliseconds
+    pub average_latency_ms: Option<u128>,
+
+    /// Highest latency of the passed proxies in milliseconds
+    pub max_latency_ms: Option<u128>,
+
+    /// Number of passed proxies per anonymity level
+    pub by_anonymity: HashMap<AnonymityLevel, usize>,
+
+    /// Report of every proxy in input order, if requested
+    pub reports: Vec<JudgementReport>,
+
+    /// Time taken by the whole batch
+    pub elapsed: Duration,
+}
+
+impl JudgeBatchSummary {
+    /// Returns the share of proxies that passed, between 0.0 and 1.0
+    #[must_use]
+    pub fn pass_rate(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let rate = self.passed as f64 / self.total as f64;
+        rate
+    }
+}
+
+/// Service for judging proxies to determine their anonymity level
+///
+/// This service provides functionality to test proxies against judge services
+/// and analyze their responses to determine the level of anonymity they provide.
+/// The anonymity levels are categorized as Transparent, Anonymous, or Elite,
+/// based on how much information about the client the proxy reveals.
+///
+/// # Examples
+///
+/// ```no_run
+/// use gooty_proxy::inspection::Judge;
+/// use gooty_proxy::definitions::proxy::Proxy;
+/// use gooty_proxy::definitions::types::Port;
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     // Create a judge service
+///     let judge = Judge::new().await?;
+///
+///     // Create a proxy to test
+///     let mut proxy = Proxy::new(
+///         ProxyType::Http,
+///         IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)),
+///         Port::new(8080).unwrap(),
+///         AnonymityLevel::Anonymous, // Initial assumption
+///     );
+///
+///     // Test the proxy and determine its actual anonymity level
+///     let anonymity = judge.judge_proxy(&mut proxy).await?;
+///     println!("Proxy anonymity level: {}", anonymity);
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "judge")]
+pub struct Judge {
+    /// URLs of proxy judge services
+    urls: Vec<String>,
+
+    /// Requestor for making HTTP requests
+    requestor: Requestor,
+
+    /// Retry policy applied to every judgement
+    retry_policy: JudgementRetryPolicy,
+
+    /// Headers every judgement request is sent with
+    request_profile: RequestProfile,
+
+    /// Whether proxy latencies are recorded above the judges' baselines
+    normalize_latency: bool,
+
+    /// Direct latency to each judge URL
+    baselines: Mutex<HashMap<String, JudgeBaseline>>,
+
+    /// Keeps the raw judge responses for debugging
+    capture: Arc<ResponseCapture>,
+
+    /// How long a liveness probe waits for the proxy
+    probe_timeout: Duration,
+
+    /// Configured rules applied on top of the built-in classification
+    rules: Vec<AnonymityRule>,
+
+    /// HTTPS target of the SNI passthrough check
+    sni_target: String,
+
+    /// Vantage point judged latencies are recorded under, if any
+    vantage: Option<String>,
+
+    /// Public address of this machine, learned from a judge once
+    real_ip: OnceCell<IpAddr>,
+}
+
+#[cfg(feature = "judge")]
+impl Judge {
+    /// Create a new judge with default configuration
+    ///
+    /// Initializes a Judge service with the default HTTP and HTTPS proxy
+    /// judge URLs and a requestor configured with the default validation
+    /// timeout. Which of them are used is up to the retry policy's
+    /// `judge_scheme`.
+    ///
+    /// # Returns
+    ///
+    /// A new Judge instance with default configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the Requestor cannot be created
+    pub fn new() -> JudgementResult<Self> {
+        let judge_urls = crate::defaults::PROXY_JUDGE_URLS
+            .iter()
+            .chain(crate::defaults::PROXY_JUDGE_HTTPS_URLS)
+            .map(|url| (*url).to_string())
+            .collect();
+
+        let requestor = Requestor::with_timeout(crate::defaults::DEFAULT_VALIDATION_TIMEOUT_SECS)?;
+
+        Ok(Judge {
+            urls: judge_urls,
+            requestor,
+            retry_policy: JudgementRetryPolicy::default(),
+            request_profile: RequestProfile::for_identity(IdentityProfile::Declared),
+            normalize_latency: true,
+            baselines: Mutex::new(HashMap::new()),
+            capture: Arc::new(ResponseCapture::default()),
+            probe_timeout: Duration::from_millis(defaults::liveness::PROBE_TIMEOUT_MS),
+            rules: Vec::new(),
+            sni_target: defaults::sni_check::TARGET_URL.to_string(),
+            vantage: None,
+            real_ip: OnceCell::new(),
+        })
+    }
+
+    /// Sets the requestor judgement requests are made with
+    ///
+    /// Lets the judge share an HTTP client, timeouts, and TLS profiles with
+    /// the rest of an application.
+    ///
+    /// # Arguments
+    ///
+    /// * `requestor` - The requestor to use
+    ///
+    /// # Returns
+    ///
+    /// Self with the requestor set
+    #[must_use]
+    pub fn with_requestor(mut self, requestor: Requestor) -> Self {
+        self.requestor = requestor;
+        self
+    }
+
+    /// Sets whether proxy latencies are recorded above the judges' baselines
+    ///
+    /// Normalization is enabled by default. Without it, recorded latencies
+    /// include the direct latency to the judge.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether to subtract the baselines
+    ///
+    /// # Returns
+    ///
+    /// Self with normalization set
+    #[must_use]
+    pub fn with_latency_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_latency = enabled;
+        self
+    }
+
+    /// Get the baselines measured so far, by judge URL
+    #[must_use]
+    pub fn baselines(&self) -> HashMap<String, JudgeBaseline> {
+        self.lock_baselines().clone()
+    }
+
+    /// Measure the direct latency to every judge URL again
+    ///
+    /// Baselines are otherwise measured when a judge is first used and
+    /// whenever they grow stale.
+    ///
+    /// # Returns
+    ///
+    /// The number of judges that could be reached directly
+    pub async fn refresh_baselines(&self) -> usize {
+        let mut reached = 0;
+        for url in &self.urls {
+            if self.measure_baseline(url).await.is_some() {
+                reached += 1;
+            }
+        }
+        reached
+    }
+
+    /// Returns the baseline of a judge, measuring it if missing or stale
+    async fn baseline_for(&self, url: &str) -> Option<u128> {
+        let cached = self.lock_baselines().get(url).copied();
+        match cached {
+            Some(baseline) if !baseline.is_stale() => baseline.latency_ms,
+            _ => self.measure_baseline(url).await,
+        }
+    }
+
+    /// Measures the direct latency to a judge and stores it
+    ///
+    /// The fastest of several direct requests is kept, so a single slow
+    /// response doesn't inflate the baseline.
+    async fn measure_baseline(&self, url: &str) -> Option<u128> {
+        let mut fastest: Option<u128> = None;
+        for _ in 0..defaults::judge_baseline::SAMPLES {
+            let start = Instant::now();
+            if let Ok(body) = self
+                .requestor
+                .get_with_profile(url, &self.request_profile)
+                .await
+            {
+                let latency = start.elapsed().as_millis();
+                fastest = Some(fastest.map_or(latency, |f| f.min(latency)));
+                // A direct request shows the judge this machine's own address
+                if let Some(ip) = exit_address(&parse_judge_headers(&body)) {
+                    let _ = self.real_ip.set(ip);
+                }
+            }
+        }
+
+        self.lock_baselines().insert(
+            url.to_string(),
+            JudgeBaseline {
+                latency_ms: fastest,
+                measured_at: Utc::now(),
+            },
+        );
+        fastest
+    }
+
+    /// Sets the public address of this machine
+    ///
+    /// Only a forwarding header carrying this address makes a proxy
+    /// `Transparent`. Without it, the address is learned from a direct
+    /// request to a judge when it's first needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `real_ip` - The address judges see for direct requests
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::inspection::judgement::Judge;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let real_ip = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 4));
+    /// let judge = Judge::new().unwrap().with_real_ip(real_ip);
+    /// assert_eq!(judge.real_ip().await, Some(real_ip));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_real_ip(mut self, real_ip: IpAddr) -> Self {
+        self.real_ip = OnceCell::new_with(Some(real_ip));
+        self
+    }
+
+    /// Returns the public address of this machine
+    ///
+    /// The address is asked of the judges with a direct request the first
+    /// time, and kept once one of them reports it.
+    ///
+    /// # Returns
+    ///
+    /// The address, or `None` if no judge could be reached directly
+    pub async fn real_ip(&self) -> Option<IpAddr> {
+        self.real_ip
+            .get_or_try_init(|| async {
+                for url in &self.urls {
+                    match self
+                        .requestor
+                        .get_with_profile(url, &self.request_profile)
+                        .await
+                    {
+                        Ok(body) => {
+                            if let Some(ip) = exit_address(&parse_judge_headers(&body)) {
+                                return Ok(ip);
+                            }
+                        }
+                        Err(e) => log::debug!("Judge {url} unreachable directly: {e}"),
+                    }
+                }
+                Err(JudgementError::Other(
+                    "No judge reported this machine's address".to_string(),
+                ))
+            })
+            .await
+            .ok()
+            .copied()
+    }
+
+    /// Locks the baselines, recovering them if a holder panicked
+    fn lock_baselines(&self) -> MutexGuard<'_, HashMap<String, JudgeBaseline>> {
+        self.baselines
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Sets the retry policy of the judge
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The retry policy applied to every judgement
+    ///
+    /// # Returns
+    ///
+    /// Self with the retry policy set
+    #[must_use]
+    pub fn with_retry_policy(mut self, policy: JudgementRetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Get the retry policy of the judge
+    #[must_use]
+    pub fn retry_policy(&self) -> &JudgementRetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Sets the request profile judgement requests are sent with
+    ///
+    /// Proxies may answer differently depending on the User-Agent and other
+    /// headers, so judging with the profile later used for scraping keeps the
+    /// verdicts representative. Baselines are measured with the same profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `profile` - The headers to send
+    ///
+    /// # Returns
+    ///
+    /// Self with the request profile set
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, JudgementMode, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::inspection::judgement::{Judge, JudgementRetryPolicy};
+    /// use gooty_proxy::io::http::RequestProfile;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let profile = RequestProfile::new("scraper", "Mozilla/5.0 (X11; Linux x86_64)")
+    ///     .with_header("Accept-Language", "de-DE");
+    /// let judge = Judge::new()
+    ///     .unwrap()
+    ///     .with_retry_policy(JudgementRetryPolicy::for_mode(JudgementMode::Quick))
+    ///     .with_request_profile(profile);
+    ///
+    /// let mut proxy = Proxy::new(
+    ///     ProxyType::Http,
+    ///     IpAddr::V4(Ipv4Addr::LOCALHOST),
+    ///     Port::new(1).unwrap(),
+    ///     AnonymityLevel::Anonymous,
+    /// );
+    /// let report = judge.judge_proxy_with_report(&mut proxy).await;
+    /// assert_eq!(report.profile.as_deref(), Some("scraper"));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_request_profile(mut self, profile: RequestProfile) -> Self {
+        self.request_profile = profile;
+        self
+    }
+
+    /// Get the request profile judgement requests are sent with
+    #[must_use]
+    pub fn request_profile(&self) -> &RequestProfile {
+        &self.request_profile
+    }
+
+    /// Keeps the bodies judges respond with in the given capture
+    ///
+    /// # Arguments
+    ///
+    /// * `capture` - The capture, possibly shared with a sleuth
+    ///
+    /// # Returns
+    ///
+    /// Self with the capture set
+    #[must_use]
+    pub fn with_response_capture(mut self, capture: Arc<ResponseCapture>) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Get the capture judge responses are kept in
+    #[must_use]
+    pub fn response_capture(&self) -> &Arc<ResponseCapture> {
+        &self.capture
+    }
+
+    /// Sets the rules applied on top of the built-in classification
+    ///
+    /// # Arguments
+    ///
+    /// * `rules` - Rules demoting proxies that send matching headers
+    #[must_use]
+    pub fn with_anonymity_rules(mut self, rules: Vec<AnonymityRule>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Returns the rules applied on top of the built-in classification
+    #[must_use]
+    pub fn anonymity_rules(&self) -> &[AnonymityRule] {
+        &self.rules
+    }
+
+    /// Sets how long a liveness probe waits for the proxy
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Time allowed for connecting and the handshake together
+    #[must_use]
+    pub fn with_probe_timeout(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = timeout;
+        self
+    }
+
+    /// Returns how long a liveness probe waits for the proxy
+    #[must_use]
+    pub fn probe_timeout(&self) -> Duration {
+        self.probe_timeout
+    }
+
+    /// Sets the target of the SNI passthrough check
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - HTTPS URL of a host that only answers connections with the
+    ///   right SNI and Host
+    #[must_use]
+    pub fn with_sni_target(mut self, url: &str) -> Self {
+        self.sni_target = url.to_string();
+        self
+    }
+
+    /// Returns the target of the SNI passthrough check
+    #[must_use]
+    pub fn sni_target(&self) -> &str {
+        &self.sni_target
+    }
+
+    /// Sets the vantage point judged latencies are recorded under
+    ///
+    /// Judges running in several regions each keep their own latency on the
+    /// proxy, besides the last latency measured, see `Proxy::latency_from`.
+    ///
+    /// # Arguments
+    ///
+    /// * `vantage` - Name of the vantage point, such as the region the judge runs in
+    #[must_use]
+    pub fn with_vantage(mut self, vantage: &str) -> Self {
+        self.vantage = Some(vantage.to_string());
+        self
+    }
+
+    /// Returns the vantage point judged latencies are recorded under, if any
+    #[must_use]
+    pub fn vantage(&self) -> Option<&str> {
+        self.vantage.as_deref()
+    }
+
+    /// Check whether a proxy passes TLS SNI and the Host header through untouched
+    ///
+    /// Requests the SNI target through the proxy. The proxy mangles the
+    /// connection if the TLS handshake fails, the target answers with
+    /// `421 Misdirected Request` or `400 Bad Request`, or the target reports
+    /// a different host or no SNI in a trace response (see
+    /// [`sni_trace_mismatch`]). The outcome is recorded in the proxy's
+    /// `sni_passthrough`.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to check, which will be modified to record the outcome
+    ///
+    /// # Returns
+    ///
+    /// Whether the proxy passed SNI and Host through untouched
+    ///
+    /// # Errors
+    ///
+    /// Returns `JudgementError::ParseError` if the SNI target is not a URL
+    /// with a host, or `JudgementError::RequestError` if the proxy failed in a
+    /// way that says nothing about SNI, such as a timeout. The proxy's
+    /// `sni_passthrough` is left alone in that case.
+    pub async fn verify_sni(&self, proxy: &mut Proxy) -> JudgementResult<bool> {
+        let host = reqwest::Url::parse(&self.sni_target)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .ok_or_else(|| {
+                JudgementError::ParseError(format!("Invalid SNI target: {}", self.sni_target))
+            })?;
+
+        let result = self
+            .requestor
+            .get_with_proxy_profile(
+                &self.sni_target,
+                &self.request_profile,
+                proxy,
+                self.retry_policy.timeout_for(proxy.proxy_type, 0),
+            )
+            .await;
+
+        let mismatch = match result {
+            Ok(body) => {
+                self.capture.record(
+                    "sni",
+                    &self.sni_target,
+                    &proxy.to_connection_string(),
+                    Some(200),
+                    &body,
+                );
+                sni_trace_mismatch(&body, &host)
+            }
+            Err(RequestorError::TlsHandshake(message)) => Some(message),
+            Err(RequestorError::StatusError(status, _)) if matches!(status.as_u16(), 400 | 421) => {
+                Some(format!("Target answered {status}"))
+            }
+            Err(e) => return Err(JudgementError::RequestError(e)),
+        };
+
+        if let Some(reason) = &mismatch {
+            log::debug!(
+                "Proxy {} mangles SNI or Host: {reason}",
+                proxy.to_connection_string()
+            );
+        }
+        proxy.sni_passthrough = Some(mismatch.is_none());
+        Ok(mismatch.is_none())
+    }
+
+    /// Check whether a proxy is alive, without judging it
+    ///
+    /// Connects to the proxy and, for SOCKS5 proxies, exchanges the method
+    /// greeting. No request is made through the proxy, so this is far cheaper
+    /// than a judgement, but says nothing about its anonymity. The outcome is
+    /// recorded as a probe of the proxy, apart from its checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to probe, which will be modified to record the outcome
+    ///
+    /// # Returns
+    ///
+    /// The time the probe took, in milliseconds
+    ///
+    /// # Errors
+    ///
+    /// Returns `JudgementError::Timeout` if the proxy did not answer within the
+    /// probe timeout, or `JudgementError::ProxyFailure` if it refused the
+    /// connection or answered the greeting with something else than SOCKS5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::inspection::judgement::Judge;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// // A SOCKS5 proxy that answers the greeting
+    /// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    /// let port = listener.local_addr().unwrap().port();
+    /// tokio::spawn(async move {
+    ///     let (mut stream, _) = listener.accept().await.unwrap();
+    ///     let mut greeting = [0_u8; 3];
+    ///     stream.read_exact(&mut greeting).await.unwrap();
+    ///     stream.write_all(&[5, 0]).await.unwrap();
+    /// });
+    ///
+    /// let judge = Judge::new().unwrap();
+    /// let mut proxy = Proxy::new(ProxyType::Socks5, IpAddr::V4(Ipv4Addr::LOCALHOST), Port::new(port).unwrap(), AnonymityLevel::Elite);
+    /// assert!(judge.probe_alive(&mut proxy).await.is_ok());
+    ///
+    /// // Nothing listens on this port
+    /// let mut dead = Proxy::new(ProxyType::Http, IpAddr::V4(Ipv4Addr::LOCALHOST), Port::new(1).unwrap(), AnonymityLevel::Elite);
+    /// assert!(judge.probe_alive(&mut dead).await.is_err());
+    ///
+    /// // Probes are not counted as checks
+    /// assert_eq!((proxy.probe_count, proxy.check_count), (1, 0));
+    /// assert_eq!(dead.probe_failure_count, 1);
+    /// # }
+    /// ```
+    pub async fn probe_alive(&self, proxy: &mut Proxy) -> JudgementResult<u128> {
+        let start = Instant::now();
+        let result = tokio::time::timeout(self.probe_timeout, Self::handshake(proxy)).await;
+
+        match result {
+            Ok(Ok(())) => {
+                let latency = start.elapsed().as_millis();
+                proxy.record_probe(latency);
+                Ok(latency)
+            }
+            Ok(Err(e)) => {
+                proxy.record_probe_failure();
+                Err(e)
+            }
+            Err(_) => {
+                proxy.record_probe_failure();
+                Err(JudgementError::Timeout)
+            }
+        }
+    }
+
+    /// Connects to a proxy and exchanges the least its protocol allows
+    async fn handshake(proxy: &Proxy) -> JudgementResult<()> {
+        let mut stream = TcpStream::connect(proxy.socket_addr())
+            .await
+            .map_err(|e| JudgementError::ProxyFailure(e.to_string()))?;
+
+        // SOCKS4 and HTTP proxies only answer a request for a target
+        if proxy.proxy_type != ProxyType::Socks5 {
+            return Ok(());
+        }
+
+        let greeting: &[u8] = if proxy.username.is_some() {
+            &[5, 2, 0, 2]
+        } else {
+            &[5, 1, 0]
+        };
+        let mut choice = [0_u8; 2];
+        stream
+            .write_all(greeting)
+            .await
+            .map_err(|e| JudgementError::ProxyFailure(e.to_string()))?;
+        stream
+            .read_exact(&mut choice)
+            .await
+            .map_err(|e| JudgementError::ProxyFailure(e.to_string()))?;
+
+        match choice {
+            [5, 0xFF] => Err(JudgementError::ProxyFailure(
+                "Proxy accepts none of the offered SOCKS5 methods".to_string(),
+            )),
+            [5, _] => Ok(()),
+            _ => Err(JudgementError::ProxyFailure(
+                "Proxy did not answer the SOCKS5 greeting".to_string(),
+            )),
+        }
+    }
+
+    /// Judge a proxy to determine its anonymity level
+    ///
+    /// Makes a request through the provided proxy to a judge service and
+    /// analyzes the response to determine the proxy's anonymity level.
+    /// Transient failures are retried according to the retry policy.
+    /// The proxy is also updated with latency information.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to judge, which will be modified to record check statistics
+    ///
+    /// # Returns
+    ///
+    /// The determined anonymity level of the proxy
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * No judge URL is available
+    /// * Every attempt to request through the proxy fails
+    /// * The response analysis fails
+    ///
+    /// # Examples
+    ///
+    /// Proxies are untrusted, and whatever they answer is judged or fails
+    /// with an error:
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::inspection::judgement::{Judge, JudgementRetryPolicy};
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// // An HTTP proxy answering garbage for one judge and a mangled page for the other
+    /// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    /// let port = listener.local_addr().unwrap().port();
+    /// tokio::spawn(async move {
+    ///     loop {
+    ///         let (mut stream, _) = listener.accept().await.unwrap();
+    ///         tokio::spawn(async move {
+    ///             let mut request = [0_u8; 1024];
+    ///             let read = stream.read(&mut request).await.unwrap();
+    ///             let response: &[u8] = if String::from_utf8_lossy(&request[..read]).contains("junk") {
+    ///                 b"\x00\xff\xfe not http at all"
+    ///             } else {
+    ///                 b"HTTP/1.1 200 OK\r\ncontent-length: 35\r\n\r\n<<b<=\xc3(:=\xff\nVIA=\nX_FORWARDED_FOR=[::"
+    ///             };
+    ///             let _ = stream.write_all(response).await;
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// let judge = |url: &str| {
+    ///     Judge::new()
+    ///         .unwrap()
+    ///         .with_judge_urls(vec![url.to_string()])
+    ///         .with_latency_normalization(false)
+    ///         .with_retry_policy(JudgementRetryPolicy { attempts: 1, ..Default::default() })
+    /// };
+    /// let mut proxy = Proxy::new(ProxyType::Http, IpAddr::V4(Ipv4Addr::LOCALHOST), Port::new(port).unwrap(), AnonymityLevel::Elite);
+    ///
+    /// assert!(judge("http://junk.judge.test/").judge_proxy(&mut proxy).await.is_err());
+    /// assert!(judge("http://mangled.judge.test/").judge_proxy(&mut proxy).await.is_ok());
+    /// # }
+    /// ```
+    pub async fn judge_proxy(&self, proxy: &mut Proxy) -> JudgementResult<AnonymityLevel> {
+        self.judge_with_consensus(proxy).await.0
+    }
+
+    /// Judge a proxy and report every attempt made
+    ///
+    /// Behaves like [`Judge::judge_proxy`], but returns the attempt history
+    /// and the verdict of every judge consulted instead of only the final
+    /// result.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to judge, which will be modified to record check statistics
+    ///
+    /// # Returns
+    ///
+    /// The anonymity level found, if any, with the attempts made
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, JudgementMode, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::inspection::judgement::{Judge, JudgementRetryPolicy};
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let judge = Judge::new()
+    ///     .unwrap()
+    ///     .with_retry_policy(JudgementRetryPolicy::for_mode(JudgementMode::Quick));
+    ///
+    /// // Nothing listens on this port, so every attempt fails
+    /// let mut proxy = Proxy::new(
+    ///     ProxyType::Http,
+    ///     IpAddr::V4(Ipv4Addr::LOCALHOST),
+    ///     Port::new(1).unwrap(),
+    ///     AnonymityLevel::Anonymous,
+    /// );
+    /// let report = judge.judge_proxy_with_report(&mut proxy).await;
+    ///
+    /// assert!(!report.is_success());
+    /// assert_eq!(report.attempts.len(), 2);
+    /// assert!(report.attempts.iter().all(|a| a.error.is_some()));
+    /// // The retry went to another judge with a longer timeout
+    /// assert_ne!(report.attempts[0].judge_url, report.attempts[1].judge_url);
+    /// assert!(report.attempts[1].timeout > report.attempts[0].timeout);
+    /// # }
+    /// ```
+    pub async fn judge_proxy_with_report(&self, proxy: &mut Proxy) -> JudgementReport {
+        self.judge_with_consensus(proxy).await.1
+    }
+
+    /// Judge a list of proxies and summarize the outcome
+    ///
+    /// Proxies are judged concurrently without needing a `ProxyManager`. Each
+    /// proxy is updated in place: a passing proxy gets its anonymity level, and
+    /// a failing one has the failed check recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxies` - The proxies to judge, updated with the outcome
+    /// * `concurrency` - Maximum number of proxies judged at once
+    /// * `options` - Latency limit and whether to keep individual reports
+    ///
+    /// # Returns
+    ///
+    /// Pass/fail counts and latency statistics of the batch
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::inspection::judgement::{JudgeBatchOptions, Judge};
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let judge = Judge::new().unwrap();
+    ///
+    /// // Nothing listens on these ports, so every proxy fails
+    /// let mut proxies: Vec<Proxy> = (1..=3)
+    ///     .map(|port| {
+    ///         Proxy::new(
+    ///             ProxyType::Http,
+    ///             IpAddr::V4(Ipv4Addr::LOCALHOST),
+    ///             Port::new(port).unwrap(),
+    ///             AnonymityLevel::Anonymous,
+    ///         )
+    ///     })
+    ///     .collect();
+    /// let options = JudgeBatchOptions {
+    ///     keep_reports: true,
+    ///     ..Default::default()
+    /// };
+    /// let summary = judge.judge_batch(&mut proxies, 2, &options).await;
+    ///
+    /// assert_eq!(summary.total, 3);
+    /// assert_eq!(summary.failed, 3);
+    /// assert_eq!(summary.average_latency_ms, None);
+    /// assert_eq!(summary.reports.len(), 3);
+    /// assert!(proxies.iter().all(|p| p.check_failure_count == 1));
+    /// # }
+    /// ```
+    pub async fn judge_batch(
+        &self,
+        proxies: &mut [Proxy],
+        concurrency: usize,
+        options: &JudgeBatchOptions,
+    ) -> JudgeBatchSummary {
+        let start = Instant::now();
+        let reports: Vec<JudgementReport> = stream::iter(proxies.iter_mut())
+            .map(|proxy| async move {
+                let report = self.judge_proxy_with_report(proxy).await;
+                match report.anonymity {
+                    Some(anonymity) => proxy.anonymity = anonymity,
+                    None => proxy.record_check_failure(),
+                }
+                report
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut summary = JudgeBatchSummary {
+            total: reports.len(),
+            ..Default::default()
+        };
+        let mut latencies = Vec::new();
+        for report in &reports {
+            let latency = report.attempts.last().and_then(|a| a.latency_ms);
+            match (report.anonymity, latency) {
+                (Some(_), Some(latency))
+                    if options.max_latency_ms.is_some_and(|max| latency > max) =>
+                {
+                    summary.too_slow += 1;
+                    summary.failed += 1;
+                }
+                (Some(anonymity), latency) => {
+                    summary.passed += 1;
+                    *summary.by_anonymity.entry(anonymity).or_insert(0) += 1;
+                    latencies.extend(latency);
+                }
+                (None, _) => summary.failed += 1,
+            }
+        }
+
+        summary.min_latency_ms = latencies.iter().copied().min();
+        summary.max_latency_ms = latencies.iter().copied().max();
+        if !latencies.is_empty() {
+            summary.average_latency_ms = Some(
+                latencies
+                    .iter()
+                    .fold(0, |sum: u128, &l| sum.saturating_add(l))
+                    / latencies.len() as u128,
+            );
+        }
+        if options.keep_reports {
+            summary.reports = reports;
+        }
+        summary.elapsed = start.elapsed();
+        summary
+    }
+
+    /// Judge a proxy against as many judges as the policy asks for
+    ///
+    /// Each judge gets its own retries, starting from a different judge URL.
+    /// The verdicts are combined under the policy's consensus rule, and the
+    /// proxy is flagged when the judges disagree.
+    async fn judge_with_consensus(
+        &self,
+        proxy: &mut Proxy,
+    ) -> (JudgementResult<AnonymityLevel>, JudgementReport) {
+        let mut report = JudgementReport {
+            profile: Some(self.request_profile.name.clone()),
+            ..JudgementReport::default()
+        };
+        let urls = self.selected_urls();
+        if urls.is_empty() {
+            return (Err(JudgementError::NoJudgeUrl), report);
+        }
+
+        if self.retry_policy.nearest_judge && proxy.preferred_judge.is_none() {
+            self.select_nearest_judge(proxy).await;
+        }
+        let start = proxy
+            .preferred_judge
+            .as_ref()
+            .and_then(|url| urls.iter().position(|u| *u == url))
+            .unwrap_or(0);
+
+        let judges = self.retry_policy.consensus_judges.clamp(1, urls.len());
+        let mut last_error = None;
+        for offset in 0..judges {
+            let (result, attempts) = self.judge_with_attempts(proxy, start + offset).await;
+            let judge_url = attempts.last().map(|a| a.judge_url.clone());
+            report.attempts.extend(attempts);
+            match (result, judge_url) {
+                (Ok(anonymity), Some(judge_url)) => report.verdicts.push(JudgeVerdict {
+                    judge_url,
+                    anonymity,
+                }),
+                (Err(e), _) => last_error = Some(e),
+                (Ok(_), None) => {}
+            }
+        }
+
+        let levels: Vec<AnonymityLevel> = report.verdicts.iter().map(|v| v.anonymity).collect();
+        report.disagreement = levels
+            .first()
+            .is_some_and(|first| levels.iter().any(|level| level != first));
+        report.anonymity = resolve_consensus(&levels, self.retry_policy.consensus_rule);
+
+        let Some(anonymity) = report.anonymity else {
+            // The proxy may have moved, so the nearest judge is measured again
+            proxy.preferred_judge = None;
+            return (
+                Err(last_error.unwrap_or(JudgementError::NoJudgeUrl)),
+                report,
+            );
+        };
+
+        proxy.anonymity_disputed = report.disagreement;
+        if report.disagreement {
+            log::debug!(
+                "Judges disagree on {}, settled on {anonymity}",
+                proxy.address
+            );
+        }
+        (Ok(anonymity), report)
+    }
+
+    /// Find the judge with the shortest round trip through a proxy
+    ///
+    /// Requests a few judges through the proxy at once, and keeps the one
+    /// that answered first as the proxy's preferred judge. Which judges are
+    /// measured depends on the proxy's address, so measurements spread over
+    /// all judges. The requests are not recorded as checks.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to measure through, which keeps the nearest judge
+    ///
+    /// # Returns
+    ///
+    /// The nearest judge URL, or `None` if no judge answered through the proxy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::inspection::judgement::Judge;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// // An HTTP proxy that answers itself, slowly for judges outside "near"
+    /// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    /// let port = listener.local_addr().unwrap().port();
+    /// tokio::spawn(async move {
+    ///     loop {
+    ///         let (mut stream, _) = listener.accept().await.unwrap();
+    ///         tokio::spawn(async move {
+    ///             let mut request = [0_u8; 1024];
+    ///             let read = stream.read(&mut request).await.unwrap();
+    ///             if !String::from_utf8_lossy(&request[..read]).contains("near.judge") {
+    ///                 tokio::time::sleep(Duration::from_millis(300)).await;
+    ///             }
+    ///             let _ = stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n").await;
+    ///         });
+    ///     }
+    /// });
+    ///
+    /// let judge = Judge::new().unwrap().with_judge_urls(vec![
+    ///     "http://far.judge.test/".to_string(),
+    ///     "http://near.judge.test/".to_string(),
+    ///     "http://other.judge.test/".to_string(),
+    /// ]);
+    /// let mut proxy = Proxy::new(ProxyType::Http, IpAddr::V4(Ipv4Addr::LOCALHOST), Port::new(port).unwrap(), AnonymityLevel::Elite);
+    ///
+    /// let nearest = judge.select_nearest_judge(&mut proxy).await;
+    /// assert_eq!(nearest.as_deref(), Some("http://near.judge.test/"));
+    /// assert_eq!(proxy.preferred_judge, nearest);
+    /// assert_eq!(proxy.check_count, 0);
+    /// # }
+    /// ```
+    pub async fn select_nearest_judge(&self, proxy: &mut Proxy) -> Option<String> {
+        let urls = self.selected_urls();
+        if urls.is_empty() {
+            return None;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        proxy.address.hash(&mut hasher);
+        #[allow(clippy::cast_possible_truncation)]
+        let first = hasher.finish() as usize % urls.len();
+        let candidates = defaults::judgement_retry::NEAREST_JUDGE_CANDIDATES.min(urls.len());
+        let timeout = self.retry_policy.timeout_for(proxy.proxy_type, 0);
+
+        let measured = proxy.clone();
+        let round_trips = urls
+            .iter()
+            .cycle()
+            .skip(first)
+            .take(candidates)
+            .map(|&url| {
+                let measured = &measured;
+                async move {
+                    let start = Instant::now();
+                    self.requestor
+                        .get_with_proxy_profile(url, &self.request_profile, measured, timeout)
+                        .await
+                        .ok()
+                        .map(|_| (start.elapsed(), url))
+                }
+            });
+
+        let nearest = futures::future::join_all(round_trips)
+            .await
+            .into_iter()
+            .flatten()
+            .min_by_key(|(elapsed, _)| *elapsed)
+            .map(|(elapsed, url)| {
+                log::debug!(
+                    "Nearest judge of {} is {url} at {}ms",
+                    proxy.address,
+                    elapsed.as_millis()
+                );
+                url.clone()
+            });
+
+        proxy.preferred_judge.clone_from(&nearest);
+        nearest
+    }
+
+    /// Judge a proxy under the retry policy, keeping the attempt history
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to judge
+    /// * `first_judge` - Index of the judge URL the first attempt uses
+    async fn judge_with_attempts(
+        &self,
+        proxy: &mut Proxy,
+        first_judge: usize,
+    ) -> (JudgementResult<AnonymityLevel>, Vec<JudgementAttempt>) {
+        let mut attempts = Vec::new();
+        let urls = self.selected_urls();
+        if urls.is_empty() {
+            return (Err(JudgementError::NoJudgeUrl), attempts);
+        }
+
+        let total = self.retry_policy.attempts.max(1);
+        let mut attempt = 0;
+        loop {
+            // Move on to the next judge on every retry, if enabled
+            let index = if self.retry_policy.rotate_judges {
+                first_judge.wrapping_add(attempt as usize) % urls.len()
+            } else {
+                first_judge % urls.len()
+            };
+            let Some(&judge_url) = urls.get(index) else {
+                return (Err(JudgementError::NoJudgeUrl), attempts);
+            };
+            let timeout = self.retry_policy.timeout_for(proxy.proxy_type, attempt);
+
+            // Attempt to make a request through the proxy
+            let start = Instant::now();
+            let result = self
+                .requestor
+                .get_with_proxy_profile(judge_url, &self.request_profile, proxy, timeout)
+                .await;
+
+            // A judge over HTTPS shows whether the proxy tunnels TLS intact;
+            // other failures say nothing about TLS
+            if JudgeScheme::Https.matches(judge_url) {
+                match &result {
+                    Ok(_) => proxy.tls_capable = Some(true),
+                    Err(RequestorError::TlsHandshake(_)) => proxy.tls_capable = Some(false),
+                    Err(_) => {}
+                }
+            }
+
+            match result {
+                Ok(response) => {
+                    // Record the latency above the judge's own distance
+                    let elapsed = start.elapsed().as_millis();
+                    let baseline = if self.normalize_latency {
+                        self.baseline_for(judge_url).await
+                    } else {
+                        None
+                    };
+                    let latency = elapsed.saturating_sub(baseline.unwrap_or(0));
+                    proxy.record_check(latency);
+                    if let Some(vantage) = &self.vantage {
+                        proxy.record_vantage_latency(vantage, latency);
+                    }
+                    attempts.push(JudgementAttempt {
+                        judge_url: judge_url.clone(),
+                        timeout,
+                        latency_ms: Some(latency),
+                        baseline_ms: baseline,
+                        error: None,
+                    });
+
+                    self.capture.record(
+                        "judge",
+                        judge_url,
+                        &proxy.to_connection_string(),
+                        Some(200),
+                        &response,
+                    );
+
+                    // Analyze the response to determine anonymity level
+                    let headers = parse_judge_headers(&response);
+                    if let Some(exit) = exit_address(&headers) {
+                        proxy.record_exit(exit);
+                    }
+                    let real_ip = self.real_ip().await;
+                    let anonymity = self.determine_anonymity_level(&headers, real_ip);
+                    return (Ok(anonymity), attempts);
+                }
+                Err(e) => {
+                    attempts.push(JudgementAttempt {
+                        judge_url: judge_url.clone(),
+                        timeout,
+                        latency_ms: None,
+                        baseline_ms: None,
+                        error: Some(e.to_string()),
+                    });
+
+                    attempt += 1;
+                    if attempt >= total || !is_transient(&e) {
+                        return (Err(JudgementError::RequestError(e)), attempts);
+                    }
+                    log::debug!(
+                        "Judgement attempt {attempt}/{total} of {} failed: {e}",
+                        proxy.address
+                    );
+                }
+            }
+        }
+    }
+
+    /// Determine the anonymity level from a judge response
+    ///
+    /// Classifies the headers parsed from the response like
+    /// [`classify_anonymity`] and applies the configured rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The headers parsed from the judge's response
+    /// * `real_ip` - The public address of this machine, if known
+    ///
+    /// # Returns
+    ///
+    /// The determined anonymity level
+    fn determine_anonymity_level(
+        &self,
+        headers: &BTreeMap<String, String>,
+        real_ip: Option<IpAddr>,
+    ) -> AnonymityLevel {
+        let level = classify_for(headers, real_ip);
+        AnonymityRule::apply_all(&self.rules, level, headers)
+    }
+
+    /// Sets the judge URLs, replacing the built-in ones
+    ///
+    /// # Arguments
+    ///
+    /// * `urls` - The URLs of the judge services to use
+    #[must_use]
+    pub fn with_judge_urls(mut self, urls: Vec<String>) -> Self {
+        self.urls = urls;
+        self
+    }
+
+    /// Add a judge URL
+    ///
+    /// Adds a new URL to the list of judge services, if it's not already present.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the judge service to add
+    pub fn add_judge_url(&mut self, url: String) {
+        if !self.urls.contains(&url) {
+            self.urls.push(url);
+        }
+    }
+
+    /// Put a judge URL, such as a [`LocalJudge`]'s, before the others
+    ///
+    /// Judgements start with the first judge URL the retry policy's scheme
+    /// selects, so the URL is tried first.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the judge service to prefer
+    #[must_use]
+    pub fn with_preferred_judge_url(mut self, url: &str) -> Self {
+        self.urls.retain(|existing| existing != url);
+        self.urls.insert(0, url.to_string());
+        self
+    }
+
+    /// Get the current judge URLs
+    ///
+    /// # Returns
+    ///
+    /// A slice containing all the judge URLs currently configured
+    #[must_use]
+    pub fn get_judge_urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    /// Returns the judge URLs the retry policy's scheme selects, in order
+    fn selected_urls(&self) -> Vec<&String> {
+        self.urls
+            .iter()
+            .filter(|url| self.retry_policy.judge_scheme.matches(url))
+            .collect()
+    }
+}
+
+/// A judge served by gooty itself
+///
+/// Answers every GET request with the request's headers in the azenv format
+/// of the public judges, so [`Judge`] can classify proxies without them. The
+/// proxies must be able to reach the judge, so bind it to an address they
+/// can connect to, and give the URL they reach it at with
+/// [`LocalJudge::with_public_url`] if that address differs.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::inspection::judgement::{Judge, LocalJudge, parse_judge_headers};
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+/// use tokio::net::TcpStream;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let local = LocalJudge::bind("127.0.0.1:0").await.unwrap();
+///     let addr = local.local_addr().unwrap();
+///     let judge = local.register(Judge::new().unwrap()).unwrap();
+///     assert_eq!(judge.get_judge_urls()[0], format!("http://{addr}/"));
+///     tokio::spawn(local.run());
+///
+///     let mut client = TcpStream::connect(addr).await.unwrap();
+///     client
+///         .write_all(b"GET / HTTP/1.1\r\nHost: judge.example\r\nVia: 1.1 squid\r\n\r\n")
+///         .await
+///         .unwrap();
+///     let mut response = String::new();
+///     client.read_to_string(&mut response).await.unwrap();
+///
+///     let headers = parse_judge_headers(&response);
+///     assert_eq!(headers["REMOTE_ADDR"], "127.0.0.1");
+///     assert_eq!(headers["VIA"], "1.1 squid");
+/// }
+/// ```
+#[cfg(feature = "judge")]
+#[derive(Debug)]
+pub struct LocalJudge {
+    /// The listening socket
+    listener: TcpListener,
+
+    /// URL proxies reach the judge at, if not its listening address
+    public_url: Option<String>,
+}
+
+#[cfg(feature = "judge")]
+impl LocalJudge {
+    /// Listen for judge requests on an address
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The address to listen on, such as `local_judge::DEFAULT_ADDRESS`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address can't be bound
+    pub async fn bind(addr: impl ToSocketAddrs) -> ListenerResult<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr).await?,
+            public_url: None,
+        })
+    }
+
+    /// Set the URL proxies reach the judge at
+    ///
+    /// Needed when the judge listens behind NAT or on a wildcard address.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The judge's public URL, such as `http://203.0.113.7:8092/`
+    #[must_use]
+    pub fn with_public_url(mut self, url: &str) -> Self {
+        self.public_url = Some(url.to_string());
+        self
+    }
+
+    /// Get the address the judge listens on
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket's address can't be read
+    pub fn local_addr(&self) -> ListenerResult<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Get the URL proxies reach the judge at
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no public URL is set and the socket's address
+    /// can't be read
+    pub fn url(&self) -> ListenerResult<String> {
+        match &self.public_url {
+            Some(url) => Ok(url.clone()),
+            None => Ok(format!("http://{}/", self.local_addr()?)),
+        }
+    }
+
+    /// Register the judge with a [`Judge`], ahead of its other judge URLs
+    ///
+    /// # Arguments
+    ///
+    /// * `judge` - The judge to register with
+    ///
+    /// # Returns
+    ///
+    /// The judge, trying this one first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the judge's URL can't be determined
+    pub fn register(&self, judge: Judge) -> ListenerResult<Judge> {
+        Ok(judge.with_preferred_judge_url(&self.url()?))
+    }
+
+    /// Answer judge requests until accepting one fails
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listening socket fails. Failures of single
+    /// clients are logged and don't stop the judge.
+    pub async fn run(self) -> ListenerResult<()> {
+        loop {
+            let (client, peer) = self.listener.accept().await?;
+            tokio::spawn(async move {
+                if let Err(e) = answer_judge_request(client, peer).await {
+                    log::debug!(peer = peer.to_string(); "Local judge request failed: {e}");
+                }
+            });
+        }
+    }
+}
+
+/// Answer a judge request with the headers it was sent with
+#[cfg(feature = "judge")]
+async fn answer_judge_request(mut client: TcpStream, peer: SocketAddr) -> ListenerResult<()> {
+    let request_timeout = Duration::from_secs(defaults::local_judge::REQUEST_TIMEOUT_SECS);
+    let head = tokio::time::timeout(request_timeout, read_request_head(&mut client))
+        .await
+        .map_err(|_| ListenerError::Timeout("waiting for the request".to_string()))??;
+
+    let response = if head.method == "GET" {
+        let body = azenv_body(&head, peer);
+        format!(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: text/html; charset=utf-8\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            body.len()
+        )
+    } else {
+        "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string()
+    };
+    client.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Render a request the way azenv.php judges echo it
+#[cfg(feature = "judge")]
+fn azenv_body(head: &RequestHead, peer: SocketAddr) -> String {
+    let mut lines = vec![
+        format!("REMOTE_ADDR = {}", peer.ip().to_canonical()),
+        format!("REMOTE_PORT = {}", peer.port()),
+        format!("REQUEST_METHOD = {}", head.method),
+        format!("REQUEST_URI = {}", head.path),
+    ];
+    lines.extend(head.headers().iter().map(|(name, value)| {
+        format!(
+            "HTTP_{} = {value}",
+            name.to_ascii_uppercase().replace('-', "_")
+        )
+    }));
+    format!("<pre>\n{}\n</pre>\n", lines.join("\n"))
+}
+
+/// Finds signs of a mangled SNI or Host in a trace response
+///
+/// Trace endpoints such as Cloudflare's `/cdn-cgi/trace` answer with
+/// `key=value` lines describing the request they received. The `h` line
+/// holds the Host, and the `sni` line whether an SNI was sent. Responses
+/// without these lines show no mismatch.
+///
+/// # Arguments
+///
+/// * `body` - The body of the trace response
+/// * `host` - The host the request was made for
+///
+/// # Returns
+///
+/// A description of the mismatch, or `None` if SNI and Host arrived intact
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::inspection::judgement::sni_trace_mismatch;
+///
+/// let intact = "fl=123\nh=www.cloudflare.com\nip=203.0.113.7\nsni=plaintext\n";
+/// assert_eq!(sni_trace_mismatch(intact, "www.cloudflare.com"), None);
+///
+/// let rewritten = "h=10.0.0.1\nsni=off\n";
+/// assert!(sni_trace_mismatch(rewritten, "www.cloudflare.com").is_some());
+///
+/// // Other targets only need to answer at all
+/// assert_eq!(sni_trace_mismatch("<html>ok</html>", "example.com"), None);
+/// ```
+#[must_use]
+pub fn sni_trace_mismatch(body: &str, host: &str) -> Option<String> {
+    for line in body.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "h" if !value.eq_ignore_ascii_case(host) => {
+                return Some(format!("Target received Host {value} instead of {host}"));
+            }
+            "sni" if value == "off" => {
+                return Some("Target received no SNI".to_string());
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Checks whether a failed judgement request is worth retrying
+///
+/// Rejected credentials and broken TLS configurations fail the same way on
+/// every attempt, so only other failures are retried.
+#[cfg(feature = "judge")]
+fn is_transient(error: &RequestorError) -> bool {
+    !matches!(
+        error,
+        RequestorError::ProxyAuthenticationFailed(_)
+            | RequestorError::TlsConfiguration(_)
+            | RequestorError::TlsHandshake(_)
+    )
 }
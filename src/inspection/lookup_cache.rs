@@ -0,0 +1,219 @@
+//! # Lookup Cache Module
+//!
+//! This module keeps the documents lookup providers returned about an
+//! address, so the same address isn't looked up twice.
+//!
+//! ## Components
+//!
+//! * **`LookupCache`** - Documents about addresses, kept until they expire
+//! * **`CachedLookup`** - A single document with when it was fetched
+//!
+//! `Sleuth` and `OwnershipLookup` both read ipinfo.io's document about an
+//! address, and bulk enrichment asks for the same addresses again and again.
+//! A cache shared by both answers repeated lookups from memory within its
+//! time to live, and can be saved to a filestore so later runs don't spend
+//! quota on addresses already looked up. A time to live of zero disables
+//! caching.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::inspection::lookup_cache::LookupCache;
+//! use std::net::IpAddr;
+//! use std::time::Duration;
+//!
+//! let cache = LookupCache::new(Duration::from_secs(3600));
+//! let ip: IpAddr = "8.8.8.8".parse().unwrap();
+//! assert!(cache.get(&ip).is_none());
+//!
+//! cache.insert(&ip, r#"{"ip": "8.8.8.8", "org": "AS15169 Google LLC"}"#);
+//! let document = cache.get(&ip).unwrap();
+//! assert_eq!(document["org"], "AS15169 Google LLC");
+//!
+//! // Documents carry over to another cache, such as one of a later run
+//! let restored = LookupCache::new(Duration::from_secs(3600));
+//! restored.restore(cache.snapshots());
+//! assert!(restored.get(&ip).is_some());
+//! ```
+
+use crate::definitions::defaults;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// A document a lookup provider returned about an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedLookup {
+    /// The address looked up
+    pub ip: IpAddr,
+
+    /// The raw JSON document
+    pub body: String,
+
+    /// When the document was fetched
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Keeps the documents lookup providers returned about addresses
+///
+/// The cache is shared through an `Arc` by everything that looks addresses
+/// up, and its time to live can be changed while it is shared.
+#[derive(Debug)]
+pub struct LookupCache {
+    /// How long documents are kept, in seconds
+    ttl_secs: AtomicU64,
+
+    /// The documents by address
+    entries: Mutex<HashMap<IpAddr, CachedLookup>>,
+}
+
+impl Default for LookupCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(defaults::lookup_cache::TTL_SECS))
+    }
+}
+
+impl LookupCache {
+    /// Create a cache keeping documents for the given time
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - How long documents are kept, zero to disable caching
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl_secs: AtomicU64::new(ttl.as_secs()),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Get how long documents are kept
+    #[must_use]
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_secs.load(Ordering::Relaxed))
+    }
+
+    /// Change how long documents are kept
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - How long documents are kept, zero to disable caching
+    pub fn set_ttl(&self, ttl: Duration) {
+        self.ttl_secs.store(ttl.as_secs(), Ordering::Relaxed);
+    }
+
+    /// Check whether documents are being cached
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.ttl_secs.load(Ordering::Relaxed) > 0
+    }
+
+    /// Get the document about an address, if one is kept and still fresh
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The address looked up
+    ///
+    /// # Returns
+    ///
+    /// The parsed document, or None if none is kept, it expired, or it
+    /// isn't valid JSON
+    #[must_use]
+    pub fn get(&self, ip: &IpAddr) -> Option<serde_json::Value> {
+        let entries = self.lock();
+        let entry = entries.get(ip).filter(|entry| self.is_fresh(entry))?;
+        serde_json::from_str(&entry.body).ok()
+    }
+
+    /// Keep the document about an address
+    ///
+    /// Does nothing while caching is disabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The address looked up
+    /// * `body` - The raw JSON document
+    pub fn insert(&self, ip: &IpAddr, body: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.lock().insert(
+            *ip,
+            CachedLookup {
+                ip: *ip,
+                body: body.to_string(),
+                fetched_at: Utc::now(),
+            },
+        );
+    }
+
+    /// Get the number of documents kept, including expired ones
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check whether no document is kept
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Forget the documents that expired
+    ///
+    /// # Returns
+    ///
+    /// The number of documents forgotten
+    pub fn prune(&self) -> usize {
+        let mut entries = self.lock();
+        let before = entries.len();
+        entries.retain(|_, entry| self.is_fresh(entry));
+        before - entries.len()
+    }
+
+    /// Get the documents still fresh, for saving
+    #[must_use]
+    pub fn snapshots(&self) -> Vec<CachedLookup> {
+        let mut snapshots: Vec<CachedLookup> = self
+            .lock()
+            .values()
+            .filter(|entry| self.is_fresh(entry))
+            .cloned()
+            .collect();
+        snapshots.sort_by_key(|entry| entry.fetched_at);
+        snapshots
+    }
+
+    /// Restore saved documents, keeping newer ones already held
+    ///
+    /// # Arguments
+    ///
+    /// * `stored` - Documents saved by an earlier run
+    pub fn restore(&self, stored: Vec<CachedLookup>) {
+        let mut entries = self.lock();
+        for entry in stored.into_iter().filter(|entry| self.is_fresh(entry)) {
+            let newer_held = entries
+                .get(&entry.ip)
+                .is_some_and(|held| held.fetched_at >= entry.fetched_at);
+            if !newer_held {
+                entries.insert(entry.ip, entry);
+            }
+        }
+    }
+
+    /// Checks whether a document was fetched within the time to live
+    fn is_fresh(&self, entry: &CachedLookup) -> bool {
+        let ttl = chrono::Duration::from_std(self.ttl()).unwrap_or(chrono::Duration::MAX);
+        Utc::now().signed_duration_since(entry.fetched_at) < ttl
+    }
+
+    /// Lock the documents, recovering them if a holder panicked
+    fn lock(&self) -> MutexGuard<'_, HashMap<IpAddr, CachedLookup>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -7,8 +7,17 @@
 //! ## Components
 //!
 //! * **IP Info** - Fetches and processes IP-related data
+//! * **Capture** - Keeps raw judge and provider responses for debugging
+//! * **Churn** - Measures how long proxies keep working after they are found
+//! * **Lookup Cache** - Keeps looked up documents so addresses aren't looked up twice
 //! * **CIDR** - Handles CIDR block operations
+//! * **Cloud Ranges** - Recognises addresses of large cloud providers
+//! * **`GeoIP`** - Looks addresses up in local `MaxMind` databases (`geoip` feature)
 //! * **Ownership** - Determines proxy ownership and related metadata
+//! * **Usage** - Accounts lookups against provider quotas
+//!
+//! The lookups and the judge make requests, so they need the `sleuth` and
+//! `judge` features. The data types they produce are always available.
 //!
 //! ## Overview
 //!
@@ -39,15 +48,36 @@
 //! # }
 //! ```
 
+pub mod capture;
+pub mod churn;
 pub mod cidr;
+pub mod cloudranges;
+#[cfg(feature = "geoip")]
+pub mod geoip;
 pub mod ipinfo;
 pub mod judgement;
 pub mod location;
+pub mod lookup_cache;
 pub mod ownership;
+pub mod usage;
 
 // Re-exports from modules
-pub use cidr::Cidr;
-pub use ipinfo::{IpMetadata, Sleuth};
+pub use capture::{CapturedResponse, ResponseCapture};
+pub use cidr::{Cidr, CidrSet};
+#[cfg(feature = "sleuth")]
+pub use cloudranges::CloudRanges;
+#[cfg(feature = "geoip")]
+pub use geoip::GeoIpDatabase;
+pub use ipinfo::IpMetadata;
+#[cfg(feature = "sleuth")]
+pub use ipinfo::Sleuth;
+#[cfg(feature = "judge")]
 pub use judgement::Judge;
+pub use judgement::{
+    JudgeBaseline, JudgeBatchOptions, JudgeBatchSummary, JudgeVerdict, JudgementReport,
+};
 pub use location::Location;
-pub use ownership::{AutonomousSystem, NetworkInfo, Organization, OwnershipLookup};
+pub use lookup_cache::LookupCache;
+#[cfg(feature = "sleuth")]
+pub use ownership::OwnershipLookup;
+pub use ownership::{AutonomousSystem, NetworkInfo, Organization};
@@ -40,11 +40,27 @@
 //! # }
 //! ```
 
-use crate::definitions::errors::{OwnershipError, OwnershipResult};
+use crate::definitions::enums::Registry;
+#[cfg(feature = "sleuth")]
+use crate::definitions::{
+    defaults,
+    errors::{OwnershipError, OwnershipResult},
+};
 use crate::inspection::Location;
-use reqwest::Client;
+#[cfg(feature = "sleuth")]
+use crate::inspection::{cidr, lookup_cache::LookupCache, usage::UsageTracker};
+#[cfg(feature = "sleuth")]
+use crate::io::http::RateLimiter;
+#[cfg(feature = "sleuth")]
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "sleuth")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "sleuth")]
 use std::net::IpAddr;
+#[cfg(feature = "sleuth")]
+use std::sync::{Arc, Mutex, PoisonError};
+#[cfg(feature = "sleuth")]
 use std::time::Duration;
 
 /// Represents the ownership information of an organization.
@@ -94,6 +110,7 @@ pub struct Organization {
 ///     cidr: Some("192.168.0.0/24".to_string()),
 ///     organization: Some(org),
 ///     location: None,
+///     ..Default::default()
 /// };
 ///
 /// assert_eq!(network.cidr.as_deref(), Some("192.168.0.0/24"));
@@ -108,6 +125,14 @@ pub struct NetworkInfo {
 
     /// Location associated with this network
     pub location: Option<Location>,
+
+    /// Email address for reporting abuse from this network
+    #[serde(default)]
+    pub abuse_email: Option<String>,
+
+    /// Regional internet registry the network was allocated by
+    #[serde(default)]
+    pub registry: Option<Registry>,
 }
 
 impl Organization {
@@ -183,6 +208,7 @@ impl Organization {
 ///     organization: Some("Google LLC".to_string()),
 ///     country: Some("US".to_string()),
 ///     description: Some("Google Global LLC".to_string()),
+///     ..Default::default()
 /// };
 ///
 /// assert_eq!(asn.number, 15169);
@@ -201,6 +227,10 @@ pub struct AutonomousSystem {
 
     /// The description or name of the ASN
     pub description: Option<String>,
+
+    /// Regional internet registry the ASN was allocated by
+    #[serde(default)]
+    pub registry: Option<Registry>,
 }
 
 /// Service for looking up ASN and organization information
@@ -235,16 +265,161 @@ pub struct AutonomousSystem {
 ///     Ok(())
 /// }
 /// ```
+#[cfg(feature = "sleuth")]
 pub struct OwnershipLookup {
     client: Client,
+
+    /// Accounts lookups against provider quotas, if set
+    usage: Option<Arc<UsageTracker>>,
+
+    /// ipinfo.io API token, if set
+    api_token: Option<String>,
+
+    /// ipinfo.io documents looked up before, if set
+    lookup_cache: Option<Arc<LookupCache>>,
+
+    /// Paces requests to ipinfo.io and the RDAP registries
+    rate_limiter: Arc<RateLimiter>,
+
+    /// RDAP entities fetched so far, by URL
+    ///
+    /// Entities that could not be found are cached as `None`.
+    rdap_cache: Arc<Mutex<HashMap<String, Option<RdapEntity>>>>,
+}
+
+/// An organization entity resolved through RDAP
+#[cfg(feature = "sleuth")]
+#[derive(Debug, Clone)]
+struct RdapEntity {
+    /// The registry handle of the entity
+    handle: Option<String>,
+
+    /// The name of the entity, from its vCard
+    name: Option<String>,
+
+    /// URLs of the entities the entity belongs to
+    parents: Vec<String>,
 }
 
+#[cfg(feature = "sleuth")]
+impl RdapEntity {
+    /// Parse an RDAP entity object
+    fn from_json(data: &serde_json::Value) -> Self {
+        let handle = data
+            .get("handle")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        let name = vcard_name(data).or_else(|| handle.clone());
+
+        let mut parents: Vec<String> = entity_links(data, "up");
+        for entity in data
+            .get("entities")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if has_role(entity, "registrant")
+                && let Some(url) = entity_url(entity)
+                && !parents.contains(&url)
+            {
+                parents.push(url);
+            }
+        }
+
+        RdapEntity {
+            handle,
+            name,
+            parents,
+        }
+    }
+
+    /// Convert the entity into an organization, without parent
+    fn to_organization(&self) -> Organization {
+        Organization::new(self.name.clone(), None)
+    }
+}
+
+/// Returns the address to look `ip` up by
+///
+/// IPv4-mapped addresses are looked up as IPv4, and addresses that aren't
+/// publicly routable are refused, since no registry has records for them.
+#[cfg(feature = "sleuth")]
+fn lookup_target(ip: &IpAddr) -> OwnershipResult<IpAddr> {
+    cidr::helpers::lookup_address(ip)
+        .ok_or_else(|| OwnershipError::NotFound(format!("{ip} is not a publicly routable address")))
+}
+
+/// Checks whether an RDAP entity has the given role
+#[cfg(feature = "sleuth")]
+fn has_role(entity: &serde_json::Value, role: &str) -> bool {
+    entity
+        .get("roles")
+        .and_then(|v| v.as_array())
+        .is_some_and(|roles| roles.iter().any(|r| r.as_str() == Some(role)))
+}
+
+/// Returns the formatted name of an RDAP object's vCard
+#[cfg(feature = "sleuth")]
+fn vcard_name(data: &serde_json::Value) -> Option<String> {
+    data.get("vcardArray")?
+        .get(1)?
+        .as_array()?
+        .iter()
+        .find(|property| property.get(0).and_then(|v| v.as_str()) == Some("fn"))?
+        .get(3)?
+        .as_str()
+        .map(String::from)
+}
+
+/// Returns the entity URLs an RDAP object links to with the given relation
+#[cfg(feature = "sleuth")]
+fn entity_links(data: &serde_json::Value, rel: &str) -> Vec<String> {
+    data.get("links")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|link| link.get("rel").and_then(|v| v.as_str()) == Some(rel))
+        .filter_map(|link| link.get("href").and_then(|v| v.as_str()))
+        .filter(|href| href.contains("/entity/"))
+        .map(String::from)
+        .collect()
+}
+
+/// Returns the URL of an RDAP entity
+///
+/// Prefers the entity's own `self` link and falls back to the bootstrap
+/// service, which redirects to the registry holding the handle.
+#[cfg(feature = "sleuth")]
+fn entity_url(entity: &serde_json::Value) -> Option<String> {
+    entity_links(entity, "self").into_iter().next().or_else(|| {
+        entity
+            .get("handle")
+            .and_then(|v| v.as_str())
+            .map(|handle| format!("{}/entity/{handle}", defaults::rdap::BOOTSTRAP_URL))
+    })
+}
+
+/// Returns the URL of the entity that registered an RDAP network
+///
+/// Falls back to the first entity if none has the registrant role.
+#[cfg(feature = "sleuth")]
+fn registrant_url(network: &serde_json::Value) -> Option<String> {
+    let entities = network.get("entities")?.as_array()?;
+    entities
+        .iter()
+        .find(|e| has_role(e, "registrant"))
+        .or_else(|| entities.first())
+        .and_then(entity_url)
+}
+
+#[cfg(feature = "sleuth")]
 impl Default for OwnershipLookup {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[cfg(feature = "sleuth")]
 impl OwnershipLookup {
     /// Create a new ownership lookup service with default configuration
     ///
@@ -261,7 +436,7 @@ impl OwnershipLookup {
             .build()
             .unwrap_or_else(|_| Client::new());
 
-        OwnershipLookup { client }
+        Self::with_client(client)
     }
 
     /// Create a new ownership lookup service with a custom HTTP client
@@ -275,34 +450,113 @@ impl OwnershipLookup {
     /// A new `OwnershipLookup` instance with the specified client
     #[must_use]
     pub fn with_client(client: Client) -> Self {
-        OwnershipLookup { client }
+        OwnershipLookup {
+            client,
+            usage: None,
+            api_token: None,
+            lookup_cache: None,
+            rate_limiter: Arc::new(RateLimiter::default()),
+            rdap_cache: Arc::new(Mutex::new(HashMap::new())),
+        }
     }
 
-    /// Lookup ASN information for an IP address
+    /// Account every lookup in a usage tracker
     ///
     /// # Arguments
     ///
-    /// * `ip` - The IP address to lookup
+    /// * `usage` - The tracker shared with other lookup services
     ///
     /// # Returns
     ///
-    /// The ASN as a string if found, or None if not available
+    /// Self with the tracker set
+    #[must_use]
+    pub fn with_usage_tracker(mut self, usage: Arc<UsageTracker>) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Authenticate requests with an ipinfo.io API token
     ///
-    /// # Errors
+    /// # Arguments
     ///
-    /// Returns an error if:
-    /// * The request to the ASN lookup service fails
-    /// * The response cannot be parsed
-    /// * The service returns an error status code
-    pub async fn lookup_asn(&self, ip: &IpAddr) -> OwnershipResult<Option<String>> {
-        // Use ipinfo.io's free API to get ASN information
-        let url = format!("https://ipinfo.io/{ip}/json");
+    /// * `token` - The API token, or `None` for anonymous requests
+    ///
+    /// # Returns
+    ///
+    /// Self with the token set
+    #[must_use]
+    pub fn with_api_token(mut self, token: Option<String>) -> Self {
+        self.api_token = token;
+        self
+    }
+
+    /// Reuse ipinfo.io documents kept in a lookup cache
+    ///
+    /// # Arguments
+    ///
+    /// * `cache` - The cache, usually shared with a `Sleuth`
+    ///
+    /// # Returns
+    ///
+    /// Self with the cache set
+    #[must_use]
+    pub fn with_lookup_cache(mut self, cache: Arc<LookupCache>) -> Self {
+        self.lookup_cache = Some(cache);
+        self
+    }
+
+    /// Pace requests with a shared rate limiter
+    ///
+    /// Requests answered with 429 Too Many Requests are retried after a
+    /// backoff instead of failing with `OwnershipError::RateLimited`.
+    ///
+    /// # Arguments
+    ///
+    /// * `limiter` - The limiter, usually shared with a `Sleuth`
+    ///
+    /// # Returns
+    ///
+    /// Self with the limiter set
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
+
+    /// Build an ipinfo.io request, accounting it and attaching the API token
+    fn ipinfo_request(&self, url: &str) -> RequestBuilder {
+        if let Some(usage) = &self.usage {
+            usage.record(defaults::providers::IPINFO);
+        }
+        let request = self.client.get(url).header("Accept", "application/json");
+        match &self.api_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Build an RDAP request, accounting it
+    fn rdap_request(&self, url: &str) -> RequestBuilder {
+        if let Some(usage) = &self.usage {
+            usage.record(defaults::providers::RDAP);
+        }
+        self.client
+            .get(url)
+            .header("Accept", "application/rdap+json")
+    }
+
+    /// Request the ipinfo.io document about an address
+    ///
+    /// Documents still in the lookup cache are reused without a request.
+    async fn ipinfo_json(&self, ip: &IpAddr) -> OwnershipResult<serde_json::Value> {
+        if let Some(data) = self.lookup_cache.as_ref().and_then(|cache| cache.get(ip)) {
+            return Ok(data);
+        }
 
+        let url = format!("https://ipinfo.io/{ip}/json");
         let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+            .rate_limiter
+            .send(self.ipinfo_request(&url))
             .await
             .map_err(|e| OwnershipError::NetworkError(e.to_string()))?;
 
@@ -317,19 +571,108 @@ impl OwnershipLookup {
             };
         }
 
-        let data: serde_json::Value = response
+        let body = response
+            .text()
+            .await
+            .map_err(|e| OwnershipError::NetworkError(e.to_string()))?;
+        let data: serde_json::Value =
+            serde_json::from_str(&body).map_err(|e| OwnershipError::ParseError(e.to_string()))?;
+
+        if let Some(cache) = &self.lookup_cache {
+            cache.insert(ip, &body);
+        }
+        Ok(data)
+    }
+
+    /// Fetch an RDAP object
+    ///
+    /// # Returns
+    ///
+    /// The object, or None if the registry doesn't know it
+    async fn fetch_rdap(&self, url: &str) -> OwnershipResult<Option<serde_json::Value>> {
+        let response = self
+            .rate_limiter
+            .send(self.rdap_request(url))
+            .await
+            .map_err(|e| OwnershipError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return match response.status().as_u16() {
+                404 => Ok(None),
+                429 => Err(OwnershipError::RateLimited),
+                _ => Err(OwnershipError::ApiError(format!(
+                    "Status {}",
+                    response.status()
+                ))),
+            };
+        }
+
+        response
             .json()
             .await
-            .map_err(|e| OwnershipError::ParseError(e.to_string()))?;
+            .map(Some)
+            .map_err(|e| OwnershipError::ParseError(e.to_string()))
+    }
+
+    /// Resolve an RDAP entity, using the cache when possible
+    async fn resolve_entity(&self, url: &str) -> OwnershipResult<Option<RdapEntity>> {
+        if let Some(cached) = self.rdap_cache().get(url) {
+            return Ok(cached.clone());
+        }
+
+        let entity = self
+            .fetch_rdap(url)
+            .await?
+            .map(|data| RdapEntity::from_json(&data));
+        self.rdap_cache().insert(url.to_string(), entity.clone());
+        Ok(entity)
+    }
+
+    /// Lock the RDAP cache, recovering it if a holder panicked
+    fn rdap_cache(&self) -> std::sync::MutexGuard<'_, HashMap<String, Option<RdapEntity>>> {
+        self.rdap_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the number of RDAP entities cached so far
+    ///
+    /// Entities are shared between the ownership chains of all IPs, so a
+    /// chain of a large ISP is fetched once rather than once per address.
+    #[must_use]
+    pub fn cached_entities(&self) -> usize {
+        self.rdap_cache().len()
+    }
+
+    /// Lookup ASN information for an IP address
+    ///
+    /// # Arguments
+    ///
+    /// * `ip` - The IP address to lookup
+    ///
+    /// # Returns
+    ///
+    /// The ASN as a string if found, or None if not available
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The address is not publicly routable
+    /// * The request to the ASN lookup service fails
+    /// * The response cannot be parsed
+    /// * The service returns an error status code
+    pub async fn lookup_asn(&self, ip: &IpAddr) -> OwnershipResult<Option<String>> {
+        let ip = &lookup_target(ip)?;
+
+        // Use ipinfo.io's free API to get ASN information
+        let data = self.ipinfo_json(ip).await?;
 
         let asn = data.get("org").and_then(|v| v.as_str()).and_then(|org| {
             // ASN is often prefixed in the org field like "AS15169 Google LLC"
-            let parts: Vec<&str> = org.split_whitespace().collect();
-            if !parts.is_empty() && parts[0].starts_with("AS") {
-                Some(parts[0].trim_start_matches("AS").to_string())
-            } else {
-                None
-            }
+            org.split_whitespace()
+                .next()
+                .filter(|asn| asn.starts_with("AS"))
+                .map(|asn| asn.trim_start_matches("AS").to_string())
         });
 
         Ok(asn)
@@ -348,49 +691,26 @@ impl OwnershipLookup {
     /// # Errors
     ///
     /// Returns an error if:
+    /// * The address is not publicly routable
     /// * The request to the organization lookup service fails
     /// * The response cannot be parsed
     /// * The service returns an error status code
     pub async fn lookup_organization(&self, ip: &IpAddr) -> OwnershipResult<Option<Organization>> {
-        // Use ipinfo.io's free API to get organization information
-        let url = format!("https://ipinfo.io/{ip}/json");
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| OwnershipError::NetworkError(e.to_string()))?;
-
-        if !response.status().is_success() {
-            return match response.status().as_u16() {
-                404 => Err(OwnershipError::NotFound(ip.to_string())),
-                429 => Err(OwnershipError::RateLimited),
-                _ => Err(OwnershipError::ApiError(format!(
-                    "Status {}",
-                    response.status()
-                ))),
-            };
-        }
+        let ip = &lookup_target(ip)?;
 
-        let data: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| OwnershipError::ParseError(e.to_string()))?;
+        // Use ipinfo.io's free API to get organization information
+        let data = self.ipinfo_json(ip).await?;
 
         let org_str = data.get("org").and_then(|v| v.as_str());
 
         if let Some(org_str) = org_str {
             // Parse organization string like "AS15169 Google LLC"
-            let parts: Vec<&str> = org_str.splitn(2, ' ').collect();
-            let (asn, name) = if parts.len() == 2 && parts[0].starts_with("AS") {
-                (
-                    Some(parts[0].trim_start_matches("AS").to_string()),
-                    Some(parts[1].to_string()),
-                )
-            } else {
-                (None, Some(org_str.to_string()))
+            let (asn, name) = match org_str.split_once(' ') {
+                Some((asn, name)) if asn.starts_with("AS") => (
+                    Some(asn.trim_start_matches("AS").to_string()),
+                    Some(name.to_string()),
+                ),
+                _ => (None, Some(org_str.to_string())),
             };
 
             let org = Organization {
@@ -405,12 +725,18 @@ impl OwnershipLookup {
         }
     }
 
-    /// Try to find parent organizations and ownership chain
+    /// Find the ownership chain of an IP address
     ///
-    /// Attempts to build a chain of organization ownership for the IP address.
-    /// In this simplified implementation, it returns just the immediate organization.
-    /// A more comprehensive implementation would follow ownership chains through
-    /// multiple data sources.
+    /// Queries RDAP for the network containing the address and follows the
+    /// registrant entity through its parent organizations, up to
+    /// `defaults::rdap::MAX_CHAIN_DEPTH` levels. Entities already visited in
+    /// the chain end it, so cyclic registrations can't loop. Resolved entities
+    /// are cached and shared by later lookups.
+    ///
+    /// If RDAP has no registrant for the network, the chain falls back to the
+    /// immediate organization reported by ipinfo.io. The direct owner carries
+    /// the ASN reported by ipinfo.io when its name matches, and every
+    /// organization in the chain has its parent set.
     ///
     /// # Arguments
     ///
@@ -423,22 +749,71 @@ impl OwnershipLookup {
     ///
     /// # Errors
     ///
-    /// Returns an error if the organization lookup fails
+    /// Returns an error if the address is not publicly routable or the
+    /// organization lookup fails
     ///
     /// # Note
     ///
-    /// This requires multiple API calls and might hit rate limits with free APIs
+    /// This requires one RDAP request per uncached organization in the chain
     pub async fn lookup_ownership_chain(&self, ip: &IpAddr) -> OwnershipResult<Vec<Organization>> {
-        // This is a simplified implementation as full ownership chain lookup
-        // would require premium API access or multiple data sources.
-        // For now, we'll just return the immediate organization.
+        let ip = &lookup_target(ip)?;
+        let url = format!("{}/ip/{ip}", defaults::rdap::BOOTSTRAP_URL);
+        let registrant = self
+            .fetch_rdap(&url)
+            .await?
+            .and_then(|network| registrant_url(&network));
+
+        let mut chain: Vec<Organization> = Vec::new();
+        let mut visited = HashSet::new();
+        let mut next = registrant;
+        while let Some(url) = next.take() {
+            if chain.len() >= defaults::rdap::MAX_CHAIN_DEPTH || !visited.insert(url.clone()) {
+                break;
+            }
+            let Some(entity) = self.resolve_entity(&url).await? else {
+                break;
+            };
+            if entity
+                .handle
+                .as_ref()
+                .is_some_and(|handle| !visited.insert(handle.clone()))
+            {
+                break;
+            }
+            chain.push(entity.to_organization());
+            next = entity
+                .parents
+                .into_iter()
+                .find(|parent| !visited.contains(parent));
+        }
 
-        let org = self.lookup_organization(ip).await?;
+        if chain.is_empty() {
+            return Ok(self.lookup_organization(ip).await?.into_iter().collect());
+        }
 
-        match org {
-            Some(org) => Ok(vec![org]),
-            None => Ok(vec![]),
+        // RDAP doesn't tie organizations to an ASN, so borrow the direct
+        // owner's from ipinfo.io when both agree on who it is
+        if let Some(owner) = chain.first_mut()
+            && let Ok(Some(org)) = self.lookup_organization(ip).await
+            && org
+                .name
+                .as_deref()
+                .zip(owner.name.as_deref())
+                .is_some_and(|(a, b)| a.eq_ignore_ascii_case(b))
+        {
+            owner.asn = org.asn;
         }
+
+        // Link every organization to its parent, from the top down
+        let mut parent = None;
+        for org in chain.iter_mut().rev() {
+            if let Some(parent) = parent.take() {
+                org.parent = Some(parent);
+            }
+            parent = Some(Box::new(org.clone()));
+        }
+
+        Ok(chain)
     }
 
     /// Lookup detailed information about an ASN
@@ -473,10 +848,8 @@ impl OwnershipLookup {
         let url = format!("https://ipinfo.io/AS{asn_num}/json");
 
         let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+            .rate_limiter
+            .send(self.ipinfo_request(&url))
             .await
             .map_err(|e| OwnershipError::NetworkError(e.to_string()))?;
 
@@ -505,6 +878,10 @@ impl OwnershipLookup {
             .get("domain")
             .and_then(|v| v.as_str())
             .map(String::from);
+        let registry = data
+            .get("registry")
+            .and_then(|v| v.as_str())
+            .and_then(|v| v.parse().ok());
 
         // Only create an ASN if we have at least some information
         if org.is_some() || country.is_some() || description.is_some() {
@@ -513,6 +890,7 @@ impl OwnershipLookup {
                 organization: org,
                 country,
                 description,
+                registry,
             };
 
             Ok(Some(asn_details))
@@ -0,0 +1,376 @@
+//! # Provider Usage Module
+//!
+//! This module accounts for lookups made against external IP information
+//! providers and compares them with the providers' quotas.
+//!
+//! ## Components
+//!
+//! * **`UsageTracker`** - Counts lookups per provider for the current hour and day
+//! * **`ProviderQuota`** - The hourly and daily allowance of a provider
+//! * **`ProviderUsage`** - A snapshot of a provider's lookup counts
+//! * **`QuotaEvent`** - Raised when a provider approaches or reaches its quota
+//!
+//! Crossing the warning threshold or the quota itself is logged once per
+//! window and queued as a `QuotaEvent`, so bulk enrichment doesn't run into a
+//! provider's limit unnoticed.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::enums::QuotaWindow;
+//! use gooty_proxy::inspection::usage::{ProviderQuota, UsageTracker};
+//! use std::collections::BTreeMap;
+//!
+//! let quotas = BTreeMap::from([(
+//!     "ipinfo".to_string(),
+//!     ProviderQuota {
+//!         hourly: None,
+//!         daily: Some(10),
+//!     },
+//! )]);
+//! let tracker = UsageTracker::new(quotas, 0.8);
+//!
+//! for _ in 0..8 {
+//!     tracker.record("ipinfo");
+//! }
+//!
+//! let usage = tracker.usage();
+//! assert_eq!(usage[0].day_count, 8);
+//!
+//! // The eighth lookup crossed 80% of the daily quota
+//! let events = tracker.take_events();
+//! assert_eq!(events.len(), 1);
+//! assert_eq!(events[0].window, QuotaWindow::Day);
+//! assert!(!events[0].exceeded);
+//! ```
+
+use crate::definitions::{defaults, enums::QuotaWindow};
+use chrono::{DateTime, Duration, DurationRound, NaiveTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Lookup allowance of a provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderQuota {
+    /// Lookups allowed per hour, if limited
+    pub hourly: Option<u64>,
+
+    /// Lookups allowed per day, if limited
+    pub daily: Option<u64>,
+}
+
+impl ProviderQuota {
+    /// Returns the default quotas of the known providers
+    #[must_use]
+    pub fn defaults() -> BTreeMap<String, ProviderQuota> {
+        BTreeMap::from([(
+            defaults::providers::IPINFO.to_string(),
+            ProviderQuota {
+                hourly: None,
+                daily: Some(defaults::providers::IPINFO_DAILY_QUOTA),
+            },
+        )])
+    }
+
+    /// Returns the quota of a window
+    #[must_use]
+    pub fn for_window(&self, window: QuotaWindow) -> Option<u64> {
+        match window {
+            QuotaWindow::Hour => self.hourly,
+            QuotaWindow::Day => self.daily,
+        }
+    }
+}
+
+/// Lookup counts of a single provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderUsage {
+    /// Name of the provider
+    pub provider: String,
+
+    /// Start of the hour `hour_count` covers
+    pub hour_start: DateTime<Utc>,
+
+    /// Lookups made in the current hour
+    pub hour_count: u64,
+
+    /// Start of the day `day_count` covers
+    pub day_start: DateTime<Utc>,
+
+    /// Lookups made in the current day
+    pub day_count: u64,
+
+    /// Lookups made since accounting started
+    pub total: u64,
+
+    /// The provider's quota
+    #[serde(default)]
+    pub quota: ProviderQuota,
+}
+
+impl ProviderUsage {
+    /// Creates empty usage for a provider
+    fn new(provider: &str, quota: ProviderQuota, now: DateTime<Utc>) -> Self {
+        ProviderUsage {
+            provider: provider.to_string(),
+            hour_start: hour_start(now),
+            hour_count: 0,
+            day_start: day_start(now),
+            day_count: 0,
+            total: 0,
+            quota,
+        }
+    }
+
+    /// Returns the lookups made in a window
+    #[must_use]
+    pub fn count(&self, window: QuotaWindow) -> u64 {
+        match window {
+            QuotaWindow::Hour => self.hour_count,
+            QuotaWindow::Day => self.day_count,
+        }
+    }
+
+    /// Returns the lookups left in a window, if it is limited
+    #[must_use]
+    pub fn remaining(&self, window: QuotaWindow) -> Option<u64> {
+        self.quota
+            .for_window(window)
+            .map(|quota| quota.saturating_sub(self.count(window)))
+    }
+
+    /// Starts new windows if `now` is past the current ones
+    ///
+    /// Counts of windows that have ended are reset to zero.
+    pub fn roll_over(&mut self, now: DateTime<Utc>) {
+        if hour_start(now) != self.hour_start {
+            self.hour_start = hour_start(now);
+            self.hour_count = 0;
+        }
+        if day_start(now) != self.day_start {
+            self.day_start = day_start(now);
+            self.day_count = 0;
+        }
+    }
+}
+
+/// Notice that a provider approaches or reached its quota
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaEvent {
+    /// Name of the provider
+    pub provider: String,
+
+    /// The window whose quota is concerned
+    pub window: QuotaWindow,
+
+    /// Lookups made in the window
+    pub used: u64,
+
+    /// The window's quota
+    pub quota: u64,
+
+    /// Whether the quota has been reached, rather than only approached
+    pub exceeded: bool,
+}
+
+/// Counts lookups per provider and warns when quotas run low
+///
+/// The tracker is shared between the lookup services, so every request made
+/// on behalf of a sleuth is accounted in one place.
+#[derive(Debug)]
+pub struct UsageTracker {
+    /// Usage of every provider seen so far
+    usage: Mutex<BTreeMap<String, ProviderUsage>>,
+
+    /// Quotas of the known providers
+    quotas: BTreeMap<String, ProviderQuota>,
+
+    /// Share of a quota after which warnings are emitted
+    warning_ratio: f64,
+
+    /// Events raised and not yet taken
+    events: Mutex<Vec<QuotaEvent>>,
+}
+
+impl Default for UsageTracker {
+    fn default() -> Self {
+        Self::new(
+            ProviderQuota::defaults(),
+            defaults::providers::QUOTA_WARNING_RATIO,
+        )
+    }
+}
+
+impl UsageTracker {
+    /// Creates a tracker with the given quotas
+    ///
+    /// # Arguments
+    ///
+    /// * `quotas` - Quotas by provider name; unlisted providers are unlimited
+    /// * `warning_ratio` - Share of a quota after which warnings are emitted
+    #[must_use]
+    pub fn new(quotas: BTreeMap<String, ProviderQuota>, warning_ratio: f64) -> Self {
+        UsageTracker {
+            usage: Mutex::new(BTreeMap::new()),
+            quotas,
+            warning_ratio: warning_ratio.clamp(0.0, 1.0),
+            events: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Accounts one lookup against a provider
+    ///
+    /// Logs a warning and queues a `QuotaEvent` when the lookup crosses the
+    /// warning threshold or the quota of a window.
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - Name of the provider the lookup was made against
+    pub fn record(&self, provider: &str) {
+        let now = Utc::now();
+        let quota = self.quotas.get(provider).copied().unwrap_or_default();
+
+        let mut raised = Vec::new();
+        {
+            let mut usage = lock(&self.usage);
+            let entry = usage
+                .entry(provider.to_string())
+                .or_insert_with(|| ProviderUsage::new(provider, quota, now));
+            entry.roll_over(now);
+            entry.hour_count += 1;
+            entry.day_count += 1;
+            entry.total += 1;
+
+            for window in [QuotaWindow::Hour, QuotaWindow::Day] {
+                if let Some(event) = self.crossed(entry, window) {
+                    raised.push(event);
+                }
+            }
+        }
+
+        for event in &raised {
+            if event.exceeded {
+                warn!(
+                    "{} lookup quota reached: {}/{} this {}",
+                    event.provider,
+                    event.used,
+                    event.quota,
+                    event.window.to_string().to_lowercase()
+                );
+            } else {
+                warn!(
+                    "{} lookup quota running low: {}/{} this {}",
+                    event.provider,
+                    event.used,
+                    event.quota,
+                    event.window.to_string().to_lowercase()
+                );
+            }
+        }
+        lock(&self.events).extend(raised);
+    }
+
+    /// Checks whether a provider has used up the quota of any window
+    #[must_use]
+    pub fn is_exhausted(&self, provider: &str) -> bool {
+        self.usage().iter().any(|u| {
+            u.provider == provider
+                && [QuotaWindow::Hour, QuotaWindow::Day]
+                    .into_iter()
+                    .any(|w| u.remaining(w) == Some(0))
+        })
+    }
+
+    /// Returns the current usage of every provider, by name
+    #[must_use]
+    pub fn usage(&self) -> Vec<ProviderUsage> {
+        let now = Utc::now();
+        let mut usage = lock(&self.usage);
+        usage
+            .values_mut()
+            .map(|entry| {
+                entry.roll_over(now);
+                entry.clone()
+            })
+            .collect()
+    }
+
+    /// Restores usage recorded earlier, such as by a previous run
+    ///
+    /// Counts of windows that have since ended are discarded, and counts of
+    /// the current windows are only ever raised.
+    ///
+    /// # Arguments
+    ///
+    /// * `stored` - Usage previously returned by `usage`
+    pub fn restore(&self, stored: Vec<ProviderUsage>) {
+        let now = Utc::now();
+        let mut usage = lock(&self.usage);
+        for mut stored in stored {
+            stored.roll_over(now);
+            let quota = self
+                .quotas
+                .get(&stored.provider)
+                .copied()
+                .unwrap_or_default();
+            let entry = usage
+                .entry(stored.provider.clone())
+                .or_insert_with(|| ProviderUsage::new(&stored.provider, quota, now));
+            entry.roll_over(now);
+            entry.hour_count = entry.hour_count.max(stored.hour_count);
+            entry.day_count = entry.day_count.max(stored.day_count);
+            entry.total = entry.total.max(stored.total);
+        }
+    }
+
+    /// Takes the events raised since the last call
+    #[must_use]
+    pub fn take_events(&self) -> Vec<QuotaEvent> {
+        std::mem::take(&mut *lock(&self.events))
+    }
+
+    /// Returns the event raised by the last lookup in a window, if any
+    ///
+    /// An event is raised only by the lookup that reaches the warning
+    /// threshold or the quota, so each is reported once per window.
+    fn crossed(&self, usage: &ProviderUsage, window: QuotaWindow) -> Option<QuotaEvent> {
+        let quota = usage.quota.for_window(window)?;
+        let used = usage.count(window);
+
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss
+        )]
+        let threshold = ((quota as f64 * self.warning_ratio).ceil() as u64).max(1);
+
+        let exceeded = used == quota;
+        if !exceeded && used != threshold {
+            return None;
+        }
+        Some(QuotaEvent {
+            provider: usage.provider.clone(),
+            window,
+            used,
+            quota,
+            exceeded,
+        })
+    }
+}
+
+/// Locks a mutex, recovering the data if a holder panicked
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the start of the hour containing `now`
+fn hour_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.duration_trunc(Duration::hours(1)).unwrap_or(now)
+}
+
+/// Returns the start of the day containing `now`
+fn day_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive().and_time(NaiveTime::MIN).and_utc()
+}
@@ -0,0 +1,212 @@
+//! # Archive Module
+//!
+//! Unpacks proxy lists that sources publish compressed or archived.
+//!
+//! Some sources serve `.txt.gz` files or zip archives holding several lists.
+//! The format of a response is recognized from its leading bytes and its
+//! `Content-Encoding` header, whatever its URL or content type claim, and
+//! the lists are extracted as text before proxies are searched for in them.
+//!
+//! ## Components
+//!
+//! * **`ArchiveFormat`** - The formats a source response can arrive in
+//! * **`extract_text`** - Turns a response body into the text of its lists
+//!
+//! Extraction stops at a size limit, so a small archive can't expand into
+//! gigabytes of memory.
+//!
+//! ## Examples
+//!
+//! ```
+//! use flate2::{Compression, write::GzEncoder};
+//! use gooty_proxy::io::archive::{self, ArchiveFormat};
+//! use std::io::Write;
+//!
+//! let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+//! encoder.write_all(b"203.0.113.7:8080\n198.51.100.1:3128\n").unwrap();
+//! let body = encoder.finish().unwrap();
+//!
+//! assert_eq!(ArchiveFormat::detect(&body, None), ArchiveFormat::Gzip);
+//! let text = archive::extract_text(&body, None, 1024).unwrap();
+//! assert_eq!(text.lines().count(), 2);
+//!
+//! // Text beyond the limit is refused
+//! assert!(archive::extract_text(&body, None, 16).is_err());
+//! ```
+
+use crate::definitions::errors::{SourceError, SourceResult};
+use flate2::read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Leading bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Leading bytes of a zip archive, the signature of its first local file header
+const ZIP_MAGIC: [u8; 4] = *b"PK\x03\x04";
+
+/// The formats a source response can arrive in
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::archive::ArchiveFormat;
+///
+/// assert_eq!(ArchiveFormat::detect(b"10.0.0.1:8080", None), ArchiveFormat::Plain);
+/// assert_eq!(ArchiveFormat::detect(b"PK\x03\x04....", None), ArchiveFormat::Zip);
+/// assert_eq!(ArchiveFormat::detect(b"x\x9c....", Some("deflate")), ArchiveFormat::Deflate);
+///
+/// // A body already decoded despite its header is plain text
+/// assert_eq!(ArchiveFormat::detect(b"10.0.0.1:8080", Some("gzip")), ArchiveFormat::Plain);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// Uncompressed text
+    Plain,
+
+    /// A gzip stream, such as a `.txt.gz` file, of one or more members
+    Gzip,
+
+    /// A body sent with `Content-Encoding: deflate`
+    Deflate,
+
+    /// A zip archive, whose files are extracted one after another
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Recognize the format of a response body
+    ///
+    /// Gzip streams and zip archives are recognized by their leading bytes,
+    /// and deflate, which has none, by the `Content-Encoding` header.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The response body
+    /// * `content_encoding` - The `Content-Encoding` header of the response, if any
+    #[must_use]
+    pub fn detect(body: &[u8], content_encoding: Option<&str>) -> Self {
+        if body.starts_with(&GZIP_MAGIC) {
+            ArchiveFormat::Gzip
+        } else if body.starts_with(&ZIP_MAGIC) {
+            ArchiveFormat::Zip
+        } else if content_encoding
+            .is_some_and(|encoding| encoding.trim().eq_ignore_ascii_case("deflate"))
+        {
+            ArchiveFormat::Deflate
+        } else {
+            ArchiveFormat::Plain
+        }
+    }
+}
+
+/// Extract the text of the lists in a response body
+///
+/// Compressed bodies are decompressed, and the files of a zip archive are
+/// joined by newlines, skipping directories. Bytes that aren't valid UTF-8
+/// are replaced, since only the proxies in the text matter.
+///
+/// # Arguments
+///
+/// * `body` - The response body
+/// * `content_encoding` - The `Content-Encoding` header of the response, if any
+/// * `max_size` - Largest text extracted, in bytes
+///
+/// # Returns
+///
+/// The text of the lists.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// * The body is compressed or archived, but corrupt or in an unsupported method
+/// * The extracted text is larger than `max_size`
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::archive;
+/// use std::io::{Cursor, Write};
+/// use zip::write::{SimpleFileOptions, ZipWriter};
+///
+/// let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+/// writer.add_directory("lists/", SimpleFileOptions::default()).unwrap();
+/// writer.start_file("lists/http.txt", SimpleFileOptions::default()).unwrap();
+/// writer.write_all(b"203.0.113.7:8080").unwrap();
+/// writer.start_file("lists/socks5.txt", SimpleFileOptions::default()).unwrap();
+/// writer.write_all(b"198.51.100.1:1080").unwrap();
+/// let body = writer.finish().unwrap().into_inner();
+///
+/// let text = archive::extract_text(&body, None, 1024).unwrap();
+/// assert_eq!(text, "203.0.113.7:8080\n198.51.100.1:1080");
+///
+/// assert!(archive::extract_text(&body[..20], None, 1024).is_err());
+/// ```
+pub fn extract_text(
+    body: &[u8],
+    content_encoding: Option<&str>,
+    max_size: u64,
+) -> SourceResult<String> {
+    let bytes = match ArchiveFormat::detect(body, content_encoding) {
+        ArchiveFormat::Plain => {
+            if u64::try_from(body.len()).unwrap_or(u64::MAX) > max_size {
+                return Err(SourceError::ResponseTooLarge(max_size));
+            }
+            return Ok(String::from_utf8_lossy(body).into_owned());
+        }
+        ArchiveFormat::Gzip => read_limited(MultiGzDecoder::new(body), max_size)?,
+        // Deflate is meant to be zlib-wrapped, but some servers send it raw
+        ArchiveFormat::Deflate => match read_limited(ZlibDecoder::new(body), max_size) {
+            Err(SourceError::ArchiveError(_)) => read_limited(DeflateDecoder::new(body), max_size)?,
+            result => result?,
+        },
+        ArchiveFormat::Zip => extract_zip(body, max_size)?,
+    };
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Join the files of a zip archive by newlines, up to a size limit
+fn extract_zip(body: &[u8], max_size: u64) -> SourceResult<Vec<u8>> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(body)).map_err(|e| SourceError::ArchiveError(e.to_string()))?;
+
+    let mut text = Vec::new();
+    for index in 0..archive.len() {
+        let file = archive
+            .by_index(index)
+            .map_err(|e| SourceError::ArchiveError(e.to_string()))?;
+        if file.is_dir() {
+            continue;
+        }
+
+        if !text.is_empty() {
+            text.push(b'\n');
+        }
+        // Every file gets what the files before it left of the limit
+        let used = u64::try_from(text.len()).unwrap_or(u64::MAX);
+        let contents = match read_limited(file, max_size.saturating_sub(used)) {
+            Err(SourceError::ResponseTooLarge(_)) => {
+                return Err(SourceError::ResponseTooLarge(max_size));
+            }
+            result => result?,
+        };
+        text.extend_from_slice(&contents);
+    }
+
+    Ok(text)
+}
+
+/// Read a decompressing reader to its end, failing once it outgrows the limit
+fn read_limited(reader: impl Read, max_size: u64) -> SourceResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader
+        .take(max_size.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .map_err(|e| SourceError::ArchiveError(e.to_string()))?;
+
+    if u64::try_from(bytes.len()).unwrap_or(u64::MAX) > max_size {
+        return Err(SourceError::ResponseTooLarge(max_size));
+    }
+    Ok(bytes)
+}
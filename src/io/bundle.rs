@@ -0,0 +1,505 @@
+//! # Bundle Module
+//!
+//! This module reads and writes source bundles, curated sets of sources that
+//! gatherers share with each other.
+//!
+//! ## Components
+//!
+//! * **`SourceBundle`** - The sources with who made them and how to use them
+//! * **`BundledSource`** - The definition of a single source, without its history
+//! * **`BundleSignature`** - An Ed25519 signature over a bundle and the key that made it
+//!
+//! A bundle is a TOML or JSON document naming its author, describing its
+//! sources, and suggesting the pattern proxies are extracted with and a cron
+//! schedule to scrape them on. Only the definitions of sources are carried,
+//! not how they fared for the author.
+//!
+//! Bundles can be signed, so whoever installs one can check it comes from an
+//! author they trust and wasn't changed since. The signature covers the
+//! bundle's JSON form without the signature, so a bundle keeps its signature
+//! when converted between TOML and JSON.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::source::Source;
+//! use gooty_proxy::io::bundle::{self, SourceBundle};
+//!
+//! let source = Source::new(
+//!     "https://example.com/proxies.txt".to_string(),
+//!     "Mozilla/5.0".to_string(),
+//!     r"(\d+\.\d+\.\d+\.\d+:\d+)".to_string(),
+//! )
+//! .unwrap();
+//!
+//! let key = bundle::generate_signing_key().unwrap();
+//! let mut shared = SourceBundle::from_sources("Daily lists".to_string(), &[source])
+//!     .with_author("alice".to_string())
+//!     .with_schedule("0 0 * * * *".to_string());
+//! shared.sign(&key).unwrap();
+//!
+//! // The signature survives a round trip through TOML
+//! let received = SourceBundle::from_toml(&shared.to_toml().unwrap()).unwrap();
+//! let signer = received.verify_trusted(&[key.verifying_key()]).unwrap();
+//! assert_eq!(signer, key.verifying_key());
+//! assert_eq!(received.to_sources().unwrap().len(), 1);
+//!
+//! // A changed bundle no longer matches its signature
+//! let mut tampered = received.clone();
+//! tampered.sources[0].url = "https://example.org/proxies.txt".to_string();
+//! assert!(tampered.verify().is_err());
+//! ```
+
+use crate::definitions::{
+    defaults,
+    errors::{BundleError, BundleResult},
+    source::Source,
+};
+use crate::utils;
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, Verifier};
+use rand::{TryRngCore, rngs::OsRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+fn default_format_version() -> u32 {
+    defaults::bundle::FORMAT_VERSION
+}
+
+/// The definition of a source carried by a bundle
+///
+/// Unlike `Source`, it carries nothing about how the source fared, and no
+/// User-Agent, which is chosen by whoever installs it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundledSource {
+    /// The URL where proxy information can be obtained
+    pub url: String,
+
+    /// Regex extracting proxies from responses, overriding the bundle's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Query parameters added to the URL
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+
+    /// Values substituted for `{name}` placeholders in the URL
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub template_variables: BTreeMap<String, Vec<String>>,
+
+    /// Regex selecting the links to list files when the URL is an index page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_pattern: Option<String>,
+}
+
+impl From<&Source> for BundledSource {
+    fn from(source: &Source) -> Self {
+        Self {
+            url: source.url.clone(),
+            pattern: Some(source.regex_pattern.clone()),
+            parameters: source
+                .parameters
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            template_variables: source.template_variables.clone(),
+            link_pattern: source.link_pattern.clone(),
+        }
+    }
+}
+
+/// An Ed25519 signature over a bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleSignature {
+    /// Base64 of the public key the bundle was signed with
+    pub public_key: String,
+
+    /// Base64 of the signature
+    pub signature: String,
+}
+
+/// A curated set of sources to share with other gatherers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceBundle {
+    /// Version of the format the bundle was written in
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
+
+    /// Name of the bundle
+    pub name: String,
+
+    /// Who curated the sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// What the sources have in common
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// When the bundle was made
+    pub created_at: DateTime<Utc>,
+
+    /// Cron expression suggested for scraping the sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+
+    /// Regex suggested for extracting proxies from sources without their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// The sources
+    #[serde(default)]
+    pub sources: Vec<BundledSource>,
+
+    /// Signature over the rest of the bundle, if signed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<BundleSignature>,
+}
+
+impl SourceBundle {
+    /// Create an empty bundle
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the bundle
+    #[must_use]
+    pub fn new(name: String) -> Self {
+        Self {
+            format_version: defaults::bundle::FORMAT_VERSION,
+            name,
+            author: None,
+            description: None,
+            created_at: Utc::now(),
+            schedule: None,
+            pattern: None,
+            sources: Vec::new(),
+            signature: None,
+        }
+    }
+
+    /// Create a bundle of the definitions of sources
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the bundle
+    /// * `sources` - The sources to share, sorted by URL in the bundle
+    #[must_use]
+    pub fn from_sources(name: String, sources: &[Source]) -> Self {
+        Self::new(name).with_sources(sources)
+    }
+
+    /// Set the sources to the definitions of sources, sorted by URL
+    #[must_use]
+    pub fn with_sources(mut self, sources: &[Source]) -> Self {
+        self.sources = sources.iter().map(BundledSource::from).collect();
+        self.sources.sort_by(|a, b| a.url.cmp(&b.url));
+        self
+    }
+
+    /// Set who curated the sources
+    #[must_use]
+    pub fn with_author(mut self, author: String) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Set what the sources have in common
+    #[must_use]
+    pub fn with_description(mut self, description: String) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    /// Set the cron expression suggested for scraping the sources
+    #[must_use]
+    pub fn with_schedule(mut self, schedule: String) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Set the regex suggested for sources without their own
+    #[must_use]
+    pub fn with_pattern(mut self, pattern: String) -> Self {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    /// Turn the bundled definitions into sources ready to install
+    ///
+    /// Sources without a pattern use the bundle's, or the default IP:PORT
+    /// pattern if the bundle suggests none. Each gets a random User-Agent.
+    ///
+    /// # Returns
+    ///
+    /// The sources, in the bundle's order
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a source has an invalid URL or pattern
+    pub fn to_sources(&self) -> BundleResult<Vec<Source>> {
+        self.sources
+            .iter()
+            .map(|bundled| {
+                let pattern = bundled
+                    .pattern
+                    .as_deref()
+                    .or(self.pattern.as_deref())
+                    .unwrap_or(defaults::regex_patterns::IP_PORT);
+                let mut source = Source::new(
+                    bundled.url.clone(),
+                    utils::get_random_user_agent().to_string(),
+                    pattern.to_string(),
+                )?;
+                for (key, value) in &bundled.parameters {
+                    source.add_parameter(key.clone(), value.clone());
+                }
+                source
+                    .template_variables
+                    .clone_from(&bundled.template_variables);
+                if let Some(link_pattern) = &bundled.link_pattern {
+                    source.set_link_pattern(link_pattern.clone())?;
+                }
+                Ok(source)
+            })
+            .collect()
+    }
+
+    /// Sign the bundle, replacing any earlier signature
+    ///
+    /// Any change made to the bundle afterwards invalidates the signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The author's signing key
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle can't be serialized for signing
+    pub fn sign(&mut self, key: &SigningKey) -> BundleResult<()> {
+        let signature = key.sign(&self.signed_content()?);
+        self.signature = Some(BundleSignature {
+            public_key: encode_key(&key.verifying_key()),
+            signature: BASE64.encode(signature.to_bytes()),
+        });
+        Ok(())
+    }
+
+    /// Check that the bundle is signed and wasn't changed since
+    ///
+    /// Any key is accepted; use `verify_trusted` to also check who signed.
+    ///
+    /// # Returns
+    ///
+    /// The key the bundle was signed with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle isn't signed or the signature doesn't
+    /// match its content
+    pub fn verify(&self) -> BundleResult<VerifyingKey> {
+        let signed = self.signature.as_ref().ok_or(BundleError::Unsigned)?;
+        let key = parse_verifying_key(&signed.public_key)?;
+        let bytes = BASE64
+            .decode(signed.signature.trim())
+            .map_err(|e| BundleError::InvalidSignature(e.to_string()))?;
+        let signature = Signature::from_slice(&bytes)
+            .map_err(|e| BundleError::InvalidSignature(e.to_string()))?;
+
+        key.verify(&self.signed_content()?, &signature)
+            .map_err(|e| BundleError::InvalidSignature(e.to_string()))?;
+        Ok(key)
+    }
+
+    /// Check that the bundle was signed by a trusted key and wasn't changed
+    /// since
+    ///
+    /// # Arguments
+    ///
+    /// * `trusted` - The keys of the authors trusted
+    ///
+    /// # Returns
+    ///
+    /// The key the bundle was signed with
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle isn't signed, the signature doesn't
+    /// match its content, or the key isn't trusted
+    pub fn verify_trusted(&self, trusted: &[VerifyingKey]) -> BundleResult<VerifyingKey> {
+        let key = self.verify()?;
+        if trusted.contains(&key) {
+            Ok(key)
+        } else {
+            Err(BundleError::UntrustedSigner(encode_key(&key)))
+        }
+    }
+
+    /// Parse a bundle from TOML
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML isn't a bundle, or the bundle was
+    /// written in a newer format
+    pub fn from_toml(content: &str) -> BundleResult<Self> {
+        toml::from_str::<Self>(content)
+            .map_err(|e| BundleError::ParseError(format!("Failed to parse TOML: {e}")))?
+            .checked()
+    }
+
+    /// Parse a bundle from JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON isn't a bundle, or the bundle was
+    /// written in a newer format
+    pub fn from_json(content: &str) -> BundleResult<Self> {
+        serde_json::from_str::<Self>(content)
+            .map_err(|e| BundleError::ParseError(format!("Failed to parse JSON: {e}")))?
+            .checked()
+    }
+
+    /// Serialize the bundle to TOML
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle can't be serialized
+    pub fn to_toml(&self) -> BundleResult<String> {
+        toml::to_string_pretty(self).map_err(|e| {
+            BundleError::SerializationError(format!("Failed to serialize to TOML: {e}"))
+        })
+    }
+
+    /// Serialize the bundle to JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle can't be serialized
+    pub fn to_json(&self) -> BundleResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            BundleError::SerializationError(format!("Failed to serialize to JSON: {e}"))
+        })
+    }
+
+    /// Read a bundle from a file
+    ///
+    /// Files ending in `.json` are read as JSON, all others as TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or isn't a bundle
+    pub fn load(path: &Path) -> BundleResult<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| BundleError::IoError(format!("Failed to read {}: {e}", path.display())))?;
+
+        if is_json(path) {
+            Self::from_json(&content)
+        } else {
+            Self::from_toml(&content)
+        }
+    }
+
+    /// Write the bundle to a file, replacing it if it exists
+    ///
+    /// Files ending in `.json` are written as JSON, all others as TOML.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle can't be serialized or the file can't
+    /// be written
+    pub fn save(&self, path: &Path) -> BundleResult<()> {
+        let content = if is_json(path) {
+            self.to_json()?
+        } else {
+            self.to_toml()?
+        };
+
+        fs::write(path, content)
+            .map_err(|e| BundleError::IoError(format!("Failed to write {}: {e}", path.display())))
+    }
+
+    /// Refuses bundles written in a newer format
+    fn checked(self) -> BundleResult<Self> {
+        if self.format_version > defaults::bundle::FORMAT_VERSION {
+            return Err(BundleError::UnsupportedVersion(self.format_version));
+        }
+        Ok(self)
+    }
+
+    /// The bytes a signature covers: the bundle's JSON without the signature
+    fn signed_content(&self) -> BundleResult<Vec<u8>> {
+        let unsigned = Self {
+            signature: None,
+            ..self.clone()
+        };
+        serde_json::to_vec(&unsigned).map_err(|e| {
+            BundleError::SerializationError(format!("Failed to serialize to JSON: {e}"))
+        })
+    }
+}
+
+/// Checks whether a bundle file is JSON by its extension
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+/// Generate a new key to sign bundles with
+///
+/// The key is drawn from the operating system's random number generator.
+///
+/// # Errors
+///
+/// Returns an error if the operating system can't provide random bytes
+pub fn generate_signing_key() -> BundleResult<SigningKey> {
+    let mut secret = [0_u8; ed25519_dalek::SECRET_KEY_LENGTH];
+    OsRng
+        .try_fill_bytes(&mut secret)
+        .map_err(|e| BundleError::IoError(format!("Failed to generate key: {e}")))?;
+    Ok(SigningKey::from_bytes(&secret))
+}
+
+/// Encode a public key as base64, the form trusted keys are given in
+#[must_use]
+pub fn encode_key(key: &VerifyingKey) -> String {
+    BASE64.encode(key.as_bytes())
+}
+
+/// Encode a signing key as base64, the form it is saved in
+#[must_use]
+pub fn encode_signing_key(key: &SigningKey) -> String {
+    BASE64.encode(key.as_bytes())
+}
+
+/// Parse a base64 public key
+///
+/// # Errors
+///
+/// Returns an error if the text isn't base64 of an Ed25519 public key
+pub fn parse_verifying_key(encoded: &str) -> BundleResult<VerifyingKey> {
+    let bytes: [u8; 32] = BASE64
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| BundleError::ParseError(format!("Invalid public key: {encoded}")))?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| BundleError::ParseError(format!("Invalid public key: {e}")))
+}
+
+/// Parse a base64 signing key, as written by `encode_signing_key`
+///
+/// # Errors
+///
+/// Returns an error if the text isn't base64 of an Ed25519 signing key
+pub fn parse_signing_key(encoded: &str) -> BundleResult<SigningKey> {
+    let bytes: [u8; 32] = BASE64
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| BundleError::ParseError("Invalid signing key".to_string()))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
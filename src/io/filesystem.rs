@@ -6,6 +6,12 @@
 //! ## Components
 //!
 //! * **Filestore** - A struct for managing file-based storage
+//! * **`ProxyJournal`** - An append-only journal for incremental proxy persistence
+//! * **`ProxyStream`** - Proxies read from a persisted pool one at a time
+//! * **`JobCheckpoint`** - Progress of a long-running job, so it can resume after an interruption
+//! * **`ValidationReport`** - Problems found in the stored files, and the files repaired
+//! * **`MergeSummary`** - What merging two persisted pools combined
+//! * **`ImportSummary`** - What importing a proxy file added to a persisted pool
 //! * **`AppConfig`** - A struct for application-wide configuration settings
 //!
 //! ## Examples
@@ -17,17 +23,42 @@
 //! let proxies = filestore.load_proxies("my_proxies").unwrap();
 //! ```
 
+#[cfg(feature = "export")]
+use crate::definitions::enums::{DuplicatePolicy, ExportColumn, ExportFormat};
 use crate::definitions::{
     defaults,
-    errors::{FilestoreError, FilestoreResult},
-    proxy::Proxy,
+    enums::{ConcurrencyIsolation, DataIssueKind, IdentityProfile, MergePolicy},
+    errors::{FilestoreError, FilestoreResult, SourceError},
+    proxy::{Proxy, ProxyId},
     source::Source,
 };
+use crate::inspection::{
+    capture::CapturedResponse,
+    cloudranges::CloudRangeSnapshot,
+    judgement::{AnonymityRule, JudgementRetryConfig},
+    lookup_cache::CachedLookup,
+    usage::{ProviderQuota, ProviderUsage},
+};
+use crate::io::http::{Egress, RateLimitConfig, RequestProfile};
+#[cfg(feature = "export")]
+use crate::io::{export, import};
+use crate::orchestration::{
+    listener::ClientStatsMap, rotation::RotationState, scheduler::ScheduleConfig,
+};
 use crate::utils::SerializableRegex;
-use chrono::Utc;
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "cli")]
+use directories::ProjectDirs;
+use serde::{
+    Deserialize, Deserializer, Serialize,
+    de::{DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, Visitor},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Configuration settings for the filestore
 ///
@@ -44,12 +75,13 @@ use std::path::PathBuf;
 ///     create_defaults_if_missing: true,
 ///     auto_save_interval_secs: 600, // 10 minutes
 ///     pretty_print: true,
+///     lenient_loading: true,
 /// };
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilestoreConfig {
     /// Directory where proxy data is stored
-    #[serde(default = "default_data_dir")]
+    #[serde(default = "default_data_dir_string")]
     pub data_dir: String,
 
     /// Whether to create default files when they don't exist
@@ -63,11 +95,93 @@ pub struct FilestoreConfig {
     /// Whether to pretty-print TOML output
     #[serde(default = "default_true")]
     pub pretty_print: bool,
+
+    /// Whether loading skips entries that can't be read instead of failing
+    ///
+    /// Off by default: skipped entries aren't kept, so saving after a lenient
+    /// load drops them from the file for good.
+    #[serde(default)]
+    pub lenient_loading: bool,
+}
+
+impl Default for FilestoreConfig {
+    fn default() -> Self {
+        FilestoreConfig {
+            data_dir: default_data_dir_string(),
+            create_defaults_if_missing: default_true(),
+            auto_save_interval_secs: default_auto_save_interval(),
+            pretty_print: default_true(),
+            lenient_loading: false,
+        }
+    }
+}
+
+/// Get the default data directory
+///
+/// The directory is taken from the `GOOTY_DATA_DIR` environment variable if
+/// set, and otherwise from the platform conventions: `$XDG_DATA_HOME/gooty-proxy`
+/// on Linux, `%APPDATA%\Azzybana\gooty-proxy\data` on Windows and
+/// `~/Library/Application Support/com.Azzybana.gooty-proxy` on macOS. If no home
+/// directory can be found, or without the `cli` feature, a relative `data`
+/// folder is used.
+///
+/// # Returns
+///
+/// The path of the default data directory
+#[must_use]
+pub fn default_data_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(defaults::persistence::DATA_DIR_ENV) {
+        if !dir.is_empty() {
+            return PathBuf::from(dir);
+        }
+    }
+
+    platform_data_dir().unwrap_or_else(|| PathBuf::from(defaults::persistence::FALLBACK_DATA_DIR))
+}
+
+/// Get the data directory the platform conventions call for, if a home directory is found
+#[cfg(feature = "cli")]
+fn platform_data_dir() -> Option<PathBuf> {
+    ProjectDirs::from(
+        defaults::persistence::APP_QUALIFIER,
+        defaults::persistence::APP_ORGANIZATION,
+        defaults::persistence::APP_NAME,
+    )
+    .map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// Get the data directory the platform conventions call for, never known without the `cli` feature
+#[cfg(not(feature = "cli"))]
+fn platform_data_dir() -> Option<PathBuf> {
+    None
+}
+
+/// Resolve the data directory to use
+///
+/// # Arguments
+///
+/// * `explicit` - A directory given explicitly, e.g. on the command line
+///
+/// # Returns
+///
+/// The explicit directory if given, otherwise `default_data_dir()`
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::filesystem::resolve_data_dir;
+/// use std::path::PathBuf;
+///
+/// assert_eq!(resolve_data_dir(Some("my_data")), PathBuf::from("my_data"));
+/// ```
+#[must_use]
+pub fn resolve_data_dir(explicit: Option<&str>) -> PathBuf {
+    explicit.map_or_else(default_data_dir, PathBuf::from)
 }
 
 // Helper functions for default values
-fn default_data_dir() -> String {
-    "data".to_string()
+fn default_data_dir_string() -> String {
+    default_data_dir().to_string_lossy().to_string()
 }
 
 fn default_true() -> bool {
@@ -78,6 +192,26 @@ fn default_auto_save_interval() -> u64 {
     defaults::persistence::AUTO_SAVE_INTERVAL_SECS
 }
 
+fn default_system_proxy() -> bool {
+    true
+}
+
+fn default_judgement_worker_threads() -> usize {
+    defaults::isolation::WORKER_THREADS
+}
+
+fn default_schedules() -> Vec<ScheduleConfig> {
+    ScheduleConfig::defaults()
+}
+
+fn default_provider_quotas() -> BTreeMap<String, ProviderQuota> {
+    ProviderQuota::defaults()
+}
+
+fn default_lookup_cache_ttl_secs() -> u64 {
+    defaults::lookup_cache::TTL_SECS
+}
+
 /// Configuration for the entire application
 ///
 /// Contains all configuration settings for the different components
@@ -119,6 +253,116 @@ pub struct AppConfig {
 
     /// Logging level (error, warn, info, debug, trace)
     pub log_level: String,
+
+    /// How bulk judgement shares the async runtime with the host application
+    #[serde(default)]
+    pub judgement_isolation: ConcurrencyIsolation,
+
+    /// Number of worker threads of the dedicated judgement runtime
+    #[serde(default = "default_judgement_worker_threads")]
+    pub judgement_worker_threads: usize,
+
+    /// Judgement retry policies of each judgement mode
+    #[serde(default)]
+    pub judgement_retry: JudgementRetryConfig,
+
+    /// Lookup quotas of the IP information providers, by provider name
+    #[serde(default = "default_provider_quotas")]
+    pub provider_quotas: BTreeMap<String, ProviderQuota>,
+
+    /// API tokens of the IP information providers, by provider name
+    ///
+    /// Overridden by the provider's environment variable, such as `GOOTY_IPINFO_TOKEN`.
+    #[serde(default)]
+    pub provider_tokens: BTreeMap<String, String>,
+
+    /// How long looked up documents about an address are reused, in seconds
+    ///
+    /// Zero disables the cache, so every lookup makes a request.
+    #[serde(default = "default_lookup_cache_ttl_secs")]
+    pub lookup_cache_ttl_secs: u64,
+
+    /// How lookups and source fetches are paced per host, and retried when a
+    /// host answers 429 Too Many Requests
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    /// Paths of local `MaxMind` `GeoLite2` or `GeoIP2` databases, such as the
+    /// City and ASN databases, that lookups are answered from before
+    /// ipinfo.io (`geoip` feature)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub geoip_databases: Vec<String>,
+
+    /// Schedules of recurring maintenance jobs
+    #[serde(default = "default_schedules")]
+    pub schedules: Vec<ScheduleConfig>,
+
+    /// Base64 public keys of the source bundle authors trusted
+    ///
+    /// When any are set, only bundles signed by one of them are installed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_bundle_keys: Vec<String>,
+
+    /// Named header profiles requests can be sent with
+    #[serde(default)]
+    pub request_profiles: Vec<RequestProfile>,
+
+    /// Name of the request profile the judge sends, if not the built-in one
+    ///
+    /// Set this to the profile used for scraping, so proxies are judged the
+    /// way they will later be used.
+    #[serde(default)]
+    pub judge_profile: Option<String>,
+
+    /// How requests gooty makes on its own behalf identify themselves
+    ///
+    /// Applies to lookups, and to judgement unless `judge_profile` is set.
+    #[serde(default)]
+    pub outbound_identity: IdentityProfile,
+
+    /// Number of raw judge and ipinfo.io responses kept for debugging
+    ///
+    /// Zero disables capturing.
+    #[serde(default)]
+    pub response_capture: usize,
+
+    /// Rules demoting proxies that send matching headers to a judge
+    ///
+    /// Applied on top of the built-in classification, which they can only
+    /// lower.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub anonymity_rules: Vec<AnonymityRule>,
+
+    /// URL that pool health anomalies are posted to as JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_webhook: Option<String>,
+
+    /// HTTPS target of the SNI passthrough check
+    ///
+    /// Must only answer connections with the right SNI and Host.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sni_target: Option<String>,
+
+    /// Vantage point judged latencies are recorded under, such as the
+    /// region this gatherer runs in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vantage: Option<String>,
+
+    /// Outbound proxy gooty's own requests go through, such as
+    /// `http://proxy.corp:3128`
+    ///
+    /// Used for source fetches, lookups, and judge baselines, never for
+    /// requests through the proxies being tested. Takes precedence over the
+    /// system proxy environment variables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub egress_proxy: Option<String>,
+
+    /// Whether gooty's own requests honor the `HTTP_PROXY`, `HTTPS_PROXY`,
+    /// `ALL_PROXY`, and `NO_PROXY` environment variables
+    ///
+    /// Turn off for tests that must not depend on the environment.
+    #[serde(default = "default_system_proxy")]
+    pub system_proxy: bool,
 }
 
 impl Default for AppConfig {
@@ -132,17 +376,81 @@ impl Default for AppConfig {
             max_acceptable_latency_ms: defaults::DEFAULT_MAX_ACCEPTABLE_LATENCY_MS,
             min_success_rate: defaults::rotation::MIN_SUCCESS_RATE,
             log_level: "info".to_string(),
+            judgement_isolation: ConcurrencyIsolation::default(),
+            judgement_worker_threads: default_judgement_worker_threads(),
+            judgement_retry: JudgementRetryConfig::default(),
+            provider_quotas: default_provider_quotas(),
+            provider_tokens: BTreeMap::new(),
+            lookup_cache_ttl_secs: default_lookup_cache_ttl_secs(),
+            rate_limit: RateLimitConfig::default(),
+            geoip_databases: Vec::new(),
+            schedules: default_schedules(),
+            trusted_bundle_keys: Vec::new(),
+            request_profiles: Vec::new(),
+            judge_profile: None,
+            outbound_identity: IdentityProfile::default(),
+            response_capture: 0,
+            anonymity_rules: Vec::new(),
+            health_webhook: None,
+            sni_target: None,
+            vantage: None,
+            egress_proxy: None,
+            system_proxy: default_system_proxy(),
         }
     }
 }
 
-/// Container for storing proxies in TOML format
+impl AppConfig {
+    /// Find a configured request profile by name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the profile
+    ///
+    /// # Returns
+    ///
+    /// The profile, or None if no profile has that name
+    #[must_use]
+    pub fn request_profile(&self, name: &str) -> Option<&RequestProfile> {
+        self.request_profiles.iter().find(|p| p.name == name)
+    }
+
+    /// Route gooty's own requests take to the network
+    ///
+    /// # Returns
+    ///
+    /// The configured egress proxy, or else the system proxies unless
+    /// `system_proxy` is off
+    #[must_use]
+    pub fn egress(&self) -> Egress {
+        Egress::from_config(self.egress_proxy.as_deref(), self.system_proxy)
+    }
+}
+
+/// Extension of the journal kept next to a proxies file
+const JOURNAL_EXTENSION: &str = "journal.jsonl";
+
+/// Extension of job checkpoint files
+const CHECKPOINT_EXTENSION: &str = "checkpoint.toml";
+
+/// Extension of rotation state files
+const ROTATION_STATE_EXTENSION: &str = "rotation.toml";
+
+/// Extension of listener client statistics files
+const CLIENT_STATS_EXTENSION: &str = "clients.toml";
+
+/// Extension of manager state files
+const MANAGER_STATE_EXTENSION: &str = "state.toml";
+
+/// A run of proxies, written and read one `[[proxies]]` block at a time
 #[derive(Debug, Serialize, Deserialize)]
-struct ProxiesContainer {
-    last_updated: String,
-    proxies: Vec<Proxy>,
+struct ProxiesChunk<T> {
+    proxies: Vec<T>,
 }
 
+/// Header of every proxy of a pool file written in chunks
+const PROXIES_HEADER: &str = "[[proxies]]";
+
 /// Container for storing sources in TOML format
 #[derive(Debug, Serialize, Deserialize)]
 struct SourcesContainer {
@@ -150,144 +458,2400 @@ struct SourcesContainer {
     sources: Vec<Source>,
 }
 
-/// File-based storage manager for proxies, sources, and configuration
+/// Container for storing lookup provider usage in TOML format
+#[derive(Debug, Serialize, Deserialize)]
+struct ProviderUsageContainer {
+    last_updated: String,
+    providers: Vec<ProviderUsage>,
+}
+
+/// Container for storing downloaded cloud ranges in TOML format
+#[derive(Debug, Serialize, Deserialize)]
+struct CloudRangesContainer {
+    last_updated: String,
+    providers: Vec<CloudRangeSnapshot>,
+}
+
+/// Container for storing cached lookups in TOML format
+#[derive(Debug, Serialize, Deserialize)]
+struct LookupCacheContainer {
+    #[serde(default)]
+    lookups: Vec<CachedLookup>,
+}
+
+/// Container for storing captured responses in TOML format
+#[derive(Debug, Serialize, Deserialize)]
+struct CapturedResponsesContainer {
+    #[serde(default)]
+    responses: Vec<CapturedResponse>,
+}
+
+/// The proxies and sources of a manager, saved together in one file
 ///
-/// The Filestore provides methods for loading and saving data to the
-/// filesystem, managing proxy lists, source lists, and configuration files.
+/// Written by `ProxyManager::save_to_filestore` and read back by
+/// `ProxyManager::load_from_filestore`. Keeping both lists in one file means
+/// a save interrupted halfway can't leave proxies and sources out of step.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManagerState {
+    /// When the manager's proxies or sources last changed, if ever
+    #[serde(default)]
+    pub last_update_time: Option<DateTime<Utc>>,
+
+    /// The managed proxies
+    #[serde(default)]
+    pub proxies: Vec<Proxy>,
+
+    /// The managed sources
+    #[serde(default)]
+    pub sources: Vec<Source>,
+}
+
+/// Progress of a long-running job over the proxy pool
 ///
-/// It handles file operations, serialization/deserialization, and ensures
-/// data consistency when reading and writing files.
+/// Jobs such as a full recheck can take hours. Saving a checkpoint after
+/// every batch keeps the proxies already judged, with their results, so an
+/// interrupted run resumes where it left off instead of starting over.
 ///
 /// # Examples
 ///
 /// ```
-/// use gooty_proxy::io::filestore::{Filestore, FilestoreConfig};
-///
-/// // Create a filestore with default configuration
-/// let filestore = Filestore::new().unwrap();
+/// use gooty_proxy::definitions::{
+///     enums::{AnonymityLevel, ProxyType},
+///     proxy::Proxy,
+///     types::Port,
+/// };
+/// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig, JobCheckpoint};
+/// use std::net::{IpAddr, Ipv4Addr};
 ///
-/// // Create a filestore with custom configuration
-/// let config = FilestoreConfig {
-///     data_dir: "custom_data".to_string(),
+/// let dir = std::env::temp_dir().join("gooty_checkpoint_example");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// let filestore = Filestore::with_config(FilestoreConfig {
+///     data_dir: dir.to_string_lossy().to_string(),
 ///     ..Default::default()
+/// })
+/// .unwrap();
+/// assert!(filestore.load_checkpoint("recheck").unwrap().is_none());
+///
+/// let proxy = Proxy::new(
+///     ProxyType::Http,
+///     IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+///     Port::new(8080).unwrap(),
+///     AnonymityLevel::Elite,
+/// );
+/// let mut checkpoint = JobCheckpoint::new("recheck");
+/// checkpoint.record(&[proxy.clone()]);
+/// filestore.save_checkpoint(&checkpoint, "recheck").unwrap();
+///
+/// let resumed = filestore.load_checkpoint("recheck").unwrap().unwrap();
+/// assert_eq!(resumed.len(), 1);
+/// assert!(resumed.contains(&proxy.id()));
+///
+/// assert!(filestore.clear_checkpoint("recheck").unwrap());
+/// assert!(filestore.load_checkpoint("recheck").unwrap().is_none());
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobCheckpoint {
+    /// Name of the job the checkpoint belongs to
+    pub job: String,
+
+    /// When the job was first started
+    pub started_at: DateTime<Utc>,
+
+    /// When the checkpoint was last updated
+    pub updated_at: DateTime<Utc>,
+
+    /// Proxies the job has finished with, carrying their results
+    #[serde(default)]
+    pub completed: Vec<Proxy>,
+}
+
+impl JobCheckpoint {
+    /// Create an empty checkpoint for a job starting now
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - Name of the job
+    #[must_use]
+    pub fn new(job: &str) -> Self {
+        let now = Utc::now();
+        JobCheckpoint {
+            job: job.to_string(),
+            started_at: now,
+            updated_at: now,
+            completed: Vec::new(),
+        }
+    }
+
+    /// Record proxies the job has finished with
+    ///
+    /// Proxies already recorded under the same `ProxyId` are replaced by
+    /// their newer copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxies` - The finished proxies, with their results
+    pub fn record(&mut self, proxies: &[Proxy]) {
+        for proxy in proxies {
+            let id = proxy.id();
+            match self.completed.iter_mut().find(|p| p.id() == id) {
+                Some(existing) => *existing = proxy.clone(),
+                None => self.completed.push(proxy.clone()),
+            }
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Check whether the job has finished with a proxy
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Identifier of the proxy
+    #[must_use]
+    pub fn contains(&self, id: &ProxyId) -> bool {
+        self.completed.iter().any(|p| p.id() == *id)
+    }
+
+    /// Number of proxies the job has finished with
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// Whether the job has not finished with any proxy yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.completed.is_empty()
+    }
+}
+
+/// Append-only journal for incremental proxy persistence
+///
+/// Rewriting the whole TOML snapshot gets slow with large pools. The journal
+/// instead appends each updated proxy as one JSON line next to the snapshot.
+/// Writes are rate limited: proxies recorded within the flush interval are
+/// buffered and appended together. Once the journal grows past the compaction
+/// threshold, `compact` folds it into the snapshot and truncates it.
+///
+/// `Filestore::load_proxies_with_journal` replays the journal over the
+/// snapshot, and `Filestore::save_proxies` truncates it, since a fresh
+/// snapshot supersedes every journaled entry.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::{
+///     enums::{AnonymityLevel, ProxyType},
+///     proxy::Proxy,
+///     types::Port,
 /// };
-/// let filestore = Filestore::with_config(config).unwrap();
+/// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use std::time::Duration;
 ///
-/// // Load proxies from a file
-/// let proxies = filestore.load_proxies("my_proxies").unwrap();
+/// let dir = std::env::temp_dir().join("gooty_journal_example");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// let filestore = Filestore::with_config(FilestoreConfig {
+///     data_dir: dir.to_string_lossy().to_string(),
+///     create_defaults_if_missing: true,
+///     ..Default::default()
+/// })
+/// .unwrap();
+///
+/// let mut journal = filestore
+///     .open_journal("proxies")
+///     .unwrap()
+///     .with_flush_interval(Duration::ZERO);
+///
+/// let proxy = Proxy::new(
+///     ProxyType::Http,
+///     IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+///     Port::new(8080).unwrap(),
+///     AnonymityLevel::Elite,
+/// );
+/// journal.record(&proxy).unwrap();
+/// assert_eq!(journal.len(), 1);
+///
+/// let proxies = filestore.load_proxies_with_journal("proxies").unwrap();
+/// assert_eq!(proxies.len(), 1);
+///
+/// journal.compact(&filestore).unwrap();
+/// assert!(journal.is_empty());
+/// assert_eq!(filestore.load_proxies("proxies").unwrap().len(), 1);
+/// # std::fs::remove_dir_all(&dir).unwrap();
 /// ```
-pub struct Filestore {
-    /// Configuration for this filestore instance
-    config: FilestoreConfig,
+#[derive(Debug)]
+pub struct ProxyJournal {
+    /// Base name of the snapshot the journal belongs to
+    name: String,
 
-    /// Base directory for all data files
-    base_dir: PathBuf,
+    /// Path of the journal file
+    path: PathBuf,
+
+    /// Proxies recorded since the last flush
+    pending: Vec<Proxy>,
+
+    /// When the journal was last flushed
+    last_flush: Option<Instant>,
+
+    /// Minimum time between flushes
+    flush_interval: Duration,
+
+    /// Number of entries written to the journal file
+    entries: usize,
+
+    /// Number of entries after which the journal should be compacted
+    compact_threshold: usize,
 }
 
-impl Filestore {
-    /// Create a new filestore with default configuration
+impl ProxyJournal {
+    /// Set the minimum time between flushes
+    ///
+    /// # Arguments
     ///
-    /// Creates a new filestore that stores data in the "data" directory.
+    /// * `interval` - Minimum time between flushes
     ///
     /// # Returns
     ///
-    /// A new Filestore instance
+    /// Self with the flush interval set
+    #[must_use]
+    pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Set the number of entries after which the journal should be compacted
     ///
-    /// # Errors
+    /// # Arguments
     ///
-    /// Returns an error if the data directory cannot be created or accessed
-    pub fn new() -> FilestoreResult<Self> {
-        Self::with_config(FilestoreConfig::default())
+    /// * `threshold` - Number of journal entries
+    ///
+    /// # Returns
+    ///
+    /// Self with the compaction threshold set
+    #[must_use]
+    pub fn with_compact_threshold(mut self, threshold: usize) -> Self {
+        self.compact_threshold = threshold;
+        self
     }
 
-    /// Create a new filestore with custom configuration
+    /// Record an updated proxy
+    ///
+    /// The proxy is buffered, and the buffer is flushed if the flush interval
+    /// has elapsed since the last flush.
     ///
     /// # Arguments
     ///
-    /// * `config` - Configuration settings for the filestore
+    /// * `proxy` - The proxy to persist
     ///
     /// # Returns
     ///
-    /// A new Filestore instance with the specified configuration
+    /// The number of entries written, or 0 if the proxy was only buffered
     ///
     /// # Errors
     ///
-    /// Returns an error if the data directory cannot be created or accessed
-    pub fn with_config(config: FilestoreConfig) -> FilestoreResult<Self> {
-        let base_dir = PathBuf::from(&config.data_dir);
+    /// Returns an error if the buffer is due but cannot be written
+    pub fn record(&mut self, proxy: &Proxy) -> FilestoreResult<usize> {
+        self.pending.push(proxy.clone());
 
-        // Create the directory if it doesn't exist
-        if !base_dir.exists() {
-            fs::create_dir_all(&base_dir).map_err(|e| {
-                FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+        let due = self
+            .last_flush
+            .is_none_or(|at| at.elapsed() >= self.flush_interval);
+        if due { self.flush() } else { Ok(0) }
+    }
+
+    /// Append all buffered proxies to the journal file
+    ///
+    /// # Returns
+    ///
+    /// The number of entries written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proxies cannot be serialized or written. The
+    /// buffer is kept so the write can be retried.
+    pub fn flush(&mut self) -> FilestoreResult<usize> {
+        self.last_flush = Some(Instant::now());
+        if self.pending.is_empty() {
+            return Ok(0);
+        }
+
+        let mut content = String::new();
+        for proxy in &self.pending {
+            let line = serde_json::to_string(proxy).map_err(|e| {
+                FilestoreError::SerializationError(format!("Failed to serialize to JSON: {e:?}"))
             })?;
+            content.push_str(&line);
+            content.push('\n');
+        }
+
+        let append = || -> std::io::Result<()> {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            file.write_all(content.as_bytes())?;
+            file.sync_data()
+        };
+        append().map_err(|e| FilestoreError::IoError(format!("Failed to write journal: {e:?}")))?;
+
+        let written = self.pending.len();
+        self.entries += written;
+        self.pending.clear();
+        Ok(written)
+    }
+
+    /// Fold the journal into the snapshot and truncate it
+    ///
+    /// Buffered proxies are flushed first.
+    ///
+    /// # Arguments
+    ///
+    /// * `filestore` - The filestore the journal was opened from
+    ///
+    /// # Returns
+    ///
+    /// The number of proxies in the compacted snapshot
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal or snapshot cannot be read or written
+    pub fn compact(&mut self, filestore: &Filestore) -> FilestoreResult<usize> {
+        self.flush()?;
+        let proxies = filestore.load_proxies_with_journal(&self.name)?;
+        filestore.save_proxies(&proxies, &self.name)?;
+        self.entries = 0;
+        Ok(proxies.len())
+    }
+
+    /// Check whether the journal has grown past the compaction threshold
+    #[must_use]
+    pub fn needs_compaction(&self) -> bool {
+        self.entries >= self.compact_threshold
+    }
+
+    /// Get the number of entries written to the journal file
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries
+    }
+
+    /// Check whether the journal file holds no entries
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries == 0
+    }
+
+    /// Get the number of proxies buffered since the last flush
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+impl Drop for ProxyJournal {
+    /// Write the buffered proxies, so none is lost when the journal goes away
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            log::warn!("Failed to flush {} journaled proxies: {e}", self.pending.len());
         }
+    }
+}
+
+/// Proxies read from a persisted pool one at a time
+///
+/// Returned by `Filestore::stream_proxies`. Pools written by
+/// `Filestore::save_proxies` are read a `[[proxies]]` block at a time, so
+/// only the proxy being read and the pool's journal are held in memory.
+/// Pools listing their proxies inline, as hand-edited files may, are read
+/// whole.
+///
+/// Journaled proxies replace the snapshot entries they match, and the ones
+/// only the journal holds come last, as with `load_proxies_with_journal`.
+/// Entries that can't be read are skipped with a warning when the filestore
+/// loads leniently, and returned as errors otherwise.
+#[derive(Debug)]
+pub struct ProxyStream {
+    /// Entries of the snapshot not read yet
+    snapshot: SnapshotReader,
+
+    /// Journaled proxies, taken once returned
+    journal: Vec<Option<Proxy>>,
+
+    /// Positions in `journal` by proxy identifier
+    journaled: HashMap<ProxyId, usize>,
+
+    /// Position in `journal` of the next proxy returned after the snapshot
+    tail: usize,
+
+    /// Whether entries that can't be read are skipped
+    lenient: bool,
+}
+
+impl Iterator for ProxyStream {
+    type Item = FilestoreResult<Proxy>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(entry) = self.snapshot.next_entry() {
+            match entry {
+                Ok(proxy) => {
+                    // The journaled copy is the newer one
+                    let journaled = self
+                        .journaled
+                        .get(&proxy.id())
+                        .and_then(|&i| self.journal.get_mut(i))
+                        .and_then(Option::take);
+                    return Some(Ok(journaled.unwrap_or(proxy)));
+                }
+                Err(e) if self.lenient => log::warn!("Skipping unreadable proxy: {e}"),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        while let Some(slot) = self.journal.get_mut(self.tail) {
+            self.tail += 1;
+            if let Some(proxy) = slot.take() {
+                return Some(Ok(proxy));
+            }
+        }
+        None
+    }
+}
+
+/// Reads the snapshot of a pool for a `ProxyStream`
+#[derive(Debug)]
+enum SnapshotReader {
+    /// Reads the `[[proxies]]` blocks of a file one at a time
+    Blocks {
+        /// The file being read
+        path: PathBuf,
+        /// Lines of the file not read yet
+        lines: std::io::Lines<BufReader<fs::File>>,
+        /// The block being read, from its header on
+        block: String,
+        /// Line the block being read starts on
+        start: usize,
+        /// Number of lines read
+        line: usize,
+    },
+    /// Hands out the entries of a file read whole
+    Loaded(std::vec::IntoIter<Proxy>),
+    /// Every entry was read
+    Done,
+}
+
+impl SnapshotReader {
+    /// Opens a pool file, reading its top-level keys
+    fn open(path: &Path, lenient: bool) -> FilestoreResult<Self> {
+        let file = fs::File::open(path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+        let mut lines = BufReader::new(file).lines();
+
+        let mut line = 0;
+        while let Some(text) = lines.next() {
+            let text =
+                text.map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+            line += 1;
+            if text.trim() == PROXIES_HEADER {
+                return Ok(SnapshotReader::Blocks {
+                    path: path.to_path_buf(),
+                    lines,
+                    block: format!("{text}\n"),
+                    start: line,
+                    line,
+                });
+            }
+
+            // An inline list can't be split into entries without parsing it
+            let inline = text
+                .trim_start()
+                .strip_prefix("proxies")
+                .is_some_and(|rest| rest.trim_start().starts_with('='));
+            if inline {
+                let content = fs::read_to_string(path)
+                    .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+                let loaded: PartialLoad<Proxy> = read_list(&content, "proxies", path)?.into();
+                return Ok(SnapshotReader::Loaded(loaded.accept(lenient)?.into_iter()));
+            }
+        }
+        Ok(SnapshotReader::Done)
+    }
+
+    /// Reads the next entry, or `None` once every entry was read
+    fn next_entry(&mut self) -> Option<FilestoreResult<Proxy>> {
+        let (path, lines, block, start, line) = match self {
+            SnapshotReader::Blocks {
+                path,
+                lines,
+                block,
+                start,
+                line,
+            } => (path, lines, block, start, line),
+            SnapshotReader::Loaded(entries) => return entries.next().map(Ok),
+            SnapshotReader::Done => return None,
+        };
+
+        loop {
+            match lines.next() {
+                Some(Ok(text)) => {
+                    *line += 1;
+                    if text.trim() == PROXIES_HEADER {
+                        let finished = std::mem::replace(block, format!("{text}\n"));
+                        let finished_start = std::mem::replace(start, *line);
+                        return Some(parse_block(&finished, path, finished_start));
+                    }
+                    block.push_str(&text);
+                    block.push('\n');
+                }
+                Some(Err(e)) => {
+                    *self = SnapshotReader::Done;
+                    return Some(Err(FilestoreError::IoError(format!(
+                        "Failed to read file: {e:?}"
+                    ))));
+                }
+                None => {
+                    let entry = parse_block(block, path, *start);
+                    *self = SnapshotReader::Done;
+                    return Some(entry);
+                }
+            }
+        }
+    }
+}
+
+/// Reads the proxy of a `[[proxies]]` block
+fn parse_block(block: &str, path: &Path, start: usize) -> FilestoreResult<Proxy> {
+    let chunk: ProxiesChunk<Proxy> = toml::from_str(block).map_err(|e| {
+        FilestoreError::ParseError(format!(
+            "Failed to parse the proxy at {}:{start}: {}",
+            path.display(),
+            e.message()
+        ))
+    })?;
+    chunk.proxies.into_iter().next().ok_or_else(|| {
+        FilestoreError::ParseError(format!("No proxy at {}:{start}", path.display()))
+    })
+}
+
+/// A problem found in a persisted file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataIssue {
+    /// The file the problem was found in
+    pub file: PathBuf,
+
+    /// Position of the entry at fault in its file, or None for the whole file
+    pub entry: Option<usize>,
+
+    /// Line the entry at fault starts on, counting from one, if known
+    pub line: Option<usize>,
+
+    /// What is wrong
+    pub kind: DataIssueKind,
+
+    /// Details of the problem
+    pub detail: String,
+}
+
+impl fmt::Display for DataIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        if let Some(entry) = self.entry {
+            write!(f, " entry {entry}")?;
+        }
+        write!(f, ": {} ({})", self.kind, self.detail)
+    }
+}
+
+/// Outcome of checking the files of a filestore
+///
+/// Returned by `Filestore::validate_data`, which also repairs the files
+/// when asked to.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::{enums::DataIssueKind, source::Source};
+/// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+///
+/// let dir = std::env::temp_dir().join("gooty_validation_example");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// let filestore = Filestore::with_config(FilestoreConfig {
+///     data_dir: dir.to_string_lossy().to_string(),
+///     ..Default::default()
+/// })
+/// .unwrap();
+///
+/// // A zero port, a duplicate, a field no longer used, and a missing anonymity
+/// std::fs::write(
+///     dir.join("proxies.toml"),
+///     r#"last_updated = "2025-01-01T00:00:00Z"
+///
+/// [[proxies]]
+/// proxy_type = "Http"
+/// address = "10.0.0.1"
+/// port = 8080
+/// anonymity = "Elite"
+///
+/// [[proxies]]
+/// proxy_type = "Http"
+/// address = "10.0.0.2"
+/// port = 0
+/// anonymity = "Elite"
+///
+/// [[proxies]]
+/// proxy_type = "Http"
+/// address = "10.0.0.1"
+/// port = 8080
+/// anonymity = "Anonymous"
+///
+/// [[proxies]]
+/// proxy_type = "Socks5"
+/// address = "10.0.0.3"
+/// port = 1080
+/// anonymity = "Elite"
+/// verified = true
+///
+/// [[proxies]]
+/// proxy_type = "Http"
+/// address = "10.0.0.4"
+/// port = 3128
+/// "#,
+/// )
+/// .unwrap();
+///
+/// let good = Source::new(
+///     "https://example.com/proxies.txt".to_string(),
+///     "Mozilla/5.0".to_string(),
+///     r"\d+\.\d+\.\d+\.\d+:\d+".to_string(),
+/// )
+/// .unwrap();
+/// let mut broken = good.clone();
+/// broken.url = "https://example.org/proxies.txt".to_string();
+/// broken.regex_pattern = "(unclosed".to_string();
+/// filestore.save_sources(&[good, broken], "sources").unwrap();
+///
+/// // Unreadable entries fail the whole file unless loading leniently
+/// assert!(filestore.load_proxies("proxies").is_err());
+/// assert_eq!(filestore.load_proxies_partial("proxies").unwrap().entries.len(), 3);
+///
+/// let report = filestore.validate_data(false).unwrap();
+/// let kinds: Vec<_> = report.issues.iter().map(|issue| issue.kind).collect();
+/// assert_eq!(
+///     kinds,
+///     [
+///         DataIssueKind::InvalidPort,
+///         DataIssueKind::StaleSchema,
+///         DataIssueKind::Malformed,
+///         DataIssueKind::DuplicateKey,
+///         DataIssueKind::InvalidRegex,
+///     ]
+/// );
+/// assert!(report.repaired.is_empty());
+///
+/// let report = filestore.validate_data(true).unwrap();
+/// assert_eq!(report.repaired.len(), 2);
+/// assert!(filestore.validate_data(false).unwrap().is_clean());
+/// assert_eq!(filestore.load_proxies("proxies").unwrap().len(), 2);
+/// assert_eq!(filestore.load_sources("sources").unwrap().len(), 1);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Files that were checked
+    pub checked: Vec<PathBuf>,
+
+    /// Problems found, file by file
+    pub issues: Vec<DataIssue>,
+
+    /// Files rewritten without their problems
+    pub repaired: Vec<PathBuf>,
+}
+
+impl ValidationReport {
+    /// Checks whether no problem was found
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// What merging two persisted proxy pools combined
+///
+/// Returned by `Filestore::merge_proxy_files`.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, MergePolicy, ProxyType};
+/// use gooty_proxy::definitions::proxy::Proxy;
+/// use gooty_proxy::definitions::types::Port;
+/// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let dir = std::env::temp_dir().join("gooty_merge_example");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// let config = FilestoreConfig {
+///     data_dir: dir.to_string_lossy().to_string(),
+///     ..Default::default()
+/// };
+/// let filestore = Filestore::with_config(config).unwrap();
+///
+/// let proxy = |last: u8| {
+///     Proxy::new(
+///         ProxyType::Http,
+///         IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)),
+///         Port::new(8080).unwrap(),
+///         AnonymityLevel::Elite,
+///     )
+/// };
+/// let mut shared = proxy(1);
+/// shared.record_check_failure();
+/// filestore.save_proxies(&[shared.clone(), proxy(2)], "europe").unwrap();
+/// filestore.save_proxies(&[shared, proxy(3)], "asia").unwrap();
+///
+/// let summary = filestore
+///     .merge_proxy_files("europe", "asia", "merged", MergePolicy::Reconcile)
+///     .unwrap();
+/// assert_eq!((summary.first, summary.second), (2, 2));
+/// assert_eq!((summary.merged, summary.reconciled), (3, 1));
+///
+/// let merged = filestore.load_proxies("merged").unwrap();
+/// assert_eq!(merged.len(), 3);
+/// assert_eq!(merged[0].check_count, 2);
+///
+/// // Missing pools aren't mistaken for empty ones
+/// assert!(filestore.merge_proxy_files("europe", "africa", "merged", MergePolicy::Reconcile).is_err());
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeSummary {
+    /// Proxies in the first pool
+    pub first: usize,
+
+    /// Proxies in the second pool
+    pub second: usize,
+
+    /// Proxies in the merged pool
+    pub merged: usize,
+
+    /// Proxies that were in both pools
+    pub reconciled: usize,
+}
+
+/// What importing a proxy file added to a persisted pool
+///
+/// Returned by `Filestore::import_proxies`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportSummary {
+    /// Proxies read from the file
+    pub read: usize,
+
+    /// Proxies that were already in the pool, or earlier in the file
+    pub duplicates: usize,
+
+    /// Proxies new to the pool, as added
+    pub added: Vec<Proxy>,
+
+    /// Entries of the file that couldn't be read, and why
+    pub skipped: Vec<DataIssue>,
+}
+
+/// Entries loaded from a file, along with the entries skipped
+///
+/// Returned by `Filestore::load_proxies_partial` and
+/// `Filestore::load_sources_partial`, which skip entries that can't be read
+/// instead of failing the whole file.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::DataIssueKind;
+/// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+///
+/// let dir = std::env::temp_dir().join("gooty_partial_load_example");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// let config = FilestoreConfig {
+///     data_dir: dir.to_string_lossy().to_string(),
+///     lenient_loading: false,
+///     ..Default::default()
+/// };
+/// let filestore = Filestore::with_config(config).unwrap();
+///
+/// std::fs::write(
+///     dir.join("proxies.toml"),
+///     r#"last_updated = "2025-01-01T00:00:00Z"
+///
+/// [[proxies]]
+/// proxy_type = "Http"
+/// address = "10.0.0.1"
+/// port = 8080
+/// anonymity = "Elite"
+///
+/// [[proxies]]
+/// proxy_type = "Http"
+/// address = "10.0.0.2"
+/// port = 99999
+/// anonymity = "Elite"
+/// "#,
+/// )
+/// .unwrap();
+///
+/// // Strict loading refuses the file
+/// assert!(filestore.load_proxies("proxies").is_err());
+///
+/// let loaded = filestore.load_proxies_partial("proxies").unwrap();
+/// assert_eq!(loaded.entries.len(), 1);
+/// assert!(!loaded.is_complete());
+/// assert_eq!(loaded.skipped[0].kind, DataIssueKind::InvalidPort);
+/// assert_eq!(loaded.skipped[0].entry, Some(1));
+/// assert_eq!(loaded.skipped[0].line, Some(9));
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialLoad<T> {
+    /// Entries that could be read
+    pub entries: Vec<T>,
+
+    /// Entries that were skipped, and why
+    pub skipped: Vec<DataIssue>,
+}
+
+impl<T> PartialLoad<T> {
+    /// Checks whether every entry of the file was loaded
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty()
+    }
+
+    /// Takes the entries if the filestore loads leniently or none were skipped
+    ///
+    /// Skipped entries are logged when loading leniently.
+    fn accept(self, lenient: bool) -> FilestoreResult<Vec<T>> {
+        match self.skipped.first() {
+            Some(issue) if !lenient => Err(FilestoreError::ParseError(format!(
+                "Invalid entry: {issue} (run `gatherer validate-data --repair` to remove it)"
+            ))),
+            _ => {
+                for issue in &self.skipped {
+                    log::warn!("Skipping unreadable entry of {issue}");
+                }
+                Ok(self.entries)
+            }
+        }
+    }
+}
+
+impl<T> From<ListEntries<T>> for PartialLoad<T> {
+    fn from(list: ListEntries<T>) -> Self {
+        PartialLoad {
+            entries: list.entries.into_iter().map(|(_, _, entry)| entry).collect(),
+            skipped: list
+                .issues
+                .into_iter()
+                .filter(|issue| issue.kind.drops_entry())
+                .collect(),
+        }
+    }
+}
+
+/// Entries of a list file, read one at a time
+struct ListEntries<T> {
+    /// Entries that could be read, with their position in the list and the
+    /// line they start on
+    entries: Vec<(usize, usize, T)>,
+
+    /// Problems of the file and of its entries
+    issues: Vec<DataIssue>,
+}
+
+/// File-based storage manager for proxies, sources, and configuration
+///
+/// The Filestore provides methods for loading and saving data to the
+/// filesystem, managing proxy lists, source lists, and configuration files.
+///
+/// It handles file operations, serialization/deserialization, and ensures
+/// data consistency when reading and writing files.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::filestore::{Filestore, FilestoreConfig};
+///
+/// // Create a filestore with default configuration
+/// let filestore = Filestore::new().unwrap();
+///
+/// // Create a filestore with custom configuration
+/// let config = FilestoreConfig {
+///     data_dir: "custom_data".to_string(),
+///     ..Default::default()
+/// };
+/// let filestore = Filestore::with_config(config).unwrap();
+///
+/// // Load proxies from a file
+/// let proxies = filestore.load_proxies("my_proxies").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Filestore {
+    /// Configuration for this filestore instance
+    config: FilestoreConfig,
+
+    /// Base directory for all data files
+    base_dir: PathBuf,
+}
+
+impl Filestore {
+    /// Create a new filestore with default configuration
+    ///
+    /// Creates a new filestore that stores data in the default data directory.
+    ///
+    /// # Returns
+    ///
+    /// A new Filestore instance
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be created or accessed
+    pub fn new() -> FilestoreResult<Self> {
+        Self::with_config(FilestoreConfig::default())
+    }
+
+    /// Create a new filestore with custom configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Configuration settings for the filestore
+    ///
+    /// # Returns
+    ///
+    /// A new Filestore instance with the specified configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be created or accessed
+    pub fn with_config(config: FilestoreConfig) -> FilestoreResult<Self> {
+        let base_dir = PathBuf::from(&config.data_dir);
+
+        // Create the directory if it doesn't exist
+        if !base_dir.exists() {
+            fs::create_dir_all(&base_dir).map_err(|e| {
+                FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+            })?;
+        }
+
+        Ok(Filestore { config, base_dir })
+    }
+
+    /// Load proxies from a file
+    ///
+    /// If `lenient_loading` is set, entries that can't be read, such as
+    /// proxies stored with port zero, are skipped with a warning rather than
+    /// failing the whole file, and are gone from the file once it's saved
+    /// again. `validate_data` reports them, and removes them when repairing.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// A vector of Proxy objects loaded from the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file doesn't exist and `create_defaults_if_missing` is false
+    /// * The file exists but cannot be read
+    /// * The file content is not valid TOML
+    /// * The `proxies` key of the file is not a list
+    /// * An entry can't be read and `lenient_loading` is false
+    pub fn load_proxies(&self, name: &str) -> FilestoreResult<Vec<Proxy>> {
+        self.load_proxies_partial(name)?
+            .accept(self.config.lenient_loading)
+    }
+
+    /// Load proxies from a file, skipping entries that can't be read
+    ///
+    /// Unlike `load_proxies`, skipped entries are returned for the caller to
+    /// report, whatever `lenient_loading` is set to.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The proxies loaded from the file, and the entries skipped with their
+    /// position and line
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file doesn't exist and `create_defaults_if_missing` is false
+    /// * The file exists but cannot be read
+    /// * The file content is not valid TOML
+    /// * The `proxies` key of the file is not a list
+    pub fn load_proxies_partial(&self, name: &str) -> FilestoreResult<PartialLoad<Proxy>> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if !file_path.exists() {
+            if self.config.create_defaults_if_missing {
+                // Create an empty proxies file
+                self.save_proxies(&Vec::new(), name)?;
+                return Ok(PartialLoad {
+                    entries: Vec::new(),
+                    skipped: Vec::new(),
+                });
+            }
+            return Err(FilestoreError::FileNotFound(
+                file_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        // Read the file content
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+
+        // Parse TOML, one proxy at a time
+        Ok(read_list::<Proxy>(&content, "proxies", &file_path)?.into())
+    }
+
+    /// Save proxies to a file
+    ///
+    /// # Arguments
+    ///
+    /// * `proxies` - Vector of proxies to save
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) if the proxies were successfully saved. The file is replaced
+    /// atomically, so concurrent readers see either the old or the new list,
+    /// and the file's journal is truncated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The proxies cannot be serialized to TOML
+    pub fn save_proxies(&self, proxies: &[Proxy], name: &str) -> FilestoreResult<()> {
+        self.save_proxies_iter(proxies, name)
+    }
+
+    /// Save proxies to a file as they are handed out
+    ///
+    /// Like `save_proxies`, but the proxies needn't be collected first, and
+    /// they are serialized `persistence::SAVE_CHUNK_SIZE` at a time, so saving
+    /// a large pool doesn't hold a second copy of it in memory. Use
+    /// `stream_proxies` to read the file back the same way.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxies` - The proxies to save, such as `ProxyManager::iter_proxies`
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The proxies cannot be serialized to TOML
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+    ///
+    /// let dir = std::env::temp_dir().join("gooty_stream_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let config = FilestoreConfig {
+    ///     data_dir: dir.to_string_lossy().to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let filestore = Filestore::with_config(config).unwrap();
+    ///
+    /// let proxies: Vec<Proxy> = (1..=2500)
+    ///     .map(|i| format!("http://10.0.{}.{}:8080", i / 250, i % 250).parse().unwrap())
+    ///     .collect();
+    /// filestore.save_proxies_iter(&proxies, "proxies").unwrap();
+    /// assert_eq!(filestore.load_proxies("proxies").unwrap(), proxies);
+    ///
+    /// let mut journal = filestore.open_journal("proxies").unwrap();
+    /// let mut checked = proxies[0].clone();
+    /// checked.record_check(120);
+    /// journal.record(&checked).unwrap();
+    /// journal.flush().unwrap();
+    ///
+    /// let mut stream = filestore.stream_proxies("proxies").unwrap();
+    /// assert_eq!(stream.next().unwrap().unwrap().check_count, 1);
+    /// assert_eq!(stream.count(), 2499);
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn save_proxies_iter<'a>(
+        &self,
+        proxies: impl IntoIterator<Item = &'a Proxy>,
+        name: &str,
+    ) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, "toml");
+
+        // Ensure the directory exists
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+                })?;
+            }
+        }
+
+        // Serialize to TOML a chunk at a time, each proxy a `[[proxies]]` block
+        let pretty = self.config.pretty_print;
+        let write_chunk = |file: &mut BufWriter<fs::File>, chunk: &ProxiesChunk<&Proxy>| {
+            let toml_content = if pretty {
+                toml::to_string_pretty(chunk)
+            } else {
+                toml::to_string(chunk)
+            }
+            .map_err(|e| {
+                FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+            })?;
+            write!(file, "\n{toml_content}").map_err(|e| write_error(&e))
+        };
+
+        // Write to file
+        Self::write_atomic_with(&file_path, |file| {
+            writeln!(file, "last_updated = \"{}\"", Utc::now().to_rfc3339())
+                .map_err(|e| write_error(&e))?;
+
+            let mut chunk = ProxiesChunk {
+                proxies: Vec::with_capacity(defaults::persistence::SAVE_CHUNK_SIZE),
+            };
+            let mut written = false;
+            for proxy in proxies {
+                chunk.proxies.push(proxy);
+                if chunk.proxies.len() == defaults::persistence::SAVE_CHUNK_SIZE {
+                    write_chunk(file, &chunk)?;
+                    chunk.proxies.clear();
+                    written = true;
+                }
+            }
+            if !chunk.proxies.is_empty() {
+                write_chunk(file, &chunk)?;
+            } else if !written {
+                writeln!(file, "proxies = []").map_err(|e| write_error(&e))?;
+            }
+            Ok(())
+        })?;
+
+        // The new snapshot supersedes every journaled entry
+        let journal_path = self.get_file_path(name, JOURNAL_EXTENSION);
+        if journal_path.exists() {
+            fs::File::create(&journal_path).map_err(|e| {
+                FilestoreError::IoError(format!("Failed to truncate journal: {e:?}"))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Open the append-only journal belonging to a proxies file
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the proxies file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// A `ProxyJournal` appending to `<name>.journal.jsonl`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an existing journal cannot be read
+    pub fn open_journal(&self, name: &str) -> FilestoreResult<ProxyJournal> {
+        let path = self.get_file_path(name, JOURNAL_EXTENSION);
+        let entries = if path.exists() {
+            fs::read_to_string(&path)
+                .map_err(|e| FilestoreError::IoError(format!("Failed to read journal: {e:?}")))?
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .count()
+        } else {
+            0
+        };
+
+        Ok(ProxyJournal {
+            name: name.to_string(),
+            path,
+            pending: Vec::new(),
+            last_flush: None,
+            flush_interval: Duration::from_millis(defaults::persistence::JOURNAL_FLUSH_INTERVAL_MS),
+            entries,
+            compact_threshold: defaults::persistence::JOURNAL_COMPACT_THRESHOLD,
+        })
+    }
+
+    /// Load proxies from a file and replay its journal over them
+    ///
+    /// Journaled proxies replace snapshot entries with the same connection
+    /// string, later entries winning. Lines that cannot be parsed, such as a
+    /// final line torn by a crash, are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// A vector of Proxy objects reflecting the snapshot and the journal
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * Neither the snapshot nor the journal exists and `create_defaults_if_missing` is false
+    /// * The snapshot or the journal exists but cannot be read
+    /// * The snapshot cannot be parsed
+    pub fn load_proxies_with_journal(&self, name: &str) -> FilestoreResult<Vec<Proxy>> {
+        let journal_path = self.get_file_path(name, JOURNAL_EXTENSION);
+        if !journal_path.exists() {
+            return self.load_proxies(name);
+        }
+
+        // Creating a default snapshot would truncate the journal, so only load
+        // a snapshot that already exists
+        let mut proxies = if self.get_file_path(name, "toml").exists() {
+            self.load_proxies(name)?
+        } else {
+            Vec::new()
+        };
+
+        let content = fs::read_to_string(&journal_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read journal: {e:?}")))?;
+
+        let mut index: HashMap<ProxyId, usize> = proxies
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.id(), i))
+            .collect();
+
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let proxy: Proxy = match serde_json::from_str(line) {
+                Ok(proxy) => proxy,
+                Err(e) => {
+                    log::warn!("Skipping unreadable journal entry: {e}");
+                    continue;
+                }
+            };
+
+            let key = proxy.id();
+            if let Some(slot) = index.get(&key).and_then(|&i| proxies.get_mut(i)) {
+                *slot = proxy;
+            } else {
+                index.insert(key, proxies.len());
+                proxies.push(proxy);
+            }
+        }
+
+        Ok(proxies)
+    }
+
+    /// Read the proxies of a file one at a time, replaying its journal
+    ///
+    /// Unlike `load_proxies_with_journal`, the pool isn't read into memory
+    /// whole, which matters for pools of hundreds of thousands of proxies.
+    /// See `ProxyStream` for how the file is read, and `save_proxies_iter`
+    /// for an example.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The proxies of the snapshot, as replaced by the journal, followed by
+    /// the proxies only the journal holds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * Neither the snapshot nor the journal exists and `create_defaults_if_missing` is false
+    /// * The snapshot or the journal exists but cannot be read
+    /// * The snapshot lists its proxies inline and can't be parsed
+    pub fn stream_proxies(&self, name: &str) -> FilestoreResult<ProxyStream> {
+        let file_path = self.get_file_path(name, "toml");
+        let journal_path = self.get_file_path(name, JOURNAL_EXTENSION);
+        if !file_path.exists() && !journal_path.exists() && !self.config.create_defaults_if_missing
+        {
+            return Err(FilestoreError::FileNotFound(
+                file_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        let lenient = self.config.lenient_loading;
+        let snapshot = if file_path.exists() {
+            SnapshotReader::open(&file_path, lenient)?
+        } else {
+            SnapshotReader::Done
+        };
+
+        // The journal is compacted before it grows large, so it's read whole
+        let mut journal = Vec::new();
+        let mut journaled = HashMap::new();
+        if journal_path.exists() {
+            let content = fs::read_to_string(&journal_path)
+                .map_err(|e| FilestoreError::IoError(format!("Failed to read journal: {e:?}")))?;
+            for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                let proxy: Proxy = match serde_json::from_str(line) {
+                    Ok(proxy) => proxy,
+                    Err(e) => {
+                        log::warn!("Skipping unreadable journal entry: {e}");
+                        continue;
+                    }
+                };
+
+                let key = proxy.id();
+                if let Some(slot) = journaled.get(&key).and_then(|&i| journal.get_mut(i)) {
+                    *slot = Some(proxy);
+                } else {
+                    journaled.insert(key, journal.len());
+                    journal.push(Some(proxy));
+                }
+            }
+        }
+
+        Ok(ProxyStream {
+            snapshot,
+            journal,
+            journaled,
+            tail: 0,
+            lenient,
+        })
+    }
+
+    /// Merge two persisted proxy pools into a third
+    ///
+    /// Combines pools gathered on different machines, such as gatherers
+    /// running in several regions. Both pools are read along with their
+    /// journals. A proxy found in both is combined as the policy says, and
+    /// the merged pool lists the first pool's proxies before those only the
+    /// second has.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - Base name of the first pool's file (without extension)
+    /// * `second` - Base name of the second pool's file (without extension)
+    /// * `out` - Base name of the file to write the merged pool to, which may
+    ///   be one of the inputs
+    /// * `policy` - How to combine a proxy found in both pools
+    ///
+    /// # Returns
+    ///
+    /// How many proxies each pool held and the merged pool holds
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * Either pool doesn't exist
+    /// * Either pool cannot be read or parsed
+    /// * The merged pool cannot be written
+    pub fn merge_proxy_files(
+        &self,
+        first: &str,
+        second: &str,
+        out: &str,
+        policy: MergePolicy,
+    ) -> FilestoreResult<MergeSummary> {
+        let mut pools = Vec::with_capacity(2);
+        for name in [first, second] {
+            let file_path = self.get_file_path(name, "toml");
+            if !file_path.exists() && !self.get_file_path(name, JOURNAL_EXTENSION).exists() {
+                return Err(FilestoreError::FileNotFound(
+                    file_path.to_string_lossy().to_string(),
+                ));
+            }
+            pools.push(self.load_proxies_with_journal(name)?);
+        }
+        let second_pool = pools.pop().unwrap_or_default();
+        let mut merged = pools.pop().unwrap_or_default();
+
+        let mut summary = MergeSummary {
+            first: merged.len(),
+            second: second_pool.len(),
+            ..MergeSummary::default()
+        };
+
+        let mut index: HashMap<ProxyId, usize> = merged
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.id(), i))
+            .collect();
+
+        for proxy in second_pool {
+            let key = proxy.id();
+            let Some(slot) = index.get(&key).and_then(|&i| merged.get_mut(i)) else {
+                index.insert(key, merged.len());
+                merged.push(proxy);
+                continue;
+            };
+
+            summary.reconciled += 1;
+            match policy {
+                MergePolicy::Reconcile => *slot = slot.clone().reconcile(proxy),
+                MergePolicy::PreferFirst => {}
+                MergePolicy::PreferSecond => *slot = proxy,
+            }
+        }
+
+        summary.merged = merged.len();
+        self.save_proxies(&merged, out)?;
+        Ok(summary)
+    }
+
+    /// Export a persisted proxy pool for other tools
+    ///
+    /// The pool is read along with its journal and written as a plain list,
+    /// URLs, JSON, CSV or Parquet, since TOML is rarely what other tools
+    /// consume. See `export::export_proxies_with_columns` for the formats.
+    /// Available with the `export` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the pool's file (without extension)
+    /// * `path` - The file to write, replaced if it exists
+    /// * `format` - The format to write the file in
+    /// * `columns` - The columns CSV and JSON exports hold, in order, or none
+    ///   for every column
+    ///
+    /// # Returns
+    ///
+    /// The number of proxies written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The pool cannot be read or parsed
+    /// * The file cannot be created or written
+    /// * Parquet is requested without the `arrow` feature
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{ExportColumn, ExportFormat};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+    ///
+    /// let dir = std::env::temp_dir().join("gooty_export_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let config = FilestoreConfig {
+    ///     data_dir: dir.to_string_lossy().to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let filestore = Filestore::with_config(config).unwrap();
+    /// let proxy: Proxy = "socks5://10.0.0.1:1080".parse().unwrap();
+    /// filestore.save_proxies(&[proxy], "proxies").unwrap();
+    ///
+    /// let list = dir.join("proxies.txt");
+    /// assert_eq!(filestore.export_proxies("proxies", &list, ExportFormat::Urls, &[]).unwrap(), 1);
+    /// assert_eq!(std::fs::read_to_string(&list).unwrap(), "socks5://10.0.0.1:1080\n");
+    ///
+    /// let csv = dir.join("proxies.csv");
+    /// let columns = [ExportColumn::Ip, ExportColumn::Port, ExportColumn::Anonymity];
+    /// filestore.export_proxies("proxies", &csv, ExportFormat::Csv, &columns).unwrap();
+    /// assert_eq!(
+    ///     std::fs::read_to_string(&csv).unwrap(),
+    ///     "ip,port,anonymity\n10.0.0.1,1080,Anonymous\n"
+    /// );
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    #[cfg(feature = "export")]
+    pub fn export_proxies(
+        &self,
+        name: &str,
+        path: &Path,
+        format: ExportFormat,
+        columns: &[ExportColumn],
+    ) -> FilestoreResult<usize> {
+        let proxies = self.load_proxies_with_journal(name)?;
+        export::export_proxies_with_columns(path, format, columns, &proxies)
+    }
+
+    /// Import proxies from a file other tools wrote into a persisted pool
+    ///
+    /// Reads plain `ip:port` lists, `scheme://ip:port` lists, JSON and CSV,
+    /// see `import::parse_proxies`. Entries that can't be read are skipped
+    /// and reported. A proxy already in the pool, or met earlier in the
+    /// file, isn't added again; the pooled copy takes what metadata it
+    /// lacks instead. New proxies are added unjudged.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the pool's file (without extension)
+    /// * `path` - The file to read, or `-` for standard input
+    /// * `format` - The format of the file, or `None` to detect it
+    ///
+    /// # Returns
+    ///
+    /// How many proxies were read, which were added, and what was skipped
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be read, or holds no proxies in the format
+    /// * The pool cannot be read, parsed or written
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+    ///
+    /// let dir = std::env::temp_dir().join("gooty_import_pool_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let config = FilestoreConfig {
+    ///     data_dir: dir.to_string_lossy().to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let filestore = Filestore::with_config(config).unwrap();
+    /// let pooled = "http://10.0.0.1:8080".parse().unwrap();
+    /// filestore.save_proxies(&[pooled], "proxies").unwrap();
+    ///
+    /// let list = dir.join("list.txt");
+    /// std::fs::write(&list, "10.0.0.1:8080\n10.0.0.2:3128\n10.0.0.2:3128\nnot a proxy\n").unwrap();
+    /// let summary = filestore.import_proxies("proxies", &list, None).unwrap();
+    /// assert_eq!((summary.read, summary.duplicates), (3, 2));
+    /// assert_eq!(summary.added[0].to_string(), "http://10.0.0.2:3128");
+    /// assert_eq!(summary.skipped[0].line, Some(4));
+    /// assert_eq!(filestore.load_proxies("proxies").unwrap().len(), 2);
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    #[cfg(feature = "export")]
+    pub fn import_proxies(
+        &self,
+        name: &str,
+        path: &Path,
+        format: Option<ExportFormat>,
+    ) -> FilestoreResult<ImportSummary> {
+        let (pool, summary) = self.merge_import(name, path, format)?;
+        self.save_proxies(&pool, name)?;
+        Ok(summary)
+    }
+
+    /// Report what importing a file into a persisted pool would do
+    ///
+    /// Reads and deduplicates the file against the pool exactly like
+    /// `import_proxies`, but leaves the pool's file as it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the pool's file (without extension)
+    /// * `path` - The file to read, or `-` for standard input
+    /// * `format` - The format of the file, or `None` to detect it
+    ///
+    /// # Returns
+    ///
+    /// How many proxies would be read, which would be added, and what was skipped
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be read, or holds no proxies in the format
+    /// * The pool cannot be read or parsed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+    ///
+    /// let dir = std::env::temp_dir().join("gooty_preview_import_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let config = FilestoreConfig {
+    ///     data_dir: dir.to_string_lossy().to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let filestore = Filestore::with_config(config).unwrap();
+    /// let pooled = "http://10.0.0.1:8080".parse().unwrap();
+    /// filestore.save_proxies(&[pooled], "proxies").unwrap();
+    ///
+    /// let list = dir.join("list.txt");
+    /// std::fs::write(&list, "10.0.0.1:8080\n10.0.0.2:3128\n").unwrap();
+    /// let summary = filestore.preview_import("proxies", &list, None).unwrap();
+    /// assert_eq!((summary.read, summary.duplicates, summary.added.len()), (2, 1, 1));
+    /// assert_eq!(filestore.load_proxies("proxies").unwrap().len(), 1);
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    #[cfg(feature = "export")]
+    pub fn preview_import(
+        &self,
+        name: &str,
+        path: &Path,
+        format: Option<ExportFormat>,
+    ) -> FilestoreResult<ImportSummary> {
+        self.merge_import(name, path, format)
+            .map(|(_, summary)| summary)
+    }
+
+    /// Merge the proxies of a file into a persisted pool, without saving it
+    ///
+    /// # Returns
+    ///
+    /// The merged pool and the summary of the import
+    #[cfg(feature = "export")]
+    fn merge_import(
+        &self,
+        name: &str,
+        path: &Path,
+        format: Option<ExportFormat>,
+    ) -> FilestoreResult<(Vec<Proxy>, ImportSummary)> {
+        let loaded = import::read_proxies(path, format)?;
+        let mut pool = match self.load_proxies_with_journal(name) {
+            Ok(pool) => pool,
+            Err(FilestoreError::FileNotFound(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut summary = ImportSummary {
+            read: loaded.entries.len(),
+            skipped: loaded.skipped,
+            ..ImportSummary::default()
+        };
+        let existing = pool.len();
+        let mut index: HashMap<ProxyId, usize> = pool
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.id(), i))
+            .collect();
+
+        for proxy in loaded.entries {
+            let id = proxy.id();
+            let Some(pooled) = index.get(&id).and_then(|&i| pool.get_mut(i)) else {
+                index.insert(id, pool.len());
+                pool.push(proxy);
+                continue;
+            };
+            summary.duplicates += 1;
+            pooled.absorb(proxy, DuplicatePolicy::Enrich);
+        }
+
+        summary.added = pool.get(existing..).unwrap_or_default().to_vec();
+        Ok((pool, summary))
+    }
+
+    /// Load sources from a file
+    ///
+    /// If `lenient_loading` is set, entries that can't be read are skipped
+    /// with a warning rather than failing the whole file, and are gone from
+    /// the file once it's saved again.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// A vector of Source objects loaded from the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file doesn't exist and `create_defaults_if_missing` is false
+    /// * The file exists but cannot be read
+    /// * The file content is not valid TOML
+    /// * The `sources` key of the file is not a list
+    /// * An entry can't be read and `lenient_loading` is false
+    pub fn load_sources(&self, name: &str) -> FilestoreResult<Vec<Source>> {
+        self.load_sources_partial(name)?
+            .accept(self.config.lenient_loading)
+    }
+
+    /// Load sources from a file, skipping entries that can't be read
+    ///
+    /// Unlike `load_sources`, skipped entries are returned for the caller to
+    /// report, whatever `lenient_loading` is set to.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The sources loaded from the file, and the entries skipped with their
+    /// position and line
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file doesn't exist and `create_defaults_if_missing` is false
+    /// * The file exists but cannot be read
+    /// * The file content is not valid TOML
+    /// * The `sources` key of the file is not a list
+    pub fn load_sources_partial(&self, name: &str) -> FilestoreResult<PartialLoad<Source>> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if !file_path.exists() {
+            if self.config.create_defaults_if_missing {
+                // Create an empty sources file
+                self.save_sources(&Vec::new(), name)?;
+                return Ok(PartialLoad {
+                    entries: Vec::new(),
+                    skipped: Vec::new(),
+                });
+            }
+            return Err(FilestoreError::FileNotFound(
+                file_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        // Read the file content
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+
+        // Parse TOML, one source at a time
+        let mut loaded: PartialLoad<Source> =
+            read_list::<Source>(&content, "sources", &file_path)?.into();
+
+        // Recompile regex patterns in sources
+        for source in &mut loaded.entries {
+            if let Ok(regex) = SerializableRegex::new(&source.regex_pattern) {
+                source.compiled_regex = Some(regex);
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    /// Save sources to a file
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - Vector of sources to save
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) if the sources were successfully saved. The file is replaced
+    /// atomically, so concurrent readers see either the old or the new list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The sources cannot be serialized to TOML
+    pub fn save_sources(&self, sources: &[Source], name: &str) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, "toml");
+
+        // Ensure the directory exists
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+                })?;
+            }
+        }
+
+        // Create a container with metadata
+        let container = SourcesContainer {
+            last_updated: Utc::now().to_rfc3339(),
+            sources: sources.to_vec(),
+        };
+
+        // Serialize to TOML
+        let toml_content = if self.config.pretty_print {
+            toml::to_string_pretty(&container).map_err(|e| {
+                FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+            })?
+        } else {
+            toml::to_string(&container).map_err(|e| {
+                FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+            })?
+        };
+
+        // Write to file
+        Self::write_atomic(&file_path, &toml_content)
+    }
+
+    /// Load lookup provider usage from a file
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The usage of every provider stored in the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file doesn't exist
+    /// * The file exists but cannot be read
+    /// * The file content is not valid TOML
+    pub fn load_provider_usage(&self, name: &str) -> FilestoreResult<Vec<ProviderUsage>> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if !file_path.exists() {
+            return Err(FilestoreError::FileNotFound(
+                file_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        // Read the file content
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+
+        // Parse TOML
+        let container: ProviderUsageContainer = toml::from_str(&content)
+            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))?;
+
+        Ok(container.providers)
+    }
+
+    /// Save lookup provider usage to a file
+    ///
+    /// # Arguments
+    ///
+    /// * `usage` - The usage of every provider
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) if the usage was successfully saved
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The usage cannot be serialized to TOML
+    pub fn save_provider_usage(&self, usage: &[ProviderUsage], name: &str) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, "toml");
+
+        // Ensure the directory exists
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+                })?;
+            }
+        }
+
+        let container = ProviderUsageContainer {
+            last_updated: Utc::now().to_rfc3339(),
+            providers: usage.to_vec(),
+        };
+
+        let toml_content = toml::to_string_pretty(&container).map_err(|e| {
+            FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+        })?;
+
+        Self::write_atomic(&file_path, &toml_content)
+    }
+
+    /// Load downloaded cloud ranges from a file
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The ranges of every provider stored in the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file doesn't exist
+    /// * The file exists but cannot be read
+    /// * The file content is not valid TOML
+    pub fn load_cloud_ranges(&self, name: &str) -> FilestoreResult<Vec<CloudRangeSnapshot>> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if !file_path.exists() {
+            return Err(FilestoreError::FileNotFound(
+                file_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+
+        let container: CloudRangesContainer = toml::from_str(&content)
+            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))?;
+
+        Ok(container.providers)
+    }
+
+    /// Load a public suffix list saved in the data directory
+    ///
+    /// The list is returned as it was downloaded from
+    /// <https://publicsuffix.org/list/public_suffix_list.dat>, for
+    /// `utils::set_public_suffix_list`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without the `.dat` extension)
+    ///
+    /// # Returns
+    ///
+    /// The content of the list
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file doesn't exist
+    /// * The file exists but cannot be read
+    pub fn load_public_suffix_list(&self, name: &str) -> FilestoreResult<String> {
+        let file_path = self.get_file_path(name, "dat");
+
+        if !file_path.exists() {
+            return Err(FilestoreError::FileNotFound(
+                file_path.to_string_lossy().to_string(),
+            ));
+        }
+
+        fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))
+    }
+
+    /// Save downloaded cloud ranges to a file
+    ///
+    /// # Arguments
+    ///
+    /// * `ranges` - The ranges of every provider
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) if the ranges were successfully saved
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The ranges cannot be serialized to TOML
+    pub fn save_cloud_ranges(
+        &self,
+        ranges: &[CloudRangeSnapshot],
+        name: &str,
+    ) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+                })?;
+            }
+        }
+
+        let container = CloudRangesContainer {
+            last_updated: Utc::now().to_rfc3339(),
+            providers: ranges.to_vec(),
+        };
+
+        let toml_content = toml::to_string_pretty(&container).map_err(|e| {
+            FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+        })?;
+
+        Self::write_atomic(&file_path, &toml_content)
+    }
+
+    /// Load saved documents of lookup providers
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The documents, or none if none were saved
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::defaults::persistence::LOOKUP_CACHE_FILE;
+    /// use gooty_proxy::inspection::lookup_cache::LookupCache;
+    /// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+    ///
+    /// let dir = std::env::temp_dir().join("gooty_lookup_cache_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let filestore = Filestore::with_config(FilestoreConfig {
+    ///     data_dir: dir.to_string_lossy().to_string(),
+    ///     ..Default::default()
+    /// })
+    /// .unwrap();
+    /// assert!(filestore.load_lookup_cache(LOOKUP_CACHE_FILE).unwrap().is_empty());
+    ///
+    /// let cache = LookupCache::default();
+    /// cache.insert(&"8.8.8.8".parse().unwrap(), "{\"org\": \"AS15169 Google LLC\"}");
+    /// filestore.save_lookup_cache(&cache.snapshots(), LOOKUP_CACHE_FILE).unwrap();
+    ///
+    /// let saved = filestore.load_lookup_cache(LOOKUP_CACHE_FILE).unwrap();
+    /// assert_eq!(saved, cache.snapshots());
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    pub fn load_lookup_cache(&self, name: &str) -> FilestoreResult<Vec<CachedLookup>> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if !file_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+
+        let container: LookupCacheContainer = toml::from_str(&content)
+            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))?;
+
+        Ok(container.lookups)
+    }
+
+    /// Save documents of lookup providers, replacing earlier ones
+    ///
+    /// # Arguments
+    ///
+    /// * `lookups` - The documents to save
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The documents cannot be serialized to TOML
+    pub fn save_lookup_cache(&self, lookups: &[CachedLookup], name: &str) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if let Some(parent) = file_path.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent).map_err(|e| {
+                FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+            })?;
+        }
+
+        let container = LookupCacheContainer {
+            lookups: lookups.to_vec(),
+        };
+
+        let toml_content = toml::to_string_pretty(&container).map_err(|e| {
+            FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+        })?;
+
+        Self::write_atomic(&file_path, &toml_content)
+    }
+
+    /// Load saved raw responses of judges and lookup providers
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The responses, oldest first, or none if none were saved
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::defaults::persistence::CAPTURED_RESPONSES_FILE;
+    /// use gooty_proxy::inspection::capture::ResponseCapture;
+    /// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+    ///
+    /// let dir = std::env::temp_dir().join("gooty_capture_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let filestore = Filestore::with_config(FilestoreConfig {
+    ///     data_dir: dir.to_string_lossy().to_string(),
+    ///     ..Default::default()
+    /// })
+    /// .unwrap();
+    /// assert!(filestore.load_captured_responses(CAPTURED_RESPONSES_FILE).unwrap().is_empty());
+    ///
+    /// let capture = ResponseCapture::new(10);
+    /// capture.record("ipinfo", "https://ipinfo.io/10.0.0.1/json", "10.0.0.1", Some(200), "{\"ip\": \"10.0.0.1\"}");
+    /// filestore.save_captured_responses(&capture.recent(), CAPTURED_RESPONSES_FILE).unwrap();
+    ///
+    /// let saved = filestore.load_captured_responses(CAPTURED_RESPONSES_FILE).unwrap();
+    /// assert_eq!(saved, capture.recent());
+    /// ```
+    pub fn load_captured_responses(&self, name: &str) -> FilestoreResult<Vec<CapturedResponse>> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if !file_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+
+        let container: CapturedResponsesContainer = toml::from_str(&content)
+            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))?;
+
+        Ok(container.responses)
+    }
+
+    /// Save raw responses of judges and lookup providers, replacing earlier ones
+    ///
+    /// # Arguments
+    ///
+    /// * `responses` - The responses, oldest first
+    /// * `name` - Base name of the file (without extension)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The responses cannot be serialized to TOML
+    pub fn save_captured_responses(
+        &self,
+        responses: &[CapturedResponse],
+        name: &str,
+    ) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, "toml");
+
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+                })?;
+            }
+        }
+
+        let container = CapturedResponsesContainer {
+            responses: responses.to_vec(),
+        };
+
+        let toml_content = toml::to_string_pretty(&container).map_err(|e| {
+            FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+        })?;
+
+        Self::write_atomic(&file_path, &toml_content)
+    }
+
+    /// Load the checkpoint of a job, if one was left behind
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the checkpoint (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The checkpoint, or None if the job has no unfinished run
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint exists but cannot be read or parsed
+    pub fn load_checkpoint(&self, name: &str) -> FilestoreResult<Option<JobCheckpoint>> {
+        let file_path = self.get_file_path(name, CHECKPOINT_EXTENSION);
+
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))
+    }
+
+    /// Save the checkpoint of a job
+    ///
+    /// The file is replaced atomically, so an interruption while saving
+    /// leaves the previous checkpoint intact.
+    ///
+    /// # Arguments
+    ///
+    /// * `checkpoint` - The progress to save
+    /// * `name` - Base name of the checkpoint (without extension)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The checkpoint cannot be serialized to TOML
+    pub fn save_checkpoint(&self, checkpoint: &JobCheckpoint, name: &str) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, CHECKPOINT_EXTENSION);
+
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+                })?;
+            }
+        }
+
+        let toml_content = toml::to_string(checkpoint).map_err(|e| {
+            FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+        })?;
+
+        Self::write_atomic(&file_path, &toml_content)
+    }
+
+    /// Load a saved rotation state
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the state file (without extension)
+    ///
+    /// # Returns
+    ///
+    /// The state, or None if none was saved
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state exists but cannot be read or parsed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{Duration, Utc};
+    /// use gooty_proxy::definitions::defaults::persistence::ROTATION_STATE_FILE;
+    /// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+    /// use gooty_proxy::orchestration::rotation::RotationState;
+    ///
+    /// let dir = std::env::temp_dir().join("gooty_rotation_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let filestore = Filestore::with_config(FilestoreConfig {
+    ///     data_dir: dir.to_string_lossy().to_string(),
+    ///     ..Default::default()
+    /// })
+    /// .unwrap();
+    /// assert!(filestore.load_rotation_state(ROTATION_STATE_FILE).unwrap().is_none());
+    ///
+    /// let mut state = RotationState::default();
+    /// state.advance(10);
+    /// state.ban("example.com", "http://10.0.0.1:8080", Utc::now() + Duration::hours(1));
+    /// filestore.save_rotation_state(&state, ROTATION_STATE_FILE).unwrap();
+    ///
+    /// // A restarted rotation picks up where it left off
+    /// let restored = filestore.load_rotation_state(ROTATION_STATE_FILE).unwrap().unwrap();
+    /// assert_eq!(restored, state);
+    /// assert_eq!(restored.cursor, 1);
+    /// ```
+    pub fn load_rotation_state(&self, name: &str) -> FilestoreResult<Option<RotationState>> {
+        let file_path = self.get_file_path(name, ROTATION_STATE_EXTENSION);
+
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))
+    }
+
+    /// Save a rotation state
+    ///
+    /// The file is replaced atomically, so an interruption while saving
+    /// leaves the previous state intact.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to save
+    /// * `name` - Base name of the state file (without extension)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The file cannot be created or written to
+    /// * The state cannot be serialized to TOML
+    pub fn save_rotation_state(&self, state: &RotationState, name: &str) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, ROTATION_STATE_EXTENSION);
+
+        if let Some(parent) = file_path.parent() {
+            if !parent.exists() {
+                fs::create_dir_all(parent).map_err(|e| {
+                    FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+                })?;
+            }
+        }
+
+        let toml_content = toml::to_string(state).map_err(|e| {
+            FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+        })?;
 
-        Ok(Filestore { config, base_dir })
+        Self::write_atomic(&file_path, &toml_content)
     }
 
-    /// Load proxies from a file
+    /// Load the client statistics saved by a listener
     ///
     /// # Arguments
     ///
-    /// * `name` - Base name of the file (without extension)
+    /// * `name` - Base name of the statistics file (without extension)
     ///
     /// # Returns
     ///
-    /// A vector of Proxy objects loaded from the file
+    /// The statistics by client, empty if none were saved
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// * The file doesn't exist and `create_defaults_if_missing` is false
-    /// * The file exists but cannot be read
-    /// * The file content is not valid TOML
-    /// * The TOML cannot be deserialized into proxies
-    pub fn load_proxies(&self, name: &str) -> FilestoreResult<Vec<Proxy>> {
-        let file_path = self.get_file_path(name, "toml");
+    /// Returns an error if the statistics exist but cannot be read or parsed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::defaults::persistence::CLIENT_STATS_FILE;
+    /// use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+    /// use gooty_proxy::orchestration::listener::{ClientStats, ClientStatsMap};
+    ///
+    /// let dir = std::env::temp_dir().join("gooty_client_stats_example");
+    /// # let _ = std::fs::remove_dir_all(&dir);
+    /// let filestore = Filestore::with_config(FilestoreConfig {
+    ///     data_dir: dir.to_string_lossy().to_string(),
+    ///     ..Default::default()
+    /// })
+    /// .unwrap();
+    /// assert!(filestore.load_client_stats(CLIENT_STATS_FILE).unwrap().is_empty());
+    ///
+    /// let mut stats = ClientStats {
+    ///     connections: 2,
+    ///     bytes_received: 4096,
+    ///     ..Default::default()
+    /// };
+    /// stats.upstreams.insert("socks5://10.0.0.1:1080".to_string(), 2);
+    /// let clients = ClientStatsMap::from([("192.168.1.20".to_string(), stats)]);
+    /// filestore.save_client_stats(&clients, CLIENT_STATS_FILE).unwrap();
+    ///
+    /// assert_eq!(filestore.load_client_stats(CLIENT_STATS_FILE).unwrap(), clients);
+    /// ```
+    pub fn load_client_stats(&self, name: &str) -> FilestoreResult<ClientStatsMap> {
+        let file_path = self.get_file_path(name, CLIENT_STATS_EXTENSION);
 
         if !file_path.exists() {
-            if self.config.create_defaults_if_missing {
-                // Create an empty proxies file
-                self.save_proxies(&Vec::new(), name)?;
-                return Ok(Vec::new());
-            }
-            return Err(FilestoreError::FileNotFound(
-                file_path.to_string_lossy().to_string(),
-            ));
+            return Ok(ClientStatsMap::new());
         }
 
-        // Read the file content
         let content = fs::read_to_string(&file_path)
             .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
 
-        // Parse TOML
-        let container: ProxiesContainer = toml::from_str(&content)
-            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))?;
-
-        Ok(container.proxies)
+        toml::from_str(&content)
+            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))
     }
 
-    /// Save proxies to a file
+    /// Save the client statistics of a listener, replacing earlier ones
     ///
     /// # Arguments
     ///
-    /// * `proxies` - Vector of proxies to save
-    /// * `name` - Base name of the file (without extension)
-    ///
-    /// # Returns
-    ///
-    /// Ok(()) if the proxies were successfully saved
+    /// * `clients` - The statistics by client
+    /// * `name` - Base name of the statistics file (without extension)
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// * The file cannot be created or written to
-    /// * The proxies cannot be serialized to TOML
-    pub fn save_proxies(&self, proxies: &[Proxy], name: &str) -> FilestoreResult<()> {
-        let file_path = self.get_file_path(name, "toml");
+    /// * The statistics cannot be serialized to TOML
+    pub fn save_client_stats(&self, clients: &ClientStatsMap, name: &str) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, CLIENT_STATS_EXTENSION);
 
-        // Ensure the directory exists
         if let Some(parent) = file_path.parent() {
             if !parent.exists() {
                 fs::create_dir_all(parent).map_err(|e| {
@@ -296,130 +2860,128 @@ impl Filestore {
             }
         }
 
-        // Create a container with metadata
-        let container = ProxiesContainer {
-            last_updated: Utc::now().to_rfc3339(),
-            proxies: proxies.to_vec(),
-        };
-
-        // Serialize to TOML
-        let toml_content = if self.config.pretty_print {
-            toml::to_string_pretty(&container).map_err(|e| {
-                FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
-            })?
-        } else {
-            toml::to_string(&container).map_err(|e| {
-                FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
-            })?
-        };
-
-        // Write to file
-        fs::write(&file_path, toml_content)
-            .map_err(|e| FilestoreError::IoError(format!("Failed to write file: {e:?}")))?;
+        let toml_content = toml::to_string(clients).map_err(|e| {
+            FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+        })?;
 
-        Ok(())
+        Self::write_atomic(&file_path, &toml_content)
     }
 
-    /// Load sources from a file
+    /// Load the state saved by a manager
     ///
     /// # Arguments
     ///
-    /// * `name` - Base name of the file (without extension)
+    /// * `name` - Base name of the state file (without extension)
     ///
     /// # Returns
     ///
-    /// A vector of Source objects loaded from the file
+    /// The saved state, or `None` if none was saved
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// * The file doesn't exist and `create_defaults_if_missing` is false
-    /// * The file exists but cannot be read
-    /// * The file content is not valid TOML
-    /// * The TOML cannot be deserialized into sources
-    pub fn load_sources(&self, name: &str) -> FilestoreResult<Vec<Source>> {
-        let file_path = self.get_file_path(name, "toml");
+    /// Returns an error if the state exists but cannot be read or parsed
+    pub fn load_manager_state(&self, name: &str) -> FilestoreResult<Option<ManagerState>> {
+        let file_path = self.get_file_path(name, MANAGER_STATE_EXTENSION);
 
         if !file_path.exists() {
-            if self.config.create_defaults_if_missing {
-                // Create an empty sources file
-                self.save_sources(&Vec::new(), name)?;
-                return Ok(Vec::new());
-            }
-            return Err(FilestoreError::FileNotFound(
-                file_path.to_string_lossy().to_string(),
-            ));
+            return Ok(None);
         }
 
-        // Read the file content
         let content = fs::read_to_string(&file_path)
             .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
 
-        // Parse TOML
-        let container: SourcesContainer = toml::from_str(&content)
-            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))?;
-
-        // Recompile regex patterns in sources
-        let mut sources = container.sources;
-        for source in &mut sources {
-            if let Ok(regex) = SerializableRegex::new(&source.regex_pattern) {
-                source.compiled_regex = Some(regex);
-            }
-        }
-
-        Ok(sources)
+        toml::from_str(&content)
+            .map(Some)
+            .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))
     }
 
-    /// Save sources to a file
+    /// Get when the state saved by a manager was last written
     ///
     /// # Arguments
     ///
-    /// * `sources` - Vector of sources to save
-    /// * `name` - Base name of the file (without extension)
+    /// * `name` - Base name of the state file (without extension)
     ///
     /// # Returns
     ///
-    /// Ok(()) if the sources were successfully saved
+    /// The modification time of the state file, or `None` if none was saved
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file's metadata cannot be read
+    pub fn manager_state_modified(&self, name: &str) -> FilestoreResult<Option<SystemTime>> {
+        let file_path = self.get_file_path(name, MANAGER_STATE_EXTENSION);
+
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        fs::metadata(&file_path)
+            .and_then(|metadata| metadata.modified())
+            .map(Some)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read metadata: {e:?}")))
+    }
+
+    /// Save the state of a manager
+    ///
+    /// The file is replaced atomically, so readers see either the old or the
+    /// new proxies and sources, never one list updated without the other.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to save
+    /// * `name` - Base name of the state file (without extension)
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// * The file cannot be created or written to
-    /// * The sources cannot be serialized to TOML
-    pub fn save_sources(&self, sources: &[Source], name: &str) -> FilestoreResult<()> {
-        let file_path = self.get_file_path(name, "toml");
+    /// * The state cannot be serialized to TOML
+    pub fn save_manager_state(&self, state: &ManagerState, name: &str) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, MANAGER_STATE_EXTENSION);
 
-        // Ensure the directory exists
-        if let Some(parent) = file_path.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent).map_err(|e| {
-                    FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
-                })?;
-            }
+        if let Some(parent) = file_path.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent).map_err(|e| {
+                FilestoreError::IoError(format!("Failed to create directory: {e:?}"))
+            })?;
         }
 
-        // Create a container with metadata
-        let container = SourcesContainer {
-            last_updated: Utc::now().to_rfc3339(),
-            sources: sources.to_vec(),
-        };
-
-        // Serialize to TOML
         let toml_content = if self.config.pretty_print {
-            toml::to_string_pretty(&container).map_err(|e| {
-                FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
-            })?
+            toml::to_string_pretty(state)
         } else {
-            toml::to_string(&container).map_err(|e| {
-                FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
-            })?
-        };
+            toml::to_string(state)
+        }
+        .map_err(|e| {
+            FilestoreError::SerializationError(format!("Failed to serialize to TOML: {e:?}"))
+        })?;
 
-        // Write to file
-        fs::write(&file_path, toml_content)
-            .map_err(|e| FilestoreError::IoError(format!("Failed to write file: {e:?}")))?;
+        Self::write_atomic(&file_path, &toml_content)
+    }
 
-        Ok(())
+    /// Remove the checkpoint of a job once it has finished
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name of the checkpoint (without extension)
+    ///
+    /// # Returns
+    ///
+    /// Whether a checkpoint was removed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint exists but cannot be removed
+    pub fn clear_checkpoint(&self, name: &str) -> FilestoreResult<bool> {
+        let file_path = self.get_file_path(name, CHECKPOINT_EXTENSION);
+
+        if !file_path.exists() {
+            return Ok(false);
+        }
+
+        fs::remove_file(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to remove checkpoint: {e:?}")))?;
+        Ok(true)
     }
 
     /// Load application configuration from a file
@@ -505,8 +3067,233 @@ impl Filestore {
         };
 
         // Write to file
-        fs::write(&file_path, toml_content)
-            .map_err(|e| FilestoreError::IoError(format!("Failed to write file: {e:?}")))?;
+        Self::write_atomic(&file_path, &toml_content)
+    }
+
+    /// Check the persisted proxies, sources and configuration for problems
+    ///
+    /// Proxies with a zero or out-of-range port, entries stored twice under
+    /// the same key, sources whose pattern doesn't compile, entries that
+    /// can't be read, and entries written by an older version are reported.
+    /// The proxies journal is checked along with the proxies file.
+    ///
+    /// # Arguments
+    ///
+    /// * `fix` - Whether to rewrite the proxies and sources files without their problems
+    ///
+    /// # Returns
+    ///
+    /// A `ValidationReport` listing the problems found and the files repaired.
+    /// Repairing drops every entry at fault but stale ones, which are
+    /// rewritten in the current format; repairing the proxies file folds its
+    /// journal into it. Files that aren't valid TOML, and the configuration,
+    /// are reported but never rewritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file exists but cannot be read, or a repaired
+    /// file cannot be written
+    pub fn validate_data(&self, fix: bool) -> FilestoreResult<ValidationReport> {
+        let mut report = ValidationReport::default();
+        self.validate_proxies(defaults::persistence::PROXIES_FILE, fix, &mut report)?;
+        self.validate_sources(defaults::persistence::SOURCES_FILE, fix, &mut report)?;
+
+        let config_path = self.get_file_path(defaults::persistence::CONFIG_FILE, "toml");
+        if config_path.exists() {
+            report.checked.push(config_path.clone());
+            if let Err(e) = self.load_config(defaults::persistence::CONFIG_FILE) {
+                report.issues.push(DataIssue {
+                    file: config_path,
+                    entry: None,
+                    line: None,
+                    kind: DataIssueKind::Malformed,
+                    detail: e.to_string(),
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Check a proxies file and its journal, repairing both if asked to
+    fn validate_proxies(
+        &self,
+        name: &str,
+        fix: bool,
+        report: &mut ValidationReport,
+    ) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, "toml");
+        let journal_path = self.get_file_path(name, JOURNAL_EXTENSION);
+        let mut issues = Vec::new();
+        let mut proxies = Vec::new();
+
+        if file_path.exists() {
+            report.checked.push(file_path.clone());
+            let content = fs::read_to_string(&file_path)
+                .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+            match read_list::<Proxy>(&content, "proxies", &file_path) {
+                Ok(list) => {
+                    issues = list.issues;
+                    proxies = list.entries;
+                }
+                Err(e) => {
+                    // Nothing can be salvaged, so leave the file for the user
+                    report.issues.push(DataIssue {
+                        file: file_path,
+                        entry: None,
+                        line: None,
+                        kind: DataIssueKind::Malformed,
+                        detail: e.to_string(),
+                    });
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut seen: HashMap<ProxyId, usize> = HashMap::new();
+        proxies.retain(|(index, line, proxy)| {
+            let key = proxy.id();
+            if let Some(first) = seen.get(&key) {
+                issues.push(DataIssue {
+                    file: file_path.clone(),
+                    entry: Some(*index),
+                    line: Some(*line),
+                    kind: DataIssueKind::DuplicateKey,
+                    detail: format!("{key} is already stored as entry {first}"),
+                });
+                return false;
+            }
+            seen.insert(key, *index);
+            true
+        });
+        let mut proxies: Vec<Proxy> = proxies.into_iter().map(|(_, _, proxy)| proxy).collect();
+
+        if journal_path.exists() {
+            report.checked.push(journal_path.clone());
+            let content = fs::read_to_string(&journal_path)
+                .map_err(|e| FilestoreError::IoError(format!("Failed to read journal: {e:?}")))?;
+
+            // Journaled proxies replace stored ones, as when the journal is replayed
+            let mut index: HashMap<ProxyId, usize> = proxies
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (p.id(), i))
+                .collect();
+            for (line_number, line) in content.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let proxy = match read_journal_entry(line) {
+                    Ok((proxy, ignored)) => {
+                        if !ignored.is_empty() {
+                            issues.push(stale_issue(
+                                &journal_path,
+                                line_number,
+                                line_number + 1,
+                                &ignored,
+                            ));
+                        }
+                        proxy
+                    }
+                    Err((kind, detail)) => {
+                        issues.push(DataIssue {
+                            file: journal_path.clone(),
+                            entry: Some(line_number),
+                            line: Some(line_number + 1),
+                            kind,
+                            detail,
+                        });
+                        continue;
+                    }
+                };
+
+                let key = proxy.id();
+                if let Some(slot) = index.get(&key).and_then(|&i| proxies.get_mut(i)) {
+                    *slot = proxy;
+                } else {
+                    index.insert(key, proxies.len());
+                    proxies.push(proxy);
+                }
+            }
+        }
+
+        if fix && !issues.is_empty() {
+            // Saving the proxies also truncates the journal folded into them
+            self.save_proxies(&proxies, name)?;
+            report.repaired.push(file_path);
+            if journal_path.exists() {
+                report.repaired.push(journal_path);
+            }
+        }
+        report.issues.extend(issues);
+
+        Ok(())
+    }
+
+    /// Check a sources file, repairing it if asked to
+    fn validate_sources(
+        &self,
+        name: &str,
+        fix: bool,
+        report: &mut ValidationReport,
+    ) -> FilestoreResult<()> {
+        let file_path = self.get_file_path(name, "toml");
+        if !file_path.exists() {
+            return Ok(());
+        }
+
+        report.checked.push(file_path.clone());
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?;
+        let list = match read_list::<Source>(&content, "sources", &file_path) {
+            Ok(list) => list,
+            Err(e) => {
+                report.issues.push(DataIssue {
+                    file: file_path,
+                    entry: None,
+                    line: None,
+                    kind: DataIssueKind::Malformed,
+                    detail: e.to_string(),
+                });
+                return Ok(());
+            }
+        };
+
+        let mut issues = list.issues;
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        let mut sources = Vec::new();
+        for (index, line, mut source) in list.entries {
+            let problem = match source.validate() {
+                Err(SourceError::InvalidRegexPattern(e)) => Some((DataIssueKind::InvalidRegex, e)),
+                Err(e) => Some((DataIssueKind::InvalidSource, e.to_string())),
+                Ok(()) => seen.get(&source.url).map(|first| {
+                    (
+                        DataIssueKind::DuplicateKey,
+                        format!("{} is already stored as entry {first}", source.url),
+                    )
+                }),
+            };
+            if let Some((kind, detail)) = problem {
+                issues.push(DataIssue {
+                    file: file_path.clone(),
+                    entry: Some(index),
+                    line: Some(line),
+                    kind,
+                    detail,
+                });
+                continue;
+            }
+
+            source.compiled_regex = SerializableRegex::new(&source.regex_pattern).ok();
+            seen.insert(source.url.clone(), index);
+            sources.push(source);
+        }
+
+        if fix && !issues.is_empty() {
+            self.save_sources(&sources, name)?;
+            report.repaired.push(file_path);
+        }
+        report.issues.extend(issues);
 
         Ok(())
     }
@@ -529,6 +3316,63 @@ impl Filestore {
         &self.config
     }
 
+    /// Write a file atomically
+    ///
+    /// The content is written to a temporary file next to the target, flushed to
+    /// disk, and then renamed over the target, so readers never observe a
+    /// partially written file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path of the file to replace
+    /// * `content` - Content to write
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the temporary file cannot be written or renamed
+    fn write_atomic(file_path: &Path, content: &str) -> FilestoreResult<()> {
+        Self::write_atomic_with(file_path, |file| {
+            file.write_all(content.as_bytes())
+                .map_err(|e| write_error(&e))
+        })
+    }
+
+    /// Replace a file atomically with what a writer writes
+    ///
+    /// Like `write_atomic`, but the content is written as it's produced
+    /// rather than handed over whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - Path of the file to replace
+    /// * `write` - Writes the content to the temporary file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the writer fails, or the temporary file cannot be
+    /// written or renamed
+    fn write_atomic_with(
+        file_path: &Path,
+        write: impl FnOnce(&mut BufWriter<fs::File>) -> FilestoreResult<()>,
+    ) -> FilestoreResult<()> {
+        let mut tmp_name = file_path.as_os_str().to_owned();
+        tmp_name.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_name);
+
+        let write_tmp = || -> FilestoreResult<()> {
+            let mut file =
+                BufWriter::new(fs::File::create(&tmp_path).map_err(|e| write_error(&e))?);
+            write(&mut file)?;
+            let file = file.into_inner().map_err(|e| write_error(e.error()))?;
+            file.sync_all().map_err(|e| write_error(&e))?;
+            fs::rename(&tmp_path, file_path).map_err(|e| write_error(&e))
+        };
+
+        write_tmp().inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })
+    }
+
     /// Create a file path by joining the base directory with the name and extension
     ///
     /// # Arguments
@@ -543,3 +3387,177 @@ impl Filestore {
         self.base_dir.join(format!("{name}.{extension}"))
     }
 }
+
+/// The list under one key of a TOML file, with the span of every entry
+struct RawList {
+    /// Whether the file records when it was written
+    stamped: bool,
+
+    /// Entries of the list, if the file has one
+    entries: Option<Vec<toml::Spanned<toml::Value>>>,
+}
+
+/// Reads the list under a key of a TOML file, ignoring the other keys
+struct RawListSeed<'a> {
+    key: &'a str,
+}
+
+impl<'de> DeserializeSeed<'de> for RawListSeed<'_> {
+    type Value = RawList;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<RawList, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for RawListSeed<'_> {
+    type Value = RawList;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a table with a `{}` list", self.key)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawList, A::Error> {
+        let mut list = RawList {
+            stamped: false,
+            entries: None,
+        };
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.key {
+                list.entries = Some(map.next_value()?);
+            } else {
+                list.stamped |= key == "last_updated";
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(list)
+    }
+}
+
+/// Read the entries of a list file one at a time
+///
+/// Entries that can't be read are reported, with their position and the
+/// line they start on, instead of failing the file.
+///
+/// # Arguments
+///
+/// * `content` - Content of the file
+/// * `key` - Key of the list in the file
+/// * `file_path` - Path of the file, for the problems reported
+///
+/// # Errors
+///
+/// Returns an error if the content is not valid TOML, or `key` is not a list
+fn read_list<T: DeserializeOwned>(
+    content: &str,
+    key: &str,
+    file_path: &Path,
+) -> FilestoreResult<ListEntries<T>> {
+    let raw = RawListSeed { key }
+        .deserialize(toml::Deserializer::new(content))
+        .map_err(|e| FilestoreError::ParseError(format!("Failed to parse TOML: {e:?}")))?;
+
+    let mut list = ListEntries {
+        entries: Vec::new(),
+        issues: Vec::new(),
+    };
+    if !raw.stamped {
+        list.issues.push(DataIssue {
+            file: file_path.to_path_buf(),
+            entry: None,
+            line: None,
+            kind: DataIssueKind::StaleSchema,
+            detail: "missing field `last_updated`".to_string(),
+        });
+    }
+
+    for (index, value) in raw.entries.unwrap_or_default().into_iter().enumerate() {
+        let line = content
+            .get(..value.span().start)
+            .map_or(1, |before| before.matches('\n').count() + 1);
+        let value = value.into_inner();
+        let port_valid = value.get("port").is_none_or(|port| {
+            port.as_integer().is_some_and(|port| (1..=65535).contains(&port))
+        });
+        match read_entry::<T, _>(value, port_valid) {
+            Ok((entry, ignored)) => {
+                if !ignored.is_empty() {
+                    list.issues.push(stale_issue(file_path, index, line, &ignored));
+                }
+                list.entries.push((index, line, entry));
+            }
+            Err((kind, detail)) => list.issues.push(DataIssue {
+                file: file_path.to_path_buf(),
+                entry: Some(index),
+                line: Some(line),
+                kind,
+                detail,
+            }),
+        }
+    }
+
+    Ok(list)
+}
+
+/// Read a stored entry, collecting the fields the current format doesn't use
+///
+/// # Arguments
+///
+/// * `deserializer` - The stored entry
+/// * `port_valid` - Whether the entry's `port` field, if it has one, holds a usable port
+///
+/// # Errors
+///
+/// Returns the kind and details of the problem if the entry can't be read
+fn read_entry<'de, T, D>(
+    deserializer: D,
+    port_valid: bool,
+) -> Result<(T, Vec<String>), (DataIssueKind, String)>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let mut ignored = Vec::new();
+    serde_ignored::deserialize(deserializer, |path| ignored.push(path.to_string()))
+        .map(|entry| (entry, ignored))
+        .map_err(|e| {
+            let kind = if port_valid {
+                DataIssueKind::Malformed
+            } else {
+                DataIssueKind::InvalidPort
+            };
+            // Deserialization errors of TOML values span several lines
+            let detail = e.to_string().split_whitespace().collect::<Vec<_>>().join(" ");
+            (kind, detail)
+        })
+}
+
+/// Read a line of a proxy journal, collecting the fields the current format doesn't use
+///
+/// # Errors
+///
+/// Returns the kind and details of the problem if the line can't be read
+fn read_journal_entry(line: &str) -> Result<(Proxy, Vec<String>), (DataIssueKind, String)> {
+    let value = serde_json::from_str::<serde_json::Value>(line)
+        .map_err(|e| (DataIssueKind::Malformed, e.to_string()))?;
+    let port_valid = value.get("port").is_none_or(|port| {
+        port.as_i64().is_some_and(|port| (1..=65535).contains(&port))
+    });
+    read_entry(value, port_valid)
+}
+
+/// Wrap a failed write in a `FilestoreError`
+fn write_error(e: &std::io::Error) -> FilestoreError {
+    FilestoreError::IoError(format!("Failed to write file: {e:?}"))
+}
+
+/// Report an entry holding fields the current format doesn't use
+fn stale_issue(file_path: &Path, index: usize, line: usize, ignored: &[String]) -> DataIssue {
+    DataIssue {
+        file: file_path.to_path_buf(),
+        entry: Some(index),
+        line: Some(line),
+        kind: DataIssueKind::StaleSchema,
+        detail: format!("fields no longer used: {}", ignored.join(", ")),
+    }
+}
@@ -6,6 +6,18 @@
 //! ## Components
 //!
 //! * **Requestor** - A struct for making HTTP requests with or without proxy support
+//!   (`network` feature)
+//! * **`ProxyAuth`** - Credentials presented to an upstream proxy
+//! * **`RateLimiter`** - Paces requests to each host and backs off when one
+//!   asks to slow down (`network` feature)
+//! * **`RequestProfile`** - A named set of request headers, sent in order
+//! * **`HeadResponse`** - The status and headers of a HEAD request, for
+//!   freshness checks and reachability probes that don't need a body
+//!   (`network` feature)
+//!
+//! Requests gooty makes on its own behalf honor the system proxy environment
+//! variables, or go directly or through an egress proxy, see [`Egress`].
+//! Requests through a tested proxy never do.
 //!
 //! ## Examples
 //!
@@ -21,12 +33,630 @@
 //! }
 //! ```
 
+use crate::definitions::{defaults, enums::IdentityProfile, proxy::Proxy};
+#[cfg(feature = "network")]
 use crate::definitions::{
+    enums::{ProxyType, TlsProfile},
     errors::{RequestResult, RequestorError},
-    proxy::Proxy,
 };
-use reqwest::{Client, Proxy as ReqwestProxy};
-use std::time::{Duration, Instant};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+#[cfg(feature = "network")]
+use chrono::{DateTime, Utc};
+#[cfg(feature = "network")]
+use rand::seq::IndexedRandom;
+#[cfg(feature = "network")]
+use reqwest::{
+    Client, ClientBuilder, NoProxy, Proxy as ReqwestProxy, RequestBuilder, Response, StatusCode,
+    header::{self, HeaderMap, HeaderValue},
+};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "network")]
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// Messages emitted by the SOCKS5 connector when the username/password
+/// sub-negotiation is refused or unsupported by the proxy.
+#[cfg(feature = "network")]
+const SOCKS_AUTH_FAILURE_MARKERS: [&str; 3] = [
+    "credentials not accepted",
+    "does not support user/pass authentication",
+    "implements authentication incorrectly",
+];
+
+/// Messages emitted by the TLS backends when the handshake with the target
+/// fails, matched case-insensitively.
+#[cfg(feature = "network")]
+const TLS_FAILURE_MARKERS: [&str; 4] = ["certificate", "handshake", "ssl routines", "fatal alert"];
+
+/// Credentials presented to an upstream proxy.
+///
+/// HTTP and HTTPS proxies receive these through an explicit
+/// `Proxy-Authorization` header, while SOCKS5 proxies receive them during the
+/// username/password sub-negotiation. SOCKS4 has no password support, so
+/// credentials are never sent to SOCKS4 proxies.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::http::ProxyAuth;
+///
+/// let auth = ProxyAuth::Basic {
+///     username: "user".to_string(),
+///     password: "pass".to_string(),
+/// };
+/// assert_eq!(auth.header_value(), "Basic dXNlcjpwYXNz");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProxyAuth {
+    /// RFC 7617 Basic authentication.
+    Basic {
+        /// Username presented to the proxy
+        username: String,
+        /// Password presented to the proxy
+        password: String,
+    },
+}
+
+impl ProxyAuth {
+    /// Extracts credentials from a proxy, if it has any.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy whose credentials should be used
+    ///
+    /// # Returns
+    ///
+    /// `Some(ProxyAuth)` when both a username and password are set, `None` otherwise.
+    #[must_use]
+    pub fn from_proxy(proxy: &Proxy) -> Option<Self> {
+        match (&proxy.username, &proxy.password) {
+            (Some(username), Some(password)) => Some(ProxyAuth::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Renders the value of the `Proxy-Authorization` header for these credentials.
+    ///
+    /// # Returns
+    ///
+    /// The header value, including the scheme prefix.
+    #[must_use]
+    pub fn header_value(&self) -> String {
+        match self {
+            ProxyAuth::Basic { username, password } => {
+                format!("Basic {}", BASE64.encode(format!("{username}:{password}")))
+            }
+        }
+    }
+}
+
+/// A named set of headers requests are sent with.
+///
+/// Some proxies treat requests differently depending on the User-Agent and
+/// other headers. Judging with the profile that will later be used for
+/// scraping keeps the verdicts representative. Headers are sent in the order
+/// they are listed, after the User-Agent.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::http::RequestProfile;
+///
+/// let profile = RequestProfile::new("firefox", "Mozilla/5.0 (X11; Linux x86_64; rv:124.0)")
+///     .with_header("Accept-Language", "en-US,en;q=0.5")
+///     .with_header("Accept", "text/html");
+///
+/// assert_eq!(profile.name, "firefox");
+/// assert_eq!(profile.headers[0].0, "Accept-Language");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RequestProfile {
+    /// Name the profile is referred to by, such as in configuration
+    pub name: String,
+
+    /// User-Agent header value
+    pub user_agent: String,
+
+    /// Further headers as name and value pairs, in sending order
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+impl RequestProfile {
+    /// Creates a profile that only sets the User-Agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the profile
+    /// * `user_agent` - User-Agent header value
+    #[must_use]
+    pub fn new(name: &str, user_agent: &str) -> Self {
+        RequestProfile {
+            name: name.to_string(),
+            user_agent: user_agent.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Creates the built-in judgement profile of an identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - How requests identify themselves
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::IdentityProfile;
+    /// use gooty_proxy::io::http::RequestProfile;
+    ///
+    /// let declared = RequestProfile::for_identity(IdentityProfile::Declared);
+    /// assert!(declared.user_agent.contains("Gooty-Proxy"));
+    ///
+    /// let stealth = RequestProfile::for_identity(IdentityProfile::Stealth);
+    /// assert!(!stealth.user_agent.contains("Gooty"));
+    /// assert!(stealth.headers.iter().any(|(name, _)| name == "Accept-Language"));
+    /// ```
+    #[must_use]
+    pub fn for_identity(identity: IdentityProfile) -> Self {
+        match identity {
+            IdentityProfile::Declared => RequestProfile::new(
+                defaults::request_profile::JUDGE_NAME,
+                defaults::request_profile::JUDGE_USER_AGENT,
+            ),
+            IdentityProfile::Stealth => RequestProfile::new(
+                defaults::request_profile::STEALTH_NAME,
+                defaults::request_profile::STEALTH_USER_AGENT,
+            )
+            .with_header("Accept", defaults::request_profile::STEALTH_ACCEPT)
+            .with_header(
+                "Accept-Language",
+                defaults::request_profile::STEALTH_ACCEPT_LANGUAGE,
+            ),
+        }
+    }
+
+    /// Adds a header, sent after the ones added before it.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Header name
+    /// * `value` - Header value
+    ///
+    /// # Returns
+    ///
+    /// Self with the header added.
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Sets the profile's headers on a request.
+    #[cfg(feature = "network")]
+    fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        self.headers.iter().fold(
+            request.header(reqwest::header::USER_AGENT, &self.user_agent),
+            |request, (name, value)| request.header(name, value),
+        )
+    }
+}
+
+/// Builds the profile of a request with a User-Agent and further headers
+#[cfg(feature = "network")]
+fn custom_profile(user_agent: &str, headers: &[(&str, &str)]) -> RequestProfile {
+    headers.iter().fold(
+        RequestProfile::new("custom", user_agent),
+        |profile, (name, value)| profile.with_header(name, value),
+    )
+}
+
+/// Settings of the rate limiter requests gooty makes on its own behalf go
+/// through.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::http::RateLimitConfig;
+///
+/// let config: RateLimitConfig = toml::from_str("max_retries = 5").unwrap();
+/// assert_eq!(config.max_retries, 5);
+/// assert_eq!(config.burst, RateLimitConfig::default().burst);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimitConfig {
+    /// Requests sent to a host per second once its burst is spent, zero
+    /// for no limit
+    pub requests_per_sec: f64,
+
+    /// Requests sent to a host at once before the rate applies
+    pub burst: u32,
+
+    /// Times a request answered with 429 Too Many Requests is sent again
+    pub max_retries: u32,
+
+    /// Wait before the first retry in milliseconds when the host doesn't
+    /// send `Retry-After`, doubled on every further retry
+    pub backoff_ms: u64,
+
+    /// Longest wait before a retry in seconds, even if the host asks for
+    /// longer
+    pub max_backoff_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            requests_per_sec: defaults::rate_limit::REQUESTS_PER_SEC,
+            burst: defaults::rate_limit::BURST,
+            max_retries: defaults::rate_limit::MAX_RETRIES,
+            backoff_ms: defaults::rate_limit::BACKOFF_MS,
+            max_backoff_secs: defaults::rate_limit::MAX_BACKOFF_SECS,
+        }
+    }
+}
+
+/// Paces the requests sent to each host, and backs off when a host answers
+/// 429 Too Many Requests.
+///
+/// Every host gets a token bucket holding `burst` requests and refilled at
+/// `requests_per_sec`. A host answering 429 is paused for everyone sharing
+/// the limiter, for as long as its `Retry-After` says or an exponential
+/// backoff otherwise, and the request is sent again. Share a limiter through
+/// an `Arc` so bursts of lookups from many tasks are paced together.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::http::RateLimiter;
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+/// use tokio::net::TcpListener;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     // A server asking to slow down once
+///     let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+///     let url = format!("http://{}/json", server.local_addr().unwrap());
+///     tokio::spawn(async move {
+///         for answer in [
+///             "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 1\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+///             "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+///         ] {
+///             let (mut stream, _) = server.accept().await.unwrap();
+///             let mut request = Vec::new();
+///             while !request.ends_with(b"\r\n\r\n") {
+///                 request.push(stream.read_u8().await.unwrap());
+///             }
+///             stream.write_all(answer.as_bytes()).await.unwrap();
+///         }
+///     });
+///
+///     let limiter = RateLimiter::default();
+///     let client = reqwest::Client::builder().no_proxy().build().unwrap();
+///     let response = limiter.send(client.get(&url)).await.unwrap();
+///     assert_eq!(response.text().await.unwrap(), "ok");
+/// }
+/// ```
+#[cfg(feature = "network")]
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    /// How requests are paced and retried
+    config: RateLimitConfig,
+
+    /// Token buckets by host
+    hosts: Mutex<HashMap<String, HostBucket>>,
+}
+
+/// The requests a host may still be sent
+#[cfg(feature = "network")]
+#[derive(Debug)]
+struct HostBucket {
+    /// Requests that may be sent right away
+    tokens: f64,
+
+    /// When the tokens were last refilled
+    refilled_at: Instant,
+
+    /// Until when the host asked not to be sent requests
+    paused_until: Option<Instant>,
+}
+
+#[cfg(feature = "network")]
+impl RateLimiter {
+    /// Creates a rate limiter.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - How requests are paced and retried
+    #[must_use]
+    pub fn new(config: RateLimitConfig) -> Self {
+        RateLimiter {
+            config,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns how requests are paced and retried.
+    #[must_use]
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
+    /// Waits until a request may be sent to a host.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The host the request is sent to
+    pub async fn acquire(&self, host: &str) {
+        while let Some(wait) = self.try_acquire(host) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Stops sending requests to a host for a while.
+    ///
+    /// Requests waiting for the host are held back too, and the host's
+    /// burst starts empty once the pause is over.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The host that asked to slow down
+    /// * `delay` - How long to stop
+    pub fn pause(&self, host: &str, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut hosts = self.lock();
+        let bucket = hosts
+            .entry(host.to_string())
+            .or_insert_with(|| HostBucket::full(f64::from(self.config.burst), until));
+        let until = bucket
+            .paused_until
+            .map_or(until, |paused| paused.max(until));
+        bucket.paused_until = Some(until);
+        bucket.tokens = 0.0;
+        bucket.refilled_at = until;
+    }
+
+    /// Returns how long to wait before retrying a request answered with 429.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - Zero-based index of the retry
+    /// * `retry_after` - How long the host asked to wait, if it said
+    ///
+    /// # Returns
+    ///
+    /// The host's wait, or the backoff doubled for every earlier retry,
+    /// capped at `max_backoff_secs`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::io::http::{RateLimitConfig, RateLimiter};
+    /// use std::time::Duration;
+    ///
+    /// let limiter = RateLimiter::new(RateLimitConfig {
+    ///     backoff_ms: 500,
+    ///     max_backoff_secs: 60,
+    ///     ..RateLimitConfig::default()
+    /// });
+    /// assert_eq!(limiter.backoff(0, None), Duration::from_millis(500));
+    /// assert_eq!(limiter.backoff(2, None), Duration::from_secs(2));
+    ///
+    /// // The host's Retry-After wins, up to the longest wait
+    /// let asked = Some(Duration::from_secs(5));
+    /// assert_eq!(limiter.backoff(0, asked), Duration::from_secs(5));
+    /// let asked = Some(Duration::from_secs(3600));
+    /// assert_eq!(limiter.backoff(0, asked), Duration::from_secs(60));
+    /// ```
+    #[must_use]
+    pub fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let exponential = || {
+            Duration::from_millis(self.config.backoff_ms)
+                .saturating_mul(2_u32.saturating_pow(attempt))
+        };
+        retry_after
+            .unwrap_or_else(exponential)
+            .min(Duration::from_secs(self.config.max_backoff_secs))
+    }
+
+    /// Sends a request once its host may be sent one, retrying while the
+    /// host answers 429 Too Many Requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request to send
+    ///
+    /// # Returns
+    ///
+    /// The response, which is still 429 if the host kept asking to slow
+    /// down after `max_retries` retries
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request cannot be built or sent
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, reqwest::Error> {
+        let (client, request) = request.build_split();
+        let mut request = request?;
+        let host = request.url().host_str().unwrap_or_default().to_string();
+
+        let mut attempt = 0;
+        loop {
+            let retry = request.try_clone();
+            self.acquire(&host).await;
+            let response = client.execute(request).await?;
+
+            let Some(retry) = retry.filter(|_| {
+                response.status() == StatusCode::TOO_MANY_REQUESTS
+                    && attempt < self.config.max_retries
+            }) else {
+                return Ok(response);
+            };
+
+            let delay = self.backoff(attempt, retry_after(&response));
+            log::debug!("{host} asked to slow down, retrying in {delay:?}");
+            self.pause(&host, delay);
+            request = retry;
+            attempt += 1;
+        }
+    }
+
+    /// Takes a request from a host's bucket.
+    ///
+    /// # Returns
+    ///
+    /// None if the request may be sent, or how long to wait otherwise
+    fn try_acquire(&self, host: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let burst = f64::from(self.config.burst.max(1));
+        let rate = self.config.requests_per_sec;
+        let mut hosts = self.lock();
+        let bucket = hosts
+            .entry(host.to_string())
+            .or_insert_with(|| HostBucket::full(burst, now));
+
+        if let Some(until) = bucket.paused_until {
+            if until > now {
+                return Some(until - now);
+            }
+            bucket.paused_until = None;
+        }
+        if rate <= 0.0 {
+            return None;
+        }
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * rate).min(burst);
+        bucket.refilled_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+
+    /// Locks the buckets, recovering them if a holder panicked
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, HostBucket>> {
+        self.hosts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(feature = "network")]
+impl HostBucket {
+    /// A bucket holding a whole burst
+    fn full(burst: f64, now: Instant) -> Self {
+        HostBucket {
+            tokens: burst,
+            refilled_at: now,
+            paused_until: None,
+        }
+    }
+}
+
+/// Reads how long a response asks to wait from its `Retry-After` header,
+/// given in seconds or as an HTTP date.
+#[cfg(feature = "network")]
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    at.with_timezone(&Utc)
+        .signed_duration_since(Utc::now())
+        .to_std()
+        .ok()
+}
+
+/// The status and headers of a response to a HEAD request.
+///
+/// No body is downloaded, so checking whether a source changed or a target
+/// is reachable costs a single round trip.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::http::HeadResponse;
+/// use reqwest::{StatusCode, header::{HeaderMap, HeaderValue}};
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("Last-Modified", HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+/// headers.insert("ETag", HeaderValue::from_static("\"33a64df5\""));
+/// headers.insert("Content-Length", HeaderValue::from_static("5120"));
+///
+/// let head = HeadResponse { status: StatusCode::OK, headers };
+/// assert_eq!(head.last_modified().unwrap().to_rfc3339(), "2015-10-21T07:28:00+00:00");
+/// assert_eq!(head.etag(), Some("\"33a64df5\""));
+/// assert_eq!(head.content_length(), Some(5120));
+/// assert_eq!(head.header("content-type"), None);
+/// ```
+#[cfg(feature = "network")]
+#[derive(Debug, Clone)]
+pub struct HeadResponse {
+    /// Status code of the response
+    pub status: StatusCode,
+
+    /// Headers of the response
+    pub headers: HeaderMap,
+}
+
+#[cfg(feature = "network")]
+impl HeadResponse {
+    /// Gets a header value, if present and valid text.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Header name, matched case-insensitively
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    /// Gets the time the resource was last modified, from `Last-Modified`.
+    #[must_use]
+    pub fn last_modified(&self) -> Option<DateTime<Utc>> {
+        let value = self.header(header::LAST_MODIFIED.as_str())?;
+        DateTime::parse_from_rfc2822(value)
+            .ok()
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    /// Gets the entity tag of the resource, quotes included.
+    #[must_use]
+    pub fn etag(&self) -> Option<&str> {
+        self.header(header::ETAG.as_str())
+    }
+
+    /// Gets the size of the body a GET request would download.
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        self.header(header::CONTENT_LENGTH.as_str())?.parse().ok()
+    }
+}
+
+#[cfg(feature = "network")]
+impl From<&Response> for HeadResponse {
+    fn from(response: &Response) -> Self {
+        HeadResponse {
+            status: response.status(),
+            headers: response.headers().clone(),
+        }
+    }
+}
 
 /// Simple HTTP requestor with optional proxy support.
 ///
@@ -54,6 +684,7 @@ use std::time::{Duration, Instant};
 ///     Ok(())
 /// }
 /// ```
+#[cfg(feature = "network")]
 #[derive(Clone)]
 pub struct Requestor {
     /// The HTTP client for making requests
@@ -61,8 +692,18 @@ pub struct Requestor {
 
     /// Request timeout duration
     timeout: Duration,
+
+    /// TLS profiles handed out to proxies that have none pinned yet
+    tls_profiles: Vec<TlsProfile>,
+
+    /// Route direct requests take to the network
+    egress: Egress,
+
+    /// Paces direct requests to each host
+    rate_limiter: Arc<RateLimiter>,
 }
 
+#[cfg(feature = "network")]
 impl Requestor {
     /// Creates a new requestor with a default timeout of 30 seconds.
     ///
@@ -98,119 +739,522 @@ impl Requestor {
         Ok(Requestor {
             client,
             timeout: Duration::from_secs(timeout_secs),
+            tls_profiles: Vec::new(),
+            egress: Egress::System,
+            rate_limiter: Arc::new(RateLimiter::default()),
         })
     }
 
-    /// Makes a GET request to the specified URL with the provided user agent.
+    /// Sets the route direct requests take to the network.
     ///
-    /// This method makes a direct GET request without using a proxy.
+    /// Direct requests are those not made through a tested proxy, such as
+    /// source fetches. Requests through a tested proxy keep connecting to it
+    /// directly. By default, the system proxy environment variables are
+    /// honored.
     ///
     /// # Arguments
     ///
-    /// * `url` - The URL to request
-    /// * `user_agent` - The User-Agent header value to use
+    /// * `egress` - The route direct requests take
     ///
     /// # Returns
     ///
-    /// The response body as a String if successful.
+    /// Self with direct requests taking the route.
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// * The request fails to send
-    /// * The response has a non-success status code
-    /// * The response body cannot be read as text
-    /// * The request times out
-    pub async fn get(&self, url: &str, user_agent: &str) -> RequestResult<String> {
-        let start_time = Instant::now();
-
-        let response = self
-            .client
-            .get(url)
-            .header(reqwest::header::USER_AGENT, user_agent)
-            .send()
-            .await?;
+    /// Returns an error if the egress proxy is not a valid proxy URL or the
+    /// HTTP client cannot be created.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::io::http::{Egress, Requestor};
+    ///
+    /// let egress = Egress::Proxy("http://proxy.corp.example:3128".to_string());
+    /// let requestor = Requestor::new().unwrap().with_egress(egress.clone()).unwrap();
+    /// assert_eq!(requestor.egress(), &egress);
+    ///
+    /// let invalid = Egress::Proxy("not a url".to_string());
+    /// assert!(Requestor::new().unwrap().with_egress(invalid).is_err());
+    /// ```
+    pub fn with_egress(mut self, egress: Egress) -> Result<Self, RequestorError> {
+        self.client = egress
+            .apply(Client::builder().timeout(self.timeout))?
+            .build()?;
+        self.egress = egress;
+        Ok(self)
+    }
 
-        if start_time.elapsed() >= self.timeout {
-            return Err(RequestorError::Timeout(self.timeout.as_secs()));
-        }
+    /// Returns the route direct requests take to the network.
+    #[must_use]
+    pub fn egress(&self) -> &Egress {
+        &self.egress
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            return Err(RequestorError::StatusError(status, status.to_string()));
-        }
+    /// Sets the rate limiter pacing direct requests to each host.
+    ///
+    /// Direct requests answered with 429 Too Many Requests are retried
+    /// after a backoff. Requests through a tested proxy aren't paced.
+    ///
+    /// # Arguments
+    ///
+    /// * `limiter` - The limiter, possibly shared with other requestors
+    ///
+    /// # Returns
+    ///
+    /// Self with direct requests paced by the limiter.
+    #[must_use]
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = limiter;
+        self
+    }
 
-        let body = response.text().await?;
-        Ok(body)
+    /// Returns the rate limiter pacing direct requests.
+    #[must_use]
+    pub fn rate_limiter(&self) -> &Arc<RateLimiter> {
+        &self.rate_limiter
     }
 
-    /// Makes a GET request using a proxy.
+    /// Sets the TLS profiles handed out to proxies by [`Requestor::assign_tls_profile`].
     ///
-    /// This method creates a new client configured to use the specified proxy,
-    /// then makes a GET request through that proxy.
+    /// Profiles other than `TlsProfile::Native` only take effect when the crate is
+    /// built with the `tls-profiles` feature.
     ///
     /// # Arguments
     ///
-    /// * `url` - The URL to request
-    /// * `user_agent` - The User-Agent header value to use
-    /// * `proxy` - The proxy to use for the request
+    /// * `profiles` - The TLS profiles to choose from
     ///
     /// # Returns
     ///
-    /// The response body as a String if successful.
+    /// Self with the TLS profile set replaced.
     ///
-    /// # Errors
+    /// # Examples
     ///
-    /// Returns an error if:
-    /// * The proxy configuration is invalid
-    /// * The request fails to send
-    /// * The response has a non-success status code
-    /// * The response body cannot be read as text
-    /// * The request times out
-    /// * There's a proxy connection error
-    pub async fn get_with_proxy(
+    /// ```
+    /// use gooty_proxy::definitions::enums::TlsProfile;
+    /// use gooty_proxy::io::http::Requestor;
+    ///
+    /// let requestor = Requestor::new()
+    ///     .unwrap()
+    ///     .with_tls_profiles(&TlsProfile::CUSTOM);
+    /// assert_eq!(requestor.tls_profiles().len(), 3);
+    /// ```
+    #[must_use]
+    pub fn with_tls_profiles(mut self, profiles: &[TlsProfile]) -> Self {
+        self.tls_profiles = profiles.to_vec();
+        self
+    }
+
+    /// Returns the TLS profiles handed out to proxies.
+    #[must_use]
+    pub fn tls_profiles(&self) -> &[TlsProfile] {
+        &self.tls_profiles
+    }
+
+    /// Pins a TLS profile to a proxy for the lifetime of its sessions.
+    ///
+    /// A proxy that already has a profile keeps it. Otherwise one of the configured
+    /// profiles is picked at random and recorded on the proxy, so every later
+    /// request through it presents the same TLS fingerprint. Without configured
+    /// profiles nothing is recorded and `TlsProfile::Native` is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to pin a profile to
+    ///
+    /// # Returns
+    ///
+    /// The TLS profile the proxy will use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::TlsProfile;
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::io::http::Requestor;
+    ///
+    /// let requestor = Requestor::new()
+    ///     .unwrap()
+    ///     .with_tls_profiles(&[TlsProfile::Mobile]);
+    /// let mut proxy: Proxy = "http://10.0.0.1:8080".parse().unwrap();
+    /// assert_eq!(requestor.assign_tls_profile(&mut proxy), TlsProfile::Mobile);
+    /// assert_eq!(proxy.tls_profile, Some(TlsProfile::Mobile));
+    ///
+    /// // A pinned profile stays, whatever the requestor offers later
+    /// let requestor = requestor.with_tls_profiles(&[TlsProfile::Modern]);
+    /// assert_eq!(requestor.assign_tls_profile(&mut proxy), TlsProfile::Mobile);
+    /// ```
+    pub fn assign_tls_profile(&self, proxy: &mut Proxy) -> TlsProfile {
+        if let Some(profile) = proxy.tls_profile {
+            return profile;
+        }
+
+        match self.tls_profiles.choose(&mut rand::rng()) {
+            Some(&profile) => {
+                proxy.tls_profile = Some(profile);
+                profile
+            }
+            None => TlsProfile::Native,
+        }
+    }
+
+    /// Makes a GET request to the specified URL with the provided user agent.
+    ///
+    /// This method makes a direct GET request without using a proxy.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `user_agent` - The User-Agent header value to use
+    ///
+    /// # Returns
+    ///
+    /// The response body as a String if successful.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The request fails to send
+    /// * The response has a non-success status code
+    /// * The response body cannot be read as text
+    /// * The request times out
+    pub async fn get(&self, url: &str, user_agent: &str) -> RequestResult<String> {
+        self.get_with_profile(url, &RequestProfile::new("custom", user_agent))
+            .await
+    }
+
+    /// Makes a direct GET request with further headers.
+    ///
+    /// Some sources only answer requests with a `Referer` or `Origin`. The
+    /// headers are sent in the order given, after the User-Agent, and a
+    /// name given twice is sent twice.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `user_agent` - The User-Agent header value to use
+    /// * `headers` - Further headers as name and value pairs, in sending order
+    ///
+    /// # Returns
+    ///
+    /// The response body as a String if successful.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Requestor::get`], and an error if a
+    /// header is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::io::http::{Egress, Requestor};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // A server answering a single request with the headers it received
+    ///     let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let url = format!("http://{}/list.txt", server.local_addr().unwrap());
+    ///     tokio::spawn(async move {
+    ///         let (mut stream, _) = server.accept().await.unwrap();
+    ///         let mut request = Vec::new();
+    ///         while !request.ends_with(b"\r\n\r\n") {
+    ///             request.push(stream.read_u8().await.unwrap());
+    ///         }
+    ///         let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", request.len());
+    ///         stream.write_all(response.as_bytes()).await.unwrap();
+    ///         stream.write_all(&request).await.unwrap();
+    ///     });
+    ///
+    ///     let requestor = Requestor::new().unwrap().with_egress(Egress::Direct).unwrap();
+    ///     let headers = [("Referer", "https://example.com/"), ("Origin", "https://example.com")];
+    ///     let echoed = requestor.get_with_headers(&url, "Mozilla/5.0", &headers).await.unwrap();
+    ///
+    ///     let referer = echoed.find("referer: https://example.com/").unwrap();
+    ///     let origin = echoed.find("origin: https://example.com").unwrap();
+    ///     assert!(referer < origin);
+    /// }
+    /// ```
+    pub async fn get_with_headers(
         &self,
         url: &str,
         user_agent: &str,
-        proxy: &Proxy,
+        headers: &[(&str, &str)],
+    ) -> RequestResult<String> {
+        self.get_with_profile(url, &custom_profile(user_agent, headers))
+            .await
+    }
+
+    /// Makes a direct GET request with the headers of a profile.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `profile` - The headers to send
+    ///
+    /// # Returns
+    ///
+    /// The response body as a String if successful.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Requestor::get`], and an error if a
+    /// header of the profile is invalid.
+    pub async fn get_with_profile(
+        &self,
+        url: &str,
+        profile: &RequestProfile,
     ) -> RequestResult<String> {
-        // Build a client with the proxy configuration
-        let proxy_url = proxy.to_connection_string();
-        let mut proxy_builder = ReqwestProxy::all(&proxy_url)?;
+        let response = self
+            .rate_limiter
+            .send(profile.apply(self.client.get(url)))
+            .await?;
 
-        // Add authentication if provided
-        if let (Some(username), Some(password)) = (&proxy.username, &proxy.password) {
-            proxy_builder = proxy_builder.basic_auth(username, password);
+        let status = response.status();
+        if !status.is_success() {
+            return Err(RequestorError::StatusError(status, status.to_string()));
         }
 
-        // Build a new client with the proxy
-        let client = Client::builder()
-            .proxy(proxy_builder)
-            .timeout(self.timeout)
-            .build()?;
+        let body = response.text().await?;
+        Ok(body)
+    }
+
+    /// Makes a direct GET request for a body of raw bytes, up to a size limit.
+    ///
+    /// Unlike [`Requestor::get`], the body isn't decoded as text, so
+    /// compressed responses and archives arrive intact. The download stops as
+    /// soon as the body outgrows the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `user_agent` - The User-Agent header value to use
+    /// * `max_size` - Largest body accepted, in bytes
+    ///
+    /// # Returns
+    ///
+    /// The status and headers of the response, and its body.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Requestor::get`], and an error if the
+    /// body is larger than `max_size`.
+    pub async fn get_bytes(
+        &self,
+        url: &str,
+        user_agent: &str,
+        max_size: u64,
+    ) -> RequestResult<(HeadResponse, Vec<u8>)> {
+        self.get_bytes_with_profile(url, &RequestProfile::new("custom", user_agent), max_size)
+            .await
+    }
+
+    /// Makes a direct GET request for raw bytes with the headers of a profile.
+    ///
+    /// Behaves like [`Requestor::get_bytes`], but sends every header of the
+    /// profile instead of only a User-Agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `profile` - The headers to send
+    /// * `max_size` - Largest body accepted, in bytes
+    ///
+    /// # Returns
+    ///
+    /// The status and headers of the response, and its body.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Requestor::get_bytes`], and an error if a
+    /// header of the profile is invalid.
+    pub async fn get_bytes_with_profile(
+        &self,
+        url: &str,
+        profile: &RequestProfile,
+        max_size: u64,
+    ) -> RequestResult<(HeadResponse, Vec<u8>)> {
+        let mut response = self
+            .rate_limiter
+            .send(profile.apply(self.client.get(url)))
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(RequestorError::StatusError(status, status.to_string()));
+        }
+        if response.content_length().is_some_and(|len| len > max_size) {
+            return Err(RequestorError::ResponseTooLarge(max_size));
+        }
+
+        let head = HeadResponse::from(&response);
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            let size = u64::try_from(body.len() + chunk.len()).unwrap_or(u64::MAX);
+            if size > max_size {
+                return Err(RequestorError::ResponseTooLarge(max_size));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok((head, body))
+    }
+
+    /// Makes a GET request using a proxy.
+    ///
+    /// This method creates a new client configured to use the specified proxy,
+    /// then makes a GET request through that proxy. The shared client is never
+    /// mutated, so a single `Requestor` can safely serve many concurrent checks.
+    /// Proxy credentials are sent according to [`ProxyAuth`], and the TLS profile
+    /// pinned to the proxy, if any, is used for the connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `user_agent` - The User-Agent header value to use
+    /// * `proxy` - The proxy to use for the request
+    ///
+    /// # Returns
+    ///
+    /// The response body as a String if successful.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The proxy configuration is invalid
+    /// * The request fails to send
+    /// * The response has a non-success status code
+    /// * The response body cannot be read as text
+    /// * The request times out
+    /// * There's a proxy connection error
+    /// * The proxy rejects the supplied credentials
+    /// * The proxy's TLS profile cannot be built
+    pub async fn get_with_proxy(
+        &self,
+        url: &str,
+        user_agent: &str,
+        proxy: &Proxy,
+    ) -> RequestResult<String> {
+        self.get_with_proxy_timeout(url, user_agent, proxy, self.timeout)
+            .await
+    }
+
+    /// Makes a GET request using a proxy, with further headers.
+    ///
+    /// Behaves like [`Requestor::get_with_proxy`], but also sends the
+    /// headers, such as the ones a browser sends for checks that must look
+    /// like one. See [`Requestor::get_with_headers`] for their order.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `user_agent` - The User-Agent header value to use
+    /// * `headers` - Further headers as name and value pairs, in sending order
+    /// * `proxy` - The proxy to use for the request
+    ///
+    /// # Returns
+    ///
+    /// The response body as a String if successful.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Requestor::get_with_proxy`], and an error
+    /// if a header is invalid.
+    pub async fn get_with_proxy_headers(
+        &self,
+        url: &str,
+        user_agent: &str,
+        headers: &[(&str, &str)],
+        proxy: &Proxy,
+    ) -> RequestResult<String> {
+        self.get_with_proxy_profile(
+            url,
+            &custom_profile(user_agent, headers),
+            proxy,
+            self.timeout,
+        )
+        .await
+    }
+
+    /// Makes a GET request through a proxy with a timeout for this request only.
+    ///
+    /// Behaves like [`Requestor::get_with_proxy`], but waits at most `timeout`
+    /// instead of the requestor's own timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `user_agent` - The User-Agent header value to use
+    /// * `proxy` - The proxy to use for the request
+    /// * `timeout` - How long to wait for the response
+    ///
+    /// # Returns
+    ///
+    /// The response body as a String if successful.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Requestor::get_with_proxy`].
+    pub async fn get_with_proxy_timeout(
+        &self,
+        url: &str,
+        user_agent: &str,
+        proxy: &Proxy,
+        timeout: Duration,
+    ) -> RequestResult<String> {
+        self.get_with_proxy_profile(
+            url,
+            &RequestProfile::new("custom", user_agent),
+            proxy,
+            timeout,
+        )
+        .await
+    }
+
+    /// Makes a GET request through a proxy with the headers of a profile.
+    ///
+    /// Behaves like [`Requestor::get_with_proxy_timeout`], but sends every
+    /// header of the profile instead of only a User-Agent.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `profile` - The headers to send
+    /// * `proxy` - The proxy to use for the request
+    /// * `timeout` - How long to wait for the response
+    ///
+    /// # Returns
+    ///
+    /// The response body as a String if successful.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Requestor::get_with_proxy`], and an error
+    /// if a header of the profile is invalid.
+    pub async fn get_with_proxy_profile(
+        &self,
+        url: &str,
+        profile: &RequestProfile,
+        proxy: &Proxy,
+        timeout: Duration,
+    ) -> RequestResult<String> {
+        let client = Self::proxy_client(proxy, timeout)?;
 
         let start_time = Instant::now();
 
-        let response = client
-            .get(url)
-            .header(reqwest::header::USER_AGENT, user_agent)
+        let response = profile
+            .apply(client.get(url))
             .send()
             .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    RequestorError::Timeout(self.timeout.as_secs())
-                } else if e.is_connect() {
-                    RequestorError::ProxyError(e.to_string())
-                } else {
-                    RequestorError::RequestError(e)
-                }
-            })?;
+            .map_err(|e| proxy_send_error(e, timeout))?;
 
-        if start_time.elapsed() >= self.timeout {
-            return Err(RequestorError::Timeout(self.timeout.as_secs()));
+        if start_time.elapsed() >= timeout {
+            return Err(RequestorError::Timeout(timeout.as_secs()));
         }
 
         let status = response.status();
+        check_proxy_auth(status, proxy)?;
         if !status.is_success() {
             return Err(RequestorError::StatusError(status, status.to_string()));
         }
@@ -219,6 +1263,204 @@ impl Requestor {
         Ok(body)
     }
 
+    /// Makes a direct HEAD request, reading the status and headers only.
+    ///
+    /// Unlike the GET methods, any status the server answers with is
+    /// returned rather than turned into an error, so probes can tell a
+    /// missing resource from an unreachable server.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `user_agent` - The User-Agent header value to use
+    ///
+    /// # Returns
+    ///
+    /// The status and headers of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails to send or times out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::io::http::{Egress, Requestor};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // A server answering a single HEAD request
+    ///     let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let url = format!("http://{}/http.txt", server.local_addr().unwrap());
+    ///     tokio::spawn(async move {
+    ///         let (mut stream, _) = server.accept().await.unwrap();
+    ///         let mut request = Vec::new();
+    ///         while !request.ends_with(b"\r\n\r\n") {
+    ///             request.push(stream.read_u8().await.unwrap());
+    ///         }
+    ///         assert!(request.starts_with(b"HEAD /http.txt HTTP/1.1\r\n"));
+    ///         stream
+    ///             .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 9\r\n\r\n")
+    ///             .await
+    ///             .unwrap();
+    ///     });
+    ///
+    ///     let requestor = Requestor::new().unwrap().with_egress(Egress::Direct).unwrap();
+    ///     let head = requestor.head(&url, "Mozilla/5.0").await.unwrap();
+    ///     assert_eq!(head.status.as_u16(), 404);
+    ///     assert_eq!(head.content_length(), Some(9));
+    /// }
+    /// ```
+    pub async fn head(&self, url: &str, user_agent: &str) -> RequestResult<HeadResponse> {
+        let response = self
+            .rate_limiter
+            .send(self.client.head(url).header(header::USER_AGENT, user_agent))
+            .await?;
+
+        Ok(HeadResponse::from(&response))
+    }
+
+    /// Makes a HEAD request through a proxy, reading the status and headers only.
+    ///
+    /// Behaves like [`Requestor::head`], but connects through the proxy the
+    /// way [`Requestor::get_with_proxy`] does, with the requestor's timeout.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to request
+    /// * `user_agent` - The User-Agent header value to use
+    /// * `proxy` - The proxy to use for the request
+    ///
+    /// # Returns
+    ///
+    /// The status and headers of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Requestor::get_with_proxy`], except that
+    /// statuses other than 407 are returned rather than turned into errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::io::http::Requestor;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // An HTTP proxy answering a single HEAD request itself
+    ///     let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let port = upstream.local_addr().unwrap().port();
+    ///     tokio::spawn(async move {
+    ///         let (mut stream, _) = upstream.accept().await.unwrap();
+    ///         let mut request = Vec::new();
+    ///         while !request.ends_with(b"\r\n\r\n") {
+    ///             request.push(stream.read_u8().await.unwrap());
+    ///         }
+    ///         assert!(request.starts_with(b"HEAD http://example.com/ HTTP/1.1\r\n"));
+    ///         stream
+    ///             .write_all(b"HTTP/1.1 200 OK\r\nETag: \"v2\"\r\nContent-Length: 1256\r\n\r\n")
+    ///             .await
+    ///             .unwrap();
+    ///     });
+    ///
+    ///     let proxy = Proxy::new(
+    ///         ProxyType::Http,
+    ///         IpAddr::V4(Ipv4Addr::LOCALHOST),
+    ///         Port::new(port).unwrap(),
+    ///         AnonymityLevel::Elite,
+    ///     );
+    ///     let requestor = Requestor::new().unwrap();
+    ///     let head = requestor
+    ///         .head_with_proxy("http://example.com/", "Mozilla/5.0", &proxy)
+    ///         .await
+    ///         .unwrap();
+    ///     assert!(head.status.is_success());
+    ///     assert_eq!(head.etag(), Some("\"v2\""));
+    /// }
+    /// ```
+    pub async fn head_with_proxy(
+        &self,
+        url: &str,
+        user_agent: &str,
+        proxy: &Proxy,
+    ) -> RequestResult<HeadResponse> {
+        let client = Self::proxy_client(proxy, self.timeout)?;
+
+        let start_time = Instant::now();
+
+        let response = client
+            .head(url)
+            .header(header::USER_AGENT, user_agent)
+            .send()
+            .await
+            .map_err(|e| proxy_send_error(e, self.timeout))?;
+
+        if start_time.elapsed() >= self.timeout {
+            return Err(RequestorError::Timeout(self.timeout.as_secs()));
+        }
+
+        check_proxy_auth(response.status(), proxy)?;
+        Ok(HeadResponse::from(&response))
+    }
+
+    /// Builds a client connecting through a proxy with its pinned TLS profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proxy configuration or TLS profile is invalid,
+    /// or the client cannot be created.
+    fn proxy_client(proxy: &Proxy, timeout: Duration) -> RequestResult<Client> {
+        let builder = Client::builder()
+            .proxy(Self::build_proxy(proxy)?)
+            .timeout(timeout);
+        Ok(apply_tls_profile(builder, proxy.tls_profile.unwrap_or_default())?.build()?)
+    }
+
+    /// Builds the reqwest proxy configuration for a proxy.
+    ///
+    /// The proxy URL never carries credentials. HTTP and HTTPS proxies get an
+    /// explicit `Proxy-Authorization` header, SOCKS5 proxies get
+    /// username/password negotiation, and SOCKS4 proxies get no credentials.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proxy URL or authorization header is invalid.
+    fn build_proxy(proxy: &Proxy) -> RequestResult<ReqwestProxy> {
+        // SocketAddr formatting brackets IPv6 addresses as URLs require
+        let endpoint = proxy.socket_addr();
+        let scheme = match proxy.proxy_type {
+            ProxyType::Http => "http",
+            ProxyType::Https => "https",
+            ProxyType::Socks4 => "socks4",
+            ProxyType::Socks5 => "socks5",
+        };
+        let mut proxy_builder = ReqwestProxy::all(format!("{scheme}://{endpoint}"))?;
+
+        if let Some(auth) = ProxyAuth::from_proxy(proxy) {
+            match (proxy.proxy_type, &auth) {
+                (ProxyType::Http | ProxyType::Https, _) => {
+                    let header = HeaderValue::from_str(&auth.header_value())
+                        .map_err(|e| RequestorError::ProxyError(e.to_string()))?;
+                    proxy_builder = proxy_builder.custom_http_auth(header);
+                }
+                (ProxyType::Socks5, ProxyAuth::Basic { username, password }) => {
+                    proxy_builder = proxy_builder.basic_auth(username, password);
+                }
+                (ProxyType::Socks4, _) => {}
+            }
+        }
+
+        Ok(proxy_builder)
+    }
+
     /// Measures the latency to a URL in milliseconds.
     ///
     /// This method makes a lightweight HEAD request to the specified URL
@@ -245,3 +1487,234 @@ impl Requestor {
         Ok(elapsed.as_millis())
     }
 }
+
+/// Route requests gooty makes on its own behalf take to the network.
+///
+/// Requests through a tested proxy connect to it directly whatever the
+/// egress, since proxies can't be chained.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::http::Egress;
+///
+/// assert_eq!(Egress::default(), Egress::System);
+/// assert_eq!(Egress::from_config(None, false), Egress::Direct);
+/// assert_eq!(
+///     Egress::from_config(Some("http://proxy.corp.example:3128"), false).proxy_url(),
+///     Some("http://proxy.corp.example:3128")
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Egress {
+    /// Through the proxies named by the `HTTP_PROXY`, `HTTPS_PROXY`, and
+    /// `ALL_PROXY` environment variables, except for hosts in `NO_PROXY`,
+    /// or directly if none are set
+    #[default]
+    System,
+    /// Directly, whatever the environment says
+    Direct,
+    /// Through the given proxy URL, except for hosts in `NO_PROXY`
+    Proxy(String),
+}
+
+impl Egress {
+    /// Returns the egress a configuration asks for.
+    ///
+    /// A configured proxy is always used. Otherwise the system proxy
+    /// environment variables are honored unless `system_proxy` is off, which
+    /// keeps tests from depending on the environment.
+    ///
+    /// # Arguments
+    ///
+    /// * `egress_proxy` - The configured egress proxy URL, if any
+    /// * `system_proxy` - Whether the system proxy environment variables are honored
+    ///
+    /// # Returns
+    ///
+    /// The egress requests should take.
+    #[must_use]
+    pub fn from_config(egress_proxy: Option<&str>, system_proxy: bool) -> Self {
+        match egress_proxy.map(str::trim).filter(|url| !url.is_empty()) {
+            Some(url) => Egress::Proxy(url.to_string()),
+            None if system_proxy => Egress::System,
+            None => Egress::Direct,
+        }
+    }
+
+    /// Returns the URL of an explicitly set egress proxy.
+    #[must_use]
+    pub fn proxy_url(&self) -> Option<&str> {
+        match self {
+            Egress::Proxy(url) => Some(url),
+            Egress::System | Egress::Direct => None,
+        }
+    }
+
+    /// Applies the egress to a client builder.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the egress proxy is not a valid proxy URL.
+    #[cfg(feature = "network")]
+    fn apply(&self, builder: ClientBuilder) -> RequestResult<ClientBuilder> {
+        Ok(match self {
+            // Clients read the system proxy variables unless told otherwise
+            Egress::System => builder,
+            Egress::Direct => builder.no_proxy(),
+            Egress::Proxy(url) => {
+                builder.proxy(ReqwestProxy::all(url)?.no_proxy(NoProxy::from_env()))
+            }
+        })
+    }
+}
+
+/// Builds the HTTP client of a lookup service for an identity.
+///
+/// Under the declared identity the client sends no User-Agent, as lookups
+/// always have. Under the stealth identity it sends a browser User-Agent and
+/// `Accept-Language` with every request. Request-specific headers, such as the
+/// `Accept` header an API needs, are still set per request.
+///
+/// # Arguments
+///
+/// * `identity` - How requests identify themselves
+/// * `timeout` - Timeout of every request
+/// * `egress` - Route requests take to the network
+///
+/// # Returns
+///
+/// The client, or a default client if the configured one cannot be built.
+/// An invalid egress proxy is logged and left out.
+#[cfg(feature = "network")]
+#[must_use]
+pub fn lookup_client(identity: IdentityProfile, timeout: Duration, egress: &Egress) -> Client {
+    let builder = Client::builder().timeout(timeout);
+    let builder = match egress.apply(builder) {
+        Ok(builder) => builder,
+        Err(e) => {
+            log::warn!("Ignoring invalid egress proxy: {e}");
+            Client::builder().timeout(timeout)
+        }
+    };
+    let builder = match identity {
+        IdentityProfile::Declared => builder,
+        IdentityProfile::Stealth => {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(
+                reqwest::header::ACCEPT_LANGUAGE,
+                HeaderValue::from_static(defaults::request_profile::STEALTH_ACCEPT_LANGUAGE),
+            );
+            builder
+                .user_agent(defaults::request_profile::STEALTH_USER_AGENT)
+                .default_headers(headers)
+        }
+    };
+    builder.build().unwrap_or_else(|_| Client::new())
+}
+
+/// Classifies an error sending a request through a proxy.
+///
+/// Timeouts, refused credentials, and failed TLS handshakes get their own
+/// errors, and other connection failures are blamed on the proxy.
+#[cfg(feature = "network")]
+fn proxy_send_error(error: reqwest::Error, timeout: Duration) -> RequestorError {
+    if error.is_timeout() {
+        RequestorError::Timeout(timeout.as_secs())
+    } else if is_socks_auth_failure(&error) {
+        RequestorError::ProxyAuthenticationFailed(error.to_string())
+    } else if let Some(message) = tls_failure(&error) {
+        RequestorError::TlsHandshake(message)
+    } else if error.is_connect() {
+        RequestorError::ProxyError(error.to_string())
+    } else {
+        RequestorError::RequestError(error)
+    }
+}
+
+/// Fails a response through a proxy that asked for other credentials.
+///
+/// # Errors
+///
+/// Returns an error if the status is 407 Proxy Authentication Required.
+#[cfg(feature = "network")]
+fn check_proxy_auth(status: StatusCode, proxy: &Proxy) -> RequestResult<()> {
+    if status == StatusCode::PROXY_AUTHENTICATION_REQUIRED {
+        return Err(RequestorError::ProxyAuthenticationFailed(format!(
+            "{} rejected credentials with {status}",
+            proxy.address
+        )));
+    }
+    Ok(())
+}
+
+/// Checks whether a request error was caused by a SOCKS5 proxy refusing credentials.
+///
+/// The SOCKS connector only reports authentication problems through its error
+/// messages, so the source chain is inspected for the known failure texts.
+#[cfg(feature = "network")]
+fn is_socks_auth_failure(error: &reqwest::Error) -> bool {
+    let mut source: Option<&(dyn StdError + 'static)> = error.source();
+    while let Some(err) = source {
+        let message = err.to_string();
+        if SOCKS_AUTH_FAILURE_MARKERS
+            .iter()
+            .any(|marker| message.contains(marker))
+        {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Finds the TLS handshake failure behind a request error, if any.
+///
+/// # Returns
+///
+/// The message of the TLS backend's error.
+#[cfg(feature = "network")]
+fn tls_failure(error: &reqwest::Error) -> Option<String> {
+    let mut source: Option<&(dyn StdError + 'static)> = error.source();
+    while let Some(err) = source {
+        let message = err.to_string();
+        let lowercase = message.to_lowercase();
+        if TLS_FAILURE_MARKERS
+            .iter()
+            .any(|marker| lowercase.contains(marker))
+        {
+            return Some(message);
+        }
+        source = err.source();
+    }
+    None
+}
+
+/// Applies a TLS profile to a client builder.
+///
+/// # Errors
+///
+/// Returns an error if the profile's TLS configuration cannot be built.
+#[cfg(feature = "tls-profiles")]
+fn apply_tls_profile(builder: ClientBuilder, profile: TlsProfile) -> RequestResult<ClientBuilder> {
+    Ok(match super::tls::client_config(profile)? {
+        Some(config) => builder.use_preconfigured_tls(config),
+        None => builder,
+    })
+}
+
+/// Applies a TLS profile to a client builder.
+///
+/// Without the `tls-profiles` feature every profile falls back to the native backend.
+///
+/// # Errors
+///
+/// Never fails; the signature matches the feature-enabled variant.
+#[cfg(all(feature = "network", not(feature = "tls-profiles")))]
+#[allow(clippy::unnecessary_wraps)]
+fn apply_tls_profile(builder: ClientBuilder, profile: TlsProfile) -> RequestResult<ClientBuilder> {
+    if profile != TlsProfile::Native {
+        log::warn!("TLS profile {profile} requires the tls-profiles feature, using native TLS");
+    }
+    Ok(builder)
+}
@@ -0,0 +1,484 @@
+//! # Import Module
+//!
+//! This module reads proxies from the files other tools and lists hand
+//! over, the reverse of the export module.
+//!
+//! ## Components
+//!
+//! * **`detect_format`** - Guesses the format of a proxy file from its content
+//! * **`parse_proxies`** - Reads proxies from text in a given or detected format
+//! * **`read_proxies`** - Reads proxies from a file, or from standard input for `-`
+//!
+//! Four formats are read:
+//!
+//! * **List** - One `ip:port` per line, imported as HTTP proxies
+//! * **URLs** - One `scheme://[user:pass@]ip:port` per line
+//! * **JSON** - An array of connection strings, or of objects with `ip` and
+//!   `port` keys like the JSON export writes
+//! * **CSV** - A header row naming an `ip` and a `port` column, or a `url` column
+//!
+//! Lists may mix bare and scheme-prefixed entries, and ignore blank lines and
+//! lines starting with `#`. Entries that can't be read are skipped and
+//! reported rather than failing the whole file.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::enums::{ExportFormat, ProxyType};
+//! use gooty_proxy::io::import;
+//! use std::path::Path;
+//!
+//! let list = "# exported yesterday\n10.0.0.1:8080\nsocks5://10.0.0.2:1080\n10.0.0.3:99999\n";
+//! assert_eq!(import::detect_format(list), ExportFormat::List);
+//!
+//! let loaded = import::parse_proxies(list, None, Path::new("list.txt")).unwrap();
+//! assert_eq!(loaded.entries.len(), 2);
+//! assert_eq!(loaded.entries[0].proxy_type, ProxyType::Http);
+//! assert_eq!(loaded.entries[1].proxy_type, ProxyType::Socks5);
+//! assert_eq!(loaded.skipped[0].line, Some(4));
+//! ```
+
+use crate::definitions::{
+    enums::{AnonymityLevel, DataIssueKind, ExportFormat, ProxyType},
+    errors::{FilestoreError, FilestoreResult},
+    proxy::Proxy,
+    types::Port,
+};
+use crate::io::filesystem::{DataIssue, PartialLoad};
+use serde_json::Value;
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+/// Guesses the format of a proxy file from its content
+///
+/// Text starting with `[` or `{` is JSON. Otherwise the first entry decides:
+/// a line with commas is a CSV header, a line with `://` is a URL, and
+/// anything else is an `ip:port` list.
+///
+/// # Arguments
+///
+/// * `text` - The content of the file
+///
+/// # Returns
+///
+/// The format the content appears to be in
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::ExportFormat;
+/// use gooty_proxy::io::import::detect_format;
+///
+/// assert_eq!(detect_format("[\"http://10.0.0.1:8080\"]"), ExportFormat::Json);
+/// assert_eq!(detect_format("ip,port\n10.0.0.1,8080\n"), ExportFormat::Csv);
+/// assert_eq!(detect_format("http://10.0.0.1:8080\n"), ExportFormat::Urls);
+/// assert_eq!(detect_format("10.0.0.1:8080\n"), ExportFormat::List);
+/// ```
+#[must_use]
+pub fn detect_format(text: &str) -> ExportFormat {
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with(['[', '{']) {
+        return ExportFormat::Json;
+    }
+
+    let first = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .unwrap_or_default();
+    if first.contains(',') {
+        ExportFormat::Csv
+    } else if first.contains("://") {
+        ExportFormat::Urls
+    } else {
+        ExportFormat::List
+    }
+}
+
+/// Reads proxies from text in a given or detected format
+///
+/// Entries that can't be read are skipped and listed with their line, or
+/// their position for JSON. The proxies are returned in the order of the
+/// text, duplicates included.
+///
+/// # Arguments
+///
+/// * `text` - The content to read
+/// * `format` - The format of the content, or `None` to detect it
+/// * `file` - The file the content came from, named in skipped entries
+///
+/// # Returns
+///
+/// The proxies read, along with the entries skipped
+///
+/// # Errors
+///
+/// Returns an error if:
+/// * JSON content isn't valid JSON or holds no array of proxies
+/// * CSV content has no `ip` and `port` or `url` columns
+/// * Parquet is given, which can't be imported
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{ExportFormat, ProxyType};
+/// use gooty_proxy::io::import::parse_proxies;
+/// use std::path::Path;
+///
+/// let file = Path::new("proxies");
+/// let csv = "ip,port,proxy_type,country\n10.0.0.1,1080,SOCKS5,DE\n10.0.0.2,80,,\n";
+/// let loaded = parse_proxies(csv, Some(ExportFormat::Csv), file).unwrap();
+/// assert_eq!(loaded.entries[0].proxy_type, ProxyType::Socks5);
+/// assert_eq!(loaded.entries[0].country.as_deref(), Some("DE"));
+/// assert_eq!(loaded.entries[1].proxy_type, ProxyType::Http);
+///
+/// let json = r#"[{"ip": "10.0.0.1", "port": 3128}, "socks4://10.0.0.3:4145", {"ip": "nowhere"}]"#;
+/// let loaded = parse_proxies(json, None, file).unwrap();
+/// assert_eq!(loaded.entries.len(), 2);
+/// assert_eq!(loaded.entries[1].proxy_type, ProxyType::Socks4);
+/// assert_eq!(loaded.skipped[0].entry, Some(2));
+///
+/// assert!(parse_proxies("name,value\na,b\n", None, file).is_err());
+/// ```
+pub fn parse_proxies(
+    text: &str,
+    format: Option<ExportFormat>,
+    file: &Path,
+) -> FilestoreResult<PartialLoad<Proxy>> {
+    let text = text.trim_start_matches('\u{feff}');
+    let mut loaded = PartialLoad {
+        entries: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut skip = |entry: Option<usize>, line: Option<usize>, (kind, detail)| {
+        loaded.skipped.push(DataIssue {
+            file: file.to_path_buf(),
+            entry,
+            line,
+            kind,
+            detail,
+        });
+    };
+    let mut proxies = Vec::new();
+
+    match format.unwrap_or_else(|| detect_format(text)) {
+        ExportFormat::List | ExportFormat::Urls => {
+            for (index, line) in text.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                // Lists often carry a comment or a country after the entry
+                let entry = line.split_whitespace().next().unwrap_or(line);
+                match parse_entry(entry) {
+                    Ok(proxy) => proxies.push(proxy),
+                    Err(issue) => skip(None, Some(index + 1), issue),
+                }
+            }
+        }
+        ExportFormat::Json => {
+            let value: Value = serde_json::from_str(text)?;
+            let entries = match value {
+                Value::Array(entries) => entries,
+                Value::Object(mut pool) => match pool.remove("proxies") {
+                    Some(Value::Array(entries)) => entries,
+                    _ => return Err(no_proxies("JSON object without a `proxies` array")),
+                },
+                _ => return Err(no_proxies("JSON that is neither an array nor an object")),
+            };
+            for (index, entry) in entries.into_iter().enumerate() {
+                match parse_value(entry) {
+                    Ok(proxy) => proxies.push(proxy),
+                    Err(issue) => skip(Some(index), None, issue),
+                }
+            }
+        }
+        ExportFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new()
+                .flexible(true)
+                .trim(csv::Trim::All)
+                .from_reader(text.as_bytes());
+            let headers: Vec<String> = reader
+                .headers()
+                .map_err(|e| FilestoreError::ParseError(format!("Invalid CSV header: {e}")))?
+                .iter()
+                .map(str::to_string)
+                .collect();
+            let columns = CsvColumns::find(&headers)?;
+
+            for (index, record) in reader.records().enumerate() {
+                let line = record
+                    .as_ref()
+                    .ok()
+                    .and_then(|record| record.position())
+                    .map(|position| usize::try_from(position.line()).unwrap_or(usize::MAX));
+                let parsed = record
+                    .map_err(|e| (DataIssueKind::Malformed, e.to_string()))
+                    .and_then(|record| columns.parse(&record));
+                match parsed {
+                    Ok(proxy) => proxies.push(proxy),
+                    Err(issue) => skip(Some(index), line, issue),
+                }
+            }
+        }
+        ExportFormat::Parquet => {
+            return Err(FilestoreError::ParseError(
+                "Parquet files can't be imported".to_string(),
+            ));
+        }
+    }
+
+    loaded.entries = proxies;
+    Ok(loaded)
+}
+
+/// Reads proxies from a file, or from standard input for `-`
+///
+/// See [`parse_proxies`] for the formats read.
+///
+/// # Arguments
+///
+/// * `path` - The file to read, or `-` for standard input
+/// * `format` - The format of the file, or `None` to detect it
+///
+/// # Returns
+///
+/// The proxies read, along with the entries skipped
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, or as [`parse_proxies`] does.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::io::import;
+///
+/// let path = std::env::temp_dir().join("gooty_import_example.txt");
+/// std::fs::write(&path, "10.0.0.1:8080\n10.0.0.2:3128\n").unwrap();
+/// assert_eq!(import::read_proxies(&path, None).unwrap().entries.len(), 2);
+///
+/// assert!(import::read_proxies(&path.with_extension("missing"), None).is_err());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn read_proxies(
+    path: &Path,
+    format: Option<ExportFormat>,
+) -> FilestoreResult<PartialLoad<Proxy>> {
+    let text = if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map_err(|e| {
+            FilestoreError::IoError(format!("Failed to read standard input: {e:?}"))
+        })?;
+        text
+    } else if path.exists() {
+        std::fs::read_to_string(path)
+            .map_err(|e| FilestoreError::IoError(format!("Failed to read file: {e:?}")))?
+    } else {
+        return Err(FilestoreError::FileNotFound(
+            path.to_string_lossy().to_string(),
+        ));
+    };
+
+    // Standard input is named `-` in skipped entries too, as on the command line
+    parse_proxies(&text, format, &PathBuf::from(path))
+}
+
+/// Why an entry was skipped
+type Skipped = (DataIssueKind, String);
+
+/// Error for a JSON file that holds no proxies at all
+fn no_proxies(what: &str) -> FilestoreError {
+    FilestoreError::ParseError(format!("Expected an array of proxies, found {what}"))
+}
+
+/// Reads a list entry, a connection string or a bare `ip:port`
+fn parse_entry(entry: &str) -> Result<Proxy, Skipped> {
+    if entry.contains("://") {
+        return entry.parse::<Proxy>().map_err(|e| {
+            let kind = if e.contains("port") {
+                DataIssueKind::InvalidPort
+            } else {
+                DataIssueKind::Malformed
+            };
+            (kind, e)
+        });
+    }
+
+    let address = entry
+        .parse::<SocketAddr>()
+        .or_else(|_| {
+            // IPv6 addresses are often listed without brackets
+            entry
+                .rsplit_once(':')
+                .and_then(|(ip, port)| Some(SocketAddr::new(ip.parse().ok()?, port.parse().ok()?)))
+                .ok_or(())
+        })
+        .map_err(|()| {
+            let kind = match entry.rsplit_once(':') {
+                Some((_, port)) if port.parse::<u64>().is_ok() => DataIssueKind::InvalidPort,
+                _ => DataIssueKind::Malformed,
+            };
+            (kind, format!("Invalid entry {entry}, expected ip:port"))
+        })?;
+    new_proxy(ProxyType::Http, address.ip(), &address.port().to_string())
+}
+
+/// Reads a JSON entry, a connection string or an object
+fn parse_value(value: Value) -> Result<Proxy, Skipped> {
+    match value {
+        Value::String(entry) => parse_entry(entry.trim()),
+        Value::Object(ref fields) => {
+            // A proxy as the pool stores it
+            if fields.contains_key("address")
+                && let Ok(proxy) = serde_json::from_value::<Proxy>(value.clone())
+            {
+                return Ok(proxy);
+            }
+
+            let field = |names: &[&str]| {
+                names
+                    .iter()
+                    .find_map(|name| fields.get(*name))
+                    .and_then(|value| match value {
+                        Value::String(text) => Some(text.trim().to_string()),
+                        Value::Number(number) => Some(number.to_string()),
+                        _ => None,
+                    })
+                    .filter(|text| !text.is_empty())
+            };
+            parse_fields(
+                field(URL_NAMES).as_deref(),
+                field(IP_NAMES).as_deref(),
+                field(PORT_NAMES).as_deref(),
+                field(TYPE_NAMES).as_deref(),
+                field(COUNTRY_NAMES),
+            )
+        }
+        other => Err((
+            DataIssueKind::Malformed,
+            format!("Expected a connection string or an object, found {other}"),
+        )),
+    }
+}
+
+/// Names of the columns and keys holding a connection string
+const URL_NAMES: &[&str] = &["url", "proxy", "connection_string"];
+/// Names of the columns and keys holding the address
+const IP_NAMES: &[&str] = &["ip", "address", "host"];
+/// Names of the columns and keys holding the port
+const PORT_NAMES: &[&str] = &["port"];
+/// Names of the columns and keys holding the protocol
+const TYPE_NAMES: &[&str] = &["proxy_type", "type", "protocol", "scheme"];
+/// Names of the columns and keys holding the country code
+const COUNTRY_NAMES: &[&str] = &["country", "country_code"];
+
+/// Positions of the columns of a CSV file that imports read
+struct CsvColumns {
+    url: Option<usize>,
+    ip: Option<usize>,
+    port: Option<usize>,
+    proxy_type: Option<usize>,
+    country: Option<usize>,
+}
+
+impl CsvColumns {
+    /// Finds the columns by their headers, ignoring case
+    fn find(headers: &[String]) -> FilestoreResult<Self> {
+        let position = |names: &[&str]| {
+            headers
+                .iter()
+                .position(|header| names.contains(&header.to_lowercase().as_str()))
+        };
+        let columns = CsvColumns {
+            url: position(URL_NAMES),
+            ip: position(IP_NAMES),
+            port: position(PORT_NAMES),
+            proxy_type: position(TYPE_NAMES),
+            country: position(COUNTRY_NAMES),
+        };
+
+        if columns.url.is_none() && (columns.ip.is_none() || columns.port.is_none()) {
+            return Err(FilestoreError::ParseError(format!(
+                "CSV header {} names neither `ip` and `port` nor `url` columns",
+                headers.join(",")
+            )));
+        }
+        Ok(columns)
+    }
+
+    /// Reads a proxy from a record
+    fn parse(&self, record: &csv::StringRecord) -> Result<Proxy, Skipped> {
+        let cell = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .filter(|cell| !cell.is_empty())
+        };
+        parse_fields(
+            cell(self.url),
+            cell(self.ip),
+            cell(self.port),
+            cell(self.proxy_type),
+            cell(self.country).map(str::to_string),
+        )
+    }
+}
+
+/// Builds a proxy from the fields of a JSON object or CSV record
+///
+/// A connection string wins over separate fields. Proxies without a
+/// protocol are HTTP proxies.
+fn parse_fields(
+    url: Option<&str>,
+    ip: Option<&str>,
+    port: Option<&str>,
+    proxy_type: Option<&str>,
+    country: Option<String>,
+) -> Result<Proxy, Skipped> {
+    let mut proxy = match (url, ip, port) {
+        (Some(url), _, _) => parse_entry(url)?,
+        (None, Some(ip), Some(port)) => {
+            let address = ip
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .map_err(|_| {
+                    (
+                        DataIssueKind::Malformed,
+                        format!("Invalid IP address: {ip}"),
+                    )
+                })?;
+            let proxy_type = proxy_type
+                .map(str::parse::<ProxyType>)
+                .transpose()
+                .map_err(|e| (DataIssueKind::Malformed, e))?
+                .unwrap_or(ProxyType::Http);
+            new_proxy(proxy_type, address, port)?
+        }
+        _ => {
+            return Err((
+                DataIssueKind::Malformed,
+                "Entry has neither an address and a port nor a URL".to_string(),
+            ));
+        }
+    };
+
+    if proxy.country.is_none() {
+        proxy.country = country;
+    }
+    Ok(proxy)
+}
+
+/// Creates a proxy awaiting judgement, validating its port
+fn new_proxy(proxy_type: ProxyType, address: IpAddr, port: &str) -> Result<Proxy, Skipped> {
+    let port = port
+        .parse::<Port>()
+        .map_err(|_| (DataIssueKind::InvalidPort, format!("Invalid port: {port}")))?;
+    Ok(Proxy::new(
+        proxy_type,
+        address,
+        port,
+        AnonymityLevel::Anonymous,
+    ))
+}
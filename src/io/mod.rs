@@ -5,12 +5,42 @@
 //!
 //! ## Components
 //!
+//! * **archive** - Extracts proxy lists from compressed responses and zip archives (`network` feature)
+//! * **bundle** - Reads, writes and signs bundles of sources shared between gatherers
+//!   (`bundle` feature)
+//! * **export** - Flattens proxy metadata into CSV, JSON or Parquet files for analytics tools,
+//!   and lists proxies for other tools (`export` feature)
+//! * **import** - Reads proxies from plain lists, JSON and CSV files other tools hand over
+//!   (`export` feature)
 //! * **filestore** - Manages persistent storage of proxies, sources, and configuration
-//! * **requestor** - Handles HTTP requests with proxy support and error handling
+//! * **requestor** - Handles HTTP requests with proxy support, error handling, and request
+//!   profiles, sending them only with the `network` feature
+//! * **serving** - Reads the requests sent to the HTTP APIs gooty serves
+//! * **socks** - Speaks SOCKS5 to clients and the proxy protocols to upstream proxies
+//! * **tls** - Builds client TLS configurations for fingerprint profiles (`tls-profiles` feature)
+//! * **wayback** - Harvests proxies from archived snapshots of sources (`wayback` feature)
 
+#[cfg(feature = "network")]
+pub mod archive;
+#[cfg(feature = "bundle")]
+pub mod bundle;
+#[cfg(feature = "export")]
+pub mod export;
 pub mod filesystem;
 pub mod http;
+#[cfg(feature = "export")]
+pub mod import;
+pub(crate) mod serving;
+pub mod socks;
+#[cfg(feature = "tls-profiles")]
+mod tls;
+#[cfg(feature = "wayback")]
+pub mod wayback;
 
 // Re-exports from modules
-pub use filesystem::{AppConfig, Filestore, FilestoreConfig};
+pub use filesystem::{
+    AppConfig, Filestore, FilestoreConfig, JobCheckpoint, ManagerState, ProxyJournal,
+};
+#[cfg(feature = "network")]
 pub use http::Requestor;
+pub use http::{ProxyAuth, RequestProfile};
@@ -0,0 +1,134 @@
+//! # Serving Module
+//!
+//! Reads the requests sent to the small HTTP APIs gooty serves, the event
+//! stream, the coordinator of distributed judging, and the local judge. All
+//! of them answer one request per connection, so only the request head and a body announced
+//! by `Content-Length` are understood.
+
+use crate::definitions::errors::{ListenerError, ListenerResult};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Largest request head a client may send
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// The head of a request sent to one of the APIs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct RequestHead {
+    /// The request method
+    pub method: String,
+
+    /// The request path, without its query
+    pub path: String,
+
+    /// The header fields, in the order sent
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    /// Get the value of a header field, matching its name case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get the header fields, in the order sent
+    #[cfg(feature = "judge")]
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Get the length of the body the request announces, none without one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `Content-Length` isn't a number
+    pub fn content_length(&self) -> ListenerResult<usize> {
+        self.header("content-length")
+            .map(str::parse::<usize>)
+            .transpose()
+            .map_err(|_| ListenerError::HandshakeError("invalid Content-Length".to_string()))
+            .map(Option::unwrap_or_default)
+    }
+}
+
+/// Read a request head up to its blank line
+///
+/// # Errors
+///
+/// Returns a handshake error if the head grows too large, or an I/O error
+/// if the client goes away first
+pub(crate) async fn read_request_head(
+    client: &mut (impl AsyncRead + Unpin),
+) -> ListenerResult<RequestHead> {
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") && !head.ends_with(b"\n\n") {
+        if head.len() >= MAX_REQUEST_HEAD {
+            return Err(ListenerError::HandshakeError(
+                "request head too large".to_string(),
+            ));
+        }
+        head.push(client.read_u8().await?);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut lines = head.lines();
+    let mut parts = lines.next().unwrap_or_default().split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    Ok(RequestHead {
+        method,
+        path,
+        headers,
+    })
+}
+
+/// Read the body a request head announces
+///
+/// # Arguments
+///
+/// * `client` - The client, read past the request head
+/// * `head` - The request head
+/// * `max_body` - Largest body accepted, in bytes
+///
+/// # Errors
+///
+/// Returns a handshake error if the body is announced wrongly or too large,
+/// or an I/O error if the client goes away first
+pub(crate) async fn read_request_body(
+    client: &mut (impl AsyncRead + Unpin),
+    head: &RequestHead,
+    max_body: usize,
+) -> ListenerResult<Vec<u8>> {
+    let length = head.content_length()?;
+    if length > max_body {
+        return Err(ListenerError::HandshakeError(format!(
+            "request body of {length} bytes too large"
+        )));
+    }
+
+    let mut body = vec![0; length];
+    client.read_exact(&mut body).await?;
+    Ok(body)
+}
+
+/// Compare a token sent by a client with the expected one
+///
+/// Takes the same time however much of the token matches, so the token
+/// can't be guessed a byte at a time.
+pub(crate) fn token_matches(sent: &str, expected: &str) -> bool {
+    let (sent, expected) = (sent.as_bytes(), expected.as_bytes());
+    sent.len() == expected.len()
+        && sent
+            .iter()
+            .zip(expected)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
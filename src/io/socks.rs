@@ -0,0 +1,689 @@
+//! # SOCKS Module
+//!
+//! This module speaks the proxy protocols needed to relay a client connection
+//! through a proxy of the pool.
+//!
+//! ## Components
+//!
+//! * **`accept`** - Performs the server side of a SOCKS5 handshake
+//! * **`send_reply`** - Answers a SOCKS5 request
+//! * **`connect_through`** - Opens a tunnel to a target through an upstream proxy
+//! * **`Tunnel`** - The connection to an upstream proxy, relaying to the target
+//! * **`TargetAddr`** - The destination a client asked for
+//! * **`SocksReply`** - The outcome reported to a client
+//!
+//! Inbound, only the CONNECT command without authentication is supported.
+//! Upstream, HTTP and HTTPS proxies are asked for a tunnel with the CONNECT
+//! method, HTTPS proxies over TLS as when requests go through them, SOCKS4 proxies with the 4a extension for domain names, and SOCKS5 proxies
+//! with the username/password sub-negotiation when the proxy has credentials.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::io::socks::{self, TargetAddr};
+//! use tokio::io::AsyncWriteExt;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let (mut client, mut server) = tokio::io::duplex(64);
+//!
+//!     // No authentication, then CONNECT example.com:443
+//!     client.write_all(&[5, 1, 0]).await.unwrap();
+//!     client.write_all(&[5, 1, 0, 3, 11]).await.unwrap();
+//!     client.write_all(b"example.com").await.unwrap();
+//!     client.write_all(&443_u16.to_be_bytes()).await.unwrap();
+//!
+//!     let target = socks::accept(&mut server).await.unwrap();
+//!     assert_eq!(target, TargetAddr::Domain("example.com".to_string(), 443));
+//!     assert_eq!(target.to_string(), "example.com:443");
+//! }
+//! ```
+
+use crate::definitions::{
+    enums::ProxyType,
+    errors::{ListenerError, ListenerResult},
+    proxy::Proxy,
+};
+use crate::io::http::ProxyAuth;
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::TcpStream;
+
+/// Version byte of SOCKS5 messages
+const SOCKS5_VERSION: u8 = 5;
+
+/// Version byte of SOCKS4 requests
+const SOCKS4_VERSION: u8 = 4;
+
+/// SOCKS5 method without authentication
+const METHOD_NONE: u8 = 0x00;
+
+/// SOCKS5 username/password method
+const METHOD_PASSWORD: u8 = 0x02;
+
+/// SOCKS5 answer when no offered method is acceptable
+const METHOD_UNACCEPTABLE: u8 = 0xFF;
+
+/// The CONNECT command, in both SOCKS versions
+const COMMAND_CONNECT: u8 = 0x01;
+
+/// SOCKS5 address type of IPv4 addresses
+const ADDRESS_IPV4: u8 = 0x01;
+
+/// SOCKS5 address type of domain names
+const ADDRESS_DOMAIN: u8 = 0x03;
+
+/// SOCKS5 address type of IPv6 addresses
+const ADDRESS_IPV6: u8 = 0x04;
+
+/// SOCKS4 reply code of a granted request
+const SOCKS4_GRANTED: u8 = 0x5A;
+
+/// Longest response header accepted from an HTTP proxy, in bytes
+const MAX_HTTP_HEADER_BYTES: usize = 8 * 1024;
+
+/// The destination a client asked to be connected to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TargetAddr {
+    /// An IP address and port
+    Ip(SocketAddr),
+
+    /// A domain name and port, resolved by the upstream proxy
+    Domain(String, u16),
+}
+
+impl TargetAddr {
+    /// Get the host of the target, without the port
+    #[must_use]
+    pub fn host(&self) -> String {
+        match self {
+            TargetAddr::Ip(addr) => addr.ip().to_string(),
+            TargetAddr::Domain(domain, _) => domain.clone(),
+        }
+    }
+
+    /// Get the port of the target
+    #[must_use]
+    pub fn port(&self) -> u16 {
+        match self {
+            TargetAddr::Ip(addr) => addr.port(),
+            TargetAddr::Domain(_, port) => *port,
+        }
+    }
+}
+
+impl fmt::Display for TargetAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetAddr::Ip(addr) => write!(f, "{addr}"),
+            TargetAddr::Domain(domain, port) => write!(f, "{domain}:{port}"),
+        }
+    }
+}
+
+/// The outcome of a SOCKS5 request, as reported to the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocksReply {
+    /// The connection to the target is open
+    Succeeded,
+    /// The request failed for another reason
+    GeneralFailure,
+    /// The target could not be reached
+    HostUnreachable,
+    /// The target refused the connection
+    ConnectionRefused,
+    /// The connection to the target timed out
+    TtlExpired,
+    /// The command is not CONNECT
+    CommandNotSupported,
+    /// The address type is unknown
+    AddressTypeNotSupported,
+}
+
+impl SocksReply {
+    /// Get the reply code sent on the wire
+    #[must_use]
+    pub fn code(self) -> u8 {
+        match self {
+            SocksReply::Succeeded => 0x00,
+            SocksReply::GeneralFailure => 0x01,
+            SocksReply::HostUnreachable => 0x04,
+            SocksReply::ConnectionRefused => 0x05,
+            SocksReply::TtlExpired => 0x06,
+            SocksReply::CommandNotSupported => 0x07,
+            SocksReply::AddressTypeNotSupported => 0x08,
+        }
+    }
+
+    /// Get the reply reporting a failed relay attempt
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Why the relay failed
+    #[must_use]
+    pub fn for_error(error: &ListenerError) -> Self {
+        match error {
+            ListenerError::UpstreamError(_) => SocksReply::HostUnreachable,
+            ListenerError::TargetRefused(_) => SocksReply::ConnectionRefused,
+            ListenerError::Timeout(_) => SocksReply::TtlExpired,
+            ListenerError::Unsupported(_) => SocksReply::AddressTypeNotSupported,
+            _ => SocksReply::GeneralFailure,
+        }
+    }
+}
+
+/// Perform the server side of a SOCKS5 handshake
+///
+/// Accepts the method without authentication and reads a CONNECT request.
+/// Requests that can't be served are answered before the error is returned,
+/// so the client learns why.
+///
+/// # Arguments
+///
+/// * `stream` - The client connection
+///
+/// # Returns
+///
+/// The target the client asked for. The caller answers with [`send_reply`].
+///
+/// # Errors
+///
+/// Returns an error if:
+/// * The client doesn't speak SOCKS5
+/// * The client doesn't offer the method without authentication
+/// * The command is not CONNECT or the address type is unknown
+/// * The connection fails
+pub async fn accept<S>(stream: &mut S) -> ListenerResult<TargetAddr>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    // Greeting: version, number of methods, methods
+    let mut header = [0_u8; 2];
+    stream.read_exact(&mut header).await?;
+    if header[0] != SOCKS5_VERSION {
+        return Err(ListenerError::HandshakeError(format!(
+            "Unsupported SOCKS version {}",
+            header[0]
+        )));
+    }
+    let mut methods = vec![0_u8; usize::from(header[1])];
+    stream.read_exact(&mut methods).await?;
+    if !methods.contains(&METHOD_NONE) {
+        stream
+            .write_all(&[SOCKS5_VERSION, METHOD_UNACCEPTABLE])
+            .await?;
+        return Err(ListenerError::Unsupported(
+            "Client requires authentication".to_string(),
+        ));
+    }
+    stream.write_all(&[SOCKS5_VERSION, METHOD_NONE]).await?;
+
+    // Request: version, command, reserved, address
+    let mut request = [0_u8; 3];
+    stream.read_exact(&mut request).await?;
+    if request[0] != SOCKS5_VERSION {
+        return Err(ListenerError::HandshakeError(format!(
+            "Unsupported SOCKS version {}",
+            request[0]
+        )));
+    }
+    if request[1] != COMMAND_CONNECT {
+        send_reply(stream, SocksReply::CommandNotSupported).await?;
+        return Err(ListenerError::Unsupported(format!(
+            "SOCKS command {}",
+            request[1]
+        )));
+    }
+
+    if let Some(target) = read_address(stream).await? {
+        Ok(target)
+    } else {
+        send_reply(stream, SocksReply::AddressTypeNotSupported).await?;
+        Err(ListenerError::Unsupported("SOCKS address type".to_string()))
+    }
+}
+
+/// Answer a SOCKS5 request
+///
+/// The bound address is always reported as unspecified, as it is the
+/// address of the upstream proxy and not reachable by the client.
+///
+/// # Arguments
+///
+/// * `stream` - The client connection
+/// * `reply` - The outcome of the request
+///
+/// # Errors
+///
+/// Returns an error if the reply can't be written
+pub async fn send_reply<S>(stream: &mut S, reply: SocksReply) -> ListenerResult<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    stream
+        .write_all(&[
+            SOCKS5_VERSION,
+            reply.code(),
+            0,
+            ADDRESS_IPV4,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ])
+        .await?;
+    Ok(())
+}
+
+/// The connection to an upstream proxy, relaying to the target once open
+#[derive(Debug)]
+pub enum Tunnel {
+    /// A plain connection, to HTTP and SOCKS proxies
+    Plain(TcpStream),
+
+    /// A TLS connection, to HTTPS proxies
+    #[cfg(feature = "network")]
+    Tls(Box<tokio_native_tls::TlsStream<TcpStream>>),
+}
+
+impl AsyncRead for Tunnel {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Tunnel::Plain(stream) => Pin::new(stream).poll_read(cx, buf),
+            #[cfg(feature = "network")]
+            Tunnel::Tls(stream) => Pin::new(stream).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Tunnel {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Tunnel::Plain(stream) => Pin::new(stream).poll_write(cx, buf),
+            #[cfg(feature = "network")]
+            Tunnel::Tls(stream) => Pin::new(stream).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Tunnel::Plain(stream) => Pin::new(stream).poll_flush(cx),
+            #[cfg(feature = "network")]
+            Tunnel::Tls(stream) => Pin::new(stream).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Tunnel::Plain(stream) => Pin::new(stream).poll_shutdown(cx),
+            #[cfg(feature = "network")]
+            Tunnel::Tls(stream) => Pin::new(stream).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Open a tunnel to a target through an upstream proxy
+///
+/// # Arguments
+///
+/// * `proxy` - The upstream proxy
+/// * `target` - The destination of the tunnel
+///
+/// # Returns
+///
+/// The connection to the proxy, relaying to the target from here on
+///
+/// # Errors
+///
+/// Returns an error if:
+/// * The proxy can't be reached, or an HTTPS proxy fails the TLS handshake or,
+///   with `verify_tls` set, certificate verification
+/// * The proxy refuses the credentials
+/// * The proxy refuses the tunnel to the target, as `ListenerError::TargetRefused`
+/// * A SOCKS4 proxy is asked for an IPv6 target
+/// * An HTTPS proxy is used without the `network` feature
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+/// use gooty_proxy::definitions::errors::ListenerError;
+/// use gooty_proxy::definitions::proxy::Proxy;
+/// use gooty_proxy::definitions::types::Port;
+/// use gooty_proxy::io::socks::{self, TargetAddr};
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+/// use tokio::net::TcpListener;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let target = TargetAddr::Domain("example.com".to_string(), 443);
+///     let proxy = |proxy_type, port| {
+///         Proxy::new(
+///             proxy_type,
+///             IpAddr::V4(Ipv4Addr::LOCALHOST),
+///             Port::new(port).unwrap(),
+///             AnonymityLevel::Elite,
+///         )
+///         .with_auth("user".to_string(), "secret".to_string())
+///     };
+///
+///     // An HTTP proxy opening the tunnel only for the right credentials
+///     let http = TcpListener::bind("127.0.0.1:0").await.unwrap();
+///     let http_port = http.local_addr().unwrap().port();
+///     tokio::spawn(async move {
+///         for _ in 0..2 {
+///             let (mut stream, _) = http.accept().await.unwrap();
+///             let mut head = Vec::new();
+///             while !head.ends_with(b"\r\n\r\n") {
+///                 head.push(stream.read_u8().await.unwrap());
+///             }
+///             // "user:secret" in Base64
+///             let authorized = String::from_utf8(head)
+///                 .unwrap()
+///                 .contains("Proxy-Authorization: Basic dXNlcjpzZWNyZXQ=\r\n");
+///             let status = if authorized { "200 OK" } else { "407 Proxy Authentication Required" };
+///             let response = format!("HTTP/1.1 {status}\r\n\r\n");
+///             stream.write_all(response.as_bytes()).await.unwrap();
+///         }
+///     });
+///     let good = proxy(ProxyType::Http, http_port);
+///     assert!(socks::connect_through(&good, &target).await.is_ok());
+///     let bad = good.with_auth("user".to_string(), "wrong".to_string());
+///     // Wrong credentials fail the proxy, not just the target
+///     assert!(matches!(
+///         socks::connect_through(&bad, &target).await,
+///         Err(ListenerError::UpstreamError(_))
+///     ));
+///
+///     // A SOCKS5 proxy insisting on the username/password sub-negotiation
+///     let socks5 = TcpListener::bind("127.0.0.1:0").await.unwrap();
+///     let socks5_port = socks5.local_addr().unwrap().port();
+///     tokio::spawn(async move {
+///         let (mut stream, _) = socks5.accept().await.unwrap();
+///         let mut greeting = [0_u8; 4];
+///         stream.read_exact(&mut greeting).await.unwrap();
+///         assert_eq!(greeting, [5, 2, 0, 2]);
+///         stream.write_all(&[5, 2]).await.unwrap();
+///
+///         let mut credentials = [0_u8; 13];
+///         stream.read_exact(&mut credentials).await.unwrap();
+///         assert_eq!(&credentials, b"\x01\x04user\x06secret");
+///         stream.write_all(&[1, 0]).await.unwrap();
+///
+///         let mut request = [0_u8; 18];
+///         stream.read_exact(&mut request).await.unwrap();
+///         stream.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await.unwrap();
+///     });
+///     let good = proxy(ProxyType::Socks5, socks5_port);
+///     assert!(socks::connect_through(&good, &target).await.is_ok());
+/// }
+/// ```
+pub async fn connect_through(proxy: &Proxy, target: &TargetAddr) -> ListenerResult<Tunnel> {
+    let mut stream = TcpStream::connect(proxy.socket_addr())
+        .await
+        .map_err(|e| ListenerError::UpstreamError(format!("Failed to reach proxy: {e}")))?;
+
+    match proxy.proxy_type {
+        ProxyType::Http => http_connect(&mut stream, proxy, target).await?,
+        ProxyType::Https => {
+            let mut stream = tls_connect(stream, proxy).await?;
+            http_connect(&mut stream, proxy, target).await?;
+            return Ok(stream);
+        }
+        ProxyType::Socks4 => socks4_connect(&mut stream, target).await?,
+        ProxyType::Socks5 => socks5_connect(&mut stream, proxy, target).await?,
+    }
+
+    Ok(Tunnel::Plain(stream))
+}
+
+/// Speak TLS to an HTTPS proxy under its configured server name, verifying
+/// its certificate only if the proxy asks for it
+#[cfg(feature = "network")]
+async fn tls_connect(stream: TcpStream, proxy: &Proxy) -> ListenerResult<Tunnel> {
+    let server_name = proxy
+        .tls_server_name
+        .clone()
+        .unwrap_or_else(|| proxy.address.to_string());
+    let connector = native_tls::TlsConnector::builder()
+        .danger_accept_invalid_certs(!proxy.verify_tls)
+        .danger_accept_invalid_hostnames(!proxy.verify_tls)
+        .build()
+        .map_err(|e| ListenerError::UpstreamError(format!("Failed to set up TLS: {e}")))?;
+    let stream = tokio_native_tls::TlsConnector::from(connector)
+        .connect(&server_name, stream)
+        .await
+        .map_err(|e| ListenerError::UpstreamError(format!("TLS handshake failed: {e}")))?;
+    Ok(Tunnel::Tls(Box::new(stream)))
+}
+
+/// Speak TLS to an HTTPS proxy, which needs the `network` feature
+#[cfg(not(feature = "network"))]
+fn tls_connect(_stream: TcpStream, _proxy: &Proxy) -> std::future::Ready<ListenerResult<Tunnel>> {
+    std::future::ready(Err(ListenerError::Unsupported(
+        "HTTPS proxies need the network feature".to_string(),
+    )))
+}
+
+/// Read a SOCKS5 address and port, or None if the address type is unknown
+async fn read_address<S>(stream: &mut S) -> ListenerResult<Option<TargetAddr>>
+where
+    S: AsyncRead + Unpin,
+{
+    let address_type = stream.read_u8().await?;
+    let host = match address_type {
+        ADDRESS_IPV4 => {
+            let mut octets = [0_u8; 4];
+            stream.read_exact(&mut octets).await?;
+            Some(IpAddr::V4(Ipv4Addr::from(octets)))
+        }
+        ADDRESS_IPV6 => {
+            let mut octets = [0_u8; 16];
+            stream.read_exact(&mut octets).await?;
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        ADDRESS_DOMAIN => None,
+        _ => return Ok(None),
+    };
+
+    let target = if let Some(ip) = host {
+        TargetAddr::Ip(SocketAddr::new(ip, stream.read_u16().await?))
+    } else {
+        let mut domain = vec![0_u8; usize::from(stream.read_u8().await?)];
+        stream.read_exact(&mut domain).await?;
+        let domain = String::from_utf8(domain)
+            .map_err(|_| ListenerError::HandshakeError("Domain is not UTF-8".to_string()))?;
+        TargetAddr::Domain(domain, stream.read_u16().await?)
+    };
+    Ok(Some(target))
+}
+
+/// Ask an HTTP proxy for a tunnel with the CONNECT method
+async fn http_connect<S>(stream: &mut S, proxy: &Proxy, target: &TargetAddr) -> ListenerResult<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let authority = match target {
+        TargetAddr::Ip(addr) => addr.to_string(),
+        TargetAddr::Domain(domain, port) => format!("{domain}:{port}"),
+    };
+    let authorization = ProxyAuth::from_proxy(proxy)
+        .map(|auth| format!("Proxy-Authorization: {}\r\n", auth.header_value()))
+        .unwrap_or_default();
+    let request =
+        format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n{authorization}\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response header byte by byte, so no tunneled data is consumed
+    let mut header = Vec::new();
+    while !header.ends_with(b"\r\n\r\n") {
+        if header.len() >= MAX_HTTP_HEADER_BYTES {
+            return Err(ListenerError::UpstreamError(
+                "Proxy response header too long".to_string(),
+            ));
+        }
+        header.push(stream.read_u8().await?);
+    }
+
+    let header = String::from_utf8_lossy(&header);
+    let status_line = header.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status.starts_with('2') {
+        Ok(())
+    } else if status == "407" {
+        Err(ListenerError::UpstreamError(format!(
+            "Proxy rejected credentials: {status_line}"
+        )))
+    } else {
+        Err(ListenerError::TargetRefused(format!(
+            "Proxy refused tunnel: {status_line}"
+        )))
+    }
+}
+
+/// Ask a SOCKS4 proxy for a connection, using the 4a extension for domain names
+async fn socks4_connect(stream: &mut TcpStream, target: &TargetAddr) -> ListenerResult<()> {
+    let mut request = vec![SOCKS4_VERSION, COMMAND_CONNECT];
+    request.extend_from_slice(&target.port().to_be_bytes());
+    match target {
+        TargetAddr::Ip(SocketAddr::V4(addr)) => {
+            request.extend_from_slice(&addr.ip().octets());
+            request.push(0);
+        }
+        TargetAddr::Ip(SocketAddr::V6(_)) => {
+            return Err(ListenerError::Unsupported(
+                "SOCKS4 proxies can't reach IPv6 targets".to_string(),
+            ));
+        }
+        TargetAddr::Domain(domain, _) => {
+            // An address of 0.0.0.x asks the proxy to resolve the name that follows
+            request.extend_from_slice(&[0, 0, 0, 1, 0]);
+            request.extend_from_slice(domain.as_bytes());
+            request.push(0);
+        }
+    }
+    stream.write_all(&request).await?;
+
+    let mut reply = [0_u8; 8];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] == SOCKS4_GRANTED {
+        Ok(())
+    } else {
+        Err(ListenerError::TargetRefused(format!(
+            "SOCKS4 proxy rejected request with code {:#04x}",
+            reply[1]
+        )))
+    }
+}
+
+/// Ask a SOCKS5 proxy for a connection, authenticating if it has credentials
+async fn socks5_connect(
+    stream: &mut TcpStream,
+    proxy: &Proxy,
+    target: &TargetAddr,
+) -> ListenerResult<()> {
+    let auth = ProxyAuth::from_proxy(proxy);
+    if auth.is_some() {
+        stream
+            .write_all(&[SOCKS5_VERSION, 2, METHOD_NONE, METHOD_PASSWORD])
+            .await?;
+    } else {
+        stream.write_all(&[SOCKS5_VERSION, 1, METHOD_NONE]).await?;
+    }
+
+    let mut choice = [0_u8; 2];
+    stream.read_exact(&mut choice).await?;
+    match (choice[1], auth) {
+        (METHOD_NONE, _) => {}
+        (METHOD_PASSWORD, Some(ProxyAuth::Basic { username, password })) => {
+            let (Ok(username_len), Ok(password_len)) =
+                (u8::try_from(username.len()), u8::try_from(password.len()))
+            else {
+                return Err(ListenerError::UpstreamError(
+                    "Proxy credentials too long for SOCKS5".to_string(),
+                ));
+            };
+            let mut request = vec![1, username_len];
+            request.extend_from_slice(username.as_bytes());
+            request.push(password_len);
+            request.extend_from_slice(password.as_bytes());
+            stream.write_all(&request).await?;
+
+            let mut status = [0_u8; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] != 0 {
+                return Err(ListenerError::UpstreamError(
+                    "Proxy rejected credentials".to_string(),
+                ));
+            }
+        }
+        (method, _) => {
+            return Err(ListenerError::UpstreamError(format!(
+                "Proxy chose unsupported method {method:#04x}"
+            )));
+        }
+    }
+
+    let mut request = vec![SOCKS5_VERSION, COMMAND_CONNECT, 0];
+    match target {
+        TargetAddr::Ip(SocketAddr::V4(addr)) => {
+            request.push(ADDRESS_IPV4);
+            request.extend_from_slice(&addr.ip().octets());
+        }
+        TargetAddr::Ip(SocketAddr::V6(addr)) => {
+            request.push(ADDRESS_IPV6);
+            request.extend_from_slice(&addr.ip().octets());
+        }
+        TargetAddr::Domain(domain, _) => {
+            let Ok(len) = u8::try_from(domain.len()) else {
+                return Err(ListenerError::HandshakeError(
+                    "Domain too long for SOCKS5".to_string(),
+                ));
+            };
+            request.push(ADDRESS_DOMAIN);
+            request.push(len);
+            request.extend_from_slice(domain.as_bytes());
+        }
+    }
+    request.extend_from_slice(&target.port().to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut reply = [0_u8; 3];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        return Err(ListenerError::TargetRefused(format!(
+            "SOCKS5 proxy rejected request with code {:#04x}",
+            reply[1]
+        )));
+    }
+
+    // Skip the bound address, which is of no use to the client
+    let bound_len = match stream.read_u8().await? {
+        ADDRESS_IPV4 => 4,
+        ADDRESS_IPV6 => 16,
+        ADDRESS_DOMAIN => usize::from(stream.read_u8().await?),
+        other => {
+            return Err(ListenerError::UpstreamError(format!(
+                "Proxy replied with unknown address type {other}"
+            )));
+        }
+    };
+    let mut bound = vec![0_u8; bound_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
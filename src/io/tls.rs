@@ -0,0 +1,128 @@
+//! # TLS Profile Module
+//!
+//! This module builds the rustls client configurations behind each
+//! [`TlsProfile`]. Profiles differ in protocol versions, cipher suite order,
+//! key exchange groups, and ALPN values, which are the inputs of a JA3
+//! fingerprint, so every profile presents a stable and distinct `ClientHello`.
+//!
+//! Only compiled with the `tls-profiles` feature.
+
+use crate::definitions::{
+    enums::TlsProfile,
+    errors::{RequestResult, RequestorError},
+};
+use rustls::{
+    ClientConfig, RootCertStore, SupportedCipherSuite, SupportedProtocolVersion,
+    crypto::{
+        CryptoProvider, SupportedKxGroup,
+        ring::{cipher_suite, default_provider, kx_group},
+    },
+    version::{TLS12, TLS13},
+};
+use std::sync::Arc;
+
+/// Offers TLS 1.3 only.
+static TLS13_ONLY: &[&SupportedProtocolVersion] = &[&TLS13];
+
+/// Offers TLS 1.3, falling back to TLS 1.2.
+static TLS13_AND_TLS12: &[&SupportedProtocolVersion] = &[&TLS13, &TLS12];
+
+/// The `ClientHello` ingredients that make up a profile's fingerprint.
+struct ProfileSpec {
+    /// Protocol versions offered, in preference order
+    versions: &'static [&'static SupportedProtocolVersion],
+    /// Cipher suites offered, in preference order
+    cipher_suites: Vec<SupportedCipherSuite>,
+    /// Key exchange groups offered, in preference order
+    kx_groups: Vec<&'static dyn SupportedKxGroup>,
+    /// ALPN protocol identifiers advertised
+    alpn: &'static [&'static [u8]],
+}
+
+impl ProfileSpec {
+    /// Returns the specification of a profile, or `None` for `TlsProfile::Native`.
+    fn for_profile(profile: TlsProfile) -> Option<Self> {
+        match profile {
+            TlsProfile::Native => None,
+            TlsProfile::Modern => Some(ProfileSpec {
+                versions: TLS13_ONLY,
+                cipher_suites: vec![
+                    cipher_suite::TLS13_AES_128_GCM_SHA256,
+                    cipher_suite::TLS13_AES_256_GCM_SHA384,
+                    cipher_suite::TLS13_CHACHA20_POLY1305_SHA256,
+                ],
+                kx_groups: vec![kx_group::X25519, kx_group::SECP256R1, kx_group::SECP384R1],
+                alpn: &[b"h2", b"http/1.1"],
+            }),
+            TlsProfile::Mobile => Some(ProfileSpec {
+                versions: TLS13_AND_TLS12,
+                cipher_suites: vec![
+                    cipher_suite::TLS13_CHACHA20_POLY1305_SHA256,
+                    cipher_suite::TLS13_AES_128_GCM_SHA256,
+                    cipher_suite::TLS13_AES_256_GCM_SHA384,
+                    cipher_suite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+                    cipher_suite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+                    cipher_suite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+                    cipher_suite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+                ],
+                kx_groups: vec![kx_group::X25519, kx_group::SECP256R1],
+                alpn: &[b"h2", b"http/1.1"],
+            }),
+            TlsProfile::Compatible => Some(ProfileSpec {
+                versions: TLS13_AND_TLS12,
+                cipher_suites: vec![
+                    cipher_suite::TLS13_AES_128_GCM_SHA256,
+                    cipher_suite::TLS13_AES_256_GCM_SHA384,
+                    cipher_suite::TLS13_CHACHA20_POLY1305_SHA256,
+                    cipher_suite::TLS_ECDHE_ECDSA_WITH_AES_128_GCM_SHA256,
+                    cipher_suite::TLS_ECDHE_RSA_WITH_AES_128_GCM_SHA256,
+                    cipher_suite::TLS_ECDHE_ECDSA_WITH_AES_256_GCM_SHA384,
+                    cipher_suite::TLS_ECDHE_RSA_WITH_AES_256_GCM_SHA384,
+                    cipher_suite::TLS_ECDHE_ECDSA_WITH_CHACHA20_POLY1305_SHA256,
+                    cipher_suite::TLS_ECDHE_RSA_WITH_CHACHA20_POLY1305_SHA256,
+                ],
+                kx_groups: vec![kx_group::SECP256R1, kx_group::X25519, kx_group::SECP384R1],
+                alpn: &[b"http/1.1"],
+            }),
+        }
+    }
+}
+
+/// Builds the client TLS configuration for a profile.
+///
+/// # Arguments
+///
+/// * `profile` - The TLS profile to build
+///
+/// # Returns
+///
+/// `Some(ClientConfig)` for custom profiles, or `None` for `TlsProfile::Native`,
+/// which keeps reqwest's own backend.
+///
+/// # Errors
+///
+/// Returns `RequestorError::TlsConfiguration` if rustls rejects the profile.
+pub(crate) fn client_config(profile: TlsProfile) -> RequestResult<Option<ClientConfig>> {
+    let Some(spec) = ProfileSpec::for_profile(profile) else {
+        return Ok(None);
+    };
+
+    let provider = CryptoProvider {
+        cipher_suites: spec.cipher_suites,
+        kx_groups: spec.kx_groups,
+        ..default_provider()
+    };
+
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+
+    let mut config = ClientConfig::builder_with_provider(Arc::new(provider))
+        .with_protocol_versions(spec.versions)
+        .map_err(|e| RequestorError::TlsConfiguration(e.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    config.alpn_protocols = spec.alpn.iter().map(|protocol| protocol.to_vec()).collect();
+
+    Ok(Some(config))
+}
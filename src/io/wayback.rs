@@ -0,0 +1,470 @@
+//! # Wayback Module
+//!
+//! Harvests proxies from past snapshots of a source, as kept by the Internet
+//! Archive's Wayback Machine (`wayback` feature).
+//!
+//! Lists published by a site years ago still name proxies, some of which keep
+//! running long after the list moved on. Reading every archived snapshot of a
+//! single source is a quick way to build a large list of candidates to judge.
+//!
+//! ## Components
+//!
+//! * **`Snapshot`** - A capture of a URL listed by the archive's CDX API
+//! * **`Wayback`** - Lists the snapshots of a source and fetches proxies from them
+//!
+//! Requests to the archive are paced, since it throttles clients that ask too
+//! quickly, and only the most recent snapshots are read.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use gooty_proxy::definitions::source::Source;
+//! use gooty_proxy::io::{http::Requestor, wayback::Wayback};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let source = Source::new(
+//!         "https://example.com/proxy-list".to_string(),
+//!         "Mozilla/5.0 (compatible; Gooty/1.0)".to_string(),
+//!         r"(\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}:\d{2,5})".to_string(),
+//!     )?;
+//!
+//!     let wayback = Wayback::new().with_max_snapshots(50);
+//!     let proxies = wayback.harvest(&Requestor::new()?, &source).await?;
+//!
+//!     println!("Found {} historic proxies", proxies.len());
+//!     Ok(())
+//! }
+//! ```
+
+use crate::definitions::{
+    defaults,
+    enums::IdentityProfile,
+    errors::{SourceError, SourceResult},
+    proxy::Proxy,
+    source::Source,
+};
+use crate::io::{
+    filesystem::AppConfig,
+    http::{RequestProfile, Requestor},
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::time::{Interval, MissedTickBehavior};
+use url::Url;
+
+/// Format of the timestamps of the CDX API and of snapshot URLs
+const TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// A capture of a URL kept by the archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// When the URL was captured
+    pub timestamp: DateTime<Utc>,
+
+    /// The URL as it was captured
+    pub original: String,
+}
+
+/// Harvests proxies from the archived snapshots of sources
+///
+/// # Examples
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use gooty_proxy::io::wayback::{Snapshot, Wayback};
+/// use std::time::Duration;
+///
+/// let wayback = Wayback::new()
+///     .with_max_snapshots(10)
+///     .with_interval(Duration::from_secs(5));
+///
+/// let snapshot = Snapshot {
+///     timestamp: Utc.with_ymd_and_hms(2021, 3, 4, 5, 6, 7).unwrap(),
+///     original: "https://example.com/proxies.txt".to_string(),
+/// };
+/// assert_eq!(
+///     wayback.snapshot_url(&snapshot),
+///     "https://web.archive.org/web/20210304050607id_/https://example.com/proxies.txt"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Wayback {
+    /// URL of the CDX API listing snapshots
+    cdx_url: String,
+
+    /// URL under which snapshots are served
+    archive_url: String,
+
+    /// Most snapshots read per source URL
+    max_snapshots: usize,
+
+    /// Time between two requests to the archive
+    interval: Duration,
+
+    /// Earliest capture read, if limited
+    from: Option<DateTime<Utc>>,
+
+    /// Latest capture read, if limited
+    to: Option<DateTime<Utc>>,
+
+    /// User-Agent the archive is queried with
+    user_agent: String,
+}
+
+impl Default for Wayback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Wayback {
+    /// Creates a harvester for the Internet Archive with default limits
+    ///
+    /// # Returns
+    ///
+    /// A harvester reading up to `defaults::wayback::MAX_SNAPSHOTS` snapshots
+    /// per URL, `defaults::wayback::REQUEST_INTERVAL_MS` apart.
+    #[must_use]
+    pub fn new() -> Self {
+        Wayback {
+            cdx_url: defaults::wayback::CDX_URL.to_string(),
+            archive_url: defaults::wayback::ARCHIVE_URL.to_string(),
+            max_snapshots: defaults::wayback::MAX_SNAPSHOTS,
+            interval: Duration::from_millis(defaults::wayback::REQUEST_INTERVAL_MS),
+            from: None,
+            to: None,
+            user_agent: RequestProfile::for_identity(IdentityProfile::default()).user_agent,
+        }
+    }
+
+    /// Creates a harvester from the application configuration
+    ///
+    /// The archive is queried under the configured identity.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The application configuration
+    ///
+    /// # Returns
+    ///
+    /// A harvester with default limits, identified by `config.outbound_identity`
+    #[must_use]
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new().with_identity(config.outbound_identity)
+    }
+
+    /// Sets the identity the archive is queried under
+    ///
+    /// Snapshots themselves are fetched with the User-Agent of their source.
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - How requests to the CDX API identify themselves
+    #[must_use]
+    pub fn with_identity(mut self, identity: IdentityProfile) -> Self {
+        self.user_agent = RequestProfile::for_identity(identity).user_agent;
+        self
+    }
+
+    /// Reads snapshots from another archive speaking the CDX API
+    ///
+    /// # Arguments
+    ///
+    /// * `cdx_url` - URL of the CDX API listing snapshots
+    /// * `archive_url` - URL under which snapshots are served, without a trailing slash
+    #[must_use]
+    pub fn with_endpoints(mut self, cdx_url: &str, archive_url: &str) -> Self {
+        self.cdx_url = cdx_url.to_string();
+        self.archive_url = archive_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the most snapshots read per source URL
+    ///
+    /// # Arguments
+    ///
+    /// * `max_snapshots` - Number of the most recent snapshots to read
+    #[must_use]
+    pub fn with_max_snapshots(mut self, max_snapshots: usize) -> Self {
+        self.max_snapshots = max_snapshots;
+        self
+    }
+
+    /// Sets the time between two requests to the archive
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Time between the starts of two requests
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Limits the snapshots read to those captured in a time range
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Earliest capture read, if limited
+    /// * `to` - Latest capture read, if limited
+    #[must_use]
+    pub fn with_range(mut self, from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>) -> Self {
+        self.from = from;
+        self.to = to;
+        self
+    }
+
+    /// Returns the URL serving a snapshot as it was captured
+    ///
+    /// The archive's own banner and link rewriting are left out, so the
+    /// response is the original list.
+    ///
+    /// # Arguments
+    ///
+    /// * `snapshot` - The snapshot to serve
+    #[must_use]
+    pub fn snapshot_url(&self, snapshot: &Snapshot) -> String {
+        format!(
+            "{}/{}id_/{}",
+            self.archive_url,
+            snapshot.timestamp.format(TIMESTAMP_FORMAT),
+            snapshot.original
+        )
+    }
+
+    /// Lists the most recent snapshots of a URL
+    ///
+    /// Only successful captures are listed, and captures identical to the one
+    /// before them are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `requestor` - The HTTP client to use for making requests
+    /// * `url` - The URL whose snapshots to list
+    ///
+    /// # Returns
+    ///
+    /// The snapshots, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The CDX API can't be reached or answers with a non-success HTTP status
+    /// * The CDX API response can't be parsed
+    pub async fn snapshots(&self, requestor: &Requestor, url: &str) -> SourceResult<Vec<Snapshot>> {
+        self.list_snapshots(requestor, url, &mut self.pace()).await
+    }
+
+    /// Fetches proxies from the snapshots of a source
+    ///
+    /// Every URL the source expands into is looked up in the archive, and
+    /// every snapshot fetched with the source's pattern and settings, one
+    /// request at a time. Proxies found in several snapshots are kept once.
+    /// Proxies are attributed to the source, or for index sources to the
+    /// archived list file they were found in.
+    ///
+    /// # Arguments
+    ///
+    /// * `requestor` - The HTTP client to use for making requests
+    /// * `source` - The source whose snapshots to read
+    ///
+    /// # Returns
+    ///
+    /// The proxies found in the snapshots, and nothing if the source was never archived.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// * The URL template of the source can't be expanded
+    /// * The snapshots can't be listed for any URL of the source
+    /// * Every snapshot fails to fetch
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::source::Source;
+    /// use gooty_proxy::io::http::{Egress, Requestor};
+    /// use gooty_proxy::io::wayback::Wayback;
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpListener;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     // An archive that captured a list twice
+    ///     let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let archive = format!("http://{}", server.local_addr().unwrap());
+    ///     tokio::spawn(async move {
+    ///         loop {
+    ///             let (mut stream, _) = server.accept().await.unwrap();
+    ///             let mut request = Vec::new();
+    ///             while !request.ends_with(b"\r\n\r\n") {
+    ///                 request.push(stream.read_u8().await.unwrap());
+    ///             }
+    ///             let request = String::from_utf8(request).unwrap();
+    ///             let path = request.split(' ').nth(1).unwrap();
+    ///             let body = if path.starts_with("/cdx") {
+    ///                 r#"[["timestamp","original"],
+    ///                     ["20190101000000","https://example.com/proxies.txt"],
+    ///                     ["20200101000000","https://example.com/proxies.txt"]]"#
+    ///             } else if path.starts_with("/web/2019") {
+    ///                 "203.0.113.7:8080\n198.51.100.1:3128"
+    ///             } else {
+    ///                 "198.51.100.1:3128\n192.0.2.44:80"
+    ///             };
+    ///             let response = format!(
+    ///                 "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+    ///                 body.len()
+    ///             );
+    ///             stream.write_all(response.as_bytes()).await.unwrap();
+    ///         }
+    ///     });
+    ///
+    ///     let source = Source::new(
+    ///         "https://example.com/proxies.txt".to_string(),
+    ///         "Mozilla/5.0".to_string(),
+    ///         r"\d{1,3}\.\d{1,3}\.\d{1,3}\.\d{1,3}:\d{2,5}".to_string(),
+    ///     )
+    ///     .unwrap();
+    ///     let wayback = Wayback::new()
+    ///         .with_endpoints(&format!("{archive}/cdx"), &format!("{archive}/web"))
+    ///         .with_interval(Duration::from_millis(10));
+    ///     let requestor = Requestor::new().unwrap().with_egress(Egress::Direct).unwrap();
+    ///
+    ///     let proxies = wayback.harvest(&requestor, &source).await.unwrap();
+    ///     assert_eq!(proxies.len(), 3);
+    ///     assert!(proxies.iter().all(|p| p.source.as_deref() == Some(source.url.as_str())));
+    /// }
+    /// ```
+    pub async fn harvest(
+        &self,
+        requestor: &Requestor,
+        source: &Source,
+    ) -> SourceResult<Vec<Proxy>> {
+        let mut pace = self.pace();
+        let mut proxies = Vec::new();
+        let mut seen = HashSet::new();
+        let mut fetched = false;
+        let mut first_error = None;
+
+        for url in source.expand_urls()? {
+            let snapshots = match self.list_snapshots(requestor, &url, &mut pace).await {
+                Ok(snapshots) => snapshots,
+                Err(e) => {
+                    log::warn!("Failed to list snapshots of {url}: {e}");
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+
+            for snapshot in &snapshots {
+                // Fetch the snapshot as the source itself, at the archived URL
+                let mut archived = source.clone();
+                archived.url = self.snapshot_url(snapshot);
+                archived.parameters.clear();
+                archived.template_variables.clear();
+
+                pace.tick().await;
+                match archived.fetch_proxies(requestor).await {
+                    Ok(found) => {
+                        fetched = true;
+                        proxies.extend(
+                            found
+                                .into_iter()
+                                .filter(|p| seen.insert((p.address, p.port))),
+                        );
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to fetch snapshot {}: {e}", archived.url);
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+        }
+
+        if !source.is_index() {
+            for proxy in &mut proxies {
+                proxy.source = Some(source.url.clone());
+            }
+        }
+
+        match first_error {
+            Some(e) if !fetched => Err(e),
+            _ => Ok(proxies),
+        }
+    }
+
+    /// Lists the snapshots of a URL, waiting for its turn to query the archive
+    async fn list_snapshots(
+        &self,
+        requestor: &Requestor,
+        url: &str,
+        pace: &mut Interval,
+    ) -> SourceResult<Vec<Snapshot>> {
+        let mut query = vec![
+            ("url", url.to_string()),
+            ("output", "json".to_string()),
+            ("fl", "timestamp,original".to_string()),
+            ("filter", "statuscode:200".to_string()),
+            ("collapse", "digest".to_string()),
+            // A negative limit counts from the most recent capture
+            ("limit", format!("-{}", self.max_snapshots)),
+        ];
+        if let Some(from) = self.from {
+            query.push(("from", from.format(TIMESTAMP_FORMAT).to_string()));
+        }
+        if let Some(to) = self.to {
+            query.push(("to", to.format(TIMESTAMP_FORMAT).to_string()));
+        }
+        let cdx_url = Url::parse_with_params(&self.cdx_url, &query)
+            .map_err(|_| SourceError::InvalidUrl(self.cdx_url.clone()))?;
+
+        pace.tick().await;
+        let (_, body) = requestor
+            .get_bytes(
+                cdx_url.as_str(),
+                &self.user_agent,
+                defaults::sources::MAX_DOWNLOAD_BYTES,
+            )
+            .await
+            .map_err(SourceError::from)?;
+
+        parse_cdx(&body)
+    }
+
+    /// Creates the pacer spacing out requests to the archive
+    fn pace(&self) -> Interval {
+        let mut pace = tokio::time::interval(self.interval.max(Duration::from_millis(1)));
+        pace.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        pace
+    }
+}
+
+/// Parse the snapshots in a CDX API response
+///
+/// The API answers with an array of rows, the first holding the field names,
+/// or with nothing at all if the URL was never captured.
+fn parse_cdx(body: &[u8]) -> SourceResult<Vec<Snapshot>> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Vec::new());
+    }
+
+    let rows: Vec<Vec<String>> =
+        serde_json::from_slice(body).map_err(|e| SourceError::ParseError(e.to_string()))?;
+
+    let mut snapshots = Vec::new();
+    for row in rows.into_iter().skip(1) {
+        let [timestamp, original] = <[String; 2]>::try_from(row)
+            .map_err(|row| SourceError::ParseError(format!("unexpected CDX row {row:?}")))?;
+        let timestamp = NaiveDateTime::parse_from_str(&timestamp, TIMESTAMP_FORMAT)
+            .map_err(|e| SourceError::ParseError(format!("CDX timestamp '{timestamp}': {e}")))?;
+        snapshots.push(Snapshot {
+            timestamp: timestamp.and_utc(),
+            original,
+        });
+    }
+
+    Ok(snapshots)
+}
@@ -15,6 +15,38 @@
 //! The library is designed to be both easy to use for simple cases and highly
 //! configurable for advanced use cases.
 //!
+//! Responses of sources, judges, and lookup providers, and the bytes clients
+//! send the local listeners, are untrusted. The library returns errors on
+//! malformed input instead of panicking. It warns on unwraps, expects, panics,
+//! and unchecked indexing, and CI runs clippy with the warnings as errors.
+//! Modules indexing only into structures they built themselves allow the
+//! indexing lint.
+//!
+//! ## Features
+//!
+//! * **`network`** - The `Requestor` and fetching proxies from sources, over
+//!   `reqwest`, unpacking compressed lists and zip archives
+//! * **`judge`** - The `Judge`, which checks proxies against judge services
+//! * **`sleuth`** - The `Sleuth` and the ownership and cloud range lookups
+//! * **`geoip`** - Offline lookups in local `MaxMind` `GeoLite2` or `GeoIP2` databases
+//! * **`manager`** (default) - The `ProxyManager`, its processes, and the scheduler
+//! * **`progress`** (default) - Progress bars on the terminal for bulk checks and fetches
+//! * **`cli`** (default) - The `gatherer` binary and its argument parsing, and
+//!   the platform's data directory
+//! * **`export`** - Writing proxy metadata to CSV files
+//! * **`arrow`** - Arrow record batches and Parquet files of proxy metadata
+//! * **`wayback`** - Harvesting proxies from archived snapshots of sources
+//! * **`tls-profiles`** - TLS fingerprint profiles for judge requests
+//! * **`mimalloc`** - mimalloc as the global allocator of the `gatherer` binary
+//!
+//! The library never sets a global allocator. Applications embedding gooty
+//! that keep the terminal to themselves should turn off default features and
+//! enable `manager` or the narrower features they need.
+//!
+//! Without default features only the data types, storage, and the
+//! standalone `ProxyPool` remain, for rotating through a known list of
+//! proxies without pulling in an HTTP client.
+//!
 //! ## Examples
 //!
 //! ```
@@ -53,13 +85,14 @@
 
 //#![allow(unsafe_code)]
 #![warn(missing_docs)]
+#![warn(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::indexing_slicing
+)]
 #![allow(clippy::multiple_crate_versions)]
 
-use mimalloc::MiMalloc;
-
-#[global_allocator]
-static GLOBAL: MiMalloc = MiMalloc;
-
 pub mod config;
 pub mod definitions;
 pub mod inspection;
@@ -73,14 +106,23 @@ pub use definitions::{
     defaults,
     enums::{AnonymityLevel, ProxyType},
     errors::{ConfigError, ConfigResult, ProxyError, SourceError, SourceResult},
-    proxy::Proxy,
+    proxy::{Proxy, ProxyId},
     source::Source,
+    types::{LatencyMs, Port},
 };
-pub use inspection::{
-    Cidr, IpMetadata, Judge, Location, NetworkInfo, Organization, OwnershipLookup, Sleuth,
-};
-pub use io::{
-    filesystem::{Filestore, FilestoreConfig},
-    http::Requestor,
+#[cfg(feature = "judge")]
+pub use inspection::Judge;
+pub use inspection::{Cidr, IpMetadata, Location, NetworkInfo, Organization};
+#[cfg(feature = "sleuth")]
+pub use inspection::{OwnershipLookup, Sleuth};
+pub use io::filesystem::{Filestore, FilestoreConfig};
+#[cfg(feature = "network")]
+pub use io::http::Requestor;
+#[cfg(feature = "manager")]
+pub use orchestration::manager::{
+    ChurnStats, DryRunChanges, ProxyCriteria, ProxyManager, Session, SessionOverrides,
+    SourceFailureStats, SourceStats, SyncSummary, WarmUpReport,
 };
-pub use orchestration::manager::{ProxyManager, ProxyStats, SourceStats};
+#[cfg(all(feature = "manager", feature = "bundle"))]
+pub use orchestration::manager::InstallSummary;
+pub use orchestration::pool::{ProxyPool, ProxyStats};
@@ -0,0 +1,798 @@
+//! # Coordinator Module
+//!
+//! Spreads judging over several gatherers, so a pool of a million proxies
+//! can be rechecked from many hosts or regions at once.
+//!
+//! ## Overview
+//!
+//! * **`Coordinator`** - Owns the canonical pool and hands its stale proxies
+//!   out to workers as leased jobs
+//! * **`CoordinatorServer`** - Serves a coordinator to workers over HTTP
+//! * **`WorkerClient`** - Claims jobs from a coordinator and reports the
+//!   judged proxies back
+//!
+//! The coordinator answers a small JSON API. Every request carries the
+//! token shared by the coordinator and its workers as
+//! `Authorization: Bearer <token>`, and is refused with 401 without it.
+//!
+//! * `POST /claim` with `{"worker": "eu-1", "max": 200}` leases up to `max`
+//!   proxies, answered with `{"proxies": [...], "finished": false}`
+//! * `POST /report` with `{"worker": "eu-1", "results": [...]}` hands judged
+//!   proxies back, answered with `{"accepted": 199, "rejected": 1}`
+//! * `GET /progress` answers with the `CoordinatorProgress` of the run
+//!
+//! A job not reported within the lease timeout goes back to the queue, so
+//! the jobs of a worker that died are handed to another. Whichever worker
+//! reports a proxy first has its result kept; later reports of the same
+//! proxy are ignored. Workers send whole proxies back, so they judge with
+//! the history the coordinator has, and the coordinator keeps their copies
+//! once it made sure they are plausible: only proxies handed to the worker
+//! are taken, and only if they were judged without their history being
+//! rewritten.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+//! use gooty_proxy::definitions::proxy::Proxy;
+//! use gooty_proxy::definitions::types::Port;
+//! use gooty_proxy::orchestration::coordinator::{Coordinator, CoordinatorServer, WorkerClient};
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let pool: Vec<Proxy> = (1..=3)
+//!         .map(|last| {
+//!             Proxy::new(
+//!                 ProxyType::Http,
+//!                 IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)),
+//!                 Port::new(8080).unwrap(),
+//!                 AnonymityLevel::Elite,
+//!             )
+//!         })
+//!         .collect();
+//!     let coordinator = Coordinator::new(pool, chrono::Duration::hours(1));
+//!     let server = CoordinatorServer::bind("127.0.0.1:0", coordinator, "s3cret").await.unwrap();
+//!     let addr = server.local_addr().unwrap();
+//!     let shared = server.coordinator();
+//!     tokio::spawn(server.run());
+//!
+//!     // Clients without the shared token are turned away
+//!     let intruder = WorkerClient::new(&addr.to_string(), "eve", "guess").unwrap();
+//!     assert!(intruder.claim(10).await.is_err());
+//!
+//!     let worker = WorkerClient::new(&addr.to_string(), "eu-1", "s3cret").unwrap();
+//!     loop {
+//!         let claim = worker.claim(2).await.unwrap();
+//!         if claim.finished {
+//!             break;
+//!         }
+//!         let mut judged = claim.proxies;
+//!         for proxy in &mut judged {
+//!             // A real worker judges the proxies here
+//!             proxy.record_check_failure();
+//!         }
+//!         worker.report(judged).await.unwrap();
+//!     }
+//!
+//!     let progress = worker.progress().await.unwrap();
+//!     assert_eq!((progress.total, progress.completed), (3, 3));
+//!     assert_eq!(progress.workers["eu-1"], 3);
+//!     assert!(shared.lock().unwrap().proxies().iter().all(|p| p.check_count == 1));
+//! }
+//! ```
+
+#[cfg(feature = "network")]
+use crate::definitions::errors::{RequestResult, RequestorError};
+use crate::definitions::{
+    defaults,
+    enums::ProxyLifecycle,
+    errors::{ListenerError, ListenerResult},
+    proxy::{Proxy, ProxyId},
+};
+use crate::io::serving::{RequestHead, read_request_body, read_request_head, token_matches};
+use chrono::Utc;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::time::timeout;
+
+/// A worker asking for jobs, the body of `POST /claim`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimRequest {
+    /// Name of the worker, as shown in the progress of the run
+    pub worker: String,
+
+    /// Largest number of proxies to lease
+    pub max: usize,
+}
+
+/// The jobs leased to a worker, the answer to `POST /claim`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClaimResponse {
+    /// Proxies to judge, none while every remaining job is leased
+    pub proxies: Vec<Proxy>,
+
+    /// Whether every job has been reported, so the worker can stop
+    pub finished: bool,
+}
+
+/// A worker handing judged proxies back, the body of `POST /report`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportRequest {
+    /// Name of the worker
+    pub worker: String,
+
+    /// The judged proxies
+    pub results: Vec<Proxy>,
+}
+
+/// The answer to `POST /report`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportResponse {
+    /// Number of proxies kept, leaving out those another worker reported first
+    pub accepted: usize,
+
+    /// Number of proxies refused as implausible, or never handed to the worker
+    pub rejected: usize,
+}
+
+/// How far a coordinator's run has come, the answer to `GET /progress`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoordinatorProgress {
+    /// Number of proxies to judge in the run
+    pub total: usize,
+
+    /// Proxies waiting to be claimed
+    pub pending: usize,
+
+    /// Proxies claimed and not reported yet
+    pub leased: usize,
+
+    /// Proxies judged and reported
+    pub completed: usize,
+
+    /// Number of proxies each worker reported
+    pub workers: BTreeMap<String, usize>,
+
+    /// Number of reported proxies refused
+    pub rejected: usize,
+}
+
+/// A job claimed by a worker
+#[derive(Debug)]
+struct Lease {
+    /// Name of the worker
+    worker: String,
+
+    /// When the job goes back to the queue
+    expires: Instant,
+}
+
+/// Owns the canonical pool and hands its stale proxies out as jobs
+///
+/// Proxies not judged within the maximum age are queued when the coordinator
+/// is created, leaving out retired ones, just as a local recheck would.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+/// use gooty_proxy::definitions::proxy::Proxy;
+/// use gooty_proxy::definitions::types::Port;
+/// use gooty_proxy::orchestration::coordinator::Coordinator;
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use std::time::Duration;
+///
+/// let proxy = |last: u8| {
+///     Proxy::new(
+///         ProxyType::Http,
+///         IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)),
+///         Port::new(8080).unwrap(),
+///         AnonymityLevel::Elite,
+///     )
+/// };
+/// let mut fresh = proxy(2);
+/// fresh.record_check_failure();
+///
+/// // Leases run out right away, so the jobs of a silent worker are handed on
+/// let mut coordinator = Coordinator::new(vec![proxy(1), fresh], chrono::Duration::hours(1))
+///     .with_lease_timeout(Duration::ZERO);
+/// assert_eq!(coordinator.progress().total, 1);
+///
+/// let mut first = coordinator.claim("eu-1", 10);
+/// let second = coordinator.claim("us-1", 10);
+/// assert_eq!(first, second);
+///
+/// // A proxy that wasn't judged, or wasn't handed to the worker, is refused
+/// assert_eq!(coordinator.report("us-1", second.clone()).rejected, 1);
+/// assert_eq!(coordinator.report("ap-1", first.clone()).rejected, 1);
+///
+/// // The first report is kept, the late one ignored
+/// first[0].record_check_failure();
+/// assert_eq!(coordinator.report("eu-1", first.clone()).accepted, 1);
+/// assert_eq!(coordinator.report("us-1", first).accepted, 0);
+/// assert!(coordinator.is_finished());
+/// assert_eq!(coordinator.proxies()[0].check_count, 1);
+/// ```
+#[derive(Debug)]
+pub struct Coordinator {
+    /// The canonical pool
+    proxies: Vec<Proxy>,
+
+    /// Positions of the proxies in the pool, by identifier
+    index: HashMap<ProxyId, usize>,
+
+    /// Proxies to hand out, in order; entries no longer pending are skipped
+    queue: VecDeque<ProxyId>,
+
+    /// Proxies waiting to be claimed
+    pending: HashSet<ProxyId>,
+
+    /// Proxies claimed and not reported yet
+    leases: HashMap<ProxyId, Lease>,
+
+    /// Workers each proxy not reported yet was handed to
+    handed: HashMap<ProxyId, HashSet<String>>,
+
+    /// Number of proxies to judge in the run
+    total: usize,
+
+    /// Number of proxies each worker reported
+    workers: BTreeMap<String, usize>,
+
+    /// Number of reported proxies refused
+    rejected: usize,
+
+    /// Positions of the proxies reported since they were last taken
+    reported: Vec<usize>,
+
+    /// Time a worker has to report a claimed job
+    lease_timeout: Duration,
+}
+
+impl Coordinator {
+    /// Create a coordinator for a pool, queuing its stale proxies
+    ///
+    /// # Arguments
+    ///
+    /// * `proxies` - The canonical pool
+    /// * `max_age` - How long a check result stays fresh
+    #[must_use]
+    pub fn new(proxies: Vec<Proxy>, max_age: chrono::Duration) -> Self {
+        let cutoff = Utc::now() - max_age;
+        let mut index = HashMap::with_capacity(proxies.len());
+        let mut queue = VecDeque::new();
+        for (i, proxy) in proxies.iter().enumerate() {
+            let key = proxy.id();
+            let stale = proxy.lifecycle != ProxyLifecycle::Retired
+                && proxy.last_tested_at().is_none_or(|at| at < cutoff);
+            if index.insert(key, i).is_none() && stale {
+                queue.push_back(key);
+            }
+        }
+
+        Self {
+            proxies,
+            index,
+            pending: queue.iter().copied().collect(),
+            total: queue.len(),
+            queue,
+            leases: HashMap::new(),
+            handed: HashMap::new(),
+            workers: BTreeMap::new(),
+            rejected: 0,
+            reported: Vec::new(),
+            lease_timeout: Duration::from_secs(defaults::coordinator::LEASE_TIMEOUT_SECS),
+        }
+    }
+
+    /// Set the time a worker has to report a claimed job
+    ///
+    /// # Arguments
+    ///
+    /// * `lease_timeout` - The time allowed, which should cover judging a
+    ///   whole claim
+    #[must_use]
+    pub fn with_lease_timeout(mut self, lease_timeout: Duration) -> Self {
+        self.lease_timeout = lease_timeout;
+        self
+    }
+
+    /// Lease proxies to a worker
+    ///
+    /// Jobs whose lease ran out are queued again first.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - Name of the worker
+    /// * `max` - Largest number of proxies to lease
+    ///
+    /// # Returns
+    ///
+    /// The proxies to judge, none if every remaining job is leased
+    pub fn claim(&mut self, worker: &str, max: usize) -> Vec<Proxy> {
+        let now = Instant::now();
+        let expired: Vec<ProxyId> = self
+            .leases
+            .iter()
+            .filter(|(_, lease)| lease.expires <= now)
+            .map(|(&key, _)| key)
+            .collect();
+        for key in expired {
+            if let Some(lease) = self.leases.remove(&key) {
+                debug!(proxy = key.to_string(), worker = lease.worker.as_str(); "Lease ran out");
+            }
+            self.pending.insert(key);
+            self.queue.push_back(key);
+        }
+
+        let mut claimed = Vec::new();
+        while claimed.len() < max {
+            let Some(key) = self.queue.pop_front() else {
+                break;
+            };
+            if !self.pending.remove(&key) {
+                continue;
+            }
+            let Some(proxy) = self.index.get(&key).and_then(|&i| self.proxies.get(i)) else {
+                continue;
+            };
+            claimed.push(proxy.clone());
+            self.handed
+                .entry(key)
+                .or_default()
+                .insert(worker.to_string());
+            let lease = Lease {
+                worker: worker.to_string(),
+                expires: now + self.lease_timeout,
+            };
+            self.leases.insert(key, lease);
+        }
+        claimed
+    }
+
+    /// Take judged proxies back from a worker
+    ///
+    /// A proxy is kept if it is still leased or waiting to be claimed again,
+    /// was handed to the worker, and is a plausible judgement of the stored
+    /// proxy: judged at least once more, with its history and provenance
+    /// left as they were. Proxies already reported, or not part of the run,
+    /// are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - Name of the worker
+    /// * `results` - The judged proxies
+    ///
+    /// # Returns
+    ///
+    /// The number of proxies kept and refused
+    pub fn report(&mut self, worker: &str, results: Vec<Proxy>) -> ReportResponse {
+        let mut response = ReportResponse::default();
+        for proxy in results {
+            let key = proxy.id();
+            if !self.leases.contains_key(&key) && !self.pending.contains(&key) {
+                continue;
+            }
+            let Some(&i) = self.index.get(&key) else {
+                continue;
+            };
+
+            let handed = self
+                .handed
+                .get(&key)
+                .is_some_and(|workers| workers.contains(worker));
+            let plausible = self
+                .proxies
+                .get(i)
+                .is_some_and(|stored| is_plausible_judgement(stored, &proxy));
+            if !handed || !plausible {
+                warn!(proxy = proxy.redacted_id(), worker = worker; "Refused reported proxy");
+                response.rejected += 1;
+                continue;
+            }
+
+            self.leases.remove(&key);
+            self.pending.remove(&key);
+            self.handed.remove(&key);
+            if let Some(slot) = self.proxies.get_mut(i) {
+                *slot = proxy;
+                self.reported.push(i);
+                response.accepted += 1;
+            }
+        }
+
+        *self.workers.entry(worker.to_string()).or_default() += response.accepted;
+        self.rejected += response.rejected;
+        response
+    }
+
+    /// Checks whether every job has been reported
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_empty() && self.leases.is_empty()
+    }
+
+    /// Get how far the run has come
+    #[must_use]
+    pub fn progress(&self) -> CoordinatorProgress {
+        CoordinatorProgress {
+            total: self.total,
+            pending: self.pending.len(),
+            leased: self.leases.len(),
+            completed: self.workers.values().sum(),
+            workers: self.workers.clone(),
+            rejected: self.rejected,
+        }
+    }
+
+    /// Get the canonical pool, with the results reported so far
+    #[must_use]
+    pub fn proxies(&self) -> &[Proxy] {
+        &self.proxies
+    }
+
+    /// Take the proxies kept since the last call, to persist them as they come in
+    pub fn take_reported(&mut self) -> Vec<Proxy> {
+        std::mem::take(&mut self.reported)
+            .into_iter()
+            .filter_map(|i| self.proxies.get(i).cloned())
+            .collect()
+    }
+
+    /// Take the canonical pool, with the results reported so far
+    #[must_use]
+    pub fn into_proxies(self) -> Vec<Proxy> {
+        self.proxies
+    }
+}
+
+/// Checks whether a reported proxy is a judgement of the stored one
+///
+/// The report must have been judged after the stored proxy was, not in the
+/// future, and keep the stored history and provenance.
+fn is_plausible_judgement(stored: &Proxy, reported: &Proxy) -> bool {
+    let skew = chrono::Duration::seconds(defaults::coordinator::MAX_CLOCK_SKEW_SECS);
+    let judged_at_plausible = reported.last_checked_at.is_some_and(|at| {
+        at <= Utc::now() + skew && stored.last_checked_at.is_none_or(|before| at > before)
+    });
+
+    reported.validate().is_ok()
+        && judged_at_plausible
+        && reported.check_count > stored.check_count
+        && reported.check_failure_count >= stored.check_failure_count
+        && reported.check_failure_count <= reported.check_count
+        && reported.added_at == stored.added_at
+        && reported.source == stored.source
+        && reported.use_count == stored.use_count
+        && reported.use_failure_count == stored.use_failure_count
+}
+
+/// Serves a coordinator to workers over HTTP
+///
+/// Every connection carries a single request, see the module documentation
+/// for the endpoints. Requests without the shared token are answered with
+/// 401 before their body is read. Errors are answered with `{"error": "..."}`.
+#[derive(Debug)]
+pub struct CoordinatorServer {
+    /// The listening socket
+    listener: TcpListener,
+
+    /// The coordinator, shared with the connections
+    coordinator: Arc<Mutex<Coordinator>>,
+
+    /// The token workers send as `Authorization: Bearer <token>`
+    token: Arc<str>,
+}
+
+impl CoordinatorServer {
+    /// Listen for workers on an address
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The address to listen on, such as `coordinator::DEFAULT_ADDRESS`
+    /// * `coordinator` - The coordinator to serve
+    /// * `token` - The token shared with the workers
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token is empty or the address can't be bound
+    pub async fn bind(
+        addr: impl ToSocketAddrs,
+        coordinator: Coordinator,
+        token: &str,
+    ) -> ListenerResult<Self> {
+        if token.is_empty() {
+            return Err(ListenerError::Unsupported(
+                "serving workers without a token".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            listener: TcpListener::bind(addr).await?,
+            coordinator: Arc::new(Mutex::new(coordinator)),
+            token: Arc::from(token),
+        })
+    }
+
+    /// Get the address the server listens on
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket's address can't be read
+    pub fn local_addr(&self) -> ListenerResult<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Get the coordinator, to follow the run and save its pool while the server runs
+    #[must_use]
+    pub fn coordinator(&self) -> Arc<Mutex<Coordinator>> {
+        Arc::clone(&self.coordinator)
+    }
+
+    /// Serve workers until accepting one fails
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listening socket fails. Failures of single
+    /// requests are logged and don't stop the server.
+    pub async fn run(self) -> ListenerResult<()> {
+        loop {
+            let (client, peer) = self.listener.accept().await?;
+            let coordinator = Arc::clone(&self.coordinator);
+            let token = Arc::clone(&self.token);
+            tokio::spawn(async move {
+                if let Err(e) = serve_client(client, &coordinator, &token).await {
+                    debug!(peer = peer.to_string(); "Coordinator request failed: {e}");
+                }
+            });
+        }
+    }
+}
+
+/// Answer a single request of a worker
+async fn serve_client(
+    mut client: TcpStream,
+    coordinator: &Mutex<Coordinator>,
+    token: &str,
+) -> ListenerResult<()> {
+    let request_timeout = Duration::from_secs(defaults::coordinator::REQUEST_TIMEOUT_SECS);
+    let request = timeout(request_timeout, read_request(&mut client, token))
+        .await
+        .map_err(|_| ListenerError::Timeout("waiting for the request".to_string()));
+
+    let (status, body) = match request {
+        Ok(Ok(Some((head, body)))) => route(coordinator, &head, &body),
+        Ok(Ok(None)) => error_body("401 Unauthorized", "missing or wrong token"),
+        Ok(Err(ListenerError::HandshakeError(e))) => error_body("400 Bad Request", &e),
+        Ok(Err(e)) | Err(e) => return Err(e),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    client.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Read a request, none if it doesn't carry the token
+async fn read_request(
+    client: &mut TcpStream,
+    token: &str,
+) -> ListenerResult<Option<(RequestHead, Vec<u8>)>> {
+    let head = read_request_head(client).await?;
+    let authorized = head
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|sent| token_matches(sent.trim(), token));
+    if !authorized {
+        return Ok(None);
+    }
+
+    let body = read_request_body(client, &head, defaults::coordinator::MAX_REQUEST_BODY).await?;
+    Ok(Some((head, body)))
+}
+
+/// Answer a request with a status and a JSON body
+fn route(
+    coordinator: &Mutex<Coordinator>,
+    head: &RequestHead,
+    body: &[u8],
+) -> (&'static str, String) {
+    let lock = || coordinator.lock().unwrap_or_else(PoisonError::into_inner);
+
+    match (head.method.as_str(), head.path.as_str()) {
+        ("POST", "/claim") => match serde_json::from_slice::<ClaimRequest>(body) {
+            Ok(claim) => {
+                let mut coordinator = lock();
+                let proxies = coordinator.claim(&claim.worker, claim.max);
+                let finished = proxies.is_empty() && coordinator.is_finished();
+                drop(coordinator);
+                json_body(&ClaimResponse { proxies, finished })
+            }
+            Err(e) => error_body("400 Bad Request", &e.to_string()),
+        },
+        ("POST", "/report") => match serde_json::from_slice::<ReportRequest>(body) {
+            Ok(report) => json_body(&lock().report(&report.worker, report.results)),
+            Err(e) => error_body("400 Bad Request", &e.to_string()),
+        },
+        ("GET", "/progress") => json_body(&lock().progress()),
+        (_, "/claim" | "/report" | "/progress") => {
+            error_body("405 Method Not Allowed", "method not allowed")
+        }
+        _ => error_body("404 Not Found", "not found"),
+    }
+}
+
+/// A successful answer carrying a value as JSON
+fn json_body(value: &impl Serialize) -> (&'static str, String) {
+    match serde_json::to_string(value) {
+        Ok(body) => ("200 OK", body),
+        Err(e) => error_body("500 Internal Server Error", &e.to_string()),
+    }
+}
+
+/// An answer carrying an error message as JSON
+fn error_body(status: &'static str, message: &str) -> (&'static str, String) {
+    (status, serde_json::json!({ "error": message }).to_string())
+}
+
+/// Claims jobs from a coordinator and reports the judged proxies back
+///
+/// The client only talks to the coordinator; judging the claimed proxies is
+/// left to the caller, such as `ProxyManager::check_all_proxies`.
+#[cfg(feature = "network")]
+#[derive(Debug, Clone)]
+pub struct WorkerClient {
+    /// Client for the coordinator's API
+    client: reqwest::Client,
+
+    /// URL of the coordinator, without a trailing slash
+    base_url: String,
+
+    /// Name of the worker
+    name: String,
+
+    /// The token shared with the coordinator
+    token: String,
+}
+
+#[cfg(feature = "network")]
+impl WorkerClient {
+    /// Create a client for a coordinator
+    ///
+    /// # Arguments
+    ///
+    /// * `coordinator` - Address or URL of the coordinator, `http://` is
+    ///   assumed without a scheme
+    /// * `name` - Name of the worker, as shown in the progress of the run
+    /// * `token` - The token shared with the coordinator
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client can't be built
+    pub fn new(coordinator: &str, name: &str, token: &str) -> RequestResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(
+                defaults::coordinator::REQUEST_TIMEOUT_SECS,
+            ))
+            .no_proxy()
+            .build()?;
+        let base_url = if coordinator.contains("://") {
+            coordinator.trim_end_matches('/').to_string()
+        } else {
+            format!("http://{}", coordinator.trim_end_matches('/'))
+        };
+
+        Ok(Self {
+            client,
+            base_url,
+            name: name.to_string(),
+            token: token.to_string(),
+        })
+    }
+
+    /// Get the name the worker reports under
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Lease proxies to judge
+    ///
+    /// # Arguments
+    ///
+    /// * `max` - Largest number of proxies to lease
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinator can't be reached or refuses the request
+    pub async fn claim(&self, max: usize) -> RequestResult<ClaimResponse> {
+        let request = ClaimRequest {
+            worker: self.name.clone(),
+            max,
+        };
+        read_json(
+            self.client
+                .post(self.url("/claim"))
+                .bearer_auth(&self.token)
+                .json(&request)
+                .send()
+                .await?,
+        )
+        .await
+    }
+
+    /// Hand judged proxies back
+    ///
+    /// # Arguments
+    ///
+    /// * `results` - The judged proxies
+    ///
+    /// # Returns
+    ///
+    /// The number of proxies the coordinator kept and refused
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinator can't be reached or refuses the request
+    pub async fn report(&self, results: Vec<Proxy>) -> RequestResult<ReportResponse> {
+        let request = ReportRequest {
+            worker: self.name.clone(),
+            results,
+        };
+        read_json(
+            self.client
+                .post(self.url("/report"))
+                .bearer_auth(&self.token)
+                .json(&request)
+                .send()
+                .await?,
+        )
+        .await
+    }
+
+    /// Get how far the coordinator's run has come
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinator can't be reached or refuses the request
+    pub async fn progress(&self) -> RequestResult<CoordinatorProgress> {
+        read_json(
+            self.client
+                .get(self.url("/progress"))
+                .bearer_auth(&self.token)
+                .send()
+                .await?,
+        )
+        .await
+    }
+
+    /// Build the URL of an endpoint
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+}
+
+/// Read a JSON answer of the coordinator, failing on error statuses
+#[cfg(feature = "network")]
+async fn read_json<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> RequestResult<T> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(RequestorError::StatusError(status, body));
+    }
+    Ok(response.json().await?)
+}
@@ -0,0 +1,448 @@
+//! # Events Module
+//!
+//! Streams what a gather run does as it happens, so web UIs can show live
+//! progress without polling the stored results.
+//!
+//! ## Overview
+//!
+//! * **`GatherEvent`** - A source fetched, a proxy checked or enriched, or a
+//!   stage of the run starting or finishing
+//! * **`EventBus`** - Hands the events of a run to every subscriber
+//! * **`EventServer`** - Serves the events over HTTP as Server-Sent Events
+//!
+//! The bulk operations of `processes` publish to the bus given to them, and
+//! a `ProxyManager` hands its own bus to them. Publishing costs next to
+//! nothing while nobody subscribes. A subscriber that falls too far behind
+//! misses the oldest events rather than holding the run up.
+//!
+//! Browsers subscribe with `new EventSource("http://127.0.0.1:8090/events")`.
+//! Every event arrives with its type as the SSE event name and its fields as
+//! JSON data.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::orchestration::events::{EventBus, EventServer, GatherEvent, GatherStage};
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//! use tokio::net::TcpStream;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let bus = EventBus::default();
+//!     let server = EventServer::bind("127.0.0.1:0", bus.clone()).await.unwrap();
+//!     let addr = server.local_addr().unwrap();
+//!     tokio::spawn(server.run());
+//!
+//!     let mut client = TcpStream::connect(addr).await.unwrap();
+//!     client.write_all(b"GET /events HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+//!     let mut head = Vec::new();
+//!     while !head.ends_with(b"\r\n\r\n") {
+//!         head.push(client.read_u8().await.unwrap());
+//!     }
+//!     assert!(String::from_utf8(head).unwrap().contains("text/event-stream"));
+//!
+//!     bus.publish(GatherEvent::StageStarted { stage: GatherStage::Check, total: 12 });
+//!
+//!     let mut event = Vec::new();
+//!     while !event.ends_with(b"\n\n") {
+//!         event.push(client.read_u8().await.unwrap());
+//!     }
+//!     assert_eq!(
+//!         String::from_utf8(event).unwrap(),
+//!         "event: stage_started\ndata: {\"type\":\"stage_started\",\"stage\":\"check\",\"total\":12}\n\n"
+//!     );
+//! }
+//! ```
+
+use crate::definitions::{
+    defaults,
+    enums::AnonymityLevel,
+    errors::{ListenerError, ListenerResult},
+    proxy::Proxy,
+    types::LatencyMs,
+};
+use crate::io::serving::read_request_head;
+use log::debug;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{MissedTickBehavior, timeout};
+
+/// The stages of a gather run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatherStage {
+    /// Fetching proxies from sources
+    Fetch,
+
+    /// Judging proxies
+    Check,
+
+    /// Looking up the IP metadata of proxies
+    Enrich,
+}
+
+/// Something that happened during a gather run
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+/// use gooty_proxy::definitions::proxy::Proxy;
+/// use gooty_proxy::definitions::types::Port;
+/// use gooty_proxy::orchestration::events::GatherEvent;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let proxy = Proxy::new(
+///     ProxyType::Http,
+///     IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)),
+///     Port::new(8080).unwrap(),
+///     AnonymityLevel::Elite,
+/// );
+/// let event = GatherEvent::checked(&proxy, false);
+/// assert_eq!(event.name(), "proxy_checked");
+/// assert_eq!(
+///     serde_json::to_string(&event).unwrap(),
+///     r#"{"type":"proxy_checked","proxy":"http://203.0.113.7:8080","working":false,"anonymity":"Elite","latency_ms":null}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GatherEvent {
+    /// A stage started working through its items
+    StageStarted {
+        /// The stage that started
+        stage: GatherStage,
+
+        /// Number of sources or proxies the stage works through
+        total: usize,
+    },
+
+    /// A source was fetched
+    SourceFetched {
+        /// URL of the source
+        source: String,
+
+        /// Number of proxies found
+        proxies: usize,
+
+        /// Why the fetch failed, if it did
+        error: Option<String>,
+    },
+
+    /// A proxy was judged
+    ProxyChecked {
+        /// Scheme, address and port of the proxy, without credentials
+        proxy: String,
+
+        /// Whether the judgement succeeded
+        working: bool,
+
+        /// Anonymity level of the proxy after the judgement
+        anonymity: AnonymityLevel,
+
+        /// Latency of the proxy, if measured
+        latency_ms: Option<LatencyMs>,
+    },
+
+    /// The IP metadata of a proxy was looked up
+    ProxyEnriched {
+        /// Scheme, address and port of the proxy, without credentials
+        proxy: String,
+
+        /// Whether the lookup succeeded
+        found: bool,
+
+        /// Country of the proxy, if known
+        country: Option<String>,
+
+        /// Autonomous system of the proxy, if known
+        asn: Option<String>,
+    },
+
+    /// A stage finished working through its items
+    StageFinished {
+        /// The stage that finished
+        stage: GatherStage,
+
+        /// Number of sources or proxies the stage worked through
+        total: usize,
+
+        /// Number of them that succeeded
+        succeeded: usize,
+    },
+}
+
+impl GatherEvent {
+    /// Describes the judgement of a proxy
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy after its judgement
+    /// * `working` - Whether the judgement succeeded
+    #[must_use]
+    pub fn checked(proxy: &Proxy, working: bool) -> Self {
+        GatherEvent::ProxyChecked {
+            proxy: proxy.redacted_id(),
+            working,
+            anonymity: proxy.anonymity,
+            latency_ms: proxy.latency_ms,
+        }
+    }
+
+    /// Describes the metadata lookup of a proxy
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy after its lookup
+    /// * `found` - Whether the lookup succeeded
+    #[must_use]
+    pub fn enriched(proxy: &Proxy, found: bool) -> Self {
+        GatherEvent::ProxyEnriched {
+            proxy: proxy.redacted_id(),
+            found,
+            country: proxy.country.clone(),
+            asn: proxy.asn.clone(),
+        }
+    }
+
+    /// Returns the type of the event, as named in its JSON
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            GatherEvent::StageStarted { .. } => "stage_started",
+            GatherEvent::SourceFetched { .. } => "source_fetched",
+            GatherEvent::ProxyChecked { .. } => "proxy_checked",
+            GatherEvent::ProxyEnriched { .. } => "proxy_enriched",
+            GatherEvent::StageFinished { .. } => "stage_finished",
+        }
+    }
+}
+
+/// Hands the events of gather runs to every subscriber
+///
+/// Clones publish to the same subscribers.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::events::{EventBus, GatherEvent, GatherStage};
+///
+/// let bus = EventBus::default();
+/// let mut events = bus.subscribe();
+/// assert!(bus.is_watched());
+///
+/// bus.publish(GatherEvent::StageStarted { stage: GatherStage::Fetch, total: 3 });
+/// assert_eq!(
+///     events.try_recv().unwrap(),
+///     GatherEvent::StageStarted { stage: GatherStage::Fetch, total: 3 }
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    /// The sending half of the channel every subscriber receives from
+    sender: broadcast::Sender<GatherEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(defaults::events::CHANNEL_CAPACITY)
+    }
+}
+
+impl EventBus {
+    /// Creates a bus without subscribers
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - Number of events a subscriber may fall behind before missing the oldest
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        EventBus { sender }
+    }
+
+    /// Hands an event to every current subscriber
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to publish
+    pub fn publish(&self, event: GatherEvent) {
+        // Nobody subscribing isn't an error, the event is just dropped
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to the events published from now on
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<GatherEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Checks whether anybody subscribes to the bus
+    #[must_use]
+    pub fn is_watched(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+}
+
+/// Serves the events of a bus over HTTP as Server-Sent Events
+///
+/// Clients `GET /events` and receive every event published while they stay
+/// connected. Other paths are answered with 404. A comment line keeps idle
+/// connections open through proxies and load balancers.
+///
+/// Browsers only let pages of the server's own origin read the stream,
+/// unless another origin is allowed with [`EventServer::with_allowed_origin`].
+#[derive(Debug)]
+pub struct EventServer {
+    /// The listening socket
+    listener: TcpListener,
+
+    /// The bus whose events are served
+    bus: EventBus,
+
+    /// Time between comment lines on an idle stream
+    keep_alive: Duration,
+
+    /// Origin of web UIs allowed to read the stream, if any besides the server's own
+    allowed_origin: Option<String>,
+}
+
+impl EventServer {
+    /// Listen for event stream clients on an address
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The address to listen on, such as `events::DEFAULT_ADDRESS`
+    /// * `bus` - The bus whose events are served
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address can't be bound
+    pub async fn bind(addr: impl ToSocketAddrs, bus: EventBus) -> ListenerResult<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr).await?,
+            bus,
+            keep_alive: Duration::from_secs(defaults::events::KEEP_ALIVE_SECS),
+            allowed_origin: None,
+        })
+    }
+
+    /// Allow a web UI served from another origin to read the stream
+    ///
+    /// Sent as `Access-Control-Allow-Origin`. Allowing `*` lets any page the
+    /// browser opens read the events, so prefer the UI's exact origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin` - The origin allowed, such as `https://dashboard.example`
+    #[must_use]
+    pub fn with_allowed_origin(mut self, origin: &str) -> Self {
+        self.allowed_origin = Some(origin.to_string());
+        self
+    }
+
+    /// Set the time between comment lines on an idle stream
+    ///
+    /// # Arguments
+    ///
+    /// * `keep_alive` - Time a stream may stay silent
+    #[must_use]
+    pub fn with_keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Get the address the server listens on
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket's address can't be read
+    pub fn local_addr(&self) -> ListenerResult<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serve clients until accepting one fails
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the listening socket fails. Failures of single
+    /// clients are logged and don't stop the server.
+    pub async fn run(self) -> ListenerResult<()> {
+        loop {
+            let (client, peer) = self.listener.accept().await?;
+            // Subscribe right away, so nothing published meanwhile is missed
+            let events = self.bus.subscribe();
+            let keep_alive = self.keep_alive;
+            let allowed_origin = self.allowed_origin.clone();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    serve_client(client, events, keep_alive, allowed_origin.as_deref()).await
+                {
+                    debug!(peer = peer.to_string(); "Event stream ended: {e}");
+                }
+            });
+        }
+    }
+}
+
+/// Answer a client's request, streaming events until either side stops
+async fn serve_client(
+    mut client: TcpStream,
+    mut events: broadcast::Receiver<GatherEvent>,
+    keep_alive: Duration,
+    allowed_origin: Option<&str>,
+) -> ListenerResult<()> {
+    let request_timeout = Duration::from_secs(defaults::events::REQUEST_TIMEOUT_SECS);
+    let head = timeout(request_timeout, read_request_head(&mut client))
+        .await
+        .map_err(|_| ListenerError::Timeout("waiting for the request".to_string()))??;
+
+    if head.method != "GET" || head.path != "/events" {
+        let status = if head.method == "GET" {
+            "404 Not Found"
+        } else {
+            "405 Method Not Allowed"
+        };
+        let response =
+            format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        client.write_all(response.as_bytes()).await?;
+        return Ok(());
+    }
+
+    let cors = allowed_origin
+        .map(|origin| format!("Access-Control-Allow-Origin: {origin}\r\n"))
+        .unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: text/event-stream\r\n\
+         Cache-Control: no-cache\r\n\
+         Connection: keep-alive\r\n\
+         {cors}\r\n"
+    );
+    client.write_all(response.as_bytes()).await?;
+
+    let mut idle = tokio::time::interval(keep_alive.max(Duration::from_millis(1)));
+    idle.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    idle.tick().await;
+
+    loop {
+        let message = tokio::select! {
+            received = events.recv() => match received {
+                Ok(event) => {
+                    let data = serde_json::to_string(&event)
+                        .map_err(|e| ListenerError::IoError(e.to_string()))?;
+                    format!("event: {}\ndata: {data}\n\n", event.name())
+                }
+                Err(RecvError::Lagged(missed)) => format!(": missed {missed} events\n\n"),
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            _ = idle.tick() => ": keep-alive\n\n".to_string(),
+        };
+        client.write_all(message.as_bytes()).await?;
+        idle.reset();
+    }
+}
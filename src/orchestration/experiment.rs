@@ -0,0 +1,434 @@
+//! # Experiment Module
+//!
+//! Provides A/B testing between subsets of the proxy pool.
+//!
+//! ## Overview
+//!
+//! An `Experiment` splits the pool into named cohorts, each defined by a filter
+//! over proxies (commonly a tag such as the provider name) and a traffic weight.
+//! Proxies are handed out from cohorts in proportion to their weights, and the
+//! outcomes reported back through `ProxyManager::report_use` are accumulated per
+//! cohort in fixed time windows. Comparing the cohorts answers questions like
+//! whether a paid provider outperforms scraped proxies for a given target.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::{
+//!     enums::{AnonymityLevel, ProxyType},
+//!     proxy::Proxy,
+//!     types::{LatencyMs, Port},
+//! };
+//! use gooty_proxy::orchestration::experiment::Experiment;
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! let mut experiment = Experiment::new("paid-vs-scraped")
+//!     .with_tagged_cohort("paid", 1, "paid")
+//!     .with_cohort("scraped", 3, |p: &Proxy| !p.has_tag("paid"));
+//!
+//! let paid = Proxy::new(
+//!     ProxyType::Http,
+//!     IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+//!     Port::new(8080).unwrap(),
+//!     AnonymityLevel::Elite,
+//! )
+//! .with_tag("paid");
+//!
+//! experiment.record(&paid, true, Some(LatencyMs::from(120)));
+//! experiment.record(&paid, false, None);
+//!
+//! let report = experiment.report();
+//! assert_eq!(report.cohorts[0].uses, 2);
+//! assert_eq!(report.cohorts[0].successes, 1);
+//! assert_eq!(report.cohorts[0].avg_latency_ms, Some(LatencyMs::from(120)));
+//! assert_eq!(report.cohorts[1].uses, 0);
+//! ```
+
+use crate::definitions::{defaults::experiment as defaults, proxy::Proxy, types::LatencyMs};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, fmt, sync::Arc};
+
+/// Predicate deciding whether a proxy belongs to a cohort.
+pub type CohortFilter = Arc<dyn Fn(&Proxy) -> bool + Send + Sync>;
+
+/// Outcomes recorded for a cohort during one time window.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CohortWindow {
+    /// Start of the window
+    pub started_at: DateTime<Utc>,
+
+    /// Number of uses reported in the window
+    pub uses: usize,
+
+    /// Number of successful uses reported in the window
+    pub successes: usize,
+}
+
+impl CohortWindow {
+    /// Calculates the success rate of the window as a percentage.
+    #[must_use]
+    pub fn success_rate(&self) -> usize {
+        if self.uses == 0 {
+            return 0;
+        }
+        100 * self.successes / self.uses
+    }
+}
+
+/// A named subset of the proxy pool taking part in an experiment.
+#[derive(Clone)]
+pub struct Cohort {
+    /// Name of the cohort
+    name: String,
+
+    /// Relative share of traffic routed to the cohort
+    weight: u32,
+
+    /// Predicate selecting the cohort's proxies
+    filter: CohortFilter,
+
+    /// Total number of uses reported
+    uses: usize,
+
+    /// Total number of successful uses reported
+    successes: usize,
+
+    /// Sum of reported latencies in milliseconds
+    latency_sum_ms: u128,
+
+    /// Number of reported latencies
+    latency_samples: u128,
+
+    /// Outcomes bucketed by time window, oldest first
+    windows: VecDeque<CohortWindow>,
+}
+
+impl fmt::Debug for Cohort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cohort")
+            .field("name", &self.name)
+            .field("weight", &self.weight)
+            .field("uses", &self.uses)
+            .field("successes", &self.successes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Cohort {
+    /// Returns the name of the cohort.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the relative share of traffic routed to the cohort.
+    #[must_use]
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+
+    /// Checks whether a proxy belongs to the cohort.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to test
+    ///
+    /// # Returns
+    ///
+    /// `true` if the cohort's filter accepts the proxy.
+    #[must_use]
+    pub fn contains(&self, proxy: &Proxy) -> bool {
+        (self.filter)(proxy)
+    }
+}
+
+/// Summary of one cohort's results.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CohortReport {
+    /// Name of the cohort
+    pub name: String,
+
+    /// Relative share of traffic routed to the cohort
+    pub weight: u32,
+
+    /// Total number of uses reported
+    pub uses: usize,
+
+    /// Total number of successful uses reported
+    pub successes: usize,
+
+    /// Overall success rate as a percentage
+    pub success_rate: usize,
+
+    /// Average reported latency
+    pub avg_latency_ms: Option<LatencyMs>,
+
+    /// Outcomes bucketed by time window, oldest first
+    pub windows: Vec<CohortWindow>,
+}
+
+/// Summary of an experiment's results.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExperimentReport {
+    /// Name of the experiment
+    pub name: String,
+
+    /// When the experiment started
+    pub started_at: DateTime<Utc>,
+
+    /// Per-cohort results, in the order the cohorts were added
+    pub cohorts: Vec<CohortReport>,
+}
+
+impl ExperimentReport {
+    /// Returns the cohort with the highest success rate among those with reported uses.
+    #[must_use]
+    pub fn leader(&self) -> Option<&CohortReport> {
+        self.cohorts
+            .iter()
+            .filter(|c| c.uses > 0)
+            .max_by_key(|c| c.success_rate)
+    }
+}
+
+/// An A/B test between subsets of the proxy pool.
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    /// Name of the experiment
+    name: String,
+
+    /// Participating cohorts
+    cohorts: Vec<Cohort>,
+
+    /// Length of each time window in seconds
+    window_secs: i64,
+
+    /// Maximum number of windows kept per cohort
+    max_windows: usize,
+
+    /// When the experiment started
+    started_at: DateTime<Utc>,
+}
+
+impl Experiment {
+    /// Creates an experiment without cohorts.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the experiment
+    ///
+    /// # Returns
+    ///
+    /// A new `Experiment` using the default window settings.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Experiment {
+            name: name.to_string(),
+            cohorts: Vec::new(),
+            window_secs: defaults::WINDOW_SECS,
+            max_windows: defaults::MAX_WINDOWS,
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Sets the length of the time windows outcomes are bucketed into.
+    ///
+    /// # Arguments
+    ///
+    /// * `window_secs` - Window length in seconds, at least one
+    ///
+    /// # Returns
+    ///
+    /// Self with the window length set.
+    #[must_use]
+    pub fn with_window(mut self, window_secs: i64) -> Self {
+        self.window_secs = window_secs.max(1);
+        self
+    }
+
+    /// Adds a cohort selected by an arbitrary filter.
+    ///
+    /// A proxy matching several cohorts is counted towards the first one added.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the cohort
+    /// * `weight` - Relative share of traffic routed to the cohort
+    /// * `filter` - Predicate selecting the cohort's proxies
+    ///
+    /// # Returns
+    ///
+    /// Self with the cohort added.
+    #[must_use]
+    pub fn with_cohort<F>(mut self, name: &str, weight: u32, filter: F) -> Self
+    where
+        F: Fn(&Proxy) -> bool + Send + Sync + 'static,
+    {
+        self.cohorts.push(Cohort {
+            name: name.to_string(),
+            weight,
+            filter: Arc::new(filter),
+            uses: 0,
+            successes: 0,
+            latency_sum_ms: 0,
+            latency_samples: 0,
+            windows: VecDeque::new(),
+        });
+        self
+    }
+
+    /// Adds a cohort made of the proxies carrying a tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the cohort
+    /// * `weight` - Relative share of traffic routed to the cohort
+    /// * `tag` - The tag selecting the cohort's proxies
+    ///
+    /// # Returns
+    ///
+    /// Self with the cohort added.
+    #[must_use]
+    pub fn with_tagged_cohort(self, name: &str, weight: u32, tag: &str) -> Self {
+        let tag = tag.to_string();
+        self.with_cohort(name, weight, move |p: &Proxy| p.has_tag(&tag))
+    }
+
+    /// Returns the name of the experiment.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the participating cohorts.
+    #[must_use]
+    pub fn cohorts(&self) -> &[Cohort] {
+        &self.cohorts
+    }
+
+    /// Picks a cohort at random in proportion to the cohort weights.
+    ///
+    /// # Returns
+    ///
+    /// The chosen cohort, or `None` if no cohort has a positive weight.
+    #[must_use]
+    pub fn select_cohort(&self) -> Option<&Cohort> {
+        let total: u64 = self.cohorts.iter().map(|c| u64::from(c.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+
+        let mut point = rand::rng().random_range(0..total);
+        for cohort in &self.cohorts {
+            let weight = u64::from(cohort.weight);
+            if point < weight {
+                return Some(cohort);
+            }
+            point -= weight;
+        }
+        None
+    }
+
+    /// Returns the cohort a proxy is counted towards.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to classify
+    ///
+    /// # Returns
+    ///
+    /// The first cohort whose filter accepts the proxy, if any.
+    #[must_use]
+    pub fn cohort_of(&self, proxy: &Proxy) -> Option<&Cohort> {
+        self.cohorts.iter().find(|c| c.contains(proxy))
+    }
+
+    /// Records the outcome of a use of a proxy.
+    ///
+    /// Outcomes for proxies outside every cohort are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy that was used
+    /// * `success` - Whether the use succeeded
+    /// * `latency_ms` - Observed latency of the use, if measured
+    ///
+    /// # Returns
+    ///
+    /// `true` if the outcome was counted towards a cohort.
+    pub fn record(&mut self, proxy: &Proxy, success: bool, latency_ms: Option<LatencyMs>) -> bool {
+        let Some(cohort) = self.cohorts.iter_mut().find(|c| c.contains(proxy)) else {
+            return false;
+        };
+
+        cohort.uses += 1;
+        if success {
+            cohort.successes += 1;
+        }
+        if let Some(latency) = latency_ms {
+            cohort.latency_sum_ms += latency.get();
+            cohort.latency_samples += 1;
+        }
+
+        let now = Utc::now();
+        let window_start = now.timestamp() - now.timestamp().rem_euclid(self.window_secs);
+        let started_at = DateTime::from_timestamp(window_start, 0).unwrap_or(now);
+
+        if cohort
+            .windows
+            .back()
+            .is_none_or(|w| w.started_at != started_at)
+        {
+            cohort.windows.push_back(CohortWindow {
+                started_at,
+                uses: 0,
+                successes: 0,
+            });
+            while cohort.windows.len() > self.max_windows {
+                cohort.windows.pop_front();
+            }
+        }
+
+        if let Some(window) = cohort.windows.back_mut() {
+            window.uses += 1;
+            if success {
+                window.successes += 1;
+            }
+        }
+
+        true
+    }
+
+    /// Summarises the results gathered so far.
+    ///
+    /// # Returns
+    ///
+    /// An `ExperimentReport` with one entry per cohort.
+    #[must_use]
+    pub fn report(&self) -> ExperimentReport {
+        let cohorts = self
+            .cohorts
+            .iter()
+            .map(|c| CohortReport {
+                name: c.name.clone(),
+                weight: c.weight,
+                uses: c.uses,
+                successes: c.successes,
+                success_rate: (100 * c.successes).checked_div(c.uses).unwrap_or(0),
+                avg_latency_ms: c
+                    .latency_sum_ms
+                    .checked_div(c.latency_samples)
+                    .map(LatencyMs::from),
+                windows: c.windows.iter().cloned().collect(),
+            })
+            .collect();
+
+        ExperimentReport {
+            name: self.name.clone(),
+            started_at: self.started_at,
+            cohorts,
+        }
+    }
+}
@@ -0,0 +1,723 @@
+//! # Listener Module
+//!
+//! Serves the pool as a local proxy, so tools can use it without knowing about
+//! gooty at all.
+//!
+//! ## Overview
+//!
+//! * **`SocksListener`** - Accepts SOCKS5 clients and relays each connection
+//!   through a proxy of the pool
+//! * **`UpstreamPool`** - The proxies a listener rotates through, a
+//!   `ProxyPool` with cooldowns for proxies that failed
+//! * **`ClientStats`** - What a single client used the listener for
+//! * **`PortWatcher`** - Probes the ports of the proxies most likely to be
+//!   selected, taking proxies that went down out of rotation
+//!
+//! Every client connection is relayed through a proxy selected in proportion
+//! to its `proxy_weight`, so fast and reliable proxies carry most connections
+//! without starving the rest. A proxy that fails is cooled down for
+//! `rotation::FAILURE_COOLDOWN_SECS` and not selected meanwhile, while a
+//! proxy that answers but refuses a target is only banned from that host,
+//! for `rotation::TARGET_BAN_SECS`.
+//!
+//! When an upstream proxy fails before the tunnel is open, the client is not
+//! told yet: the connection fails over to another proxy, up to
+//! `listener::MAX_UPSTREAM_ATTEMPTS` proxies in total, so the local endpoint
+//! stays reliable while single proxies come and go.
+//!
+//! Failures only show once a client's connection runs into them. A
+//! `PortWatcher` sharing the listener's pool finds proxies that went down
+//! within seconds instead, by probing the ports of the highest scoring ones.
+//!
+//! The listener keeps statistics per client, by source address, so that
+//! several users of a single endpoint can be told apart when billing or
+//! debugging.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+//! use gooty_proxy::definitions::proxy::Proxy;
+//! use gooty_proxy::definitions::types::Port;
+//! use gooty_proxy::orchestration::listener::{SocksListener, UpstreamPool};
+//! use std::net::{IpAddr, Ipv4Addr};
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//! use tokio::net::{TcpListener, TcpStream};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     // An upstream HTTP proxy that opens every tunnel and echoes through it
+//!     let upstream = TcpListener::bind("127.0.0.1:0").await.unwrap();
+//!     let upstream_port = upstream.local_addr().unwrap().port();
+//!     tokio::spawn(async move {
+//!         let (mut stream, _) = upstream.accept().await.unwrap();
+//!         let mut header = Vec::new();
+//!         while !header.ends_with(b"\r\n\r\n") {
+//!             header.push(stream.read_u8().await.unwrap());
+//!         }
+//!         assert!(header.starts_with(b"CONNECT example.com:80 HTTP/1.1\r\n"));
+//!         stream.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n").await.unwrap();
+//!         let mut buf = [0_u8; 4];
+//!         stream.read_exact(&mut buf).await.unwrap();
+//!         stream.write_all(&buf).await.unwrap();
+//!     });
+//!
+//!     let mut pool = UpstreamPool::new();
+//!     pool.add_proxy(Proxy::new(
+//!         ProxyType::Http,
+//!         IpAddr::V4(Ipv4Addr::LOCALHOST),
+//!         Port::new(upstream_port).unwrap(),
+//!         AnonymityLevel::Elite,
+//!     ));
+//!     let listener = SocksListener::bind("127.0.0.1:0", pool).await.unwrap();
+//!     let addr = listener.local_addr().unwrap();
+//!     tokio::spawn(listener.run());
+//!
+//!     // A SOCKS5 client connecting to example.com:80 through the listener
+//!     let mut client = TcpStream::connect(addr).await.unwrap();
+//!     client.write_all(&[5, 1, 0]).await.unwrap();
+//!     client.write_all(&[5, 1, 0, 3, 11]).await.unwrap();
+//!     client.write_all(b"example.com").await.unwrap();
+//!     client.write_all(&80_u16.to_be_bytes()).await.unwrap();
+//!
+//!     let mut reply = [0_u8; 12];
+//!     client.read_exact(&mut reply).await.unwrap();
+//!     assert_eq!(reply[..2], [5, 0]); // No authentication
+//!     assert_eq!(reply[2..4], [5, 0]); // Succeeded
+//!
+//!     client.write_all(b"ping").await.unwrap();
+//!     let mut echo = [0_u8; 4];
+//!     client.read_exact(&mut echo).await.unwrap();
+//!     assert_eq!(&echo, b"ping");
+//! }
+//! ```
+
+use crate::definitions::{
+    defaults,
+    errors::{ListenerError, ListenerResult},
+    proxy::ProxyId,
+};
+use crate::io::socks::{self, SocksReply, TargetAddr, Tunnel};
+use crate::orchestration::events::{EventBus, GatherEvent};
+use crate::orchestration::pool::ProxyPool;
+use chrono::{DateTime, Utc};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::time::{MissedTickBehavior, timeout};
+
+/// The proxies a listener rotates through
+///
+/// Listeners take a plain `ProxyPool`; the alias keeps the name the pool
+/// had before it could be used on its own.
+pub type UpstreamPool = ProxyPool;
+
+/// What a single client used the listener for
+///
+/// Bytes are counted once a tunnel closes, so connections still open are
+/// missing from them.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::listener::ClientStats;
+///
+/// let stats = ClientStats {
+///     connections: 4,
+///     failures: 1,
+///     ..Default::default()
+/// };
+/// assert_eq!(stats.failure_rate(), 25);
+/// assert_eq!(ClientStats::default().failure_rate(), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientStats {
+    /// Number of connections accepted from the client
+    #[serde(default)]
+    pub connections: usize,
+
+    /// Number of connections that failed before or while relaying
+    #[serde(default)]
+    pub failures: usize,
+
+    /// Bytes relayed from the client to its targets
+    #[serde(default)]
+    pub bytes_sent: u64,
+
+    /// Bytes relayed from the targets to the client
+    #[serde(default)]
+    pub bytes_received: u64,
+
+    /// Number of connections served by each upstream proxy, by `ProxyId`,
+    /// which leaves out the proxy's credentials
+    #[serde(default)]
+    pub upstreams: BTreeMap<String, usize>,
+
+    /// When the client last connected
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl ClientStats {
+    /// Calculate the share of failed connections, in percent
+    #[must_use]
+    pub fn failure_rate(&self) -> usize {
+        if self.connections == 0 {
+            return 0;
+        }
+
+        100 * self.failures / self.connections
+    }
+
+    /// Record a finished connection of the client
+    fn record(&mut self, relay: &Relay, succeeded: bool) {
+        self.connections += 1;
+        if !succeeded {
+            self.failures += 1;
+        }
+        self.bytes_sent += relay.sent;
+        self.bytes_received += relay.received;
+        if let Some(upstream) = &relay.upstream {
+            *self.upstreams.entry(upstream.to_string()).or_insert(0) += 1;
+        }
+        self.last_seen = Some(Utc::now());
+    }
+}
+
+/// Statistics of the clients of a listener, by source address
+pub type ClientStatsMap = BTreeMap<String, ClientStats>;
+
+/// A local SOCKS5 endpoint relaying connections through the pool
+///
+/// Clients connect without authentication and ask for a target with the
+/// CONNECT command. Each connection is relayed through a proxy of the
+/// [`UpstreamPool`], whatever protocol the proxy speaks.
+#[derive(Debug)]
+pub struct SocksListener {
+    /// The listening socket
+    listener: TcpListener,
+
+    /// The proxies connections are relayed through, shared with the connections
+    pool: Arc<Mutex<UpstreamPool>>,
+
+    /// Time a client has to finish the handshake
+    handshake_timeout: Duration,
+
+    /// Time an upstream proxy has to reach the target
+    connect_timeout: Duration,
+
+    /// Number of upstream proxies tried for a client
+    max_attempts: usize,
+
+    /// Statistics of the clients, shared with the connections
+    clients: Arc<Mutex<ClientStatsMap>>,
+}
+
+impl SocksListener {
+    /// Listen for SOCKS5 clients on an address
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The address to listen on, such as `listener::DEFAULT_SOCKS_ADDRESS`
+    /// * `pool` - The proxies to relay through
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address can't be bound
+    pub async fn bind(addr: impl ToSocketAddrs, pool: UpstreamPool) -> ListenerResult<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr).await?,
+            pool: Arc::new(Mutex::new(pool)),
+            handshake_timeout: Duration::from_secs(defaults::listener::HANDSHAKE_TIMEOUT_SECS),
+            connect_timeout: Duration::from_secs(defaults::listener::UPSTREAM_CONNECT_TIMEOUT_SECS),
+            max_attempts: defaults::listener::MAX_UPSTREAM_ATTEMPTS,
+            clients: Arc::new(Mutex::new(ClientStatsMap::new())),
+        })
+    }
+
+    /// Continue the statistics of an earlier run
+    ///
+    /// # Arguments
+    ///
+    /// * `clients` - Statistics saved by an earlier run
+    #[must_use]
+    pub fn with_client_stats(self, clients: ClientStatsMap) -> Self {
+        *self.clients.lock().unwrap_or_else(PoisonError::into_inner) = clients;
+        self
+    }
+
+    /// Set the number of upstream proxies tried for a client
+    ///
+    /// A client is only refused once this many proxies failed to reach its
+    /// target, or the pool ran out of proxies to try.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_attempts` - The number of proxies tried, at least one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::orchestration::listener::{SocksListener, UpstreamPool};
+    /// use std::net::{IpAddr, Ipv4Addr};
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::{TcpListener, TcpStream};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let proxy = |port| {
+    ///         Proxy::new(ProxyType::Socks5, IpAddr::V4(Ipv4Addr::LOCALHOST), Port::new(port).unwrap(), AnonymityLevel::Elite)
+    ///     };
+    ///
+    ///     // A SOCKS5 proxy granting every request, and a port nothing listens on
+    ///     let working = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    ///     let working_port = working.local_addr().unwrap().port();
+    ///     let dead_port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+    ///     tokio::spawn(async move {
+    ///         let (mut stream, _) = working.accept().await.unwrap();
+    ///         let mut request = [0_u8; 13];
+    ///         stream.read_exact(&mut request[..3]).await.unwrap();
+    ///         stream.write_all(&[5, 0]).await.unwrap();
+    ///         stream.read_exact(&mut request[3..]).await.unwrap();
+    ///         stream.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0]).await.unwrap();
+    ///     });
+    ///
+    ///     let pool = UpstreamPool::from_proxies(vec![proxy(dead_port), proxy(working_port)]);
+    ///     let listener = SocksListener::bind("127.0.0.1:0", pool)
+    ///         .await
+    ///         .unwrap()
+    ///         .with_max_attempts(2);
+    ///     let addr = listener.local_addr().unwrap();
+    ///     tokio::spawn(listener.run());
+    ///
+    ///     // Whichever proxy is selected first, the client gets through
+    ///     let mut client = TcpStream::connect(addr).await.unwrap();
+    ///     client.write_all(&[5, 1, 0, 5, 1, 0, 1, 10, 0, 0, 1, 0, 80]).await.unwrap();
+    ///     let mut reply = [0_u8; 12];
+    ///     client.read_exact(&mut reply).await.unwrap();
+    ///     assert_eq!(reply[2..4], [5, 0]);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the time an upstream proxy has to reach the target
+    ///
+    /// # Arguments
+    ///
+    /// * `connect_timeout` - The time allowed
+    #[must_use]
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Get the address the listener is bound to
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket has no local address
+    pub fn local_addr(&self) -> ListenerResult<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Get the pool, to add or remove proxies while the listener runs
+    #[must_use]
+    pub fn pool(&self) -> Arc<Mutex<UpstreamPool>> {
+        Arc::clone(&self.pool)
+    }
+
+    /// Get the statistics of the clients, updated while the listener runs
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::orchestration::listener::{SocksListener, UpstreamPool};
+    /// use std::time::Duration;
+    /// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    /// use tokio::net::TcpStream;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let listener = SocksListener::bind("127.0.0.1:0", UpstreamPool::new()).await.unwrap();
+    ///     let addr = listener.local_addr().unwrap();
+    ///     let clients = listener.clients();
+    ///     tokio::spawn(listener.run());
+    ///
+    ///     // Without proxies, the client is refused
+    ///     let mut client = TcpStream::connect(addr).await.unwrap();
+    ///     client.write_all(&[5, 1, 0, 5, 1, 0, 1, 10, 0, 0, 1, 0, 80]).await.unwrap();
+    ///     let mut reply = [0_u8; 12];
+    ///     client.read_exact(&mut reply).await.unwrap();
+    ///     assert_eq!(reply[3], 1);
+    ///
+    ///     // The refusal is recorded just after the reply is sent
+    ///     tokio::time::sleep(Duration::from_millis(100)).await;
+    ///     let clients = clients.lock().unwrap();
+    ///     let stats = &clients["127.0.0.1"];
+    ///     assert_eq!(stats.connections, 1);
+    ///     assert_eq!(stats.failure_rate(), 100);
+    /// }
+    /// ```
+    #[must_use]
+    pub fn clients(&self) -> Arc<Mutex<ClientStatsMap>> {
+        Arc::clone(&self.clients)
+    }
+
+    /// Accept clients until the listening socket fails
+    ///
+    /// Each client is served on its own task. Failures of single connections
+    /// are logged and don't stop the listener.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if accepting connections fails
+    pub async fn run(self) -> ListenerResult<()> {
+        loop {
+            let (client, peer) = self.listener.accept().await?;
+            let connection = Connection {
+                pool: Arc::clone(&self.pool),
+                handshake_timeout: self.handshake_timeout,
+                connect_timeout: self.connect_timeout,
+                max_attempts: self.max_attempts,
+            };
+            let clients = Arc::clone(&self.clients);
+            tokio::spawn(async move {
+                let mut relay = Relay::default();
+                let result = connection.serve(client, &mut relay).await;
+                if let Err(e) = &result {
+                    debug!(peer = peer.to_string(); "SOCKS connection failed: {e}");
+                }
+                clients
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .entry(peer.ip().to_string())
+                    .or_default()
+                    .record(&relay, result.is_ok());
+            });
+        }
+    }
+}
+
+/// What happened to a single client connection, for its statistics
+#[derive(Debug, Default)]
+struct Relay {
+    /// The proxy that opened the tunnel
+    upstream: Option<ProxyId>,
+
+    /// Bytes relayed from the client
+    sent: u64,
+
+    /// Bytes relayed to the client
+    received: u64,
+}
+
+/// What a task serving a single client needs from its listener
+struct Connection {
+    /// The proxies to relay through
+    pool: Arc<Mutex<UpstreamPool>>,
+
+    /// Time the client has to finish the handshake
+    handshake_timeout: Duration,
+
+    /// Time the upstream proxy has to reach the target
+    connect_timeout: Duration,
+
+    /// Number of upstream proxies tried
+    max_attempts: usize,
+}
+
+impl Connection {
+    /// Relay a client connection through a proxy of the pool until either side closes
+    async fn serve(&self, mut client: TcpStream, relay: &mut Relay) -> ListenerResult<()> {
+        let target = within(
+            self.handshake_timeout,
+            "handshake",
+            socks::accept(&mut client),
+        )
+        .await?;
+
+        let mut upstream = match self.open_upstream(&target).await {
+            Ok((id, upstream)) => {
+                relay.upstream = Some(id);
+                upstream
+            }
+            Err(e) => {
+                socks::send_reply(&mut client, SocksReply::for_error(&e)).await?;
+                return Err(e);
+            }
+        };
+
+        socks::send_reply(&mut client, SocksReply::Succeeded).await?;
+        let (sent, received) = tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+        relay.sent = sent;
+        relay.received = received;
+        Ok(())
+    }
+
+    /// Open a tunnel to the target, failing over to other proxies of the pool
+    ///
+    /// Every proxy that fails is reported to the pool, which cools it down, or
+    /// bans it from the target's host if it refused the target, so the next
+    /// attempt goes through a different proxy. The error of the last attempt
+    /// is returned once all attempts failed.
+    async fn open_upstream(&self, target: &TargetAddr) -> ListenerResult<(ProxyId, Tunnel)> {
+        let mut tried: Vec<Arc<str>> = Vec::new();
+        let mut last_error = ListenerError::NoProxyAvailable;
+        let host = target.host();
+
+        for attempt in 1..=self.max_attempts {
+            let Some(proxy) = self.lock().next_proxy_for(&host) else {
+                break;
+            };
+            let id = proxy.shared_id();
+            if tried.contains(&id) {
+                // Without a cooldown, a failed proxy can come up again
+                break;
+            }
+
+            let result = within(
+                self.connect_timeout,
+                "upstream connect",
+                socks::connect_through(&proxy, target),
+            )
+            .await;
+            // The pool keys proxies by `ProxyId`, which leaves credentials out;
+            // the typed id is what client statistics count upstreams by
+            let endpoint = proxy.proxy().id();
+            // Reporting a proxy whose handle is still held would copy it
+            drop(proxy);
+
+            match result {
+                Ok(upstream) => {
+                    self.lock().report_success(&id);
+                    return Ok((endpoint, upstream));
+                }
+                Err(e) => {
+                    warn!(
+                        proxy = endpoint.to_string(), target = target.to_string(), attempt = attempt;
+                        "Upstream proxy failed: {e}"
+                    );
+                    if matches!(e, ListenerError::TargetRefused(_)) {
+                        self.lock().report_refusal(&host, &id);
+                    } else {
+                        self.lock().report_failure(&id);
+                    }
+                    tried.push(id);
+                    last_error = e;
+                }
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Locks the pool, recovering it if a holder panicked
+    fn lock(&self) -> MutexGuard<'_, UpstreamPool> {
+        self.pool.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Probes the ports of the proxies a listener is most likely to select
+///
+/// Every interval, a TCP connection is opened to each of the highest scoring
+/// proxies of the pool that aren't cooling down. A proxy whose port doesn't
+/// accept it in time is reported down with [`ProxyPool::report_down`], which
+/// degrades it and cools it down, so clients stop being relayed through it
+/// before the next full health check. The probe only shows that the port
+/// accepts connections, not that the proxy relays them.
+///
+/// With an event bus, every probe is published as a proxy check.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyLifecycle, ProxyType};
+/// use gooty_proxy::definitions::proxy::Proxy;
+/// use gooty_proxy::definitions::types::Port;
+/// use gooty_proxy::orchestration::listener::{PortWatcher, UpstreamPool};
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use std::sync::{Arc, Mutex};
+/// use tokio::net::TcpListener;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let open = TcpListener::bind("127.0.0.1:0").await.unwrap();
+///     let closed_port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+///     let proxy = |port| {
+///         let mut proxy = Proxy::new(
+///             ProxyType::Socks5,
+///             IpAddr::V4(Ipv4Addr::LOCALHOST),
+///             Port::new(port).unwrap(),
+///             AnonymityLevel::Elite,
+///         );
+///         proxy.record_check(100);
+///         proxy
+///     };
+///     let dead = proxy(closed_port);
+///     let pool = UpstreamPool::from_proxies([proxy(open.local_addr().unwrap().port()), dead.clone()]);
+///     let pool = Arc::new(Mutex::new(pool));
+///
+///     let watcher = PortWatcher::new(Arc::clone(&pool));
+///     assert_eq!(watcher.probe_once().await, 1);
+///
+///     let pool = pool.lock().unwrap();
+///     assert_eq!(pool.cooling_down_count(), 1);
+///     let dead = pool.proxy(&dead.to_connection_string()).unwrap();
+///     assert_eq!(dead.lifecycle, ProxyLifecycle::Degraded);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PortWatcher {
+    /// The pool whose proxies are watched
+    pool: Arc<Mutex<UpstreamPool>>,
+
+    /// Number of the highest scoring proxies watched
+    top: usize,
+
+    /// Time between probes
+    interval: Duration,
+
+    /// Time a port has to accept a connection
+    connect_timeout: Duration,
+
+    /// Bus receiving the probes, if any
+    events: Option<EventBus>,
+}
+
+impl PortWatcher {
+    /// Create a watcher of a pool, such as the one of a `SocksListener`
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - The pool whose proxies are watched
+    #[must_use]
+    pub fn new(pool: Arc<Mutex<UpstreamPool>>) -> Self {
+        Self {
+            pool,
+            top: defaults::listener::WATCH_TOP_PROXIES,
+            interval: Duration::from_secs(defaults::listener::WATCH_INTERVAL_SECS),
+            connect_timeout: Duration::from_secs(defaults::listener::WATCH_CONNECT_TIMEOUT_SECS),
+            events: None,
+        }
+    }
+
+    /// Set the number of the highest scoring proxies watched
+    ///
+    /// # Arguments
+    ///
+    /// * `top` - Number of proxies probed every interval
+    #[must_use]
+    pub fn with_top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    /// Set the time between probes
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Time between two probes of the same proxies
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the time a port has to accept a connection
+    ///
+    /// # Arguments
+    ///
+    /// * `connect_timeout` - Time after which a port counts as down
+    #[must_use]
+    pub fn with_connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Set the bus receiving the probes
+    ///
+    /// # Arguments
+    ///
+    /// * `events` - The bus each probe is published to as a proxy check
+    #[must_use]
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Probe the watched proxies once, reporting those that are down
+    ///
+    /// # Returns
+    ///
+    /// The number of proxies reported down
+    pub async fn probe_once(&self) -> usize {
+        let proxies = self
+            .pool
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .top_proxies(self.top);
+
+        let probes = proxies.iter().map(|proxy| async move {
+            let addr = SocketAddr::new(proxy.address, proxy.port.get());
+            let up = matches!(
+                timeout(self.connect_timeout, TcpStream::connect(addr)).await,
+                Ok(Ok(_))
+            );
+            (proxy, up)
+        });
+        let results = futures::future::join_all(probes).await;
+        if let Some(events) = &self.events {
+            for &(proxy, up) in &results {
+                events.publish(GatherEvent::checked(proxy, up));
+            }
+        }
+
+        // The pool is keyed by the string form of `ProxyId`, which leaves
+        // credentials out and reads the same as the redacted id in logs
+        let down: Vec<(Arc<str>, String)> = results
+            .into_iter()
+            .filter(|&(_, up)| !up)
+            .map(|(proxy, _)| (proxy.shared_id(), proxy.redacted_id()))
+            .collect();
+
+        let mut pool = self.pool.lock().unwrap_or_else(PoisonError::into_inner);
+        for (id, redacted) in &down {
+            warn!(proxy = redacted.as_str(); "Watched proxy stopped accepting connections");
+            pool.report_down(id);
+        }
+        down.len()
+    }
+
+    /// Probe the watched proxies every interval, forever
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(self.interval.max(Duration::from_millis(1)));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            self.probe_once().await;
+        }
+    }
+}
+
+/// Run a step of a connection, failing it if it takes too long
+async fn within<T>(
+    limit: Duration,
+    step: &str,
+    future: impl Future<Output = ListenerResult<T>>,
+) -> ListenerResult<T> {
+    timeout(limit, future)
+        .await
+        .map_err(|_| ListenerError::Timeout(step.to_string()))?
+}
@@ -19,6 +19,15 @@
 //! assert!(manager.is_ok());
 //! ```
 
+pub mod coordinator;
+pub mod events;
+pub mod experiment;
+pub mod listener;
+#[cfg(feature = "manager")]
 pub mod manager;
+pub mod pool;
+#[cfg(feature = "manager")]
 pub mod processes;
+pub mod rotation;
+pub mod scheduler;
 pub mod threading;
@@ -0,0 +1,836 @@
+//! # Pool Module
+//!
+//! Rotation over a known list of proxies, usable without the rest of gooty.
+//!
+//! ## Overview
+//!
+//! * **`ProxyPool`** - Holds proxies and rotates through them by a
+//!   `RotationStrategy`, with cooldowns for proxies that failed
+//! * **`ProxyStats`** - Counts of the proxies of a pool or manager
+//!
+//! The pool needs neither the judge nor the sleuth, and makes no requests of
+//! its own. Integrators who already know which proxies work can build the
+//! crate without default features to leave out `reqwest` and every other
+//! networking component:
+//!
+//! ```toml
+//! gooty-proxy = { version = "0.2", default-features = false }
+//! ```
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+//! use gooty_proxy::definitions::proxy::Proxy;
+//! use gooty_proxy::definitions::types::Port;
+//! use gooty_proxy::orchestration::pool::ProxyPool;
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! let proxies = (1..=3).map(|last| {
+//!     Proxy::new(
+//!         ProxyType::Http,
+//!         IpAddr::V4(Ipv4Addr::new(203, 0, 113, last)),
+//!         Port::new(8080).unwrap(),
+//!         AnonymityLevel::Elite,
+//!     )
+//! });
+//! let mut pool = ProxyPool::from_proxies(proxies);
+//!
+//! let id = pool.next_proxy().unwrap().to_connection_string();
+//! pool.report_success(&id);
+//!
+//! let stats = pool.stats();
+//! assert_eq!(stats.total, 3);
+//! assert_eq!(pool.proxy(&id).unwrap().use_count, 1);
+//! ```
+
+use crate::definitions::{
+    defaults,
+    enums::{
+        AnonymityLevel, DedupPolicy, ProxyLifecycle, ProxySortKey, ProxyType, RotationStrategy,
+    },
+    proxy::Proxy,
+    types::LatencyMs,
+};
+use crate::orchestration::rotation::{
+    ProxyHandle, RecentNetworks, RotationState, WeightedSelector, proxy_key, proxy_reliability,
+    proxy_weight,
+};
+use ahash::AHashMap;
+use chrono::{DateTime, Utc};
+use rand::seq::IteratorRandom;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Statistics about the proxies of a `ProxyPool` or `ProxyManager`
+#[derive(Debug, Clone)]
+pub struct ProxyStats {
+    /// Total number of proxies
+    pub total: usize,
+
+    /// Number of working proxies (active or degraded)
+    pub working: usize,
+
+    /// Number of distinct exits among working proxies, under the dedup policy
+    /// they were counted with
+    pub working_exits: usize,
+
+    /// Number of proxies by lifecycle state
+    pub by_lifecycle: HashMap<ProxyLifecycle, usize>,
+
+    /// Number of proxies by anonymity level
+    pub by_anonymity: HashMap<AnonymityLevel, usize>,
+
+    /// Number of proxies by type
+    pub by_type: HashMap<ProxyType, usize>,
+
+    /// Number of proxies by country
+    pub by_country: HashMap<String, usize>,
+
+    /// Average latency of working proxies
+    pub avg_latency: Option<LatencyMs>,
+}
+
+impl ProxyStats {
+    /// Count a set of proxies
+    ///
+    /// # Arguments
+    ///
+    /// * `proxies` - The proxies to count
+    /// * `policy` - The dedup policy telling exits apart
+    ///
+    /// # Returns
+    ///
+    /// The statistics of the proxies.
+    #[must_use]
+    pub fn from_proxies<'a>(
+        proxies: impl IntoIterator<Item = &'a Proxy>,
+        policy: DedupPolicy,
+    ) -> Self {
+        let mut total = 0;
+        let mut working = 0;
+        let mut exits = HashSet::new();
+        let mut by_lifecycle = HashMap::new();
+        let mut by_anonymity = HashMap::new();
+        let mut by_type = HashMap::new();
+        let mut by_country = HashMap::new();
+        let mut latencies = Vec::new();
+
+        for proxy in proxies {
+            total += 1;
+
+            // Count active and degraded proxies as working
+            let lifecycle = proxy.effective_lifecycle();
+            if lifecycle.is_usable() {
+                working += 1;
+                exits.insert(proxy.exit_key(policy));
+            }
+            *by_lifecycle.entry(lifecycle).or_insert(0) += 1;
+
+            // Count by anonymity
+            *by_anonymity.entry(proxy.anonymity).or_insert(0) += 1;
+
+            // Count by type
+            *by_type.entry(proxy.proxy_type).or_insert(0) += 1;
+
+            // Count by country
+            if let Some(country) = &proxy.country {
+                *by_country.entry(country.clone()).or_insert(0) += 1;
+            }
+
+            latencies.extend(proxy.latency_ms);
+        }
+
+        ProxyStats {
+            total,
+            working,
+            working_exits: exits.len(),
+            by_lifecycle,
+            by_anonymity,
+            by_type,
+            by_country,
+            avg_latency: LatencyMs::average(latencies),
+        }
+    }
+}
+
+/// Proxies to rotate through
+///
+/// Proxies are selected by the pool's `RotationStrategy`, by default in
+/// proportion to their `proxy_weight`. A proxy reported as failed gets no
+/// connections until its cooldown ends.
+///
+/// Selections hand out `ProxyHandle`s, so selecting a proxy doesn't copy it.
+/// Drop a handle before reporting its proxy, or the report copies the proxy
+/// to keep the handle unchanged.
+///
+/// With subnet diversity, consecutive selections avoid the /24 subnets and
+/// ASNs of the latest selections, so a target blocking whole ranges bans
+/// fewer of them at once. A selection only falls back to a recent network
+/// when every selectable proxy is on one.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+/// use gooty_proxy::definitions::proxy::Proxy;
+/// use gooty_proxy::definitions::types::Port;
+/// use gooty_proxy::orchestration::pool::ProxyPool;
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// let proxy = Proxy::new(
+///     ProxyType::Socks5,
+///     IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+///     Port::new(1080).unwrap(),
+///     AnonymityLevel::Elite,
+/// );
+/// let mut pool = ProxyPool::from_proxies(vec![proxy.clone()]);
+/// assert_eq!(pool.next_proxy().as_deref(), Some(&proxy));
+///
+/// // A failed proxy is cooled down, leaving nothing to select
+/// let id = proxy.to_connection_string();
+/// pool.report_failure(&id);
+/// assert_eq!(pool.cooling_down_count(), 1);
+/// assert!(pool.next_proxy().is_none());
+/// assert_eq!(pool.proxy(&id).unwrap().use_failure_count, 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProxyPool {
+    /// The proxies, by `ProxyId`
+    proxies: AHashMap<Arc<str>, ProxyHandle>,
+
+    /// Identifiers of the proxies, in the order they were added
+    order: Vec<Arc<str>>,
+
+    /// How the next proxy is selected
+    strategy: RotationStrategy,
+
+    /// Selection in proportion to the scores of the proxies
+    selector: WeightedSelector,
+
+    /// Cursor, cooldowns of proxies that failed, and bans from targets
+    state: RotationState,
+
+    /// How long a failed proxy is skipped
+    cooldown: Duration,
+
+    /// How long a proxy that refused a target is skipped for it
+    ban: Duration,
+
+    /// Networks of the latest selections, if consecutive selections must
+    /// differ in network
+    recent: Option<RecentNetworks>,
+}
+
+impl ProxyPool {
+    /// Create a pool without proxies
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cooldown: Duration::from_secs(defaults::rotation::FAILURE_COOLDOWN_SECS),
+            ban: Duration::from_secs(defaults::rotation::TARGET_BAN_SECS),
+            ..Self::default()
+        }
+    }
+
+    /// Create a pool of the given proxies
+    ///
+    /// # Arguments
+    ///
+    /// * `proxies` - The proxies to rotate through
+    #[must_use]
+    pub fn from_proxies(proxies: impl IntoIterator<Item = Proxy>) -> Self {
+        let mut pool = Self::new();
+        for proxy in proxies {
+            pool.add_proxy(proxy);
+        }
+        pool
+    }
+
+    /// Set how long a failed proxy is skipped
+    ///
+    /// # Arguments
+    ///
+    /// * `cooldown` - Time until a failed proxy is selected again
+    #[must_use]
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Set how long a proxy that refused a target is skipped for it
+    ///
+    /// # Arguments
+    ///
+    /// * `ban` - Time until the proxy is selected for the target again
+    #[must_use]
+    pub fn with_ban_duration(mut self, ban: Duration) -> Self {
+        self.ban = ban;
+        self
+    }
+
+    /// Restore the state of an earlier rotation
+    ///
+    /// Picks up the cursor of a sequential rotation, and the cooldowns and
+    /// bans that haven't ended, so a restarted rotation doesn't hammer the
+    /// proxies that failed before. Proxies cooling down that are added later
+    /// wait out their cooldown too.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state, as saved with `Filestore::save_rotation_state`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::orchestration::pool::ProxyPool;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// let proxies: Vec<Proxy> = (1..=2)
+    ///     .map(|last| {
+    ///         Proxy::new(
+    ///             ProxyType::Http,
+    ///             IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)),
+    ///             Port::new(8080).unwrap(),
+    ///             AnonymityLevel::Elite,
+    ///         )
+    ///     })
+    ///     .collect();
+    /// let (failed, banned) = (proxies[0].to_connection_string(), proxies[1].to_connection_string());
+    ///
+    /// let mut pool = ProxyPool::from_proxies(proxies.clone());
+    /// pool.report_failure(&failed);
+    /// pool.report_refusal("example.com", &banned);
+    ///
+    /// // A restarted pool keeps skipping both
+    /// let mut restarted = ProxyPool::from_proxies(proxies).with_state(pool.state().clone());
+    /// assert_eq!(restarted.cooling_down_count(), 1);
+    /// assert!(restarted.next_proxy_for("example.com").is_none());
+    /// assert_eq!(restarted.next_proxy_for("example.org").unwrap().id(), banned);
+    /// ```
+    #[must_use]
+    pub fn with_state(mut self, mut state: RotationState) -> Self {
+        state.rekey();
+        state.prune(Utc::now());
+        for id in state.cooldowns.keys() {
+            if self.proxies.contains_key(id.as_str()) {
+                self.selector.set_weight(id, 0.0);
+            }
+        }
+        self.state = state;
+        self
+    }
+
+    /// Get the state of the rotation, to save it for a restart
+    #[must_use]
+    pub fn state(&self) -> &RotationState {
+        &self.state
+    }
+
+    /// Set how the next proxy is selected
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The rotation strategy
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType, RotationStrategy};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::{LatencyMs, Port};
+    /// use gooty_proxy::orchestration::pool::ProxyPool;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// let proxies: Vec<Proxy> = [300, 50, 120]
+    ///     .into_iter()
+    ///     .zip(1..)
+    ///     .map(|(latency, last)| {
+    ///         let mut proxy = Proxy::new(
+    ///             ProxyType::Http,
+    ///             IpAddr::V4(Ipv4Addr::new(10, 0, 0, last)),
+    ///             Port::new(8080).unwrap(),
+    ///             AnonymityLevel::Elite,
+    ///         );
+    ///         proxy.latency_ms = Some(LatencyMs::from(latency));
+    ///         proxy
+    ///     })
+    ///     .collect();
+    /// let ids: Vec<String> = proxies.iter().map(Proxy::to_connection_string).collect();
+    ///
+    /// // Sequential rotation goes round in the order the proxies were added
+    /// let mut pool = ProxyPool::from_proxies(proxies.clone()).with_strategy(RotationStrategy::Sequential);
+    /// let picks: Vec<String> = (0..4).map(|_| pool.next_proxy().unwrap().id().to_string()).collect();
+    /// assert_eq!(picks, [0, 1, 2, 0].map(|i| ids[i].clone()));
+    ///
+    /// // Performance rotation keeps to the fastest proxy until it fails
+    /// let mut pool = ProxyPool::from_proxies(proxies).with_strategy(RotationStrategy::Performance);
+    /// assert_eq!(pool.next_proxy().unwrap().id(), ids[1]);
+    /// pool.report_result(&ids[1], false);
+    /// assert_eq!(pool.next_proxy().unwrap().id(), ids[2]);
+    /// ```
+    #[must_use]
+    pub fn with_strategy(mut self, strategy: RotationStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Get how the next proxy is selected
+    #[must_use]
+    pub fn strategy(&self) -> RotationStrategy {
+        self.strategy
+    }
+
+    /// Keep consecutive selections on different subnets and ASNs
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - Number of latest selections whose /24 subnets and ASNs
+    ///   are avoided, none at zero
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::orchestration::pool::ProxyPool;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// let proxies = [[203, 0, 113, 7], [203, 0, 113, 8], [198, 51, 100, 1]].map(|octets| {
+    ///     Proxy::new(ProxyType::Http, IpAddr::V4(Ipv4Addr::from(octets)), Port::new(8080).unwrap(), AnonymityLevel::Elite)
+    /// });
+    /// let mut pool = ProxyPool::from_proxies(proxies).with_subnet_diversity(1);
+    ///
+    /// // Selections alternate between the two subnets
+    /// let mut previous = pool.next_proxy().unwrap().address;
+    /// for _ in 0..20 {
+    ///     let address = pool.next_proxy().unwrap().address;
+    ///     assert_ne!(address.to_string().starts_with("203."), previous.to_string().starts_with("203."));
+    ///     previous = address;
+    /// }
+    /// ```
+    #[must_use]
+    pub fn with_subnet_diversity(mut self, window: usize) -> Self {
+        self.recent = (window > 0).then(|| RecentNetworks::new(window));
+        self
+    }
+
+    /// Add a proxy, replacing a proxy with the same `ProxyId`
+    ///
+    /// A replaced proxy keeps its cooldowns and bans, even if its
+    /// credentials changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `proxy` - The proxy to add
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::definitions::types::Port;
+    /// use gooty_proxy::orchestration::pool::ProxyPool;
+    /// use std::net::{IpAddr, Ipv4Addr};
+    ///
+    /// let proxy = Proxy::new(
+    ///     ProxyType::Http,
+    ///     IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+    ///     Port::new(8080).unwrap(),
+    ///     AnonymityLevel::Elite,
+    /// )
+    /// .with_auth("user".to_string(), "old".to_string());
+    /// let mut pool = ProxyPool::from_proxies(vec![proxy.clone()]);
+    /// pool.report_failure(&proxy.to_connection_string());
+    ///
+    /// // New credentials don't readmit the proxy
+    /// pool.add_proxy(proxy.with_auth("user".to_string(), "new".to_string()));
+    /// assert_eq!(pool.len(), 1);
+    /// assert!(pool.next_proxy().is_none());
+    /// ```
+    pub fn add_proxy(&mut self, proxy: Proxy) {
+        let handle = ProxyHandle::new(proxy);
+        if !self.state.cooldowns.contains_key(handle.id()) {
+            self.selector.set_weight(handle.id(), proxy_weight(&handle));
+        }
+        let id = handle.shared_id();
+        if self.proxies.insert(Arc::clone(&id), handle).is_none() {
+            self.order.push(id);
+        }
+    }
+
+    /// Remove a proxy
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Connection string of the proxy, with or without credentials
+    ///
+    /// # Returns
+    ///
+    /// The removed proxy, if it was in the pool
+    pub fn remove_proxy(&mut self, id: &str) -> Option<Proxy> {
+        let id = proxy_key(id);
+        let id = id.as_ref();
+        self.selector.remove(id);
+        self.state.cooldowns.remove(id);
+        self.order.retain(|entry| &**entry != id);
+        self.proxies.remove(id).map(ProxyHandle::into_proxy)
+    }
+
+    /// Get the number of proxies, including those cooling down
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.proxies.len()
+    }
+
+    /// Check whether the pool has no proxies
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.proxies.is_empty()
+    }
+
+    /// Get a proxy of the pool, with the uses reported to the pool
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Connection string of the proxy, with or without credentials
+    #[must_use]
+    pub fn proxy(&self, id: &str) -> Option<&Proxy> {
+        self.proxies
+            .get(proxy_key(id).as_ref())
+            .map(ProxyHandle::proxy)
+    }
+
+    /// Iterate over the proxies, including those cooling down
+    pub fn proxies(&self) -> impl Iterator<Item = &Proxy> {
+        self.proxies.values().map(ProxyHandle::proxy)
+    }
+
+    /// Count the proxies by state, anonymity, type, and country
+    ///
+    /// Exits are told apart by the default dedup policy.
+    #[must_use]
+    pub fn stats(&self) -> ProxyStats {
+        ProxyStats::from_proxies(self.proxies(), DedupPolicy::default())
+    }
+
+    /// Get the number of proxies cooling down
+    #[must_use]
+    pub fn cooling_down_count(&self) -> usize {
+        self.state.cooldowns.len()
+    }
+
+    /// Get the proxies most likely to be selected, by score
+    ///
+    /// Proxies cooling down are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - Most proxies returned
+    ///
+    /// # Returns
+    ///
+    /// Handles to the proxies, the highest scoring first
+    #[must_use]
+    pub fn top_proxies(&self, count: usize) -> Vec<ProxyHandle> {
+        let mut proxies: Vec<&ProxyHandle> = self
+            .proxies
+            .values()
+            .filter(|proxy| !self.state.cooldowns.contains_key(proxy.id()))
+            .collect();
+        proxies.sort_by(|a, b| proxy_weight(b).total_cmp(&proxy_weight(a)));
+        proxies.into_iter().take(count).cloned().collect()
+    }
+
+    /// Select the proxy for the next connection
+    ///
+    /// Proxies whose cooldown ended are eligible again. Weighted selection
+    /// doesn't allocate, unless cooldowns ended or the scores changed since
+    /// the last selection.
+    ///
+    /// # Returns
+    ///
+    /// A handle to the proxy picked by the pool's strategy, or None if every
+    /// proxy is cooling down
+    pub fn next_proxy(&mut self) -> Option<ProxyHandle> {
+        self.select(None)
+    }
+
+    /// Select the proxy for the next connection to a target
+    ///
+    /// Like [`ProxyPool::next_proxy`], but skips the proxies banned from
+    /// the target.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target of the connection, such as its host name
+    ///
+    /// # Returns
+    ///
+    /// A handle to the proxy picked by the pool's strategy, or None if every
+    /// proxy is cooling down or banned from the target
+    pub fn next_proxy_for(&mut self, target: &str) -> Option<ProxyHandle> {
+        self.select(Some(target))
+    }
+
+    /// Select a proxy by the pool's strategy, skipping those banned from the target
+    fn select(&mut self, target: Option<&str>) -> Option<ProxyHandle> {
+        let now = Utc::now();
+        let (proxies, selector) = (&self.proxies, &mut self.selector);
+        self.state.cooldowns.retain(|id, until| {
+            if *until > now {
+                return true;
+            }
+            if let Some(proxy) = proxies.get(id.as_str()) {
+                selector.set_weight(id, proxy_weight(proxy));
+            }
+            false
+        });
+
+        let selected = match self.strategy {
+            RotationStrategy::Weighted => self.select_weighted(target, now)?,
+            // A proxy on a recent network is only picked if no other is selectable
+            strategy => self
+                .select_by(strategy, target, now, true)
+                .or_else(|| self.select_by(strategy, target, now, false))?,
+        };
+
+        if let Some(recent) = &mut self.recent {
+            recent.record(&selected);
+        }
+        Some(selected)
+    }
+
+    /// Select a proxy in proportion to its score
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target whose banned proxies are skipped, if any
+    /// * `now` - The current time
+    fn select_weighted(&mut self, target: Option<&str>, now: DateTime<Utc>) -> Option<ProxyHandle> {
+        let mut rng = rand::rng();
+        let state = &self.state;
+        let banned = |proxy: &ProxyHandle| {
+            target.is_some_and(|target| state.is_banned(target, proxy.id(), now))
+        };
+        let recent = self.recent.as_ref();
+        if recent.is_none() && target.is_none_or(|target| !state.bans.contains_key(target)) {
+            // A proxy drawn after leaving the pool falls through to the draws below
+            let id = self.selector.select(&mut rng)?;
+            if let Some(proxy) = self.proxies.get(id) {
+                return Some(proxy.clone());
+            }
+        }
+
+        // Weighted draws are tried first, then the best selectable proxy on
+        // another network, before giving in to a recent network
+        let mut fallback = None;
+        let mut selected = None;
+        for _ in 0..defaults::rotation::DIVERSITY_MAX_DRAWS {
+            let id = self.selector.select(&mut rng)?;
+            let Some(proxy) = self.proxies.get(id) else {
+                continue;
+            };
+            if banned(proxy) {
+                continue;
+            }
+            if recent.is_none_or(|recent| !recent.contains(proxy)) {
+                selected = Some(proxy);
+                break;
+            }
+            fallback.get_or_insert(proxy);
+        }
+        selected
+            .or_else(|| {
+                self.proxies
+                    .iter()
+                    .filter(|(id, proxy)| {
+                        self.selector.weight(id).is_some_and(|weight| weight > 0.0)
+                            && !banned(proxy)
+                            && recent.is_none_or(|recent| !recent.contains(proxy))
+                    })
+                    .map(|(_, proxy)| proxy)
+                    .max_by(|a, b| proxy_weight(a).total_cmp(&proxy_weight(b)))
+            })
+            .or(fallback)
+            .cloned()
+    }
+
+    /// Select a proxy that isn't cooling down by an unweighted strategy
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The rotation strategy
+    /// * `target` - The target whose banned proxies are skipped, if any
+    /// * `now` - The current time
+    /// * `avoid_recent` - Whether to skip proxies on the networks of the latest selections
+    fn select_by(
+        &mut self,
+        strategy: RotationStrategy,
+        target: Option<&str>,
+        now: DateTime<Utc>,
+        avoid_recent: bool,
+    ) -> Option<ProxyHandle> {
+        let recent = self.recent.as_ref().filter(|_| avoid_recent);
+        let selectable = |state: &RotationState, proxy: &ProxyHandle| {
+            !state.cooldowns.contains_key(proxy.id())
+                && target.is_none_or(|target| !state.is_banned(target, proxy.id(), now))
+                && recent.is_none_or(|recent| !recent.contains(proxy))
+        };
+
+        if strategy == RotationStrategy::Sequential {
+            // Walk at most once around the rotation from where it left off
+            for _ in 0..self.order.len() {
+                let position = self.state.advance(self.order.len())?;
+                let proxy = self
+                    .order
+                    .get(position)
+                    .and_then(|id| self.proxies.get(id))?;
+                if selectable(&self.state, proxy) {
+                    return Some(proxy.clone());
+                }
+            }
+            return None;
+        }
+
+        let candidates = self
+            .order
+            .iter()
+            .filter_map(|id| self.proxies.get(id))
+            .filter(|proxy| selectable(&self.state, proxy));
+        match strategy {
+            RotationStrategy::Performance => {
+                candidates.min_by(|a, b| a.cmp_by(b, ProxySortKey::Latency))
+            }
+            RotationStrategy::Reliability => {
+                candidates.min_by(|a, b| proxy_reliability(b).total_cmp(&proxy_reliability(a)))
+            }
+            _ => candidates.choose(&mut rand::rng()),
+        }
+        .cloned()
+    }
+
+    /// Report the outcome of a connection through a proxy
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Connection string of the proxy, with or without credentials
+    /// * `success` - Whether the proxy relayed the connection
+    pub fn report_result(&mut self, id: &str, success: bool) {
+        if success {
+            self.report_success(id);
+        } else {
+            self.report_failure(id);
+        }
+    }
+
+    /// Report a proxy that opened a tunnel for a connection
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Connection string of the proxy, with or without credentials
+    pub fn report_success(&mut self, id: &str) {
+        if let Some(proxy) = self.proxies.get_mut(proxy_key(id).as_ref()) {
+            proxy.proxy_mut().record_use();
+        }
+    }
+
+    /// Report a proxy that failed to relay a connection
+    ///
+    /// The failed use is recorded against the proxy, and the proxy is skipped
+    /// until its cooldown ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Connection string of the proxy, with or without credentials
+    pub fn report_failure(&mut self, id: &str) {
+        let id = proxy_key(id);
+        let id = id.as_ref();
+        let Some(handle) = self.proxies.get_mut(id) else {
+            return;
+        };
+        let proxy = handle.proxy_mut();
+        proxy.record_use();
+        proxy.record_use_failure();
+        self.cool_down(id);
+    }
+
+    /// Report a proxy whose port stopped accepting connections
+    ///
+    /// The failed probe is recorded against the proxy, which degrades it,
+    /// and the proxy is skipped until its cooldown ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Connection string of the proxy, with or without credentials
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use gooty_proxy::definitions::proxy::Proxy;
+    /// use gooty_proxy::orchestration::pool::ProxyPool;
+    /// use std::time::Duration;
+    ///
+    /// let proxy: Proxy = "http://10.0.0.1:8080".parse().unwrap();
+    /// let mut pool = ProxyPool::from_proxies([proxy]).with_cooldown(Duration::MAX);
+    ///
+    /// // A cooldown past the end of time keeps the proxy out for good
+    /// pool.report_down("http://10.0.0.1:8080");
+    /// assert!(pool.next_proxy().is_none());
+    /// ```
+    pub fn report_down(&mut self, id: &str) {
+        let id = proxy_key(id);
+        let id = id.as_ref();
+        let Some(handle) = self.proxies.get_mut(id) else {
+            return;
+        };
+        handle.proxy_mut().record_probe_failure();
+        self.cool_down(id);
+    }
+
+    /// Skip a proxy until its cooldown ends
+    fn cool_down(&mut self, id: &str) {
+        let cooldown = chrono::Duration::from_std(self.cooldown).unwrap_or(chrono::Duration::MAX);
+        let until = Utc::now()
+            .checked_add_signed(cooldown)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.state.cool_down(id, until);
+        self.selector.set_weight(id, 0.0);
+    }
+
+    /// Report a proxy that answered but refused to reach a target
+    ///
+    /// The failed use is recorded against the proxy, which is banned from
+    /// the target until the ban ends but stays selectable for other targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The target the proxy refused, such as its host name
+    /// * `id` - Connection string of the proxy, with or without credentials
+    pub fn report_refusal(&mut self, target: &str, id: &str) {
+        let id = proxy_key(id);
+        let id = id.as_ref();
+        let Some(handle) = self.proxies.get_mut(id) else {
+            return;
+        };
+        let proxy = handle.proxy_mut();
+        proxy.record_use();
+        proxy.record_use_failure();
+
+        let now = Utc::now();
+        let ban = chrono::Duration::from_std(self.ban).unwrap_or(chrono::Duration::MAX);
+        // Ended bans of the target are dropped here, so they don't pile up
+        if let Some(bans) = self.state.bans.get_mut(target) {
+            bans.retain(|_, end| *end > now);
+        }
+        let until = now
+            .checked_add_signed(ban)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        self.state.ban(target, id, until);
+    }
+}
@@ -31,16 +31,32 @@
 ///
 /// processes::start_process("example_process");
 /// ```
-use crate::definitions::{errors::ManagerResult, proxy::Proxy};
+use crate::definitions::{
+    defaults,
+    errors::{ErrorContext, ManagerResult, SourceResult},
+    proxy::Proxy,
+    source::Source,
+};
 use crate::inspection::{ipinfo::Sleuth, judgement::Judge};
 use crate::io::http::Requestor;
-use crate::orchestration::threading;
-use futures::FutureExt;
+use crate::orchestration::events::{EventBus, GatherEvent, GatherStage};
+use crate::orchestration::threading::{self, BulkExecutor};
+use crate::utils;
+use futures::{FutureExt, StreamExt, stream};
+#[cfg(feature = "progress")]
 use indicatif::{ProgressBar, ProgressStyle};
 use log::{debug, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
+use tokio::time::Instant;
+
+/// Future of a single source fetch, yielding the updated source, its proxies, and success.
+type SourceFetchFuture = Pin<Box<dyn Future<Output = ((Source, Vec<Proxy>), bool)> + Send>>;
 
 /// Helper function to create a progress bar with consistent styling.
 ///
@@ -51,17 +67,122 @@ use std::sync::Arc;
 /// # Returns
 ///
 /// A configured `ProgressBar` instance ready for tracking progress.
+#[cfg(feature = "progress")]
 fn create_progress_bar(total: u64) -> ProgressBar {
     let progress = ProgressBar::new(total);
     progress.set_style(
         ProgressStyle::default_bar()
             .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
-            .expect("Failed to create progress bar style")
+            .unwrap_or_else(|_| ProgressStyle::default_bar())
             .progress_chars("##-"),
     );
     progress
 }
 
+/// Stand-in for a progress bar, drawing nothing without the `progress` feature
+#[cfg(not(feature = "progress"))]
+struct ProgressBar;
+
+#[cfg(not(feature = "progress"))]
+#[allow(clippy::unused_self)]
+impl ProgressBar {
+    fn inc(&self, _delta: u64) {}
+
+    fn finish_with_message(&self, _message: String) {}
+}
+
+/// Helper function to create a progress bar, which draws nothing without the
+/// `progress` feature.
+#[cfg(not(feature = "progress"))]
+fn create_progress_bar(_total: u64) -> ProgressBar {
+    ProgressBar
+}
+
+/// Logs a failed operation with its context.
+///
+/// The operation, identifier, and attempt are attached as key-values, so
+/// bulk-run logs can be filtered by the proxy or source that failed.
+///
+/// # Arguments
+///
+/// * `context` - What was being worked on
+/// * `error` - The error that occurred
+pub(crate) fn warn_failure(context: &ErrorContext, error: &dyn fmt::Display) {
+    warn!(
+        operation = context.operation.as_str(),
+        id = context.id.as_deref(),
+        attempt = context.attempt;
+        "Failed to {context}: {error}"
+    );
+}
+
+/// Describes the outcome of a source fetch as an event.
+///
+/// # Arguments
+///
+/// * `source` - The fetched source
+/// * `result` - The proxies found, or why the fetch failed
+fn fetched_event(source: &Source, result: &SourceResult<Vec<Proxy>>) -> GatherEvent {
+    GatherEvent::SourceFetched {
+        source: source.url.clone(),
+        proxies: result.as_ref().map_or(0, Vec::len),
+        error: result.as_ref().err().map(ToString::to_string),
+    }
+}
+
+/// Judge a single proxy, recording the outcome on it.
+///
+/// # Arguments
+///
+/// * `judge` - The Judge service to test the proxy with
+/// * `executor` - Runs the judgement according to the configured isolation mode
+/// * `proxy` - The proxy to judge
+///
+/// # Returns
+///
+/// The updated proxy and whether the judgement succeeded.
+async fn judge_one(judge: Arc<Judge>, executor: &BulkExecutor, mut proxy: Proxy) -> (Proxy, bool) {
+    proxy.begin_validation();
+    let judged = proxy.clone();
+    let (mut proxy, result) = match executor
+        .run(async move {
+            let mut proxy = judged;
+            let result = judge.judge_proxy(&mut proxy).await;
+            (proxy, result)
+        })
+        .await
+    {
+        Ok(outcome) => outcome,
+        // The judgement never ran, so the proxy isn't held to it
+        Err(e) => {
+            warn!("Failed to judge {}: {e}", proxy.redacted_id());
+            return (proxy, false);
+        }
+    };
+
+    match result {
+        Ok(anonymity) => {
+            if anonymity != proxy.anonymity {
+                debug!(
+                    "Anonymity of {} changed from {} to {anonymity}",
+                    proxy.redacted_id(),
+                    proxy.anonymity
+                );
+            }
+            proxy.record_anonymity(anonymity);
+            (proxy, true)
+        }
+        Err(e) => {
+            proxy.record_check_failure();
+            let context = ErrorContext::new("judge")
+                .with_id(&proxy.redacted_id())
+                .with_attempt(proxy.check_count);
+            debug!("Failed to {context}: {e}");
+            (proxy, false)
+        }
+    }
+}
+
 /// Verify a batch of proxies with the judge service.
 ///
 /// This function tests each proxy in the batch concurrently using the provided judge service,
@@ -72,6 +193,8 @@ fn create_progress_bar(total: u64) -> ProgressBar {
 /// * `proxies` - A mutable slice of proxies to verify
 /// * `judge` - An Arc reference to the Judge service for testing proxies
 /// * `concurrency` - The maximum number of concurrent verification operations
+/// * `executor` - Runs each judgement according to the configured isolation mode
+/// * `events` - Receives an event for every judged proxy
 ///
 /// # Returns
 ///
@@ -87,12 +210,14 @@ fn create_progress_bar(total: u64) -> ProgressBar {
 /// ```
 /// let judge = Arc::new(Judge::new().await?);
 /// let mut proxies = vec![/* proxies to verify */];
-/// verify_proxies(&mut proxies, &judge, 10).await?;
+/// verify_proxies(&mut proxies, &judge, 10, &BulkExecutor::shared(), &EventBus::default()).await?;
 /// ```
 pub async fn verify_proxies(
     proxies: &mut [Proxy],
     judge: &Arc<Judge>,
     concurrency: usize,
+    executor: &BulkExecutor,
+    events: &EventBus,
 ) -> ManagerResult<()> {
     if proxies.is_empty() {
         return Ok(());
@@ -100,6 +225,10 @@ pub async fn verify_proxies(
 
     let total = proxies.len();
     info!("Verifying {total} proxies with concurrency {concurrency}");
+    events.publish(GatherEvent::StageStarted {
+        stage: GatherStage::Check,
+        total,
+    });
 
     // Create a progress bar and wrap in Arc for safe sharing
     let progress = Arc::new(create_progress_bar(total as u64));
@@ -111,53 +240,134 @@ pub async fn verify_proxies(
     // This properly clones the Arc for each future without moving ownership
     let judge = Arc::clone(judge);
     let progress_clone = Arc::clone(&progress);
+    let executor = executor.clone();
+    let events_clone = events.clone();
 
-    let job_fn = move |mut proxy: Proxy| -> Pin<Box<dyn Future<Output = (Proxy, bool)> + Send>> {
+    let job_fn = move |proxy: Proxy| -> Pin<Box<dyn Future<Output = (Proxy, bool)> + Send>> {
         // Create local clones for the async block
         let judge = Arc::clone(&judge);
         let progress = Arc::clone(&progress_clone);
+        let executor = executor.clone();
+        let events = events_clone.clone();
 
         // Box::pin automatically pins the future
         async move {
-            let result = judge.judge_proxy(&mut proxy).await;
+            let result = judge_one(judge, &executor, proxy).await;
             // Update progress regardless of result
             progress.inc(1);
-
-            if let Ok(anonymity) = result {
-                proxy.anonymity = anonymity;
-                (proxy, true)
-            } else {
-                proxy.record_check_failure();
-                (proxy, false)
-            }
+            events.publish(GatherEvent::checked(&result.0, result.1));
+            result
         }
         .boxed()
     };
 
     // Use thread utility to run concurrent batch
-    let results = threading::run_concurrent_batch(proxy_vec, concurrency, &job_fn).await;
+    let batch = threading::run_concurrent_batch(proxy_vec, concurrency, &job_fn).await;
+    let panicked = batch.panicked;
+    progress.inc(panicked as u64);
 
     // Update the original proxies slice with results
     let mut success_count = 0;
 
-    for (i, (updated_proxy, success)) in results.into_iter().enumerate() {
-        if i < proxies.len() {
-            proxies[i] = updated_proxy;
-            if success {
-                success_count += 1;
+    for (slot, result) in proxies.iter_mut().zip(batch.results) {
+        match result {
+            Some((updated_proxy, success)) => {
+                *slot = updated_proxy;
+                if success {
+                    success_count += 1;
+                }
             }
+            // A judgement that panicked counts as a failed check
+            None => slot.record_check_failure(),
         }
     }
 
     progress.finish_with_message(format!(
-        "Verified {total}/{total} ({success_count} successful)"
+        "Verified {total}/{total} ({success_count} successful, {panicked} panicked)"
     ));
 
-    info!("Verified {total}/{total} proxies ({success_count} successful)");
+    info!("Verified {total}/{total} proxies ({success_count} successful, {panicked} panicked)");
+    events.publish(GatherEvent::StageFinished {
+        stage: GatherStage::Check,
+        total,
+        succeeded: success_count,
+    });
 
     Ok(())
 }
 
+/// Probe a batch of proxies for liveness.
+///
+/// A cheap alternative to [`verify_proxies`] that keeps lifecycles current
+/// between judgements without judging anonymity again. See
+/// [`Judge::probe_alive`].
+///
+/// # Arguments
+///
+/// * `proxies` - A mutable slice of proxies to probe
+/// * `judge` - The Judge service probing the proxies
+/// * `concurrency` - The maximum number of concurrent probes
+/// * `events` - Receives an event for every probed proxy
+///
+/// # Returns
+///
+/// The number of proxies found alive.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+/// use gooty_proxy::definitions::proxy::Proxy;
+/// use gooty_proxy::definitions::types::Port;
+/// use gooty_proxy::inspection::judgement::Judge;
+/// use gooty_proxy::orchestration::events::EventBus;
+/// use gooty_proxy::orchestration::processes;
+/// use std::net::{IpAddr, Ipv4Addr};
+/// use std::sync::Arc;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+/// let port = listener.local_addr().unwrap().port();
+/// let mut proxies: Vec<Proxy> = [port, 1]
+///     .into_iter()
+///     .map(|port| Proxy::new(ProxyType::Http, IpAddr::V4(Ipv4Addr::LOCALHOST), Port::new(port).unwrap(), AnonymityLevel::Elite))
+///     .collect();
+///
+/// let judge = Arc::new(Judge::new().unwrap());
+/// let alive = processes::probe_proxies(&mut proxies, &judge, 4, &EventBus::default()).await;
+/// assert_eq!(alive, 1);
+/// assert_eq!(proxies[1].probe_failure_count, 1);
+/// // The anonymity level was not judged
+/// assert!(proxies[0].anonymity_checked_at.is_none());
+/// # }
+/// ```
+pub async fn probe_proxies(
+    proxies: &mut [Proxy],
+    judge: &Arc<Judge>,
+    concurrency: usize,
+    events: &EventBus,
+) -> usize {
+    if proxies.is_empty() {
+        return 0;
+    }
+    debug!(
+        "Probing {} proxies with concurrency {concurrency}",
+        proxies.len()
+    );
+
+    stream::iter(proxies.iter_mut())
+        .map(|proxy| async move {
+            let alive = judge.probe_alive(proxy).await.is_ok();
+            events.publish(GatherEvent::checked(proxy, alive));
+            alive
+        })
+        .buffer_unordered(concurrency.max(1))
+        .filter(|alive| futures::future::ready(*alive))
+        .count()
+        .await
+}
+
 /// Enrich a batch of proxies with IP metadata.
 ///
 /// This function adds metadata to each proxy in the batch concurrently using the provided
@@ -168,6 +378,8 @@ pub async fn verify_proxies(
 /// * `proxies` - A mutable slice of proxies to enrich with metadata
 /// * `sleuth` - An Arc reference to the Sleuth service for IP lookups
 /// * `concurrency` - The maximum number of concurrent enrichment operations
+/// * `executor` - Runs each lookup according to the configured isolation mode
+/// * `events` - Receives an event for every looked up proxy
 ///
 /// # Returns
 ///
@@ -183,12 +395,14 @@ pub async fn verify_proxies(
 /// ```
 /// let sleuth = Arc::new(Sleuth::new());
 /// let mut proxies = vec![/* proxies to enrich */];
-/// enrich_proxies(&mut proxies, &sleuth, 10).await?;
+/// enrich_proxies(&mut proxies, &sleuth, 10, &BulkExecutor::shared(), &EventBus::default()).await?;
 /// ```
 pub async fn enrich_proxies(
     proxies: &mut [Proxy],
     sleuth: &Arc<Sleuth>,
     concurrency: usize,
+    executor: &BulkExecutor,
+    events: &EventBus,
 ) -> ManagerResult<()> {
     if proxies.is_empty() {
         return Ok(());
@@ -196,6 +410,10 @@ pub async fn enrich_proxies(
 
     let total = proxies.len();
     info!("Enriching {total} proxies with concurrency {concurrency}");
+    events.publish(GatherEvent::StageStarted {
+        stage: GatherStage::Enrich,
+        total,
+    });
 
     // Create a progress bar and wrap in Arc for safe sharing
     let progress = Arc::new(create_progress_bar(total as u64));
@@ -207,40 +425,59 @@ pub async fn enrich_proxies(
     // Set up job function using Arc-wrapped Sleuth for thread safety
     // This properly clones the Arc for each future without moving ownership
     let sleuth = Arc::clone(sleuth);
+    let executor = executor.clone();
+    let events_clone = events.clone();
     let job_fn = move |mut proxy: Proxy| -> Pin<Box<dyn Future<Output = (Proxy, bool)> + Send>> {
         // Create local clones for the async block
         let sleuth = Arc::clone(&sleuth);
         let progress = Arc::clone(&progress_clone);
+        let executor = executor.clone();
+        let events = events_clone.clone();
 
         // Box::pin automatically pins the future
         async move {
-            let result = sleuth.lookup_ip_metadata(&proxy.address).await;
+            // Run the lookup itself according to the isolation mode, for
+            // the exit address of gateways since that's what targets see
+            let address = proxy.enrichment_address();
+            let result = match executor
+                .run(async move { sleuth.lookup_ip_metadata(&address).await })
+                .await
+            {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
             // Update progress regardless of result
             progress.inc(1);
 
-            match result {
+            let found = match result {
                 Ok(metadata) => {
                     proxy.update_with_ip_metadata(metadata);
-                    (proxy, true)
+                    true
                 }
-                Err(_) => {
+                Err(e) => {
                     // No need to record failure for enrichment
-                    (proxy, false)
+                    let context = ErrorContext::new("enrich").with_id(&proxy.redacted_id());
+                    debug!("Failed to {context}: {e}");
+                    false
                 }
-            }
+            };
+            events.publish(GatherEvent::enriched(&proxy, found));
+            (proxy, found)
         }
         .boxed()
     };
 
     // Use thread utility to run concurrent batch
-    let results = threading::run_concurrent_batch(proxy_vec, concurrency, &job_fn).await;
+    let batch = threading::run_concurrent_batch(proxy_vec, concurrency, &job_fn).await;
+    let panicked = batch.panicked;
+    progress.inc(panicked as u64);
 
-    // Update the original proxies slice with results
+    // Update the original proxies slice with results, keeping proxies whose lookup panicked
     let mut success_count = 0;
 
-    for (i, (updated_proxy, success)) in results.into_iter().enumerate() {
-        if i < proxies.len() {
-            proxies[i] = updated_proxy;
+    for (slot, result) in proxies.iter_mut().zip(batch.results) {
+        if let Some((updated_proxy, success)) = result {
+            *slot = updated_proxy;
             if success {
                 success_count += 1;
             }
@@ -248,24 +485,163 @@ pub async fn enrich_proxies(
     }
 
     progress.finish_with_message(format!(
-        "Enriched {total}/{total} ({success_count} successful)"
+        "Enriched {total}/{total} ({success_count} successful, {panicked} panicked)"
     ));
 
-    info!("Enriched {total}/{total} proxies ({success_count} successful)");
+    info!("Enriched {total}/{total} proxies ({success_count} successful, {panicked} panicked)");
+    events.publish(GatherEvent::StageFinished {
+        stage: GatherStage::Enrich,
+        total,
+        succeeded: success_count,
+    });
 
     Ok(())
 }
 
+/// Keeps fetches from hammering a single site.
+///
+/// Sites often host several list pages, each its own source. The throttle
+/// groups sources by registered domain, and lets only a few fetches of a
+/// domain run at once, started at least a minimum interval apart. Sources of
+/// other domains are not held up.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::processes::DomainThrottle;
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let throttle = DomainThrottle::new(1, Duration::ZERO);
+///     let first = throttle.acquire("https://lists.example.com/http.txt").await;
+///
+///     // Another page of the same site waits for the first fetch
+///     let second = throttle.acquire("https://www.example.com/socks.txt");
+///     assert!(tokio::time::timeout(Duration::from_millis(50), second).await.is_err());
+///
+///     // Other sites don't
+///     let _other = throttle.acquire("https://example.org/list.txt").await;
+///
+///     drop(first);
+///     let _second = throttle.acquire("https://www.example.com/socks.txt").await;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DomainThrottle {
+    /// Number of fetches of a domain running at once
+    per_domain: usize,
+
+    /// Time between the starts of two fetches of a domain
+    min_interval: Duration,
+
+    /// State of each domain seen so far
+    domains: Mutex<HashMap<String, Arc<DomainSlot>>>,
+}
+
+/// Fetches of a single registered domain
+#[derive(Debug)]
+struct DomainSlot {
+    /// Permits of the fetches allowed at once
+    permits: Arc<Semaphore>,
+
+    /// Earliest start of the next fetch
+    next_start: tokio::sync::Mutex<Instant>,
+}
+
+/// Permission to fetch from a domain, released when dropped
+#[derive(Debug)]
+pub struct DomainPermit {
+    /// The held permit of the domain, None if its semaphore was closed
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Default for DomainThrottle {
+    fn default() -> Self {
+        Self::new(
+            defaults::sources::PER_DOMAIN_CONCURRENCY,
+            Duration::from_millis(defaults::sources::PER_DOMAIN_INTERVAL_MS),
+        )
+    }
+}
+
+impl DomainThrottle {
+    /// Create a throttle
+    ///
+    /// # Arguments
+    ///
+    /// * `per_domain` - Number of fetches of a domain running at once, at least one
+    /// * `min_interval` - Time between the starts of two fetches of a domain
+    #[must_use]
+    pub fn new(per_domain: usize, min_interval: Duration) -> Self {
+        Self {
+            per_domain: per_domain.max(1),
+            min_interval,
+            domains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait until a URL may be fetched
+    ///
+    /// URLs without a host are grouped by the whole URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL about to be fetched
+    ///
+    /// # Returns
+    ///
+    /// A permit to hold while fetching
+    pub async fn acquire(&self, url: &str) -> DomainPermit {
+        let domain = utils::registered_domain(url).unwrap_or_else(|| url.to_string());
+        let slot = {
+            let mut domains = self.domains.lock().unwrap_or_else(PoisonError::into_inner);
+            Arc::clone(domains.entry(domain).or_insert_with(|| {
+                Arc::new(DomainSlot {
+                    permits: Arc::new(Semaphore::new(self.per_domain)),
+                    next_start: tokio::sync::Mutex::new(Instant::now()),
+                })
+            }))
+        };
+
+        let permit = Arc::clone(&slot.permits).acquire_owned().await.ok();
+
+        let start = {
+            let mut next_start = slot.next_start.lock().await;
+            let start = (*next_start).max(Instant::now());
+            *next_start = start + self.min_interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+
+        DomainPermit { _permit: permit }
+    }
+}
+
+/// Fetch the proxies of a source, finding none if its list is unchanged
+async fn fetch_changed(source: &mut Source, requestor: &Requestor) -> SourceResult<Vec<Proxy>> {
+    let result = source.fetch_changed_proxies(requestor).await;
+    if matches!(result, Ok(None)) {
+        debug!("{} is unchanged since the last fetch", source.url);
+    }
+    result.map(Option::unwrap_or_default)
+}
+
 /// Fetch proxies from multiple sources concurrently.
 ///
 /// This function scrapes proxies from all provided sources in parallel,
-/// applying rate limiting and error handling.
+/// applying rate limiting and error handling. The outcome of each fetch is
+/// recorded on the corresponding source, including the failure reason and
+/// HTTP status code of failed fetches. Sources of the same registered domain
+/// are throttled by a default [`DomainThrottle`], and lists unchanged since
+/// the last fetch aren't downloaded again (see `Source::fetch_changed_proxies`).
 ///
 /// # Arguments
 ///
-/// * `sources` - Slice of Source objects to fetch proxies from
+/// * `sources` - Slice of Source objects to fetch proxies from, updated in place
 /// * `requestor` - The Requestor instance to use for HTTP requests
 /// * `concurrency` - Maximum number of concurrent fetch operations
+/// * `events` - Receives an event for every fetched source
 ///
 /// # Returns
 ///
@@ -276,9 +652,10 @@ pub async fn enrich_proxies(
 /// Returns an error if there's a critical failure in the fetch process.
 /// Individual source failures are logged but don't cause the entire operation to fail.
 pub async fn fetch_from_sources(
-    sources: &[crate::definitions::source::Source],
+    sources: &mut [Source],
     requestor: &Requestor,
     concurrency: usize,
+    events: &EventBus,
 ) -> ManagerResult<Vec<Proxy>> {
     if sources.is_empty() {
         return Ok(Vec::new());
@@ -286,54 +663,80 @@ pub async fn fetch_from_sources(
 
     let total = sources.len();
     info!("Fetching from {total} sources with concurrency {concurrency}");
+    events.publish(GatherEvent::StageStarted {
+        stage: GatherStage::Fetch,
+        total,
+    });
 
     // Create a progress bar and wrap in Arc for safe sharing
     let progress = Arc::new(create_progress_bar(total as u64));
 
     // Make a copy of sources for processing
-    let source_vec: Vec<crate::definitions::source::Source> = sources.to_vec();
+    let source_vec: Vec<Source> = sources.to_vec();
 
     // Arc-wrap the requestor for thread safety
     let requestor = Arc::new(requestor.clone());
+    let throttle = Arc::new(DomainThrottle::default());
     let progress_clone = Arc::clone(&progress);
+    let events_clone = events.clone();
 
     // Set up job function with proper captures
-    let job_fn = move |source: crate::definitions::source::Source| -> Pin<Box<dyn Future<Output = (Vec<Proxy>, bool)> + Send>> {
+    let job_fn = move |mut source: Source| -> SourceFetchFuture {
         // Create local clones for the async block
         let requestor = Arc::clone(&requestor);
+        let throttle = Arc::clone(&throttle);
         let progress = Arc::clone(&progress_clone);
+        let events = events_clone.clone();
 
         // Box::pin automatically pins the future
         async move {
-            let result = source.fetch_proxies(&requestor).await;
+            let permit = throttle.acquire(&source.url).await;
+            let result = fetch_changed(&mut source, &requestor).await;
+            drop(permit);
             // Update progress regardless of result
             progress.inc(1);
+            events.publish(fetched_event(&source, &result));
 
             match result {
                 Ok(proxies) => {
                     debug!("Found {} proxies from {}", proxies.len(), source.url);
-                    (proxies, true)
+                    source.record_success(proxies.len());
+                    ((source, proxies), true)
                 }
                 Err(e) => {
-                    warn!("Failed to fetch from {}: {}", source.url, e);
-                    (Vec::new(), false)
+                    source.record_fetch_failure(&e);
+                    let context = ErrorContext::new("fetch")
+                        .with_id(&source.url)
+                        .with_attempt(source.consecutive_failures);
+                    warn_failure(&context, &e);
+                    ((source, Vec::new()), false)
                 }
             }
-        }.boxed()
+        }
+        .boxed()
     };
 
     // Use thread utility to run concurrent batch
-    let results = threading::run_concurrent_batch(source_vec, concurrency, &job_fn).await;
+    let batch = threading::run_concurrent_batch(source_vec, concurrency, &job_fn).await;
+    let panicked = batch.panicked;
+    progress.inc(panicked as u64);
+    if panicked > 0 {
+        warn!("{panicked} source fetches panicked");
+    }
 
-    // Collect unique proxies
+    // Collect unique proxies, keeping sources whose fetch panicked unchanged
     let mut all_proxies = Vec::new();
     let mut success_count = 0;
     let mut proxy_count = 0;
 
-    for (proxies, success) in results {
+    for ((updated, proxies), success) in batch.results.into_iter().flatten() {
         if success {
             success_count += 1;
         }
+        // Match sources back by URL rather than relying on position
+        if let Some(source) = sources.iter_mut().find(|s| s.url == updated.url) {
+            *source = updated;
+        }
         proxy_count += proxies.len();
         all_proxies.extend(proxies);
     }
@@ -364,6 +767,188 @@ pub async fn fetch_from_sources(
         proxy_count,
         unique_proxies.len()
     );
+    events.publish(GatherEvent::StageFinished {
+        stage: GatherStage::Fetch,
+        total,
+        succeeded: success_count,
+    });
 
     Ok(unique_proxies)
 }
+
+/// Settings for streaming proxies from sources to validators.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::processes::StreamingOptions;
+///
+/// let options = StreamingOptions {
+///     verify_concurrency: 50,
+///     ..Default::default()
+/// };
+/// assert!(options.channel_capacity > 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamingOptions {
+    /// Maximum number of concurrent source fetches
+    pub fetch_concurrency: usize,
+
+    /// Maximum number of concurrent proxy judgements
+    pub verify_concurrency: usize,
+
+    /// Number of scraped proxies buffered between fetchers and validators
+    pub channel_capacity: usize,
+}
+
+impl Default for StreamingOptions {
+    fn default() -> Self {
+        StreamingOptions {
+            fetch_concurrency: defaults::streaming::FETCH_CONCURRENCY,
+            verify_concurrency: defaults::DEFAULT_PARALLEL_VALIDATIONS,
+            channel_capacity: defaults::streaming::CHANNEL_CAPACITY,
+        }
+    }
+}
+
+/// Fetch proxies from multiple sources and verify them as they arrive.
+///
+/// Unlike `fetch_from_sources` followed by `verify_proxies`, scraped proxies
+/// are not accumulated before judging. Fetchers push each new proxy into a
+/// bounded channel that validators consume, and wait whenever the channel is
+/// full, so memory use stays bounded when validation lags behind scraping.
+/// The outcome of each fetch is recorded on the corresponding source, and
+/// sources of the same registered domain are throttled as in `fetch_from_sources`.
+///
+/// # Arguments
+///
+/// * `sources` - Slice of Source objects to fetch proxies from, updated in place
+/// * `requestor` - The Requestor instance to use for HTTP requests
+/// * `judge` - An Arc reference to the Judge service for testing proxies
+/// * `options` - Concurrency and buffering settings
+/// * `executor` - Runs each judgement according to the configured isolation mode
+/// * `events` - Receives an event for every fetched source and judged proxy
+///
+/// # Returns
+///
+/// The unique proxies fetched from all sources, each with its judgement recorded.
+///
+/// # Errors
+///
+/// Returns an error if there's a critical failure in the streaming process.
+/// Individual source and proxy failures are recorded but don't cause the entire
+/// operation to fail.
+pub async fn stream_from_sources(
+    sources: &mut [Source],
+    requestor: &Requestor,
+    judge: &Arc<Judge>,
+    options: StreamingOptions,
+    executor: &BulkExecutor,
+    events: &EventBus,
+) -> ManagerResult<Vec<Proxy>> {
+    if sources.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let total = sources.len();
+    info!(
+        "Streaming from {total} sources with fetch concurrency {} and verify concurrency {}",
+        options.fetch_concurrency, options.verify_concurrency
+    );
+    events.publish(GatherEvent::StageStarted {
+        stage: GatherStage::Fetch,
+        total,
+    });
+
+    let (tx, rx) = mpsc::channel::<Proxy>(options.channel_capacity.max(1));
+    let source_vec: Vec<Source> = sources.to_vec();
+
+    // Fetch sources concurrently and push every new proxy into the channel
+    let throttle = DomainThrottle::default();
+    let producer = async move {
+        let throttle = &throttle;
+        let mut fetches = stream::iter(source_vec)
+            .map(|mut source| async move {
+                let permit = throttle.acquire(&source.url).await;
+                let result = fetch_changed(&mut source, requestor).await;
+                drop(permit);
+                (source, result)
+            })
+            .buffer_unordered(options.fetch_concurrency.max(1));
+
+        let mut seen = HashSet::new();
+        let mut updated = Vec::with_capacity(total);
+        let mut consumer_closed = false;
+
+        while let Some((mut source, result)) = fetches.next().await {
+            events.publish(fetched_event(&source, &result));
+            match result {
+                Ok(proxies) => {
+                    debug!("Found {} proxies from {}", proxies.len(), source.url);
+                    source.record_success(proxies.len());
+                    for proxy in proxies {
+                        if consumer_closed || !seen.insert(proxy.id()) {
+                            continue;
+                        }
+                        // Waits while the channel is full, throttling the fetchers
+                        consumer_closed = tx.send(proxy).await.is_err();
+                    }
+                }
+                Err(e) => {
+                    source.record_fetch_failure(&e);
+                    let context = ErrorContext::new("fetch")
+                        .with_id(&source.url)
+                        .with_attempt(source.consecutive_failures);
+                    warn_failure(&context, &e);
+                }
+            }
+            updated.push(source);
+        }
+
+        updated
+    };
+
+    // Judge proxies as they arrive, until the fetchers are done
+    let judge = Arc::clone(judge);
+    let consumer = stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|proxy| (proxy, rx))
+    })
+    .map(|proxy| async {
+        let result = judge_one(Arc::clone(&judge), executor, proxy).await;
+        events.publish(GatherEvent::checked(&result.0, result.1));
+        result
+    })
+    .buffer_unordered(options.verify_concurrency.max(1))
+    .collect::<Vec<(Proxy, bool)>>();
+
+    let (updated, results) = tokio::join!(producer, consumer);
+
+    // Write the recorded outcomes back to the sources
+    let mut success_count = 0;
+    for source in updated {
+        if source.consecutive_failures == 0 {
+            success_count += 1;
+        }
+        if let Some(s) = sources.iter_mut().find(|s| s.url == source.url) {
+            *s = source;
+        }
+    }
+
+    let verified = results.iter().filter(|(_, success)| *success).count();
+    info!(
+        "Streamed {} unique proxies from {success_count}/{total} sources ({verified} verified)",
+        results.len()
+    );
+    events.publish(GatherEvent::StageFinished {
+        stage: GatherStage::Fetch,
+        total,
+        succeeded: success_count,
+    });
+    events.publish(GatherEvent::StageFinished {
+        stage: GatherStage::Check,
+        total: results.len(),
+        succeeded: verified,
+    });
+
+    Ok(results.into_iter().map(|(proxy, _)| proxy).collect())
+}
@@ -0,0 +1,799 @@
+//! # Scheduler Module
+//!
+//! Runs recurring maintenance jobs on cron-style schedules.
+//!
+//! ## Overview
+//!
+//! The `Scheduler` keeps one cron schedule per `ScheduledJob`: fetching sources,
+//! rechecking stale proxies, pruning failing proxies, and snapshotting
+//! statistics. Schedules are loaded from `AppConfig::schedules`, so they are
+//! persisted with the rest of the configuration. The scheduler exposes when each
+//! job runs next, lets jobs be triggered manually, and runs due jobs against a
+//! `ProxyManager`, which makes it the backbone of long-running operation.
+//!
+//! Each statistics snapshot is compared with the ones before it. A working
+//! proxy count dropping, or an average latency rising, much faster than it
+//! usually moves raises a `HealthAnomaly`. Such changes rarely mean proxies
+//! died on their own; more often a judge is down or a provider banned the
+//! checks. Anomalies are logged, queued until taken, and posted to a webhook
+//! if one is set.
+//!
+//! Expressions use cron syntax with either five fields (minute, hour, day of
+//! month, month, day of week) or six fields with a leading seconds field.
+//!
+//! ## Examples
+//!
+//! ```
+//! use gooty_proxy::definitions::enums::ScheduledJob;
+//! use gooty_proxy::orchestration::scheduler::Scheduler;
+//!
+//! let mut scheduler = Scheduler::new();
+//! scheduler.add(ScheduledJob::FetchSources, "0 * * * *").unwrap();
+//! scheduler.add(ScheduledJob::SnapshotStats, "*/5 * * * *").unwrap();
+//!
+//! // Both jobs have an upcoming run
+//! assert_eq!(scheduler.upcoming().len(), 2);
+//!
+//! // A manual trigger makes the job due straight away
+//! scheduler.trigger(ScheduledJob::FetchSources);
+//! let due = scheduler.take_due(chrono::Utc::now());
+//! assert_eq!(due, vec![ScheduledJob::FetchSources]);
+//! ```
+
+#[cfg(feature = "manager")]
+use crate::definitions::errors::{ManagerError, ManagerResult};
+use crate::definitions::{
+    defaults,
+    enums::{HealthMetric, ScheduledJob},
+};
+#[cfg(feature = "manager")]
+use crate::io::filesystem::{AppConfig, Filestore};
+#[cfg(feature = "manager")]
+use crate::orchestration::manager::{ProxyManager, ProxyStats, SourceStats};
+#[cfg(feature = "manager")]
+use crate::orchestration::processes::StreamingOptions;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "manager")]
+use cron::Schedule;
+#[cfg(feature = "manager")]
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "manager")]
+use std::{collections::VecDeque, str::FromStr};
+
+/// A persisted schedule for one job.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// The job to run
+    pub job: ScheduledJob,
+
+    /// Cron expression deciding when the job runs
+    pub cron: String,
+
+    /// Whether the job runs on its schedule
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ScheduleConfig {
+    /// Returns the default schedules of all jobs.
+    ///
+    /// # Returns
+    ///
+    /// One enabled schedule per job, using the expressions in `defaults::scheduler`.
+    #[must_use]
+    pub fn defaults() -> Vec<ScheduleConfig> {
+        [
+            (
+                ScheduledJob::FetchSources,
+                defaults::scheduler::FETCH_SOURCES_CRON,
+            ),
+            (
+                ScheduledJob::RecheckStale,
+                defaults::scheduler::RECHECK_STALE_CRON,
+            ),
+            (ScheduledJob::Prune, defaults::scheduler::PRUNE_CRON),
+            (
+                ScheduledJob::SnapshotStats,
+                defaults::scheduler::SNAPSHOT_STATS_CRON,
+            ),
+            (
+                ScheduledJob::RefreshCloudRanges,
+                defaults::scheduler::REFRESH_CLOUD_RANGES_CRON,
+            ),
+        ]
+        .into_iter()
+        .map(|(job, cron)| ScheduleConfig {
+            job,
+            cron: cron.to_string(),
+            enabled: true,
+        })
+        .collect()
+    }
+}
+
+/// Statistics recorded by the `SnapshotStats` job.
+#[cfg(feature = "manager")]
+#[derive(Debug, Clone)]
+pub struct StatsSnapshot {
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+
+    /// Proxy statistics at that time
+    pub proxies: ProxyStats,
+
+    /// Source statistics at that time
+    pub sources: SourceStats,
+}
+
+/// An abnormally fast change of a pool health metric.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthAnomaly {
+    /// When the snapshot showing the change was taken
+    pub detected_at: DateTime<Utc>,
+
+    /// The metric that changed
+    pub metric: HealthMetric,
+
+    /// Value of the metric in the snapshot
+    pub value: f64,
+
+    /// Average value of the metric in the snapshots before it
+    pub mean: f64,
+
+    /// How many standard deviations the value lies from the mean
+    pub z_score: f64,
+}
+
+impl std::fmt::Display for HealthAnomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {:.0}, usually {:.0} (z-score {:.1})",
+            self.metric, self.value, self.mean, self.z_score
+        )
+    }
+}
+
+/// A job registered with the scheduler.
+#[cfg(feature = "manager")]
+#[derive(Debug, Clone)]
+pub struct ScheduleEntry {
+    /// The job to run
+    job: ScheduledJob,
+
+    /// The expression the schedule was parsed from
+    expression: String,
+
+    /// The parsed schedule
+    schedule: Schedule,
+
+    /// Whether the job runs on its schedule
+    enabled: bool,
+
+    /// When the job runs next
+    next_run: Option<DateTime<Utc>>,
+
+    /// When the job last ran
+    last_run: Option<DateTime<Utc>>,
+
+    /// Number of times the job has run
+    run_count: usize,
+
+    /// Whether the job was triggered manually and runs at the next opportunity
+    triggered: bool,
+}
+
+#[cfg(feature = "manager")]
+impl ScheduleEntry {
+    /// Returns the scheduled job.
+    #[must_use]
+    pub fn job(&self) -> ScheduledJob {
+        self.job
+    }
+
+    /// Returns the cron expression of the schedule.
+    #[must_use]
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Returns whether the job runs on its schedule.
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns when the job runs next, if it is enabled.
+    #[must_use]
+    pub fn next_run(&self) -> Option<DateTime<Utc>> {
+        if self.enabled { self.next_run } else { None }
+    }
+
+    /// Returns when the job last ran.
+    #[must_use]
+    pub fn last_run(&self) -> Option<DateTime<Utc>> {
+        self.last_run
+    }
+
+    /// Returns the number of times the job has run.
+    #[must_use]
+    pub fn run_count(&self) -> usize {
+        self.run_count
+    }
+
+    /// Checks whether the job should run at `now`.
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.triggered || (self.enabled && self.next_run.is_some_and(|at| at <= now))
+    }
+}
+
+/// Runs recurring jobs on cron schedules.
+#[cfg(feature = "manager")]
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    /// Registered jobs, one entry per job
+    entries: Vec<ScheduleEntry>,
+
+    /// Maximum number of concurrent operations used by jobs
+    concurrency: usize,
+
+    /// How long a proxy check stays fresh for `RecheckStale`
+    max_proxy_age: chrono::Duration,
+
+    /// Minimum check success rate kept by `Prune`, as a percentage
+    min_success_rate: usize,
+
+    /// Snapshots recorded by `SnapshotStats`, oldest first
+    snapshots: VecDeque<StatsSnapshot>,
+
+    /// Z-score beyond which a health metric is considered abnormal
+    anomaly_threshold: f64,
+
+    /// Anomalies detected and not yet taken
+    anomalies: Vec<HealthAnomaly>,
+
+    /// URL anomalies are posted to, if any
+    webhook: Option<String>,
+
+    /// Filestore `RecheckStale` checkpoints its progress to, if any
+    checkpoints: Option<Filestore>,
+}
+
+#[cfg(feature = "manager")]
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "manager")]
+impl Scheduler {
+    /// Creates a scheduler without jobs.
+    #[must_use]
+    pub fn new() -> Self {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let min_success_rate = (defaults::rotation::MIN_SUCCESS_RATE * 100.0) as usize;
+
+        Scheduler {
+            entries: Vec::new(),
+            concurrency: defaults::DEFAULT_PARALLEL_VALIDATIONS,
+            max_proxy_age: chrono::Duration::seconds(
+                i64::try_from(defaults::persistence::MAX_PROXY_AGE_SECS).unwrap_or(i64::MAX),
+            ),
+            min_success_rate,
+            snapshots: VecDeque::new(),
+            anomaly_threshold: defaults::scheduler::ANOMALY_Z_THRESHOLD,
+            anomalies: Vec::new(),
+            webhook: None,
+            checkpoints: None,
+        }
+    }
+
+    /// Creates a scheduler from the application configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The application configuration
+    ///
+    /// # Returns
+    ///
+    /// A scheduler with every configured schedule registered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a configured expression cannot be parsed.
+    pub fn from_config(config: &AppConfig) -> ManagerResult<Self> {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let min_success_rate = (config.min_success_rate.clamp(0.0, 1.0) * 100.0) as usize;
+
+        let mut scheduler = Scheduler::new().with_concurrency(config.parallel_validations);
+        scheduler.min_success_rate = min_success_rate;
+        scheduler.webhook.clone_from(&config.health_webhook);
+
+        for schedule in &config.schedules {
+            scheduler.add(schedule.job, &schedule.cron)?;
+            scheduler.set_enabled(schedule.job, schedule.enabled);
+        }
+        Ok(scheduler)
+    }
+
+    /// Sets the maximum number of concurrent operations used by jobs.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - Maximum number of concurrent operations, at least one
+    ///
+    /// # Returns
+    ///
+    /// Self with the concurrency set.
+    #[must_use]
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Checkpoints `RecheckStale` progress to a filestore.
+    ///
+    /// An interrupted recheck, for example by a daemon restart, then resumes
+    /// where it left off the next time the job runs.
+    ///
+    /// After every run of due jobs, the proxies they changed are saved to the
+    /// filestore's journal with `ProxyManager::save_changes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `filestore` - The filestore checkpoints are kept in
+    ///
+    /// # Returns
+    ///
+    /// Self with checkpointing enabled.
+    #[must_use]
+    pub fn with_checkpoints(mut self, filestore: Filestore) -> Self {
+        self.checkpoints = Some(filestore);
+        self
+    }
+
+    /// Sets the z-score beyond which a health metric is considered abnormal.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - Number of standard deviations from the recent mean
+    ///
+    /// # Returns
+    ///
+    /// Self with the threshold set.
+    #[must_use]
+    pub fn with_anomaly_threshold(mut self, threshold: f64) -> Self {
+        self.anomaly_threshold = threshold.abs();
+        self
+    }
+
+    /// Posts detected health anomalies to a webhook.
+    ///
+    /// Each anomaly is sent as a JSON `HealthAnomaly` in its own request.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL anomalies are posted to
+    ///
+    /// # Returns
+    ///
+    /// Self with the webhook set.
+    #[must_use]
+    pub fn with_webhook(mut self, url: &str) -> Self {
+        self.webhook = Some(url.to_string());
+        self
+    }
+
+    /// Registers a job, replacing any existing schedule for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The job to run
+    /// * `expression` - Cron expression with five or six fields
+    ///
+    /// # Errors
+    ///
+    /// Returns `ManagerError::InvalidSchedule` if the expression cannot be parsed.
+    pub fn add(&mut self, job: ScheduledJob, expression: &str) -> ManagerResult<()> {
+        let schedule = parse_expression(expression)?;
+        let next_run = schedule.upcoming(Utc).next();
+
+        self.remove(job);
+        self.entries.push(ScheduleEntry {
+            job,
+            expression: expression.to_string(),
+            schedule,
+            enabled: true,
+            next_run,
+            last_run: None,
+            run_count: 0,
+            triggered: false,
+        });
+        Ok(())
+    }
+
+    /// Unregisters a job.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the job was registered.
+    pub fn remove(&mut self, job: ScheduledJob) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.job != job);
+        self.entries.len() != before
+    }
+
+    /// Enables or disables a job's schedule.
+    ///
+    /// Disabled jobs still run when triggered manually.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the job is registered.
+    pub fn set_enabled(&mut self, job: ScheduledJob, enabled: bool) -> bool {
+        match self.entries.iter_mut().find(|e| e.job == job) {
+            Some(entry) => {
+                entry.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the registered jobs.
+    #[must_use]
+    pub fn entries(&self) -> &[ScheduleEntry] {
+        &self.entries
+    }
+
+    /// Returns when a job runs next.
+    ///
+    /// # Returns
+    ///
+    /// The next run time, or `None` if the job is not registered or disabled.
+    #[must_use]
+    pub fn next_run(&self, job: ScheduledJob) -> Option<DateTime<Utc>> {
+        self.entries
+            .iter()
+            .find(|e| e.job == job)
+            .and_then(ScheduleEntry::next_run)
+    }
+
+    /// Returns the upcoming run of every enabled job, soonest first.
+    #[must_use]
+    pub fn upcoming(&self) -> Vec<(ScheduledJob, DateTime<Utc>)> {
+        let mut upcoming: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|e| e.next_run().map(|at| (e.job, at)))
+            .collect();
+        upcoming.sort_by_key(|&(_, at)| at);
+        upcoming
+    }
+
+    /// Returns how long until the next job is due.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    ///
+    /// # Returns
+    ///
+    /// Zero if a job is already due, or `None` if nothing is scheduled.
+    #[must_use]
+    pub fn time_until_next(&self, now: DateTime<Utc>) -> Option<std::time::Duration> {
+        if self.entries.iter().any(|e| e.triggered) {
+            return Some(std::time::Duration::ZERO);
+        }
+
+        self.upcoming()
+            .first()
+            .map(|&(_, at)| (at - now).to_std().unwrap_or_default())
+    }
+
+    /// Marks a job to run at the next opportunity, regardless of its schedule.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the job is registered.
+    pub fn trigger(&mut self, job: ScheduledJob) -> bool {
+        match self.entries.iter_mut().find(|e| e.job == job) {
+            Some(entry) => {
+                entry.triggered = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes the jobs due at `now` and advances their schedules.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    ///
+    /// # Returns
+    ///
+    /// The due jobs, in registration order.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<ScheduledJob> {
+        let mut due = Vec::new();
+        for entry in self.entries.iter_mut().filter(|e| e.is_due(now)) {
+            entry.triggered = false;
+            entry.last_run = Some(now);
+            entry.run_count += 1;
+            entry.next_run = entry.schedule.after(&now).next();
+            due.push(entry.job);
+        }
+        due
+    }
+
+    /// Returns the statistics snapshots recorded so far, oldest first.
+    #[must_use]
+    pub fn snapshots(&self) -> &VecDeque<StatsSnapshot> {
+        &self.snapshots
+    }
+
+    /// Takes the health anomalies detected since they were last taken.
+    pub fn take_anomalies(&mut self) -> Vec<HealthAnomaly> {
+        std::mem::take(&mut self.anomalies)
+    }
+
+    /// Runs the jobs due now against a manager.
+    ///
+    /// A failing job is logged and doesn't stop the remaining jobs.
+    ///
+    /// # Arguments
+    ///
+    /// * `manager` - The manager to run the jobs against
+    ///
+    /// # Returns
+    ///
+    /// The jobs that ran, each with a summary of its work or its error.
+    pub async fn run_due(
+        &mut self,
+        manager: &mut ProxyManager,
+    ) -> Vec<(ScheduledJob, ManagerResult<String>)> {
+        let mut results = Vec::new();
+        for job in self.take_due(Utc::now()) {
+            let result = self.run_job(job, manager).await;
+            match &result {
+                Ok(summary) => info!("Scheduled job {job}: {summary}"),
+                Err(e) => warn!("Scheduled job {job} failed: {e}"),
+            }
+            results.push((job, result));
+        }
+
+        if let (false, Some(filestore)) = (results.is_empty(), &self.checkpoints) {
+            match manager.save_changes(filestore) {
+                Ok(saved) => debug!("Journaled {saved} changed proxies"),
+                Err(e) => warn!("Failed to journal changed proxies: {e}"),
+            }
+        }
+        results
+    }
+
+    /// Runs a single job against a manager, outside of its schedule.
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The job to run
+    /// * `manager` - The manager to run the job against
+    ///
+    /// # Returns
+    ///
+    /// A summary of the work done.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job fails.
+    pub async fn run_job(
+        &mut self,
+        job: ScheduledJob,
+        manager: &mut ProxyManager,
+    ) -> ManagerResult<String> {
+        match job {
+            ScheduledJob::FetchSources => {
+                let added = manager
+                    .stream_from_all_sources(StreamingOptions {
+                        verify_concurrency: self.concurrency,
+                        ..StreamingOptions::default()
+                    })
+                    .await?;
+                Ok(format!("added {added} proxies"))
+            }
+            ScheduledJob::RecheckStale => {
+                let rechecked = match &self.checkpoints {
+                    Some(filestore) => {
+                        manager
+                            .recheck_stale_proxies_checkpointed(
+                                self.max_proxy_age,
+                                self.concurrency,
+                                filestore,
+                            )
+                            .await?
+                    }
+                    None => {
+                        manager
+                            .recheck_stale_proxies(self.max_proxy_age, self.concurrency)
+                            .await?
+                    }
+                };
+                Ok(format!("rechecked {rechecked} proxies"))
+            }
+            ScheduledJob::Prune => {
+                let removed = manager
+                    .prune_proxies(self.min_success_rate, defaults::scheduler::PRUNE_MIN_CHECKS);
+                Ok(format!("removed {removed} proxies"))
+            }
+            ScheduledJob::SnapshotStats => {
+                let snapshot = StatsSnapshot {
+                    taken_at: Utc::now(),
+                    proxies: manager.get_proxy_stats(),
+                    sources: manager.get_source_stats(),
+                };
+                let mut summary = format!(
+                    "{} proxies ({} working), {} sources ({} active)",
+                    snapshot.proxies.total,
+                    snapshot.proxies.working,
+                    snapshot.sources.total,
+                    snapshot.sources.active
+                );
+
+                let anomalies = self.detect_anomalies(&snapshot);
+                self.snapshots.push_back(snapshot);
+                while self.snapshots.len() > defaults::scheduler::MAX_SNAPSHOTS {
+                    self.snapshots.pop_front();
+                }
+
+                if !anomalies.is_empty() {
+                    summary = format!("{summary}, {} health anomalies", anomalies.len());
+                    for anomaly in &anomalies {
+                        warn!("Pool health anomaly: {anomaly}");
+                        if let Some(url) = &self.webhook {
+                            if let Err(e) = post_anomaly(url, anomaly).await {
+                                warn!("Failed to post health anomaly to {url}: {e}");
+                            }
+                        }
+                    }
+                    self.anomalies.extend(anomalies);
+                }
+                Ok(summary)
+            }
+            ScheduledJob::RefreshCloudRanges => {
+                let ranges = manager.refresh_cloud_ranges().await?;
+                Ok(format!("{ranges} cloud ranges known"))
+            }
+        }
+    }
+
+    /// Compares a snapshot with the recent ones before it.
+    ///
+    /// # Returns
+    ///
+    /// The metrics of the snapshot that changed abnormally fast for the worse.
+    #[allow(clippy::cast_precision_loss)]
+    fn detect_anomalies(&self, snapshot: &StatsSnapshot) -> Vec<HealthAnomaly> {
+        let recent: Vec<&StatsSnapshot> = self
+            .snapshots
+            .iter()
+            .rev()
+            .take(defaults::scheduler::ANOMALY_WINDOW)
+            .collect();
+
+        let working: Vec<f64> = recent.iter().map(|s| s.proxies.working as f64).collect();
+        let latency: Vec<f64> = recent
+            .iter()
+            .filter_map(|s| s.proxies.avg_latency.map(|l| l.get() as f64))
+            .collect();
+
+        let checks = [
+            (
+                HealthMetric::WorkingProxies,
+                working,
+                Some(snapshot.proxies.working as f64),
+                -1.0,
+            ),
+            (
+                HealthMetric::AverageLatency,
+                latency,
+                snapshot.proxies.avg_latency.map(|l| l.get() as f64),
+                1.0,
+            ),
+        ];
+
+        checks
+            .into_iter()
+            .filter(|(_, history, _, _)| history.len() >= defaults::scheduler::ANOMALY_MIN_SAMPLES)
+            .filter_map(|(metric, history, value, direction)| {
+                let value = value?;
+                let z_score = z_score(&history, value)?;
+                (z_score * direction >= self.anomaly_threshold).then(|| HealthAnomaly {
+                    detected_at: snapshot.taken_at,
+                    metric,
+                    value,
+                    mean: history.iter().sum::<f64>() / history.len() as f64,
+                    z_score,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Computes how many standard deviations a value lies from earlier values.
+///
+/// The standard deviation is taken to be at least
+/// `defaults::scheduler::ANOMALY_MIN_RELATIVE_SPREAD` of the mean, so
+/// values that barely moved don't turn small changes into large scores.
+///
+/// # Arguments
+///
+/// * `history` - The earlier values
+/// * `value` - The value to score
+///
+/// # Returns
+///
+/// The z-score of the value, negative below the mean, or `None` without
+/// earlier values.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::scheduler::z_score;
+///
+/// let working = [100.0, 104.0, 98.0, 101.0, 97.0, 100.0];
+/// assert!(z_score(&working, 99.0).unwrap().abs() < 1.0);
+/// assert!(z_score(&working, 40.0).unwrap() < -3.0);
+/// assert_eq!(z_score(&[], 40.0), None);
+/// ```
+#[must_use]
+pub fn z_score(history: &[f64], value: f64) -> Option<f64> {
+    if history.is_empty() {
+        return None;
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let count = history.len() as f64;
+    let mean = history.iter().sum::<f64>() / count;
+    let variance = history.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+    let spread = variance
+        .sqrt()
+        .max(mean.abs() * defaults::scheduler::ANOMALY_MIN_RELATIVE_SPREAD)
+        .max(f64::EPSILON);
+    Some((value - mean) / spread)
+}
+
+/// Posts a health anomaly to a webhook as JSON.
+#[cfg(feature = "manager")]
+async fn post_anomaly(url: &str, anomaly: &HealthAnomaly) -> reqwest::Result<()> {
+    reqwest::Client::new()
+        .post(url)
+        .timeout(std::time::Duration::from_secs(
+            defaults::DEFAULT_REQUEST_TIMEOUT_SECS,
+        ))
+        .json(anomaly)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Parses a cron expression with five or six fields.
+///
+/// Five-field expressions get a leading seconds field of zero.
+#[cfg(feature = "manager")]
+fn parse_expression(expression: &str) -> ManagerResult<Schedule> {
+    let normalized = if expression.split_whitespace().count() == 5 {
+        format!("0 {expression}")
+    } else {
+        expression.to_string()
+    };
+
+    Schedule::from_str(&normalized)
+        .map_err(|e| ManagerError::InvalidSchedule(format!("{expression}: {e}")))
+}
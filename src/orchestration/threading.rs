@@ -35,16 +35,141 @@
 ///     println!("Worker thread running");
 /// });
 /// ```
-use futures::{StreamExt, stream};
+use crate::definitions::{
+    defaults,
+    enums::ConcurrencyIsolation,
+    errors::{ManagerError, ManagerResult},
+};
+use crate::io::filesystem::AppConfig;
+use futures::{FutureExt, StreamExt, stream};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::future::Future;
 use std::pin::Pin;
-use tokio::sync::mpsc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::{Notify, Semaphore, mpsc};
 use tokio::task::JoinHandle;
 
-/// Manages a collection of task handles for concurrent execution
+/// How a task managed by `TaskManager` ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskOutcome {
+    /// The task ran to completion
+    Completed,
+    /// The task returned an error, with its description
+    Failed(String),
+    /// The task panicked, with the panic message
+    Panicked(String),
+    /// The task exceeded its timeout and was stopped
+    TimedOut,
+    /// The task was aborted before it completed
+    Aborted,
+}
+
+impl TaskOutcome {
+    /// Check whether the task ran to completion
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        matches!(self, TaskOutcome::Completed)
+    }
+}
+
+impl std::fmt::Display for TaskOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskOutcome::Completed => write!(f, "completed"),
+            TaskOutcome::Failed(e) => write!(f, "failed: {e}"),
+            TaskOutcome::Panicked(message) => write!(f, "panicked: {message}"),
+            TaskOutcome::TimedOut => write!(f, "timed out"),
+            TaskOutcome::Aborted => write!(f, "aborted"),
+        }
+    }
+}
+
+/// The outcome of a named task
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskResult {
+    /// Name the task was registered under
+    pub name: String,
+
+    /// How the task ended
+    pub outcome: TaskOutcome,
+}
+
+/// A task registered with a `TaskManager`
+struct ManagedTask {
+    name: String,
+    handle: JoinHandle<TaskOutcome>,
+}
+
+/// Manages a collection of named tasks for concurrent execution
+///
+/// Tasks are registered under unique names, may carry a timeout, and can be
+/// aborted individually. Joining reports how every task ended, including
+/// errors, panics, and timeouts, instead of discarding them. Tasks that
+/// already ended are reaped whenever a new one is spawned, with those that
+/// didn't complete logged as warnings, so only tasks still running or ended
+/// since the last spawn are reported when joining.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::threading::{TaskManager, TaskOutcome};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let mut tasks = TaskManager::new();
+/// tasks.spawn_named("ok", async {}).unwrap();
+/// tasks
+///     .spawn_task("fails", None, async { Err::<(), _>("no sources") })
+///     .unwrap();
+/// tasks
+///     .spawn_task("slow", Some(Duration::from_millis(10)), async {
+///         tokio::time::sleep(Duration::from_secs(60)).await;
+///         Ok::<(), String>(())
+///     })
+///     .unwrap();
+/// tasks.spawn_named("panics", async { panic!("boom") }).unwrap();
+///
+/// let outcomes: Vec<TaskOutcome> = tasks
+///     .join_all_results()
+///     .await
+///     .into_iter()
+///     .map(|r| r.outcome)
+///     .collect();
+/// assert_eq!(
+///     outcomes,
+///     vec![
+///         TaskOutcome::Completed,
+///         TaskOutcome::Failed("no sources".to_string()),
+///         TaskOutcome::TimedOut,
+///         TaskOutcome::Panicked("boom".to_string()),
+///     ]
+/// );
+///
+/// // Finished tasks are reaped when the next one is spawned, but the
+/// // results of those that didn't complete are kept until joined
+/// tasks.spawn_named("first", async {}).unwrap();
+/// tasks.spawn_named("crashes", async { panic!("lost") }).unwrap();
+/// tokio::task::yield_now().await;
+/// tasks.spawn_named("second", async {}).unwrap();
+/// assert_eq!(tasks.names(), vec!["second"]);
+///
+/// let results = tasks.join_all_results().await;
+/// assert_eq!(results.len(), 2);
+/// assert_eq!(results[0].name, "crashes");
+/// assert_eq!(results[0].outcome, TaskOutcome::Panicked("lost".to_string()));
+/// # }
+/// ```
 #[derive(Default)]
 pub struct TaskManager {
-    tasks: Vec<JoinHandle<()>>,
+    tasks: Vec<ManagedTask>,
+    /// Results of reaped tasks that didn't complete, kept until joined
+    ended: Vec<TaskResult>,
+    next_id: usize,
 }
 
 impl TaskManager {
@@ -55,74 +180,784 @@ impl TaskManager {
     }
 
     /// Spawn a new task and add it to the managed set
+    ///
+    /// The task is registered under a generated name, skipping the names of
+    /// running tasks spawned with `spawn_named`.
     pub fn spawn<F>(&mut self, future: F)
     where
         F: Future<Output = ()> + Send + 'static,
     {
-        let handle = tokio::spawn(future);
-        self.tasks.push(handle);
+        let name = loop {
+            let name = format!("task-{}", self.next_id);
+            if !self.is_running(&name) {
+                break name;
+            }
+            self.next_id += 1;
+        };
+        let handle = tokio::spawn(Self::supervise(None, async move {
+            future.await;
+            Ok::<(), Infallible>(())
+        }));
+        self.register(name, handle);
+    }
+
+    /// Spawn a new task under a name
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Unique name of the task
+    /// * `future` - The task to run
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a running task is already registered under the name
+    pub fn spawn_named<F>(&mut self, name: &str, future: F) -> ManagerResult<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn_task(name, None, async move {
+            future.await;
+            Ok::<(), Infallible>(())
+        })
+    }
+
+    /// Spawn a new fallible task under a name, optionally with a timeout
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Unique name of the task
+    /// * `timeout` - How long the task may run before it is stopped
+    /// * `future` - The task to run
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a running task is already registered under the name
+    pub fn spawn_task<F, E>(
+        &mut self,
+        name: &str,
+        timeout: Option<Duration>,
+        future: F,
+    ) -> ManagerResult<()>
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+        E: std::fmt::Display + Send + 'static,
+    {
+        if self.is_running(name) {
+            return Err(ManagerError::TaskAlreadyRunning(name.to_string()));
+        }
+
+        let handle = tokio::spawn(Self::supervise(timeout, future));
+        self.register(name.to_string(), handle);
+        Ok(())
+    }
+
+    /// Abort a task by name
+    ///
+    /// The task is reported as `TaskOutcome::Aborted` when joined.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a task was registered under the name
+    pub fn abort(&mut self, name: &str) -> bool {
+        let mut found = false;
+        for task in self.tasks.iter().filter(|t| t.name == name) {
+            task.handle.abort();
+            found = true;
+        }
+        found
+    }
+
+    /// Check whether a task registered under a name is still running
+    #[must_use]
+    pub fn is_running(&self, name: &str) -> bool {
+        self.tasks
+            .iter()
+            .any(|t| t.name == name && !t.handle.is_finished())
+    }
+
+    /// Get the names of the registered tasks, in spawn order
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.tasks.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    /// Get the number of registered tasks
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Check whether no tasks are registered
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
     }
 
     /// Wait for all tasks to complete
+    ///
+    /// Tasks that did not complete are logged as warnings.
     pub async fn join_all(&mut self) {
-        while let Some(task) = self.tasks.pop() {
-            let _ = task.await;
+        for result in self.join_all_results().await {
+            if !result.outcome.is_success() {
+                log::warn!("Task {} {}", result.name, result.outcome);
+            }
         }
     }
 
+    /// Wait for all tasks to complete and report how each ended
+    ///
+    /// Tasks reaped since the last join are included if they didn't complete.
+    ///
+    /// # Returns
+    ///
+    /// The result of every registered task, in spawn order
+    pub async fn join_all_results(&mut self) -> Vec<TaskResult> {
+        // Reaped tasks were all spawned before the registered ones
+        let mut results = std::mem::take(&mut self.ended);
+        results.reserve(self.tasks.len());
+        for task in self.tasks.drain(..) {
+            let outcome = Self::outcome_of(task.handle.await);
+            results.push(TaskResult {
+                name: task.name,
+                outcome,
+            });
+        }
+        results
+    }
+
     /// Cancel all running tasks
     pub fn cancel_all(&mut self) {
         for task in self.tasks.drain(..) {
-            task.abort();
+            task.handle.abort();
+        }
+    }
+
+    /// Add a spawned task to the managed set, reaping finished tasks first
+    fn register(&mut self, name: String, handle: JoinHandle<TaskOutcome>) {
+        self.reap_finished();
+        self.next_id += 1;
+        self.tasks.push(ManagedTask { name, handle });
+    }
+
+    /// Drop the tasks that already ended, keeping the results of those that
+    /// didn't complete for the next join
+    ///
+    /// Keeps the managed set from growing without bound in a long-running
+    /// process that keeps spawning tasks that complete and never joins them.
+    fn reap_finished(&mut self) {
+        let (finished, running) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition::<Vec<_>, _>(|t| t.handle.is_finished());
+        self.tasks = running;
+
+        for task in finished {
+            let Some(result) = task.handle.now_or_never() else {
+                continue;
+            };
+            let outcome = Self::outcome_of(result);
+            if !outcome.is_success() {
+                self.ended.push(TaskResult {
+                    name: task.name,
+                    outcome,
+                });
+            }
+        }
+    }
+
+    /// Turn the result of joining a task into its outcome
+    fn outcome_of(result: Result<TaskOutcome, tokio::task::JoinError>) -> TaskOutcome {
+        match result {
+            Ok(outcome) => outcome,
+            Err(e) if e.is_cancelled() => TaskOutcome::Aborted,
+            Err(e) => TaskOutcome::Panicked(panic_message(e.into_panic().as_ref())),
+        }
+    }
+
+    /// Run a task, turning its result and timeout into an outcome
+    async fn supervise<F, E>(timeout: Option<Duration>, future: F) -> TaskOutcome
+    where
+        F: Future<Output = Result<(), E>>,
+        E: std::fmt::Display,
+    {
+        let result = match timeout {
+            Some(limit) => match tokio::time::timeout(limit, future).await {
+                Ok(result) => result,
+                Err(_) => return TaskOutcome::TimedOut,
+            },
+            None => future.await,
+        };
+
+        match result {
+            Ok(()) => TaskOutcome::Completed,
+            Err(e) => TaskOutcome::Failed(e.to_string()),
         }
     }
 }
 
-/// Creates a set of worker tasks with a bounded channel for work distribution
-pub fn create_worker_pool<T, F, Fut>(
-    concurrency: usize,
-    worker_fn: F,
-) -> (mpsc::Sender<T>, TaskManager)
-where
-    T: Send + 'static,
-    F: FnMut(T) -> Fut + Send + Clone + 'static,
-    Fut: Future<Output = ()> + Send + 'static,
-{
-    let (tx, rx) = mpsc::channel::<T>(concurrency);
-    let rx = std::sync::Arc::new(tokio::sync::Mutex::new(rx));
+/// Extract a readable message from a panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
 
-    let mut task_manager = TaskManager::new();
+/// Runtime owned by a `BulkExecutor`, shut down without blocking when dropped
+struct DedicatedRuntime(Option<Runtime>);
 
-    for _ in 0..concurrency {
-        let mut worker_fn = worker_fn.clone();
-        let rx = rx.clone();
+impl Drop for DedicatedRuntime {
+    fn drop(&mut self) {
+        // Dropping a runtime blocks, which panics inside another runtime
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Where the jobs of a `BulkExecutor` run
+#[derive(Clone)]
+enum ExecutorKind {
+    /// On the caller's runtime
+    Shared,
+    /// On the caller's runtime, holding a permit from a shared budget
+    Budget(Arc<Semaphore>),
+    /// On a runtime of its own
+    Dedicated(Arc<DedicatedRuntime>),
+}
 
-        task_manager.spawn(async move {
+/// Runs the jobs of bulk operations according to a `ConcurrencyIsolation` mode
+///
+/// Bulk judgement at high concurrency can starve a busy host runtime. An
+/// executor either bounds the number of jobs in flight across all bulk
+/// operations sharing it, or moves the jobs onto a dedicated multi-thread
+/// runtime, leaving the host runtime to await their results only.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::threading::BulkExecutor;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let executor = BulkExecutor::dedicated(2).unwrap();
+/// assert_eq!(executor.run(async { 21 * 2 }).await.unwrap(), 42);
+///
+/// let budget = BulkExecutor::with_budget(4);
+/// assert_eq!(budget.run(async { "done" }).await.unwrap(), "done");
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct BulkExecutor {
+    kind: ExecutorKind,
+}
+
+impl Default for BulkExecutor {
+    fn default() -> Self {
+        Self::shared()
+    }
+}
+
+impl std::fmt::Debug for BulkExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulkExecutor")
+            .field("isolation", &self.isolation())
+            .finish_non_exhaustive()
+    }
+}
+
+impl BulkExecutor {
+    /// Create an executor running jobs on the caller's runtime
+    #[must_use]
+    pub fn shared() -> Self {
+        Self {
+            kind: ExecutorKind::Shared,
+        }
+    }
+
+    /// Create an executor bounding the number of jobs in flight
+    ///
+    /// The budget is shared by every clone of the executor, so it caps the
+    /// combined concurrency of all bulk operations using it.
+    ///
+    /// # Arguments
+    ///
+    /// * `permits` - Maximum number of jobs in flight, at least one
+    #[must_use]
+    pub fn with_budget(permits: usize) -> Self {
+        Self {
+            kind: ExecutorKind::Budget(Arc::new(Semaphore::new(permits.max(1)))),
+        }
+    }
+
+    /// Create an executor running jobs on a dedicated multi-thread runtime
+    ///
+    /// # Arguments
+    ///
+    /// * `worker_threads` - Number of worker threads of the runtime, at least one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime cannot be started
+    pub fn dedicated(worker_threads: usize) -> std::io::Result<Self> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(worker_threads.max(1))
+            .thread_name(defaults::isolation::THREAD_NAME)
+            .enable_all()
+            .build()?;
+
+        Ok(Self {
+            kind: ExecutorKind::Dedicated(Arc::new(DedicatedRuntime(Some(runtime)))),
+        })
+    }
+
+    /// Create an executor from the application configuration
+    ///
+    /// `Budget` uses `parallel_validations` as the number of permits, and
+    /// `Dedicated` uses `judgement_worker_threads` worker threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The application configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a dedicated runtime cannot be started
+    pub fn from_config(config: &AppConfig) -> ManagerResult<Self> {
+        match config.judgement_isolation {
+            ConcurrencyIsolation::Shared => Ok(Self::shared()),
+            ConcurrencyIsolation::Budget => Ok(Self::with_budget(config.parallel_validations)),
+            ConcurrencyIsolation::Dedicated => Self::dedicated(config.judgement_worker_threads)
+                .map_err(|e| ManagerError::ExecutorError(e.to_string())),
+        }
+    }
+
+    /// Get the isolation mode of the executor
+    #[must_use]
+    pub fn isolation(&self) -> ConcurrencyIsolation {
+        match self.kind {
+            ExecutorKind::Shared => ConcurrencyIsolation::Shared,
+            ExecutorKind::Budget(_) => ConcurrencyIsolation::Budget,
+            ExecutorKind::Dedicated(_) => ConcurrencyIsolation::Dedicated,
+        }
+    }
+
+    /// Run a job according to the executor's isolation mode
+    ///
+    /// # Arguments
+    ///
+    /// * `job` - The future to run
+    ///
+    /// # Returns
+    ///
+    /// The output of the job
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the dedicated runtime shut down before the job finished
+    ///
+    /// # Panics
+    ///
+    /// Resumes the panic of a job that panicked on the dedicated runtime.
+    pub async fn run<F, R>(&self, job: F) -> ManagerResult<R>
+    where
+        F: Future<Output = R> + Send + 'static,
+        R: Send + 'static,
+    {
+        match &self.kind {
+            ExecutorKind::Shared => Ok(job.await),
+            ExecutorKind::Budget(semaphore) => {
+                // The semaphore is never closed, so acquiring only waits
+                let _permit = semaphore.acquire().await.ok();
+                Ok(job.await)
+            }
+            ExecutorKind::Dedicated(runtime) => {
+                let Some(runtime) = runtime.0.as_ref() else {
+                    return Err(ManagerError::ExecutorError(
+                        "Dedicated runtime has shut down".to_string(),
+                    ));
+                };
+                match runtime.spawn(job).await {
+                    Ok(output) => Ok(output),
+                    Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                    Err(e) => Err(ManagerError::ExecutorError(format!(
+                        "Job did not finish on the dedicated runtime: {e}"
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+/// A boxed unit of work run by a pool worker
+type BoxedJob = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The work function of a single pool worker
+type WorkerFn<T> = Box<dyn FnMut(T) -> BoxedJob + Send>;
+
+/// Counters of a single pool worker
+struct WorkerCounters {
+    started: Instant,
+    completed: u64,
+    busy: Duration,
+}
+
+/// State shared between a worker pool and its workers
+struct PoolState {
+    /// Workers currently running
+    workers: AtomicUsize,
+    /// Number of workers the pool is sized to
+    target: AtomicUsize,
+    /// Items being processed
+    in_flight: AtomicUsize,
+    /// Items processed since the pool was created
+    completed: AtomicU64,
+    /// Total processing time of all items, in microseconds
+    busy_micros: AtomicU64,
+    /// Counters of the running workers, by worker id
+    per_worker: std::sync::Mutex<BTreeMap<usize, WorkerCounters>>,
+    /// Wakes idle workers when the pool is resized
+    resized: Notify,
+}
+
+impl PoolState {
+    /// Retires the calling worker if the pool has more workers than its target
+    fn try_retire(&self) -> bool {
+        let mut workers = self.workers.load(Ordering::Acquire);
+        while workers > self.target.load(Ordering::Acquire) {
+            match self.workers.compare_exchange(
+                workers,
+                workers - 1,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return true,
+                Err(current) => workers = current,
+            }
+        }
+        false
+    }
+
+    /// Collects the statistics of the pool
+    fn snapshot(&self, queue_depth: usize, queue_capacity: usize) -> WorkerPoolStats {
+        let completed = self.completed.load(Ordering::Acquire);
+        let busy_micros = self.busy_micros.load(Ordering::Acquire);
+        let per_worker = self
+            .per_worker()
+            .iter()
+            .map(|(&id, counters)| {
+                let elapsed = counters.started.elapsed().as_secs_f64();
+                #[allow(clippy::cast_precision_loss)]
+                let throughput = if elapsed > 0.0 {
+                    counters.completed as f64 / elapsed
+                } else {
+                    0.0
+                };
+                WorkerStats {
+                    id,
+                    completed: counters.completed,
+                    throughput,
+                    average_latency: average(counters.busy, counters.completed),
+                }
+            })
+            .collect();
+
+        WorkerPoolStats {
+            queue_depth,
+            queue_capacity,
+            in_flight: self.in_flight.load(Ordering::Acquire),
+            workers: self.workers.load(Ordering::Acquire),
+            target_workers: self.target.load(Ordering::Acquire),
+            completed,
+            average_latency: average(Duration::from_micros(busy_micros), completed),
+            per_worker,
+        }
+    }
+
+    /// Locks the per-worker counters, recovering them if a worker panicked
+    fn per_worker(&self) -> std::sync::MutexGuard<'_, BTreeMap<usize, WorkerCounters>> {
+        self.per_worker
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Statistics of a single worker of a `WorkerPool`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerStats {
+    /// Identifier of the worker within its pool
+    pub id: usize,
+    /// Items processed by the worker
+    pub completed: u64,
+    /// Items processed per second since the worker started
+    pub throughput: f64,
+    /// Average processing time of the worker's items
+    pub average_latency: Duration,
+}
+
+/// Point-in-time statistics of a `WorkerPool`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkerPoolStats {
+    /// Items waiting in the queue
+    pub queue_depth: usize,
+    /// Maximum number of items the queue holds
+    pub queue_capacity: usize,
+    /// Items being processed
+    pub in_flight: usize,
+    /// Workers currently running
+    pub workers: usize,
+    /// Number of workers the pool is sized to
+    pub target_workers: usize,
+    /// Items processed since the pool was created
+    pub completed: u64,
+    /// Average processing time of all items
+    pub average_latency: Duration,
+    /// Statistics of each running worker, by id
+    pub per_worker: Vec<WorkerStats>,
+}
+
+/// A resizable set of workers fed through a bounded channel
+///
+/// Items sent through `sender` are picked up by whichever worker is idle.
+/// The pool reports queue depth, in-flight items, throughput, and latency,
+/// and can be resized while it runs: growing spawns workers straight away,
+/// shrinking retires workers as they become idle.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::threading::create_worker_pool;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let mut pool = create_worker_pool(2, |n: u64| async move {
+///     tokio::task::yield_now().await;
+///     let _ = n * 2;
+/// });
+/// assert_eq!(pool.concurrency(), 2);
+///
+/// let sender = pool.sender();
+/// for n in 0..10 {
+///     sender.send(n).await.unwrap();
+/// }
+/// drop(sender);
+///
+/// // Grow the pool without restarting it
+/// pool.resize(4);
+/// assert_eq!(pool.stats().target_workers, 4);
+///
+/// let stats = pool.join().await;
+/// assert_eq!(stats.completed, 10);
+/// assert_eq!(stats.queue_depth, 0);
+/// assert_eq!(stats.in_flight, 0);
+///
+/// // Shrinking retires idle workers
+/// let mut pool = create_worker_pool(4, |_: u64| async {});
+/// pool.resize(1);
+/// while pool.stats().per_worker.len() > 1 {
+///     tokio::task::yield_now().await;
+/// }
+/// assert_eq!(pool.stats().workers, 1);
+/// # }
+/// ```
+pub struct WorkerPool<T> {
+    sender: mpsc::Sender<T>,
+    receiver: Arc<tokio::sync::Mutex<mpsc::Receiver<T>>>,
+    state: Arc<PoolState>,
+    factory: Box<dyn Fn() -> WorkerFn<T> + Send>,
+    tasks: TaskManager,
+    next_worker_id: usize,
+}
+
+impl<T: Send + 'static> WorkerPool<T> {
+    /// Returns a sender for submitting items to the pool
+    #[must_use]
+    pub fn sender(&self) -> mpsc::Sender<T> {
+        self.sender.clone()
+    }
+
+    /// Returns the number of workers the pool is sized to
+    #[must_use]
+    pub fn concurrency(&self) -> usize {
+        self.state.target.load(Ordering::Acquire)
+    }
+
+    /// Changes the number of workers while the pool runs
+    ///
+    /// Growing spawns the missing workers straight away. Shrinking lets
+    /// surplus workers finish their current item and exit. The queue keeps
+    /// the capacity it was created with.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - The new number of workers, at least one
+    pub fn resize(&mut self, concurrency: usize) {
+        let concurrency = concurrency.max(1);
+        self.state.target.store(concurrency, Ordering::Release);
+
+        let running = self.state.workers.load(Ordering::Acquire);
+        for _ in running..concurrency {
+            self.spawn_worker();
+        }
+        self.state.resized.notify_waiters();
+    }
+
+    /// Returns the current statistics of the pool
+    #[must_use]
+    pub fn stats(&self) -> WorkerPoolStats {
+        self.state.snapshot(
+            self.sender.max_capacity() - self.sender.capacity(),
+            self.sender.max_capacity(),
+        )
+    }
+
+    /// Closes the pool and waits for the queued items to be processed
+    ///
+    /// Workers keep running until every sender obtained from `sender` has
+    /// been dropped as well.
+    ///
+    /// # Returns
+    ///
+    /// The final statistics of the pool
+    pub async fn join(self) -> WorkerPoolStats {
+        let WorkerPool {
+            sender,
+            state,
+            mut tasks,
+            ..
+        } = self;
+        let queue_capacity = sender.max_capacity();
+        drop(sender);
+
+        tasks.join_all().await;
+        state.snapshot(0, queue_capacity)
+    }
+
+    /// Spawns one more worker
+    fn spawn_worker(&mut self) {
+        let id = self.next_worker_id;
+        self.next_worker_id += 1;
+
+        let mut job = (self.factory)();
+        let receiver = Arc::clone(&self.receiver);
+        let state = Arc::clone(&self.state);
+
+        state.workers.fetch_add(1, Ordering::AcqRel);
+        state.per_worker().insert(
+            id,
+            WorkerCounters {
+                started: Instant::now(),
+                completed: 0,
+                busy: Duration::ZERO,
+            },
+        );
+
+        self.tasks.spawn(async move {
+            let mut retired = false;
             loop {
                 let message = {
-                    let mut rx_lock = rx.lock().await;
-                    rx_lock.recv().await
+                    let mut receiver = receiver.lock().await;
+                    // Register for resizes before checking, so one landing
+                    // in between still wakes the worker
+                    let resized = state.resized.notified();
+                    tokio::pin!(resized);
+                    resized.as_mut().enable();
+                    if state.try_retire() {
+                        retired = true;
+                        break;
+                    }
+                    tokio::select! {
+                        message = receiver.recv() => Some(message),
+                        () = resized => None,
+                    }
                 };
 
-                match message {
-                    Some(item) => {
-                        worker_fn(item).await;
-                    }
-                    None => break,
+                // Woken by a resize, check again whether to retire
+                let Some(message) = message else { continue };
+                let Some(item) = message else { break };
+
+                state.in_flight.fetch_add(1, Ordering::AcqRel);
+                let started = Instant::now();
+                job(item).await;
+                let elapsed = started.elapsed();
+                state.in_flight.fetch_sub(1, Ordering::AcqRel);
+
+                state.completed.fetch_add(1, Ordering::AcqRel);
+                state.busy_micros.fetch_add(
+                    u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+                    Ordering::AcqRel,
+                );
+                if let Some(counters) = state.per_worker().get_mut(&id) {
+                    counters.completed += 1;
+                    counters.busy += elapsed;
                 }
             }
+
+            if !retired {
+                state.workers.fetch_sub(1, Ordering::AcqRel);
+            }
+            state.per_worker().remove(&id);
         });
     }
+}
 
-    (tx, task_manager)
+/// Average of a total duration over a number of items
+fn average(total: Duration, count: u64) -> Duration {
+    if count == 0 {
+        return Duration::ZERO;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    Duration::from_secs_f64(total.as_secs_f64() / count as f64)
 }
 
-/// Execute multiple futures concurrently with a limit on parallelism
+/// Creates a set of worker tasks with a bounded channel for work distribution
 ///
-/// # Panics
+/// # Arguments
 ///
-/// This function will panic if the semaphore is closed, which can happen
-/// if the semaphore is dropped while permits are still active.
+/// * `concurrency` - Initial number of workers and capacity of the queue
+/// * `worker_fn` - Processes a single item
+///
+/// # Returns
+///
+/// A `WorkerPool` reporting statistics and supporting runtime re-sizing
+pub fn create_worker_pool<T, F, Fut>(concurrency: usize, worker_fn: F) -> WorkerPool<T>
+where
+    T: Send + 'static,
+    F: FnMut(T) -> Fut + Send + Clone + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let concurrency = concurrency.max(1);
+    let (sender, receiver) = mpsc::channel::<T>(concurrency);
+
+    let mut pool = WorkerPool {
+        sender,
+        receiver: Arc::new(tokio::sync::Mutex::new(receiver)),
+        state: Arc::new(PoolState {
+            workers: AtomicUsize::new(0),
+            target: AtomicUsize::new(concurrency),
+            in_flight: AtomicUsize::new(0),
+            completed: AtomicU64::new(0),
+            busy_micros: AtomicU64::new(0),
+            per_worker: std::sync::Mutex::new(BTreeMap::new()),
+            resized: Notify::new(),
+        }),
+        factory: Box::new(move || {
+            let mut worker_fn = worker_fn.clone();
+            Box::new(move |item| Box::pin(worker_fn(item)) as BoxedJob)
+        }),
+        tasks: TaskManager::new(),
+        next_worker_id: 0,
+    };
+
+    for _ in 0..concurrency {
+        pool.spawn_worker();
+    }
+    pool
+}
+
+/// Execute multiple futures concurrently with a limit on parallelism
 pub async fn execute_with_concurrency_limit<T, F, Fut>(
     items: Vec<T>,
     concurrency: usize,
@@ -137,7 +972,10 @@ where
     let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(concurrency));
 
     for item in items {
-        let permit = semaphore.clone().acquire_owned().await.unwrap();
+        // The semaphore is never closed, so acquiring only waits
+        let Ok(permit) = semaphore.clone().acquire_owned().await else {
+            break;
+        };
         let future = job_fn(item);
 
         futures.push(Box::pin(async move {
@@ -149,18 +987,55 @@ where
     futures
 }
 
+/// The results of a batch run by `run_concurrent_batch`
+///
+/// Holds the result of every item in input order. An item whose job panicked
+/// has no result, and counts as a failed item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResults<R> {
+    /// Result and success of each item, `None` for items whose job panicked
+    pub results: Vec<Option<(R, bool)>>,
+
+    /// Number of items whose job panicked
+    pub panicked: usize,
+}
+
+impl<R> BatchResults<R> {
+    /// Count the items whose job completed and reported success
+    #[must_use]
+    pub fn succeeded(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|result| matches!(result, Some((_, true))))
+            .count()
+    }
+}
+
 /// Run a batch of operations concurrently with limited parallelism.
 ///
 /// This function takes a collection of items, a concurrency limit, and a job function.
 /// It processes the items concurrently but limited to the specified level of parallelism,
 /// returning the results when all operations are complete.
 ///
+/// # Ordering
+///
+/// Results are returned in input order, whatever order the jobs finish in, so
+/// the result at index `i` always belongs to the item at index `i`. Callers may
+/// write results back by position.
+///
+/// # Panics
+///
+/// Each job runs as a task of its own, so a job that panics fails its item
+/// only. The rest of the batch carries on, and the panicked items are counted
+/// in the returned `BatchResults`. The release profile sets `panic = "abort"`,
+/// which ends the process on any panic, so there the isolation only applies to
+/// builds that unwind.
+///
 /// # Type Parameters
 ///
 /// * `T` - The input item type
 /// * `R` - The result type
 /// * `F` - The function type that processes each item
-/// * `Fut` - The future type returned by the function
 ///
 /// # Arguments
 ///
@@ -170,41 +1045,70 @@ where
 ///
 /// # Returns
 ///
-/// A vector containing the results of all operations in the same order as the input items.
+/// The results of all operations in the same order as the input items.
 ///
 /// # Examples
 ///
 /// ```
-/// async fn process_item(item: u32) -> u32 {
-///     // Some async processing
-///     item * 2
-/// }
+/// use futures::FutureExt;
+/// use gooty_proxy::orchestration::threading::run_concurrent_batch;
+/// use std::{future::Future, pin::Pin};
 ///
-/// let items = vec![1, 2, 3, 4, 5];
-/// let concurrency = 2;
-/// let results = run_concurrent_batch(items, concurrency, |item| async move {
-///     process_item(item).await
-/// }).await;
+/// # #[tokio::main]
+/// # async fn main() {
+/// // Earlier items take longer, so they finish last
+/// let job = |item: u32| -> Pin<Box<dyn Future<Output = (u32, bool)> + Send>> {
+///     async move {
+///         for _ in item..10 {
+///             tokio::task::yield_now().await;
+///         }
+///         (item * 2, true)
+///     }
+///     .boxed()
+/// };
+///
+/// let batch = run_concurrent_batch(vec![1, 2, 3, 4, 5], 5, &job).await;
+/// let values: Vec<u32> = batch.results.into_iter().flatten().map(|(value, _)| value).collect();
+/// assert_eq!(values, vec![2, 4, 6, 8, 10]);
+///
+/// // A panicking job fails its own item only
+/// let fragile = |item: u32| -> Pin<Box<dyn Future<Output = (u32, bool)> + Send>> {
+///     async move {
+///         assert_ne!(item, 2, "cannot handle two");
+///         (item, true)
+///     }
+///     .boxed()
+/// };
+///
+/// let batch = run_concurrent_batch(vec![1, 2, 3], 2, &fragile).await;
+/// assert_eq!(batch.results, vec![Some((1, true)), None, Some((3, true))]);
+/// assert_eq!(batch.panicked, 1);
+/// assert_eq!(batch.succeeded(), 2);
+/// # }
 /// ```
 pub async fn run_concurrent_batch<T, R, F>(
     items: Vec<T>,
     concurrency: usize,
     job_fn: &F,
-) -> Vec<(R, bool)>
+) -> BatchResults<R>
 where
     T: Send + 'static,
     R: Send + 'static,
     F: Fn(T) -> Pin<Box<dyn Future<Output = (R, bool)> + Send>> + Send + Sync + Clone + 'static,
 {
-    // Create a buffered stream with the specified concurrency
-    stream::iter(items)
+    // Create a buffered stream with the specified concurrency, yielding results in input order.
+    // Every job runs as a task of its own, so a panic ends that task only
+    let results = stream::iter(items)
         .map(|item| {
             let job = job_fn.clone();
-            async move { job(item).await }
+            join_isolated(job(item))
         })
-        .buffer_unordered(concurrency.max(1)) // Ensure at least 1 concurrency
+        .buffered(concurrency.max(1)) // Ensure at least 1 concurrency
         .collect::<Vec<_>>()
-        .await
+        .await;
+
+    let panicked = results.iter().filter(|result| result.is_none()).count();
+    BatchResults { results, panicked }
 }
 
 /// Process items concurrently with a shared state
@@ -212,6 +1116,11 @@ where
 /// Similar to `run_concurrent_batch`, but allows for a shared state that
 /// can be accessed and modified by each job.
 ///
+/// # Panics
+///
+/// As with `run_concurrent_batch`, a job that panics fails its own item only,
+/// unless the build aborts on panic as the release profile does.
+///
 /// # Type Parameters
 ///
 /// * `T` - The type of items to process
@@ -228,29 +1137,76 @@ where
 ///
 /// # Returns
 ///
-/// A vector containing the results from processing each item
+/// The results from processing each item, in the same order as the input items
+///
+/// # Examples
+///
+/// ```
+/// use futures::FutureExt;
+/// use gooty_proxy::orchestration::threading::run_concurrent_batch_with_state;
+/// use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let calls = Arc::new(AtomicUsize::new(0));
+/// let batch = run_concurrent_batch_with_state(
+///     vec![3_u32, 2, 1],
+///     Arc::clone(&calls),
+///     3,
+///     |item, calls: Arc<AtomicUsize>| {
+///         async move {
+///             for _ in 0..item {
+///                 tokio::task::yield_now().await;
+///             }
+///             calls.fetch_add(1, Ordering::SeqCst);
+///             (item, true)
+///         }
+///         .boxed()
+///     },
+/// )
+/// .await;
+///
+/// assert_eq!(calls.load(Ordering::SeqCst), 3);
+/// assert_eq!(batch.results, vec![Some((3, true)), Some((2, true)), Some((1, true))]);
+///
+/// // A panicking job fails its own item only
+/// let batch = run_concurrent_batch_with_state(vec![1_u32, 2, 3], calls, 2, |item, _| {
+///     async move {
+///         assert_ne!(item, 2, "cannot handle two");
+///         (item, true)
+///     }
+///     .boxed()
+/// })
+/// .await;
+/// assert_eq!(batch.results, vec![Some((1, true)), None, Some((3, true))]);
+/// assert_eq!(batch.panicked, 1);
+/// # }
+/// ```
 pub async fn run_concurrent_batch_with_state<T, R, S, F>(
     items: Vec<T>,
     state: S,
     concurrency: usize,
     job_fn: F,
-) -> Vec<(R, bool)>
+) -> BatchResults<R>
 where
     T: Send + 'static,
     R: Send + 'static,
     S: Clone + Send + Sync + 'static,
     F: Fn(T, S) -> Pin<Box<dyn Future<Output = (R, bool)> + Send>> + Send + Sync + Clone + 'static,
 {
-    // Create a buffered stream with the specified concurrency
-    stream::iter(items)
+    // Create a buffered stream with the specified concurrency, each job a task of its own
+    let results = stream::iter(items)
         .map(move |item| {
             let job = job_fn.clone();
             let state = state.clone();
-            async move { job(item, state).await }
+            join_isolated(job(item, state))
         })
-        .buffer_unordered(concurrency.max(1)) // Ensure at least 1 concurrency
+        .buffered(concurrency.max(1)) // Ensure at least 1 concurrency, keeping input order
         .collect::<Vec<_>>()
-        .await
+        .await;
+
+    let panicked = results.iter().filter(|result| result.is_none()).count();
+    BatchResults { results, panicked }
 }
 
 /// Runs a batch of operations with progress reporting.
@@ -259,36 +1215,73 @@ where
 /// This is useful for long-running operations where you want to update a progress bar
 /// or log periodic status updates.
 ///
+/// # Panics
+///
+/// As with `run_concurrent_batch`, a job that panics fails its own item only,
+/// unless the build aborts on panic as the release profile does. Its progress
+/// is reported with no result.
+///
 /// # Type Parameters
 ///
 /// * `T` - The input item type
 /// * `R` - The result type
-/// * `F` - The function type that processes each item
 /// * `Fut` - The future type returned by the function
-/// * `P` - The progress callback function type
 ///
 /// # Arguments
 ///
 /// * `items` - Vector of items to process
 /// * `concurrency` - Maximum number of concurrent operations
 /// * `job_fn` - Function that processes each item and returns a future
-/// * `progress_fn` - Callback function called after each item is processed
+/// * `progress_fn` - Callback function called with the index and result of
+///   each item once it is processed, or no result if its job panicked
 ///
 /// # Returns
 ///
-/// A vector containing the results of all operations in the same order as the input items.
-pub async fn run_concurrent_batch_with_progress<T, R, F, Fut, P>(
+/// The results of all operations in the same order as the input items.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::orchestration::threading::run_concurrent_batch_with_progress;
+/// use std::sync::{Arc, Mutex};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let reported = Arc::new(Mutex::new(Vec::new()));
+/// let seen = Arc::clone(&reported);
+///
+/// // Earlier items take longer, so they finish last
+/// let batch = run_concurrent_batch_with_progress(
+///     vec![3_u32, 2, 1],
+///     3,
+///     |item| async move {
+///         for _ in 0..item {
+///             tokio::task::yield_now().await;
+///         }
+///         assert_ne!(item, 2, "cannot handle two");
+///         (item * 10, true)
+///     },
+///     move |index, result: Option<&(u32, bool)>| {
+///         seen.lock().unwrap().push((index, result.map(|(value, _)| *value)));
+///     },
+/// )
+/// .await;
+///
+/// assert_eq!(batch.results, vec![Some((30, true)), None, Some((10, true))]);
+/// assert_eq!(batch.panicked, 1);
+/// assert_eq!(*reported.lock().unwrap(), vec![(0, Some(30)), (1, None), (2, Some(10))]);
+/// # }
+/// ```
+pub async fn run_concurrent_batch_with_progress<T, R, Fut>(
     items: Vec<T>,
     concurrency: usize,
     job_fn: impl Fn(T) -> Fut + Send + Sync + Clone + 'static,
-    progress_fn: impl Fn(usize, &R) + Send + Sync + Clone + 'static,
-) -> Vec<R>
+    progress_fn: impl Fn(usize, Option<&(R, bool)>) + Send + Sync + Clone + 'static,
+) -> BatchResults<R>
 where
     T: Send + 'static,
     R: Send + 'static,
-    F: FnOnce(T) -> Fut + Send + Sync + Clone + 'static,
-    Fut: Future<Output = R> + Send,
-    P: Fn(usize, &R) + Send + Sync + Clone + 'static,
+    Fut: Future<Output = (R, bool)> + Send + 'static,
 {
     let mut results = Vec::with_capacity(items.len());
 
@@ -296,30 +1289,50 @@ where
     let mut iter = items.into_iter().enumerate();
 
     loop {
-        let batch: Vec<(usize, T)> = iter.by_ref().take(concurrency).collect();
+        let batch: Vec<(usize, T)> = iter.by_ref().take(concurrency.max(1)).collect();
         if batch.is_empty() {
             break;
         }
 
-        // Process this batch concurrently
+        // Process this batch concurrently, keeping input order
         let batch_results = stream::iter(batch)
             .map(|(idx, item)| {
                 let job = job_fn.clone();
-                async move { (idx, job(item).await) }
+                async move { (idx, join_isolated(job(item)).await) }
             })
-            .buffer_unordered(concurrency)
-            .collect::<Vec<(usize, R)>>()
+            .buffered(concurrency.max(1))
+            .collect::<Vec<_>>()
             .await;
 
         // Update progress for each result
         for (idx, result) in &batch_results {
             let progress = progress_fn.clone();
-            progress(*idx, result);
+            progress(*idx, result.as_ref());
         }
 
         // Store results
         results.extend(batch_results.into_iter().map(|(_, r)| r));
     }
 
-    results
+    let panicked = results.iter().filter(|result| result.is_none()).count();
+    BatchResults { results, panicked }
+}
+
+/// Run a batch job as a task of its own, so a panic fails its item only
+///
+/// # Returns
+///
+/// The result of the job, or None if it panicked
+async fn join_isolated<R, Fut>(job: Fut) -> Option<(R, bool)>
+where
+    R: Send + 'static,
+    Fut: Future<Output = (R, bool)> + Send + 'static,
+{
+    match tokio::spawn(job).await {
+        Ok(result) => Some(result),
+        Err(e) => {
+            log::warn!("Batch item failed: {e}");
+            None
+        }
+    }
 }
@@ -31,6 +31,8 @@ use serde::{self};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::str::FromStr;
+#[cfg(feature = "manager")]
+use std::sync::{Arc, LazyLock, PoisonError, RwLock};
 use url::Url;
 
 /// A wrapper type for `fancy_regex::Regex` that implements Serialize, Deserialize, `PartialEq`, Eq
@@ -246,6 +248,129 @@ pub fn sanitize_url_for_filename(url: &str) -> String {
     }
 }
 
+/// The compiled-in public suffix list, parsed on first use
+///
+/// A copy of <https://publicsuffix.org/list/public_suffix_list.dat> is
+/// compiled in, so grouping works offline. If the copy can't be parsed, the
+/// error is logged and hosts are grouped whole.
+///
+/// The copy is refreshed by downloading the list over
+/// `data/public_suffix_list.dat` before a release. Between releases, the
+/// list can be replaced at runtime with [`set_public_suffix_list`].
+#[cfg(feature = "manager")]
+static EMBEDDED_SUFFIXES: LazyLock<Option<publicsuffix::List>> =
+    LazyLock::new(
+        || match include_str!("../data/public_suffix_list.dat").parse() {
+            Ok(list) => Some(list),
+            Err(e) => {
+                log::error!("Failed to parse the public suffix list, grouping hosts whole: {e}");
+                None
+            }
+        },
+    );
+
+/// The public suffix list replacing the compiled-in one, if any
+#[cfg(feature = "manager")]
+static LOADED_SUFFIXES: RwLock<Option<Arc<publicsuffix::List>>> = RwLock::new(None);
+
+/// Replaces the compiled-in public suffix list
+///
+/// Hosts are grouped by the new list from then on. The manager calls this
+/// with the `public_suffix_list.dat` of the data directory, if there is one,
+/// when importing from a filestore.
+///
+/// # Arguments
+///
+/// * `list` - The list, in the format of `public_suffix_list.dat`
+///
+/// # Errors
+///
+/// Returns `UtilError::InvalidSuffixList` if the list can't be parsed, in
+/// which case the list in use is kept.
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::utils::{registered_domain, set_public_suffix_list};
+///
+/// let list = "\
+/// // ===BEGIN ICANN DOMAINS===
+/// com
+/// // ===END ICANN DOMAINS===
+/// // ===BEGIN PRIVATE DOMAINS===
+/// lists.example.com
+/// // ===END PRIVATE DOMAINS===
+/// ";
+/// set_public_suffix_list(list).unwrap();
+/// assert!(set_public_suffix_list("not a list").is_err());
+/// assert_eq!(
+///     registered_domain("https://alice.lists.example.com/").as_deref(),
+///     Some("alice.lists.example.com")
+/// );
+/// ```
+#[cfg(feature = "manager")]
+pub fn set_public_suffix_list(list: &str) -> UtilResult<()> {
+    let list: publicsuffix::List = list
+        .parse()
+        .map_err(|e: publicsuffix::Error| UtilError::InvalidSuffixList(e.to_string()))?;
+    *LOADED_SUFFIXES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(list));
+    Ok(())
+}
+
+/// Groups a URL by the domain its owner registered
+///
+/// The registered domain is the public suffix of the host, such as `com`,
+/// `co.uk`, or `github.io`, and the label before it, as found in the public
+/// suffix list. Hosts that are a public suffix themselves or under no known
+/// suffix are returned whole, and so are addresses.
+///
+/// # Arguments
+///
+/// * `url` - The URL to group
+///
+/// # Returns
+///
+/// The registered domain, or None if the URL has no host
+///
+/// # Examples
+///
+/// ```
+/// use gooty_proxy::utils::registered_domain;
+///
+/// assert_eq!(registered_domain("https://www.example.com/list.txt").as_deref(), Some("example.com"));
+/// assert_eq!(registered_domain("https://lists.example.co.uk/").as_deref(), Some("example.co.uk"));
+/// assert_eq!(registered_domain("https://a.b.example.com.au/").as_deref(), Some("example.com.au"));
+/// // Sites of different owners on a shared host are told apart
+/// assert_eq!(registered_domain("https://alice.github.io/proxies").as_deref(), Some("alice.github.io"));
+/// assert_eq!(registered_domain("http://10.0.0.1:8080/").as_deref(), Some("10.0.0.1"));
+/// assert_eq!(registered_domain("not a url"), None);
+/// ```
+#[cfg(feature = "manager")]
+#[must_use]
+pub fn registered_domain(url: &str) -> Option<String> {
+    use publicsuffix::Psl;
+
+    let parsed = Url::parse(url).ok()?;
+    let host = match parsed.host()? {
+        url::Host::Domain(domain) => domain.trim_end_matches('.').to_ascii_lowercase(),
+        url::Host::Ipv4(ip) => return Some(ip.to_string()),
+        url::Host::Ipv6(ip) => return Some(ip.to_string()),
+    };
+
+    let loaded = LOADED_SUFFIXES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let registered = loaded
+        .as_deref()
+        .or(EMBEDDED_SUFFIXES.as_ref())
+        .and_then(|list| list.domain(host.as_bytes()))
+        .map(|domain| String::from_utf8_lossy(domain.as_bytes()).into_owned());
+    Some(registered.unwrap_or(host))
+}
+
 /// Checks if a string is a valid IPv4 or IPv6 address
 ///
 /// # Arguments
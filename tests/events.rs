@@ -0,0 +1,60 @@
+//! Progress published by long-running commands
+//!
+//! `serve --events` streams the probes of the port watcher, so the watcher
+//! must publish one check per probed proxy, up or down.
+
+use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+use gooty_proxy::definitions::proxy::Proxy;
+use gooty_proxy::definitions::types::Port;
+use gooty_proxy::orchestration::events::{EventBus, GatherEvent};
+use gooty_proxy::orchestration::listener::{PortWatcher, UpstreamPool};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+fn proxy(port: u16) -> Proxy {
+    let mut proxy = Proxy::new(
+        ProxyType::Socks5,
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+        Port::new(port).unwrap(),
+        AnonymityLevel::Elite,
+    );
+    proxy.record_check(100);
+    proxy
+}
+
+fn working(event: &GatherEvent) -> (String, bool) {
+    match event {
+        GatherEvent::ProxyChecked { proxy, working, .. } => (proxy.clone(), *working),
+        other => panic!("unexpected event {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn port_watcher_publishes_every_probe() {
+    let open = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed_port = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let up = proxy(open.local_addr().unwrap().port());
+    let down = proxy(closed_port);
+    let pool = UpstreamPool::from_proxies([up.clone(), down.clone()]);
+
+    let bus = EventBus::default();
+    let mut events = bus.subscribe();
+    let watcher = PortWatcher::new(Arc::new(Mutex::new(pool))).with_events(bus);
+    assert_eq!(watcher.probe_once().await, 1);
+
+    let mut probes = vec![
+        working(&events.try_recv().unwrap()),
+        working(&events.try_recv().unwrap()),
+    ];
+    probes.sort();
+    let mut expected = vec![(up.redacted_id(), true), (down.redacted_id(), false)];
+    expected.sort();
+    assert_eq!(probes, expected);
+    assert!(events.try_recv().is_err());
+}
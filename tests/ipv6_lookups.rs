@@ -0,0 +1,198 @@
+//! IPv6 handling of the sleuth and the ownership lookup
+//!
+//! Addresses that no provider has records for, IPv6 or IPv4-mapped, must be
+//! refused before any request. IPv4-mapped addresses must be looked up by the
+//! IPv4 address they carry, and reported under the address asked about.
+//! Documents come from a prefilled lookup cache, so no test needs a network.
+
+use gooty_proxy::definitions::enums::CloudProvider;
+use gooty_proxy::definitions::errors::{OwnershipError, SleuthError};
+use gooty_proxy::inspection::cidr::{CidrSet, helpers};
+use gooty_proxy::inspection::lookup_cache::LookupCache;
+use gooty_proxy::inspection::{OwnershipLookup, Sleuth};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Addresses outside the public unicast space, by the range they fall in
+const NOT_ROUTABLE: [&str; 10] = [
+    "::",                // unspecified
+    "::1",               // loopback
+    "fe80::1",           // link-local
+    "fd12:3456::1",      // unique local
+    "2001:db8::8",       // documentation
+    "ff02::1",           // multicast
+    "100::1",            // discard-only
+    "::ffff:10.0.0.1",   // private IPv4, mapped
+    "::ffff:127.0.0.1",  // loopback IPv4, mapped
+    "::ffff:100.64.0.1", // shared address space, mapped
+];
+
+const CLOUDFLARE_V4: &str = r#"{
+    "ip": "1.1.1.1",
+    "hostname": "one.one.one.one",
+    "org": "AS13335 Cloudflare, Inc.",
+    "cidr": "1.1.1.0/24",
+    "country": "AU"
+}"#;
+
+const CLOUDFLARE_V6: &str = r#"{
+    "ip": "2606:4700:4700::1111",
+    "hostname": "one.one.one.one",
+    "org": "AS13335 Cloudflare, Inc.",
+    "cidr": "2606:4700::/32",
+    "country": "US"
+}"#;
+
+fn ip(address: &str) -> IpAddr {
+    address.parse().unwrap()
+}
+
+/// Builds a sleuth answering from cached documents
+fn sleuth_with(documents: &[(&str, &str)]) -> Sleuth {
+    let cache = Arc::new(LookupCache::new(Duration::from_secs(600)));
+    for (address, body) in documents {
+        cache.insert(&ip(address), body);
+    }
+    let sleuth = Sleuth::new().with_lookup_cache(cache);
+    sleuth.cloud_ranges().set_ranges(
+        CloudProvider::Cloudflare,
+        vec!["1.1.1.0/24".to_string(), "2606:4700::/32".to_string()],
+    );
+    sleuth
+}
+
+/// Checks that the sleuth made no request to any provider
+fn assert_no_requests(sleuth: &Sleuth) {
+    let usage = sleuth.usage_tracker().usage();
+    assert!(
+        usage.iter().all(|u| u.hour_count == 0),
+        "requests were made: {usage:?}"
+    );
+}
+
+#[tokio::test]
+async fn sleuth_refuses_unroutable_addresses_without_a_request() {
+    let sleuth = sleuth_with(&[]);
+    for address in NOT_ROUTABLE {
+        let target = ip(address);
+        assert!(
+            matches!(
+                sleuth.lookup_ip_metadata(&target).await,
+                Err(SleuthError::NotFound(_))
+            ),
+            "{address} was looked up"
+        );
+        assert!(matches!(
+            sleuth.lookup_hostname(&target).await,
+            Err(SleuthError::NotFound(_))
+        ));
+        assert!(matches!(
+            sleuth.lookup_cidr(&target).await,
+            Err(SleuthError::NotFound(_))
+        ));
+        assert!(matches!(
+            sleuth.lookup_location(&target).await,
+            Err(SleuthError::NotFound(_))
+        ));
+
+        // Ownership lookups are refused alike, and never worth retrying
+        let asn = sleuth.lookup_asn(&target).await.unwrap_err();
+        assert!(matches!(
+            asn,
+            SleuthError::OwnershipError(OwnershipError::NotFound(_))
+        ));
+        assert!(asn.is_permanent());
+    }
+    assert_no_requests(&sleuth);
+}
+
+#[tokio::test]
+async fn ownership_lookup_refuses_unroutable_addresses() {
+    let lookup = OwnershipLookup::new();
+    for address in NOT_ROUTABLE {
+        let target = ip(address);
+        assert!(matches!(
+            lookup.lookup_asn(&target).await,
+            Err(OwnershipError::NotFound(_))
+        ));
+        assert!(matches!(
+            lookup.lookup_organization(&target).await,
+            Err(OwnershipError::NotFound(_))
+        ));
+        assert!(matches!(
+            lookup.lookup_ownership_chain(&target).await,
+            Err(OwnershipError::NotFound(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn mapped_addresses_are_looked_up_as_ipv4() {
+    let sleuth = sleuth_with(&[("1.1.1.1", CLOUDFLARE_V4)]);
+    let mapped = ip("::ffff:1.1.1.1");
+
+    let metadata = sleuth.lookup_ip_metadata(&mapped).await.unwrap();
+    assert_eq!(metadata.ip, mapped);
+    assert_eq!(metadata.hostname.as_deref(), Some("one.one.one.one"));
+    assert_eq!(metadata.asn.as_deref(), Some("13335"));
+    assert_eq!(metadata.cloud_provider, Some(CloudProvider::Cloudflare));
+    assert_eq!(metadata.is_hosting, Some(true));
+
+    let network = metadata.network.unwrap();
+    assert_eq!(network.cidr.as_deref(), Some("1.1.1.0/24"));
+    assert_eq!(network.location.unwrap().country.as_deref(), Some("AU"));
+
+    assert_eq!(
+        sleuth.lookup_cidr(&mapped).await.unwrap().as_deref(),
+        Some("1.1.1.0/24")
+    );
+    assert_no_requests(&sleuth);
+}
+
+#[tokio::test]
+async fn public_ipv6_addresses_are_looked_up_as_given() {
+    let sleuth = sleuth_with(&[
+        ("1.1.1.1", CLOUDFLARE_V4),
+        ("2606:4700:4700::1111", CLOUDFLARE_V6),
+    ]);
+    let address = ip("2606:4700:4700::1111");
+
+    let metadata = sleuth.lookup_ip_metadata(&address).await.unwrap();
+    assert_eq!(metadata.ip, address);
+    assert_eq!(metadata.asn.as_deref(), Some("13335"));
+    assert_eq!(metadata.cloud_provider, Some(CloudProvider::Cloudflare));
+
+    let network = metadata.network.unwrap();
+    assert_eq!(network.cidr.as_deref(), Some("2606:4700::/32"));
+    assert_eq!(network.location.unwrap().country.as_deref(), Some("US"));
+    assert_no_requests(&sleuth);
+}
+
+#[test]
+fn lookup_addresses_are_canonical() {
+    assert_eq!(
+        helpers::lookup_address(&ip("::ffff:1.1.1.1")),
+        Some(ip("1.1.1.1"))
+    );
+    assert_eq!(
+        helpers::lookup_address(&ip("2606:4700:4700::1111")),
+        Some(ip("2606:4700:4700::1111"))
+    );
+    for address in NOT_ROUTABLE {
+        assert_eq!(helpers::lookup_address(&ip(address)), None, "{address}");
+    }
+}
+
+#[test]
+fn cidr_sets_keep_the_families_apart() {
+    let set = CidrSet::from_strs(["1.1.1.0/24", "2606:4700::/32"]).unwrap();
+
+    assert!(set.contains(&ip("2606:4700:4700::1111")));
+    assert!(!set.contains(&ip("2606:4701::1")));
+
+    // Mapped addresses match IPv4 blocks, other IPv6 addresses never do
+    assert_eq!(set.longest_match(&ip("::ffff:1.1.1.9")), Some(24));
+    assert!(!set.contains(&ip("::101:101")));
+    assert!(!set.contains(&ip("1.1.2.1")));
+}
@@ -0,0 +1,81 @@
+//! Classification of captured judge responses
+//!
+//! The fixtures under `tests/fixtures/judges` are azenv bodies as judges
+//! returned them, taken through proxies of each anonymity level. The checker
+//! ran from 198.51.100.4 and every proxy exited from 203.0.113.7.
+
+use gooty_proxy::definitions::enums::AnonymityLevel;
+use gooty_proxy::inspection::judgement::{classify_anonymity, exit_address, parse_judge_headers};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+
+/// Public address of the machine the fixtures were captured from
+const REAL_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 4));
+
+/// Address the judge saw every proxy connect from
+const PROXY_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+
+fn fixture(name: &str) -> String {
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "tests",
+        "fixtures",
+        "judges",
+        name,
+    ]
+    .iter()
+    .collect();
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn classify(name: &str) -> AnonymityLevel {
+    classify_anonymity(&parse_judge_headers(&fixture(name)), REAL_IP)
+}
+
+#[test]
+fn elite_response_has_no_forwarding_headers() {
+    let headers = parse_judge_headers(&fixture("elite.html"));
+    assert_eq!(exit_address(&headers), Some(PROXY_IP));
+    assert_eq!(headers["HOST"], "judge.example");
+    assert_eq!(classify("elite.html"), AnonymityLevel::Elite);
+}
+
+#[test]
+fn chained_and_private_forwarded_addresses_are_anonymous() {
+    let headers = parse_judge_headers(&fixture("anonymous.html"));
+    assert_eq!(headers["X_FORWARDED_FOR"], "10.20.0.14, 192.0.2.50");
+    assert_eq!(classify("anonymous.html"), AnonymityLevel::Anonymous);
+}
+
+#[test]
+fn forwarded_checker_address_is_transparent() {
+    assert_eq!(classify("transparent.html"), AnonymityLevel::Transparent);
+}
+
+#[test]
+fn forwarded_addresses_only_leak_for_the_checker() {
+    // The same response seen from another machine doesn't reveal that one
+    let headers = parse_judge_headers(&fixture("transparent.html"));
+    let elsewhere = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 99));
+    assert_eq!(
+        classify_anonymity(&headers, elsewhere),
+        AnonymityLevel::Anonymous
+    );
+}
+
+#[test]
+fn header_names_in_page_text_are_not_headers() {
+    let headers = parse_judge_headers(&fixture("header_names.html"));
+    for name in [
+        "X_FORWARDED_FOR",
+        "X_REAL_IP",
+        "VIA",
+        "PROXY_CONNECTION",
+        "FORWARDED",
+        "CLIENT_IP",
+    ] {
+        assert!(!headers.contains_key(name), "{name} parsed from page text");
+    }
+    assert_eq!(exit_address(&headers), Some(PROXY_IP));
+    assert_eq!(classify("header_names.html"), AnonymityLevel::Elite);
+}
@@ -0,0 +1,38 @@
+//! Public suffix lists replacing the compiled-in copy
+//!
+//! Kept in a test binary of its own, since the list in use is shared by
+//! the whole process.
+
+use gooty_proxy::definitions::enums::SyncPolicy;
+use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+use gooty_proxy::orchestration::manager::ProxyManager;
+use gooty_proxy::utils::registered_domain;
+use std::path::PathBuf;
+
+/// Opens the fixtures holding a public suffix list without creating any file
+fn fixtures() -> Filestore {
+    let data_dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", "suffixes"]
+        .iter()
+        .collect();
+    Filestore::with_config(FilestoreConfig {
+        data_dir: data_dir.to_string_lossy().into_owned(),
+        create_defaults_if_missing: false,
+        ..FilestoreConfig::default()
+    })
+    .unwrap()
+}
+
+#[test]
+fn stored_list_replaces_the_compiled_in_one() {
+    let url = "https://alice.lists.example.com/proxies.txt";
+    assert_eq!(registered_domain(url).as_deref(), Some("example.com"));
+
+    let mut manager = ProxyManager::new().unwrap();
+    manager
+        .import_from_filestore(&fixtures(), SyncPolicy::Merge)
+        .unwrap();
+    assert_eq!(
+        registered_domain(url).as_deref(),
+        Some("alice.lists.example.com")
+    );
+}
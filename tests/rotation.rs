@@ -0,0 +1,105 @@
+//! Weighted rotation over a pool that changes between selections
+//!
+//! The selector draws from an alias table rebuilt only after enough scores
+//! changed, so proxies added since must still be drawable and proxies
+//! removed or failed since must not be.
+
+use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType, RotationStrategy};
+use gooty_proxy::definitions::proxy::Proxy;
+use gooty_proxy::definitions::types::Port;
+use gooty_proxy::orchestration::pool::ProxyPool;
+use gooty_proxy::orchestration::rotation::WeightedSelector;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// Number of proxies in a pool, enough that one change doesn't rebuild
+const POOL_SIZE: u8 = 64;
+
+/// Selections made when checking which proxies can be drawn
+const DRAWS: usize = 200;
+
+fn proxy(last: u8) -> Proxy {
+    Proxy::new(
+        ProxyType::Http,
+        IpAddr::V4(Ipv4Addr::new(10, 0, 1, last)),
+        Port::new(8080).unwrap(),
+        AnonymityLevel::Elite,
+    )
+}
+
+/// A proxy whose every check failed, scoring close to zero
+fn unreliable(last: u8) -> Proxy {
+    let mut proxy = proxy(last);
+    for _ in 0..100 {
+        proxy.record_check_failure();
+    }
+    proxy
+}
+
+/// A proxy whose every check passed quickly, scoring close to one
+fn reliable(last: u8) -> Proxy {
+    let mut proxy = proxy(last);
+    for _ in 0..100 {
+        proxy.record_check(1);
+    }
+    proxy
+}
+
+fn weighted_pool() -> ProxyPool {
+    let mut pool = ProxyPool::from_proxies((1..=POOL_SIZE).map(unreliable))
+        .with_strategy(RotationStrategy::Weighted);
+    // Builds the table over the unreliable proxies
+    assert!(pool.next_proxy().is_some());
+    pool
+}
+
+#[test]
+fn selector_draws_a_proxy_added_after_the_table_was_built() {
+    let mut selector = WeightedSelector::new();
+    for last in 1..=POOL_SIZE {
+        selector.set_weight(&proxy(last).to_connection_string(), 1e-9);
+    }
+    let mut rng = rand::rng();
+    assert!(selector.select(&mut rng).is_some());
+
+    let added = reliable(200).to_connection_string();
+    selector.set_weight(&added, 1.0);
+    assert_eq!(selector.select(&mut rng), Some(added.as_str()));
+}
+
+#[test]
+fn proxy_added_to_a_weighted_pool_is_drawn_right_away() {
+    let mut pool = weighted_pool();
+    let added = reliable(200);
+    pool.add_proxy(added.clone());
+
+    let id = added.to_connection_string();
+    assert!((0..DRAWS).any(|_| pool.next_proxy().is_some_and(|proxy| proxy.id() == id)));
+}
+
+#[test]
+fn removed_proxy_is_never_drawn() {
+    let mut pool = weighted_pool();
+    let removed = reliable(200);
+    pool.add_proxy(removed.clone());
+    let id = removed.to_connection_string();
+    assert!(pool.remove_proxy(&id).is_some());
+
+    for _ in 0..DRAWS {
+        let proxy = pool.next_proxy().unwrap();
+        assert_ne!(proxy.id(), id);
+    }
+}
+
+#[test]
+fn failed_proxy_is_not_drawn_while_cooling_down() {
+    let mut pool = weighted_pool();
+    let failed = reliable(200);
+    pool.add_proxy(failed.clone());
+    let id = failed.to_connection_string();
+    pool.report_failure(&id);
+
+    for _ in 0..DRAWS {
+        let proxy = pool.next_proxy().unwrap();
+        assert_ne!(proxy.id(), id);
+    }
+}
@@ -0,0 +1,89 @@
+//! Compatibility tests for pools stored by earlier releases
+//!
+//! The fixtures under `tests/fixtures/<version>` were written by the
+//! `save_proxies` of that release, and must keep loading as `Proxy` grows.
+
+use chrono::{DateTime, Utc};
+use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyLifecycle, ProxyType};
+use gooty_proxy::definitions::proxy::Proxy;
+use gooty_proxy::io::filesystem::{Filestore, FilestoreConfig};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+
+/// Opens the fixtures of a release without creating any file
+fn fixtures(version: &str) -> Filestore {
+    let data_dir: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", version]
+        .iter()
+        .collect();
+    Filestore::with_config(FilestoreConfig {
+        data_dir: data_dir.to_string_lossy().into_owned(),
+        create_defaults_if_missing: false,
+        ..FilestoreConfig::default()
+    })
+    .unwrap()
+}
+
+fn time(rfc3339: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc()
+}
+
+#[test]
+fn pool_from_0_2_0_loads() {
+    let proxies = fixtures("0.2.0").load_proxies("proxies").unwrap();
+    assert_eq!(proxies.len(), 3);
+
+    let fresh = &proxies[0];
+    assert_eq!(fresh.proxy_type, ProxyType::Socks5);
+    assert_eq!(fresh.address, IpAddr::V4(Ipv4Addr::new(203, 0, 113, 1)));
+    assert_eq!(fresh.port, 1080);
+    assert_eq!(fresh.anonymity, AnonymityLevel::Anonymous);
+    assert_eq!(fresh.lifecycle, ProxyLifecycle::New);
+    assert!(fresh.tags.is_empty());
+
+    let checked = &proxies[1];
+    assert_eq!(checked.username.as_deref(), Some("user"));
+    assert_eq!(checked.password.as_deref(), Some("secret"));
+    assert_eq!(checked.country.as_deref(), Some("DE"));
+    assert_eq!(checked.asn.as_deref(), Some("AS64500"));
+    assert_eq!((checked.check_count, checked.check_failure_count), (3, 1));
+    assert_eq!((checked.use_count, checked.use_failure_count), (2, 1));
+    assert!(checked.latency_ms.is_none());
+
+    let failing = &proxies[2];
+    assert_eq!(failing.proxy_type, ProxyType::Https);
+    assert_eq!((failing.check_count, failing.check_failure_count), (2, 2));
+}
+
+#[test]
+fn first_seen_times_are_kept_across_loads() {
+    let store = fixtures("0.2.0");
+    let first = store.load_proxies("proxies").unwrap();
+    let second = store.load_proxies("proxies").unwrap();
+
+    assert_eq!(
+        first[0].added_at,
+        Some(time("2026-10-17T15:37:51.804145610Z"))
+    );
+    assert_eq!(
+        first.iter().map(|p| p.added_at).collect::<Vec<_>>(),
+        second.iter().map(|p| p.added_at).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn pool_from_0_2_0_round_trips() {
+    let stored = fixtures("0.2.0").load_proxies("proxies").unwrap();
+
+    let dir = std::env::temp_dir().join(format!("gooty-stored-pools-{}", std::process::id()));
+    let store = Filestore::with_config(FilestoreConfig {
+        data_dir: dir.to_string_lossy().into_owned(),
+        create_defaults_if_missing: false,
+        ..FilestoreConfig::default()
+    })
+    .unwrap();
+    store.save_proxies(&stored, "proxies").unwrap();
+    let reloaded: Vec<Proxy> = store.load_proxies("proxies").unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(reloaded, stored);
+}
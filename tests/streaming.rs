@@ -0,0 +1,136 @@
+//! Back-pressure between source fetches and judgements
+//!
+//! Each test serves two proxy lists from a mock source server. The listed
+//! proxies accept connections but never answer, so every judgement outlasts
+//! the test and scraped proxies pile up in the channel to the validators.
+
+use gooty_proxy::definitions::source::Source;
+use gooty_proxy::inspection::judgement::Judge;
+use gooty_proxy::io::http::Requestor;
+use gooty_proxy::orchestration::events::EventBus;
+use gooty_proxy::orchestration::processes::{self, StreamingOptions};
+use gooty_proxy::orchestration::threading::BulkExecutor;
+use std::fmt::Write;
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Proxies listed by each source
+const PROXIES_PER_SOURCE: u8 = 8;
+
+/// Time given to the pipeline, past the throttle between the two fetches
+const RUN_FOR: Duration = Duration::from_millis(2500);
+
+/// Accepts connections on every loopback address and never answers them
+async fn black_hole() -> u16 {
+    let listener = TcpListener::bind("0.0.0.0:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            held.push(stream);
+        }
+    });
+    port
+}
+
+/// Serves `/first` and `/second`, each listing proxies on distinct loopback
+/// addresses, and counts the lists downloaded
+async fn source_server(proxy_port: u16) -> (u16, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let downloads = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&downloads);
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let counter = Arc::clone(&counter);
+            tokio::spawn(async move {
+                let mut head = Vec::new();
+                while !head.ends_with(b"\r\n\r\n") {
+                    let Ok(byte) = stream.read_u8().await else {
+                        return;
+                    };
+                    head.push(byte);
+                }
+                let head = String::from_utf8_lossy(&head);
+                let first = if head.contains(" /first ") { 1 } else { 101 };
+                let mut body = String::new();
+                for host in first..first + PROXIES_PER_SOURCE {
+                    writeln!(body, "127.0.0.{host}:{proxy_port}").unwrap();
+                }
+
+                let mut response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                if head.starts_with("GET ") {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    response.push_str(&body);
+                }
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+    (port, downloads)
+}
+
+/// Streams from both sources for a while, judging one proxy at a time
+///
+/// # Returns
+///
+/// The number of lists downloaded by then
+async fn lists_downloaded(channel_capacity: usize) -> usize {
+    let (port, downloads) = source_server(black_hole().await).await;
+    let mut sources: Vec<Source> = ["first", "second"]
+        .iter()
+        .map(|path| {
+            Source::new(
+                format!("http://127.0.0.1:{port}/{path}"),
+                "gooty-test".to_string(),
+                r"\d+\.\d+\.\d+\.\d+:\d+".to_string(),
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let requestor = Requestor::new().unwrap();
+    let judge = Arc::new(
+        Judge::new()
+            .unwrap()
+            .with_real_ip(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))),
+    );
+    let options = StreamingOptions {
+        fetch_concurrency: 1,
+        verify_concurrency: 1,
+        channel_capacity,
+    };
+    let executor = BulkExecutor::shared();
+    let events = EventBus::default();
+
+    let streaming = processes::stream_from_sources(
+        &mut sources,
+        &requestor,
+        &judge,
+        options,
+        &executor,
+        &events,
+    );
+    assert!(tokio::time::timeout(RUN_FOR, streaming).await.is_err());
+    downloads.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn fetching_waits_while_the_channel_is_full() {
+    // One proxy is judged and one buffered, so the first list can't be
+    // handed over and the second is never downloaded
+    assert_eq!(lists_downloaded(1).await, 1);
+}
+
+#[tokio::test]
+async fn fetching_continues_while_the_channel_has_room() {
+    let capacity = usize::from(PROXIES_PER_SOURCE) * 2;
+    assert_eq!(lists_downloaded(capacity).await, 2);
+}
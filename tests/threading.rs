@@ -0,0 +1,216 @@
+//! Regression tests for the batch helpers and the worker pool
+//!
+//! Callers such as `verify_proxies` write results back by position, so a
+//! result must stay at the index of its item however the jobs interleave.
+//! Shrinking a worker pool must retire idle workers without new items. The
+//! task manager must report tasks in spawn order, and keep the results of
+//! tasks reaped before the join if they didn't complete.
+
+use futures::FutureExt;
+use gooty_proxy::orchestration::threading::{
+    TaskManager, TaskOutcome, create_worker_pool, run_concurrent_batch,
+    run_concurrent_batch_with_progress, run_concurrent_batch_with_state,
+};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Number of items in a batch
+const BATCH_SIZE: u32 = 64;
+
+/// Yields more often for earlier items, so jobs finish in reverse order
+async fn finish_reversed(item: u32) {
+    for _ in item..BATCH_SIZE {
+        tokio::task::yield_now().await;
+    }
+}
+
+fn items() -> Vec<u32> {
+    (0..BATCH_SIZE).collect()
+}
+
+fn reversed_job(item: u32) -> Pin<Box<dyn Future<Output = (u32, bool)> + Send>> {
+    async move {
+        finish_reversed(item).await;
+        (item, !item.is_multiple_of(3))
+    }
+    .boxed()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn run_concurrent_batch_keeps_input_order() {
+    for concurrency in [1, 7, BATCH_SIZE as usize] {
+        let batch = run_concurrent_batch(items(), concurrency, &reversed_job).await;
+        let results: Vec<(u32, bool)> = batch.results.into_iter().flatten().collect();
+        let expected: Vec<(u32, bool)> = items()
+            .into_iter()
+            .map(|i| (i, !i.is_multiple_of(3)))
+            .collect();
+        assert_eq!(results, expected, "concurrency {concurrency}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn run_concurrent_batch_with_state_keeps_input_order() {
+    for concurrency in [1, 7, BATCH_SIZE as usize] {
+        let batch = run_concurrent_batch_with_state(
+            items(),
+            Arc::new(()),
+            concurrency,
+            |item, _state: Arc<()>| reversed_job(item),
+        )
+        .await;
+        let indices: Vec<u32> = batch
+            .results
+            .into_iter()
+            .flatten()
+            .map(|(item, _)| item)
+            .collect();
+        assert_eq!(indices, items(), "concurrency {concurrency}");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn run_concurrent_batch_with_progress_keeps_input_order() {
+    for concurrency in [1, 7, BATCH_SIZE as usize] {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&reported);
+        let batch = run_concurrent_batch_with_progress(
+            items(),
+            concurrency,
+            reversed_job,
+            move |index, result: Option<&(u32, bool)>| {
+                seen.lock()
+                    .unwrap()
+                    .push((index, result.map(|(item, _)| *item)));
+            },
+        )
+        .await;
+
+        let indices: Vec<u32> = batch
+            .results
+            .into_iter()
+            .flatten()
+            .map(|(item, _)| item)
+            .collect();
+        assert_eq!(indices, items(), "concurrency {concurrency}");
+        let reported = reported.lock().unwrap();
+        assert!(
+            reported
+                .iter()
+                .all(|&(index, item)| item == u32::try_from(index).ok()),
+            "progress reported a result under another item's index"
+        );
+        assert_eq!(reported.len(), BATCH_SIZE as usize);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn shrinking_an_idle_worker_pool_retires_workers() {
+    for round in 0..32 {
+        let mut pool = create_worker_pool(8, |_: u32| async {});
+        for _ in 0..round % 4 {
+            tokio::task::yield_now().await;
+        }
+
+        pool.resize(1);
+        let shrunk = tokio::time::timeout(Duration::from_secs(5), async {
+            while pool.stats().per_worker.len() > 1 {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await;
+        assert!(shrunk.is_ok(), "surplus workers stayed in round {round}");
+        assert_eq!(pool.stats().workers, 1);
+
+        let stats = pool.join().await;
+        assert_eq!(stats.workers, 0);
+    }
+}
+
+/// Waits until none of the named tasks is running
+async fn wait_for_end(tasks: &TaskManager, names: &[&str]) {
+    let ended = tokio::time::timeout(Duration::from_secs(5), async {
+        while names.iter().any(|name| tasks.is_running(name)) {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await;
+    assert!(ended.is_ok(), "tasks {names:?} kept running");
+}
+
+/// Holds a task until the gate opens, so spawning more tasks can't reap it
+async fn after(gate: Arc<Semaphore>, task: impl Future<Output = ()>) {
+    let _permit = gate.acquire().await.unwrap();
+    task.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn join_all_results_keeps_spawn_order() {
+    let gate = Arc::new(Semaphore::new(0));
+    let mut tasks = TaskManager::new();
+    for item in items() {
+        let task = after(Arc::clone(&gate), finish_reversed(item));
+        tasks.spawn_named(&format!("item-{item}"), task).unwrap();
+    }
+    gate.add_permits(BATCH_SIZE as usize);
+
+    let results = tasks.join_all_results().await;
+    let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+    let expected: Vec<String> = items().iter().map(|i| format!("item-{i}")).collect();
+    assert_eq!(names, expected);
+    assert!(results.iter().all(|r| r.outcome.is_success()));
+    assert!(tasks.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn reaping_keeps_panicked_and_cancelled_results() {
+    let mut tasks = TaskManager::new();
+    tasks.spawn_named("completes", async {}).unwrap();
+    tasks
+        .spawn_named("panics", async { panic!("worker crashed") })
+        .unwrap();
+    tasks
+        .spawn_named("cancelled", async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        })
+        .unwrap();
+    assert!(tasks.abort("cancelled"));
+    wait_for_end(&tasks, &["completes", "panics", "cancelled"]).await;
+
+    // Spawning reaps the ended tasks, dropping the one that completed
+    let gate = Arc::new(Semaphore::new(0));
+    tasks
+        .spawn_named("last", after(Arc::clone(&gate), async {}))
+        .unwrap();
+    assert_eq!(tasks.names(), vec!["last"]);
+    // A reaped name can be reused
+    tasks
+        .spawn_named("panics", after(Arc::clone(&gate), async {}))
+        .unwrap();
+    gate.add_permits(2);
+
+    let results: Vec<(String, TaskOutcome)> = tasks
+        .join_all_results()
+        .await
+        .into_iter()
+        .map(|r| (r.name, r.outcome))
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            (
+                "panics".to_string(),
+                TaskOutcome::Panicked("worker crashed".to_string())
+            ),
+            ("cancelled".to_string(), TaskOutcome::Aborted),
+            ("last".to_string(), TaskOutcome::Completed),
+            ("panics".to_string(), TaskOutcome::Completed),
+        ]
+    );
+
+    // Reaped results are reported once
+    assert!(tasks.join_all_results().await.is_empty());
+}
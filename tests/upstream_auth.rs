@@ -0,0 +1,236 @@
+//! Credentials presented to upstream proxies
+//!
+//! Each test stands up a mock upstream on localhost that only lets requests
+//! through for the expected credentials, and records what it was sent.
+
+use gooty_proxy::definitions::enums::{AnonymityLevel, ProxyType};
+use gooty_proxy::definitions::errors::ListenerError;
+use gooty_proxy::definitions::proxy::Proxy;
+use gooty_proxy::definitions::types::Port;
+use gooty_proxy::io::socks::{self, TargetAddr};
+use std::net::{IpAddr, Ipv4Addr};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// "user:secret" in Base64
+const AUTHORIZATION: &str = "Proxy-Authorization: Basic dXNlcjpzZWNyZXQ=\r\n";
+
+fn proxy(proxy_type: ProxyType, port: u16, password: &str) -> Proxy {
+    Proxy::new(
+        proxy_type,
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+        Port::new(port).unwrap(),
+        AnonymityLevel::Elite,
+    )
+    .with_auth("user".to_string(), password.to_string())
+}
+
+fn target() -> TargetAddr {
+    TargetAddr::Domain("example.com".to_string(), 443)
+}
+
+async fn read_head<S: AsyncRead + Unpin>(stream: &mut S) -> String {
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        head.push(stream.read_u8().await.unwrap());
+    }
+    String::from_utf8(head).unwrap()
+}
+
+/// Serves one connection as an HTTP proxy, answering 200 to the right
+/// credentials and 407 otherwise, then echoing whatever is tunneled
+async fn http_upstream() -> (u16, JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let upstream = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let head = read_head(&mut stream).await;
+        if head.contains(AUTHORIZATION) {
+            stream.write_all(b"HTTP/1.1 200 OK\r\n\r\n").await.unwrap();
+            let mut ping = [0_u8; 4];
+            stream.read_exact(&mut ping).await.unwrap();
+            stream.write_all(&ping).await.unwrap();
+        } else {
+            stream
+                .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                .await
+                .unwrap();
+        }
+        head
+    });
+    (port, upstream)
+}
+
+/// Serves one connection as a SOCKS5 proxy insisting on username/password
+/// authentication, then echoing whatever is tunneled. Yields the
+/// sub-negotiation it was sent.
+async fn socks5_upstream() -> (u16, JoinHandle<Vec<u8>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let upstream = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut greeting = [0_u8; 4];
+        stream.read_exact(&mut greeting).await.unwrap();
+        assert_eq!(greeting, [5, 2, 0, 2], "password method not offered");
+        stream.write_all(&[5, 2]).await.unwrap();
+
+        let mut credentials = vec![stream.read_u8().await.unwrap()];
+        for _ in 0..2 {
+            let len = stream.read_u8().await.unwrap();
+            let mut field = vec![0_u8; usize::from(len)];
+            stream.read_exact(&mut field).await.unwrap();
+            credentials.push(len);
+            credentials.extend(field);
+        }
+        if credentials != b"\x01\x04user\x06secret" {
+            stream.write_all(&[1, 1]).await.unwrap();
+            return credentials;
+        }
+        stream.write_all(&[1, 0]).await.unwrap();
+
+        // CONNECT to example.com:443 by name
+        let mut request = [0_u8; 18];
+        stream.read_exact(&mut request).await.unwrap();
+        assert_eq!(&request[..5], &[5, 1, 0, 3, 11]);
+        assert_eq!(&request[5..16], b"example.com");
+        assert_eq!(&request[16..], &443_u16.to_be_bytes());
+        stream
+            .write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0])
+            .await
+            .unwrap();
+
+        let mut ping = [0_u8; 4];
+        stream.read_exact(&mut ping).await.unwrap();
+        stream.write_all(&ping).await.unwrap();
+        credentials
+    });
+    (port, upstream)
+}
+
+#[tokio::test]
+async fn http_tunnel_sends_proxy_authorization() {
+    let (port, upstream) = http_upstream().await;
+    let mut tunnel = socks::connect_through(&proxy(ProxyType::Http, port, "secret"), &target())
+        .await
+        .unwrap();
+
+    tunnel.write_all(b"ping").await.unwrap();
+    let mut echo = [0_u8; 4];
+    tunnel.read_exact(&mut echo).await.unwrap();
+    assert_eq!(&echo, b"ping");
+
+    let head = upstream.await.unwrap();
+    assert!(head.starts_with("CONNECT example.com:443 HTTP/1.1\r\n"));
+    assert!(head.contains(AUTHORIZATION));
+}
+
+#[tokio::test]
+async fn http_tunnel_with_rejected_credentials_fails_the_proxy() {
+    let (port, upstream) = http_upstream().await;
+    let result = socks::connect_through(&proxy(ProxyType::Http, port, "wrong"), &target()).await;
+
+    assert!(matches!(result, Err(ListenerError::UpstreamError(_))));
+    assert!(!upstream.await.unwrap().contains(AUTHORIZATION));
+}
+
+#[tokio::test]
+async fn http_tunnel_without_credentials_sends_no_authorization() {
+    let (port, upstream) = http_upstream().await;
+    let mut anonymous = proxy(ProxyType::Http, port, "secret");
+    anonymous.username = None;
+    anonymous.password = None;
+
+    let _ = socks::connect_through(&anonymous, &target()).await;
+    assert!(!upstream.await.unwrap().contains("Proxy-Authorization"));
+}
+
+#[tokio::test]
+async fn socks5_tunnel_negotiates_username_and_password() {
+    let (port, upstream) = socks5_upstream().await;
+    let mut tunnel = socks::connect_through(&proxy(ProxyType::Socks5, port, "secret"), &target())
+        .await
+        .unwrap();
+
+    tunnel.write_all(b"ping").await.unwrap();
+    let mut echo = [0_u8; 4];
+    tunnel.read_exact(&mut echo).await.unwrap();
+    assert_eq!(&echo, b"ping");
+    assert_eq!(upstream.await.unwrap(), b"\x01\x04user\x06secret");
+}
+
+#[tokio::test]
+async fn socks5_tunnel_with_rejected_credentials_fails_the_proxy() {
+    let (port, upstream) = socks5_upstream().await;
+    let result = socks::connect_through(&proxy(ProxyType::Socks5, port, "wrong"), &target()).await;
+
+    assert!(matches!(result, Err(ListenerError::UpstreamError(_))));
+    assert_eq!(upstream.await.unwrap(), b"\x01\x04user\x05wrong");
+}
+
+#[cfg(feature = "network")]
+mod requestor {
+    use super::*;
+    use gooty_proxy::definitions::errors::RequestorError;
+    use gooty_proxy::io::http::Requestor;
+
+    /// Serves one plain HTTP request as a forwarding proxy, answering the
+    /// right credentials with a body and others with 407
+    async fn forwarding_upstream() -> (u16, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let upstream = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            // hyper sends header names in lowercase
+            let head = read_head(&mut stream).await.to_ascii_lowercase();
+            let response: &[u8] = if head.contains(&AUTHORIZATION.to_ascii_lowercase()) {
+                b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok"
+            } else {
+                b"HTTP/1.1 407 Proxy Authentication Required\r\n\
+                  Content-Length: 0\r\nConnection: close\r\n\r\n"
+            };
+            stream.write_all(response).await.unwrap();
+            head
+        });
+        (port, upstream)
+    }
+
+    #[tokio::test]
+    async fn requestor_sends_proxy_authorization() {
+        let (port, upstream) = forwarding_upstream().await;
+        let body = Requestor::new()
+            .unwrap()
+            .get_with_proxy(
+                "http://judge.example/",
+                "gooty-test",
+                &proxy(ProxyType::Http, port, "secret"),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(body, "ok");
+        let head = upstream.await.unwrap();
+        assert!(head.starts_with("get http://judge.example/ http/1.1\r\n"));
+        assert!(head.contains(&AUTHORIZATION.to_ascii_lowercase()));
+    }
+
+    #[tokio::test]
+    async fn requestor_reports_rejected_credentials() {
+        let (port, upstream) = forwarding_upstream().await;
+        let result = Requestor::new()
+            .unwrap()
+            .get_with_proxy(
+                "http://judge.example/",
+                "gooty-test",
+                &proxy(ProxyType::Http, port, "wrong"),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(RequestorError::ProxyAuthenticationFailed(_))
+        ));
+        let head = upstream.await.unwrap();
+        assert!(!head.contains(&AUTHORIZATION.to_ascii_lowercase()));
+    }
+}